@@ -10,8 +10,9 @@ declare -A project_wasm_names
 project_wasm_names["core-banking"]="bcb_core"
 project_wasm_names["did-management"]="bcb_did"
 project_wasm_names["account-recovery"]="bcb_acc"
+project_wasm_names["governance"]="bcb_gov"
 
-for project_dir in "core-banking" "did-management" "account-recovery"; do
+for project_dir in "core-banking" "did-management" "account-recovery" "governance"; do
     WASM_FILE_NAME="${project_wasm_names[$project_dir]}"
     UNOPTIMIZED_WASM_PATH="./target/wasm32-unknown-unknown/release/${WASM_FILE_NAME}.wasm"
     OPTIMIZED_WASM_PATH="./res/${WASM_FILE_NAME}.wasm"
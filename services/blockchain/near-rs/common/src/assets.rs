@@ -0,0 +1,15 @@
+// services/blockchain/near-rs/common/src/assets.rs
+use near_sdk::{near, AccountId};
+
+/// Asset an account can hold at the bank. Amounts of every asset are in its smallest unit
+/// (yoctoNEAR for `Native`).
+#[near(serializers = [borsh, json])]
+#[serde(rename_all = "snake_case")]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub enum AssetId {
+    /// NEAR held in the account balance. Also accepted as `near`.
+    #[serde(alias = "near")]
+    Native,
+    /// Whitelisted NEP-141 token, by contract id.
+    Ft(AccountId),
+}
@@ -0,0 +1,63 @@
+// services/blockchain/near-rs/common/src/did.rs
+use near_sdk::{near, AccountId};
+use near_sdk::serde_json::Value;
+
+/// Credential type the registry's KYC attestations are issued under.
+pub const KYC_CREDENTIAL_TYPE: &str = "kyc";
+/// Tag prefix carrying the verified level, e.g. `tier:2`.
+pub const KYC_TIER_TAG_PREFIX: &str = "tier:";
+
+/// Verified KYC level of an account; 0 means unverified.
+pub type KycLevel = u8;
+
+/// Level attested by a KYC credential carrying `tags`, capped at `max_level`. A credential
+/// without a tier tag counts as level 1.
+pub fn kyc_level(tags: &[String], max_level: KycLevel) -> KycLevel {
+    tags.iter()
+        .filter_map(|tag| tag.strip_prefix(KYC_TIER_TAG_PREFIX)?.parse::<KycLevel>().ok())
+        .max()
+        .unwrap_or(1)
+        .min(max_level)
+}
+
+/// A public key the DID subject can authenticate with, e.g. an
+/// `Ed25519VerificationKey2020` as `ed25519:<base58>`.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct VerificationMethod {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub method_type: String,
+    pub public_key: String,
+}
+
+/// The parts of a DID registry resolution other contracts read.
+#[near(serializers = [json])]
+#[derive(Debug, PartialEq)]
+pub struct DidResolution {
+    pub did_document: DidDocument,
+    pub did_document_metadata: DidDocumentMetadata,
+}
+
+#[near(serializers = [json])]
+#[derive(Debug, PartialEq)]
+pub struct DidDocument {
+    pub verifiable_credentials: Vec<DidCredential>,
+}
+
+#[near(serializers = [json])]
+#[derive(Debug, PartialEq)]
+pub struct DidCredential {
+    pub credential_type: String,
+    pub tags: Vec<String>,
+    pub issuer: AccountId,
+    pub expires_at: Option<u64>,
+    pub revocation: Option<Value>,
+    pub suspended: bool,
+}
+
+#[near(serializers = [json])]
+#[derive(Debug, PartialEq)]
+pub struct DidDocumentMetadata {
+    pub deactivated: bool,
+}
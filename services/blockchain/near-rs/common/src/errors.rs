@@ -0,0 +1,31 @@
+// services/blockchain/near-rs/common/src/errors.rs
+use std::fmt;
+
+use near_sdk::env;
+
+/// Failures every contract reports with the same message.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ContractError {
+    OwnerOnly,
+    /// Caller is neither the owner nor the governance contract.
+    OwnerOrGovernanceOnly,
+    /// The named record does not exist, e.g. `NotFound("Recovery request")`.
+    NotFound(&'static str),
+}
+
+impl fmt::Display for ContractError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ContractError::OwnerOnly => write!(f, "Only the owner can call this function."),
+            ContractError::OwnerOrGovernanceOnly => write!(f, "Only the owner or governance can call this function."),
+            ContractError::NotFound(what) => write!(f, "{} not found.", what),
+        }
+    }
+}
+
+impl ContractError {
+    /// Aborts the call with this error's message.
+    pub fn panic(self) -> ! {
+        env::panic_str(&self.to_string())
+    }
+}
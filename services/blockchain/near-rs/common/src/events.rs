@@ -0,0 +1,21 @@
+// services/blockchain/near-rs/common/src/events.rs
+use near_sdk::{env, serde_json};
+use near_sdk::serde::Serialize;
+
+/// A NEP-297 event as logged: `EVENT_JSON:` followed by this object.
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Nep297Event<'a, T: Serialize> {
+    pub standard: &'a str,
+    pub version: &'a str,
+    pub event: &'a str,
+    pub data: [&'a T; 1],
+}
+
+/// Logs `data` as NEP-297 event `event` of `standard` at `version`, for contracts that do
+/// not declare their events with `#[near(event_json)]`.
+pub fn emit_event<T: Serialize>(standard: &str, version: &str, event: &str, data: &T) {
+    let event = Nep297Event { standard, version, event, data: [data] };
+    let json = serde_json::to_string(&event).unwrap_or_else(|_| env::panic_str("Failed to serialize event."));
+    env::log_str(&format!("EVENT_JSON:{}", json));
+}
@@ -0,0 +1,29 @@
+// services/blockchain/near-rs/common/src/interfaces.rs
+//! Cross-contract interfaces, each declaring the subset of methods callers use.
+#![allow(dead_code)]
+
+use near_sdk::{ext_contract, AccountId};
+use near_sdk::json_types::U128;
+
+use crate::did::{DidResolution, VerificationMethod};
+
+/// Interface of the DID registry (`bcb-did`).
+#[ext_contract(ext_did_registry)]
+pub trait DidRegistry {
+    fn resolve_did(&self, account_id: AccountId) -> Option<DidResolution>;
+    fn get_verification_method(&self, account_id: AccountId, key_id: String) -> Option<VerificationMethod>;
+}
+
+/// Interface of the account recovery contract (`bcb-acc`).
+#[ext_contract(ext_account_recovery)]
+pub trait AccountRecovery {
+    fn get_guardians(&self, account_id: AccountId) -> Option<Vec<AccountId>>;
+}
+
+/// Interface of a NEP-141 fungible token.
+#[ext_contract(ext_ft)]
+pub trait FungibleToken {
+    fn ft_transfer(&mut self, receiver_id: AccountId, amount: U128, memo: Option<String>);
+    fn ft_transfer_call(&mut self, receiver_id: AccountId, amount: U128, memo: Option<String>, msg: String) -> U128;
+    fn ft_balance_of(&self, account_id: AccountId) -> U128;
+}
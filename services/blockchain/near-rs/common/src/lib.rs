@@ -0,0 +1,15 @@
+// services/blockchain/near-rs/common/src/lib.rs
+//! Types, interfaces and helpers shared by the BioCrypticBank contracts: the core bank
+//! (`bcb-core`), the DID registry (`bcb-did`), account recovery (`bcb-acc`) and
+//! governance (`bcb-gov`).
+
+pub mod assets;
+pub mod did;
+pub mod errors;
+pub mod events;
+pub mod interfaces;
+
+pub use assets::AssetId;
+pub use did::{KycLevel, VerificationMethod};
+pub use errors::ContractError;
+pub use events::emit_event;
@@ -0,0 +1,113 @@
+// services/blockchain/near-rs/core-banking/src/account_closure.rs
+use near_sdk::{near, env, assert_one_yocto, AccountId, Gas, NearToken, Promise, PromiseError};
+use near_sdk::json_types::Base58CryptoHash;
+
+use crate::fees::FeeOperation;
+use crate::ledger::{EntryDirection, EntryKind};
+use crate::pause::Feature;
+use crate::{BankEvent, BioCrypticBankCore, BioCrypticBankCoreExt};
+
+const GAS_FOR_CLOSURE_CALLBACK: Gas = Gas::from_tgas(10);
+
+/// What is kept of a closed account. Its ledger entries are deleted, but their hashes stay
+/// in the ledger hash chain, so receipts issued before closure can still be verified up to
+/// `last_entry_hash`.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct ClosedAccount {
+  pub beneficiary_id: AccountId,
+  /// Balance paid to the beneficiary, after the withdrawal fee.
+  pub balance_paid: NearToken,
+  pub storage_refund: NearToken,
+  pub ledger_entry_count: u64,
+  pub last_entry_id: Option<u64>,
+  pub last_entry_hash: Option<Base58CryptoHash>,
+  pub closed_at: u64,
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Closes the caller's account: the remaining balance, less the withdrawal fee, and the
+  /// storage deposit are paid in NEAR to `beneficiary_id`, and the account's balance,
+  /// limits, envelopes and ledger history are deleted. Everything `storage_unregister`
+  /// requires to be emptied first must be. A balance that would need guardian approval, a
+  /// timelock or a KYC check to leave must be withdrawn normally first. Requires exactly
+  /// 1 yoctoNEAR attached.
+  #[payable]
+  pub fn close_account(&mut self, beneficiary_id: AccountId) -> Promise {
+      assert_one_yocto();
+      self.assert_not_paused(Feature::Withdrawals);
+      let account_id = env::predecessor_account_id();
+      let storage = self.storage_accounts.get(&account_id).cloned()
+          .unwrap_or_else(|| env::panic_str("Account is not registered."));
+      self.assert_not_dormant(&account_id);
+      self.assert_no_recovery_hold(&account_id);
+      self.assert_trusted_destination(&account_id, &beneficiary_id);
+
+      let balance = self.get_balance(account_id.clone());
+      assert!(
+          !self.internal_guardian_approval_needed(&account_id, balance)
+              && !self.internal_withdrawal_timelock_needed(balance)
+              && !self.internal_kyc_check_needed(&account_id, balance),
+          "Balance needs approval to leave; withdraw it before closing the account."
+      );
+      let mut balance_paid = NearToken::from_yoctonear(0);
+      if !balance.is_zero() {
+          self.internal_record_outflow(&account_id, balance);
+          self.internal_record_kyc_volume(&account_id, balance);
+          self.envelopes.remove(&account_id);
+          self.internal_debit(&account_id, balance);
+          let fee = self.internal_discounted_fee(&account_id, FeeOperation::Withdrawal, balance).min(balance);
+          self.internal_charge_fee(&account_id, fee, Some("close".to_string()));
+          balance_paid = balance.saturating_sub(fee);
+          self.internal_remove_liabilities(balance_paid);
+          self.internal_record_entry_with_counterparty(
+              &account_id, Some(beneficiary_id.clone()), EntryKind::Withdrawal, EntryDirection::Debit, balance_paid,
+              None, Some("close".to_string()),
+          );
+      }
+
+      let ledger_entry_count = self.internal_ledger_len(&account_id);
+      let last_entry_id = ledger_entry_count.checked_sub(1)
+          .and_then(|position| self.internal_ledger_entry_id(&account_id, position));
+      let storage_refund = self.internal_close_registration(&account_id, storage);
+      self.closed_accounts.insert(account_id.clone(), ClosedAccount {
+          beneficiary_id: beneficiary_id.clone(),
+          balance_paid,
+          storage_refund,
+          ledger_entry_count,
+          last_entry_id,
+          last_entry_hash: last_entry_id.and_then(|id| self.get_ledger_entry_hash(id)),
+          closed_at: env::block_timestamp(),
+      });
+
+      let payout = balance_paid.saturating_add(storage_refund);
+      self.internal_record_contract_outflow(payout);
+      BankEvent::AccountClosed { account_id: account_id.clone(), beneficiary_id: beneficiary_id.clone(), balance_paid, storage_refund }.emit();
+      Promise::new(beneficiary_id).transfer(payout).then(
+          Self::ext(env::current_account_id())
+              .with_static_gas(GAS_FOR_CLOSURE_CALLBACK)
+              .on_account_closed(account_id, payout)
+      )
+  }
+
+  /// Callback placing a failed closure payout in escheatment custody for the closed
+  /// account, which can reclaim it after registering again.
+  #[private]
+  pub fn on_account_closed(&mut self, account_id: AccountId, amount: NearToken, #[callback_result] result: Result<(), PromiseError>) -> bool {
+      if result.is_err() {
+          self.internal_add_liabilities(amount);
+          let held = self.escheated_balances.get(&account_id).copied().unwrap_or(NearToken::from_yoctonear(0));
+          self.escheated_balances.insert(account_id.clone(), held.saturating_add(amount));
+          self.total_escheated = self.total_escheated.saturating_add(amount);
+          env::log_str("Account closure payout failed; amount held for the account to reclaim.");
+      }
+      result.is_ok()
+  }
+
+  /// Record of `account_id`'s last closure.
+  /// View function.
+  pub fn get_closed_account(&self, account_id: AccountId) -> Option<ClosedAccount> {
+      self.closed_accounts.get(&account_id).cloned()
+  }
+}
@@ -0,0 +1,177 @@
+// services/blockchain/near-rs/core-banking/src/address_book.rs
+use near_sdk::{near, env, AccountId, Gas, Promise, PromiseError};
+use biocrypticbank_common::interfaces::ext_account_recovery;
+
+use crate::{assert_valid_memo, BankEvent, BioCrypticBankCore, BioCrypticBankCoreExt};
+
+const NANOS_PER_SECOND: u64 = 1_000_000_000;
+/// Delay before a new recipient becomes usable, or before address-book mode switches off.
+const ADDRESS_BOOK_TIMELOCK_SEC: u64 = 24 * 60 * 60;
+const MAX_TRUSTED_RECIPIENTS: usize = 50;
+const GAS_FOR_GET_GUARDIANS: Gas = Gas::from_tgas(10);
+const GAS_FOR_RECIPIENT_CALLBACK: Gas = Gas::from_tgas(10);
+
+/// Address-book mode of one account. While enabled, money can only leave the account to
+/// its active trusted recipients.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct AddressBook {
+  pub enabled: bool,
+  /// Time (ns) at which a requested switch-off takes effect.
+  pub disable_at: Option<u64>,
+}
+
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct TrustedRecipient {
+  pub recipient_id: AccountId,
+  pub label: Option<String>,
+  pub added_at: u64,
+  /// Time (ns) from which payments to the recipient are allowed.
+  pub active_at: u64,
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Restricts the caller's transfers, allowance pulls, standing orders, streams, escrows,
+  /// invoice, intent, merchant and subscription payments to its trusted recipients, and
+  /// disables gifts. Takes effect immediately and cancels a pending switch-off.
+  pub fn enable_address_book(&mut self) {
+      let initial_storage = env::storage_usage();
+      let account_id = env::predecessor_account_id();
+      self.assert_registered(&account_id);
+      self.address_books.insert(account_id.clone(), AddressBook { enabled: true, disable_at: None });
+      self.internal_charge_storage(&account_id, initial_storage);
+      BankEvent::AddressBookModeChanged { account_id, enabled: true, effective_at: env::block_timestamp() }.emit();
+  }
+
+  /// Switches the caller's address-book mode off after a 24 hour timelock.
+  pub fn disable_address_book(&mut self) {
+      let account_id = env::predecessor_account_id();
+      let book = self.address_books.get_mut(&account_id)
+          .filter(|book| book.enabled && book.disable_at.is_none())
+          .unwrap_or_else(|| env::panic_str("Address-book mode is not on."));
+      let effective_at = env::block_timestamp() + ADDRESS_BOOK_TIMELOCK_SEC * NANOS_PER_SECOND;
+      book.disable_at = Some(effective_at);
+      BankEvent::AddressBookModeChanged { account_id, enabled: false, effective_at }.emit();
+  }
+
+  /// Adds `recipient_id` to the caller's trusted recipients. It becomes usable after a 24
+  /// hour timelock, or as soon as one of the caller's guardians approves it.
+  pub fn add_trusted_recipient(&mut self, recipient_id: AccountId, label: Option<String>) {
+      let initial_storage = env::storage_usage();
+      let account_id = env::predecessor_account_id();
+      assert!(recipient_id != account_id, "Cannot add yourself as a recipient.");
+      assert_valid_memo(&label);
+      self.assert_registered(&account_id);
+      let now = env::block_timestamp();
+      let active_at = now + ADDRESS_BOOK_TIMELOCK_SEC * NANOS_PER_SECOND;
+      let recipients = self.trusted_recipients.entry(account_id.clone()).or_default();
+      assert!(recipients.iter().all(|recipient| recipient.recipient_id != recipient_id), "Recipient is already trusted.");
+      assert!(recipients.len() < MAX_TRUSTED_RECIPIENTS, "Cannot exceed {} trusted recipients.", MAX_TRUSTED_RECIPIENTS);
+      recipients.push(TrustedRecipient { recipient_id: recipient_id.clone(), label, added_at: now, active_at });
+      self.internal_charge_storage(&account_id, initial_storage);
+      BankEvent::TrustedRecipientAdded { account_id, recipient_id, active_at }.emit();
+  }
+
+  /// Removes `recipient_id` from the caller's trusted recipients. Takes effect immediately.
+  pub fn remove_trusted_recipient(&mut self, recipient_id: AccountId) {
+      let initial_storage = env::storage_usage();
+      let account_id = env::predecessor_account_id();
+      let recipients = self.trusted_recipients.get_mut(&account_id).unwrap_or_else(|| env::panic_str("Recipient is not trusted."));
+      let count = recipients.len();
+      recipients.retain(|recipient| recipient.recipient_id != recipient_id);
+      assert!(recipients.len() < count, "Recipient is not trusted.");
+      if recipients.is_empty() {
+          self.trusted_recipients.remove(&account_id);
+      }
+      self.internal_charge_storage(&account_id, initial_storage);
+      BankEvent::TrustedRecipientRemoved { account_id, recipient_id }.emit();
+  }
+
+  /// Activates `recipient_id` in `account_id`'s address book before its timelock ends.
+  /// Guardians of `account_id` only, as read from the account recovery contract.
+  pub fn approve_trusted_recipient(&mut self, account_id: AccountId, recipient_id: AccountId) -> Promise {
+      assert!(self.internal_pending_recipient(&account_id, &recipient_id), "No pending recipient to approve.");
+      let recovery_contract_id = self.recovery_contract_id.clone()
+          .unwrap_or_else(|| env::panic_str("No account recovery contract is configured."));
+      ext_account_recovery::ext(recovery_contract_id)
+          .with_static_gas(GAS_FOR_GET_GUARDIANS)
+          .get_guardians(account_id.clone())
+          .then(
+              Self::ext(env::current_account_id())
+                  .with_static_gas(GAS_FOR_RECIPIENT_CALLBACK)
+                  .on_recipient_guardians_fetched(account_id, recipient_id, env::predecessor_account_id())
+          )
+  }
+
+  /// Callback activating a trusted recipient once `guardian_id` is confirmed as a guardian.
+  #[private]
+  pub fn on_recipient_guardians_fetched(
+      &mut self,
+      account_id: AccountId,
+      recipient_id: AccountId,
+      guardian_id: AccountId,
+      #[callback_result] result: Result<Option<Vec<AccountId>>, PromiseError>,
+  ) -> bool {
+      let is_guardian = result.ok().flatten().is_some_and(|guardians| guardians.contains(&guardian_id));
+      if !is_guardian {
+          env::log_str("Approver is not a guardian of this account.");
+          return false;
+      }
+      let now = env::block_timestamp();
+      let Some(recipient) = self.trusted_recipients.get_mut(&account_id)
+          .and_then(|recipients| recipients.iter_mut().find(|recipient| recipient.recipient_id == recipient_id))
+      else {
+          env::log_str("Recipient is no longer in the address book.");
+          return false;
+      };
+      recipient.active_at = recipient.active_at.min(now);
+      BankEvent::TrustedRecipientApproved { account_id, recipient_id, guardian_id }.emit();
+      true
+  }
+
+  /// View function.
+  pub fn get_address_book(&self, account_id: AccountId) -> Option<AddressBook> {
+      self.address_books.get(&account_id).cloned()
+  }
+
+  /// View function.
+  pub fn get_trusted_recipients(&self, account_id: AccountId) -> Vec<TrustedRecipient> {
+      self.trusted_recipients.get(&account_id).cloned().unwrap_or_default()
+  }
+}
+
+impl BioCrypticBankCore {
+  fn internal_address_book_enabled(&self, account_id: &AccountId) -> bool {
+      self.address_books.get(account_id).is_some_and(|book| {
+          book.enabled && book.disable_at.is_none_or(|disable_at| env::block_timestamp() < disable_at)
+      })
+  }
+
+  fn internal_pending_recipient(&self, account_id: &AccountId, recipient_id: &AccountId) -> bool {
+      self.trusted_recipients.get(account_id).is_some_and(|recipients| {
+          recipients.iter().any(|recipient| recipient.recipient_id == *recipient_id && recipient.active_at > env::block_timestamp())
+      })
+  }
+
+  /// True unless `account_id` is in address-book mode and `recipient_id` is not one of its
+  /// active trusted recipients.
+  pub(crate) fn internal_is_trusted_destination(&self, account_id: &AccountId, recipient_id: &AccountId) -> bool {
+      !self.internal_address_book_enabled(account_id)
+          || self.trusted_recipients.get(account_id).is_some_and(|recipients| {
+              recipients.iter().any(|recipient| recipient.recipient_id == *recipient_id && recipient.active_at <= env::block_timestamp())
+          })
+  }
+
+  pub(crate) fn assert_trusted_destination(&self, account_id: &AccountId, recipient_id: &AccountId) {
+      assert!(
+          self.internal_is_trusted_destination(account_id, recipient_id),
+          "Recipient is not an active trusted recipient in your address book."
+      );
+  }
+
+  pub(crate) fn assert_address_book_off(&self, account_id: &AccountId) {
+      assert!(!self.internal_address_book_enabled(account_id), "Not available in address-book mode.");
+  }
+}
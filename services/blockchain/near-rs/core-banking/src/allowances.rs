@@ -0,0 +1,137 @@
+// services/blockchain/near-rs/core-banking/src/allowances.rs
+use near_sdk::{near, env, AccountId, NearToken};
+
+use crate::ledger::EntryKind;
+use crate::pause::Feature;
+use crate::{assert_valid_memo, BankEvent, BioCrypticBankCore, BioCrypticBankCoreExt};
+
+const MAX_SPENDERS_PER_ACCOUNT: usize = 20;
+
+/// Permission for `spender_id` to pull up to `remaining` from `owner_id`'s balance.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct Allowance {
+  pub owner_id: AccountId,
+  pub spender_id: AccountId,
+  pub remaining: NearToken,
+  pub expires_at: Option<u64>,
+  /// Total pulled by the spender since the allowance was first granted.
+  pub total_spent: NearToken,
+  pub approved_at: u64,
+}
+
+impl Allowance {
+  fn is_expired(&self, now: u64) -> bool {
+      self.expires_at.is_some_and(|expires_at| now > expires_at)
+  }
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Lets `spender_id` pull up to `allowance` from the caller's balance via `transfer_from`
+  /// until `expires_at` (ns, optional). Replaces any existing allowance for that spender
+  /// but keeps its usage history.
+  pub fn approve_spender(&mut self, spender_id: AccountId, allowance: NearToken, expires_at: Option<u64>) {
+      let initial_storage = env::storage_usage();
+      let owner_id = env::predecessor_account_id();
+      assert!(owner_id != spender_id, "Cannot approve yourself.");
+      self.assert_address_book_off(&owner_id);
+      let now = env::block_timestamp();
+      if let Some(expires_at) = expires_at {
+          assert!(expires_at > now, "Expiry must be in the future.");
+      }
+
+      let key = (owner_id.clone(), spender_id.clone());
+      let total_spent = match self.allowances.get(&key) {
+          Some(existing) => existing.total_spent,
+          None => {
+              let spenders = self.spenders_by_owner.entry(owner_id.clone()).or_default();
+              assert!(spenders.len() < MAX_SPENDERS_PER_ACCOUNT, "Too many approved spenders.");
+              spenders.push(spender_id.clone());
+              NearToken::from_yoctonear(0)
+          }
+      };
+      self.allowances.insert(key, Allowance {
+          owner_id: owner_id.clone(),
+          spender_id: spender_id.clone(),
+          remaining: allowance,
+          expires_at,
+          total_spent,
+          approved_at: now,
+      });
+      self.internal_charge_storage(&owner_id, initial_storage);
+      BankEvent::AllowanceApproved { owner_id, spender_id, allowance, expires_at }.emit();
+  }
+
+  /// Reduces the allowance granted to `spender_id` by `amount` (floored at zero).
+  pub fn decrease_allowance(&mut self, spender_id: AccountId, amount: NearToken) {
+      let owner_id = env::predecessor_account_id();
+      let allowance = self.allowances.get_mut(&(owner_id.clone(), spender_id.clone()))
+          .unwrap_or_else(|| env::panic_str("No allowance for this spender."));
+      allowance.remaining = allowance.remaining.saturating_sub(amount);
+      let remaining = allowance.remaining;
+      BankEvent::AllowanceDecreased { owner_id, spender_id, remaining }.emit();
+  }
+
+  /// Removes `spender_id`'s allowance entirely.
+  pub fn revoke_spender(&mut self, spender_id: AccountId) {
+      let owner_id = env::predecessor_account_id();
+      assert!(
+          self.allowances.remove(&(owner_id.clone(), spender_id.clone())).is_some(),
+          "No allowance for this spender."
+      );
+      if let Some(spenders) = self.spenders_by_owner.get_mut(&owner_id) {
+          spenders.retain(|existing| *existing != spender_id);
+          if spenders.is_empty() {
+              self.spenders_by_owner.remove(&owner_id);
+          }
+      }
+      BankEvent::AllowanceRevoked { owner_id, spender_id }.emit();
+  }
+
+  /// Pulls `amount` from `owner_id`'s balance to `receiver_id` using the caller's allowance.
+  /// The owner's spending limits apply and the owner pays for the ledger storage.
+  pub fn transfer_from(&mut self, owner_id: AccountId, receiver_id: AccountId, amount: NearToken, memo: Option<String>) {
+      self.assert_not_paused(Feature::Transfers);
+      let initial_storage = env::storage_usage();
+      let spender_id = env::predecessor_account_id();
+      assert!(amount.as_yoctonear() > 0, "Transfer amount must be greater than 0.");
+      assert!(owner_id != receiver_id, "Cannot transfer to the owner.");
+      assert_valid_memo(&memo);
+      self.assert_registered(&receiver_id);
+      self.assert_trusted_destination(&owner_id, &receiver_id);
+
+      let allowance = self.allowances.get_mut(&(owner_id.clone(), spender_id.clone()))
+          .unwrap_or_else(|| env::panic_str("No allowance for this spender."));
+      assert!(!allowance.is_expired(env::block_timestamp()), "Allowance has expired.");
+      assert!(amount <= allowance.remaining, "Amount exceeds the remaining allowance.");
+      allowance.remaining = allowance.remaining.saturating_sub(amount);
+      allowance.total_spent = allowance.total_spent.saturating_add(amount);
+      let remaining = allowance.remaining;
+
+      self.internal_record_outflow(&owner_id, amount);
+      self.internal_debit(&owner_id, amount);
+      self.internal_credit(&receiver_id, amount);
+      self.internal_record_movement(
+          &owner_id, &receiver_id, EntryKind::Transfer, amount, memo, Some(format!("allowance:{}", spender_id)),
+      );
+      self.internal_charge_storage(&owner_id, initial_storage);
+      BankEvent::TransferFrom { owner_id, spender_id, receiver_id, amount, remaining }.emit();
+  }
+
+  /// Retrieves the allowance `owner_id` granted to `spender_id`.
+  /// View function.
+  pub fn get_allowance(&self, owner_id: AccountId, spender_id: AccountId) -> Option<Allowance> {
+      self.allowances.get(&(owner_id, spender_id)).cloned()
+  }
+
+  /// Lists every allowance granted by `owner_id`, including usage to date.
+  /// View function.
+  pub fn get_allowances(&self, owner_id: AccountId) -> Vec<Allowance> {
+      self.spenders_by_owner.get(&owner_id)
+          .map(|spenders| spenders.iter()
+              .filter_map(|spender_id| self.allowances.get(&(owner_id.clone(), spender_id.clone())).cloned())
+              .collect())
+          .unwrap_or_default()
+  }
+}
@@ -0,0 +1,247 @@
+// services/blockchain/near-rs/core-banking/src/amortization.rs
+use near_sdk::{near, env, NearToken};
+
+use crate::lending::{LendingConfig, Loan};
+use crate::ledger::{EntryDirection, EntryKind};
+use crate::pause::Feature;
+use crate::savings::BPS_DENOMINATOR;
+use crate::term_deposits::NANOS_PER_DAY;
+use crate::{mul_div, BankEvent, BioCrypticBankCore, BioCrypticBankCoreExt, DEFAULT_PAGE_LIMIT};
+
+const MAX_INSTALLMENTS: u32 = 360;
+const MAX_PERIOD_DAYS: u32 = 365;
+
+/// Requested repayment plan for a new loan.
+#[near(serializers = [json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct ScheduleTerms {
+  pub installments: u32,
+  pub period_days: u32,
+}
+
+/// Equal-principal repayment plan of a loan. Each installment repays
+/// `original_principal / installments` plus the interest accrued by then.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct AmortizationSchedule {
+  pub installments: u32,
+  pub period_days: u32,
+  pub original_principal: NearToken,
+  pub started_at: u64,
+  /// Late fees added to the loan's interest so far.
+  pub late_fees_charged: NearToken,
+  /// Time (ns) up to which late fees have been charged.
+  pub late_fees_accrued_to: u64,
+}
+
+#[near(serializers = [json])]
+#[serde(rename_all = "snake_case")]
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum LoanStatus {
+  /// No installment is overdue.
+  Current,
+  /// An installment is overdue but still within the grace period.
+  Late,
+  /// Past the grace period; late fees accrue.
+  Delinquent,
+  /// Too many installments missed; the loan is in default.
+  Defaulted,
+}
+
+#[near(serializers = [json])]
+#[derive(Debug, PartialEq)]
+pub struct Installment {
+  pub number: u32,
+  pub due_at: u64,
+  /// Principal still owed for this installment.
+  pub principal_due: NearToken,
+  pub overdue: bool,
+}
+
+#[near(serializers = [json])]
+#[derive(Debug, PartialEq)]
+pub struct LoanScheduleView {
+  pub loan_id: u64,
+  pub status: LoanStatus,
+  pub schedule: AmortizationSchedule,
+  /// Overdue principal plus all interest and late fees owed now.
+  pub amount_due: NearToken,
+  /// Installments not yet fully repaid, oldest first.
+  pub remaining_installments: Vec<Installment>,
+}
+
+#[near(serializers = [json])]
+#[derive(Debug, PartialEq)]
+pub struct CollectionSummary {
+  pub collected: u32,
+  pub short: u32,
+}
+
+impl AmortizationSchedule {
+  pub(crate) fn new(terms: &ScheduleTerms, principal: NearToken, now: u64) -> Self {
+      assert!(terms.installments > 0 && terms.installments <= MAX_INSTALLMENTS, "Installments must be between 1 and {}.", MAX_INSTALLMENTS);
+      assert!(terms.period_days > 0 && terms.period_days <= MAX_PERIOD_DAYS, "Period must be between 1 and {} days.", MAX_PERIOD_DAYS);
+      Self {
+          installments: terms.installments,
+          period_days: terms.period_days,
+          original_principal: principal,
+          started_at: now,
+          late_fees_charged: NearToken::from_yoctonear(0),
+          late_fees_accrued_to: now,
+      }
+  }
+
+  fn due_at(&self, number: u32) -> u64 {
+      self.started_at + number as u64 * self.period_days as u64 * NANOS_PER_DAY
+  }
+
+  /// Installments whose due date has passed.
+  fn installments_due(&self, now: u64) -> u32 {
+      let elapsed = now.saturating_sub(self.started_at) / (self.period_days as u64 * NANOS_PER_DAY);
+      elapsed.min(self.installments as u64) as u32
+  }
+
+  /// Principal scheduled to be repaid by the end of installment `number`.
+  fn scheduled_principal(&self, number: u32) -> u128 {
+      mul_div(self.original_principal.as_yoctonear(), number as u128, self.installments as u128)
+  }
+
+  /// Oldest installment not fully covered by `principal_repaid`.
+  fn first_unpaid(&self, principal_repaid: u128) -> u32 {
+      (1..=self.installments)
+          .find(|number| self.scheduled_principal(*number) > principal_repaid)
+          .unwrap_or(self.installments + 1)
+  }
+}
+
+impl Loan {
+  fn principal_repaid(&self, schedule: &AmortizationSchedule) -> u128 {
+      schedule.original_principal.saturating_sub(self.principal).as_yoctonear()
+  }
+
+  /// Principal of installments already due that has not been repaid.
+  pub(crate) fn overdue_principal(&self, now: u64) -> NearToken {
+      let Some(schedule) = &self.schedule else { return NearToken::from_yoctonear(0) };
+      let scheduled = schedule.scheduled_principal(schedule.installments_due(now));
+      NearToken::from_yoctonear(scheduled.saturating_sub(self.principal_repaid(schedule)))
+  }
+
+  pub(crate) fn status(&self, config: &LendingConfig, now: u64) -> LoanStatus {
+      let Some(schedule) = &self.schedule else { return LoanStatus::Current };
+      let first_unpaid = schedule.first_unpaid(self.principal_repaid(schedule));
+      let due = schedule.installments_due(now);
+      if first_unpaid > due {
+          return LoanStatus::Current;
+      }
+      let overdue_since = schedule.due_at(first_unpaid);
+      if now - overdue_since < config.grace_days as u64 * NANOS_PER_DAY {
+          LoanStatus::Late
+      } else if due - first_unpaid + 1 < config.default_after_installments {
+          LoanStatus::Delinquent
+      } else {
+          LoanStatus::Defaulted
+      }
+  }
+
+  /// Adds the daily late fee on the principal overdue now for every whole day past the grace
+  /// period since late fees were last charged.
+  pub(crate) fn accrue_late_fees(&mut self, config: &LendingConfig, now: u64) {
+      let overdue = self.overdue_principal(now);
+      let Some(schedule) = self.schedule.as_mut() else { return };
+      let first_unpaid = schedule.first_unpaid(schedule.original_principal.saturating_sub(self.principal).as_yoctonear());
+      let grace_end = schedule.due_at(first_unpaid) + config.grace_days as u64 * NANOS_PER_DAY;
+      let from = schedule.late_fees_accrued_to.max(grace_end);
+      if overdue.is_zero() || now <= from {
+          return;
+      }
+      let days = (now - from) / NANOS_PER_DAY;
+      if days == 0 {
+          return;
+      }
+      let fee = NearToken::from_yoctonear(mul_div(
+          overdue.as_yoctonear(), config.daily_late_fee_bps as u128 * days as u128, BPS_DENOMINATOR,
+      ));
+      schedule.late_fees_charged = schedule.late_fees_charged.saturating_add(fee);
+      schedule.late_fees_accrued_to = from + days * NANOS_PER_DAY;
+      self.interest = self.interest.saturating_add(fee);
+  }
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Permissionless crank (suitable for a Croncat task or any keeper) collecting what is due
+  /// on up to `limit` amortized loans, starting at `from_index`: overdue principal plus
+  /// interest and late fees, taken from the borrower's unallocated balance. A balance that
+  /// cannot cover the whole amount is collected in part.
+  pub fn collect_installments(&mut self, from_index: Option<u64>, limit: Option<u64>) -> CollectionSummary {
+      self.assert_not_paused(Feature::Lending);
+      let now = env::block_timestamp();
+      let loan_ids: Vec<u64> = self.loans.iter()
+          .skip(from_index.unwrap_or(0) as usize)
+          .take(limit.unwrap_or(DEFAULT_PAGE_LIMIT) as usize)
+          .filter(|(_, loan)| loan.schedule.as_ref().is_some_and(|schedule| schedule.installments_due(now) > 0))
+          .map(|(id, _)| *id)
+          .collect();
+
+      let mut summary = CollectionSummary { collected: 0, short: 0 };
+      for loan_id in loan_ids {
+          let config = self.lending_config.clone();
+          let apr_bps = self.internal_borrow_apr_bps();
+          let loan = self.internal_get_loan_mut(loan_id);
+          loan.accrue(apr_bps, now);
+          loan.accrue_late_fees(&config, now);
+          let overdue = loan.overdue_principal(now);
+          if overdue.is_zero() {
+              continue;
+          }
+          let due = overdue.saturating_add(loan.interest);
+          let borrower_id = loan.borrower_id.clone();
+          let amount = due.min(self.internal_unallocated_balance(&borrower_id));
+          if amount < due {
+              summary.short += 1;
+          }
+          if amount.is_zero() {
+              continue;
+          }
+          self.internal_debit(&borrower_id, amount);
+          self.internal_record_entry(
+              &borrower_id, EntryKind::Loan, EntryDirection::Debit, amount, None, Some(format!("loan:{}", loan_id)),
+          );
+          self.internal_apply_loan_repayment(loan_id, amount);
+          summary.collected += 1;
+          BankEvent::InstallmentCollected { loan_id, borrower_id, amount, amount_due: due }.emit();
+      }
+      summary
+  }
+
+  /// Full remaining repayment schedule of an amortized loan, with its delinquency status.
+  /// View function.
+  pub fn get_loan_schedule(&self, loan_id: u64) -> Option<LoanScheduleView> {
+      let now = env::block_timestamp();
+      let mut loan = self.loans.get(&loan_id)?.clone();
+      loan.accrue(self.internal_borrow_apr_bps(), now);
+      loan.accrue_late_fees(&self.lending_config, now);
+      let schedule = loan.schedule.clone()?;
+      let repaid = loan.principal_repaid(&schedule);
+      let due = schedule.installments_due(now);
+      let remaining_installments = (schedule.first_unpaid(repaid)..=schedule.installments)
+          .map(|number| {
+              let owed = schedule.scheduled_principal(number)
+                  - schedule.scheduled_principal(number - 1).max(repaid.min(schedule.scheduled_principal(number)));
+              Installment {
+                  number,
+                  due_at: schedule.due_at(number),
+                  principal_due: NearToken::from_yoctonear(owed),
+                  overdue: number <= due,
+              }
+          })
+          .collect();
+      Some(LoanScheduleView {
+          loan_id,
+          status: loan.status(&self.lending_config, now),
+          amount_due: loan.overdue_principal(now).saturating_add(loan.interest),
+          schedule,
+          remaining_installments,
+      })
+  }
+}
@@ -0,0 +1,173 @@
+// services/blockchain/near-rs/core-banking/src/assets.rs
+use near_sdk::{near, env, AccountId, Gas, NearToken, PromiseOrValue};
+use near_sdk::json_types::U128;
+use biocrypticbank_common::assets::AssetId;
+use biocrypticbank_common::interfaces::ext_ft;
+
+use crate::ledger::{EntryDirection, EntryKind};
+use crate::operation_locks::LockedOperation;
+use crate::pause::Feature;
+use crate::{assert_valid_memo, BankEvent, BioCrypticBankCore, BioCrypticBankCoreExt};
+
+const GAS_FOR_FT_TRANSFER: Gas = Gas::from_tgas(10);
+const GAS_FOR_FT_WITHDRAW_CALLBACK: Gas = Gas::from_tgas(10);
+
+#[near]
+impl BioCrypticBankCore {
+  /// Moves `amount` of `asset_id` from the caller to `receiver_id`. NEAR goes through
+  /// `transfer`, with its fees, limits and holds; tokens move between balances directly.
+  pub fn transfer_asset(
+      &mut self,
+      receiver_id: AccountId,
+      asset_id: AssetId,
+      amount: U128,
+      memo: Option<String>,
+  ) -> PromiseOrValue<()> {
+      if asset_id == AssetId::Native {
+          return self.transfer(receiver_id, NearToken::from_yoctonear(amount.0), memo, None, None);
+      }
+      self.assert_not_paused(Feature::Transfers);
+      let sender_id = env::predecessor_account_id();
+      assert!(amount.0 > 0, "Transfer amount must be greater than 0.");
+      assert!(sender_id != receiver_id, "Cannot transfer to self.");
+      self.assert_not_dormant(&sender_id);
+      self.assert_no_recovery_hold(&sender_id);
+      assert_valid_memo(&memo);
+      self.assert_registered(&receiver_id);
+      self.assert_trusted_destination(&sender_id, &receiver_id);
+      self.internal_transfer_asset(&sender_id, &receiver_id, &asset_id, amount.0, memo);
+      PromiseOrValue::Value(())
+  }
+
+  /// Withdraws `amount` of `asset_id` from the caller's balance to its NEAR account. NEAR
+  /// goes through `withdraw`; tokens are sent with `ft_transfer`.
+  pub fn withdraw_asset(&mut self, asset_id: AssetId, amount: U128) -> PromiseOrValue<bool> {
+      match asset_id {
+          AssetId::Native => self.withdraw(NearToken::from_yoctonear(amount.0), None, None, None),
+          AssetId::Ft(token_id) => self.ft_withdraw(token_id, amount),
+      }
+  }
+
+  /// `account_id`'s balance of `asset_id`. For NEAR this is the full balance, including
+  /// funds reserved in envelopes.
+  /// View function.
+  pub fn get_asset_balance(&self, account_id: AccountId, asset_id: AssetId) -> U128 {
+      match asset_id {
+          AssetId::Native => U128(self.get_balance(account_id).as_yoctonear()),
+          AssetId::Ft(token_id) => self.get_ft_balance(account_id, token_id),
+      }
+  }
+}
+
+impl BioCrypticBankCore {
+  /// Part of `account_id`'s `asset` balance it can spend without drawing on credit.
+  pub(crate) fn internal_asset_balance(&self, account_id: &AccountId, asset: &AssetId) -> u128 {
+      match asset {
+          AssetId::Native => self.internal_unallocated_balance(account_id).as_yoctonear(),
+          AssetId::Ft(token_id) => self.ft_balances.get(&(account_id.clone(), token_id.clone())).copied().unwrap_or(0),
+      }
+  }
+
+  /// Adds `amount` of `asset` to `account_id`'s balance. NEAR movements are booked in the
+  /// ledger under `kind`; token balances are not.
+  pub(crate) fn internal_asset_credit(
+      &mut self,
+      account_id: &AccountId,
+      asset: &AssetId,
+      amount: u128,
+      kind: EntryKind,
+      reference: Option<String>,
+  ) {
+      match asset {
+          AssetId::Native => {
+              let amount = NearToken::from_yoctonear(amount);
+              self.internal_credit(account_id, amount);
+              self.internal_record_entry(account_id, kind, EntryDirection::Credit, amount, None, reference);
+          }
+          AssetId::Ft(token_id) => self.internal_ft_credit(account_id, token_id, amount),
+      }
+  }
+
+  /// Takes `amount` of `asset` from `account_id`'s balance, panicking if it is short. NEAR
+  /// movements are booked in the ledger under `kind`; token balances are not.
+  pub(crate) fn internal_asset_debit(
+      &mut self,
+      account_id: &AccountId,
+      asset: &AssetId,
+      amount: u128,
+      kind: EntryKind,
+      reference: Option<String>,
+  ) {
+      match asset {
+          AssetId::Native => {
+              let amount = NearToken::from_yoctonear(amount);
+              self.internal_debit(account_id, amount);
+              self.internal_record_entry(account_id, kind, EntryDirection::Debit, amount, None, reference);
+          }
+          AssetId::Ft(token_id) => self.internal_ft_debit(account_id, token_id, amount),
+      }
+  }
+
+  /// Credits a deposit of `amount` of `asset` received from outside the bank.
+  pub(crate) fn internal_deposit_asset(&mut self, account_id: &AccountId, asset: &AssetId, amount: u128) {
+      match asset {
+          AssetId::Native => self.internal_deposit(account_id, None, NearToken::from_yoctonear(amount), None, None),
+          AssetId::Ft(token_id) => {
+              let initial_storage = env::storage_usage();
+              self.internal_ft_credit(account_id, token_id, amount);
+              self.internal_charge_storage(account_id, initial_storage);
+              BankEvent::FtDeposit { account_id: account_id.clone(), token_id: token_id.clone(), amount: U128(amount) }.emit();
+          }
+      }
+  }
+
+  /// Debits `amount` of `asset` and sends it to `account_id`. A token balance is restored if
+  /// `ft_transfer` fails.
+  pub(crate) fn internal_withdraw_asset(&mut self, account_id: &AccountId, asset: &AssetId, amount: u128) -> PromiseOrValue<bool> {
+      let AssetId::Ft(token_id) = asset else {
+          return self.internal_withdraw(account_id, NearToken::from_yoctonear(amount), None, None, None);
+      };
+      self.internal_ft_debit(account_id, token_id, amount);
+      let lock_id = self.internal_lock_account(account_id, LockedOperation::TokenWithdrawal);
+      env::log_str(&format!("Withdrawing {} of {} for {}", amount, token_id, account_id));
+      ext_ft::ext(token_id.clone())
+          .with_attached_deposit(NearToken::from_yoctonear(1))
+          .with_static_gas(GAS_FOR_FT_TRANSFER)
+          .ft_transfer(account_id.clone(), U128(amount), None)
+          .then(
+              Self::ext(env::current_account_id())
+                  .with_static_gas(GAS_FOR_FT_WITHDRAW_CALLBACK)
+                  .ft_withdraw_callback(account_id.clone(), token_id.clone(), U128(amount), Some(lock_id))
+          )
+          .into()
+  }
+
+  /// Moves `amount` of `asset` between two accounts. Checks that the move neither creates
+  /// nor destroys funds.
+  pub(crate) fn internal_transfer_asset(
+      &mut self,
+      sender_id: &AccountId,
+      receiver_id: &AccountId,
+      asset: &AssetId,
+      amount: u128,
+      memo: Option<String>,
+  ) {
+      let AssetId::Ft(token_id) = asset else {
+          return self.internal_transfer(sender_id, receiver_id, NearToken::from_yoctonear(amount), memo, None, None);
+      };
+      let initial_storage = env::storage_usage();
+      let held_before = self.internal_asset_balance(sender_id, asset) + self.internal_asset_balance(receiver_id, asset);
+      self.internal_ft_debit(sender_id, token_id, amount);
+      self.internal_ft_credit(receiver_id, token_id, amount);
+      let held_after = self.internal_asset_balance(sender_id, asset) + self.internal_asset_balance(receiver_id, asset);
+      assert_eq!(held_before, held_after, "Asset transfer must conserve balances.");
+      self.internal_charge_storage(sender_id, initial_storage);
+      BankEvent::FtTransfer {
+          sender_id: sender_id.clone(),
+          receiver_id: receiver_id.clone(),
+          token_id: token_id.clone(),
+          amount: U128(amount),
+          memo,
+      }.emit();
+  }
+}
@@ -0,0 +1,146 @@
+// services/blockchain/near-rs/core-banking/src/batch.rs
+use near_sdk::{near, env, AccountId, NearToken};
+
+use crate::ledger::EntryKind;
+use crate::pause::Feature;
+use crate::savings::BPS_DENOMINATOR;
+use crate::{mul_div, BankEvent, BioCrypticBankCore, BioCrypticBankCoreExt, MAX_MEMO_LENGTH};
+
+/// Hard ceiling on `max_batch_size`, bounding the gas a single batch can use.
+const MAX_BATCH_SIZE_LIMIT: u32 = 200;
+/// Upper bound on the batch fee (5%).
+const MAX_BATCH_FEE_BPS: u16 = 500;
+
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct BatchConfig {
+  pub max_batch_size: u32,
+  /// Fee charged once per batch on the total amount sent, in basis points.
+  pub fee_bps: u16,
+}
+
+impl Default for BatchConfig {
+  fn default() -> Self {
+      Self { max_batch_size: 100, fee_bps: 0 }
+  }
+}
+
+#[near(serializers = [json])]
+#[derive(Debug, PartialEq)]
+pub struct BatchItemResult {
+  pub index: u32,
+  pub receiver_id: AccountId,
+  pub amount: NearToken,
+  pub success: bool,
+  pub error: Option<String>,
+}
+
+#[near(serializers = [json])]
+#[derive(Debug, PartialEq)]
+pub struct BatchTransferResult {
+  pub batch_id: u64,
+  pub results: Vec<BatchItemResult>,
+  pub total_sent: NearToken,
+  pub fee: NearToken,
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Updates the maximum batch size and batch fee. Owner or governance only.
+  pub fn set_batch_config(&mut self, max_batch_size: u32, fee_bps: u16) {
+      self.assert_owner_or_governance();
+      assert!(
+          max_batch_size > 0 && max_batch_size <= MAX_BATCH_SIZE_LIMIT,
+          "Batch size must be between 1 and {}.", MAX_BATCH_SIZE_LIMIT
+      );
+      assert!(fee_bps <= MAX_BATCH_FEE_BPS, "Batch fee cannot exceed {} bps.", MAX_BATCH_FEE_BPS);
+      self.batch_config = BatchConfig { max_batch_size, fee_bps };
+  }
+
+  /// Pays many recipients from the caller's balance in one call, e.g. for payroll. Invalid
+  /// items (zero amount, self, unregistered or untrusted receiver, oversized memo) are skipped and reported;
+  /// the valid items plus a single fee on their total are then debited all at once, and the
+  /// whole batch fails if the balance or spending limits cannot cover them.
+  pub fn batch_transfer(&mut self, transfers: Vec<(AccountId, NearToken, Option<String>)>) -> BatchTransferResult {
+      self.assert_not_paused(Feature::Transfers);
+      let initial_storage = env::storage_usage();
+      let sender_id = env::predecessor_account_id();
+      assert!(!transfers.is_empty(), "Batch cannot be empty.");
+      self.assert_not_dormant(&sender_id);
+      self.assert_no_recovery_hold(&sender_id);
+      let requested = transfers.iter().fold(NearToken::from_yoctonear(0), |total, (_, amount, _)| total.saturating_add(*amount));
+      assert!(
+          !self.internal_guardian_approval_needed(&sender_id, requested),
+          "Batches above your guardian threshold are not allowed; use transfer instead."
+      );
+      assert!(
+          transfers.len() <= self.batch_config.max_batch_size as usize,
+          "Batch cannot exceed {} transfers.", self.batch_config.max_batch_size
+      );
+
+      let mut results = Vec::with_capacity(transfers.len());
+      let mut valid = Vec::new();
+      let mut total = 0u128;
+      for (index, (receiver_id, amount, memo)) in transfers.into_iter().enumerate() {
+          let error = if amount.is_zero() {
+              Some("Amount must be greater than 0.")
+          } else if receiver_id == sender_id {
+              Some("Cannot transfer to self.")
+          } else if !self.storage_accounts.contains_key(&receiver_id) {
+              Some("Receiver is not registered.")
+          } else if memo.as_ref().is_some_and(|memo| memo.len() > MAX_MEMO_LENGTH) {
+              Some("Memo is too long.")
+          } else if !self.internal_is_trusted_destination(&sender_id, &receiver_id) {
+              Some("Receiver is not in the address book.")
+          } else {
+              None
+          };
+          if error.is_none() {
+              total += amount.as_yoctonear();
+              valid.push((receiver_id.clone(), amount, memo));
+          }
+          results.push(BatchItemResult {
+              index: index as u32,
+              receiver_id,
+              amount,
+              success: error.is_none(),
+              error: error.map(str::to_string),
+          });
+      }
+
+      let batch_id = self.next_batch_id;
+      self.next_batch_id += 1;
+      let total_sent = NearToken::from_yoctonear(total);
+      let fee = NearToken::from_yoctonear(mul_div(total, self.batch_config.fee_bps as u128, BPS_DENOMINATOR));
+      let fee = self.internal_spend_fee_credit(&sender_id, fee);
+      let reference = format!("batch:{}", batch_id);
+
+      self.internal_record_outflow(&sender_id, total_sent);
+      self.internal_debit(&sender_id, total_sent.saturating_add(fee));
+      for (receiver_id, amount, memo) in valid {
+          self.internal_credit(&receiver_id, amount);
+          self.internal_record_movement(&sender_id, &receiver_id, EntryKind::Transfer, amount, memo, Some(reference.clone()));
+          self.internal_apply_round_up(&sender_id, amount);
+          self.internal_apply_donation_rule(&sender_id, amount);
+      }
+      self.internal_charge_fee(&sender_id, fee, Some(reference));
+      self.internal_charge_storage(&sender_id, initial_storage);
+
+      let succeeded = results.iter().filter(|result| result.success).count() as u32;
+      BankEvent::BatchTransfer {
+          batch_id,
+          sender_id,
+          succeeded,
+          failed: results.len() as u32 - succeeded,
+          total_sent,
+          fee,
+      }.emit();
+      BatchTransferResult { batch_id, results, total_sent, fee }
+  }
+
+  /// Retrieves the batch size limit and fee.
+  /// View function.
+  pub fn get_batch_config(&self) -> BatchConfig {
+      self.batch_config.clone()
+  }
+}
@@ -0,0 +1,93 @@
+// services/blockchain/near-rs/core-banking/src/change_feed.rs
+use near_sdk::{near, env, AccountId, NearToken};
+
+use crate::ledger::LedgerEntry;
+use crate::{BioCrypticBankCore, BioCrypticBankCoreExt, DEFAULT_PAGE_LIMIT};
+
+/// Ledger entries from a sequence number onward. Sequence numbers are ledger entry ids.
+#[near(serializers = [json])]
+#[derive(Debug, PartialEq)]
+pub struct LedgerEntriesPage {
+  pub entries: Vec<LedgerEntry>,
+  /// Sequence to pass on the next call. Equals the current ledger length once caught up.
+  pub next_sequence: u64,
+}
+
+/// An account's balance after one ledger entry.
+#[near(serializers = [json])]
+#[derive(Debug, PartialEq)]
+pub struct BalanceChange {
+  pub sequence: u64,
+  pub block_height: u64,
+  pub account_id: AccountId,
+  pub balance: NearToken,
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Reads up to `limit` sequence numbers starting at `sequence`, for indexers and mobile
+  /// sync pulling deltas. Entries removed when their account unregistered are skipped, so a
+  /// page can hold fewer than `limit` entries before the feed is caught up.
+  /// View function.
+  pub fn get_ledger_entries_since(&self, sequence: u64, limit: Option<u64>) -> LedgerEntriesPage {
+      let end = sequence.saturating_add(limit.unwrap_or(DEFAULT_PAGE_LIMIT)).min(self.next_ledger_entry_id);
+      LedgerEntriesPage {
+          entries: (sequence..end).filter_map(|entry_id| self.ledger_entries.get(&entry_id).cloned()).collect(),
+          next_sequence: end.max(sequence),
+      }
+  }
+
+  /// Balance changes booked in `block_height` or later, oldest first, reading up to `limit`
+  /// sequence numbers. To page on, call again with the block height of the last change and
+  /// drop the sequences already seen. Only blocks since the feed was added are covered.
+  /// View function.
+  pub fn get_balance_changes_since(&self, block_height: u64, limit: Option<u64>) -> Vec<BalanceChange> {
+      let mut block_index = self.internal_first_ledger_block_at(block_height);
+      let Some((_, start)) = self.ledger_blocks.get(block_index).copied() else {
+          return Vec::new();
+      };
+      let end = start.saturating_add(limit.unwrap_or(DEFAULT_PAGE_LIMIT)).min(self.next_ledger_entry_id);
+      let mut changes = Vec::new();
+      for sequence in start..end {
+          while self.ledger_blocks.get(block_index + 1).is_some_and(|(_, first_entry_id)| *first_entry_id <= sequence) {
+              block_index += 1;
+          }
+          let Some(entry) = self.ledger_entries.get(&sequence) else {
+              continue;
+          };
+          changes.push(BalanceChange {
+              sequence,
+              block_height: self.ledger_blocks[block_index].0,
+              account_id: entry.account_id.clone(),
+              balance: entry.balance_after,
+          });
+      }
+      changes
+  }
+}
+
+impl BioCrypticBankCore {
+  /// Notes the block ledger entry `entry_id` is booked in, once per block.
+  pub(crate) fn internal_index_entry_block(&mut self, entry_id: u64) {
+      let block_height = env::block_height();
+      let len = self.ledger_blocks.len();
+      if len > 0 && self.ledger_blocks[len - 1].0 == block_height {
+          return;
+      }
+      self.ledger_blocks.push((block_height, entry_id));
+  }
+
+  /// Index into `ledger_blocks` of the first block at or after `block_height`.
+  fn internal_first_ledger_block_at(&self, block_height: u64) -> u32 {
+      let (mut low, mut high) = (0, self.ledger_blocks.len());
+      while low < high {
+          let mid = low + (high - low) / 2;
+          if self.ledger_blocks[mid].0 < block_height {
+              low = mid + 1;
+          } else {
+              high = mid;
+          }
+      }
+      low
+  }
+}
@@ -0,0 +1,228 @@
+// services/blockchain/near-rs/core-banking/src/charity.rs
+use near_sdk::{near, env, AccountId, NearToken};
+
+use crate::ledger::{EntryDirection, EntryKind};
+use crate::savings::BPS_DENOMINATOR;
+use crate::spending_categories::month_of;
+use crate::{mul_div, BankEvent, BioCrypticBankCore, BioCrypticBankCoreExt, DEFAULT_PAGE_LIMIT};
+
+const MAX_CHARITY_NAME_LEN: usize = 64;
+/// Highest share of each payment a donation rule can give (10%).
+const MAX_DONATION_BPS: u16 = 1_000;
+/// Highest match rate (3:1).
+const MAX_MATCH_BPS: u16 = 30_000;
+
+/// A charity verified by the bank. Donations are credited to its account at the bank.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct Charity {
+  pub name: String,
+  /// Matching gift paid from the matching pool per donation, in basis points of it.
+  pub match_bps: u16,
+  pub verified_at: u64,
+  pub total_received: NearToken,
+  pub total_matched: NearToken,
+}
+
+/// An account's opt-in rule donating `bps` of each outgoing transfer to `charity_id`.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct DonationRule {
+  pub charity_id: AccountId,
+  pub bps: u16,
+}
+
+/// What a donor gave one charity in one calendar year.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct CharityDonation {
+  pub charity_id: AccountId,
+  pub amount: NearToken,
+  /// Matching gifts the bank added on the donor's behalf.
+  pub matched: NearToken,
+  pub count: u64,
+}
+
+/// A donor's annual giving, for tax receipts.
+#[near(serializers = [json])]
+#[derive(Debug, PartialEq)]
+pub struct DonationReceipt {
+  pub account_id: AccountId,
+  pub year: u32,
+  pub total_donated: NearToken,
+  pub total_matched: NearToken,
+  pub donations: Vec<CharityDonation>,
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Verifies `charity_id`, which must hold a bank account, so donations can be sent to it.
+  /// Re-registering updates its name and match rate and keeps its totals. Owner only.
+  pub fn register_charity(&mut self, charity_id: AccountId, name: String, match_bps: u16) {
+      self.assert_owner();
+      self.assert_registered(&charity_id);
+      assert!(!name.is_empty() && name.len() <= MAX_CHARITY_NAME_LEN, "Charity name must be 1 to {} bytes.", MAX_CHARITY_NAME_LEN);
+      assert!(match_bps <= MAX_MATCH_BPS, "Match rate cannot exceed {} bps.", MAX_MATCH_BPS);
+      let charity = match self.charities.get(&charity_id).cloned() {
+          Some(existing) => Charity { name: name.clone(), match_bps, ..existing },
+          None => Charity {
+              name: name.clone(),
+              match_bps,
+              verified_at: env::block_timestamp(),
+              total_received: NearToken::from_yoctonear(0),
+              total_matched: NearToken::from_yoctonear(0),
+          },
+      };
+      self.charities.insert(charity_id.clone(), charity);
+      BankEvent::CharityRegistered { charity_id, name, match_bps }.emit();
+  }
+
+  /// Removes a charity from the registry. Rules and round-ups naming it stop donating.
+  /// Owner only.
+  pub fn deregister_charity(&mut self, charity_id: AccountId) {
+      self.assert_owner();
+      assert!(self.charities.remove(&charity_id).is_some(), "Charity not found.");
+      BankEvent::CharityDeregistered { charity_id }.emit();
+  }
+
+  /// Donates `bps` of each of the caller's outgoing transfers to `charity_id`, on top of the
+  /// transfer. Donations are skipped when the unallocated balance cannot cover them.
+  pub fn set_donation_rule(&mut self, charity_id: AccountId, bps: u16) {
+      let initial_storage = env::storage_usage();
+      let account_id = env::predecessor_account_id();
+      self.assert_registered(&account_id);
+      assert!(self.charities.get(&charity_id).is_some(), "Charity not found.");
+      assert!(bps > 0 && bps <= MAX_DONATION_BPS, "Donation share must be between 1 and {} bps.", MAX_DONATION_BPS);
+      self.donation_rules.insert(account_id.clone(), DonationRule { charity_id: charity_id.clone(), bps });
+      self.internal_charge_storage(&account_id, initial_storage);
+      BankEvent::DonationRuleSet { account_id, charity_id, bps }.emit();
+  }
+
+  /// Stops donating a share of the caller's transfers. Donation records are kept.
+  pub fn disable_donation_rule(&mut self) {
+      let account_id = env::predecessor_account_id();
+      assert!(self.donation_rules.remove(&account_id).is_some(), "No donation rule is set.");
+      BankEvent::DonationRuleDisabled { account_id }.emit();
+  }
+
+  /// View function.
+  pub fn get_charity(&self, charity_id: AccountId) -> Option<Charity> {
+      self.charities.get(&charity_id).cloned()
+  }
+
+  /// View function.
+  pub fn get_charities(&self, from_index: Option<u64>, limit: Option<u64>) -> Vec<(AccountId, Charity)> {
+      self.charities.iter()
+          .skip(from_index.unwrap_or(0) as usize)
+          .take(limit.unwrap_or(DEFAULT_PAGE_LIMIT) as usize)
+          .map(|(charity_id, charity)| (charity_id.clone(), charity.clone()))
+          .collect()
+  }
+
+  /// View function.
+  pub fn get_donation_rule(&self, account_id: AccountId) -> Option<DonationRule> {
+      self.donation_rules.get(&account_id).cloned()
+  }
+
+  /// View function.
+  pub fn get_matching_pool(&self) -> NearToken {
+      self.matching_pool
+  }
+
+  /// `account_id`'s donations in calendar year `year` (UTC), by charity.
+  /// View function.
+  pub fn get_donation_receipt(&self, account_id: AccountId, year: u32) -> DonationReceipt {
+      let donations = self.donation_records.get(&(account_id.clone(), year)).cloned().unwrap_or_default();
+      let total_donated = donations.iter().fold(NearToken::from_yoctonear(0), |total, donation| total.saturating_add(donation.amount));
+      let total_matched = donations.iter().fold(NearToken::from_yoctonear(0), |total, donation| total.saturating_add(donation.matched));
+      DonationReceipt { account_id, year, total_donated, total_matched, donations }
+  }
+
+  /// Years in which `account_id` donated, ascending.
+  /// View function.
+  pub fn get_donation_years(&self, account_id: AccountId) -> Vec<u32> {
+      self.donation_years.get(&account_id).cloned().unwrap_or_default()
+  }
+}
+
+impl BioCrypticBankCore {
+  /// Moves `amount` from the fee treasury into the donation matching pool, as approved by a
+  /// `MultisigAction::FundMatchingPool` proposal.
+  pub(crate) fn internal_fund_matching_pool(&mut self, amount: NearToken) {
+      self.internal_spend_treasury(amount);
+      self.matching_pool = self.matching_pool.saturating_add(amount);
+      BankEvent::MatchingPoolFunded { amount, pool: self.matching_pool }.emit();
+  }
+
+  pub(crate) fn internal_charity_exists(&self, charity_id: &AccountId) -> bool {
+      self.charities.get(charity_id).is_some() && self.storage_accounts.contains_key(charity_id)
+  }
+
+  /// Donates the rule's share of `amount`, just sent by `account_id`. Best effort: never
+  /// fails the transfer.
+  pub(crate) fn internal_apply_donation_rule(&mut self, account_id: &AccountId, amount: NearToken) {
+      let Some(rule) = self.donation_rules.get(account_id).cloned() else { return };
+      let donation = NearToken::from_yoctonear(mul_div(amount.as_yoctonear(), rule.bps as u128, BPS_DENOMINATOR));
+      if donation.is_zero()
+          || *account_id == rule.charity_id
+          || !self.internal_charity_exists(&rule.charity_id)
+          || self.internal_unallocated_balance(account_id) < donation
+      {
+          return;
+      }
+      self.internal_debit(account_id, donation);
+      self.internal_donate(account_id, &rule.charity_id, donation);
+  }
+
+  /// Credits `amount`, already debited from `donor_id`, to `charity_id` and adds the
+  /// charity's matching gift as far as the matching pool covers it.
+  pub(crate) fn internal_donate(&mut self, donor_id: &AccountId, charity_id: &AccountId, amount: NearToken) {
+      self.internal_credit(charity_id, amount);
+      self.internal_record_movement(donor_id, charity_id, EntryKind::Donation, amount, None, Some("donation".to_string()));
+
+      let charity = self.charities.get(charity_id).cloned().unwrap();
+      let matched = NearToken::from_yoctonear(mul_div(amount.as_yoctonear(), charity.match_bps as u128, BPS_DENOMINATOR))
+          .min(self.matching_pool);
+      if !matched.is_zero() {
+          self.matching_pool = self.matching_pool.saturating_sub(matched);
+          self.internal_add_liabilities(matched);
+          self.internal_credit(charity_id, matched);
+          self.internal_record_entry_with_counterparty(
+              charity_id, Some(donor_id.clone()), EntryKind::Donation, EntryDirection::Credit, matched,
+              None, Some("match".to_string()),
+          );
+      }
+      self.charities.insert(charity_id.clone(), Charity {
+          total_received: charity.total_received.saturating_add(amount),
+          total_matched: charity.total_matched.saturating_add(matched),
+          ..charity
+      });
+
+      let year = month_of(env::block_timestamp()) / 100;
+      let key = (donor_id.clone(), year);
+      if !self.donation_records.contains_key(&key) {
+          let years = self.donation_years.entry(donor_id.clone()).or_default();
+          if !years.contains(&year) {
+              years.push(year);
+          }
+      }
+      let donations = self.donation_records.entry(key).or_default();
+      match donations.iter_mut().find(|donation| donation.charity_id == *charity_id) {
+          Some(donation) => {
+              donation.amount = donation.amount.saturating_add(amount);
+              donation.matched = donation.matched.saturating_add(matched);
+              donation.count += 1;
+          }
+          None => donations.push(CharityDonation { charity_id: charity_id.clone(), amount, matched, count: 1 }),
+      }
+      BankEvent::DonationMade { donor_id: donor_id.clone(), charity_id: charity_id.clone(), amount, matched }.emit();
+  }
+
+  /// Deletes `account_id`'s donation rule and annual records.
+  pub(crate) fn internal_remove_donations(&mut self, account_id: &AccountId) {
+      self.donation_rules.remove(account_id);
+      for year in self.donation_years.remove(account_id).unwrap_or_default() {
+          self.donation_records.remove(&(account_id.clone(), year));
+      }
+  }
+}
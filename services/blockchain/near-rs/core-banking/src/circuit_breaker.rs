@@ -0,0 +1,199 @@
+// services/blockchain/near-rs/core-banking/src/circuit_breaker.rs
+use near_sdk::{near, env, AccountId, NearToken};
+
+use crate::pause::Feature;
+use crate::savings::BPS_DENOMINATOR;
+use crate::{mul_div, BankEvent, BioCrypticBankCore, BioCrypticBankCoreExt};
+
+const NANOS_PER_SECOND: u64 = 1_000_000_000;
+/// Slices the rolling window is tracked in.
+const WINDOW_BUCKETS: u64 = 12;
+const MIN_WINDOW_SEC: u64 = 60 * 60;
+
+/// When the breaker trips and who must agree to reset it. Changed through a multisig
+/// proposal. The breaker only arms once there are `resets_required` multisig admins to
+/// reset it.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct CircuitBreakerConfig {
+  /// Length of the rolling window flows are summed over.
+  pub window_sec: u64,
+  /// Net outflow over the window, as a share of total liabilities, that trips the breaker.
+  /// Zero disables the breaker.
+  pub max_outflow_bps: u16,
+  /// Net outflow the breaker always tolerates, so a small book is not halted by a single
+  /// withdrawal.
+  pub min_trip_outflow: NearToken,
+  /// Multisig admins that must approve a reset.
+  pub resets_required: u32,
+}
+
+impl Default for CircuitBreakerConfig {
+  fn default() -> Self {
+      Self {
+          window_sec: 24 * 60 * 60,
+          max_outflow_bps: 2_000,
+          min_trip_outflow: NearToken::from_near(100),
+          resets_required: 2,
+      }
+  }
+}
+
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct CircuitBreakerState {
+  /// NEAR sent out per window slice, as `(slice_start, amount)`, oldest first.
+  pub outflow_buckets: Vec<(u64, NearToken)>,
+  /// NEAR deposited per window slice, offsetting outflow.
+  pub inflow_buckets: Vec<(u64, NearToken)>,
+  /// Set while the breaker holds withdrawals, independently of any manual pause.
+  pub tripped_at: Option<u64>,
+  /// Admins that approved resetting the tripped breaker.
+  pub reset_approvals: Vec<AccountId>,
+}
+
+#[near(serializers = [json])]
+#[derive(Debug, PartialEq)]
+pub struct CircuitBreakerView {
+  pub config: CircuitBreakerConfig,
+  pub state: CircuitBreakerState,
+  /// NEAR sent out over the current window.
+  pub window_outflow: NearToken,
+  /// NEAR deposited over the current window.
+  pub window_inflow: NearToken,
+  /// Net window outflow that trips the breaker at current liabilities.
+  pub trip_threshold: NearToken,
+}
+
+impl CircuitBreakerState {
+  /// Outflow over the window less inflow over the same window.
+  fn net_outflow(&self, now: u64, window: u64) -> NearToken {
+      window_total(&self.outflow_buckets, now, window).saturating_sub(window_total(&self.inflow_buckets, now, window))
+  }
+}
+
+fn window_total(buckets: &[(u64, NearToken)], now: u64, window: u64) -> NearToken {
+  buckets.iter()
+      .filter(|(start, _)| start + window > now)
+      .fold(NearToken::from_yoctonear(0), |total, (_, amount)| total.saturating_add(*amount))
+}
+
+fn record_in_window(buckets: &mut Vec<(u64, NearToken)>, amount: NearToken, now: u64, window: u64) {
+  let slice = (window / WINDOW_BUCKETS).max(1);
+  let start = now - now % slice;
+  buckets.retain(|(bucket_start, _)| bucket_start + window > now);
+  match buckets.last_mut() {
+      Some((bucket_start, total)) if *bucket_start == start => *total = total.saturating_add(amount),
+      _ => buckets.push((start, amount)),
+  }
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Approves resetting the tripped breaker. Once enough admins approve, the breaker releases
+  /// withdrawals and the flow window starts afresh. A manual pause set by a pauser stays in
+  /// place. Admins only.
+  pub fn approve_circuit_breaker_reset(&mut self) {
+      let admin_id = env::predecessor_account_id();
+      self.assert_multisig_admin(&admin_id);
+      assert!(self.circuit_breaker.tripped_at.is_some(), "Circuit breaker is not tripped.");
+      let approvals = &mut self.circuit_breaker.reset_approvals;
+      assert!(!approvals.contains(&admin_id), "Admin has already approved the reset.");
+      approvals.push(admin_id.clone());
+      let valid = approvals.iter().filter(|approver| self.multisig_admins.contains(*approver)).count() as u32;
+      BankEvent::CircuitBreakerResetApproved { admin_id, approvals: valid }.emit();
+      if valid < self.circuit_breaker_config.resets_required {
+          return;
+      }
+      self.circuit_breaker = CircuitBreakerState::default();
+      BankEvent::CircuitBreakerReset { approvals: valid }.emit();
+  }
+
+  /// View function.
+  pub fn get_circuit_breaker(&self) -> CircuitBreakerView {
+      let now = env::block_timestamp();
+      let window = self.circuit_breaker_config.window_sec * NANOS_PER_SECOND;
+      CircuitBreakerView {
+          config: self.circuit_breaker_config.clone(),
+          state: self.circuit_breaker.clone(),
+          window_outflow: window_total(&self.circuit_breaker.outflow_buckets, now, window),
+          window_inflow: window_total(&self.circuit_breaker.inflow_buckets, now, window),
+          trip_threshold: self.internal_circuit_breaker_threshold(self.circuit_breaker.net_outflow(now, window)),
+      }
+  }
+}
+
+impl BioCrypticBankCore {
+  /// Panics unless `config` is valid and the current admins can reset the breaker under it.
+  pub(crate) fn assert_valid_circuit_breaker_config(&self, config: &CircuitBreakerConfig) {
+      assert!(config.window_sec >= MIN_WINDOW_SEC, "Window must be at least {} seconds.", MIN_WINDOW_SEC);
+      assert!(config.max_outflow_bps as u128 <= BPS_DENOMINATOR, "Threshold cannot exceed 10000 bps.");
+      assert!(config.resets_required > 0, "At least one admin must approve a reset.");
+      assert!(
+          config.resets_required <= self.multisig_admins.len(),
+          "Resets required cannot exceed the number of admins."
+      );
+  }
+
+  /// Trip threshold given `net_outflow` already left over the window: a share of the
+  /// liabilities as they stood before that outflow, and never below the configured floor.
+  fn internal_circuit_breaker_threshold(&self, net_outflow: NearToken) -> NearToken {
+      let config = &self.circuit_breaker_config;
+      let base = self.get_total_liabilities().saturating_add(net_outflow);
+      NearToken::from_yoctonear(mul_div(base.as_yoctonear(), config.max_outflow_bps as u128, BPS_DENOMINATOR))
+          .max(config.min_trip_outflow)
+  }
+
+  /// Counts `amount` of user funds leaving the contract, first checking solvency if
+  /// `set_solvency_checks` is on. If net outflow over the window passes the threshold,
+  /// holds withdrawals until admins reset the breaker; the outflow that trips it still goes
+  /// through. The breaker does not trip while there are too few admins to reset it.
+  pub(crate) fn internal_record_contract_outflow(&mut self, amount: NearToken) {
+      if self.solvency_checks {
+          self.internal_assert_solvent(amount);
+      }
+      let config = self.circuit_breaker_config.clone();
+      if config.max_outflow_bps == 0 {
+          return;
+      }
+      let now = env::block_timestamp();
+      let window = config.window_sec * NANOS_PER_SECOND;
+      record_in_window(&mut self.circuit_breaker.outflow_buckets, amount, now, window);
+      if self.circuit_breaker.tripped_at.is_some() || self.multisig_admins.len() < config.resets_required {
+          return;
+      }
+      let net_outflow = self.circuit_breaker.net_outflow(now, window);
+      let threshold = self.internal_circuit_breaker_threshold(net_outflow);
+      if net_outflow <= threshold {
+          return;
+      }
+      self.circuit_breaker.tripped_at = Some(now);
+      BankEvent::CircuitBreakerTripped { net_outflow, threshold }.emit();
+  }
+
+  /// Counts `amount` of NEAR deposited into the contract against the window's outflow.
+  pub(crate) fn internal_record_contract_inflow(&mut self, amount: NearToken) {
+      let config = &self.circuit_breaker_config;
+      if config.max_outflow_bps == 0 {
+          return;
+      }
+      let window = config.window_sec * NANOS_PER_SECOND;
+      record_in_window(&mut self.circuit_breaker.inflow_buckets, amount, env::block_timestamp(), window);
+  }
+
+  /// Takes back `amount` of recorded outflow that never left the contract, newest first.
+  pub(crate) fn internal_reverse_contract_outflow(&mut self, amount: NearToken) {
+      let mut remaining = amount;
+      for (_, total) in self.circuit_breaker.outflow_buckets.iter_mut().rev() {
+          let reversed = remaining.min(*total);
+          *total = total.saturating_sub(reversed);
+          remaining = remaining.saturating_sub(reversed);
+      }
+      self.circuit_breaker.outflow_buckets.retain(|(_, total)| !total.is_zero());
+  }
+
+  /// Whether the tripped breaker holds `feature`.
+  pub(crate) fn is_held_by_circuit_breaker(&self, feature: Feature) -> bool {
+      feature == Feature::Withdrawals && self.circuit_breaker.tripped_at.is_some()
+  }
+}
@@ -0,0 +1,168 @@
+// services/blockchain/near-rs/core-banking/src/claimable_transfers.rs
+use near_sdk::{near, env, AccountId, NearToken};
+
+use crate::ledger::{EntryDirection, EntryKind};
+use crate::pause::Feature;
+use crate::{assert_valid_memo, BankEvent, BioCrypticBankCore, BioCrypticBankCoreExt, DEFAULT_PAGE_LIMIT};
+
+const NANOS_PER_SECOND: u64 = 1_000_000_000;
+const MAX_CLAIMABLE_TRANSFERS_PER_ACCOUNT: usize = 50;
+const DEFAULT_CLAIM_WINDOW_SEC: u64 = 7 * 24 * 60 * 60;
+const MAX_CLAIM_WINDOW_SEC: u64 = 30 * 24 * 60 * 60;
+
+/// Funds sent to `receiver_id` that move only once the receiver claims them.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct ClaimableTransfer {
+  pub id: u64,
+  pub sender_id: AccountId,
+  pub receiver_id: AccountId,
+  pub amount: NearToken,
+  pub memo: Option<String>,
+  pub created_at: u64,
+  /// Timestamp (ns) after which the transfer can no longer be claimed and refunds the sender.
+  pub expires_at: u64,
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Sets `amount` aside from the caller's balance for `receiver_id`, who must call
+  /// `claim_transfer` within `claim_window_sec` (default 7 days) for the funds to move. The
+  /// receiver need not be registered yet, so a mistyped account id costs nothing: the sender
+  /// can cancel while the transfer is unclaimed and it is refunded after expiry.
+  /// Returns the transfer id.
+  pub fn send_claimable_transfer(
+      &mut self,
+      receiver_id: AccountId,
+      amount: NearToken,
+      claim_window_sec: Option<u64>,
+      memo: Option<String>,
+  ) -> u64 {
+      self.assert_not_paused(Feature::Transfers);
+      let initial_storage = env::storage_usage();
+      let sender_id = env::predecessor_account_id();
+      assert!(amount.as_yoctonear() > 0, "Amount must be greater than 0.");
+      assert!(sender_id != receiver_id, "Cannot transfer to self.");
+      let claim_window_sec = claim_window_sec.unwrap_or(DEFAULT_CLAIM_WINDOW_SEC);
+      assert!(
+          claim_window_sec > 0 && claim_window_sec <= MAX_CLAIM_WINDOW_SEC,
+          "Claim window must be between 1 and {} seconds.", MAX_CLAIM_WINDOW_SEC
+      );
+      assert_valid_memo(&memo);
+      self.assert_not_dormant(&sender_id);
+      self.assert_no_recovery_hold(&sender_id);
+      self.assert_trusted_destination(&sender_id, &receiver_id);
+
+      let id = self.next_claimable_transfer_id;
+      self.next_claimable_transfer_id += 1;
+      self.internal_record_outflow(&sender_id, amount);
+      self.internal_debit(&sender_id, amount);
+      self.internal_record_entry(
+          &sender_id, EntryKind::ClaimableTransfer, EntryDirection::Debit, amount, memo.clone(), Some(format!("claimable:{}", id)),
+      );
+      for account_id in [&sender_id, &receiver_id] {
+          let ids = self.claimable_transfers_by_account.entry(account_id.clone()).or_default();
+          assert!(ids.len() < MAX_CLAIMABLE_TRANSFERS_PER_ACCOUNT, "Too many unclaimed transfers.");
+          ids.push(id);
+      }
+      let now = env::block_timestamp();
+      let expires_at = now + claim_window_sec * NANOS_PER_SECOND;
+      self.claimable_transfers.insert(id, ClaimableTransfer {
+          id,
+          sender_id: sender_id.clone(),
+          receiver_id: receiver_id.clone(),
+          amount,
+          memo,
+          created_at: now,
+          expires_at,
+      });
+      self.internal_charge_storage(&sender_id, initial_storage);
+      BankEvent::ClaimableTransferSent { id, sender_id, receiver_id, amount, expires_at }.emit();
+      id
+  }
+
+  /// Moves unexpired transfer `transfer_id` into the caller's balance. Receiver only.
+  pub fn claim_transfer(&mut self, transfer_id: u64) {
+      self.assert_not_paused(Feature::Transfers);
+      let receiver_id = env::predecessor_account_id();
+      let transfer = self.internal_get_claimable_transfer(transfer_id);
+      assert_eq!(transfer.receiver_id, receiver_id, "Only the receiver can claim this transfer.");
+      assert!(env::block_timestamp() <= transfer.expires_at, "Transfer has expired.");
+      self.assert_registered(&receiver_id);
+      let initial_storage = env::storage_usage();
+      self.internal_remove_claimable_transfer(&transfer);
+      self.internal_credit(&receiver_id, transfer.amount);
+      self.internal_record_entry_with_counterparty(
+          &receiver_id, Some(transfer.sender_id.clone()), EntryKind::ClaimableTransfer, EntryDirection::Credit,
+          transfer.amount, transfer.memo.clone(), Some(format!("claimable:{}", transfer_id)),
+      );
+      self.internal_charge_storage(&receiver_id, initial_storage);
+      BankEvent::ClaimableTransferClaimed { id: transfer_id, sender_id: transfer.sender_id, receiver_id, amount: transfer.amount }.emit();
+  }
+
+  /// Takes back unclaimed transfer `transfer_id`. Sender only.
+  pub fn cancel_claimable_transfer(&mut self, transfer_id: u64) {
+      let transfer = self.internal_get_claimable_transfer(transfer_id);
+      assert_eq!(transfer.sender_id, env::predecessor_account_id(), "Only the sender can cancel this transfer.");
+      self.internal_refund_claimable_transfer(transfer, false);
+  }
+
+  /// Permissionless crank refunding up to `limit` expired, unclaimed transfers to their
+  /// senders, starting at `from_index`. Returns the number refunded.
+  pub fn refund_expired_transfers(&mut self, from_index: Option<u64>, limit: Option<u64>) -> u32 {
+      let now = env::block_timestamp();
+      let expired: Vec<ClaimableTransfer> = self.claimable_transfers.values()
+          .skip(from_index.unwrap_or(0) as usize)
+          .take(limit.unwrap_or(DEFAULT_PAGE_LIMIT) as usize)
+          .filter(|transfer| now > transfer.expires_at)
+          .cloned()
+          .collect();
+      let refunded = expired.len() as u32;
+      for transfer in expired {
+          self.internal_refund_claimable_transfer(transfer, true);
+      }
+      refunded
+  }
+
+  /// View function.
+  pub fn get_claimable_transfer(&self, transfer_id: u64) -> Option<ClaimableTransfer> {
+      self.claimable_transfers.get(&transfer_id).cloned()
+  }
+
+  /// Unclaimed transfers `account_id` has sent or can claim.
+  /// View function.
+  pub fn get_claimable_transfers_for_account(&self, account_id: AccountId) -> Vec<ClaimableTransfer> {
+      self.claimable_transfers_by_account.get(&account_id)
+          .map(|ids| ids.iter().filter_map(|id| self.claimable_transfers.get(id).cloned()).collect())
+          .unwrap_or_default()
+  }
+}
+
+impl BioCrypticBankCore {
+  fn internal_get_claimable_transfer(&self, transfer_id: u64) -> ClaimableTransfer {
+      self.claimable_transfers.get(&transfer_id).cloned()
+          .unwrap_or_else(|| env::panic_str("Claimable transfer not found."))
+  }
+
+  fn internal_refund_claimable_transfer(&mut self, transfer: ClaimableTransfer, expired: bool) {
+      self.internal_remove_claimable_transfer(&transfer);
+      self.internal_credit(&transfer.sender_id, transfer.amount);
+      self.internal_record_entry(
+          &transfer.sender_id, EntryKind::ClaimableTransfer, EntryDirection::Credit, transfer.amount,
+          transfer.memo.clone(), Some(format!("claimable:{}", transfer.id)),
+      );
+      BankEvent::ClaimableTransferRefunded { id: transfer.id, sender_id: transfer.sender_id, amount: transfer.amount, expired }.emit();
+  }
+
+  fn internal_remove_claimable_transfer(&mut self, transfer: &ClaimableTransfer) {
+      self.claimable_transfers.remove(&transfer.id);
+      for account_id in [&transfer.sender_id, &transfer.receiver_id] {
+          if let Some(ids) = self.claimable_transfers_by_account.get_mut(account_id) {
+              ids.retain(|existing| *existing != transfer.id);
+              if ids.is_empty() {
+                  self.claimable_transfers_by_account.remove(account_id);
+              }
+          }
+      }
+  }
+}
@@ -0,0 +1,168 @@
+// services/blockchain/near-rs/core-banking/src/crank_tasks.rs
+use near_sdk::{near, env, Gas};
+
+use crate::roles::Role;
+use crate::{BankEvent, BioCrypticBankCore, BioCrypticBankCoreExt};
+
+const NANOS_PER_SECOND: u64 = 1_000_000_000;
+const DEFAULT_PAGE_LIMIT: u64 = 50;
+const DEFAULT_DISPATCH_LIMIT: u32 = 10;
+/// Shortest interval between runs of a task (1 minute).
+const MIN_TASK_INTERVAL_SEC: u64 = 60;
+const MIN_TASK_GAS_TGAS: u64 = 10;
+const MAX_TASK_GAS_TGAS: u64 = 250;
+/// Gas the dispatcher keeps for itself after scheduling the last task.
+const GAS_FOR_DISPATCH: Gas = Gas::from_tgas(10);
+
+/// Recurring bank work a crank task can run.
+#[near(serializers = [borsh, json])]
+#[serde(rename_all = "snake_case")]
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum CrankJob {
+  /// Brings the savings interest index up to date (`accrue`).
+  AccrueInterest,
+  /// Bills due subscriptions (`bill_due_subscriptions`).
+  BillSubscriptions,
+  /// Executes due standing orders (`execute_due_payments`).
+  ExecuteStandingOrders,
+}
+
+/// A job run every `interval_sec` by `run_due_crank_tasks`, each run in its own receipt
+/// with `gas_budget_tgas` of gas so a failing or heavy task cannot starve the others.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct CrankTask {
+  pub id: u64,
+  pub job: CrankJob,
+  pub interval_sec: u64,
+  /// Items a run may process, for jobs that work in batches.
+  pub batch_limit: u64,
+  pub gas_budget_tgas: u64,
+  pub next_run_at: u64,
+  /// Number of runs dispatched; each run carries its number to the handler.
+  pub runs_dispatched: u64,
+  /// Number of the last run that completed. A run whose number is not above it is ignored,
+  /// so a replayed handler call never runs a job twice.
+  pub last_completed_run: u64,
+  pub last_completed_at: Option<u64>,
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Registers a task running `job` every `interval_sec`, first at `start_at` (ns, defaults
+  /// to now), with `gas_budget_tgas` of gas per run. Returns the task id. Crank manager only.
+  pub fn register_crank_task(
+      &mut self,
+      job: CrankJob,
+      interval_sec: u64,
+      batch_limit: u64,
+      gas_budget_tgas: u64,
+      start_at: Option<u64>,
+  ) -> u64 {
+      self.assert_role(Role::CrankManager);
+      assert!(interval_sec >= MIN_TASK_INTERVAL_SEC, "Interval is shorter than the minimum.");
+      assert!(batch_limit > 0, "Batch limit must be greater than 0.");
+      assert!(
+          (MIN_TASK_GAS_TGAS..=MAX_TASK_GAS_TGAS).contains(&gas_budget_tgas),
+          "Gas budget must be between {} and {} Tgas.", MIN_TASK_GAS_TGAS, MAX_TASK_GAS_TGAS
+      );
+      let id = self.next_crank_task_id;
+      self.next_crank_task_id += 1;
+      let next_run_at = start_at.unwrap_or_else(env::block_timestamp);
+      self.crank_tasks.insert(id, CrankTask {
+          id,
+          job,
+          interval_sec,
+          batch_limit,
+          gas_budget_tgas,
+          next_run_at,
+          runs_dispatched: 0,
+          last_completed_run: 0,
+          last_completed_at: None,
+      });
+      BankEvent::CrankTaskRegistered { task_id: id, job, interval_sec, gas_budget_tgas, next_run_at }.emit();
+      id
+  }
+
+  /// Removes a task. A run already dispatched still completes. Crank manager only.
+  pub fn deregister_crank_task(&mut self, task_id: u64) {
+      self.assert_role(Role::CrankManager);
+      assert!(self.crank_tasks.remove(&task_id).is_some(), "Crank task not found.");
+      BankEvent::CrankTaskDeregistered { task_id }.emit();
+  }
+
+  /// Permissionless crank (the single entry point to register with Croncat or any keeper)
+  /// dispatching up to `limit` due tasks, each as its own call with its gas budget. Stops
+  /// early when the attached gas cannot cover the next budget; undispatched tasks stay due.
+  /// Returns the ids of the dispatched tasks.
+  pub fn run_due_crank_tasks(&mut self, limit: Option<u32>) -> Vec<u64> {
+      let now = env::block_timestamp();
+      let due: Vec<u64> = self.crank_tasks.values()
+          .filter(|task| task.next_run_at <= now)
+          .map(|task| task.id)
+          .take(limit.unwrap_or(DEFAULT_DISPATCH_LIMIT) as usize)
+          .collect();
+      let mut dispatched = Vec::new();
+      let mut gas_left = env::prepaid_gas().saturating_sub(env::used_gas()).saturating_sub(GAS_FOR_DISPATCH);
+      for task_id in due {
+          let task = self.crank_tasks.get_mut(&task_id).unwrap();
+          let budget = Gas::from_tgas(task.gas_budget_tgas);
+          if budget > gas_left {
+              break;
+          }
+          gas_left = gas_left.saturating_sub(budget);
+          task.runs_dispatched += 1;
+          // Skip missed intervals rather than running the job once per interval missed.
+          let interval = task.interval_sec * NANOS_PER_SECOND;
+          task.next_run_at += (now - task.next_run_at) / interval * interval + interval;
+          let run = task.runs_dispatched;
+          Self::ext(env::current_account_id())
+              .with_static_gas(budget)
+              .run_crank_task(task_id, run);
+          dispatched.push(task_id);
+      }
+      dispatched
+  }
+
+  /// Handler running run `run` of a task dispatched by `run_due_crank_tasks`. Ignores a run
+  /// of a removed task or one that already completed.
+  #[private]
+  pub fn run_crank_task(&mut self, task_id: u64, run: u64) -> bool {
+      let Some(task) = self.crank_tasks.get(&task_id).cloned() else {
+          return false;
+      };
+      if run <= task.last_completed_run || run > task.runs_dispatched {
+          return false;
+      }
+      match task.job {
+          CrankJob::AccrueInterest => {
+              self.internal_accrue_savings();
+          }
+          CrankJob::BillSubscriptions => {
+              self.bill_due_subscriptions(Some(task.batch_limit));
+          }
+          CrankJob::ExecuteStandingOrders => {
+              self.execute_due_payments(Some(task.batch_limit));
+          }
+      }
+      let task = self.crank_tasks.get_mut(&task_id).unwrap();
+      task.last_completed_run = run;
+      task.last_completed_at = Some(env::block_timestamp());
+      BankEvent::CrankTaskRun { task_id, job: task.job, run }.emit();
+      true
+  }
+
+  /// View function.
+  pub fn get_crank_task(&self, task_id: u64) -> Option<CrankTask> {
+      self.crank_tasks.get(&task_id).cloned()
+  }
+
+  /// View function.
+  pub fn get_crank_tasks(&self, from_index: Option<u64>, limit: Option<u64>) -> Vec<CrankTask> {
+      self.crank_tasks.values()
+          .skip(from_index.unwrap_or(0) as usize)
+          .take(limit.unwrap_or(DEFAULT_PAGE_LIMIT) as usize)
+          .cloned()
+          .collect()
+  }
+}
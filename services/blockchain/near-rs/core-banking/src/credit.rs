@@ -0,0 +1,208 @@
+// services/blockchain/near-rs/core-banking/src/credit.rs
+use near_sdk::{near, env, AccountId, NearToken};
+
+use crate::overdraft::{MAX_KYC_TIER, MAX_OVERDRAFT_GRACE_SEC};
+use crate::term_deposits::NANOS_PER_DAY;
+use crate::{mul_div, BankEvent, BioCrypticBankCore, BioCrypticBankCoreExt};
+
+pub const MAX_CREDIT_SCORE: u16 = 1000;
+const MAX_STABILITY_POINTS: u128 = 200;
+const MAX_REPAYMENT_POINTS: i64 = 300;
+const NEUTRAL_REPAYMENT_POINTS: i64 = 150;
+const ON_TIME_REPAYMENT_POINTS: i64 = 30;
+const LATE_REPAYMENT_PENALTY: i64 = 100;
+const LIQUIDATION_PENALTY: i64 = 150;
+const MAX_AGE_POINTS: u128 = 200;
+const FULL_AGE_DAYS: u128 = 365;
+const MAX_KYC_POINTS: u128 = 200;
+const DID_POINTS: u16 = 100;
+/// Daily balance samples needed before stability counts in full.
+const FULL_STABILITY_SAMPLES: u128 = 30;
+/// Weight of a new sample in the balance moving average (1/8).
+const BALANCE_EMA_WEIGHT: u128 = 8;
+const MAX_CREDIT_TIERS: usize = 10;
+
+/// Banking history the score is derived from, updated as loans are repaid or liquidated
+/// and as balances are sampled.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct CreditRecord {
+  pub on_time_repayments: u32,
+  pub late_repayments: u32,
+  pub liquidations: u32,
+  /// Moving average of daily balance samples.
+  pub balance_average: NearToken,
+  pub balance_samples: u32,
+  pub last_sampled_at: u64,
+  /// Score as of the last update.
+  pub score: u16,
+}
+
+/// Borrowing and overdraft limits granted from `min_score` upwards. Owner controlled.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct CreditTier {
+  pub min_score: u16,
+  /// Largest total principal across the account's open loans.
+  pub max_loan_principal: NearToken,
+  /// Overdraft limit granted on opening; zero means not eligible.
+  pub overdraft_limit: NearToken,
+  /// Interest-free period granted with the overdraft.
+  pub overdraft_grace_period_sec: u64,
+}
+
+#[near(serializers = [json])]
+#[derive(Debug, PartialEq)]
+pub struct CreditScoreView {
+  /// 0 to `MAX_CREDIT_SCORE`.
+  pub score: u16,
+  pub balance_stability: u16,
+  pub repayment_record: u16,
+  pub account_age: u16,
+  pub kyc_tier: u16,
+  pub did_reputation: u16,
+  /// Tier the score currently qualifies for, if tiers are configured.
+  pub tier: Option<CreditTier>,
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Replaces the score tiers that set loan and overdraft limits. With no tiers, loans are
+  /// limited only by collateral and overdrafts use the default limit. Owner only.
+  pub fn set_credit_tiers(&mut self, tiers: Vec<CreditTier>) {
+      self.assert_owner();
+      let mut tiers = tiers;
+      assert!(tiers.len() <= MAX_CREDIT_TIERS, "Cannot exceed {} credit tiers.", MAX_CREDIT_TIERS);
+      tiers.sort_by_key(|tier| tier.min_score);
+      assert!(tiers.windows(2).all(|pair| pair[0].min_score < pair[1].min_score), "Tier scores must be unique.");
+      assert!(tiers.iter().all(|tier| tier.min_score <= MAX_CREDIT_SCORE), "Tier score cannot exceed {}.", MAX_CREDIT_SCORE);
+      assert!(
+          tiers.iter().all(|tier| tier.overdraft_grace_period_sec <= MAX_OVERDRAFT_GRACE_SEC),
+          "Grace period cannot exceed {} seconds.", MAX_OVERDRAFT_GRACE_SEC
+      );
+      self.credit_tiers = tiers;
+  }
+
+  /// Samples `account_id`'s (default: caller's) balance and recomputes its score.
+  /// Callable by anyone.
+  pub fn refresh_credit_score(&mut self, account_id: Option<AccountId>) -> u16 {
+      let initial_storage = env::storage_usage();
+      let account_id = account_id.unwrap_or_else(env::predecessor_account_id);
+      self.assert_registered(&account_id);
+      let score = self.internal_update_credit(&account_id, |_| {});
+      self.internal_charge_storage(&account_id, initial_storage);
+      score
+  }
+
+  /// `account_id`'s credit score with its components and the tier it qualifies for.
+  /// View function.
+  pub fn get_credit_score(&self, account_id: AccountId) -> CreditScoreView {
+      let record = self.credit_records.get(&account_id).cloned().unwrap_or_default();
+      self.internal_credit_score(&account_id, &record)
+  }
+
+  /// View function.
+  pub fn get_credit_record(&self, account_id: AccountId) -> Option<CreditRecord> {
+      self.credit_records.get(&account_id).cloned()
+  }
+
+  /// View function.
+  pub fn get_credit_tiers(&self) -> Vec<CreditTier> {
+      self.credit_tiers.clone()
+  }
+}
+
+impl BioCrypticBankCore {
+  fn internal_credit_score(&self, account_id: &AccountId, record: &CreditRecord) -> CreditScoreView {
+      let now = env::block_timestamp();
+      let balance = self.get_balance(account_id.clone()).as_yoctonear();
+      let average = record.balance_average.as_yoctonear();
+      let balance_stability = if balance.max(average) == 0 {
+          0
+      } else {
+          let steadiness = mul_div(MAX_STABILITY_POINTS, balance.min(average), balance.max(average));
+          mul_div(steadiness, (record.balance_samples as u128).min(FULL_STABILITY_SAMPLES), FULL_STABILITY_SAMPLES) as u16
+      };
+      let repayment_record = (NEUTRAL_REPAYMENT_POINTS
+          + ON_TIME_REPAYMENT_POINTS * record.on_time_repayments as i64
+          - LATE_REPAYMENT_PENALTY * record.late_repayments as i64
+          - LIQUIDATION_PENALTY * record.liquidations as i64)
+          .clamp(0, MAX_REPAYMENT_POINTS) as u16;
+      let first_activity = self.internal_ledger_entry_at(account_id, 0)
+          .map_or(now, |entry| entry.timestamp);
+      let age_days = ((now - first_activity) / NANOS_PER_DAY) as u128;
+      let account_age = mul_div(MAX_AGE_POINTS, age_days.min(FULL_AGE_DAYS), FULL_AGE_DAYS) as u16;
+      let kyc_tier = mul_div(MAX_KYC_POINTS, self.get_kyc_tier(account_id.clone()) as u128, MAX_KYC_TIER as u128) as u16;
+      let did_reputation = if self.kyc_cache.get(account_id).is_some_and(|cached| cached.did_active) { DID_POINTS } else { 0 };
+      let score = balance_stability + repayment_record + account_age + kyc_tier + did_reputation;
+      CreditScoreView {
+          score,
+          balance_stability,
+          repayment_record,
+          account_age,
+          kyc_tier,
+          did_reputation,
+          tier: self.internal_credit_tier(score).cloned(),
+      }
+  }
+
+  fn internal_credit_tier(&self, score: u16) -> Option<&CreditTier> {
+      self.credit_tiers.iter().rev().find(|tier| tier.min_score <= score)
+  }
+
+  /// Applies `update` to `account_id`'s record, samples its balance at most once a day and
+  /// stores the recomputed score. Returns the score.
+  pub(crate) fn internal_update_credit(&mut self, account_id: &AccountId, update: impl FnOnce(&mut CreditRecord)) -> u16 {
+      let now = env::block_timestamp();
+      let balance = self.get_balance(account_id.clone()).as_yoctonear();
+      let mut record = self.credit_records.get(account_id).cloned().unwrap_or_default();
+      update(&mut record);
+      if record.balance_samples == 0 {
+          record.balance_average = NearToken::from_yoctonear(balance);
+          record.balance_samples = 1;
+          record.last_sampled_at = now;
+      } else if now - record.last_sampled_at >= NANOS_PER_DAY {
+          let average = record.balance_average.as_yoctonear();
+          let weighted = average - average / BALANCE_EMA_WEIGHT + balance / BALANCE_EMA_WEIGHT;
+          record.balance_average = NearToken::from_yoctonear(weighted);
+          record.balance_samples = record.balance_samples.saturating_add(1);
+          record.last_sampled_at = now;
+      }
+      let score = self.internal_credit_score(account_id, &record).score;
+      let previous = record.score;
+      record.score = score;
+      self.credit_records.insert(account_id.clone(), record);
+      if score != previous {
+          BankEvent::CreditScoreUpdated { account_id: account_id.clone(), score }.emit();
+      }
+      score
+  }
+
+  /// Panics if `borrow_amount` would take `borrower_id`'s open principal past its tier limit.
+  pub(crate) fn assert_within_credit_limit(&mut self, borrower_id: &AccountId, borrow_amount: NearToken) {
+      if self.credit_tiers.is_empty() {
+          return;
+      }
+      let score = self.internal_update_credit(borrower_id, |_| {});
+      let limit = self.internal_credit_tier(score)
+          .map_or(NearToken::from_yoctonear(0), |tier| tier.max_loan_principal);
+      let outstanding = self.loans_by_account.get(borrower_id).map_or(0, |ids| {
+          ids.iter().filter_map(|id| self.loans.get(id)).map(|loan| loan.principal.as_yoctonear()).sum()
+      });
+      assert!(
+          borrow_amount.saturating_add(NearToken::from_yoctonear(outstanding)) <= limit,
+          "Borrow amount exceeds the limit for your credit score."
+      );
+  }
+
+  /// Overdraft limit and grace period `account_id` qualifies for: from its credit tier if
+  /// tiers are configured, otherwise the defaults.
+  pub(crate) fn internal_overdraft_terms_for(&mut self, account_id: &AccountId) -> (NearToken, u64) {
+      if self.credit_tiers.is_empty() {
+          return (self.overdraft_settings.default_limit, self.overdraft_settings.default_grace_period_sec);
+      }
+      let score = self.internal_update_credit(account_id, |_| {});
+      self.internal_credit_tier(score)
+          .map_or((NearToken::from_yoctonear(0), 0), |tier| (tier.overdraft_limit, tier.overdraft_grace_period_sec))
+  }
+}
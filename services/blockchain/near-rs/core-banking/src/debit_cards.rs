@@ -0,0 +1,169 @@
+// services/blockchain/near-rs/core-banking/src/debit_cards.rs
+use near_sdk::{near, env, AccountId, Allowance, NearToken, Promise, PromiseOrValue, PublicKey};
+
+use crate::ledger::{EntryDirection, EntryKind};
+use crate::term_deposits::NANOS_PER_DAY;
+use crate::{assert_valid_memo, BankEvent, BioCrypticBankCore, BioCrypticBankCoreExt};
+
+const MAX_CARDS_PER_ACCOUNT: usize = 5;
+const MAX_CARD_LABEL_LENGTH: usize = 32;
+/// Gas budget of a card key, paid by the cardholder on issue and not refunded.
+const CARD_KEY_ALLOWANCE: NearToken = NearToken::from_millinear(50);
+const CARD_METHODS: &str = "card_transfer,card_pay_intent";
+
+/// Spending caps of a debit card. Set by the cardholder.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct DebitCardLimits {
+  pub per_transaction: NearToken,
+  /// Total spend per UTC day.
+  pub daily: NearToken,
+}
+
+/// A function-call key on this contract that pays from `account_id`'s balance within
+/// `limits`. Revoking the card deletes the key, so it stops working at once.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct DebitCard {
+  pub account_id: AccountId,
+  pub label: Option<String>,
+  pub limits: DebitCardLimits,
+  /// Start (ns) of the day `spent_today` is counted for.
+  pub day_started_at: u64,
+  pub spent_today: NearToken,
+  pub issued_at: u64,
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Issues a debit card for the caller: `public_key` is added to this contract as a
+  /// function-call key limited to `card_transfer` and `card_pay_intent`, which pay from the
+  /// caller's balance within `limits`. Keep the secret key on the card device. The key's gas
+  /// allowance is charged to the caller's balance.
+  pub fn issue_debit_card(&mut self, public_key: PublicKey, label: Option<String>, limits: DebitCardLimits) -> Promise {
+      let initial_storage = env::storage_usage();
+      let account_id = env::predecessor_account_id();
+      self.assert_registered(&account_id);
+      self.assert_no_recovery_hold(&account_id);
+      assert!(self.debit_cards.get(&public_key).is_none(), "A debit card already uses this key.");
+      assert!(self.gifts.get(&public_key).is_none(), "Key is in use as a gift claim key.");
+      assert!(label.as_ref().is_none_or(|label| label.len() <= MAX_CARD_LABEL_LENGTH), "Label cannot exceed {} bytes.", MAX_CARD_LABEL_LENGTH);
+      assert_valid_card_limits(&limits);
+
+      self.internal_debit(&account_id, CARD_KEY_ALLOWANCE);
+      self.internal_remove_liabilities(CARD_KEY_ALLOWANCE);
+      self.internal_record_entry(&account_id, EntryKind::Fee, EntryDirection::Debit, CARD_KEY_ALLOWANCE, None, Some("card:issue".to_string()));
+
+      let keys = self.debit_cards_by_account.entry(account_id.clone()).or_default();
+      assert!(keys.len() < MAX_CARDS_PER_ACCOUNT, "Cannot exceed {} debit cards.", MAX_CARDS_PER_ACCOUNT);
+      keys.push(public_key.clone());
+      let now = env::block_timestamp();
+      self.debit_cards.insert(public_key.clone(), DebitCard {
+          account_id: account_id.clone(),
+          label,
+          limits: limits.clone(),
+          day_started_at: now - now % NANOS_PER_DAY,
+          spent_today: NearToken::from_yoctonear(0),
+          issued_at: now,
+      });
+      self.internal_charge_storage(&account_id, initial_storage);
+      BankEvent::DebitCardIssued { account_id, public_key: public_key.clone(), limits }.emit();
+      Promise::new(env::current_account_id()).add_access_key_allowance(
+          public_key,
+          Allowance::limited(CARD_KEY_ALLOWANCE).unwrap(),
+          env::current_account_id(),
+          CARD_METHODS.to_string(),
+      )
+  }
+
+  /// Changes the limits of the caller's card `public_key`. Takes effect immediately.
+  pub fn set_debit_card_limits(&mut self, public_key: PublicKey, limits: DebitCardLimits) {
+      assert_valid_card_limits(&limits);
+      let account_id = env::predecessor_account_id();
+      let card = self.debit_cards.get_mut(&public_key).unwrap_or_else(|| env::panic_str("Debit card not found."));
+      assert_eq!(card.account_id, account_id, "Only the cardholder can change this card.");
+      card.limits = limits.clone();
+      BankEvent::DebitCardLimitsUpdated { account_id, public_key, limits }.emit();
+  }
+
+  /// Cancels the caller's card `public_key` and deletes its key.
+  pub fn revoke_debit_card(&mut self, public_key: PublicKey) -> Promise {
+      let initial_storage = env::storage_usage();
+      let account_id = env::predecessor_account_id();
+      let card = self.debit_cards.get(&public_key).unwrap_or_else(|| env::panic_str("Debit card not found."));
+      assert_eq!(card.account_id, account_id, "Only the cardholder can revoke this card.");
+      self.internal_remove_debit_card(&public_key);
+      self.internal_charge_storage(&account_id, initial_storage);
+      BankEvent::DebitCardRevoked { account_id, public_key: public_key.clone() }.emit();
+      Promise::new(env::current_account_id()).delete_key(public_key)
+  }
+
+  /// Transfers `amount` to `receiver_id` from the cardholder's balance, as `transfer` would.
+  /// Must be signed with a card key.
+  pub fn card_transfer(&mut self, receiver_id: AccountId, amount: NearToken, memo: Option<String>) -> PromiseOrValue<()> {
+      assert_valid_memo(&memo);
+      let (account_id, public_key) = self.internal_spend_on_card(amount);
+      BankEvent::DebitCardPayment { account_id: account_id.clone(), public_key, amount }.emit();
+      self.internal_request_transfer(account_id, receiver_id, amount, memo, None, None)
+  }
+
+  /// Pays intent `intent_id` from the cardholder's balance, as `pay_intent` would. Must be
+  /// signed with a card key.
+  pub fn card_pay_intent(&mut self, intent_id: u64, amount: NearToken) {
+      let (account_id, public_key) = self.internal_spend_on_card(amount);
+      BankEvent::DebitCardPayment { account_id: account_id.clone(), public_key, amount }.emit();
+      self.internal_pay_intent(account_id, intent_id, amount);
+  }
+
+  /// View function.
+  pub fn get_debit_card(&self, public_key: PublicKey) -> Option<DebitCard> {
+      self.debit_cards.get(&public_key).cloned()
+  }
+
+  /// Lists `account_id`'s cards with their keys.
+  /// View function.
+  pub fn get_debit_cards(&self, account_id: AccountId) -> Vec<(PublicKey, DebitCard)> {
+      self.debit_cards_by_account.get(&account_id)
+          .map(|keys| keys.iter().filter_map(|key| Some((key.clone(), self.debit_cards.get(key)?.clone()))).collect())
+          .unwrap_or_default()
+  }
+}
+
+impl BioCrypticBankCore {
+  /// Counts `amount` against the limits of the card that signed this call. Returns the
+  /// cardholder and the card key.
+  fn internal_spend_on_card(&mut self, amount: NearToken) -> (AccountId, PublicKey) {
+      assert_eq!(
+          env::predecessor_account_id(), env::current_account_id(),
+          "Card payments must be signed with a card key."
+      );
+      let public_key = env::signer_account_pk();
+      let card = self.debit_cards.get_mut(&public_key).unwrap_or_else(|| env::panic_str("Debit card not found."));
+      assert!(amount <= card.limits.per_transaction, "Amount exceeds the card's per-transaction limit.");
+      let now = env::block_timestamp();
+      let today = now - now % NANOS_PER_DAY;
+      if card.day_started_at < today {
+          card.day_started_at = today;
+          card.spent_today = NearToken::from_yoctonear(0);
+      }
+      let spent = card.spent_today.saturating_add(amount);
+      assert!(spent <= card.limits.daily, "Amount exceeds the card's daily limit.");
+      card.spent_today = spent;
+      (card.account_id.clone(), public_key)
+  }
+
+  fn internal_remove_debit_card(&mut self, public_key: &PublicKey) {
+      let Some(card) = self.debit_cards.remove(public_key) else { return };
+      if let Some(keys) = self.debit_cards_by_account.get_mut(&card.account_id) {
+          keys.retain(|key| key != public_key);
+          if keys.is_empty() {
+              self.debit_cards_by_account.remove(&card.account_id);
+          }
+      }
+  }
+}
+
+fn assert_valid_card_limits(limits: &DebitCardLimits) {
+  assert!(!limits.per_transaction.is_zero(), "Per-transaction limit must be greater than 0.");
+  assert!(limits.per_transaction <= limits.daily, "Per-transaction limit cannot exceed the daily limit.");
+}
@@ -0,0 +1,185 @@
+// services/blockchain/near-rs/core-banking/src/dex.rs
+use near_sdk::{near, env, ext_contract, AccountId, Gas, NearToken, Promise, PromiseError};
+use near_sdk::json_types::U128;
+use biocrypticbank_common::interfaces::ext_ft;
+
+use crate::operation_locks::LockedOperation;
+use crate::pause::Feature;
+use crate::savings::BPS_DENOMINATOR;
+use crate::{mul_div, BankEvent, BioCrypticBankCore, BioCrypticBankCoreExt};
+
+/// Upper bound on the exchange spread (5%).
+const MAX_SPREAD_BPS: u16 = 500;
+const GAS_FOR_DEX_DEPOSIT: Gas = Gas::from_tgas(40);
+const GAS_FOR_DEX_SWAP: Gas = Gas::from_tgas(20);
+const GAS_FOR_DEX_WITHDRAW: Gas = Gas::from_tgas(30);
+const GAS_FOR_SWAP_CALLBACK: Gas = Gas::from_tgas(50);
+
+/// One hop of a Ref Finance style swap.
+#[near(serializers = [json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct SwapAction {
+  pub pool_id: u64,
+  pub token_in: AccountId,
+  pub amount_in: Option<U128>,
+  pub token_out: AccountId,
+  pub min_amount_out: U128,
+}
+
+/// Interface of a Ref Finance style exchange.
+#[ext_contract(ext_dex)]
+#[allow(dead_code)]
+trait Exchange {
+  fn swap(&mut self, actions: Vec<SwapAction>, referral_id: Option<AccountId>) -> U128;
+  fn withdraw(&mut self, token_id: AccountId, amount: U128, unregister: Option<bool>);
+}
+
+/// Exchange used by `swap` and the spread the bank keeps from each output. Owner controlled.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct DexConfig {
+  pub dex_id: Option<AccountId>,
+  pub spread_bps: u16,
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Sets the exchange and spread. The bank must be registered on the exchange and on both
+  /// tokens of every routed pool. Owner only.
+  pub fn set_dex_config(&mut self, config: DexConfig) {
+      self.assert_owner();
+      assert!(config.spread_bps <= MAX_SPREAD_BPS, "Spread cannot exceed {} bps.", MAX_SPREAD_BPS);
+      self.dex_config = config;
+  }
+
+  /// Routes swaps between `token_a` and `token_b` (both directions) through exchange pool
+  /// `pool_id`, or stops offering the pair with `None`. Owner only.
+  pub fn set_dex_pool(&mut self, token_a: AccountId, token_b: AccountId, pool_id: Option<u64>) {
+      self.assert_owner();
+      assert!(token_a != token_b, "Tokens must differ.");
+      let key = pair_key(token_a, token_b);
+      match pool_id {
+          Some(pool_id) => {
+              assert!(self.token_whitelist.contains(&key.0) && self.token_whitelist.contains(&key.1), "Both tokens must be whitelisted.");
+              self.dex_pools.insert(key, pool_id);
+          }
+          None => {
+              assert!(self.dex_pools.remove(&key).is_some(), "Pair is not offered.");
+          }
+      }
+  }
+
+  /// Exchanges `amount` of the caller's `token_in` balance for `token_out` through the
+  /// configured exchange, using the bank's pooled position there. The output minus the
+  /// spread is credited once the swap settles and must be at least `min_out`; otherwise the
+  /// input is refunded. Swap NEAR by wrapping it into wNEAR first.
+  pub fn swap(&mut self, token_in: AccountId, token_out: AccountId, amount: U128, min_out: U128) -> Promise {
+      self.assert_not_paused(Feature::Transfers);
+      let account_id = env::predecessor_account_id();
+      let dex_id = self.dex_config.dex_id.clone().unwrap_or_else(|| env::panic_str("No exchange is configured."));
+      assert!(amount.0 > 0, "Amount must be greater than 0.");
+      let pool_id = *self.dex_pools.get(&pair_key(token_in.clone(), token_out.clone()))
+          .unwrap_or_else(|| env::panic_str("Pair is not offered."));
+      self.assert_registered(&account_id);
+      self.assert_not_dormant(&account_id);
+      self.assert_no_recovery_hold(&account_id);
+      self.internal_ft_debit(&account_id, &token_in, amount.0);
+      let lock_id = self.internal_lock_account(&account_id, LockedOperation::Swap);
+
+      // Ask the exchange for enough gross output to leave `min_out` after the spread.
+      let keep_bps = BPS_DENOMINATOR - self.dex_config.spread_bps as u128;
+      let mut min_gross = mul_div(min_out.0, BPS_DENOMINATOR, keep_bps);
+      if self.dex_config.spread_bps > 0 {
+          min_gross += 1;
+      }
+      let action = SwapAction {
+          pool_id,
+          token_in: token_in.clone(),
+          amount_in: Some(amount),
+          token_out: token_out.clone(),
+          min_amount_out: U128(min_gross),
+      };
+      BankEvent::SwapRequested { account_id: account_id.clone(), token_in: token_in.clone(), token_out: token_out.clone(), amount, min_out }.emit();
+      ext_ft::ext(token_in.clone())
+          .with_attached_deposit(NearToken::from_yoctonear(1))
+          .with_static_gas(GAS_FOR_DEX_DEPOSIT)
+          .ft_transfer_call(dex_id.clone(), amount, None, String::new())
+          .then(
+              ext_dex::ext(dex_id)
+                  .with_static_gas(GAS_FOR_DEX_SWAP)
+                  .swap(vec![action], None)
+          )
+          .then(
+              Self::ext(env::current_account_id())
+                  .with_static_gas(GAS_FOR_SWAP_CALLBACK)
+                  .on_swap_settled(account_id, token_in, token_out, amount, Some(lock_id))
+          )
+  }
+
+  /// Callback crediting the swap output minus the spread and pulling it back from the
+  /// exchange, or refunding the input if the swap failed. Releases the account's lock.
+  #[private]
+  pub fn on_swap_settled(
+      &mut self,
+      account_id: AccountId,
+      token_in: AccountId,
+      token_out: AccountId,
+      amount: U128,
+      lock_id: Option<u64>,
+      #[callback_result] result: Result<U128, PromiseError>,
+  ) -> bool {
+      self.internal_unlock_account(&account_id, lock_id);
+      let Ok(amount_out) = result else {
+          self.internal_ft_credit(&account_id, &token_in, amount.0);
+          BankEvent::SwapFailed { account_id, token_in, amount }.emit();
+          return false;
+      };
+      // The exchange enforced the gross minimum, so `net` is at least `min_out`.
+      let spread = mul_div(amount_out.0, self.dex_config.spread_bps as u128, BPS_DENOMINATOR);
+      let net = amount_out.0 - spread;
+      self.internal_ft_credit(&account_id, &token_out, net);
+      let revenue = self.dex_revenue.get(&token_out).copied().unwrap_or(0);
+      self.dex_revenue.insert(token_out.clone(), revenue + spread);
+      BankEvent::SwapSettled { account_id, token_in, token_out: token_out.clone(), amount_in: amount, amount_out: U128(net), spread: U128(spread) }.emit();
+      if let Some(dex_id) = self.dex_config.dex_id.clone() {
+          ext_dex::ext(dex_id)
+              .with_attached_deposit(NearToken::from_yoctonear(1))
+              .with_static_gas(GAS_FOR_DEX_WITHDRAW)
+              .withdraw(token_out, amount_out, None);
+      }
+      true
+  }
+
+  /// Pulls `amount` of `token_id` held in the bank's account on the exchange back to the
+  /// bank, e.g. inputs stranded by failed swaps. Owner only.
+  pub fn withdraw_from_dex(&mut self, token_id: AccountId, amount: U128) -> Promise {
+      self.assert_owner();
+      let dex_id = self.dex_config.dex_id.clone().unwrap_or_else(|| env::panic_str("No exchange is configured."));
+      ext_dex::ext(dex_id)
+          .with_attached_deposit(NearToken::from_yoctonear(1))
+          .with_static_gas(GAS_FOR_DEX_WITHDRAW)
+          .withdraw(token_id, amount, None)
+  }
+
+  /// View function.
+  pub fn get_dex_config(&self) -> DexConfig {
+      self.dex_config.clone()
+  }
+
+  /// Exchange pool routing swaps between `token_a` and `token_b`.
+  /// View function.
+  pub fn get_dex_pool(&self, token_a: AccountId, token_b: AccountId) -> Option<u64> {
+      self.dex_pools.get(&pair_key(token_a, token_b)).copied()
+  }
+
+  /// Spread collected in `token_id`.
+  /// View function.
+  pub fn get_dex_revenue(&self, token_id: AccountId) -> U128 {
+      U128(self.dex_revenue.get(&token_id).copied().unwrap_or(0))
+  }
+}
+
+/// Order-independent key of a token pair.
+fn pair_key(token_a: AccountId, token_b: AccountId) -> (AccountId, AccountId) {
+  if token_a <= token_b { (token_a, token_b) } else { (token_b, token_a) }
+}
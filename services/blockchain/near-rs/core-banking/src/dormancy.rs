@@ -0,0 +1,162 @@
+// services/blockchain/near-rs/core-banking/src/dormancy.rs
+use near_sdk::{near, env, AccountId, NearToken};
+
+use crate::ledger::{EntryDirection, EntryKind};
+use crate::roles::Role;
+use crate::term_deposits::NANOS_PER_DAY;
+use crate::{BankEvent, BioCrypticBankCore, BioCrypticBankCoreExt};
+
+const MAX_DORMANCY_BATCH: usize = 50;
+
+#[near(serializers = [borsh, json])]
+#[serde(rename_all = "snake_case")]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum DormancyStatus {
+  Active,
+  Inactive,
+  /// Withdrawals and transfers are blocked until the holder shows activity again.
+  Dormant,
+}
+
+/// Days without own activity after which an account changes state. Owner controlled.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct DormancyConfig {
+  pub inactive_after_days: u32,
+  pub dormant_after_days: u32,
+  /// Dormant accounts inactive this long may be escheated into custody.
+  pub escheat_after_days: u32,
+}
+
+impl Default for DormancyConfig {
+  fn default() -> Self {
+      Self { inactive_after_days: 180, dormant_after_days: 365, escheat_after_days: 5 * 365 }
+  }
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Re-evaluates the dormancy status of `account_ids`, emitting an event for each change
+  /// since the last evaluation so the app can notify holders. Permissionless crank. Returns
+  /// the number of accounts whose status changed.
+  pub fn update_dormancy(&mut self, account_ids: Vec<AccountId>) -> u32 {
+      assert!(account_ids.len() <= MAX_DORMANCY_BATCH, "Cannot update more than {} accounts at once.", MAX_DORMANCY_BATCH);
+      let mut changed = 0;
+      for account_id in account_ids {
+          if !self.storage_accounts.contains_key(&account_id) {
+              continue;
+          }
+          let status = self.get_dormancy_status(account_id.clone());
+          let previous = self.dormancy_statuses.get(&account_id).copied().unwrap_or(DormancyStatus::Active);
+          if status == previous {
+              continue;
+          }
+          if status == DormancyStatus::Active {
+              self.dormancy_statuses.remove(&account_id);
+          } else {
+              self.dormancy_statuses.insert(account_id.clone(), status);
+          }
+          BankEvent::DormancyStatusChanged { account_id, status }.emit();
+          changed += 1;
+      }
+      changed
+  }
+
+  /// Moves the whole balance of an account that has been dormant past the escheatment
+  /// threshold into segregated custody, dissolving its envelopes. The funds stay owed to
+  /// the holder, who can reclaim them at any time. Compliance admin only.
+  pub fn escheat_account(&mut self, account_id: AccountId) -> NearToken {
+      self.assert_role(Role::ComplianceAdmin);
+      let initial_storage = env::storage_usage();
+      let inactive_for = env::block_timestamp().saturating_sub(self.get_last_activity(account_id.clone()));
+      assert!(
+          inactive_for >= self.dormancy_config.escheat_after_days as u64 * NANOS_PER_DAY,
+          "Account has not been abandoned long enough to escheat."
+      );
+      let amount = self.get_balance(account_id.clone());
+      assert!(!amount.is_zero(), "Account has no balance to escheat.");
+
+      self.envelopes.remove(&account_id);
+      self.internal_debit(&account_id, amount);
+      self.internal_record_entry(&account_id, EntryKind::Escheatment, EntryDirection::Debit, amount, None, None);
+      let held = self.escheated_balances.get(&account_id).copied().unwrap_or(NearToken::from_yoctonear(0));
+      self.escheated_balances.insert(account_id.clone(), held.saturating_add(amount));
+      self.total_escheated = self.total_escheated.saturating_add(amount);
+      self.internal_charge_storage(&account_id, initial_storage);
+      BankEvent::AccountEscheated { account_id, amount, escheated_by: env::predecessor_account_id() }.emit();
+      amount
+  }
+
+  /// Returns the caller's escheated funds from custody to its balance. Returns the amount.
+  pub fn reclaim_escheated_funds(&mut self) -> NearToken {
+      let initial_storage = env::storage_usage();
+      let account_id = env::predecessor_account_id();
+      let amount = self.escheated_balances.remove(&account_id)
+          .unwrap_or_else(|| env::panic_str("No escheated funds."));
+      self.total_escheated = self.total_escheated.saturating_sub(amount);
+      self.internal_credit(&account_id, amount);
+      self.internal_record_entry(&account_id, EntryKind::Escheatment, EntryDirection::Credit, amount, None, None);
+      self.internal_charge_storage(&account_id, initial_storage);
+      BankEvent::EscheatedFundsReclaimed { account_id, amount }.emit();
+      amount
+  }
+
+  /// Updates the dormancy thresholds. Owner only.
+  pub fn set_dormancy_config(&mut self, config: DormancyConfig) {
+      self.assert_owner();
+      assert!(
+          0 < config.inactive_after_days
+              && config.inactive_after_days < config.dormant_after_days
+              && config.dormant_after_days <= config.escheat_after_days,
+          "Thresholds must satisfy 0 < inactive < dormant <= escheat."
+      );
+      self.dormancy_config = config;
+  }
+
+  /// Dormancy status of `account_id` derived from its last own activity.
+  /// View function.
+  pub fn get_dormancy_status(&self, account_id: AccountId) -> DormancyStatus {
+      let inactive_for = env::block_timestamp().saturating_sub(self.get_last_activity(account_id));
+      let config = &self.dormancy_config;
+      if inactive_for >= config.dormant_after_days as u64 * NANOS_PER_DAY {
+          DormancyStatus::Dormant
+      } else if inactive_for >= config.inactive_after_days as u64 * NANOS_PER_DAY {
+          DormancyStatus::Inactive
+      } else {
+          DormancyStatus::Active
+      }
+  }
+
+  /// View function.
+  pub fn get_dormancy_config(&self) -> DormancyConfig {
+      self.dormancy_config.clone()
+  }
+
+  /// Funds of `account_id` held in escheatment custody.
+  /// View function.
+  pub fn get_escheated_balance(&self, account_id: AccountId) -> NearToken {
+      self.escheated_balances.get(&account_id).copied().unwrap_or(NearToken::from_yoctonear(0))
+  }
+
+  /// View function.
+  pub fn get_total_escheated(&self) -> NearToken {
+      self.total_escheated
+  }
+}
+
+impl BioCrypticBankCore {
+  /// Panics if `account_id` is dormant. A heartbeat or deposit reactivates it.
+  pub(crate) fn assert_not_dormant(&self, account_id: &AccountId) {
+      assert!(
+          self.get_dormancy_status(account_id.clone()) != DormancyStatus::Dormant,
+          "Account is dormant. Call heartbeat to reactivate it."
+      );
+  }
+
+  /// Clears a recorded non-active status after the holder showed activity.
+  pub(crate) fn internal_reactivate(&mut self, account_id: &AccountId) {
+      if self.dormancy_statuses.remove(account_id).is_some() {
+          BankEvent::DormancyStatusChanged { account_id: account_id.clone(), status: DormancyStatus::Active }.emit();
+      }
+  }
+}
@@ -0,0 +1,139 @@
+// services/blockchain/near-rs/core-banking/src/envelopes.rs
+use near_sdk::{near, env, AccountId, NearToken};
+
+use crate::{BankEvent, BioCrypticBankCore, BioCrypticBankCoreExt};
+
+const MAX_ENVELOPES_PER_ACCOUNT: usize = 20;
+const MAX_ENVELOPE_NAME_LENGTH: usize = 32;
+
+/// A named partition of an account's balance. Funds in an envelope are only spent when a
+/// transfer or withdrawal names it as the source; locked envelopes cannot be spent from.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct Envelope {
+  pub name: String,
+  pub balance: NearToken,
+  pub locked: bool,
+}
+
+#[near(serializers = [json])]
+#[derive(Debug, PartialEq)]
+pub struct EnvelopeBreakdown {
+  pub total: NearToken,
+  /// Balance not assigned to any envelope.
+  pub unallocated: NearToken,
+  pub envelopes: Vec<Envelope>,
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Creates an empty envelope called `name`.
+  pub fn create_envelope(&mut self, name: String, locked: Option<bool>) {
+      let initial_storage = env::storage_usage();
+      let account_id = env::predecessor_account_id();
+      assert!(!name.is_empty() && name.len() <= MAX_ENVELOPE_NAME_LENGTH, "Envelope name must be 1 to {} bytes.", MAX_ENVELOPE_NAME_LENGTH);
+      let envelopes = self.envelopes.entry(account_id.clone()).or_default();
+      assert!(envelopes.len() < MAX_ENVELOPES_PER_ACCOUNT, "Cannot exceed {} envelopes.", MAX_ENVELOPES_PER_ACCOUNT);
+      assert!(envelopes.iter().all(|envelope| envelope.name != name), "Envelope already exists.");
+      envelopes.push(Envelope { name: name.clone(), balance: NearToken::from_yoctonear(0), locked: locked.unwrap_or(false) });
+      self.internal_charge_storage(&account_id, initial_storage);
+      BankEvent::EnvelopeCreated { account_id, name }.emit();
+  }
+
+  /// Assigns `amount` of the caller's unallocated balance to envelope `name`.
+  pub fn fund_envelope(&mut self, name: String, amount: NearToken) {
+      let account_id = env::predecessor_account_id();
+      assert!(amount.as_yoctonear() > 0, "Amount must be greater than 0.");
+      assert!(self.internal_unallocated_balance(&account_id) >= amount, "Insufficient unallocated balance.");
+      let envelope = self.internal_get_envelope_mut(&account_id, &name);
+      envelope.balance = envelope.balance.saturating_add(amount);
+      BankEvent::EnvelopeFunded { account_id, name, amount }.emit();
+  }
+
+  /// Returns `amount` from an unlocked envelope to the caller's unallocated balance.
+  pub fn release_envelope(&mut self, name: String, amount: NearToken) {
+      let account_id = env::predecessor_account_id();
+      let envelope = self.internal_get_spendable_envelope_mut(&account_id, &name);
+      envelope.balance = envelope.balance.checked_sub(amount)
+          .unwrap_or_else(|| env::panic_str("Insufficient envelope balance."));
+      BankEvent::EnvelopeReleased { account_id, name, amount }.emit();
+  }
+
+  /// Moves `amount` between two of the caller's envelopes. The source must be unlocked.
+  pub fn move_between_envelopes(&mut self, from: String, to: String, amount: NearToken) {
+      let account_id = env::predecessor_account_id();
+      assert!(from != to, "Source and destination must differ.");
+      self.internal_get_envelope_mut(&account_id, &to);
+      let source = self.internal_get_spendable_envelope_mut(&account_id, &from);
+      source.balance = source.balance.checked_sub(amount)
+          .unwrap_or_else(|| env::panic_str("Insufficient envelope balance."));
+      let destination = self.internal_get_envelope_mut(&account_id, &to);
+      destination.balance = destination.balance.saturating_add(amount);
+  }
+
+  /// Locks or unlocks envelope `name`.
+  pub fn set_envelope_lock(&mut self, name: String, locked: bool) {
+      let account_id = env::predecessor_account_id();
+      self.internal_get_envelope_mut(&account_id, &name).locked = locked;
+      BankEvent::EnvelopeLockChanged { account_id, name, locked }.emit();
+  }
+
+  /// Deletes an unlocked envelope; its balance returns to the unallocated balance.
+  pub fn delete_envelope(&mut self, name: String) {
+      let account_id = env::predecessor_account_id();
+      self.internal_get_spendable_envelope_mut(&account_id, &name);
+      let envelopes = self.envelopes.get_mut(&account_id).unwrap();
+      envelopes.retain(|envelope| envelope.name != name);
+      if envelopes.is_empty() {
+          self.envelopes.remove(&account_id);
+      }
+      BankEvent::EnvelopeDeleted { account_id, name }.emit();
+  }
+
+  /// Retrieves the caller's balance broken down by envelope.
+  /// View function.
+  pub fn get_envelopes(&self, account_id: AccountId) -> EnvelopeBreakdown {
+      EnvelopeBreakdown {
+          total: self.get_balance(account_id.clone()),
+          unallocated: self.internal_unallocated_balance(&account_id),
+          envelopes: self.envelopes.get(&account_id).cloned().unwrap_or_default(),
+      }
+  }
+}
+
+impl BioCrypticBankCore {
+  /// Balance not reserved by any envelope; the only part general debits may use.
+  pub(crate) fn internal_unallocated_balance(&self, account_id: &AccountId) -> NearToken {
+      let allocated = self.envelopes.get(account_id).map_or(0, |envelopes| {
+          envelopes.iter().map(|envelope| envelope.balance.as_yoctonear()).sum()
+      });
+      self.get_balance(account_id.clone()).saturating_sub(NearToken::from_yoctonear(allocated))
+  }
+
+  /// Debits `amount` from envelope `envelope` if given, otherwise from the unallocated balance.
+  /// Does not check the operation lock; callers hold it or check it is free. Returns the new
+  /// total balance.
+  pub(crate) fn internal_debit_from(&mut self, account_id: &AccountId, amount: NearToken, envelope: Option<&str>) -> NearToken {
+      let Some(name) = envelope else {
+          return self.internal_debit_unallocated(account_id, amount);
+      };
+      let envelope = self.internal_get_spendable_envelope_mut(account_id, name);
+      envelope.balance = envelope.balance.checked_sub(amount)
+          .unwrap_or_else(|| env::panic_str("Insufficient envelope balance."));
+      let balance = self.get_balance(account_id.clone()).saturating_sub(amount);
+      self.internal_set_balance(account_id, balance);
+      balance
+  }
+
+  fn internal_get_envelope_mut(&mut self, account_id: &AccountId, name: &str) -> &mut Envelope {
+      self.envelopes.get_mut(account_id)
+          .and_then(|envelopes| envelopes.iter_mut().find(|envelope| envelope.name == name))
+          .unwrap_or_else(|| env::panic_str("Envelope not found."))
+  }
+
+  fn internal_get_spendable_envelope_mut(&mut self, account_id: &AccountId, name: &str) -> &mut Envelope {
+      let envelope = self.internal_get_envelope_mut(account_id, name);
+      assert!(!envelope.locked, "Envelope is locked.");
+      envelope
+  }
+}
@@ -0,0 +1,201 @@
+// services/blockchain/near-rs/core-banking/src/escrow.rs
+use near_sdk::{near, env, AccountId, NearToken};
+
+use crate::ledger::{EntryDirection, EntryKind};
+use crate::pause::Feature;
+use crate::{assert_valid_memo, BankEvent, BioCrypticBankCore, BioCrypticBankCoreExt};
+
+const MAX_OPEN_ESCROWS_PER_ACCOUNT: usize = 50;
+
+#[near(serializers = [borsh, json])]
+#[serde(rename_all = "snake_case")]
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum EscrowStatus {
+  /// Funds locked, awaiting delivery.
+  Funded,
+  /// Payee has confirmed delivery, awaiting release.
+  Delivered,
+  /// Awaiting an arbiter ruling.
+  Disputed,
+  Released,
+  Refunded,
+}
+
+/// Funds locked by `payer_id` for `payee_id` until release, refund or an arbiter ruling.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct Escrow {
+  pub id: u64,
+  pub payer_id: AccountId,
+  pub payee_id: AccountId,
+  pub arbiter_id: Option<AccountId>,
+  pub amount: NearToken,
+  pub memo: Option<String>,
+  pub created_at: u64,
+  /// Timestamp (ns) after which an undelivered escrow refunds the payer and a delivered,
+  /// undisputed one releases to the payee.
+  pub deadline: u64,
+  pub status: EscrowStatus,
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Locks `amount` from the caller's balance for `payee_id` until `deadline` (ns).
+  /// `arbiter_id`: Optional account that may rule on disputes. Returns the escrow id.
+  pub fn create_escrow(
+      &mut self,
+      payee_id: AccountId,
+      amount: NearToken,
+      deadline: u64,
+      arbiter_id: Option<AccountId>,
+      memo: Option<String>,
+  ) -> u64 {
+      self.assert_not_paused(Feature::Transfers);
+      let initial_storage = env::storage_usage();
+      let payer_id = env::predecessor_account_id();
+      assert!(amount.as_yoctonear() > 0, "Amount must be greater than 0.");
+      assert!(payer_id != payee_id, "Cannot create an escrow to self.");
+      self.assert_no_recovery_hold(&payer_id);
+      assert!(deadline > env::block_timestamp(), "Deadline must be in the future.");
+      if let Some(arbiter_id) = &arbiter_id {
+          assert!(*arbiter_id != payer_id && *arbiter_id != payee_id, "Arbiter must be a third party.");
+      }
+      assert_valid_memo(&memo);
+      self.assert_registered(&payee_id);
+      self.assert_trusted_destination(&payer_id, &payee_id);
+
+      let id = self.next_escrow_id;
+      self.next_escrow_id += 1;
+      self.internal_record_outflow(&payer_id, amount);
+      self.internal_debit(&payer_id, amount);
+      self.internal_record_entry(
+          &payer_id, EntryKind::Escrow, EntryDirection::Debit, amount, memo.clone(), Some(format!("escrow:{}", id)),
+      );
+
+      let mut parties = vec![payer_id.clone(), payee_id.clone()];
+      parties.extend(arbiter_id.clone());
+      for account_id in parties {
+          let ids = self.open_escrows_by_account.entry(account_id).or_default();
+          assert!(ids.len() < MAX_OPEN_ESCROWS_PER_ACCOUNT, "Too many open escrows.");
+          ids.push(id);
+      }
+      self.escrows.insert(id, Escrow {
+          id,
+          payer_id: payer_id.clone(),
+          payee_id: payee_id.clone(),
+          arbiter_id: arbiter_id.clone(),
+          amount,
+          memo,
+          created_at: env::block_timestamp(),
+          deadline,
+          status: EscrowStatus::Funded,
+      });
+      self.internal_charge_storage(&payer_id, initial_storage);
+
+      BankEvent::EscrowCreated { id, payer_id, payee_id, arbiter_id, amount, deadline }.emit();
+      id
+  }
+
+  /// Payee confirms that the goods or services were delivered.
+  pub fn confirm_escrow_delivery(&mut self, escrow_id: u64) {
+      let escrow = self.internal_get_escrow_mut(escrow_id);
+      assert_eq!(escrow.payee_id, env::predecessor_account_id(), "Only the payee can confirm delivery.");
+      assert_eq!(escrow.status, EscrowStatus::Funded, "Escrow is not awaiting delivery.");
+      assert!(env::block_timestamp() <= escrow.deadline, "Escrow deadline has passed.");
+      escrow.status = EscrowStatus::Delivered;
+      BankEvent::EscrowStatusChanged { id: escrow_id, status: EscrowStatus::Delivered }.emit();
+  }
+
+  /// Payer releases the locked funds to the payee.
+  pub fn release_escrow(&mut self, escrow_id: u64) {
+      let escrow = self.internal_get_escrow_mut(escrow_id);
+      assert_eq!(escrow.payer_id, env::predecessor_account_id(), "Only the payer can release the escrow.");
+      assert!(
+          matches!(escrow.status, EscrowStatus::Funded | EscrowStatus::Delivered),
+          "Escrow cannot be released in its current state."
+      );
+      self.internal_settle_escrow(escrow_id, true);
+  }
+
+  /// Payer or payee escalates the escrow to its arbiter.
+  pub fn dispute_escrow(&mut self, escrow_id: u64) {
+      let account_id = env::predecessor_account_id();
+      let escrow = self.internal_get_escrow_mut(escrow_id);
+      assert!(account_id == escrow.payer_id || account_id == escrow.payee_id, "Only a party to the escrow can dispute it.");
+      assert!(escrow.arbiter_id.is_some(), "Escrow has no arbiter.");
+      assert!(
+          matches!(escrow.status, EscrowStatus::Funded | EscrowStatus::Delivered),
+          "Escrow cannot be disputed in its current state."
+      );
+      escrow.status = EscrowStatus::Disputed;
+      BankEvent::EscrowStatusChanged { id: escrow_id, status: EscrowStatus::Disputed }.emit();
+  }
+
+  /// Arbiter rules on a disputed escrow, releasing to the payee or refunding the payer.
+  pub fn resolve_escrow(&mut self, escrow_id: u64, release_to_payee: bool) {
+      let escrow = self.internal_get_escrow_mut(escrow_id);
+      assert_eq!(escrow.arbiter_id.as_ref(), Some(&env::predecessor_account_id()), "Only the arbiter can resolve the escrow.");
+      assert_eq!(escrow.status, EscrowStatus::Disputed, "Escrow is not disputed.");
+      self.internal_settle_escrow(escrow_id, release_to_payee);
+  }
+
+  /// Settles an escrow whose deadline has passed: undelivered escrows refund the payer,
+  /// delivered and undisputed ones release to the payee. Callable by anyone.
+  pub fn settle_expired_escrow(&mut self, escrow_id: u64) {
+      let escrow = self.internal_get_escrow_mut(escrow_id);
+      assert!(env::block_timestamp() > escrow.deadline, "Escrow deadline has not passed.");
+      match escrow.status {
+          EscrowStatus::Funded => self.internal_settle_escrow(escrow_id, false),
+          EscrowStatus::Delivered => self.internal_settle_escrow(escrow_id, true),
+          EscrowStatus::Disputed => env::panic_str("Disputed escrows must be resolved by the arbiter."),
+          _ => env::panic_str("Escrow is already settled."),
+      }
+  }
+
+  /// Retrieves an escrow by id.
+  /// View function.
+  pub fn get_escrow(&self, escrow_id: u64) -> Option<Escrow> {
+      self.escrows.get(&escrow_id).cloned()
+  }
+
+  /// Lists the open escrows in which `account_id` is payer, payee or arbiter.
+  /// View function.
+  pub fn get_open_escrows(&self, account_id: AccountId) -> Vec<Escrow> {
+      self.open_escrows_by_account.get(&account_id)
+          .map(|ids| ids.iter().filter_map(|id| self.escrows.get(id).cloned()).collect())
+          .unwrap_or_default()
+  }
+}
+
+impl BioCrypticBankCore {
+  fn internal_get_escrow_mut(&mut self, escrow_id: u64) -> &mut Escrow {
+      self.escrows.get_mut(&escrow_id).unwrap_or_else(|| env::panic_str("Escrow not found."))
+  }
+
+  /// Pays out an open escrow to the payee or back to the payer and closes it.
+  fn internal_settle_escrow(&mut self, escrow_id: u64, release_to_payee: bool) {
+      let escrow = self.escrows.get_mut(&escrow_id).unwrap();
+      let status = if release_to_payee { EscrowStatus::Released } else { EscrowStatus::Refunded };
+      escrow.status = status;
+      let escrow = escrow.clone();
+
+      let receiver_id = if release_to_payee { &escrow.payee_id } else { &escrow.payer_id };
+      self.internal_credit(receiver_id, escrow.amount);
+      self.internal_record_entry(
+          receiver_id, EntryKind::Escrow, EntryDirection::Credit, escrow.amount,
+          escrow.memo.clone(), Some(format!("escrow:{}", escrow_id)),
+      );
+
+      let mut parties = vec![escrow.payer_id.clone(), escrow.payee_id.clone()];
+      parties.extend(escrow.arbiter_id.clone());
+      for account_id in parties {
+          if let Some(ids) = self.open_escrows_by_account.get_mut(&account_id) {
+              ids.retain(|existing| *existing != escrow_id);
+              if ids.is_empty() {
+                  self.open_escrows_by_account.remove(&account_id);
+              }
+          }
+      }
+      BankEvent::EscrowStatusChanged { id: escrow_id, status }.emit();
+  }
+}
@@ -0,0 +1,648 @@
+// services/blockchain/near-rs/core-banking/src/events.rs
+use near_sdk::{near, AccountId, NearToken, PublicKey};
+use near_sdk::json_types::U128;
+use biocrypticbank_common::assets::AssetId;
+
+use crate::crank_tasks::CrankJob;
+use crate::debit_cards::DebitCardLimits;
+use crate::dormancy::DormancyStatus;
+use crate::escrow::EscrowStatus;
+use crate::fees::{FeeSchedule, FeeSweepConfig};
+use crate::inheritance::Beneficiary;
+use crate::insurance::CoveredEvent;
+use crate::mandates::MandateStatus;
+use crate::monitoring::MonitoringRule;
+use crate::multisig::MultisigAction;
+use crate::operation_locks::LockedOperation;
+use crate::overdraft::OverdraftSettings;
+use crate::pause::Feature;
+use crate::prize_savings::PrizeSavingsConfig;
+use crate::rate_model::RateModel;
+use crate::roles::Role;
+use crate::round_ups::RoundUpTarget;
+
+/// NEP-297 events emitted by the core banking contract.
+#[near(event_json(standard = "bcb-core"))]
+pub enum BankEvent {
+  #[event_version("1.1.0")]
+  Transfer { sender_id: AccountId, receiver_id: AccountId, amount: NearToken, memo: Option<String>, reference: Option<String> },
+
+  #[event_version("1.1.0")]
+  Deposit { account_id: AccountId, funder_id: Option<AccountId>, amount: NearToken, memo: Option<String>, reference: Option<String> },
+
+  #[event_version("1.0.0")]
+  Withdraw { account_id: AccountId, amount: NearToken, memo: Option<String>, reference: Option<String> },
+
+  #[event_version("1.0.0")]
+  FtDeposit { account_id: AccountId, token_id: AccountId, amount: U128 },
+
+  #[event_version("1.0.0")]
+  FtWithdraw { account_id: AccountId, token_id: AccountId, amount: U128 },
+
+  #[event_version("1.0.0")]
+  FtWithdrawFailed { account_id: AccountId, token_id: AccountId, amount: U128 },
+
+  #[event_version("1.0.0")]
+  SavingsDeposit { account_id: AccountId, amount: NearToken },
+
+  #[event_version("1.0.0")]
+  SavingsWithdraw { account_id: AccountId, amount: NearToken },
+
+  #[event_version("1.0.0")]
+  SavingsAprUpdated { apr_bps: u16 },
+
+  #[event_version("1.0.0")]
+  TermDepositOpened { id: u64, account_id: AccountId, amount: NearToken, term_days: u32, apr_bps: u16, matures_at: u64 },
+
+  #[event_version("1.0.0")]
+  TermDepositRolledOver { id: u64, principal: NearToken, apr_bps: u16, matures_at: u64 },
+
+  #[event_version("1.0.0")]
+  TermDepositRedeemed { id: u64, account_id: AccountId, payout: NearToken, interest: NearToken, penalty: NearToken },
+
+  #[event_version("1.0.0")]
+  StandingOrderCreated { id: u64, payer_id: AccountId, receiver_id: AccountId, amount: NearToken, interval_seconds: u64, next_due_at: u64 },
+
+  #[event_version("1.0.0")]
+  StandingOrderExecuted { id: u64, payer_id: AccountId, receiver_id: AccountId, amount: NearToken, execution: u64, memo: Option<String> },
+
+  #[event_version("1.0.0")]
+  StandingOrderFailed { id: u64, attempt: u8, will_retry: bool },
+
+  #[event_version("1.0.0")]
+  StandingOrderCancelled { id: u64 },
+
+  #[event_version("1.0.0")]
+  StandingOrderCompleted { id: u64, executions: u64 },
+
+  #[event_version("1.0.0")]
+  InvoiceCreated { id: u64, payee_id: AccountId, payer_id: AccountId, amount: NearToken, expires_at: Option<u64> },
+
+  #[event_version("1.0.0")]
+  InvoicePaid { id: u64, payee_id: AccountId, payer_id: AccountId, amount: NearToken },
+
+  #[event_version("1.0.0")]
+  InvoiceDeclined { id: u64 },
+
+  #[event_version("1.0.0")]
+  InvoiceCancelled { id: u64 },
+
+  #[event_version("1.0.0")]
+  JointAccountCreated { id: u64, owners: Vec<AccountId>, required_approvals: u8, approval_threshold: NearToken },
+
+  #[event_version("1.0.0")]
+  JointAccountDeposit { id: u64, account_id: AccountId, amount: NearToken },
+
+  #[event_version("1.0.0")]
+  JointProposalCreated { id: u64, joint_account_id: u64, proposer_id: AccountId, amount: NearToken, required_approvals: u8 },
+
+  #[event_version("1.0.0")]
+  JointProposalApproved { id: u64, account_id: AccountId },
+
+  #[event_version("1.0.0")]
+  JointProposalExecuted { id: u64, joint_account_id: u64, receiver_id: AccountId, amount: NearToken },
+
+  #[event_version("1.0.0")]
+  JointProposalCancelled { id: u64 },
+
+  #[event_version("1.0.0")]
+  RoleGranted { account_id: AccountId, role: Role },
+
+  #[event_version("1.0.0")]
+  RoleRevoked { account_id: AccountId, role: Role },
+
+  #[event_version("1.0.0")]
+  SpendingLimitsUpdated { account_id: AccountId, daily: Option<NearToken>, monthly: Option<NearToken>, set_by: Option<AccountId> },
+
+  #[event_version("1.0.0")]
+  SpendingLimitsIncreaseQueued { account_id: AccountId, daily: Option<NearToken>, monthly: Option<NearToken>, effective_at: u64 },
+
+  /// `feature` is `None` when the global kill switch is engaged.
+  #[event_version("1.0.0")]
+  FeaturePaused { feature: Option<Feature>, account_id: AccountId },
+
+  #[event_version("1.0.0")]
+  FeatureUnpaused { feature: Option<Feature>, account_id: AccountId },
+
+  #[event_version("1.0.0")]
+  AccountRegistered { account_id: AccountId },
+
+  #[event_version("1.0.0")]
+  AccountUnregistered { account_id: AccountId },
+
+  #[event_version("1.0.0")]
+  WithdrawFailed { account_id: AccountId, amount: NearToken, ledger_entry_id: u64 },
+
+  #[event_version("1.0.0")]
+  BatchTransfer { batch_id: u64, sender_id: AccountId, succeeded: u32, failed: u32, total_sent: NearToken, fee: NearToken },
+
+  #[event_version("1.0.0")]
+  EscrowCreated { id: u64, payer_id: AccountId, payee_id: AccountId, arbiter_id: Option<AccountId>, amount: NearToken, deadline: u64 },
+
+  #[event_version("1.0.0")]
+  EscrowStatusChanged { id: u64, status: EscrowStatus },
+
+  #[event_version("1.0.0")]
+  StreamCreated { id: u64, sender_id: AccountId, receiver_id: AccountId, rate_per_second: NearToken, deposit: NearToken, start_at: u64, end_at: u64 },
+
+  #[event_version("1.0.0")]
+  StreamWithdrawn { id: u64, receiver_id: AccountId, amount: NearToken },
+
+  #[event_version("1.0.0")]
+  StreamClosed { id: u64, receiver_amount: NearToken, sender_refund: NearToken },
+
+  #[event_version("1.0.0")]
+  AllowanceApproved { owner_id: AccountId, spender_id: AccountId, allowance: NearToken, expires_at: Option<u64> },
+
+  #[event_version("1.0.0")]
+  AllowanceDecreased { owner_id: AccountId, spender_id: AccountId, remaining: NearToken },
+
+  #[event_version("1.0.0")]
+  AllowanceRevoked { owner_id: AccountId, spender_id: AccountId },
+
+  #[event_version("1.0.0")]
+  TransferFrom { owner_id: AccountId, spender_id: AccountId, receiver_id: AccountId, amount: NearToken, remaining: NearToken },
+
+  #[event_version("1.0.0")]
+  MandateCreated { id: u64, payer_id: AccountId, merchant_id: AccountId, max_per_period: NearToken, period_seconds: u64 },
+
+  #[event_version("1.0.0")]
+  MandateCollected { id: u64, payer_id: AccountId, merchant_id: AccountId, amount: NearToken, reference: Option<String> },
+
+  #[event_version("1.0.0")]
+  MandateStatusChanged { id: u64, status: MandateStatus },
+
+  #[event_version("1.0.0")]
+  EnvelopeCreated { account_id: AccountId, name: String },
+
+  #[event_version("1.0.0")]
+  EnvelopeFunded { account_id: AccountId, name: String, amount: NearToken },
+
+  #[event_version("1.0.0")]
+  EnvelopeReleased { account_id: AccountId, name: String, amount: NearToken },
+
+  #[event_version("1.0.0")]
+  EnvelopeLockChanged { account_id: AccountId, name: String, locked: bool },
+
+  #[event_version("1.0.0")]
+  EnvelopeDeleted { account_id: AccountId, name: String },
+
+  #[event_version("1.0.0")]
+  KycTierUpdated { account_id: AccountId, tier: u8 },
+
+  #[event_version("1.1.0")]
+  OverdraftSettingsUpdated { settings: OverdraftSettings },
+
+  #[event_version("1.1.0")]
+  OverdraftOpened { account_id: AccountId, limit: NearToken, grace_period_sec: u64 },
+
+  #[event_version("1.0.0")]
+  OverdraftLimitUpdated { account_id: AccountId, limit: NearToken, set_by: AccountId },
+
+  #[event_version("1.1.0")]
+  OverdraftDrawn { account_id: AccountId, amount: NearToken, owed: NearToken, interest_starts_at: Option<u64> },
+
+  #[event_version("1.0.0")]
+  OverdraftRepaid { account_id: AccountId, interest: NearToken, principal: NearToken, owed: NearToken },
+
+  #[event_version("1.0.0")]
+  OverdraftClosed { account_id: AccountId },
+
+  #[event_version("1.1.0")]
+  LoanOpened { id: u64, borrower_id: AccountId, collateral_asset: AssetId, collateral_amount: U128, principal: NearToken },
+
+  #[event_version("1.0.0")]
+  LoanCollateralAdded { id: u64, amount: U128 },
+
+  #[event_version("1.0.0")]
+  LoanRepaid { id: u64, interest: NearToken, principal: NearToken, debt_remaining: NearToken },
+
+  #[event_version("1.0.0")]
+  LoanClosed { id: u64, collateral_released: U128 },
+
+  #[event_version("1.0.0")]
+  LoanLiquidated {
+      id: u64,
+      borrower_id: AccountId,
+      liquidator_id: AccountId,
+      repaid: NearToken,
+      collateral_seized: U128,
+      /// Health factor at the time of liquidation.
+      health_factor_bps: u64,
+  },
+
+  #[event_version("1.0.0")]
+  PricesUpdated { asset_ids: Vec<String>, timestamp: u64 },
+
+  #[event_version("1.0.0")]
+  PoolStaked { pool_id: AccountId, amount: NearToken, success: bool },
+
+  #[event_version("1.0.0")]
+  PoolUnstaked { pool_id: AccountId, amount: NearToken, success: bool },
+
+  #[event_version("1.0.0")]
+  PoolWithdrawn { pool_id: AccountId, amount: NearToken, success: bool },
+
+  #[event_version("1.0.0")]
+  WithdrawalQueued { id: u64, account_id: AccountId, amount: NearToken },
+
+  #[event_version("1.0.0")]
+  QueuedWithdrawalPaid { id: u64, account_id: AccountId, amount: NearToken },
+
+  #[event_version("1.0.0")]
+  StakingRewardsSynced { pool_id: AccountId, rewards: NearToken },
+
+  #[event_version("1.0.0")]
+  YieldDistributed { amount: NearToken, yield_per_share: String },
+
+  #[event_version("1.0.0")]
+  YieldClaimed { account_id: AccountId, amount: NearToken, compounded: bool },
+
+  #[event_version("1.0.0")]
+  FeeScheduleProposed { schedule: FeeSchedule, executable_at: u64 },
+
+  #[event_version("1.0.0")]
+  FeeScheduleApplied { schedule: FeeSchedule },
+
+  #[event_version("1.0.0")]
+  FeeScheduleCancelled { cancelled_by: AccountId },
+
+  /// Carries the buckets left after the withdrawal, showing it came out of fees alone.
+  #[event_version("1.1.0")]
+  TreasuryWithdrawn {
+      receiver_id: AccountId,
+      amount: NearToken,
+      treasury_remaining: NearToken,
+      retention_buffer: NearToken,
+      insurance_fund: NearToken,
+      referral_budget: NearToken,
+      sponsorship_pool: NearToken,
+      user_liabilities: NearToken,
+  },
+
+  #[event_version("1.0.0")]
+  ReferralRegistered { account_id: AccountId, referrer_id: AccountId },
+
+  #[event_version("1.0.0")]
+  ReferralRewardsClaimed { account_id: AccountId, amount: NearToken },
+
+  #[event_version("1.0.0")]
+  ReferralBudgetFunded { amount: NearToken, budget: NearToken },
+
+  #[event_version("1.0.0")]
+  LoyaltyEnrolled { account_id: AccountId },
+
+  #[event_version("1.0.0")]
+  LoyaltyPointsRedeemed { account_id: AccountId, points: u64, fee_credit: NearToken },
+
+  #[event_version("1.0.0")]
+  LoyaltyPointsExpired { account_id: AccountId, points: u64 },
+
+  #[event_version("1.0.0")]
+  KycTierCached { account_id: AccountId, tier: u8, did_active: bool },
+
+  #[event_version("1.0.0")]
+  InheritancePlanSet { account_id: AccountId, beneficiaries: Vec<Beneficiary>, inactivity_days: u32 },
+
+  #[event_version("1.0.0")]
+  InheritancePlanRemoved { account_id: AccountId },
+
+  #[event_version("1.0.0")]
+  InheritanceClaimInitiated { account_id: AccountId, initiated_by: AccountId, challenge_ends_at: u64 },
+
+  #[event_version("1.0.0")]
+  InheritanceClaimCancelled { account_id: AccountId },
+
+  #[event_version("1.0.0")]
+  InheritancePaid { account_id: AccountId, beneficiary_id: AccountId, amount: NearToken },
+
+  #[event_version("1.0.0")]
+  DormancyStatusChanged { account_id: AccountId, status: DormancyStatus },
+
+  #[event_version("1.0.0")]
+  AccountEscheated { account_id: AccountId, amount: NearToken, escheated_by: AccountId },
+
+  #[event_version("1.0.0")]
+  EscheatedFundsReclaimed { account_id: AccountId, amount: NearToken },
+
+  #[event_version("1.0.0")]
+  GiftCreated { sender_id: AccountId, public_key: PublicKey, amount: NearToken, expires_at: u64 },
+
+  #[event_version("1.0.0")]
+  GiftClaimed { public_key: PublicKey, sender_id: AccountId, receiver_id: AccountId, amount: NearToken },
+
+  #[event_version("1.0.0")]
+  GiftRefunded { public_key: PublicKey, sender_id: AccountId, amount: NearToken },
+
+  #[event_version("1.0.0")]
+  MerchantRegistered { merchant_id: AccountId, name: String },
+
+  #[event_version("1.0.0")]
+  MerchantPaymentApproved { merchant_id: AccountId, customer_id: AccountId, order_id: String, amount: NearToken },
+
+  #[event_version("1.0.0")]
+  MerchantCharged { merchant_id: AccountId, customer_id: AccountId, order_id: String, amount: NearToken },
+
+  #[event_version("1.1.0")]
+  MerchantSettled { merchant_id: AccountId, index: u64, amount: NearToken, fee: NearToken, order_count: u64 },
+
+  #[event_version("1.0.0")]
+  PaymentIntentCreated { id: u64, payee_id: AccountId, amount: NearToken, expires_at: u64 },
+
+  #[event_version("1.0.0")]
+  PaymentIntentPaid { id: u64, payee_id: AccountId, payer_id: AccountId, amount: NearToken },
+
+  #[event_version("1.0.0")]
+  PaymentIntentCancelled { id: u64 },
+
+  #[event_version("1.0.0")]
+  SubscriptionPlanCreated { id: u64, merchant_id: AccountId, price: NearToken, interval_seconds: u64 },
+
+  #[event_version("1.0.0")]
+  Subscribed { id: u64, plan_id: u64, subscriber_id: AccountId, merchant_id: AccountId },
+
+  #[event_version("1.0.0")]
+  SubscriptionCharged { id: u64, subscriber_id: AccountId, amount: NearToken },
+
+  /// Dunning notice: the charge failed and will be retried until `grace_ends_at`.
+  #[event_version("1.0.0")]
+  SubscriptionPaymentFailed { id: u64, subscriber_id: AccountId, attempt: u32, grace_ends_at: u64 },
+
+  #[event_version("1.0.0")]
+  SubscriptionLapsed { id: u64, subscriber_id: AccountId },
+
+  #[event_version("1.0.0")]
+  SubscriptionCancelled { id: u64, cancelled_by: AccountId, refund: NearToken },
+
+  #[event_version("1.0.0")]
+  ChargeDisputed { merchant_id: AccountId, order_id: String, customer_id: AccountId, reason: String },
+
+  #[event_version("1.0.0")]
+  ChargeDisputeResolved { merchant_id: AccountId, order_id: String, refunded: bool },
+
+  #[event_version("1.0.0")]
+  ComplianceAlert { account_id: AccountId, rule: MonitoringRule, amount: NearToken, blocked: bool },
+
+  #[event_version("1.0.0")]
+  MonitoringOverrideGranted { account_id: AccountId, expires_at: u64, granted_by: AccountId },
+
+  #[event_version("1.0.0")]
+  GuardianThresholdSet { account_id: AccountId, threshold: Option<NearToken>, effective_at: u64 },
+
+  #[event_version("1.0.0")]
+  GuardianApprovalRequested { id: u64, account_id: AccountId, amount: NearToken, expires_at: u64 },
+
+  #[event_version("1.0.0")]
+  GuardedActionApproved { id: u64, account_id: AccountId, guardian_id: AccountId },
+
+  #[event_version("1.0.0")]
+  GuardedActionCancelled { id: u64, account_id: AccountId },
+
+  #[event_version("1.0.0")]
+  AddressBookModeChanged { account_id: AccountId, enabled: bool, effective_at: u64 },
+  #[event_version("1.0.0")]
+  TrustedRecipientAdded { account_id: AccountId, recipient_id: AccountId, active_at: u64 },
+  #[event_version("1.0.0")]
+  TrustedRecipientApproved { account_id: AccountId, recipient_id: AccountId, guardian_id: AccountId },
+  #[event_version("1.0.0")]
+  TrustedRecipientRemoved { account_id: AccountId, recipient_id: AccountId },
+
+  #[event_version("1.0.0")]
+  WithdrawalTimelocked { id: u64, account_id: AccountId, amount: NearToken, executable_at: u64 },
+  #[event_version("1.0.0")]
+  TimelockedWithdrawalCancelled { id: u64, account_id: AccountId, cancelled_by: AccountId },
+  #[event_version("1.0.0")]
+  TimelockedWithdrawalExecuted { id: u64, account_id: AccountId, amount: NearToken, executed_by: AccountId },
+
+  #[event_version("1.0.0")]
+  MultisigAdminAdded { account_id: AccountId },
+  #[event_version("1.0.0")]
+  MultisigAdminRemoved { account_id: AccountId },
+  #[event_version("1.0.0")]
+  WithdrawalProposed { id: u64, proposer_id: AccountId, receiver_id: AccountId, amount: NearToken },
+  #[event_version("1.0.0")]
+  WithdrawalApproved { id: u64, admin_id: AccountId },
+  #[event_version("1.0.0")]
+  WithdrawalProposalApproved { id: u64, executable_at: u64 },
+  #[event_version("1.0.0")]
+  WithdrawalProposalCancelled { id: u64, cancelled_by: AccountId },
+  #[event_version("1.0.0")]
+  WithdrawalExecuted { id: u64, receiver_id: AccountId, amount: NearToken },
+  #[event_version("1.0.0")]
+  MultisigChangeProposed { id: u64, proposer_id: AccountId, action: MultisigAction },
+  #[event_version("1.0.0")]
+  MultisigChangeApproved { id: u64, admin_id: AccountId },
+  #[event_version("1.0.0")]
+  MultisigChangeProposalApproved { id: u64, executable_at: u64 },
+  #[event_version("1.0.0")]
+  MultisigChangeCancelled { id: u64, cancelled_by: AccountId },
+  #[event_version("1.0.0")]
+  MultisigChangeExecuted { id: u64, action: MultisigAction },
+
+  #[event_version("1.0.0")]
+  NearWrapped { account_id: AccountId, amount: NearToken },
+  #[event_version("1.0.0")]
+  NearUnwrapped { account_id: AccountId, amount: NearToken },
+  #[event_version("1.0.0")]
+  WnearConversionFailed { account_id: AccountId, amount: NearToken, wrap: bool },
+
+  #[event_version("1.0.0")]
+  ComplianceUsdLimitsUpdated { account_id: AccountId, daily_usd: Option<U128>, monthly_usd: Option<U128>, set_by: AccountId },
+
+  #[event_version("1.0.0")]
+  SwapRequested { account_id: AccountId, token_in: AccountId, token_out: AccountId, amount: U128, min_out: U128 },
+  #[event_version("1.0.0")]
+  SwapSettled { account_id: AccountId, token_in: AccountId, token_out: AccountId, amount_in: U128, amount_out: U128, spread: U128 },
+  #[event_version("1.0.0")]
+  SwapFailed { account_id: AccountId, token_in: AccountId, amount: U128 },
+
+  #[event_version("1.0.0")]
+  SavingsGoalCreated { account_id: AccountId, name: String, target: NearToken, target_date: u64, hard_lock: bool },
+  #[event_version("1.0.0")]
+  SavingsGoalFunded { account_id: AccountId, name: String, amount: NearToken, balance: NearToken },
+  /// A goal's balance reached its target for the first time.
+  #[event_version("1.0.0")]
+  SavingsGoalReached { account_id: AccountId, name: String, target: NearToken },
+  #[event_version("1.0.0")]
+  SavingsGoalWithdrawn { account_id: AccountId, name: String, amount: NearToken, penalty: NearToken },
+  #[event_version("1.0.0")]
+  SavingsGoalDeleted { account_id: AccountId, name: String, refunded: NearToken },
+
+  #[event_version("1.0.0")]
+  RoundUpEnabled { account_id: AccountId, unit: NearToken, target: RoundUpTarget },
+  #[event_version("1.0.0")]
+  RoundUpDisabled { account_id: AccountId },
+  #[event_version("1.0.0")]
+  RoundUpSwept { account_id: AccountId, amount: NearToken, target: RoundUpTarget },
+
+  #[event_version("1.0.0")]
+  InsuranceContribution { contributor_id: AccountId, amount: NearToken },
+  #[event_version("1.0.0")]
+  InsuranceClaimSubmitted { id: u64, claimant_id: AccountId, event: CoveredEvent, amount: NearToken },
+  #[event_version("1.0.0")]
+  InsuranceClaimReviewed { id: u64, assessor_id: AccountId, approved: bool, amount: NearToken },
+  #[event_version("1.0.0")]
+  InsuranceClaimPaid { id: u64, claimant_id: AccountId, amount: NearToken },
+
+  #[event_version("1.0.0")]
+  RateModelProposed { model: RateModel, executable_at: u64 },
+  #[event_version("1.0.0")]
+  RateModelApplied { model: RateModel },
+  #[event_version("1.0.0")]
+  RateModelCancelled { cancelled_by: AccountId },
+
+  #[event_version("1.0.0")]
+  CreditScoreUpdated { account_id: AccountId, score: u16 },
+
+  #[event_version("1.0.0")]
+  InstallmentCollected { loan_id: u64, borrower_id: AccountId, amount: NearToken, amount_due: NearToken },
+
+  #[event_version("1.0.0")]
+  GuaranteeRequested { loan_id: u64, guarantor_id: AccountId, cover: NearToken },
+  #[event_version("1.0.0")]
+  GuaranteeAccepted { loan_id: u64, guarantor_id: AccountId, locked: NearToken },
+  #[event_version("1.0.0")]
+  GuaranteeReleased { loan_id: u64, guarantor_id: AccountId, released: NearToken },
+  #[event_version("1.0.0")]
+  GuaranteeClaimed { loan_id: u64, guarantor_id: AccountId, drawn: NearToken },
+
+  #[event_version("1.0.0")]
+  NftCollectionUpdated { nft_contract_id: AccountId, ltv_bps: Option<u16> },
+  #[event_version("1.0.0")]
+  NftAppraised { nft_contract_id: AccountId, token_id: String, value: NearToken, appraiser_id: AccountId },
+  #[event_version("1.0.0")]
+  NftPledged { line_id: u64, borrower_id: AccountId, nft_contract_id: AccountId, token_id: String, limit: NearToken, due_at: u64 },
+  #[event_version("1.0.0")]
+  NftCreditDrawn { line_id: u64, borrower_id: AccountId, amount: NearToken },
+  #[event_version("1.0.0")]
+  NftCreditRepaid { line_id: u64, interest: NearToken, principal: NearToken, debt_remaining: NearToken },
+  #[event_version("1.0.0")]
+  NftAuctionStarted { line_id: u64, reserve: NearToken, ends_at: u64 },
+  #[event_version("1.0.0")]
+  NftAuctionBid { line_id: u64, bidder_id: AccountId, amount: NearToken },
+  #[event_version("1.0.0")]
+  NftAuctionSettled { line_id: u64, winner_id: Option<AccountId>, proceeds: NearToken, written_off: NearToken },
+  #[event_version("1.0.0")]
+  NftCollateralReleased { line_id: u64, nft_contract_id: AccountId, token_id: String, receiver_id: AccountId },
+
+  #[event_version("1.0.0")]
+  RecoveryHoldPlaced { account_id: AccountId, recovery_id: String },
+  #[event_version("1.0.0")]
+  RecoveryHoldLifted { account_id: AccountId, recovery_id: String },
+
+  #[event_version("1.0.0")]
+  StateMigrated { from_version: u16, to_version: u16 },
+
+  #[event_version("1.0.0")]
+  CircuitBreakerTripped { net_outflow: NearToken, threshold: NearToken },
+  #[event_version("1.0.0")]
+  CircuitBreakerResetApproved { admin_id: AccountId, approvals: u32 },
+  #[event_version("1.0.0")]
+  CircuitBreakerReset { approvals: u32 },
+
+  #[event_version("1.0.0")]
+  ClaimableTransferSent { id: u64, sender_id: AccountId, receiver_id: AccountId, amount: NearToken, expires_at: u64 },
+  #[event_version("1.0.0")]
+  ClaimableTransferClaimed { id: u64, sender_id: AccountId, receiver_id: AccountId, amount: NearToken },
+  #[event_version("1.0.0")]
+  ClaimableTransferRefunded { id: u64, sender_id: AccountId, amount: NearToken, expired: bool },
+
+  #[event_version("1.0.0")]
+  TransferPending { id: u64, sender_id: AccountId, receiver_id: AccountId, amount: NearToken, executable_at: u64 },
+  #[event_version("1.0.0")]
+  PendingTransferCancelled { id: u64, sender_id: AccountId, receiver_id: AccountId, amount: NearToken },
+  #[event_version("1.0.0")]
+  PendingTransferExecuted { id: u64, sender_id: AccountId, executed_by: AccountId },
+
+  #[event_version("1.0.0")]
+  ProfileUpdated { account_id: AccountId },
+
+  #[event_version("1.0.0")]
+  FtTransfer { sender_id: AccountId, receiver_id: AccountId, token_id: AccountId, amount: U128, memo: Option<String> },
+
+  #[event_version("1.0.0")]
+  SponsorshipPoolFunded { amount: NearToken, pool: NearToken },
+  #[event_version("1.0.0")]
+  SponsoredRegistration { account_id: AccountId, storage: NearToken },
+
+  #[event_version("1.0.0")]
+  DebitCardIssued { account_id: AccountId, public_key: PublicKey, limits: DebitCardLimits },
+  #[event_version("1.0.0")]
+  DebitCardLimitsUpdated { account_id: AccountId, public_key: PublicKey, limits: DebitCardLimits },
+  #[event_version("1.0.0")]
+  DebitCardRevoked { account_id: AccountId, public_key: PublicKey },
+  #[event_version("1.0.0")]
+  DebitCardPayment { account_id: AccountId, public_key: PublicKey, amount: NearToken },
+
+  #[event_version("1.0.0")]
+  MerchantPosNettingChanged { merchant_id: AccountId, enabled: bool },
+  #[event_version("1.0.0")]
+  MerchantPosPayment { merchant_id: AccountId, customer_id: AccountId, amount: NearToken, netted: bool },
+
+  #[event_version("1.0.0")]
+  FeeSweepConfigUpdated { config: FeeSweepConfig },
+  #[event_version("1.0.0")]
+  FeesSwept { destination: AccountId, amount: NearToken, retained: NearToken },
+
+  #[event_version("1.0.0")]
+  PrizeSavingsConfigUpdated { config: PrizeSavingsConfig },
+  #[event_version("1.0.0")]
+  PrizeSavingsEntered { account_id: AccountId, amount: NearToken },
+  #[event_version("1.0.0")]
+  PrizeSavingsExited { account_id: AccountId, amount: NearToken },
+  #[event_version("1.0.0")]
+  PrizeEpochClosed { epoch: u64, total_tickets: U128, prize: NearToken },
+  #[event_version("1.0.0")]
+  PrizeAwarded { epoch: u64, winner_id: AccountId, prize: NearToken },
+
+  #[event_version("1.0.0")]
+  AccountClosed { account_id: AccountId, beneficiary_id: AccountId, balance_paid: NearToken, storage_refund: NearToken },
+
+  #[event_version("1.0.0")]
+  OperationLockExpired { account_id: AccountId, lock_id: u64, operation: LockedOperation },
+
+  #[event_version("1.0.0")]
+  WithdrawalAuthorized { account_id: AccountId, amount: NearToken, nonce: u64, key_id: String, executor_id: AccountId },
+
+  #[event_version("1.0.0")]
+  TokenReserveRead { token_id: AccountId, balance: U128, liabilities: U128 },
+
+  #[event_version("1.0.0")]
+  CrankTaskRegistered { task_id: u64, job: CrankJob, interval_sec: u64, gas_budget_tgas: u64, next_run_at: u64 },
+
+  #[event_version("1.0.0")]
+  CrankTaskDeregistered { task_id: u64 },
+
+  #[event_version("1.0.0")]
+  CrankTaskRun { task_id: u64, job: CrankJob, run: u64 },
+
+  #[event_version("1.0.0")]
+  SpendingCategoriesUpdated { categories: Vec<String> },
+
+  #[event_version("1.0.0")]
+  CategoryRuleUpdated { account_id: AccountId, counterparty_id: AccountId, category: Option<String> },
+
+  #[event_version("1.0.0")]
+  EntryCategorized { account_id: AccountId, entry_id: u64, category: Option<String> },
+
+  #[event_version("1.0.0")]
+  CharityRegistered { charity_id: AccountId, name: String, match_bps: u16 },
+
+  #[event_version("1.0.0")]
+  CharityDeregistered { charity_id: AccountId },
+
+  #[event_version("1.0.0")]
+  MatchingPoolFunded { amount: NearToken, pool: NearToken },
+
+  #[event_version("1.0.0")]
+  DonationRuleSet { account_id: AccountId, charity_id: AccountId, bps: u16 },
+
+  #[event_version("1.0.0")]
+  DonationRuleDisabled { account_id: AccountId },
+
+  #[event_version("1.0.0")]
+  DonationMade { donor_id: AccountId, charity_id: AccountId, amount: NearToken, matched: NearToken },
+
+  #[event_version("1.0.0")]
+  GovernanceUpdated { governance_id: Option<AccountId> },
+}
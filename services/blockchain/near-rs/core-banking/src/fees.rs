@@ -0,0 +1,203 @@
+// services/blockchain/near-rs/core-banking/src/fees.rs
+use near_sdk::{near, env, AccountId, Gas, NearToken, Promise, PromiseError};
+
+use crate::ledger::{EntryDirection, EntryKind};
+use crate::savings::BPS_DENOMINATOR;
+use crate::{mul_div, BankEvent, BioCrypticBankCore, BioCrypticBankCoreExt};
+
+const NANOS_PER_SECOND: u64 = 1_000_000_000;
+/// Delay between proposing a fee schedule and being able to apply it (48 hours).
+pub const FEE_UPDATE_TIMELOCK_SEC: u64 = 48 * 60 * 60;
+/// Upper bound on any percentage fee (5%).
+const MAX_FEE_BPS: u16 = 500;
+const GAS_FOR_TREASURY_CALLBACK: Gas = Gas::from_tgas(10);
+
+/// Operations that carry a fee under the schedule.
+#[near(serializers = [json])]
+#[serde(rename_all = "snake_case")]
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum FeeOperation {
+  Withdrawal,
+  Transfer,
+  /// Conversion between NEAR and a token, charged as a spread on the converted amount.
+  Fx,
+  /// Point-of-sale revenue received by a merchant.
+  MerchantSettlement,
+}
+
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct FeeSchedule {
+  /// Charged on every NEAR withdrawal, on top of the amount withdrawn.
+  pub withdrawal_flat_fee: NearToken,
+  /// Charged on the amount of every internal transfer, on top of it.
+  pub transfer_fee_bps: u16,
+  /// Spread taken on currency conversions.
+  pub fx_spread_bps: u16,
+  /// Charged to merchants on point-of-sale payments: once per settlement batch in netting
+  /// mode, otherwise on each payment. Deducted from the amount received.
+  pub merchant_fee_bps: u16,
+  /// KYC tiers that pay no fees.
+  pub exempt_kyc_tiers: Vec<u8>,
+}
+
+/// Where collected fees are swept. Changed only through a `MultisigAction::SetFeeSweepConfig`
+/// proposal.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct FeeSweepConfig {
+  /// DAO or treasury contract receiving swept fees. Sweeping is off while unset.
+  pub destination: Option<AccountId>,
+  /// Fees kept in the contract's treasury for operations; only the excess is swept or can
+  /// be withdrawn through a multisig proposal.
+  pub retention_buffer: NearToken,
+}
+
+/// A fee schedule waiting out its timelock.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct PendingFeeSchedule {
+  pub schedule: FeeSchedule,
+  pub proposed_by: AccountId,
+  /// Earliest time (ns) the schedule can be applied.
+  pub executable_at: u64,
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Proposes a new fee schedule, replacing any pending proposal. It can be applied once
+  /// `FEE_UPDATE_TIMELOCK_SEC` have passed, giving depositors time to react. Owner or governance only.
+  pub fn propose_fee_schedule(&mut self, schedule: FeeSchedule) {
+      self.assert_owner_or_governance();
+      assert!(
+          schedule.transfer_fee_bps <= MAX_FEE_BPS && schedule.fx_spread_bps <= MAX_FEE_BPS
+              && schedule.merchant_fee_bps <= MAX_FEE_BPS,
+          "Fees cannot exceed {} bps.", MAX_FEE_BPS
+      );
+      let executable_at = env::block_timestamp() + FEE_UPDATE_TIMELOCK_SEC * NANOS_PER_SECOND;
+      self.pending_fee_schedule = Some(PendingFeeSchedule {
+          schedule: schedule.clone(),
+          proposed_by: env::predecessor_account_id(),
+          executable_at,
+      });
+      BankEvent::FeeScheduleProposed { schedule, executable_at }.emit();
+  }
+
+  /// Makes the pending fee schedule current once its timelock has passed. Callable by anyone.
+  pub fn apply_fee_schedule(&mut self) {
+      let pending = self.pending_fee_schedule.take()
+          .unwrap_or_else(|| env::panic_str("No fee schedule is pending."));
+      assert!(env::block_timestamp() >= pending.executable_at, "Fee schedule timelock has not passed.");
+      self.fee_schedule = pending.schedule.clone();
+      BankEvent::FeeScheduleApplied { schedule: pending.schedule }.emit();
+  }
+
+  /// Discards the pending fee schedule. Owner or governance only.
+  pub fn cancel_fee_schedule(&mut self) {
+      self.assert_owner_or_governance();
+      assert!(self.pending_fee_schedule.take().is_some(), "No fee schedule is pending.");
+      BankEvent::FeeScheduleCancelled { cancelled_by: env::predecessor_account_id() }.emit();
+  }
+
+  /// Permissionless crank sending the fee treasury above the retention buffer to the sweep
+  /// destination, bounded by the contract's withdrawable surplus. The sweep counts towards the
+  /// circuit breaker's outflow. Returns the amount swept.
+  pub fn sweep_fees(&mut self) -> NearToken {
+      let destination = self.fee_sweep_config.destination.clone()
+          .unwrap_or_else(|| env::panic_str("No fee sweep destination is set."));
+      let amount = self.internal_sweepable_fees();
+      assert!(!amount.is_zero(), "No fees above the retention buffer to sweep.");
+      self.treasury_balance = self.treasury_balance.saturating_sub(amount);
+      self.internal_record_contract_outflow(amount);
+      BankEvent::FeesSwept { destination: destination.clone(), amount, retained: self.treasury_balance }.emit();
+      Promise::new(destination).transfer(amount).then(
+          Self::ext(env::current_account_id())
+              .with_static_gas(GAS_FOR_TREASURY_CALLBACK)
+              .on_treasury_withdrawn(amount)
+      );
+      amount
+  }
+
+  /// Callback returning a failed treasury payout to the treasury.
+  #[private]
+  pub fn on_treasury_withdrawn(&mut self, amount: NearToken, #[callback_result] result: Result<(), PromiseError>) -> bool {
+      if result.is_err() {
+          self.internal_collect_fee(amount);
+          env::log_str("Treasury withdrawal failed; amount returned to the treasury.");
+      }
+      result.is_ok()
+  }
+
+  /// View function.
+  pub fn get_fee_schedule(&self) -> FeeSchedule {
+      self.fee_schedule.clone()
+  }
+
+  /// View function.
+  pub fn get_fee_sweep_config(&self) -> FeeSweepConfig {
+      self.fee_sweep_config.clone()
+  }
+
+  /// Amount `sweep_fees` would send now, which is also the most a multisig withdrawal can pay.
+  /// View function.
+  pub fn get_sweepable_fees(&self) -> NearToken {
+      self.internal_sweepable_fees()
+  }
+
+  /// View function.
+  pub fn get_pending_fee_schedule(&self) -> Option<PendingFeeSchedule> {
+      self.pending_fee_schedule.clone()
+  }
+
+  /// Fee `account_id` would pay for `operation` on `amount` under the current schedule.
+  /// View function.
+  pub fn get_fee_quote(&self, account_id: AccountId, operation: FeeOperation, amount: NearToken) -> NearToken {
+      self.internal_operation_fee(&account_id, operation, amount)
+  }
+}
+
+impl BioCrypticBankCore {
+  pub(crate) fn internal_set_fee_sweep_config(&mut self, config: FeeSweepConfig) {
+      self.fee_sweep_config = config.clone();
+      BankEvent::FeeSweepConfigUpdated { config }.emit();
+  }
+
+  fn internal_sweepable_fees(&self) -> NearToken {
+      self.treasury_balance
+          .saturating_sub(self.fee_sweep_config.retention_buffer)
+          .min(self.get_withdrawable_surplus())
+  }
+
+  /// Fee owed by `account_id` for `operation` on `amount`; zero for exempt tiers.
+  pub(crate) fn internal_operation_fee(&self, account_id: &AccountId, operation: FeeOperation, amount: NearToken) -> NearToken {
+      let schedule = &self.fee_schedule;
+      if schedule.exempt_kyc_tiers.contains(&self.get_kyc_tier(account_id.clone())) {
+          return NearToken::from_yoctonear(0);
+      }
+      let bps = match operation {
+          FeeOperation::Withdrawal => return schedule.withdrawal_flat_fee,
+          FeeOperation::Transfer => schedule.transfer_fee_bps,
+          FeeOperation::Fx => schedule.fx_spread_bps,
+          FeeOperation::MerchantSettlement => schedule.merchant_fee_bps,
+      };
+      NearToken::from_yoctonear(mul_div(amount.as_yoctonear(), bps as u128, BPS_DENOMINATOR))
+  }
+
+  /// Fee owed by `account_id` for `operation` on `amount` after spending its loyalty fee credit.
+  pub(crate) fn internal_discounted_fee(&mut self, account_id: &AccountId, operation: FeeOperation, amount: NearToken) -> NearToken {
+      let fee = self.internal_operation_fee(account_id, operation, amount);
+      self.internal_spend_fee_credit(account_id, fee)
+  }
+
+  /// Moves a fee already debited from `account_id` into the treasury, records it and
+  /// accrues the referrer's share.
+  pub(crate) fn internal_charge_fee(&mut self, account_id: &AccountId, fee: NearToken, reference: Option<String>) {
+      if fee.is_zero() {
+          return;
+      }
+      self.internal_remove_liabilities(fee);
+      self.internal_collect_fee_with_insurance(fee);
+      self.internal_accrue_referral_reward(account_id, fee);
+      self.internal_record_entry(account_id, EntryKind::Fee, EntryDirection::Debit, fee, None, reference);
+  }
+}
@@ -0,0 +1,100 @@
+// services/blockchain/near-rs/core-banking/src/fiat.rs
+use near_sdk::{near, env, AccountId, NearToken};
+use near_sdk::json_types::U128;
+
+use crate::oracle::USD_DECIMALS;
+use crate::{mul_div, BioCrypticBankCore, BioCrypticBankCoreExt};
+
+/// Built-in reference currency every oracle price is quoted in.
+pub const USD: &str = "USD";
+const MAX_REFERENCE_CURRENCIES: u32 = 20;
+
+/// An account's spending limit usage expressed in a fiat reference currency. Amounts have
+/// `USD_DECIMALS` decimals; `None` where no cap applies or no price is cached.
+#[near(serializers = [json])]
+#[derive(Debug, PartialEq)]
+pub struct FiatLimitsView {
+  pub currency: String,
+  pub spent_today: Option<U128>,
+  pub spent_this_month: Option<U128>,
+  pub remaining_daily: Option<U128>,
+  pub remaining_monthly: Option<U128>,
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Adds or replaces fiat reference currency `currency` (ISO 4217 code, e.g. `EUR`), priced
+  /// by oracle asset `asset_id` quoting the USD value of one unit of it. `None` removes the
+  /// currency. Refresh its price with `refresh_prices`. Owner only.
+  pub fn set_reference_currency(&mut self, currency: String, asset_id: Option<String>) {
+      self.assert_owner();
+      assert!(
+          currency.len() == 3 && currency.bytes().all(|byte| byte.is_ascii_uppercase()) && currency != USD,
+          "Currency must be a three-letter ISO code other than USD."
+      );
+      match asset_id {
+          Some(asset_id) => {
+              assert!(
+                  self.reference_currencies.contains_key(&currency) || self.reference_currencies.len() < MAX_REFERENCE_CURRENCIES,
+                  "Cannot exceed {} reference currencies.", MAX_REFERENCE_CURRENCIES
+              );
+              self.reference_currencies.insert(currency, asset_id);
+          }
+          None => {
+              assert!(self.reference_currencies.remove(&currency).is_some(), "Currency is not configured.");
+          }
+      }
+  }
+
+  /// Reference currencies besides USD and the oracle asset pricing each.
+  /// View function.
+  pub fn get_reference_currencies(&self) -> Vec<(String, String)> {
+      self.reference_currencies.iter().map(|(currency, asset_id)| (currency.clone(), asset_id.clone())).collect()
+  }
+
+  /// Value of `amount` in `currency` (`USD_DECIMALS` decimals) at the cached prices, e.g. to
+  /// show a transaction amount. `None` if a price is missing.
+  /// View function.
+  pub fn quote_near_amount(&self, amount: NearToken, currency: String) -> Option<U128> {
+      self.internal_fiat_value(amount, &currency).map(U128)
+  }
+
+  /// Value of `account_id`'s NEAR balance in `currency` (`USD_DECIMALS` decimals).
+  /// View function.
+  pub fn get_balance_in(&self, account_id: AccountId, currency: String) -> Option<U128> {
+      self.quote_near_amount(self.get_balance(account_id), currency)
+  }
+
+  /// `account_id`'s spending limit usage and remaining allowance valued in `currency`.
+  /// View function.
+  pub fn get_spending_limits_in(&self, account_id: AccountId, currency: String) -> Option<FiatLimitsView> {
+      let view = self.get_spending_limits(account_id)?;
+      let convert = |amount: NearToken| self.internal_fiat_value(amount, &currency).map(U128);
+      Some(FiatLimitsView {
+          spent_today: convert(view.spent_today),
+          spent_this_month: convert(view.spent_this_month),
+          remaining_daily: view.remaining_daily.and_then(convert),
+          remaining_monthly: view.remaining_monthly.and_then(convert),
+          currency,
+      })
+  }
+}
+
+impl BioCrypticBankCore {
+  /// Value of one unit of `currency` in USD (`USD_DECIMALS` decimals).
+  fn internal_usd_per_unit(&self, currency: &str) -> Option<u128> {
+      if currency == USD {
+          return Some(10u128.pow(USD_DECIMALS as u32));
+      }
+      let asset_id = self.reference_currencies.get(currency)
+          .unwrap_or_else(|| env::panic_str("Unknown reference currency."));
+      self.internal_usd_value(asset_id, 1).filter(|usd| *usd > 0)
+  }
+
+  /// Value of `amount` in `currency` (`USD_DECIMALS` decimals) at the cached prices.
+  pub(crate) fn internal_fiat_value(&self, amount: NearToken, currency: &str) -> Option<u128> {
+      let usd_per_unit = self.internal_usd_per_unit(currency)?;
+      let usd = self.internal_usd_value(&self.oracle_config.near_asset_id, amount.as_yoctonear())?;
+      Some(mul_div(usd, 10u128.pow(USD_DECIMALS as u32), usd_per_unit))
+  }
+}
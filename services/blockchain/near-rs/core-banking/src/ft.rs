@@ -0,0 +1,103 @@
+// services/blockchain/near-rs/core-banking/src/ft.rs
+use near_sdk::{near, env, AccountId, PromiseError, PromiseOrValue};
+use near_sdk::json_types::U128;
+use biocrypticbank_common::assets::AssetId;
+
+use crate::pause::Feature;
+use crate::{BankEvent, BioCrypticBankCore, BioCrypticBankCoreExt};
+
+#[near]
+impl BioCrypticBankCore {
+  /// Allows deposits of the NEP-141 token at `token_id`. Owner only.
+  pub fn add_whitelisted_token(&mut self, token_id: AccountId) {
+      self.assert_owner();
+      assert!(self.token_whitelist.insert(token_id.clone()), "Token is already whitelisted.");
+      env::log_str(&format!("Token whitelisted: {}", token_id));
+  }
+
+  /// Stops accepting deposits of `token_id`. Existing balances remain withdrawable. Owner only.
+  pub fn remove_whitelisted_token(&mut self, token_id: AccountId) {
+      self.assert_owner();
+      assert!(self.token_whitelist.remove(&token_id), "Token is not whitelisted.");
+      env::log_str(&format!("Token removed from whitelist: {}", token_id));
+  }
+
+  /// NEP-141 receiver hook. Credits `amount` of the calling token contract to `sender_id`.
+  /// Only whitelisted tokens from registered senders are accepted; the full amount is always used.
+  #[allow(unused_variables)]
+  pub fn ft_on_transfer(&mut self, sender_id: AccountId, amount: U128, msg: String) -> PromiseOrValue<U128> {
+      self.assert_not_paused(Feature::Deposits);
+      let token_id = env::predecessor_account_id();
+      assert!(self.token_whitelist.contains(&token_id), "Token is not whitelisted.");
+      assert!(amount.0 > 0, "Deposit amount must be greater than 0.");
+      self.assert_registered(&sender_id);
+
+      self.internal_deposit_asset(&sender_id, &AssetId::Ft(token_id), amount.0);
+      PromiseOrValue::Value(U128(0))
+  }
+
+  /// Withdraws `amount` of `token_id` from the caller's balance via `ft_transfer`.
+  /// The balance is restored if the transfer fails.
+  pub fn ft_withdraw(&mut self, token_id: AccountId, amount: U128) -> PromiseOrValue<bool> {
+      self.assert_not_paused(Feature::Withdrawals);
+      let account_id = env::predecessor_account_id();
+      assert!(amount.0 > 0, "Withdrawal amount must be greater than 0.");
+      self.assert_no_recovery_hold(&account_id);
+      self.internal_withdraw_asset(&account_id, &AssetId::Ft(token_id), amount.0)
+  }
+
+  /// Callback re-crediting the token balance when `ft_transfer` fails and releasing the
+  /// account's lock.
+  #[private]
+  pub fn ft_withdraw_callback(
+      &mut self,
+      account_id: AccountId,
+      token_id: AccountId,
+      amount: U128,
+      lock_id: Option<u64>,
+      #[callback_result] result: Result<(), PromiseError>,
+  ) -> bool {
+      self.internal_unlock_account(&account_id, lock_id);
+      if result.is_ok() {
+          BankEvent::FtWithdraw { account_id, token_id, amount }.emit();
+          return true;
+      }
+      self.internal_ft_credit(&account_id, &token_id, amount.0);
+      BankEvent::FtWithdrawFailed { account_id, token_id, amount }.emit();
+      false
+  }
+
+  /// Retrieves the balance of `token_id` held for `account_id`.
+  pub fn get_ft_balance(&self, account_id: AccountId, token_id: AccountId) -> U128 {
+      U128(self.ft_balances.get(&(account_id, token_id)).copied().unwrap_or(0))
+  }
+
+  /// Lists the tokens currently accepted for deposit.
+  pub fn get_whitelisted_tokens(&self) -> Vec<AccountId> {
+      self.token_whitelist.iter().cloned().collect()
+  }
+}
+
+impl BioCrypticBankCore {
+  pub(crate) fn internal_ft_credit(&mut self, account_id: &AccountId, token_id: &AccountId, amount: u128) {
+      let key = (account_id.clone(), token_id.clone());
+      let balance = self.ft_balances.get(&key).copied().unwrap_or(0)
+          .checked_add(amount)
+          .unwrap_or_else(|| env::panic_str("Balance overflow."));
+      self.ft_balances.insert(key, balance);
+      self.internal_add_token_liabilities(token_id, amount);
+  }
+
+  pub(crate) fn internal_ft_debit(&mut self, account_id: &AccountId, token_id: &AccountId, amount: u128) {
+      let key = (account_id.clone(), token_id.clone());
+      let balance = self.ft_balances.get(&key).copied().unwrap_or(0)
+          .checked_sub(amount)
+          .unwrap_or_else(|| env::panic_str("Insufficient token balance."));
+      if balance == 0 {
+          self.ft_balances.remove(&key);
+      } else {
+          self.ft_balances.insert(key, balance);
+      }
+      self.internal_remove_token_liabilities(token_id, amount);
+  }
+}
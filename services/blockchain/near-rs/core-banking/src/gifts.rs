@@ -0,0 +1,162 @@
+// services/blockchain/near-rs/core-banking/src/gifts.rs
+use near_sdk::{near, env, AccountId, Allowance, Gas, NearToken, Promise, PromiseError, PromiseOrValue, PublicKey};
+
+use crate::ledger::{EntryDirection, EntryKind};
+use crate::pause::Feature;
+use crate::{assert_valid_memo, BankEvent, BioCrypticBankCore, BioCrypticBankCoreExt};
+
+const MAX_OPEN_GIFTS_PER_ACCOUNT: usize = 50;
+/// Gas budget of a claim key, paid by the sender on top of the gift and not refunded.
+const GIFT_KEY_ALLOWANCE: NearToken = NearToken::from_millinear(10);
+const GAS_FOR_GIFT_CALLBACK: Gas = Gas::from_tgas(10);
+
+/// Funds escrowed by `sender_id` for whoever holds the secret key of the gift's claim key.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct Gift {
+  pub sender_id: AccountId,
+  pub amount: NearToken,
+  pub memo: Option<String>,
+  pub created_at: u64,
+  /// Timestamp (ns) after which the gift can no longer be claimed and refunds the sender.
+  pub expires_at: u64,
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Escrows `amount` from the caller's balance as a gift claimable until `expires_at` (ns).
+  /// `claim_public_key` is added to this contract as a function-call key limited to
+  /// `claim_gift`; sharing its secret key (e.g. in a link) lets anyone, even without a NEAR
+  /// account, claim the gift. The key's gas allowance is charged on top of the gift.
+  pub fn create_gift(&mut self, amount: NearToken, claim_public_key: PublicKey, expires_at: u64, memo: Option<String>) -> Promise {
+      self.assert_not_paused(Feature::Transfers);
+      let initial_storage = env::storage_usage();
+      let sender_id = env::predecessor_account_id();
+      assert!(amount.as_yoctonear() > 0, "Amount must be greater than 0.");
+      assert!(expires_at > env::block_timestamp(), "Expiry must be in the future.");
+      assert!(self.gifts.get(&claim_public_key).is_none(), "A gift already uses this claim key.");
+      assert!(self.debit_cards.get(&claim_public_key).is_none(), "Key is in use as a debit card.");
+      assert_valid_memo(&memo);
+      self.assert_not_dormant(&sender_id);
+      self.assert_no_recovery_hold(&sender_id);
+      self.assert_address_book_off(&sender_id);
+
+      let total = amount.saturating_add(GIFT_KEY_ALLOWANCE);
+      self.internal_record_outflow(&sender_id, total);
+      self.internal_debit(&sender_id, total);
+      self.internal_remove_liabilities(GIFT_KEY_ALLOWANCE);
+      self.internal_record_entry(&sender_id, EntryKind::Gift, EntryDirection::Debit, total, memo.clone(), None);
+
+      let keys = self.gifts_by_sender.entry(sender_id.clone()).or_default();
+      assert!(keys.len() < MAX_OPEN_GIFTS_PER_ACCOUNT, "Too many open gifts.");
+      keys.push(claim_public_key.clone());
+      self.gifts.insert(claim_public_key.clone(), Gift {
+          sender_id: sender_id.clone(),
+          amount,
+          memo,
+          created_at: env::block_timestamp(),
+          expires_at,
+      });
+      self.internal_charge_storage(&sender_id, initial_storage);
+
+      BankEvent::GiftCreated { sender_id, public_key: claim_public_key.clone(), amount, expires_at }.emit();
+      Promise::new(env::current_account_id()).add_access_key_allowance(
+          claim_public_key,
+          Allowance::limited(GIFT_KEY_ALLOWANCE).unwrap(),
+          env::current_account_id(),
+          "claim_gift".to_string(),
+      )
+  }
+
+  /// Claims the gift of the signing claim key for `receiver_id` and deletes the key. Must be
+  /// signed with the claim key. Registered accounts are credited in the bank; any other
+  /// account, including a not yet existing implicit account, is paid out in NEAR.
+  pub fn claim_gift(&mut self, receiver_id: AccountId) -> PromiseOrValue<bool> {
+      assert_eq!(
+          env::predecessor_account_id(), env::current_account_id(),
+          "Gifts must be claimed with their claim key."
+      );
+      let initial_storage = env::storage_usage();
+      let public_key = env::signer_account_pk();
+      let gift = self.internal_remove_gift(&public_key);
+      assert!(env::block_timestamp() <= gift.expires_at, "Gift has expired.");
+      self.internal_charge_storage(&gift.sender_id, initial_storage);
+      Promise::new(env::current_account_id()).delete_key(public_key.clone());
+      BankEvent::GiftClaimed {
+          public_key,
+          sender_id: gift.sender_id.clone(),
+          receiver_id: receiver_id.clone(),
+          amount: gift.amount,
+      }.emit();
+
+      if self.storage_accounts.contains_key(&receiver_id) {
+          let initial_storage = env::storage_usage();
+          self.internal_credit(&receiver_id, gift.amount);
+          self.internal_record_entry(&receiver_id, EntryKind::Gift, EntryDirection::Credit, gift.amount, gift.memo, None);
+          self.internal_charge_storage(&receiver_id, initial_storage);
+          return PromiseOrValue::Value(true);
+      }
+      assert!(self.get_reserves().liquid >= gift.amount, "Insufficient liquid reserves. Claim to a registered account.");
+      self.internal_remove_liabilities(gift.amount);
+      self.internal_record_contract_outflow(gift.amount);
+      Promise::new(receiver_id).transfer(gift.amount).then(
+          Self::ext(env::current_account_id())
+              .with_static_gas(GAS_FOR_GIFT_CALLBACK)
+              .on_gift_paid_out(gift.sender_id, gift.amount)
+      ).into()
+  }
+
+  /// Callback refunding the sender of a gift whose payout failed.
+  #[private]
+  pub fn on_gift_paid_out(&mut self, sender_id: AccountId, amount: NearToken, #[callback_result] result: Result<(), PromiseError>) -> bool {
+      if result.is_err() {
+          self.internal_add_liabilities(amount);
+          self.internal_refund_gift(&sender_id, amount);
+          env::log_str("Gift payout failed; amount refunded to the sender.");
+      }
+      result.is_ok()
+  }
+
+  /// Refunds an expired, unclaimed gift to its sender and deletes its claim key. Callable
+  /// by anyone.
+  pub fn refund_expired_gift(&mut self, claim_public_key: PublicKey) -> Promise {
+      let initial_storage = env::storage_usage();
+      let gift = self.internal_remove_gift(&claim_public_key);
+      assert!(env::block_timestamp() > gift.expires_at, "Gift has not expired.");
+      self.internal_refund_gift(&gift.sender_id, gift.amount);
+      self.internal_charge_storage(&gift.sender_id, initial_storage);
+      BankEvent::GiftRefunded { public_key: claim_public_key.clone(), sender_id: gift.sender_id, amount: gift.amount }.emit();
+      Promise::new(env::current_account_id()).delete_key(claim_public_key)
+  }
+
+  /// View function.
+  pub fn get_gift(&self, claim_public_key: PublicKey) -> Option<Gift> {
+      self.gifts.get(&claim_public_key).cloned()
+  }
+
+  /// Lists the open gifts created by `account_id` with their claim keys.
+  /// View function.
+  pub fn get_gifts_by_sender(&self, account_id: AccountId) -> Vec<(PublicKey, Gift)> {
+      self.gifts_by_sender.get(&account_id)
+          .map(|keys| keys.iter().filter_map(|key| Some((key.clone(), self.gifts.get(key)?.clone()))).collect())
+          .unwrap_or_default()
+  }
+}
+
+impl BioCrypticBankCore {
+  fn internal_remove_gift(&mut self, public_key: &PublicKey) -> Gift {
+      let gift = self.gifts.remove(public_key).unwrap_or_else(|| env::panic_str("Gift not found."));
+      if let Some(keys) = self.gifts_by_sender.get_mut(&gift.sender_id) {
+          keys.retain(|key| key != public_key);
+          if keys.is_empty() {
+              self.gifts_by_sender.remove(&gift.sender_id);
+          }
+      }
+      gift
+  }
+
+  fn internal_refund_gift(&mut self, sender_id: &AccountId, amount: NearToken) {
+      self.internal_credit(sender_id, amount);
+      self.internal_record_entry(sender_id, EntryKind::Gift, EntryDirection::Credit, amount, None, None);
+  }
+}
@@ -0,0 +1,187 @@
+// services/blockchain/near-rs/core-banking/src/goals.rs
+use near_sdk::{near, env, AccountId, NearToken};
+
+use crate::savings::BPS_DENOMINATOR;
+use crate::{mul_div, BankEvent, BioCrypticBankCore, BioCrypticBankCoreExt};
+
+const MAX_GOALS_PER_ACCOUNT: usize = 20;
+const MAX_GOAL_NAME_LENGTH: usize = 32;
+
+/// A named savings target funded from the checking balance. Soft-locked goals can be drawn
+/// down at any time; hard-locked goals charge the early-withdrawal penalty until
+/// `target_date`.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct SavingsGoal {
+  pub name: String,
+  pub target: NearToken,
+  /// Nanosecond timestamp the goal is saved towards.
+  pub target_date: u64,
+  pub balance: NearToken,
+  pub hard_lock: bool,
+  pub created_at: u64,
+  /// When the balance first reached `target`.
+  pub reached_at: Option<u64>,
+}
+
+#[near(serializers = [json])]
+#[derive(Debug, PartialEq)]
+pub struct GoalProgress {
+  pub goal: SavingsGoal,
+  /// Share of the target saved, capped at 100.
+  pub percent_complete: u8,
+  pub remaining: NearToken,
+  /// Whether withdrawing now would incur the early-withdrawal penalty.
+  pub locked: bool,
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Sets the penalty, in basis points of the amount withdrawn, charged on withdrawals from
+  /// hard-locked goals before their target date. Owner or governance only.
+  pub fn set_goal_penalty(&mut self, penalty_bps: u16) {
+      self.assert_owner_or_governance();
+      assert!(penalty_bps as u128 <= BPS_DENOMINATOR, "Penalty cannot exceed 10000 bps.");
+      self.goal_penalty_bps = penalty_bps;
+  }
+
+  /// Creates an empty savings goal `name` of `target` to reach by `target_date` (nanosecond
+  /// timestamp). `hard_lock` makes early withdrawals pay the goal penalty.
+  pub fn create_savings_goal(&mut self, name: String, target: NearToken, target_date: u64, hard_lock: Option<bool>) {
+      let initial_storage = env::storage_usage();
+      let account_id = env::predecessor_account_id();
+      self.assert_registered(&account_id);
+      assert!(!name.is_empty() && name.len() <= MAX_GOAL_NAME_LENGTH, "Goal name must be 1 to {} bytes.", MAX_GOAL_NAME_LENGTH);
+      assert!(target.as_yoctonear() > 0, "Target must be greater than 0.");
+      assert!(target_date > env::block_timestamp(), "Target date must be in the future.");
+      let goals = self.savings_goals.entry(account_id.clone()).or_default();
+      assert!(goals.len() < MAX_GOALS_PER_ACCOUNT, "Cannot exceed {} goals.", MAX_GOALS_PER_ACCOUNT);
+      assert!(goals.iter().all(|goal| goal.name != name), "Goal already exists.");
+      let hard_lock = hard_lock.unwrap_or(false);
+      goals.push(SavingsGoal {
+          name: name.clone(),
+          target,
+          target_date,
+          balance: NearToken::from_yoctonear(0),
+          hard_lock,
+          created_at: env::block_timestamp(),
+          reached_at: None,
+      });
+      self.internal_charge_storage(&account_id, initial_storage);
+      BankEvent::SavingsGoalCreated { account_id, name, target, target_date, hard_lock }.emit();
+  }
+
+  /// Moves `amount` from the caller's unallocated balance into goal `name`.
+  pub fn fund_savings_goal(&mut self, name: String, amount: NearToken) {
+      let account_id = env::predecessor_account_id();
+      assert!(amount.as_yoctonear() > 0, "Amount must be greater than 0.");
+      assert!(self.internal_unallocated_balance(&account_id) >= amount, "Insufficient unallocated balance.");
+      self.internal_get_goal_mut(&account_id, &name);
+      self.internal_debit(&account_id, amount);
+      self.internal_fund_goal(&account_id, &name, amount);
+  }
+
+  /// Returns `amount` from goal `name` to the caller's checking balance. Before the target
+  /// date a hard-locked goal pays the goal penalty on `amount`, which goes to the fee
+  /// treasury. Returns the amount credited.
+  pub fn withdraw_from_savings_goal(&mut self, name: String, amount: NearToken) -> NearToken {
+      let account_id = env::predecessor_account_id();
+      assert!(amount.as_yoctonear() > 0, "Amount must be greater than 0.");
+      let penalty_bps = self.goal_penalty_bps;
+      let goal = self.internal_get_goal_mut(&account_id, &name);
+      goal.balance = goal.balance.checked_sub(amount)
+          .unwrap_or_else(|| env::panic_str("Insufficient goal balance."));
+      let penalty = if goal.is_locked(env::block_timestamp()) {
+          NearToken::from_yoctonear(mul_div(amount.as_yoctonear(), penalty_bps as u128, BPS_DENOMINATOR))
+      } else {
+          NearToken::from_yoctonear(0)
+      };
+      let payout = amount.saturating_sub(penalty);
+      self.internal_credit(&account_id, payout);
+      self.internal_remove_liabilities(penalty);
+      self.internal_collect_fee(penalty);
+      BankEvent::SavingsGoalWithdrawn { account_id, name, amount: payout, penalty }.emit();
+      payout
+  }
+
+  /// Deletes goal `name`, returning its balance to the caller's checking balance. A
+  /// hard-locked goal can only be deleted early once it is empty.
+  pub fn delete_savings_goal(&mut self, name: String) {
+      let account_id = env::predecessor_account_id();
+      let goal = self.internal_get_goal_mut(&account_id, &name).clone();
+      assert!(
+          !goal.is_locked(env::block_timestamp()) || goal.balance.is_zero(),
+          "Goal is hard-locked until its target date; withdraw its balance first."
+      );
+      let goals = self.savings_goals.get_mut(&account_id).unwrap();
+      goals.retain(|existing| existing.name != name);
+      if goals.is_empty() {
+          self.savings_goals.remove(&account_id);
+      }
+      self.internal_credit(&account_id, goal.balance);
+      BankEvent::SavingsGoalDeleted { account_id, name, refunded: goal.balance }.emit();
+  }
+
+  /// Retrieves the goal penalty in basis points.
+  /// View function.
+  pub fn get_goal_penalty(&self) -> u16 {
+      self.goal_penalty_bps
+  }
+
+  /// View function.
+  pub fn get_savings_goal(&self, account_id: AccountId, name: String) -> Option<GoalProgress> {
+      self.savings_goals.get(&account_id)?
+          .iter()
+          .find(|goal| goal.name == name)
+          .map(|goal| goal.progress(env::block_timestamp()))
+  }
+
+  /// Lists an account's goals with their progress.
+  /// View function.
+  pub fn get_savings_goals(&self, account_id: AccountId) -> Vec<GoalProgress> {
+      let now = env::block_timestamp();
+      self.savings_goals.get(&account_id)
+          .map(|goals| goals.iter().map(|goal| goal.progress(now)).collect())
+          .unwrap_or_default()
+  }
+}
+
+impl SavingsGoal {
+  fn is_locked(&self, now: u64) -> bool {
+      self.hard_lock && now < self.target_date
+  }
+
+  fn progress(&self, now: u64) -> GoalProgress {
+      let percent = mul_div(self.balance.as_yoctonear(), 100, self.target.as_yoctonear()).min(100);
+      GoalProgress {
+          goal: self.clone(),
+          percent_complete: percent as u8,
+          remaining: self.target.saturating_sub(self.balance),
+          locked: self.is_locked(now),
+      }
+  }
+}
+
+impl BioCrypticBankCore {
+  /// Adds `amount`, already debited from the checking balance, to goal `name` and celebrates
+  /// the first time the goal reaches its target.
+  pub(crate) fn internal_fund_goal(&mut self, account_id: &AccountId, name: &str, amount: NearToken) {
+      let goal = self.internal_get_goal_mut(account_id, name);
+      goal.balance = goal.balance.saturating_add(amount);
+      let newly_reached = goal.reached_at.is_none() && goal.balance >= goal.target;
+      if newly_reached {
+          goal.reached_at = Some(env::block_timestamp());
+      }
+      let (target, balance) = (goal.target, goal.balance);
+      BankEvent::SavingsGoalFunded { account_id: account_id.clone(), name: name.to_string(), amount, balance }.emit();
+      if newly_reached {
+          BankEvent::SavingsGoalReached { account_id: account_id.clone(), name: name.to_string(), target }.emit();
+      }
+  }
+
+  pub(crate) fn internal_get_goal_mut(&mut self, account_id: &AccountId, name: &str) -> &mut SavingsGoal {
+      self.savings_goals.get_mut(account_id)
+          .and_then(|goals| goals.iter_mut().find(|goal| goal.name == name))
+          .unwrap_or_else(|| env::panic_str("Savings goal not found."))
+  }
+}
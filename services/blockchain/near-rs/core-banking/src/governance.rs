@@ -0,0 +1,33 @@
+// services/blockchain/near-rs/core-banking/src/governance.rs
+use near_sdk::{near, env, AccountId};
+
+use biocrypticbank_common::ContractError;
+
+use crate::{BankEvent, BioCrypticBankCore, BioCrypticBankCoreExt};
+
+#[near]
+impl BioCrypticBankCore {
+  /// Sets the governance contract allowed to change protocol parameters (rate model, fee
+  /// schedule, penalties, fee shares) alongside the owner, or removes it with `None`.
+  /// Owner only.
+  pub fn set_governance(&mut self, governance_id: Option<AccountId>) {
+      self.assert_owner();
+      self.governance_id = governance_id.clone();
+      BankEvent::GovernanceUpdated { governance_id }.emit();
+  }
+
+  /// View function.
+  pub fn get_governance(&self) -> Option<AccountId> {
+      self.governance_id.clone()
+  }
+}
+
+impl BioCrypticBankCore {
+  /// Guard for protocol parameter setters: the owner or the configured governance contract.
+  pub(crate) fn assert_owner_or_governance(&self) {
+      let caller = env::predecessor_account_id();
+      if caller != self.owner_id && self.governance_id.as_ref() != Some(&caller) {
+          ContractError::OwnerOrGovernanceOnly.panic();
+      }
+  }
+}
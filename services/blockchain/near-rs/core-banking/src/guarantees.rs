@@ -0,0 +1,204 @@
+// services/blockchain/near-rs/core-banking/src/guarantees.rs
+use near_sdk::{near, env, AccountId, NearToken};
+
+use crate::amortization::LoanStatus;
+use crate::ledger::{EntryDirection, EntryKind};
+use crate::savings::BPS_DENOMINATOR;
+use crate::{BankEvent, BioCrypticBankCore, BioCrypticBankCoreExt};
+
+const MAX_GUARANTEES_PER_GUARANTOR: usize = 10;
+
+#[near(serializers = [borsh, json])]
+#[serde(rename_all = "snake_case")]
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum GuaranteeStatus {
+  /// Requested by the borrower; waiting for the guarantor's consent.
+  Requested,
+  /// Co-signed by the guarantor.
+  Active,
+}
+
+/// A third party's backing of a loan: funds locked from its balance plus an unsecured
+/// promise to cover up to `cover` in total.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct Guarantee {
+  pub loan_id: u64,
+  pub guarantor_id: AccountId,
+  /// Total the guarantor answers for.
+  pub cover: NearToken,
+  /// Part of `cover` locked from the guarantor's balance.
+  pub locked: NearToken,
+  pub status: GuaranteeStatus,
+  pub requested_at: u64,
+}
+
+#[near(serializers = [json])]
+#[derive(Debug, PartialEq)]
+pub struct GuarantorExposure {
+  pub guarantees: Vec<Guarantee>,
+  pub total_cover: NearToken,
+  pub total_locked: NearToken,
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Asks `guarantor_id` to co-sign loan `loan_id` for up to `cover`, replacing any request
+  /// it has not accepted yet. Borrower only.
+  pub fn request_guarantee(&mut self, loan_id: u64, guarantor_id: AccountId, cover: NearToken) {
+      let initial_storage = env::storage_usage();
+      let borrower_id = env::predecessor_account_id();
+      let loan = self.loans.get(&loan_id).unwrap_or_else(|| env::panic_str("Loan not found."));
+      assert_eq!(loan.borrower_id, borrower_id, "Only the borrower can request a guarantee.");
+      assert!(guarantor_id != borrower_id, "Borrowers cannot guarantee their own loans.");
+      assert!(!cover.is_zero(), "Cover must be greater than 0.");
+      self.assert_registered(&guarantor_id);
+      assert!(
+          self.loan_guarantees.get(&loan_id).is_none_or(|guarantee| guarantee.status == GuaranteeStatus::Requested),
+          "Loan is already guaranteed."
+      );
+      self.loan_guarantees.insert(loan_id, Guarantee {
+          loan_id,
+          guarantor_id: guarantor_id.clone(),
+          cover,
+          locked: NearToken::from_yoctonear(0),
+          status: GuaranteeStatus::Requested,
+          requested_at: env::block_timestamp(),
+      });
+      self.internal_charge_storage(&borrower_id, initial_storage);
+      BankEvent::GuaranteeRequested { loan_id, guarantor_id, cover }.emit();
+  }
+
+  /// Co-signs loan `loan_id`, locking `lock` (at most the requested cover) from the caller's
+  /// unallocated balance; the rest of the cover is accepted as unsecured liability.
+  pub fn accept_guarantee(&mut self, loan_id: u64, lock: NearToken) {
+      let initial_storage = env::storage_usage();
+      let guarantor_id = env::predecessor_account_id();
+      let guarantee = self.loan_guarantees.get(&loan_id)
+          .filter(|guarantee| guarantee.guarantor_id == guarantor_id && guarantee.status == GuaranteeStatus::Requested)
+          .cloned()
+          .unwrap_or_else(|| env::panic_str("No guarantee request for this loan."));
+      assert!(lock <= guarantee.cover, "Cannot lock more than the requested cover.");
+      let loan_ids = self.guarantees_by_guarantor.entry(guarantor_id.clone()).or_default();
+      assert!(loan_ids.len() < MAX_GUARANTEES_PER_GUARANTOR, "Cannot guarantee more than {} loans.", MAX_GUARANTEES_PER_GUARANTOR);
+      loan_ids.push(loan_id);
+      if !lock.is_zero() {
+          assert!(self.internal_unallocated_balance(&guarantor_id) >= lock, "Insufficient unallocated balance.");
+          self.internal_debit(&guarantor_id, lock);
+          self.internal_record_entry(
+              &guarantor_id, EntryKind::Guarantee, EntryDirection::Debit, lock, None, Some(format!("loan:{}", loan_id)),
+          );
+      }
+      self.loan_guarantees.insert(loan_id, Guarantee { locked: lock, status: GuaranteeStatus::Active, ..guarantee });
+      self.internal_charge_storage(&guarantor_id, initial_storage);
+      BankEvent::GuaranteeAccepted { loan_id, guarantor_id, locked: lock }.emit();
+  }
+
+  /// Declines a guarantee request (guarantor) or withdraws it (borrower). Active guarantees
+  /// are released only when the loan is repaid.
+  pub fn cancel_guarantee_request(&mut self, loan_id: u64) {
+      let caller_id = env::predecessor_account_id();
+      let guarantee = self.loan_guarantees.get(&loan_id)
+          .filter(|guarantee| guarantee.status == GuaranteeStatus::Requested)
+          .cloned()
+          .unwrap_or_else(|| env::panic_str("No guarantee request for this loan."));
+      let is_borrower = self.loans.get(&loan_id).is_some_and(|loan| loan.borrower_id == caller_id);
+      assert!(caller_id == guarantee.guarantor_id || is_borrower, "Only the borrower or guarantor can cancel this request.");
+      self.loan_guarantees.remove(&loan_id);
+      BankEvent::GuaranteeReleased { loan_id, guarantor_id: guarantee.guarantor_id, released: NearToken::from_yoctonear(0) }.emit();
+  }
+
+  /// Draws loan `loan_id`'s guarantee once the loan is unhealthy or in default: the locked
+  /// funds first, then the guarantor's unallocated balance up to the rest of the cover.
+  /// Must run before the loan can be liquidated. Callable by anyone. Returns the amount
+  /// applied to the debt.
+  pub fn claim_guarantee(&mut self, loan_id: u64) -> NearToken {
+      let now = env::block_timestamp();
+      let apr_bps = self.internal_borrow_apr_bps();
+      let config = self.lending_config.clone();
+      let loan = self.internal_get_loan_mut(loan_id);
+      loan.accrue(apr_bps, now);
+      loan.accrue_late_fees(&config, now);
+      let loan = loan.clone();
+      let unhealthy = self.internal_health_factor_bps(&loan).is_some_and(|health| (health as u128) < BPS_DENOMINATOR);
+      assert!(unhealthy || loan.status(&config, now) == LoanStatus::Defaulted, "Loan is neither unhealthy nor in default.");
+      let guarantee = self.loan_guarantees.get(&loan_id)
+          .filter(|guarantee| guarantee.status == GuaranteeStatus::Active)
+          .cloned()
+          .unwrap_or_else(|| env::panic_str("Loan has no active guarantee."));
+      self.internal_remove_guarantee(&guarantee);
+
+      let reference = Some(format!("loan:{}", loan_id));
+      let debt = loan.debt();
+      let from_locked = guarantee.locked.min(debt);
+      let unsecured = guarantee.cover.saturating_sub(guarantee.locked).min(debt.saturating_sub(from_locked));
+      let from_balance = unsecured.min(self.internal_unallocated_balance(&guarantee.guarantor_id));
+      if !from_balance.is_zero() {
+          self.internal_debit(&guarantee.guarantor_id, from_balance);
+          self.internal_record_entry(&guarantee.guarantor_id, EntryKind::Guarantee, EntryDirection::Debit, from_balance, None, reference.clone());
+      }
+      let leftover = guarantee.locked.saturating_sub(from_locked);
+      if !leftover.is_zero() {
+          self.internal_credit(&guarantee.guarantor_id, leftover);
+          self.internal_record_entry(&guarantee.guarantor_id, EntryKind::Guarantee, EntryDirection::Credit, leftover, None, reference);
+      }
+      let drawn = from_locked.saturating_add(from_balance);
+      if !drawn.is_zero() {
+          self.internal_apply_loan_repayment(loan_id, drawn);
+      }
+      self.internal_update_credit(&loan.borrower_id, |record| record.late_repayments += 1);
+      BankEvent::GuaranteeClaimed { loan_id, guarantor_id: guarantee.guarantor_id, drawn }.emit();
+      drawn
+  }
+
+  /// View function.
+  pub fn get_loan_guarantee(&self, loan_id: u64) -> Option<Guarantee> {
+      self.loan_guarantees.get(&loan_id).cloned()
+  }
+
+  /// Loans `account_id` has co-signed and what it stands to lose on them.
+  /// View function.
+  pub fn get_guarantor_exposure(&self, account_id: AccountId) -> GuarantorExposure {
+      let guarantees: Vec<Guarantee> = self.guarantees_by_guarantor.get(&account_id)
+          .map(|ids| ids.iter().filter_map(|id| self.loan_guarantees.get(id).cloned()).collect())
+          .unwrap_or_default();
+      GuarantorExposure {
+          total_cover: guarantees.iter().fold(NearToken::from_yoctonear(0), |total, guarantee| total.saturating_add(guarantee.cover)),
+          total_locked: guarantees.iter().fold(NearToken::from_yoctonear(0), |total, guarantee| total.saturating_add(guarantee.locked)),
+          guarantees,
+      }
+  }
+}
+
+impl BioCrypticBankCore {
+  /// Panics if loan `loan_id` has an active guarantee that must be claimed first.
+  pub(crate) fn assert_guarantee_claimed(&self, loan_id: u64) {
+      assert!(
+          self.loan_guarantees.get(&loan_id).is_none_or(|guarantee| guarantee.status != GuaranteeStatus::Active),
+          "Claim the loan's guarantee before liquidating it."
+      );
+  }
+
+  /// Returns the locked funds of loan `loan_id`'s guarantee, if any, to the guarantor.
+  pub(crate) fn internal_release_guarantee(&mut self, loan_id: u64) {
+      let Some(guarantee) = self.loan_guarantees.get(&loan_id).cloned() else { return };
+      self.internal_remove_guarantee(&guarantee);
+      if guarantee.status == GuaranteeStatus::Active && !guarantee.locked.is_zero() {
+          self.internal_credit(&guarantee.guarantor_id, guarantee.locked);
+          self.internal_record_entry(
+              &guarantee.guarantor_id, EntryKind::Guarantee, EntryDirection::Credit, guarantee.locked, None, Some(format!("loan:{}", loan_id)),
+          );
+      }
+      BankEvent::GuaranteeReleased { loan_id, guarantor_id: guarantee.guarantor_id, released: guarantee.locked }.emit();
+  }
+
+  fn internal_remove_guarantee(&mut self, guarantee: &Guarantee) {
+      self.loan_guarantees.remove(&guarantee.loan_id);
+      if let Some(ids) = self.guarantees_by_guarantor.get_mut(&guarantee.guarantor_id) {
+          ids.retain(|existing| *existing != guarantee.loan_id);
+          if ids.is_empty() {
+              self.guarantees_by_guarantor.remove(&guarantee.guarantor_id);
+          }
+      }
+  }
+}
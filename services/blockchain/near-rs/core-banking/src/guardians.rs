@@ -0,0 +1,237 @@
+// services/blockchain/near-rs/core-banking/src/guardians.rs
+use near_sdk::{near, env, AccountId, Gas, NearToken, Promise, PromiseError, PromiseOrValue};
+use biocrypticbank_common::interfaces::ext_account_recovery;
+
+use crate::kyc::KycPendingAction;
+use crate::{BankEvent, BioCrypticBankCore, BioCrypticBankCoreExt};
+
+const NANOS_PER_SECOND: u64 = 1_000_000_000;
+/// How long a held operation waits for a guardian before it can no longer be approved.
+const GUARDIAN_APPROVAL_TTL_SEC: u64 = 24 * 60 * 60;
+/// Delay before raising or removing the guardian threshold takes effect.
+const THRESHOLD_LOOSENING_DELAY_SEC: u64 = 24 * 60 * 60;
+const MAX_GUARDED_ACTIONS_PER_ACCOUNT: usize = 10;
+const GAS_FOR_GET_GUARDIANS: Gas = Gas::from_tgas(10);
+/// Covers running the approved operation, including a follow-up KYC check.
+const GAS_FOR_GUARDIAN_CALLBACK: Gas = Gas::from_tgas(70);
+
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct PendingGuardianThreshold {
+  pub threshold: Option<NearToken>,
+  pub effective_at: u64,
+}
+
+/// Withdrawals and transfers above `threshold` need a guardian's co-approval.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct GuardianPolicy {
+  pub threshold: Option<NearToken>,
+  pub pending_change: Option<PendingGuardianThreshold>,
+}
+
+impl GuardianPolicy {
+  fn apply_pending(&mut self, now: u64) {
+      if self.pending_change.as_ref().is_some_and(|change| now >= change.effective_at) {
+          self.threshold = self.pending_change.take().unwrap().threshold;
+      }
+  }
+}
+
+/// A withdrawal or transfer held until one of the account's guardians approves it.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct GuardedAction {
+  pub id: u64,
+  pub account_id: AccountId,
+  pub amount: NearToken,
+  pub action: KycPendingAction,
+  pub created_at: u64,
+  pub expires_at: u64,
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Sets the amount above which the caller's withdrawals and transfers need a guardian's
+  /// co-approval (`None` = never). Lowering or enabling the threshold applies immediately;
+  /// raising or removing it is queued for 24 hours so a stolen key cannot switch it off.
+  pub fn set_guardian_threshold(&mut self, threshold: Option<NearToken>) {
+      let initial_storage = env::storage_usage();
+      let account_id = env::predecessor_account_id();
+      self.assert_registered(&account_id);
+      if threshold.is_some() {
+          assert!(self.recovery_contract_id.is_some(), "No account recovery contract is configured.");
+      }
+      let now = env::block_timestamp();
+      let policy = self.guardian_policies.entry(account_id.clone())
+          .or_insert(GuardianPolicy { threshold: None, pending_change: None });
+      policy.apply_pending(now);
+      let loosens = match (policy.threshold, threshold) {
+          (Some(current), Some(proposed)) => proposed > current,
+          (Some(_), None) => true,
+          (None, _) => false,
+      };
+      if loosens {
+          let effective_at = now + THRESHOLD_LOOSENING_DELAY_SEC * NANOS_PER_SECOND;
+          policy.pending_change = Some(PendingGuardianThreshold { threshold, effective_at });
+      } else {
+          policy.threshold = threshold;
+          policy.pending_change = None;
+      }
+      let effective_at = policy.pending_change.as_ref().map_or(now, |change| change.effective_at);
+      self.internal_charge_storage(&account_id, initial_storage);
+      BankEvent::GuardianThresholdSet { account_id, threshold, effective_at }.emit();
+  }
+
+  /// Co-approves held operation `action_id` as one of its account's guardians. Guardian
+  /// membership is read from the account recovery contract before the operation runs.
+  pub fn approve_guarded_action(&mut self, action_id: u64) -> Promise {
+      let guardian_id = env::predecessor_account_id();
+      let pending = self.guarded_actions.get(&action_id).unwrap_or_else(|| env::panic_str("Held operation not found."));
+      assert!(env::block_timestamp() <= pending.expires_at, "Held operation has expired.");
+      assert!(guardian_id != pending.account_id, "Account holders cannot approve their own operations.");
+      let recovery_contract_id = self.recovery_contract_id.clone()
+          .unwrap_or_else(|| env::panic_str("No account recovery contract is configured."));
+      ext_account_recovery::ext(recovery_contract_id)
+          .with_static_gas(GAS_FOR_GET_GUARDIANS)
+          .get_guardians(pending.account_id.clone())
+          .then(
+              Self::ext(env::current_account_id())
+                  .with_static_gas(GAS_FOR_GUARDIAN_CALLBACK)
+                  .on_guardians_fetched(action_id, guardian_id)
+          )
+  }
+
+  /// Callback running a held operation once `guardian_id` is confirmed as a guardian.
+  #[private]
+  pub fn on_guardians_fetched(
+      &mut self,
+      action_id: u64,
+      guardian_id: AccountId,
+      #[callback_result] result: Result<Option<Vec<AccountId>>, PromiseError>,
+  ) -> PromiseOrValue<bool> {
+      let Ok(guardians) = result else {
+          env::log_str("Guardian lookup failed.");
+          return PromiseOrValue::Value(false);
+      };
+      let Some(pending) = self.guarded_actions.get(&action_id).cloned() else {
+          env::log_str("Held operation no longer exists.");
+          return PromiseOrValue::Value(false);
+      };
+      if !guardians.unwrap_or_default().contains(&guardian_id) {
+          env::log_str("Approver is not a guardian of this account.");
+          return PromiseOrValue::Value(false);
+      }
+      if env::block_timestamp() > pending.expires_at {
+          env::log_str("Held operation has expired.");
+          return PromiseOrValue::Value(false);
+      }
+      if self.is_on_recovery_hold(&pending.account_id) {
+          env::log_str("Account is on hold while its recovery is pending.");
+          return PromiseOrValue::Value(false);
+      }
+      let initial_storage = env::storage_usage();
+      self.internal_remove_guarded_action(action_id);
+      self.internal_charge_storage(&pending.account_id, initial_storage);
+      BankEvent::GuardedActionApproved { id: action_id, account_id: pending.account_id.clone(), guardian_id }.emit();
+      self.internal_run_guarded_action(pending.account_id, pending.amount, pending.action)
+  }
+
+  /// Drops held operation `action_id`. The account holder can cancel at any time, anyone
+  /// else once it has expired.
+  pub fn cancel_guarded_action(&mut self, action_id: u64) {
+      let pending = self.guarded_actions.get(&action_id).unwrap_or_else(|| env::panic_str("Held operation not found.")).clone();
+      assert!(
+          env::predecessor_account_id() == pending.account_id || env::block_timestamp() > pending.expires_at,
+          "Only the account holder can cancel an unexpired operation."
+      );
+      let initial_storage = env::storage_usage();
+      self.internal_remove_guarded_action(action_id);
+      self.internal_charge_storage(&pending.account_id, initial_storage);
+      BankEvent::GuardedActionCancelled { id: action_id, account_id: pending.account_id }.emit();
+  }
+
+  /// Sets the account recovery contract guardians are read from. Owner only.
+  pub fn set_recovery_contract(&mut self, recovery_contract_id: Option<AccountId>) {
+      self.assert_owner();
+      self.recovery_contract_id = recovery_contract_id;
+  }
+
+  /// View function.
+  pub fn get_recovery_contract(&self) -> Option<AccountId> {
+      self.recovery_contract_id.clone()
+  }
+
+  /// View function.
+  pub fn get_guardian_policy(&self, account_id: AccountId) -> Option<GuardianPolicy> {
+      let mut policy = self.guardian_policies.get(&account_id)?.clone();
+      policy.apply_pending(env::block_timestamp());
+      Some(policy)
+  }
+
+  /// Lists `account_id`'s operations awaiting guardian approval.
+  /// View function.
+  pub fn get_guarded_actions(&self, account_id: AccountId) -> Vec<GuardedAction> {
+      self.guarded_actions_by_account.get(&account_id)
+          .map(|ids| ids.iter().filter_map(|id| self.guarded_actions.get(id).cloned()).collect())
+          .unwrap_or_default()
+  }
+}
+
+impl BioCrypticBankCore {
+  /// True if moving `amount` out of `account_id` needs a guardian's co-approval.
+  pub(crate) fn internal_guardian_approval_needed(&self, account_id: &AccountId, amount: NearToken) -> bool {
+      self.recovery_contract_id.is_some()
+          && self.get_guardian_policy(account_id.clone())
+              .and_then(|policy| policy.threshold)
+              .is_some_and(|threshold| amount > threshold)
+  }
+
+  /// Holds `action` until a guardian approves it. Returns the held operation's id.
+  pub(crate) fn internal_hold_for_guardian(&mut self, account_id: AccountId, amount: NearToken, action: KycPendingAction) -> u64 {
+      let initial_storage = env::storage_usage();
+      let ids = self.guarded_actions_by_account.entry(account_id.clone()).or_default();
+      assert!(ids.len() < MAX_GUARDED_ACTIONS_PER_ACCOUNT, "Too many operations awaiting guardian approval.");
+      let id = self.next_guarded_action_id;
+      self.next_guarded_action_id += 1;
+      ids.push(id);
+      let now = env::block_timestamp();
+      let expires_at = now + GUARDIAN_APPROVAL_TTL_SEC * NANOS_PER_SECOND;
+      self.guarded_actions.insert(id, GuardedAction { id, account_id: account_id.clone(), amount, action, created_at: now, expires_at });
+      self.internal_charge_storage(&account_id, initial_storage);
+      BankEvent::GuardianApprovalRequested { id, account_id, amount, expires_at }.emit();
+      id
+  }
+
+  /// Runs an approved withdrawal or transfer. Large withdrawals are queued behind the
+  /// withdrawal timelock; otherwise the KYC tier is re-checked first if needed.
+  fn internal_run_guarded_action(&mut self, account_id: AccountId, amount: NearToken, action: KycPendingAction) -> PromiseOrValue<bool> {
+      if self.internal_kyc_check_needed(&account_id, amount) {
+          return self.internal_resolve_kyc(account_id, action).into();
+      }
+      match action {
+          KycPendingAction::Withdraw { amount, envelope, memo, reference } => {
+              if self.internal_withdrawal_timelock_needed(amount) {
+                  self.internal_timelock_withdrawal(account_id, amount, envelope, memo, reference);
+                  return PromiseOrValue::Value(false);
+              }
+              self.internal_withdraw(&account_id, amount, envelope, memo, reference)
+          }
+          KycPendingAction::Transfer { receiver_id, amount, memo, envelope, reference } => {
+              self.internal_transfer(&account_id, &receiver_id, amount, memo, envelope, reference);
+              PromiseOrValue::Value(true)
+          }
+          _ => env::panic_str("Only withdrawals and transfers are held for guardians."),
+      }
+  }
+
+  fn internal_remove_guarded_action(&mut self, action_id: u64) {
+      let Some(pending) = self.guarded_actions.remove(&action_id) else { return };
+      if let Some(ids) = self.guarded_actions_by_account.get_mut(&pending.account_id) {
+          ids.retain(|existing| *existing != action_id);
+          if ids.is_empty() {
+              self.guarded_actions_by_account.remove(&pending.account_id);
+          }
+      }
+  }
+}
@@ -0,0 +1,189 @@
+// services/blockchain/near-rs/core-banking/src/inheritance.rs
+use near_sdk::{near, env, AccountId, NearToken};
+
+use crate::ledger::EntryKind;
+use crate::savings::BPS_DENOMINATOR;
+use crate::term_deposits::NANOS_PER_DAY;
+use crate::{mul_div, BankEvent, BioCrypticBankCore, BioCrypticBankCoreExt};
+
+const MAX_BENEFICIARIES: usize = 10;
+const MIN_INACTIVITY_DAYS: u32 = 90;
+/// Time the account holder has to show signs of life after a claim is opened.
+const CHALLENGE_PERIOD_DAYS: u64 = 30;
+
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct Beneficiary {
+  pub account_id: AccountId,
+  /// Portion of the balance, taken when payouts start, this beneficiary receives.
+  pub share_bps: u16,
+  pub paid: bool,
+}
+
+/// An open claim on an inactive account. Any activity by the holder before
+/// `challenge_ends_at` cancels it.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct InheritanceClaim {
+  pub initiated_by: AccountId,
+  pub initiated_at: u64,
+  pub challenge_ends_at: u64,
+  /// Balance the shares are computed on, fixed by the first payout.
+  pub settled_balance: Option<NearToken>,
+}
+
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct InheritancePlan {
+  pub beneficiaries: Vec<Beneficiary>,
+  pub inactivity_days: u32,
+  pub claim: Option<InheritanceClaim>,
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Designates who receives which share of the caller's balance once the account has been
+  /// inactive for `inactivity_days`. Shares may total less than 100%; the remainder stays
+  /// in the account. Replaces any existing plan.
+  pub fn set_inheritance_plan(&mut self, beneficiaries: Vec<(AccountId, u16)>, inactivity_days: u32) {
+      let initial_storage = env::storage_usage();
+      let account_id = env::predecessor_account_id();
+      self.assert_registered(&account_id);
+      assert!(!beneficiaries.is_empty() && beneficiaries.len() <= MAX_BENEFICIARIES, "Plan must name 1 to {} beneficiaries.", MAX_BENEFICIARIES);
+      assert!(inactivity_days >= MIN_INACTIVITY_DAYS, "Inactivity threshold must be at least {} days.", MIN_INACTIVITY_DAYS);
+      let mut total_bps = 0u128;
+      for (index, (beneficiary_id, share_bps)) in beneficiaries.iter().enumerate() {
+          assert!(*beneficiary_id != account_id, "Cannot name yourself as a beneficiary.");
+          assert!(*share_bps > 0, "Shares must be greater than 0.");
+          assert!(beneficiaries[..index].iter().all(|(other, _)| other != beneficiary_id), "Duplicate beneficiary.");
+          self.assert_registered(beneficiary_id);
+          total_bps += *share_bps as u128;
+      }
+      assert!(total_bps <= BPS_DENOMINATOR, "Shares cannot exceed 100%.");
+
+      let beneficiaries: Vec<Beneficiary> = beneficiaries.into_iter()
+          .map(|(account_id, share_bps)| Beneficiary { account_id, share_bps, paid: false })
+          .collect();
+      self.internal_touch_activity(&account_id);
+      self.inheritance_plans.insert(account_id.clone(), InheritancePlan {
+          beneficiaries: beneficiaries.clone(),
+          inactivity_days,
+          claim: None,
+      });
+      self.internal_charge_storage(&account_id, initial_storage);
+      BankEvent::InheritancePlanSet { account_id, beneficiaries, inactivity_days }.emit();
+  }
+
+  /// Removes the caller's inheritance plan.
+  pub fn remove_inheritance_plan(&mut self) {
+      let initial_storage = env::storage_usage();
+      let account_id = env::predecessor_account_id();
+      assert!(self.inheritance_plans.remove(&account_id).is_some(), "No inheritance plan.");
+      self.internal_touch_activity(&account_id);
+      self.internal_charge_storage(&account_id, initial_storage);
+      BankEvent::InheritancePlanRemoved { account_id }.emit();
+  }
+
+  /// Proves the caller is still around: resets its inactivity clock, reactivates a dormant
+  /// account and cancels any open inheritance claim.
+  pub fn heartbeat(&mut self) {
+      let initial_storage = env::storage_usage();
+      let account_id = env::predecessor_account_id();
+      self.assert_registered(&account_id);
+      self.internal_touch_activity(&account_id);
+      self.internal_charge_storage(&account_id, initial_storage);
+  }
+
+  /// Opens a claim on `account_id` once it has been inactive past its plan's threshold.
+  /// Beneficiaries only. Payouts become claimable after the challenge period.
+  pub fn initiate_inheritance_claim(&mut self, account_id: AccountId) {
+      let beneficiary_id = env::predecessor_account_id();
+      let now = env::block_timestamp();
+      let inactive_since = self.get_last_activity(account_id.clone());
+      let plan = self.inheritance_plans.get_mut(&account_id)
+          .unwrap_or_else(|| env::panic_str("No inheritance plan."));
+      assert!(plan.beneficiaries.iter().any(|b| b.account_id == beneficiary_id), "Only a beneficiary can claim.");
+      assert!(plan.claim.is_none(), "A claim is already open.");
+      assert!(
+          now.saturating_sub(inactive_since) >= plan.inactivity_days as u64 * NANOS_PER_DAY,
+          "Account has not been inactive long enough."
+      );
+      let challenge_ends_at = now + CHALLENGE_PERIOD_DAYS * NANOS_PER_DAY;
+      plan.claim = Some(InheritanceClaim {
+          initiated_by: beneficiary_id.clone(),
+          initiated_at: now,
+          challenge_ends_at,
+          settled_balance: None,
+      });
+      BankEvent::InheritanceClaimInitiated { account_id, initiated_by: beneficiary_id, challenge_ends_at }.emit();
+  }
+
+  /// Pays the caller its share of `account_id`'s balance once the challenge period of an
+  /// open claim has passed unchallenged. The first payout fixes the balance shares are
+  /// computed on and dissolves the account's envelopes. Returns the amount paid.
+  pub fn claim_inheritance(&mut self, account_id: AccountId) -> NearToken {
+      let initial_storage = env::storage_usage();
+      let beneficiary_id = env::predecessor_account_id();
+      let balance = self.get_balance(account_id.clone());
+      let plan = self.inheritance_plans.get_mut(&account_id)
+          .unwrap_or_else(|| env::panic_str("No inheritance plan."));
+      let claim = plan.claim.as_mut().unwrap_or_else(|| env::panic_str("No claim is open."));
+      assert!(env::block_timestamp() >= claim.challenge_ends_at, "Challenge period has not ended.");
+      let first_payout = claim.settled_balance.is_none();
+      let settled_balance = *claim.settled_balance.get_or_insert(balance);
+      let beneficiary = plan.beneficiaries.iter_mut()
+          .find(|b| b.account_id == beneficiary_id)
+          .unwrap_or_else(|| env::panic_str("Only a beneficiary can claim."));
+      assert!(!beneficiary.paid, "Share already paid.");
+      beneficiary.paid = true;
+      let share = NearToken::from_yoctonear(mul_div(settled_balance.as_yoctonear(), beneficiary.share_bps as u128, BPS_DENOMINATOR));
+      let amount = share.min(balance);
+      if plan.beneficiaries.iter().all(|b| b.paid) {
+          self.inheritance_plans.remove(&account_id);
+          BankEvent::InheritancePlanRemoved { account_id: account_id.clone() }.emit();
+      }
+      if first_payout {
+          self.envelopes.remove(&account_id);
+      }
+
+      if !amount.is_zero() {
+          self.internal_debit(&account_id, amount);
+          self.internal_credit(&beneficiary_id, amount);
+          self.internal_record_movement(&account_id, &beneficiary_id, EntryKind::Inheritance, amount, None, None);
+      }
+      self.internal_charge_storage(&beneficiary_id, initial_storage);
+      BankEvent::InheritancePaid { account_id, beneficiary_id, amount }.emit();
+      amount
+  }
+
+  /// View function.
+  pub fn get_inheritance_plan(&self, account_id: AccountId) -> Option<InheritancePlan> {
+      self.inheritance_plans.get(&account_id).cloned()
+  }
+
+  /// Time of `account_id`'s last own activity: a heartbeat or any ledger entry it initiated.
+  /// Falls back to its latest ledger entry for accounts not seen since tracking began.
+  /// View function.
+  pub fn get_last_activity(&self, account_id: AccountId) -> u64 {
+      if let Some(timestamp) = self.last_activity.get(&account_id) {
+          return *timestamp;
+      }
+      self.internal_ledger_len(&account_id).checked_sub(1)
+          .and_then(|position| self.internal_ledger_entry_at(&account_id, position))
+          .map_or(0, |entry| entry.timestamp)
+  }
+}
+
+impl BioCrypticBankCore {
+  /// Records activity by `account_id` now, reactivating it if it went dormant and cancelling
+  /// any open inheritance claim on it.
+  pub(crate) fn internal_touch_activity(&mut self, account_id: &AccountId) {
+      self.last_activity.insert(account_id.clone(), env::block_timestamp());
+      self.internal_reactivate(account_id);
+      let Some(plan) = self.inheritance_plans.get_mut(account_id) else { return };
+      if plan.claim.as_ref().is_some_and(|claim| claim.settled_balance.is_none()) {
+          plan.claim = None;
+          BankEvent::InheritanceClaimCancelled { account_id: account_id.clone() }.emit();
+      }
+  }
+}
@@ -0,0 +1,191 @@
+// services/blockchain/near-rs/core-banking/src/insurance.rs
+use near_sdk::{near, env, AccountId, NearToken};
+
+use crate::ledger::{EntryDirection, EntryKind};
+use crate::roles::Role;
+use crate::savings::BPS_DENOMINATOR;
+use crate::{mul_div, BankEvent, BioCrypticBankCore, BioCrypticBankCoreExt, DEFAULT_PAGE_LIMIT};
+
+const MAX_OPEN_CLAIMS_PER_ACCOUNT: usize = 5;
+const MAX_EVIDENCE_LENGTH: usize = 512;
+
+/// Losses the insurance fund covers.
+#[near(serializers = [borsh, json])]
+#[serde(rename_all = "snake_case")]
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum CoveredEvent {
+  /// A withdrawal lost to a contract bug rather than restored by the failure callback.
+  FailedWithdrawal,
+  /// Funds stolen from an account, verified after it was recovered by its guardians.
+  Theft,
+}
+
+#[near(serializers = [borsh, json])]
+#[serde(rename_all = "snake_case")]
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ClaimStatus {
+  Submitted,
+  /// Approved by an assessor; waiting for payout.
+  Approved,
+  Rejected,
+  Paid,
+}
+
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct InsuranceClaim {
+  pub id: u64,
+  pub claimant_id: AccountId,
+  pub event: CoveredEvent,
+  pub amount_claimed: NearToken,
+  /// Transaction hashes, recovery references or other proof supporting the claim.
+  pub evidence: String,
+  pub status: ClaimStatus,
+  pub submitted_at: u64,
+  pub assessor_id: Option<AccountId>,
+  /// Amount approved by the assessor; at most `amount_claimed`.
+  pub amount_approved: Option<NearToken>,
+  pub note: Option<String>,
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Sets the share of every fee, in basis points, routed into the insurance fund instead of
+  /// the treasury. Owner or governance only.
+  pub fn set_insurance_fee_share(&mut self, share_bps: u16) {
+      self.assert_owner_or_governance();
+      assert!(share_bps as u128 <= BPS_DENOMINATOR, "Share cannot exceed 10000 bps.");
+      self.insurance_fee_share_bps = share_bps;
+  }
+
+  /// Adds the attached deposit to the insurance fund as a voluntary contribution.
+  #[payable]
+  pub fn contribute_to_insurance(&mut self) {
+      let amount = env::attached_deposit();
+      assert!(!amount.is_zero(), "Attach a contribution.");
+      self.insurance_fund = self.insurance_fund.saturating_add(amount);
+      BankEvent::InsuranceContribution { contributor_id: env::predecessor_account_id(), amount }.emit();
+  }
+
+  /// Files a claim for `amount` lost to a covered `event`, with `evidence` for the assessor.
+  /// Returns the claim id.
+  pub fn submit_insurance_claim(&mut self, event: CoveredEvent, amount: NearToken, evidence: String) -> u64 {
+      let initial_storage = env::storage_usage();
+      let claimant_id = env::predecessor_account_id();
+      self.assert_registered(&claimant_id);
+      assert!(!amount.is_zero(), "Claim amount must be greater than 0.");
+      assert!(!evidence.is_empty() && evidence.len() <= MAX_EVIDENCE_LENGTH, "Evidence must be 1 to {} bytes.", MAX_EVIDENCE_LENGTH);
+      let claim_ids = self.open_insurance_claims.entry(claimant_id.clone()).or_default();
+      assert!(claim_ids.len() < MAX_OPEN_CLAIMS_PER_ACCOUNT, "Cannot have more than {} open claims.", MAX_OPEN_CLAIMS_PER_ACCOUNT);
+      let id = self.next_insurance_claim_id;
+      self.next_insurance_claim_id += 1;
+      claim_ids.push(id);
+      self.insurance_claims.insert(id, InsuranceClaim {
+          id,
+          claimant_id: claimant_id.clone(),
+          event,
+          amount_claimed: amount,
+          evidence,
+          status: ClaimStatus::Submitted,
+          submitted_at: env::block_timestamp(),
+          assessor_id: None,
+          amount_approved: None,
+          note: None,
+      });
+      self.internal_charge_storage(&claimant_id, initial_storage);
+      BankEvent::InsuranceClaimSubmitted { id, claimant_id, event, amount }.emit();
+      id
+  }
+
+  /// Approves claim `claim_id` for `amount_approved` (default: the full claim), or rejects it
+  /// if `approve` is false. InsuranceAssessor only.
+  pub fn review_insurance_claim(&mut self, claim_id: u64, approve: bool, amount_approved: Option<NearToken>, note: Option<String>) {
+      self.assert_role(Role::InsuranceAssessor);
+      let assessor_id = env::predecessor_account_id();
+      let claim = self.insurance_claims.get_mut(&claim_id)
+          .filter(|claim| claim.status == ClaimStatus::Submitted)
+          .unwrap_or_else(|| env::panic_str("No submitted claim with this id."));
+      assert!(claim.claimant_id != assessor_id, "Assessors cannot review their own claims.");
+      assert!(note.as_ref().is_none_or(|note| note.len() <= MAX_EVIDENCE_LENGTH), "Note is too long.");
+      let amount = if approve {
+          let amount = amount_approved.unwrap_or(claim.amount_claimed);
+          assert!(!amount.is_zero() && amount <= claim.amount_claimed, "Approved amount must be between 1 yoctoNEAR and the claimed amount.");
+          claim.status = ClaimStatus::Approved;
+          claim.amount_approved = Some(amount);
+          amount
+      } else {
+          claim.status = ClaimStatus::Rejected;
+          NearToken::from_yoctonear(0)
+      };
+      claim.assessor_id = Some(assessor_id.clone());
+      claim.note = note;
+      let claimant_id = claim.claimant_id.clone();
+      if !approve {
+          self.internal_close_insurance_claim(&claimant_id, claim_id);
+      }
+      BankEvent::InsuranceClaimReviewed { id: claim_id, assessor_id, approved: approve, amount }.emit();
+  }
+
+  /// Pays approved claim `claim_id` from the insurance fund into the claimant's balance.
+  /// Callable by anyone once the fund can cover it.
+  pub fn execute_insurance_payout(&mut self, claim_id: u64) -> NearToken {
+      let claim = self.insurance_claims.get_mut(&claim_id)
+          .filter(|claim| claim.status == ClaimStatus::Approved)
+          .unwrap_or_else(|| env::panic_str("No approved claim with this id."));
+      let amount = claim.amount_approved.unwrap();
+      assert!(self.insurance_fund >= amount, "Insurance fund cannot cover this claim.");
+      claim.status = ClaimStatus::Paid;
+      let claimant_id = claim.claimant_id.clone();
+      self.insurance_fund = self.insurance_fund.saturating_sub(amount);
+      self.internal_close_insurance_claim(&claimant_id, claim_id);
+      self.internal_credit(&claimant_id, amount);
+      self.internal_add_liabilities(amount);
+      self.internal_record_entry(&claimant_id, EntryKind::InsurancePayout, EntryDirection::Credit, amount, None, Some(format!("claim:{}", claim_id)));
+      BankEvent::InsuranceClaimPaid { id: claim_id, claimant_id, amount }.emit();
+      amount
+  }
+
+  /// View function.
+  pub fn get_insurance_fund(&self) -> NearToken {
+      self.insurance_fund
+  }
+
+  /// View function.
+  pub fn get_insurance_fee_share(&self) -> u16 {
+      self.insurance_fee_share_bps
+  }
+
+  /// View function.
+  pub fn get_insurance_claim(&self, claim_id: u64) -> Option<InsuranceClaim> {
+      self.insurance_claims.get(&claim_id).cloned()
+  }
+
+  /// View function.
+  pub fn get_insurance_claims(&self, from_index: Option<u64>, limit: Option<u64>) -> Vec<InsuranceClaim> {
+      self.insurance_claims.values()
+          .skip(from_index.unwrap_or(0) as usize)
+          .take(limit.unwrap_or(DEFAULT_PAGE_LIMIT) as usize)
+          .cloned()
+          .collect()
+  }
+}
+
+impl BioCrypticBankCore {
+  /// Splits a collected fee between the insurance fund and the treasury.
+  pub(crate) fn internal_collect_fee_with_insurance(&mut self, fee: NearToken) {
+      let share = NearToken::from_yoctonear(mul_div(fee.as_yoctonear(), self.insurance_fee_share_bps as u128, BPS_DENOMINATOR));
+      self.insurance_fund = self.insurance_fund.saturating_add(share);
+      self.total_fees_collected = self.total_fees_collected.saturating_add(share);
+      self.internal_collect_fee(fee.saturating_sub(share));
+  }
+
+  /// Drops a claim that is no longer open from its claimant's open claims.
+  fn internal_close_insurance_claim(&mut self, claimant_id: &AccountId, claim_id: u64) {
+      if let Some(claim_ids) = self.open_insurance_claims.get_mut(claimant_id) {
+          claim_ids.retain(|existing| *existing != claim_id);
+          if claim_ids.is_empty() {
+              self.open_insurance_claims.remove(claimant_id);
+          }
+      }
+  }
+}
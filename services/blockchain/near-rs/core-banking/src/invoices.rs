@@ -0,0 +1,160 @@
+// services/blockchain/near-rs/core-banking/src/invoices.rs
+use near_sdk::{near, env, AccountId, NearToken};
+
+use crate::ledger::EntryKind;
+use crate::pause::Feature;
+use crate::{assert_valid_memo, BankEvent, BioCrypticBankCore, BioCrypticBankCoreExt};
+
+/// Maximum number of open invoices a payer can have outstanding.
+const MAX_PENDING_INVOICES_PER_PAYER: usize = 100;
+
+#[near(serializers = [borsh, json])]
+#[serde(rename_all = "snake_case")]
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum InvoiceStatus {
+  Pending,
+  Paid,
+  Declined,
+  Cancelled,
+}
+
+/// A payment request from `payee_id` to `payer_id`.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct Invoice {
+  pub id: u64,
+  pub payee_id: AccountId,
+  pub payer_id: AccountId,
+  pub amount: NearToken,
+  pub memo: Option<String>,
+  pub created_at: u64,
+  pub expires_at: Option<u64>,
+  pub status: InvoiceStatus,
+  /// Payer-side ledger entry created when the invoice was paid.
+  pub ledger_entry_id: Option<u64>,
+}
+
+impl Invoice {
+  fn is_expired(&self, now: u64) -> bool {
+      self.expires_at.is_some_and(|expires_at| now > expires_at)
+  }
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Requests `amount` from `payer_id`. `expires_at`: Optional timestamp (ns) after which the
+  /// invoice can no longer be paid. Returns the invoice id.
+  pub fn create_invoice(&mut self, payer_id: AccountId, amount: NearToken, memo: Option<String>, expires_at: Option<u64>) -> u64 {
+      let initial_storage = env::storage_usage();
+      let payee_id = env::predecessor_account_id();
+      assert!(amount.as_yoctonear() > 0, "Invoice amount must be greater than 0.");
+      assert!(payee_id != payer_id, "Cannot invoice yourself.");
+      assert_valid_memo(&memo);
+      let now = env::block_timestamp();
+      if let Some(expires_at) = expires_at {
+          assert!(expires_at > now, "Expiry must be in the future.");
+      }
+
+      let pending = self.pending_invoices.entry(payer_id.clone()).or_default();
+      assert!(pending.len() < MAX_PENDING_INVOICES_PER_PAYER, "Payer has too many pending invoices.");
+      let id = self.next_invoice_id;
+      self.next_invoice_id += 1;
+      pending.push(id);
+
+      self.invoices.insert(id, Invoice {
+          id,
+          payee_id: payee_id.clone(),
+          payer_id: payer_id.clone(),
+          amount,
+          memo,
+          created_at: now,
+          expires_at,
+          status: InvoiceStatus::Pending,
+          ledger_entry_id: None,
+      });
+      self.internal_charge_storage(&payee_id, initial_storage);
+      BankEvent::InvoiceCreated { id, payee_id, payer_id, amount, expires_at }.emit();
+      id
+  }
+
+  /// Settles invoice `invoice_id` from the caller's balance. Both parties receive a ledger
+  /// entry referencing the invoice.
+  pub fn pay_invoice(&mut self, invoice_id: u64) {
+      self.assert_not_paused(Feature::Transfers);
+      let initial_storage = env::storage_usage();
+      let invoice = self.internal_get_pending_invoice(invoice_id);
+      assert_eq!(invoice.payer_id, env::predecessor_account_id(), "Only the payer can pay this invoice.");
+      assert!(!invoice.is_expired(env::block_timestamp()), "Invoice has expired.");
+      self.assert_trusted_destination(&invoice.payer_id, &invoice.payee_id);
+
+      self.internal_record_outflow(&invoice.payer_id, invoice.amount);
+      self.internal_debit(&invoice.payer_id, invoice.amount);
+      self.internal_credit(&invoice.payee_id, invoice.amount);
+      let reference = format!("invoice:{}", invoice_id);
+      let (debit_id, credit_id) = self.internal_record_movement(
+          &invoice.payer_id, &invoice.payee_id, EntryKind::InvoicePayment, invoice.amount,
+          invoice.memo.clone(), Some(reference.clone()),
+      );
+      self.internal_index_payment_reference(&invoice.payee_id, &reference, credit_id);
+      self.internal_close_invoice(invoice_id, InvoiceStatus::Paid, Some(debit_id));
+      self.internal_charge_storage(&invoice.payer_id, initial_storage);
+      BankEvent::InvoicePaid { id: invoice_id, payee_id: invoice.payee_id, payer_id: invoice.payer_id, amount: invoice.amount }.emit();
+  }
+
+  /// Declines a pending invoice addressed to the caller.
+  pub fn decline_invoice(&mut self, invoice_id: u64) {
+      let invoice = self.internal_get_pending_invoice(invoice_id);
+      assert_eq!(invoice.payer_id, env::predecessor_account_id(), "Only the payer can decline this invoice.");
+      self.internal_close_invoice(invoice_id, InvoiceStatus::Declined, None);
+      BankEvent::InvoiceDeclined { id: invoice_id }.emit();
+  }
+
+  /// Withdraws a pending invoice issued by the caller.
+  pub fn cancel_invoice(&mut self, invoice_id: u64) {
+      let invoice = self.internal_get_pending_invoice(invoice_id);
+      assert_eq!(invoice.payee_id, env::predecessor_account_id(), "Only the payee can cancel this invoice.");
+      self.internal_close_invoice(invoice_id, InvoiceStatus::Cancelled, None);
+      BankEvent::InvoiceCancelled { id: invoice_id }.emit();
+  }
+
+  /// Retrieves an invoice by id.
+  /// View function.
+  pub fn get_invoice(&self, invoice_id: u64) -> Option<Invoice> {
+      self.invoices.get(&invoice_id).cloned()
+  }
+
+  /// Lists unexpired invoices awaiting payment by `payer_id`.
+  /// View function.
+  pub fn get_pending_invoices(&self, payer_id: AccountId) -> Vec<Invoice> {
+      let now = env::block_timestamp();
+      self.pending_invoices.get(&payer_id)
+          .map(|ids| ids.iter()
+              .filter_map(|id| self.invoices.get(id))
+              .filter(|invoice| !invoice.is_expired(now))
+              .cloned()
+              .collect())
+          .unwrap_or_default()
+  }
+}
+
+impl BioCrypticBankCore {
+  fn internal_get_pending_invoice(&self, invoice_id: u64) -> Invoice {
+      let invoice = self.invoices.get(&invoice_id)
+          .unwrap_or_else(|| env::panic_str("Invoice not found."));
+      assert_eq!(invoice.status, InvoiceStatus::Pending, "Invoice is not pending.");
+      invoice.clone()
+  }
+
+  fn internal_close_invoice(&mut self, invoice_id: u64, status: InvoiceStatus, ledger_entry_id: Option<u64>) {
+      let invoice = self.invoices.get_mut(&invoice_id).unwrap();
+      invoice.status = status;
+      invoice.ledger_entry_id = ledger_entry_id;
+      let payer_id = invoice.payer_id.clone();
+      if let Some(ids) = self.pending_invoices.get_mut(&payer_id) {
+          ids.retain(|existing| *existing != invoice_id);
+          if ids.is_empty() {
+              self.pending_invoices.remove(&payer_id);
+          }
+      }
+  }
+}
@@ -0,0 +1,253 @@
+// services/blockchain/near-rs/core-banking/src/joint_accounts.rs
+use near_sdk::{near, env, AccountId, NearToken};
+
+use crate::ledger::{EntryDirection, EntryKind};
+use crate::pause::Feature;
+use crate::{assert_valid_memo, BankEvent, BioCrypticBankCore, BioCrypticBankCoreExt};
+
+const MAX_JOINT_OWNERS: usize = 10;
+/// How long a proposal collects co-signatures before it lapses (7 days).
+const JOINT_PROPOSAL_TTL_NANOS: u64 = 7 * 24 * 60 * 60 * 1_000_000_000;
+const MAX_PENDING_PROPOSALS: usize = 20;
+
+/// A balance shared by several owners. Outflows up to `approval_threshold` need a single
+/// owner; larger ones need `required_approvals` co-signatures.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct JointAccount {
+  pub id: u64,
+  pub owners: Vec<AccountId>,
+  pub required_approvals: u8,
+  pub approval_threshold: NearToken,
+  pub balance: NearToken,
+  pub pending_proposal_ids: Vec<u64>,
+}
+
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub enum JointAction {
+  /// Moves `amount` to the proposer's own checking balance.
+  Withdraw { amount: NearToken },
+  /// Moves `amount` to `receiver_id`'s checking balance.
+  Transfer { receiver_id: AccountId, amount: NearToken, memo: Option<String> },
+}
+
+impl JointAction {
+  fn amount(&self) -> NearToken {
+      match self {
+          JointAction::Withdraw { amount } | JointAction::Transfer { amount, .. } => *amount,
+      }
+  }
+}
+
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct JointProposal {
+  pub id: u64,
+  pub joint_account_id: u64,
+  pub proposer_id: AccountId,
+  pub action: JointAction,
+  pub approvals: Vec<AccountId>,
+  pub required_approvals: u8,
+  pub created_at: u64,
+  pub expires_at: u64,
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Opens a joint account owned by the caller and `co_owners`. Returns its id.
+  pub fn create_joint_account(&mut self, co_owners: Vec<AccountId>, required_approvals: u8, approval_threshold: NearToken) -> u64 {
+      let initial_storage = env::storage_usage();
+      let creator_id = env::predecessor_account_id();
+      let mut owners = vec![creator_id.clone()];
+      for co_owner in co_owners {
+          assert!(!owners.contains(&co_owner), "Duplicate owner: {}", co_owner);
+          owners.push(co_owner);
+      }
+      assert!(owners.len() >= 2, "A joint account needs at least two owners.");
+      assert!(owners.len() <= MAX_JOINT_OWNERS, "A joint account cannot have more than {} owners.", MAX_JOINT_OWNERS);
+      assert!(
+          required_approvals >= 1 && required_approvals as usize <= owners.len(),
+          "Required approvals must be between 1 and the number of owners."
+      );
+
+      let id = self.next_joint_account_id;
+      self.next_joint_account_id += 1;
+      for owner_id in &owners {
+          self.joint_accounts_by_owner.entry(owner_id.clone()).or_default().push(id);
+      }
+      BankEvent::JointAccountCreated { id, owners: owners.clone(), required_approvals, approval_threshold }.emit();
+      self.joint_accounts.insert(id, JointAccount {
+          id,
+          owners,
+          required_approvals,
+          approval_threshold,
+          balance: NearToken::from_yoctonear(0),
+          pending_proposal_ids: Vec::new(),
+      });
+      self.internal_charge_storage(&creator_id, initial_storage);
+      id
+  }
+
+  /// Moves `amount` from the caller's checking balance into joint account `joint_account_id`.
+  /// Anyone may fund a joint account.
+  pub fn deposit_to_joint_account(&mut self, joint_account_id: u64, amount: NearToken) {
+      self.assert_not_paused(Feature::Transfers);
+      let account_id = env::predecessor_account_id();
+      assert!(amount.as_yoctonear() > 0, "Amount must be greater than 0.");
+      self.internal_record_outflow(&account_id, amount);
+      self.internal_debit(&account_id, amount);
+      self.internal_record_entry(
+          &account_id, EntryKind::JointAccount, EntryDirection::Debit, amount,
+          None, Some(format!("joint:{}", joint_account_id)),
+      );
+      let joint = self.internal_get_joint_account_mut(joint_account_id);
+      joint.balance = joint.balance.saturating_add(amount);
+      BankEvent::JointAccountDeposit { id: joint_account_id, account_id, amount }.emit();
+  }
+
+  /// Proposes an outflow from a joint account the caller co-owns, counting as the caller's
+  /// approval. Executes immediately when no further approvals are required. Returns the proposal id.
+  pub fn propose_joint_action(&mut self, joint_account_id: u64, action: JointAction) -> u64 {
+      self.assert_not_paused(Feature::Transfers);
+      let proposer_id = env::predecessor_account_id();
+      let amount = action.amount();
+      assert!(amount.as_yoctonear() > 0, "Amount must be greater than 0.");
+      if let JointAction::Transfer { memo, .. } = &action {
+          assert_valid_memo(memo);
+      }
+
+      if let JointAction::Transfer { receiver_id, .. } = &action {
+          self.assert_registered(receiver_id);
+      }
+
+      let initial_storage = env::storage_usage();
+      let now = env::block_timestamp();
+      let id = self.next_joint_proposal_id;
+      let joint = self.internal_get_joint_account_mut(joint_account_id);
+      assert!(joint.owners.contains(&proposer_id), "Only a co-owner can propose.");
+      assert!(joint.pending_proposal_ids.len() < MAX_PENDING_PROPOSALS, "Too many pending proposals.");
+      let required_approvals = if amount > joint.approval_threshold { joint.required_approvals } else { 1 };
+      joint.pending_proposal_ids.push(id);
+      self.next_joint_proposal_id += 1;
+
+      self.joint_proposals.insert(id, JointProposal {
+          id,
+          joint_account_id,
+          proposer_id: proposer_id.clone(),
+          action,
+          approvals: vec![proposer_id.clone()],
+          required_approvals,
+          created_at: now,
+          expires_at: now + JOINT_PROPOSAL_TTL_NANOS,
+      });
+      BankEvent::JointProposalCreated { id, joint_account_id, proposer_id: proposer_id.clone(), amount, required_approvals }.emit();
+      self.internal_try_execute_joint_proposal(id);
+      self.internal_charge_storage(&proposer_id, initial_storage);
+      id
+  }
+
+  /// Co-signs a pending proposal. Executes it once enough approvals are collected.
+  /// Returns true if the proposal was executed.
+  pub fn approve_joint_proposal(&mut self, proposal_id: u64) -> bool {
+      self.assert_not_paused(Feature::Transfers);
+      let account_id = env::predecessor_account_id();
+      let proposal = self.internal_get_live_joint_proposal(proposal_id);
+      let joint = self.joint_accounts.get(&proposal.joint_account_id).unwrap();
+      assert!(joint.owners.contains(&account_id), "Only a co-owner can approve.");
+      assert!(!proposal.approvals.contains(&account_id), "Already approved.");
+
+      self.joint_proposals.get_mut(&proposal_id).unwrap().approvals.push(account_id.clone());
+      BankEvent::JointProposalApproved { id: proposal_id, account_id }.emit();
+      self.internal_try_execute_joint_proposal(proposal_id)
+  }
+
+  /// Withdraws a pending proposal. Callable by its proposer, or by any co-owner once it has expired.
+  pub fn cancel_joint_proposal(&mut self, proposal_id: u64) {
+      let account_id = env::predecessor_account_id();
+      let proposal = self.joint_proposals.get(&proposal_id)
+          .unwrap_or_else(|| env::panic_str("Proposal not found."));
+      let joint = self.joint_accounts.get(&proposal.joint_account_id).unwrap();
+      let expired = env::block_timestamp() > proposal.expires_at;
+      assert!(
+          proposal.proposer_id == account_id || (expired && joint.owners.contains(&account_id)),
+          "Only the proposer can cancel a live proposal."
+      );
+      self.internal_remove_joint_proposal(proposal_id);
+      BankEvent::JointProposalCancelled { id: proposal_id }.emit();
+  }
+
+  /// Retrieves a joint account by id.
+  /// View function.
+  pub fn get_joint_account(&self, joint_account_id: u64) -> Option<JointAccount> {
+      self.joint_accounts.get(&joint_account_id).cloned()
+  }
+
+  /// Lists the joint accounts `account_id` co-owns.
+  /// View function.
+  pub fn get_joint_accounts_for(&self, account_id: AccountId) -> Vec<JointAccount> {
+      self.joint_accounts_by_owner.get(&account_id)
+          .map(|ids| ids.iter().filter_map(|id| self.joint_accounts.get(id).cloned()).collect())
+          .unwrap_or_default()
+  }
+
+  /// Lists unexpired proposals across every joint account `account_id` co-owns,
+  /// including those the account has already approved.
+  /// View function.
+  pub fn get_pending_joint_proposals(&self, account_id: AccountId) -> Vec<JointProposal> {
+      let now = env::block_timestamp();
+      self.get_joint_accounts_for(account_id).iter()
+          .flat_map(|joint| joint.pending_proposal_ids.iter())
+          .filter_map(|id| self.joint_proposals.get(id))
+          .filter(|proposal| now <= proposal.expires_at)
+          .cloned()
+          .collect()
+  }
+}
+
+impl BioCrypticBankCore {
+  fn internal_get_joint_account_mut(&mut self, joint_account_id: u64) -> &mut JointAccount {
+      self.joint_accounts.get_mut(&joint_account_id)
+          .unwrap_or_else(|| env::panic_str("Joint account not found."))
+  }
+
+  fn internal_get_live_joint_proposal(&self, proposal_id: u64) -> JointProposal {
+      let proposal = self.joint_proposals.get(&proposal_id)
+          .unwrap_or_else(|| env::panic_str("Proposal not found."));
+      assert!(env::block_timestamp() <= proposal.expires_at, "Proposal has expired.");
+      proposal.clone()
+  }
+
+  /// Executes `proposal_id` if it has collected enough approvals. Returns true if executed.
+  fn internal_try_execute_joint_proposal(&mut self, proposal_id: u64) -> bool {
+      let proposal = self.joint_proposals.get(&proposal_id).unwrap().clone();
+      if proposal.approvals.len() < proposal.required_approvals as usize {
+          return false;
+      }
+
+      let amount = proposal.action.amount();
+      let joint = self.internal_get_joint_account_mut(proposal.joint_account_id);
+      joint.balance = joint.balance.checked_sub(amount)
+          .unwrap_or_else(|| env::panic_str("Insufficient joint account balance."));
+
+      let (receiver_id, memo) = match proposal.action {
+          JointAction::Withdraw { .. } => (proposal.proposer_id, None),
+          JointAction::Transfer { receiver_id, memo, .. } => (receiver_id, memo),
+      };
+      self.internal_credit(&receiver_id, amount);
+      self.internal_record_entry(
+          &receiver_id, EntryKind::JointAccount, EntryDirection::Credit, amount,
+          memo, Some(format!("joint:{}", proposal.joint_account_id)),
+      );
+      self.internal_remove_joint_proposal(proposal_id);
+      BankEvent::JointProposalExecuted { id: proposal_id, joint_account_id: proposal.joint_account_id, receiver_id, amount }.emit();
+      true
+  }
+
+  fn internal_remove_joint_proposal(&mut self, proposal_id: u64) {
+      let Some(proposal) = self.joint_proposals.remove(&proposal_id) else { return };
+      if let Some(joint) = self.joint_accounts.get_mut(&proposal.joint_account_id) {
+          joint.pending_proposal_ids.retain(|existing| *existing != proposal_id);
+      }
+  }
+}
@@ -0,0 +1,301 @@
+// services/blockchain/near-rs/core-banking/src/kyc.rs
+use near_sdk::{near, env, AccountId, Gas, NearToken, Promise, PromiseError, PromiseOrValue};
+use biocrypticbank_common::did::{kyc_level, DidResolution, KYC_CREDENTIAL_TYPE};
+use biocrypticbank_common::interfaces::ext_did_registry;
+
+use crate::limits::DAY_NANOS;
+use crate::operation_locks::LockedOperation;
+use crate::overdraft::MAX_KYC_TIER;
+use crate::storage::MAX_DEPOSIT_STORAGE_BYTES;
+use crate::{BankEvent, BioCrypticBankCore, BioCrypticBankCoreExt};
+
+const NANOS_PER_SECOND: u64 = 1_000_000_000;
+const GAS_FOR_RESOLVE_DID: Gas = Gas::from_tgas(10);
+const GAS_FOR_KYC_CALLBACK: Gas = Gas::from_tgas(40);
+
+/// Who may open a bank account with their first deposit.
+#[near(serializers = [borsh, json])]
+#[serde(rename_all = "snake_case")]
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum OnboardingPolicy {
+  /// Any registered account.
+  #[default]
+  Open,
+  /// Only accounts with a registered, non-deactivated DID in the registry.
+  RequireDid,
+}
+
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct KycConfig {
+  pub did_registry_id: Option<AccountId>,
+  /// Issuers whose KYC credentials the bank accepts.
+  pub trusted_issuers: Vec<AccountId>,
+  /// How long a tier read from the registry is trusted for large operations.
+  pub cache_ttl_sec: u64,
+  /// Deposits, withdrawals and transfers of at least this amount re-check a stale tier.
+  pub large_amount_threshold: NearToken,
+  /// Daily deposit, withdrawal and transfer volume allowed per tier, indexed by tier. Tiers
+  /// beyond the list are unlimited, e.g. `["10 NEAR"]` caps only unverified accounts.
+  pub tier_daily_limits: Vec<NearToken>,
+  pub onboarding_policy: OnboardingPolicy,
+}
+
+impl Default for KycConfig {
+  fn default() -> Self {
+      Self {
+          did_registry_id: None,
+          trusted_issuers: Vec::new(),
+          cache_ttl_sec: 24 * 60 * 60,
+          large_amount_threshold: NearToken::from_near(100),
+          tier_daily_limits: Vec::new(),
+          onboarding_policy: OnboardingPolicy::Open,
+      }
+  }
+}
+
+/// Tier last read from the DID registry.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct CachedKycTier {
+  pub tier: u8,
+  /// False if the account has no DID or it was deactivated.
+  pub did_active: bool,
+  pub fetched_at: u64,
+}
+
+/// Operation deferred until the caller's tier has been read from the registry, or until a
+/// guardian has approved it.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum KycPendingAction {
+  Refresh,
+  Deposit {
+      amount: NearToken,
+      memo: Option<String>,
+      reference: Option<String>,
+      /// Third party funding the deposit; refunded instead of the account if it is rejected.
+      funder_id: Option<AccountId>,
+  },
+  Withdraw { amount: NearToken, envelope: Option<String>, memo: Option<String>, reference: Option<String> },
+  Transfer {
+      receiver_id: AccountId,
+      amount: NearToken,
+      memo: Option<String>,
+      envelope: Option<String>,
+      reference: Option<String>,
+  },
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Re-reads `account_id`'s (default: caller's) KYC tier from the DID registry and caches it.
+  pub fn refresh_kyc_tier(&mut self, account_id: Option<AccountId>) -> Promise {
+      let account_id = account_id.unwrap_or_else(env::predecessor_account_id);
+      self.assert_registered(&account_id);
+      self.internal_resolve_kyc(account_id, KycPendingAction::Refresh)
+  }
+
+  /// Callback releasing the account's lock and caching the tier read from the DID registry,
+  /// then running the deferred operation. A deposit that is no longer allowed, that would
+  /// open an account without an active DID when onboarding requires one, or whose storage
+  /// deposit could not pay for the records it writes, is refunded and resolves to false.
+  #[private]
+  pub fn on_kyc_resolved(
+      &mut self,
+      account_id: AccountId,
+      action: KycPendingAction,
+      lock_id: Option<u64>,
+      #[callback_result] result: Result<Option<DidResolution>, PromiseError>,
+  ) -> PromiseOrValue<bool> {
+      self.internal_unlock_account(&account_id, lock_id);
+      let resolution = match result {
+          Ok(resolution) => resolution,
+          Err(_) => {
+              env::log_str("DID registry lookup failed.");
+              if let KycPendingAction::Deposit { amount, funder_id, .. } = action {
+                  Promise::new(funder_id.unwrap_or(account_id)).transfer(amount);
+              }
+              return PromiseOrValue::Value(false);
+          }
+      };
+      self.internal_cache_kyc_tier(&account_id, resolution.as_ref());
+
+      match action {
+          KycPendingAction::Refresh => PromiseOrValue::Value(true),
+          KycPendingAction::Deposit { amount, memo, reference, funder_id } => {
+              let refund_id = funder_id.clone().unwrap_or_else(|| account_id.clone());
+              if !self.storage_accounts.contains_key(&account_id) {
+                  env::log_str("Account is no longer registered; deposit refunded.");
+                  Promise::new(refund_id).transfer(amount);
+                  return PromiseOrValue::Value(false);
+              }
+              if self.internal_onboarding_check_needed(&account_id)
+                  && !self.kyc_cache.get(&account_id).is_some_and(|cached| cached.did_active) {
+                  env::log_str("Opening an account requires an active DID; deposit refunded.");
+                  Promise::new(refund_id).transfer(amount);
+                  return PromiseOrValue::Value(false);
+              }
+              if !self.internal_kyc_allows(&account_id, amount) {
+                  env::log_str("Deposit exceeds the KYC tier limit and was refunded.");
+                  Promise::new(refund_id).transfer(amount);
+                  return PromiseOrValue::Value(false);
+              }
+              if !self.storage_accounts.get(&account_id).is_some_and(|storage| storage.covers(MAX_DEPOSIT_STORAGE_BYTES)) {
+                  env::log_str("Storage deposit cannot cover the deposit's records; deposit refunded.");
+                  Promise::new(refund_id).transfer(amount);
+                  return PromiseOrValue::Value(false);
+              }
+              self.internal_deposit(&account_id, funder_id.as_ref(), amount, memo, reference);
+              PromiseOrValue::Value(true)
+          }
+          KycPendingAction::Withdraw { amount, envelope, memo, reference } => {
+              self.internal_withdraw(&account_id, amount, envelope, memo, reference)
+          }
+          KycPendingAction::Transfer { receiver_id, amount, memo, envelope, reference } => {
+              self.internal_transfer(&account_id, &receiver_id, amount, memo, envelope, reference);
+              PromiseOrValue::Value(true)
+          }
+      }
+  }
+
+  /// Sets the DID registry, trusted issuers, cache TTL, tier limits and onboarding policy.
+  /// Owner only.
+  pub fn set_kyc_config(&mut self, config: KycConfig) {
+      self.assert_owner();
+      assert!(
+          config.onboarding_policy == OnboardingPolicy::Open || config.did_registry_id.is_some(),
+          "Requiring a DID needs a DID registry."
+      );
+      assert!(config.tier_daily_limits.len() <= MAX_KYC_TIER as usize + 1, "Too many tier limits.");
+      self.kyc_config = config;
+  }
+
+  /// View function.
+  pub fn get_kyc_config(&self) -> KycConfig {
+      self.kyc_config.clone()
+  }
+
+  /// Tier last read from the DID registry for `account_id`.
+  /// View function.
+  pub fn get_cached_kyc_tier(&self, account_id: AccountId) -> Option<CachedKycTier> {
+      self.kyc_cache.get(&account_id).cloned()
+  }
+
+  /// Volume `account_id` may still deposit, withdraw or transfer today under its tier;
+  /// `None` when its tier is unlimited.
+  /// View function.
+  pub fn get_kyc_remaining_today(&self, account_id: AccountId) -> Option<NearToken> {
+      let limit = self.internal_kyc_daily_limit(&account_id)?;
+      let used = self.kyc_usage.get(&account_id)
+          .map_or(NearToken::from_yoctonear(0), |window| window.spent_at(env::block_timestamp(), DAY_NANOS));
+      Some(limit.saturating_sub(used))
+  }
+}
+
+impl BioCrypticBankCore {
+  /// True if an operation of `amount` by `account_id` must first re-read its tier: the
+  /// amount is large and the cached tier is missing or older than the TTL.
+  pub(crate) fn internal_kyc_check_needed(&self, account_id: &AccountId, amount: NearToken) -> bool {
+      self.kyc_config.did_registry_id.is_some()
+          && amount >= self.kyc_config.large_amount_threshold
+          && self.kyc_cache.get(account_id).is_none_or(|cached| {
+              env::block_timestamp().saturating_sub(cached.fetched_at) > self.kyc_config.cache_ttl_sec * NANOS_PER_SECOND
+          })
+  }
+
+  /// True if a deposit by `account_id` would open its bank account under a policy requiring
+  /// a DID.
+  pub(crate) fn internal_onboarding_check_needed(&self, account_id: &AccountId) -> bool {
+      self.kyc_config.onboarding_policy == OnboardingPolicy::RequireDid && !self.internal_has_account(account_id)
+  }
+
+  /// Reads `account_id`'s DID from the registry, then runs `action` in `on_kyc_resolved`.
+  /// Deferred operations lock the account, so it cannot be unregistered before the callback.
+  pub(crate) fn internal_resolve_kyc(&mut self, account_id: AccountId, action: KycPendingAction) -> Promise {
+      let registry_id = self.kyc_config.did_registry_id.clone()
+          .unwrap_or_else(|| env::panic_str("No DID registry is configured."));
+      let lock_id = match action {
+          KycPendingAction::Deposit { .. } | KycPendingAction::Withdraw { .. } | KycPendingAction::Transfer { .. } => {
+              Some(self.internal_lock_account(&account_id, LockedOperation::KycCheck))
+          }
+          KycPendingAction::Refresh => None,
+      };
+      ext_did_registry::ext(registry_id)
+          .with_static_gas(GAS_FOR_RESOLVE_DID)
+          .resolve_did(account_id.clone())
+          .then(
+              Self::ext(env::current_account_id())
+                  .with_static_gas(GAS_FOR_KYC_CALLBACK)
+                  .on_kyc_resolved(account_id, action, lock_id)
+          )
+  }
+
+  fn internal_cache_kyc_tier(&mut self, account_id: &AccountId, resolution: Option<&DidResolution>) {
+      let now = env::block_timestamp();
+      let did_active = resolution.is_some_and(|resolution| !resolution.did_document_metadata.deactivated);
+      let tier = resolution.filter(|_| did_active).map_or(0, |resolution| {
+          resolution.did_document.verifiable_credentials.iter()
+              .filter(|credential| {
+                  credential.credential_type == KYC_CREDENTIAL_TYPE
+                      && self.kyc_config.trusted_issuers.contains(&credential.issuer)
+                      && credential.revocation.is_none()
+                      && !credential.suspended
+                      && credential.expires_at.is_none_or(|expires_at| expires_at > now)
+              })
+              .map(|credential| kyc_level(&credential.tags, MAX_KYC_TIER))
+              .max()
+              .unwrap_or(0)
+      });
+      self.kyc_cache.insert(account_id.clone(), CachedKycTier { tier, did_active, fetched_at: now });
+      BankEvent::KycTierCached { account_id: account_id.clone(), tier, did_active }.emit();
+  }
+
+  fn internal_kyc_daily_limit(&self, account_id: &AccountId) -> Option<NearToken> {
+      self.kyc_config.tier_daily_limits.get(self.get_kyc_tier(account_id.clone()) as usize).copied()
+  }
+
+  fn internal_kyc_allows(&self, account_id: &AccountId, amount: NearToken) -> bool {
+      self.get_kyc_remaining_today(account_id.clone()).is_none_or(|remaining| amount <= remaining)
+  }
+
+  /// Counts `amount` against `account_id`'s daily KYC tier limit, panicking if it would
+  /// exceed it. Must be called for every deposit, withdrawal and transfer.
+  pub(crate) fn internal_record_kyc_volume(&mut self, account_id: &AccountId, amount: NearToken) {
+      if self.internal_kyc_daily_limit(account_id).is_none() {
+          return;
+      }
+      assert!(self.internal_kyc_allows(account_id, amount), "Amount exceeds the daily limit of your KYC tier.");
+      self.kyc_usage.entry(account_id.clone()).or_default().record(amount, env::block_timestamp(), DAY_NANOS);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use near_sdk::test_utils::{accounts, VMContextBuilder};
+  use near_sdk::testing_env;
+
+  use super::*;
+
+  #[test]
+  fn deferred_deposits_are_refunded_when_storage_is_short() {
+      testing_env!(VMContextBuilder::new()
+          .predecessor_account_id(accounts(1))
+          .attached_deposit(NearToken::from_near(1))
+          .build());
+      let mut contract = BioCrypticBankCore::new(accounts(0));
+      contract.storage_deposit(None, Some(true));
+      contract.storage_accounts.get_mut(&accounts(1)).unwrap().used_bytes = 1_800;
+
+      let action = KycPendingAction::Deposit {
+          amount: NearToken::from_near(1),
+          memo: None,
+          reference: None,
+          funder_id: None,
+      };
+      let resolved = contract.on_kyc_resolved(accounts(1), action, None, Ok(None));
+      assert!(matches!(resolved, PromiseOrValue::Value(false)));
+      assert_eq!(contract.get_balance(accounts(1)), NearToken::from_yoctonear(0));
+  }
+}
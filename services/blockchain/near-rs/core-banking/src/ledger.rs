@@ -0,0 +1,230 @@
+// services/blockchain/near-rs/core-banking/src/ledger.rs
+use near_sdk::{near, env, AccountId, NearToken};
+
+use crate::{BioCrypticBankCore, BioCrypticBankCoreExt, DEFAULT_PAGE_LIMIT};
+
+const MAX_PAYMENTS_PER_REFERENCE: usize = 100;
+
+#[near(serializers = [borsh, json])]
+#[serde(rename_all = "snake_case")]
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum EntryDirection {
+  Credit,
+  Debit,
+}
+
+#[near(serializers = [borsh, json])]
+#[serde(rename_all = "snake_case")]
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum EntryKind {
+  Deposit,
+  Withdrawal,
+  /// Credit restoring a withdrawal whose on-chain transfer failed.
+  WithdrawalReversal,
+  Transfer,
+  InvoicePayment,
+  JointAccount,
+  Fee,
+  Escrow,
+  Stream,
+  DirectDebit,
+  /// Collateral locked or released and loan proceeds or repayments.
+  Loan,
+  /// Depositor yield paid out of bank revenue.
+  Yield,
+  /// Referral rewards paid from the rewards budget.
+  Referral,
+  /// Share of an inactive account paid to a beneficiary.
+  Inheritance,
+  /// Abandoned balance moved into or reclaimed from custody.
+  Escheatment,
+  /// Funds escrowed for, claimed from or refunded by a claim-key gift.
+  Gift,
+  /// Customer payment for a merchant order, held until the merchant's next settlement.
+  MerchantPayment,
+  /// Batch of merchant payments paid out to the settlement account.
+  MerchantSettlement,
+  /// Refund of a disputed merchant charge ruled in the customer's favour.
+  Chargeback,
+  /// Settlement of a payment intent, e.g. scanned from a QR code.
+  IntentPayment,
+  /// Recurring charge of a subscription plan.
+  Subscription,
+  /// Prorated refund of a subscription cancelled mid-period.
+  SubscriptionRefund,
+  /// NEAR wrapped into or unwrapped from wNEAR.
+  WnearConversion,
+  /// Approved insurance claim paid from the insurance fund.
+  InsurancePayout,
+  /// Funds a loan guarantor locked, had released or had drawn to repay the loan.
+  Guarantee,
+  /// Draws and repayments of NFT-backed credit lines, and bids and proceeds of their auctions.
+  NftCredit,
+  /// Funds set aside for, claimed from or refunded by a claimable transfer.
+  ClaimableTransfer,
+  /// Principal moved into or out of the prize pool, and prizes won.
+  PrizeSavings,
+  /// Gift to a registered charity, or the bank's matching gift added to it.
+  Donation,
+}
+
+/// One side of a balance movement. Movements between two accounts produce a debit and a
+/// credit entry that reference each other through `linked_entry_id`.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct LedgerEntry {
+  pub id: u64,
+  pub account_id: AccountId,
+  pub counterparty_id: Option<AccountId>,
+  pub kind: EntryKind,
+  pub direction: EntryDirection,
+  pub amount: NearToken,
+  pub balance_after: NearToken,
+  pub memo: Option<String>,
+  /// Identifier of the business object behind the entry, e.g. `invoice:42`.
+  pub reference: Option<String>,
+  pub linked_entry_id: Option<u64>,
+  pub timestamp: u64,
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Retrieves a ledger entry by id.
+  /// View function.
+  pub fn get_ledger_entry(&self, entry_id: u64) -> Option<LedgerEntry> {
+      self.ledger_entries.get(&entry_id).cloned()
+  }
+
+  /// Lists `account_id`'s ledger entries oldest first, paginated.
+  /// View function.
+  pub fn get_account_ledger(&self, account_id: AccountId, from_index: Option<u64>, limit: Option<u64>) -> Vec<LedgerEntry> {
+      self.internal_ledger_entry_ids(&account_id, from_index.unwrap_or(0), limit.unwrap_or(DEFAULT_PAGE_LIMIT))
+          .iter()
+          .filter_map(|entry_id| self.ledger_entries.get(entry_id).cloned())
+          .collect()
+  }
+
+  /// Payments received by `merchant_id` carrying `reference`, oldest first: transfers with
+  /// that reference, invoice settlements under `invoice:<id>`, payment intents under
+  /// `intent:<id>`, subscription charges under `subscription:<id>`, or merchant order
+  /// charges under `order:<order_id>`.
+  /// View function.
+  pub fn get_payments_by_reference(&self, merchant_id: AccountId, reference: String) -> Vec<LedgerEntry> {
+      self.payments_by_reference.get(&(merchant_id, reference))
+          .map(|entry_ids| entry_ids.iter().filter_map(|entry_id| self.ledger_entries.get(entry_id).cloned()).collect())
+          .unwrap_or_default()
+  }
+
+  /// Counts the ledger entries recorded for `account_id`.
+  /// View function.
+  pub fn get_account_ledger_length(&self, account_id: AccountId) -> u64 {
+      self.internal_ledger_len(&account_id)
+  }
+}
+
+impl BioCrypticBankCore {
+  /// Indexes the credit entry `entry_id` of a payment to `merchant_id` under `reference`.
+  pub(crate) fn internal_index_payment_reference(&mut self, merchant_id: &AccountId, reference: &str, entry_id: u64) {
+      let entry_ids = self.payments_by_reference.entry((merchant_id.clone(), reference.to_string())).or_default();
+      assert!(entry_ids.len() < MAX_PAYMENTS_PER_REFERENCE, "Too many payments with this reference.");
+      entry_ids.push(entry_id);
+  }
+
+  /// Appends a single-sided entry (deposit, withdrawal, ...) for `account_id`. Returns its id.
+  pub(crate) fn internal_record_entry(
+      &mut self,
+      account_id: &AccountId,
+      kind: EntryKind,
+      direction: EntryDirection,
+      amount: NearToken,
+      memo: Option<String>,
+      reference: Option<String>,
+  ) -> u64 {
+      self.internal_push_entry(account_id, None, kind, direction, amount, memo, reference, None)
+  }
+
+  /// Appends an entry for a movement whose other side is not a bank account, e.g. a deposit
+  /// funded by a third party. Returns the entry id.
+  #[allow(clippy::too_many_arguments)]
+  pub(crate) fn internal_record_entry_with_counterparty(
+      &mut self,
+      account_id: &AccountId,
+      counterparty_id: Option<AccountId>,
+      kind: EntryKind,
+      direction: EntryDirection,
+      amount: NearToken,
+      memo: Option<String>,
+      reference: Option<String>,
+  ) -> u64 {
+      self.internal_push_entry(account_id, counterparty_id, kind, direction, amount, memo, reference, None)
+  }
+
+  /// Appends the linked debit and credit entries of a movement from `sender_id` to
+  /// `receiver_id`. Must be called after both balances were updated. Payments earn the
+  /// sender loyalty points. Returns `(debit_id, credit_id)`.
+  pub(crate) fn internal_record_movement(
+      &mut self,
+      sender_id: &AccountId,
+      receiver_id: &AccountId,
+      kind: EntryKind,
+      amount: NearToken,
+      memo: Option<String>,
+      reference: Option<String>,
+  ) -> (u64, u64) {
+      let debit_id = self.next_ledger_entry_id;
+      let credit_id = debit_id + 1;
+      self.internal_push_entry(
+          sender_id, Some(receiver_id.clone()), kind, EntryDirection::Debit, amount,
+          memo.clone(), reference.clone(), Some(credit_id),
+      );
+      self.internal_push_entry(
+          receiver_id, Some(sender_id.clone()), kind, EntryDirection::Credit, amount,
+          memo, reference, Some(debit_id),
+      );
+      if matches!(kind, EntryKind::Transfer | EntryKind::InvoicePayment | EntryKind::DirectDebit | EntryKind::IntentPayment
+          | EntryKind::Subscription) {
+          self.internal_award_payment_points(sender_id, amount);
+      }
+      (debit_id, credit_id)
+  }
+
+  #[allow(clippy::too_many_arguments)]
+  fn internal_push_entry(
+      &mut self,
+      account_id: &AccountId,
+      counterparty_id: Option<AccountId>,
+      kind: EntryKind,
+      direction: EntryDirection,
+      amount: NearToken,
+      memo: Option<String>,
+      reference: Option<String>,
+      linked_entry_id: Option<u64>,
+  ) -> u64 {
+      let id = self.next_ledger_entry_id;
+      self.next_ledger_entry_id += 1;
+      if let (EntryDirection::Debit, Some(counterparty_id)) = (direction, &counterparty_id) {
+          self.internal_auto_categorize(account_id, counterparty_id, id, amount);
+      }
+      let entry = LedgerEntry {
+          id,
+          account_id: account_id.clone(),
+          counterparty_id,
+          kind,
+          direction,
+          amount,
+          balance_after: self.get_balance(account_id.clone()),
+          memo,
+          reference,
+          linked_entry_id,
+          timestamp: env::block_timestamp(),
+      };
+      self.internal_chain_entry(&entry);
+      self.internal_index_entry_block(id);
+      self.ledger_entries.insert(id, entry);
+      self.internal_append_ledger_entry(account_id, id);
+      if *account_id == env::predecessor_account_id() {
+          self.internal_touch_activity(account_id);
+      }
+      id
+  }
+}
@@ -0,0 +1,174 @@
+// services/blockchain/near-rs/core-banking/src/ledger_shards.rs
+use near_sdk::{near, AccountId};
+use near_sdk::store::{LookupMap, Vector};
+
+use crate::ledger::LedgerEntry;
+use crate::{BioCrypticBankCore, BioCrypticBankCoreExt, StorageKey};
+
+/// Entry ids per shard. A page of history costs one storage read per shard instead of one
+/// per entry, and an appended id adds 8 bytes to a shard instead of a keyed record of its
+/// own, while rewriting a shard on append stays bounded.
+pub const LEDGER_SHARD_SIZE: u64 = 64;
+
+/// Key of shard `shard` of `account_id`'s ledger, holding the ids at positions
+/// `shard * LEDGER_SHARD_SIZE..(shard + 1) * LEDGER_SHARD_SIZE`.
+pub type LedgerShardKey = (AccountId, u64);
+
+/// Per-account ledgers stored before sharding, one `Vector` of entry ids per account.
+/// Accounts that have one keep reading and appending to it; accounts without one are sharded.
+fn legacy_ledgers() -> LookupMap<AccountId, Vector<u64>> {
+  LookupMap::new(StorageKey::AccountLedgers)
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Entry ids in shard `shard` of `account_id`'s ledger, oldest first.
+  /// View function.
+  pub fn get_ledger_shard(&self, account_id: AccountId, shard: u64) -> Vec<u64> {
+      self.ledger_shards.get(&(account_id, shard)).cloned().unwrap_or_default()
+  }
+}
+
+impl BioCrypticBankCore {
+  /// Number of entries in `account_id`'s ledger.
+  pub(crate) fn internal_ledger_len(&self, account_id: &AccountId) -> u64 {
+      if let Some(len) = self.ledger_lengths.get(account_id) {
+          return *len;
+      }
+      legacy_ledgers().get(account_id).map_or(0, |entry_ids| entry_ids.len() as u64)
+  }
+
+  /// Id of the entry at `position` in `account_id`'s ledger.
+  pub(crate) fn internal_ledger_entry_id(&self, account_id: &AccountId, position: u64) -> Option<u64> {
+      if !self.ledger_lengths.contains_key(account_id) {
+          let ledgers = legacy_ledgers();
+          return ledgers.get(account_id)?.get(u32::try_from(position).ok()?).copied();
+      }
+      self.ledger_shards.get(&(account_id.clone(), position / LEDGER_SHARD_SIZE))?
+          .get((position % LEDGER_SHARD_SIZE) as usize)
+          .copied()
+  }
+
+  /// The entry at `position` in `account_id`'s ledger.
+  pub(crate) fn internal_ledger_entry_at(&self, account_id: &AccountId, position: u64) -> Option<&LedgerEntry> {
+      self.internal_ledger_entry_id(account_id, position).and_then(|entry_id| self.ledger_entries.get(&entry_id))
+  }
+
+  /// Up to `limit` entry ids of `account_id`'s ledger from `from_index`, reading only the
+  /// shards they are in.
+  pub(crate) fn internal_ledger_entry_ids(&self, account_id: &AccountId, from_index: u64, limit: u64) -> Vec<u64> {
+      let end = self.internal_ledger_len(account_id).min(from_index.saturating_add(limit));
+      if !self.ledger_lengths.contains_key(account_id) {
+          return (from_index..end).filter_map(|position| self.internal_ledger_entry_id(account_id, position)).collect();
+      }
+      let mut entry_ids = Vec::new();
+      let mut position = from_index;
+      while position < end {
+          let shard = position / LEDGER_SHARD_SIZE;
+          let Some(ids) = self.ledger_shards.get(&(account_id.clone(), shard)) else { break };
+          let start = (position % LEDGER_SHARD_SIZE) as usize;
+          let take = (end - position).min(LEDGER_SHARD_SIZE - start as u64) as usize;
+          entry_ids.extend(ids.iter().skip(start).take(take));
+          position = (shard + 1) * LEDGER_SHARD_SIZE;
+      }
+      entry_ids
+  }
+
+  /// Appends `entry_id` to `account_id`'s ledger, starting a new shard when the last is full.
+  pub(crate) fn internal_append_ledger_entry(&mut self, account_id: &AccountId, entry_id: u64) {
+      if !self.ledger_lengths.contains_key(account_id) {
+          let mut ledgers = legacy_ledgers();
+          if let Some(entry_ids) = ledgers.get_mut(account_id) {
+              entry_ids.push(entry_id);
+              entry_ids.flush();
+              ledgers.flush();
+              return;
+          }
+      }
+      let len = self.internal_ledger_len(account_id);
+      self.ledger_shards.entry((account_id.clone(), len / LEDGER_SHARD_SIZE)).or_default().push(entry_id);
+      self.ledger_lengths.insert(account_id.clone(), len + 1);
+  }
+
+  /// Deletes `account_id`'s ledger, its shards and the entries they point to, with their
+  /// categories.
+  pub(crate) fn internal_remove_ledger(&mut self, account_id: &AccountId) {
+      let mut ledgers = legacy_ledgers();
+      if let Some(mut entry_ids) = ledgers.remove(account_id) {
+          for entry_id in entry_ids.drain(..) {
+              self.ledger_entries.remove(&entry_id);
+              self.entry_categories.remove(&entry_id);
+          }
+          entry_ids.flush();
+          ledgers.flush();
+      }
+      let Some(len) = self.ledger_lengths.remove(account_id) else { return };
+      for shard in 0..len.div_ceil(LEDGER_SHARD_SIZE) {
+          for entry_id in self.ledger_shards.remove(&(account_id.clone(), shard)).unwrap_or_default() {
+              self.ledger_entries.remove(&entry_id);
+              self.entry_categories.remove(&entry_id);
+          }
+      }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use near_sdk::test_utils::{accounts, VMContextBuilder};
+  use near_sdk::{env, testing_env, Gas};
+
+  use super::*;
+
+  const LEDGER_LEN: u64 = 256;
+  const PAGE: u64 = 50;
+
+  fn gas_used(run: impl FnOnce()) -> Gas {
+      let before = env::used_gas();
+      run();
+      env::used_gas().saturating_sub(before)
+  }
+
+  #[test]
+  fn sharded_pages_and_appends_use_less_gas_than_legacy_ledgers() {
+      testing_env!(VMContextBuilder::new().build());
+      let mut contract = BioCrypticBankCore::new(accounts(0));
+      let (sharded, legacy) = (accounts(1), accounts(2));
+      let mut ledgers = legacy_ledgers();
+      let mut entry_ids = Vector::new(StorageKey::AccountLedger { account_id_hash: env::sha256(legacy.as_bytes()) });
+      for entry_id in 0..LEDGER_LEN {
+          contract.internal_append_ledger_entry(&sharded, entry_id);
+          entry_ids.push(entry_id);
+      }
+      entry_ids.flush();
+      ledgers.insert(legacy.clone(), entry_ids);
+      ledgers.flush();
+      contract.ledger_shards.flush();
+      contract.ledger_lengths.flush();
+      testing_env!(VMContextBuilder::new().build());
+      let contract = BioCrypticBankCore::new(accounts(0));
+
+      let mut pages = (Vec::new(), Vec::new());
+      let sharded_page = gas_used(|| pages.0 = contract.internal_ledger_entry_ids(&sharded, 100, PAGE));
+      let legacy_page = gas_used(|| pages.1 = contract.internal_ledger_entry_ids(&legacy, 100, PAGE));
+      assert_eq!(pages.0, (100..100 + PAGE).collect::<Vec<_>>());
+      assert_eq!(pages.0, pages.1);
+      assert!(
+          sharded_page.as_gas() * 4 < legacy_page.as_gas(),
+          "Sharded page used {} gas, legacy page {}.", sharded_page, legacy_page
+      );
+
+      let mut contract = contract;
+      let sharded_append = gas_used(|| {
+          contract.internal_append_ledger_entry(&sharded, LEDGER_LEN);
+          contract.ledger_shards.flush();
+          contract.ledger_lengths.flush();
+      });
+      let legacy_append = gas_used(|| contract.internal_append_ledger_entry(&legacy, LEDGER_LEN));
+      assert_eq!(contract.internal_ledger_len(&legacy), LEDGER_LEN + 1);
+      assert_eq!(contract.internal_ledger_entry_id(&legacy, LEDGER_LEN), Some(LEDGER_LEN));
+      assert!(
+          sharded_append < legacy_append,
+          "Sharded append used {} gas, legacy append {}.", sharded_append, legacy_append
+      );
+  }
+}
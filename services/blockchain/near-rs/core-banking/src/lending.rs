@@ -0,0 +1,380 @@
+// services/blockchain/near-rs/core-banking/src/lending.rs
+use near_sdk::{near, env, AccountId, NearToken};
+use near_sdk::json_types::U128;
+use biocrypticbank_common::assets::AssetId;
+
+use crate::amortization::{AmortizationSchedule, ScheduleTerms};
+use crate::ledger::{EntryDirection, EntryKind};
+use crate::pause::Feature;
+use crate::savings::{BPS_DENOMINATOR, SECONDS_PER_YEAR};
+use crate::{mul_div, BankEvent, BioCrypticBankCore, BioCrypticBankCoreExt, DEFAULT_PAGE_LIMIT};
+
+const NANOS_PER_SECOND: u64 = 1_000_000_000;
+const MAX_LOANS_PER_ACCOUNT: usize = 20;
+
+/// Risk parameters of one collateral asset. Owner controlled.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct CollateralConfig {
+  /// Maximum debt as a share of collateral value when borrowing.
+  pub ltv_bps: u16,
+  /// Share of collateral value the debt may reach before the loan is unhealthy.
+  pub liquidation_threshold_bps: u16,
+}
+
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct LendingConfig {
+  /// Maximum principal outstanding across all loans; zero disables new borrowing.
+  pub borrow_cap: NearToken,
+  /// Largest share of a loan's debt one liquidation may repay.
+  pub close_factor_bps: u16,
+  /// Collateral bonus paid to liquidators on top of the value they repay.
+  pub liquidation_bonus_bps: u16,
+  /// Late fee charged per day on overdue installment principal once the grace period ends.
+  pub daily_late_fee_bps: u16,
+  /// Days an installment may be overdue before late fees accrue.
+  pub grace_days: u32,
+  /// Installments missed, counting the oldest overdue one, that put a loan in default.
+  pub default_after_installments: u32,
+}
+
+impl Default for LendingConfig {
+  fn default() -> Self {
+      Self {
+          borrow_cap: NearToken::from_yoctonear(0),
+          close_factor_bps: 5_000,
+          liquidation_bonus_bps: 500,
+          daily_late_fee_bps: 0,
+          grace_days: 3,
+          default_after_installments: 3,
+      }
+  }
+}
+
+/// NEAR borrowed by `borrower_id` against locked collateral.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct Loan {
+  pub id: u64,
+  pub borrower_id: AccountId,
+  pub collateral_asset: AssetId,
+  pub collateral_amount: U128,
+  pub principal: NearToken,
+  /// Interest accrued and not yet repaid.
+  pub interest: NearToken,
+  pub last_accrued_at: u64,
+  pub opened_at: u64,
+  /// Installment plan, if the loan is amortized.
+  pub schedule: Option<AmortizationSchedule>,
+}
+
+impl Loan {
+  pub(crate) fn debt(&self) -> NearToken {
+      self.principal.saturating_add(self.interest)
+  }
+
+  pub(crate) fn accrue(&mut self, apr_bps: u16, now: u64) {
+      let seconds = now.saturating_sub(self.last_accrued_at) / NANOS_PER_SECOND;
+      if seconds == 0 {
+          return;
+      }
+      let interest = mul_div(
+          self.principal.as_yoctonear(), apr_bps as u128 * seconds as u128, BPS_DENOMINATOR * SECONDS_PER_YEAR,
+      );
+      self.interest = self.interest.saturating_add(NearToken::from_yoctonear(interest));
+      self.last_accrued_at += seconds * NANOS_PER_SECOND;
+  }
+}
+
+#[near(serializers = [json])]
+#[derive(Debug, PartialEq)]
+pub struct LoanView {
+  /// Loan with interest accrued up to now.
+  pub loan: Loan,
+  pub debt: NearToken,
+  pub collateral_value: NearToken,
+  /// Collateral value at the liquidation threshold over debt; 10_000 == 1.0. `None` without debt.
+  pub health_factor_bps: Option<u64>,
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Updates the borrow cap and liquidation parameters. The borrow rate comes from the rate
+  /// model. Owner only.
+  pub fn set_lending_config(&mut self, config: LendingConfig) {
+      self.assert_owner();
+      assert!(config.close_factor_bps > 0 && config.close_factor_bps as u128 <= BPS_DENOMINATOR, "Close factor must be between 0 and 100%.");
+      assert!((config.liquidation_bonus_bps as u128) < BPS_DENOMINATOR, "Liquidation bonus must be below 100%.");
+      assert!(config.daily_late_fee_bps as u128 <= BPS_DENOMINATOR, "Late fee cannot exceed 100% per day.");
+      assert!(config.default_after_installments > 0, "Default threshold must be at least one installment.");
+      self.lending_config = config;
+  }
+
+  /// Accepts `asset` as collateral with `config`. With `None`, new borrowing against the asset
+  /// stops while existing loans keep its liquidation threshold. Owner only.
+  pub fn set_collateral_config(&mut self, asset: AssetId, config: Option<CollateralConfig>) {
+      self.assert_owner();
+      let Some(config) = config else {
+          self.collateral_configs.get_mut(&asset)
+              .unwrap_or_else(|| env::panic_str("Asset is not accepted as collateral."))
+              .ltv_bps = 0;
+          return;
+      };
+      if let AssetId::Ft(token_id) = &asset {
+          assert!(self.token_whitelist.contains(token_id), "Token is not whitelisted.");
+      }
+      assert!(config.ltv_bps <= config.liquidation_threshold_bps, "LTV cannot exceed the liquidation threshold.");
+      assert!((config.liquidation_threshold_bps as u128) < BPS_DENOMINATOR, "Liquidation threshold must be below 100%.");
+      self.collateral_configs.insert(asset, config);
+  }
+
+  /// Locks `collateral_amount` of `collateral_asset` from the caller's balance and borrows
+  /// `borrow_amount` against it, up to the asset's LTV and the caller's credit tier. The loan is credited to the caller's
+  /// balance. With `schedule`, the principal is repaid in equal installments collected by
+  /// `collect_installments`. Returns the loan id.
+  pub fn open_loan(
+      &mut self,
+      collateral_asset: AssetId,
+      collateral_amount: U128,
+      borrow_amount: NearToken,
+      schedule: Option<ScheduleTerms>,
+  ) -> u64 {
+      self.assert_not_paused(Feature::Lending);
+      let initial_storage = env::storage_usage();
+      let borrower_id = env::predecessor_account_id();
+      assert!(collateral_amount.0 > 0, "Collateral amount must be greater than 0.");
+      assert!(borrow_amount.as_yoctonear() > 0, "Borrow amount must be greater than 0.");
+      let config = self.internal_collateral_config(&collateral_asset);
+      let max_borrow = mul_div(
+          self.internal_fresh_collateral_value(&collateral_asset, collateral_amount.0).as_yoctonear(),
+          config.ltv_bps as u128, BPS_DENOMINATOR,
+      );
+      assert!(borrow_amount.as_yoctonear() <= max_borrow, "Borrow amount exceeds the collateral's LTV.");
+      self.assert_within_credit_limit(&borrower_id, borrow_amount);
+      let total_borrowed = self.total_borrowed.saturating_add(borrow_amount);
+      assert!(total_borrowed <= self.lending_config.borrow_cap, "Borrow cap reached.");
+      self.total_borrowed = total_borrowed;
+
+      let id = self.next_loan_id;
+      self.next_loan_id += 1;
+      let loan_ids = self.loans_by_account.entry(borrower_id.clone()).or_default();
+      assert!(loan_ids.len() < MAX_LOANS_PER_ACCOUNT, "Too many open loans.");
+      loan_ids.push(id);
+
+      let reference = Some(format!("loan:{}", id));
+      assert!(
+          self.internal_asset_balance(&borrower_id, &collateral_asset) >= collateral_amount.0,
+          "Insufficient balance for collateral."
+      );
+      self.internal_asset_debit(&borrower_id, &collateral_asset, collateral_amount.0, EntryKind::Loan, reference.clone());
+      self.internal_credit(&borrower_id, borrow_amount);
+      self.internal_add_liabilities(borrow_amount);
+      self.internal_record_entry(&borrower_id, EntryKind::Loan, EntryDirection::Credit, borrow_amount, None, reference);
+
+      let now = env::block_timestamp();
+      self.loans.insert(id, Loan {
+          id,
+          borrower_id: borrower_id.clone(),
+          collateral_asset: collateral_asset.clone(),
+          collateral_amount,
+          principal: borrow_amount,
+          interest: NearToken::from_yoctonear(0),
+          last_accrued_at: now,
+          opened_at: now,
+          schedule: schedule.map(|terms| AmortizationSchedule::new(&terms, borrow_amount, now)),
+      });
+      self.internal_charge_storage(&borrower_id, initial_storage);
+      BankEvent::LoanOpened { id, borrower_id, collateral_asset, collateral_amount, principal: borrow_amount }.emit();
+      id
+  }
+
+  /// Locks additional collateral from the caller's balance into one of their loans.
+  pub fn add_collateral(&mut self, loan_id: u64, amount: U128) {
+      assert!(amount.0 > 0, "Amount must be greater than 0.");
+      let borrower_id = env::predecessor_account_id();
+      let loan = self.internal_get_loan_mut(loan_id);
+      assert_eq!(loan.borrower_id, borrower_id, "Only the borrower can add collateral.");
+      loan.collateral_amount = U128(loan.collateral_amount.0.saturating_add(amount.0));
+      let collateral_asset = loan.collateral_asset.clone();
+      assert!(self.internal_asset_balance(&borrower_id, &collateral_asset) >= amount.0, "Insufficient balance for collateral.");
+      self.internal_asset_debit(&borrower_id, &collateral_asset, amount.0, EntryKind::Loan, Some(format!("loan:{}", loan_id)));
+      BankEvent::LoanCollateralAdded { id: loan_id, amount }.emit();
+  }
+
+  /// Repays up to `amount` of a loan from the caller's balance, interest first. Paying off the
+  /// whole debt closes the loan and releases the collateral to the borrower. Repayments made
+  /// while the loan is not liquidatable earn loyalty points, and closing a loan that way
+  /// improves the credit score. Returns the amount applied.
+  pub fn repay_loan(&mut self, loan_id: u64, amount: NearToken) -> NearToken {
+      let initial_storage = env::storage_usage();
+      let payer_id = env::predecessor_account_id();
+      let apr_bps = self.internal_borrow_apr_bps();
+      let config = self.lending_config.clone();
+      let loan = self.internal_get_loan_mut(loan_id);
+      assert_eq!(loan.borrower_id, payer_id, "Only the borrower can repay the loan.");
+      loan.accrue(apr_bps, env::block_timestamp());
+      loan.accrue_late_fees(&config, env::block_timestamp());
+      let amount = amount.min(loan.debt());
+      assert!(!amount.is_zero(), "Amount must be greater than 0.");
+      let on_time = self.loans.get(&loan_id)
+          .and_then(|loan| self.internal_health_factor_bps(loan))
+          .is_none_or(|health| health as u128 >= BPS_DENOMINATOR);
+
+      self.internal_debit(&payer_id, amount);
+      self.internal_record_entry(
+          &payer_id, EntryKind::Loan, EntryDirection::Debit, amount, None, Some(format!("loan:{}", loan_id)),
+      );
+      self.internal_apply_loan_repayment(loan_id, amount);
+      if on_time {
+          self.internal_award_repayment_points(&payer_id, amount);
+      }
+      let closed = self.loans.get(&loan_id).is_none();
+      self.internal_update_credit(&payer_id, |record| {
+          if !on_time {
+              record.late_repayments += 1;
+          } else if closed {
+              record.on_time_repayments += 1;
+          }
+      });
+      self.internal_charge_storage(&payer_id, initial_storage);
+      amount
+  }
+
+  /// Retrieves a loan with its current debt and health.
+  /// View function.
+  pub fn get_loan(&self, loan_id: u64) -> Option<LoanView> {
+      let mut loan = self.loans.get(&loan_id)?.clone();
+      loan.accrue(self.internal_borrow_apr_bps(), env::block_timestamp());
+      loan.accrue_late_fees(&self.lending_config, env::block_timestamp());
+      let collateral_value = self.internal_collateral_value(&loan.collateral_asset, loan.collateral_amount.0);
+      Some(LoanView {
+          debt: loan.debt(),
+          health_factor_bps: self.internal_health_factor_bps(&loan),
+          collateral_value,
+          loan,
+      })
+  }
+
+  /// Lists the open loans of `account_id`.
+  /// View function.
+  pub fn get_loans_for_account(&self, account_id: AccountId) -> Vec<LoanView> {
+      self.loans_by_account.get(&account_id)
+          .map(|ids| ids.iter().filter_map(|id| self.get_loan(*id)).collect())
+          .unwrap_or_default()
+  }
+
+  /// Pages through every open loan, e.g. to monitor health factors.
+  /// View function.
+  pub fn get_loans(&self, from_index: Option<u64>, limit: Option<u64>) -> Vec<LoanView> {
+      self.loans.keys()
+          .skip(from_index.unwrap_or(0) as usize)
+          .take(limit.unwrap_or(DEFAULT_PAGE_LIMIT) as usize)
+          .filter_map(|id| self.get_loan(*id))
+          .collect()
+  }
+
+  /// View function.
+  pub fn get_collateral_configs(&self) -> Vec<(AssetId, CollateralConfig)> {
+      self.collateral_configs.iter().map(|(asset, config)| (asset.clone(), config.clone())).collect()
+  }
+
+  /// View function.
+  pub fn get_lending_config(&self) -> LendingConfig {
+      self.lending_config.clone()
+  }
+
+  /// Loan principal outstanding across all borrowers.
+  /// View function.
+  pub fn get_total_borrowed(&self) -> NearToken {
+      self.total_borrowed
+  }
+}
+
+impl BioCrypticBankCore {
+  pub(crate) fn internal_get_loan_mut(&mut self, loan_id: u64) -> &mut Loan {
+      self.loans.get_mut(&loan_id).unwrap_or_else(|| env::panic_str("Loan not found."))
+  }
+
+  fn internal_collateral_config(&self, asset: &AssetId) -> CollateralConfig {
+      self.collateral_configs.get(asset).cloned()
+          .unwrap_or_else(|| env::panic_str("Asset is not accepted as collateral."))
+  }
+
+  /// Value of `amount` of `asset` in NEAR at the cached oracle prices, however old; zero
+  /// without prices.
+  pub(crate) fn internal_collateral_value(&self, asset: &AssetId, amount: u128) -> NearToken {
+      self.internal_valued_collateral(asset, amount, false)
+  }
+
+  /// Like `internal_collateral_value`, but panics unless the prices are fresh enough to act on.
+  pub(crate) fn internal_fresh_collateral_value(&self, asset: &AssetId, amount: u128) -> NearToken {
+      self.internal_valued_collateral(asset, amount, true)
+  }
+
+  fn internal_valued_collateral(&self, asset: &AssetId, amount: u128, fresh: bool) -> NearToken {
+      let value = match asset {
+          AssetId::Native => amount,
+          AssetId::Ft(_) => self.internal_near_value(&self.internal_oracle_asset_id(asset), amount, fresh).unwrap_or(0),
+      };
+      NearToken::from_yoctonear(value)
+  }
+
+  /// Health factor of an accrued loan; `None` once the debt is zero.
+  pub(crate) fn internal_health_factor_bps(&self, loan: &Loan) -> Option<u64> {
+      let collateral_value = self.internal_collateral_value(&loan.collateral_asset, loan.collateral_amount.0);
+      self.internal_health_factor_at(loan, collateral_value)
+  }
+
+  /// Health factor of an accrued loan given its collateral's value.
+  pub(crate) fn internal_health_factor_at(&self, loan: &Loan, collateral_value: NearToken) -> Option<u64> {
+      let debt = loan.debt();
+      if debt.is_zero() {
+          return None;
+      }
+      let threshold = self.collateral_configs.get(&loan.collateral_asset)
+          .map_or(0, |config| config.liquidation_threshold_bps as u128);
+      let adjusted = mul_div(collateral_value.as_yoctonear(), threshold, BPS_DENOMINATOR);
+      Some(mul_div(adjusted, BPS_DENOMINATOR, debt.as_yoctonear()).min(u64::MAX as u128) as u64)
+  }
+
+  /// Applies `amount` (already collected from the payer) to an accrued loan, interest first;
+  /// interest is split between savers and the treasury. Closes the loan and returns its collateral to the
+  /// borrower once the debt reaches zero.
+  pub(crate) fn internal_apply_loan_repayment(&mut self, loan_id: u64, amount: NearToken) {
+      let loan = self.internal_get_loan_mut(loan_id);
+      let interest = amount.min(loan.interest);
+      let principal = amount.saturating_sub(interest).min(loan.principal);
+      loan.interest = loan.interest.saturating_sub(interest);
+      loan.principal = loan.principal.saturating_sub(principal);
+      let debt_remaining = loan.debt();
+      let loan = loan.clone();
+      self.total_borrowed = self.total_borrowed.saturating_sub(principal);
+      self.internal_remove_liabilities(principal);
+      self.internal_collect_revenue(interest);
+      BankEvent::LoanRepaid { id: loan_id, interest, principal, debt_remaining }.emit();
+
+      if debt_remaining.is_zero() {
+          self.internal_close_loan(&loan);
+      }
+  }
+
+  /// Returns the remaining collateral to the borrower and any guarantee's locked funds to the
+  /// guarantor, and removes the loan.
+  fn internal_close_loan(&mut self, loan: &Loan) {
+      if loan.collateral_amount.0 > 0 {
+          self.internal_asset_credit(
+              &loan.borrower_id, &loan.collateral_asset, loan.collateral_amount.0, EntryKind::Loan, Some(format!("loan:{}", loan.id)),
+          );
+      }
+      self.loans.remove(&loan.id);
+      if let Some(ids) = self.loans_by_account.get_mut(&loan.borrower_id) {
+          ids.retain(|existing| *existing != loan.id);
+          if ids.is_empty() {
+              self.loans_by_account.remove(&loan.borrower_id);
+          }
+      }
+      self.internal_release_guarantee(loan.id);
+      BankEvent::LoanClosed { id: loan.id, collateral_released: loan.collateral_amount }.emit();
+  }
+}
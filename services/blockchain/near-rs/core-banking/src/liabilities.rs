@@ -0,0 +1,252 @@
+// services/blockchain/near-rs/core-banking/src/liabilities.rs
+use std::collections::BTreeSet;
+
+use near_sdk::{near, env, AccountId, Gas, NearToken, Promise, PromiseError};
+use near_sdk::json_types::U128;
+use biocrypticbank_common::interfaces::ext_ft;
+
+use crate::{BankEvent, BioCrypticBankCore, BioCrypticBankCoreExt};
+
+const GAS_FOR_FT_BALANCE_OF: Gas = Gas::from_tgas(5);
+const GAS_FOR_RESERVE_CALLBACK: Gas = Gas::from_tgas(5);
+
+/// What the contract holds against what it owes its users.
+#[near(serializers = [json])]
+#[derive(Debug, PartialEq)]
+pub struct BalanceSheet {
+  pub contract_balance: NearToken,
+  pub staked: NearToken,
+  pub unstaking: NearToken,
+  pub loans_outstanding: NearToken,
+  pub overdrafts_outstanding: NearToken,
+  /// Part of `contract_balance` locked to pay for contract storage.
+  pub storage_cost: NearToken,
+  /// Checking balances, pots, queued payouts, storage deposits and undistributed yield.
+  pub user_liabilities: NearToken,
+  /// Current value of the savings pool, including accrued interest.
+  pub savings_liabilities: NearToken,
+  pub treasury_balance: NearToken,
+  pub referral_budget: NearToken,
+  /// Bank funds earmarked for insurance claims; part of the surplus but never withdrawable.
+  pub insurance_fund: NearToken,
+  /// Assets minus storage and liabilities; the bank's own funds.
+  pub surplus: NearToken,
+  /// Part of the surplus held liquid in the contract, i.e. what can be paid out.
+  pub withdrawable_surplus: NearToken,
+  /// False if liabilities exceed the assets left after storage.
+  pub invariant_holds: bool,
+}
+
+/// The bank's own token balance at `token_id`, as last read with `refresh_token_reserve`.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct TokenReserve {
+  pub balance: U128,
+  pub checked_at: u64,
+}
+
+/// What the bank owes in one token against what it last read it holds.
+#[near(serializers = [json])]
+#[derive(Debug, PartialEq)]
+pub struct TokenSolvency {
+  pub token_id: AccountId,
+  /// Sum of all accounts' balances of the token.
+  pub liabilities: U128,
+  /// Swap spread the bank collected in the token.
+  pub dex_revenue: U128,
+  pub reserve: Option<TokenReserve>,
+  /// Whether the reserve covers liabilities and revenue; `None` until a reserve is read.
+  pub covered: Option<bool>,
+}
+
+/// Running counters of the bank checked against what it actually holds.
+#[near(serializers = [json])]
+#[derive(Debug, PartialEq)]
+pub struct SolvencyReport {
+  pub balance_sheet: BalanceSheet,
+  /// NEAR deposited into accounts since counting began.
+  pub total_deposits: NearToken,
+  /// Fees and penalties charged since counting began, including the insurance share.
+  pub total_fees_collected: NearToken,
+  pub tokens: Vec<TokenSolvency>,
+  /// The NEAR invariant holds and no read token reserve falls short.
+  pub solvent: bool,
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Everything owed to users.
+  /// View function.
+  pub fn get_total_liabilities(&self) -> NearToken {
+      self.total_liabilities.saturating_add(self.internal_savings_value())
+  }
+
+  /// Contract funds that may leave without touching user money.
+  /// View function.
+  pub fn get_withdrawable_surplus(&self) -> NearToken {
+      self.get_balance_sheet().withdrawable_surplus
+  }
+
+  /// Assets, liabilities and surplus, with a check of the solvency invariant.
+  /// View function.
+  pub fn get_balance_sheet(&self) -> BalanceSheet {
+      let reserves = self.get_reserves();
+      let contract_balance = env::account_balance();
+      let storage_cost = env::storage_byte_cost().saturating_mul(env::storage_usage().into());
+      let savings_liabilities = self.internal_savings_value();
+      let liabilities = self.total_liabilities.saturating_add(savings_liabilities);
+      let assets = contract_balance
+          .saturating_add(reserves.staked)
+          .saturating_add(reserves.unstaking)
+          .saturating_add(self.total_borrowed)
+          .saturating_add(self.total_overdrawn);
+      let committed = storage_cost.saturating_add(liabilities);
+      let surplus = assets.saturating_sub(committed);
+      BalanceSheet {
+          contract_balance,
+          staked: reserves.staked,
+          unstaking: reserves.unstaking,
+          loans_outstanding: self.total_borrowed,
+          overdrafts_outstanding: self.total_overdrawn,
+          storage_cost,
+          user_liabilities: self.total_liabilities,
+          savings_liabilities,
+          treasury_balance: self.treasury_balance,
+          referral_budget: self.referral_budget,
+          insurance_fund: self.insurance_fund,
+          surplus,
+          withdrawable_surplus: surplus.min(contract_balance.saturating_sub(storage_cost)).saturating_sub(self.insurance_fund),
+          invariant_holds: assets >= committed,
+      }
+  }
+
+  /// The balance sheet with deposit and fee counters, and per-token liabilities against
+  /// the reserves last read with `refresh_token_reserve`.
+  /// View function.
+  pub fn get_solvency_report(&self) -> SolvencyReport {
+      let balance_sheet = self.get_balance_sheet();
+      let token_ids: BTreeSet<AccountId> = self.token_whitelist.iter().cloned()
+          .chain(self.token_liabilities.keys().cloned())
+          .collect();
+      let tokens: Vec<TokenSolvency> = token_ids.into_iter().map(|token_id| {
+          let liabilities = self.token_liabilities.get(&token_id).copied().unwrap_or(0);
+          let dex_revenue = self.dex_revenue.get(&token_id).copied().unwrap_or(0);
+          let reserve = self.token_reserves.get(&token_id).cloned();
+          let covered = reserve.as_ref().map(|reserve| reserve.balance.0 >= liabilities.saturating_add(dex_revenue));
+          TokenSolvency { token_id, liabilities: U128(liabilities), dex_revenue: U128(dex_revenue), reserve, covered }
+      }).collect();
+      let solvent = balance_sheet.invariant_holds && tokens.iter().all(|token| token.covered != Some(false));
+      SolvencyReport {
+          balance_sheet,
+          total_deposits: self.total_deposits,
+          total_fees_collected: self.total_fees_collected,
+          tokens,
+          solvent,
+      }
+  }
+
+  /// Panics with the shortfall if the solvency report is not solvent, for integration tests
+  /// and monitors that want a hard failure.
+  /// View function.
+  pub fn assert_solvency(&self) {
+      self.internal_assert_solvent(NearToken::from_yoctonear(0));
+  }
+
+  /// Reads the bank's balance of `token_id` from the token contract and records it as the
+  /// token's reserve for the solvency report. Callable by anyone.
+  pub fn refresh_token_reserve(&mut self, token_id: AccountId) -> Promise {
+      assert!(
+          self.token_whitelist.contains(&token_id) || self.token_liabilities.get(&token_id).is_some(),
+          "Token is not held by the bank."
+      );
+      ext_ft::ext(token_id.clone())
+          .with_static_gas(GAS_FOR_FT_BALANCE_OF)
+          .ft_balance_of(env::current_account_id())
+          .then(
+              Self::ext(env::current_account_id())
+                  .with_static_gas(GAS_FOR_RESERVE_CALLBACK)
+                  .on_token_reserve_read(token_id)
+          )
+  }
+
+  /// Callback recording the token balance read by `refresh_token_reserve`.
+  #[private]
+  pub fn on_token_reserve_read(&mut self, token_id: AccountId, #[callback_result] result: Result<U128, PromiseError>) -> bool {
+      let Ok(balance) = result else {
+          env::log_str("Token balance could not be read; reserve unchanged.");
+          return false;
+      };
+      let liabilities = U128(self.token_liabilities.get(&token_id).copied().unwrap_or(0));
+      self.token_reserves.insert(token_id.clone(), TokenReserve { balance, checked_at: env::block_timestamp() });
+      BankEvent::TokenReserveRead { token_id, balance, liabilities }.emit();
+      true
+  }
+
+  /// Turns on panicking whenever NEAR leaving the contract would break the solvency
+  /// invariant. Meant for test and staging deployments; each payout then pays for a
+  /// balance sheet. Owner only.
+  pub fn set_solvency_checks(&mut self, enabled: bool) {
+      self.assert_owner();
+      self.solvency_checks = enabled;
+      env::log_str(&format!("Solvency checks {}", if enabled { "enabled" } else { "disabled" }));
+  }
+
+  /// View function.
+  pub fn get_solvency_checks(&self) -> bool {
+      self.solvency_checks
+  }
+}
+
+impl BioCrypticBankCore {
+  /// Records `amount` entering user hands: deposits, payouts from bank funds, credit drawn.
+  pub(crate) fn internal_add_liabilities(&mut self, amount: NearToken) {
+      self.total_liabilities = self.total_liabilities.saturating_add(amount);
+  }
+
+  /// Records `amount` leaving user hands: payouts, fees, or repaid credit.
+  pub(crate) fn internal_remove_liabilities(&mut self, amount: NearToken) {
+      self.total_liabilities = self.total_liabilities.saturating_sub(amount);
+  }
+
+  /// Counts `amount` of `token_id` entering account balances.
+  pub(crate) fn internal_add_token_liabilities(&mut self, token_id: &AccountId, amount: u128) {
+      let total = self.token_liabilities.get(token_id).copied().unwrap_or(0).saturating_add(amount);
+      self.token_liabilities.insert(token_id.clone(), total);
+  }
+
+  /// Counts `amount` of `token_id` leaving account balances.
+  pub(crate) fn internal_remove_token_liabilities(&mut self, token_id: &AccountId, amount: u128) {
+      let total = self.token_liabilities.get(token_id).copied().unwrap_or(0).saturating_sub(amount);
+      if total == 0 {
+          self.token_liabilities.remove(token_id);
+      } else {
+          self.token_liabilities.insert(token_id.clone(), total);
+      }
+  }
+
+  /// Panics if the solvency report, with `outflow` already gone from the contract balance,
+  /// is not solvent.
+  pub(crate) fn internal_assert_solvent(&self, outflow: NearToken) {
+      let report = self.get_solvency_report();
+      let sheet = &report.balance_sheet;
+      let assets = sheet.contract_balance.saturating_sub(outflow)
+          .saturating_add(sheet.staked)
+          .saturating_add(sheet.unstaking)
+          .saturating_add(sheet.loans_outstanding)
+          .saturating_add(sheet.overdrafts_outstanding);
+      let committed = sheet.storage_cost.saturating_add(sheet.user_liabilities).saturating_add(sheet.savings_liabilities);
+      assert!(
+          assets >= committed,
+          "Solvency violated: assets {} yoctoNEAR below committed {} yoctoNEAR.",
+          assets.as_yoctonear(), committed.as_yoctonear()
+      );
+      if let Some(token) = report.tokens.iter().find(|token| token.covered == Some(false)) {
+          env::panic_str(&format!("Solvency violated: reserve of {} below its liabilities.", token.token_id));
+      }
+  }
+
+  /// Panics unless `amount` can be paid out of the bank's own funds.
+  pub(crate) fn assert_within_surplus(&self, amount: NearToken) {
+      assert!(amount <= self.get_withdrawable_surplus(), "Amount exceeds the contract's withdrawable surplus.");
+  }
+}
@@ -1,84 +1,1175 @@
-// services/blockchain/near-rs/core-banking/src/lib.rs
-
-use near_sdk::{
-  near, env, BorshStorageKey, PanicOnDefault, AccountId, Promise, NearToken,
-  store::LookupMap
-};
-use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
-
-#[derive(BorshSerialize, BorshDeserialize, BorshStorageKey, Debug)]
-pub enum StorageKey {
-  Balances,
-}
-
-#[near(contract_state)]
-#[derive(PanicOnDefault)]
-pub struct BioCrypticBankCore {
-  pub balances: LookupMap<AccountId, NearToken>,
-  pub owner_id: AccountId,
-}
-
-#[near]
-impl BioCrypticBankCore {
-  /// Initializes the contract with an owner.
-  #[init]
-  pub fn new(owner_id: AccountId) -> Self {
-      Self {
-          balances: LookupMap::new(StorageKey::Balances),
-          owner_id,
-      }
-  }
-
-  /// Allows users to deposit NEAR tokens into their account within the contract.
-  #[payable]
-  pub fn deposit(&mut self) {
-      let account_id = env::predecessor_account_id();
-      let deposit_amount: NearToken = env::attached_deposit();
-      assert!(deposit_amount.as_yoctonear() > 0, "Attached deposit must be greater than 0.");
-
-      let mut balance_yocto = self.balances.get(&account_id).map_or(0, |b| b.as_yoctonear());
-      balance_yocto += deposit_amount.as_yoctonear();
-      self.balances.insert(account_id.clone(), NearToken::from_yoctonear(balance_yocto));
-
-      env::log_str(&format!(
-          "Deposited {} yoctoNEAR to {}'s account. New balance: {}",
-          deposit_amount.as_yoctonear(), account_id, balance_yocto
-      ));
-  }
-
-  /// Allows users to withdraw NEAR tokens from their account in the contract.
-  pub fn withdraw(&mut self, amount: NearToken) -> Promise {
-      let account_id = env::predecessor_account_id();
-      let mut current_balance_yocto = self.balances.get(&account_id)
-          .map_or_else(|| env::panic_str("No balance found for this account."), |b| b.as_yoctonear());
-
-      assert!(amount.as_yoctonear() > 0, "Withdrawal amount must be greater than 0.");
-      assert!(current_balance_yocto >= amount.as_yoctonear(), "Insufficient balance for withdrawal.");
-
-      current_balance_yocto -= amount.as_yoctonear();
-      self.balances.insert(account_id.clone(), NearToken::from_yoctonear(current_balance_yocto));
-
-      env::log_str(&format!(
-          "Withdrawing {} yoctoNEAR from {}'s account. New balance: {}",
-          amount.as_yoctonear(), account_id.clone(), current_balance_yocto
-      ));
-
-      Promise::new(account_id).transfer(amount)
-  }
-
-  /// Retrieves the balance of a specific account.
-  pub fn get_balance(&self, account_id: AccountId) -> NearToken {
-      *self.balances.get(&account_id).unwrap_or(&NearToken::from_yoctonear(0))
-  }
-
-  /// Allows the owner to retrieve accidental deposits or contract fees.
-  #[payable]
-  pub fn owner_withdraw(&mut self, amount: NearToken) -> Promise {
-      assert_eq!(env::predecessor_account_id(), self.owner_id, "Only the owner can call this function.");
-      assert!(amount.as_yoctonear() > 0, "Withdrawal amount must be greater than 0.");
-      assert!(env::account_balance().as_yoctonear() >= amount.as_yoctonear(), "Contract has insufficient balance.");
-
-      env::log_str(&format!("Owner withdrawing {} yoctoNEAR.", amount.as_yoctonear()));
-      Promise::new(self.owner_id.clone()).transfer(amount)
-  }
-}
+// services/blockchain/near-rs/core-banking/src/lib.rs
+
+use near_sdk::{
+  near, env, BorshStorageKey, PanicOnDefault, AccountId, CryptoHash, Gas, Promise, PromiseError, PromiseOrValue, NearToken, PublicKey,
+  store::{IterableMap, IterableSet, LookupMap, Vector}
+};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use biocrypticbank_common::ContractError;
+
+use fees::FeeOperation;
+use kyc::KycPendingAction;
+use ledger::{EntryDirection, EntryKind};
+use ledger_shards::LedgerShardKey;
+
+mod account_closure;
+mod address_book;
+mod allowances;
+mod amortization;
+mod assets;
+mod batch;
+mod change_feed;
+mod charity;
+mod circuit_breaker;
+mod claimable_transfers;
+mod crank_tasks;
+mod credit;
+mod debit_cards;
+mod dex;
+mod dormancy;
+mod envelopes;
+mod escrow;
+mod events;
+mod fees;
+mod fiat;
+mod ft;
+mod gifts;
+mod goals;
+mod governance;
+mod guarantees;
+mod guardians;
+mod inheritance;
+mod insurance;
+mod invoices;
+mod joint_accounts;
+mod kyc;
+mod ledger;
+mod ledger_shards;
+mod lending;
+mod liabilities;
+mod limits;
+mod liquidation;
+mod loyalty;
+mod mandates;
+mod merchants;
+mod migration;
+mod monitoring;
+mod multisig;
+mod nft_credit;
+mod operation_locks;
+mod oracle;
+mod overdraft;
+mod pause;
+mod payment_intents;
+mod pending_transfers;
+mod prize_savings;
+mod profiles;
+mod rate_model;
+mod receipts;
+mod recovery_holds;
+mod referrals;
+mod roles;
+mod round_ups;
+mod savings;
+mod scheduler;
+mod spending_categories;
+mod sponsorship;
+mod staking;
+mod statements;
+mod storage;
+mod streams;
+mod subscriptions;
+mod term_deposits;
+mod withdrawal_authorizations;
+mod withdrawal_timelock;
+mod wnear;
+mod yield_distribution;
+pub use account_closure::ClosedAccount;
+pub use address_book::{AddressBook, TrustedRecipient};
+pub use allowances::Allowance;
+pub use amortization::{AmortizationSchedule, LoanStatus};
+pub use batch::BatchConfig;
+pub use biocrypticbank_common::AssetId;
+pub use change_feed::{BalanceChange, LedgerEntriesPage};
+pub use charity::{Charity, CharityDonation, DonationReceipt, DonationRule};
+pub use circuit_breaker::{CircuitBreakerConfig, CircuitBreakerState, CircuitBreakerView};
+pub use claimable_transfers::ClaimableTransfer;
+pub use crank_tasks::{CrankJob, CrankTask};
+pub use credit::{CreditRecord, CreditTier};
+pub use debit_cards::{DebitCard, DebitCardLimits};
+pub use dex::{DexConfig, SwapAction};
+pub use dormancy::{DormancyConfig, DormancyStatus};
+pub use envelopes::Envelope;
+pub use escrow::Escrow;
+pub use events::BankEvent;
+pub use fees::{FeeSchedule, FeeSweepConfig, PendingFeeSchedule};
+pub use fiat::FiatLimitsView;
+pub use gifts::Gift;
+pub use goals::SavingsGoal;
+pub use guarantees::{Guarantee, GuaranteeStatus};
+pub use guardians::{GuardedAction, GuardianPolicy, PendingGuardianThreshold};
+pub use inheritance::{Beneficiary, InheritanceClaim, InheritancePlan};
+pub use insurance::{ClaimStatus, CoveredEvent, InsuranceClaim};
+pub use invoices::Invoice;
+pub use joint_accounts::{JointAccount, JointProposal};
+pub use kyc::{CachedKycTier, KycConfig, OnboardingPolicy};
+pub use ledger::LedgerEntry;
+pub use lending::{CollateralConfig, LendingConfig, Loan};
+pub use liabilities::{BalanceSheet, SolvencyReport, TokenReserve, TokenSolvency};
+pub use limits::{OutflowWindow, SpendingLimits};
+pub use loyalty::{LoyaltyAccount, LoyaltyConfig};
+pub use mandates::Mandate;
+pub use merchants::{Merchant, MerchantOrder, MerchantSettlement, OrderApproval, OrderStatus};
+pub use migration::{Account, AccountV1, VersionedAccount, VersionedState, STATE_VERSION};
+pub use monitoring::{CountWindow, MonitoringActivity, MonitoringRule, MonitoringRules};
+pub use multisig::{MultisigAction, MultisigChangeProposal, MultisigConfig, ProposalStatus, WithdrawalProposal};
+pub use nft_credit::{NftAppraisal, NftAuction, NftCreditConfig, NftCreditLine, NftCreditStatus};
+pub use operation_locks::{LockedOperation, OperationLock};
+pub use oracle::{CachedPrice, OracleConfig, Price};
+pub use overdraft::{Overdraft, OverdraftSettings};
+pub use pause::{Feature, PauseState};
+pub use payment_intents::{IntentStatus, PaymentIntent};
+pub use pending_transfers::{PendingTransfer, TransferDelayRule};
+pub use prize_savings::{PendingPrizeDraw, PrizeDraw, PrizePool, PrizePoolView, PrizeSaver, PrizeSaverView, PrizeSavingsConfig, RandomnessSource};
+pub use profiles::{AccountProfile, Contact};
+pub use rate_model::{LendingRateModel, PendingRateModel, RateModel};
+pub use receipts::{ChainLink, LedgerChainHead, PaymentReceipt};
+pub use recovery_holds::RecoveryHold;
+pub use referrals::{ReferralConfig, ReferrerStats};
+pub use roles::Role;
+pub use round_ups::{RoundUpSettings, RoundUpStats, RoundUpTarget};
+pub use savings::{SavingsAccount, SavingsPool};
+pub use scheduler::StandingOrder;
+pub use spending_categories::{CategoryRule, CategorySpend, MonthlySpending};
+pub use sponsorship::{DelegateActionView, DelegateFunctionCall, SponsorshipConfig, SponsorshipDecision, SponsorshipUsage};
+pub use staking::{PoolPosition, QueuedWithdrawal, StakingConfig};
+pub use storage::StorageAccount;
+pub use streams::Stream;
+pub use subscriptions::{BillingSummary, Subscription, SubscriptionPlan, SubscriptionStatus};
+pub use term_deposits::TermDeposit;
+pub use withdrawal_authorizations::WithdrawalAuthorization;
+pub use withdrawal_timelock::{TimelockedWithdrawal, WithdrawalTimelockConfig};
+pub use wnear::NearHoldings;
+pub use yield_distribution::YieldConfig;
+
+pub(crate) const MAX_MEMO_LENGTH: usize = 256;
+pub(crate) const MAX_REFERENCE_LENGTH: usize = 64;
+const DEFAULT_PAGE_LIMIT: u64 = 50;
+const GAS_FOR_WITHDRAW_CALLBACK: Gas = Gas::from_tgas(10);
+
+#[derive(BorshSerialize, BorshDeserialize, BorshStorageKey, Debug)]
+pub enum StorageKey {
+  Balances,
+  TokenWhitelist,
+  FtBalances,
+  SavingsAccounts,
+  TermRates,
+  TermDeposits,
+  TermDepositsByAccount,
+  StandingOrders,
+  StandingOrdersByAccount,
+  LedgerEntries,
+  /// Retired with per-account `Vector` ledgers; kept so later prefixes do not move.
+  AccountLedgers,
+  /// Retired with per-account `Vector` ledgers; kept so later prefixes do not move.
+  AccountLedger { account_id_hash: Vec<u8> },
+  Invoices,
+  PendingInvoices,
+  JointAccounts,
+  JointAccountsByOwner,
+  JointProposals,
+  Roles,
+  SpendingLimits,
+  StorageAccounts,
+  Escrows,
+  OpenEscrowsByAccount,
+  Streams,
+  OutgoingStreams,
+  IncomingStreams,
+  Allowances,
+  SpendersByOwner,
+  Mandates,
+  MandatesByPayer,
+  MandatesByMerchant,
+  Envelopes,
+  Overdrafts,
+  KycTiers,
+  Loans,
+  LoansByAccount,
+  CollateralConfigs,
+  Prices,
+  StakingPools,
+  QueuedWithdrawals,
+  Referrers,
+  ReferrerStats,
+  LoyaltyAccounts,
+  KycCache,
+  KycUsage,
+  PaymentsByReference,
+  InheritancePlans,
+  LastActivity,
+  DormancyStatuses,
+  EscheatedBalances,
+  Gifts,
+  GiftsBySender,
+  Merchants,
+  MerchantApprovals,
+  MerchantOrders,
+  MerchantOrderIdsByMerchant,
+  MerchantOrderIds { merchant_id_hash: Vec<u8> },
+  MerchantSettlements,
+  MerchantSettlementHistory { merchant_id_hash: Vec<u8> },
+  PaymentIntents,
+  SubscriptionPlans,
+  Subscriptions,
+  SubscriptionsByAccount,
+  MerchantHeldOrders,
+  MonitoringActivity,
+  MonitoringOverrides,
+  GuardianPolicies,
+  GuardedActions,
+  GuardedActionsByAccount,
+  AddressBooks,
+  TrustedRecipients,
+  TimelockedWithdrawals,
+  TimelockedWithdrawalsByAccount,
+  MultisigAdmins,
+  WithdrawalProposals,
+  ReferenceCurrencies,
+  DexPools,
+  DexRevenue,
+  SavingsGoals,
+  RoundUpSettings,
+  RoundUpStats,
+  InsuranceClaims,
+  OpenInsuranceClaims,
+  CreditRecords,
+  LoanGuarantees,
+  GuaranteesByGuarantor,
+  NftCollections,
+  NftAppraisals,
+  NftCreditLines,
+  NftCreditLinesByAccount,
+  RecoveryHolds,
+  Accounts,
+  ClaimableTransfers,
+  ClaimableTransfersByAccount,
+  PendingTransfers,
+  PendingTransfersByAccount,
+  TransferDelays,
+  LedgerChainLinks,
+  LedgerBlocks,
+  Profiles,
+  Contacts,
+  SponsorshipUsage,
+  DebitCards,
+  DebitCardsByAccount,
+  PrizeSavers,
+  PrizeDraws,
+  ClosedAccounts,
+  OperationLocks,
+  LedgerLengths,
+  LedgerShards,
+  AuthorizationNonces,
+  TokenLiabilities,
+  TokenReserves,
+  CrankTasks,
+  CategoryRules,
+  EntryCategories,
+  CategorySpending,
+  SpendingMonths,
+  Charities,
+  DonationRules,
+  DonationRecords,
+  DonationYears,
+  MultisigChanges,
+}
+
+#[near(contract_state)]
+#[derive(PanicOnDefault)]
+pub struct BioCrypticBankCore {
+  /// Versioned per-account records.
+  pub balances: LookupMap<AccountId, VersionedAccount>,
+  /// Balances stored before accounts were versioned; moved into `balances` on first write.
+  pub legacy_balances: LookupMap<AccountId, NearToken>,
+  pub owner_id: AccountId,
+  pub token_whitelist: IterableSet<AccountId>,
+  /// NEP-141 balances keyed by `(account_id, token_contract_id)`.
+  pub ft_balances: LookupMap<(AccountId, AccountId), u128>,
+  pub savings_pool: SavingsPool,
+  pub savings_accounts: LookupMap<AccountId, SavingsAccount>,
+  /// Term length in days -> APR in basis points.
+  pub term_rates: IterableMap<u32, u16>,
+  pub early_withdrawal_penalty_bps: u16,
+  pub term_deposits: LookupMap<u64, TermDeposit>,
+  pub term_deposits_by_account: LookupMap<AccountId, Vec<u64>>,
+  pub next_term_deposit_id: u64,
+  /// Fees and penalties collected by the bank, withdrawable through multisig proposals.
+  pub treasury_balance: NearToken,
+  pub standing_orders: IterableMap<u64, StandingOrder>,
+  pub standing_orders_by_account: LookupMap<AccountId, Vec<u64>>,
+  pub next_standing_order_id: u64,
+  pub ledger_entries: LookupMap<u64, LedgerEntry>,
+  pub next_ledger_entry_id: u64,
+  pub invoices: LookupMap<u64, Invoice>,
+  /// Payer -> ids of invoices awaiting payment.
+  pub pending_invoices: LookupMap<AccountId, Vec<u64>>,
+  pub next_invoice_id: u64,
+  pub joint_accounts: LookupMap<u64, JointAccount>,
+  pub joint_accounts_by_owner: LookupMap<AccountId, Vec<u64>>,
+  pub next_joint_account_id: u64,
+  pub joint_proposals: LookupMap<u64, JointProposal>,
+  pub next_joint_proposal_id: u64,
+  pub roles: LookupMap<AccountId, Vec<Role>>,
+  pub spending_limits: LookupMap<AccountId, SpendingLimits>,
+  pub pause_state: PauseState,
+  /// NEP-145 registrations and the storage each account has paid for.
+  pub storage_accounts: LookupMap<AccountId, StorageAccount>,
+  pub batch_config: BatchConfig,
+  pub next_batch_id: u64,
+  pub escrows: LookupMap<u64, Escrow>,
+  /// Party (payer, payee or arbiter) -> ids of escrows not yet settled.
+  pub open_escrows_by_account: LookupMap<AccountId, Vec<u64>>,
+  pub next_escrow_id: u64,
+  pub streams: LookupMap<u64, Stream>,
+  pub outgoing_streams: LookupMap<AccountId, Vec<u64>>,
+  pub incoming_streams: LookupMap<AccountId, Vec<u64>>,
+  pub next_stream_id: u64,
+  /// Allowances keyed by `(owner_id, spender_id)`.
+  pub allowances: LookupMap<(AccountId, AccountId), Allowance>,
+  pub spenders_by_owner: LookupMap<AccountId, Vec<AccountId>>,
+  pub mandates: LookupMap<u64, Mandate>,
+  pub mandates_by_payer: LookupMap<AccountId, Vec<u64>>,
+  pub mandates_by_merchant: LookupMap<AccountId, Vec<u64>>,
+  pub next_mandate_id: u64,
+  pub envelopes: LookupMap<AccountId, Vec<Envelope>>,
+  pub overdrafts: LookupMap<AccountId, Overdraft>,
+  pub overdraft_settings: OverdraftSettings,
+  /// Overdraft principal outstanding across all accounts.
+  pub total_overdrawn: NearToken,
+  /// Verified KYC tier per account; absent means tier 0.
+  pub kyc_tiers: LookupMap<AccountId, u8>,
+  pub lending_config: LendingConfig,
+  pub collateral_configs: IterableMap<AssetId, CollateralConfig>,
+  pub loans: IterableMap<u64, Loan>,
+  pub loans_by_account: LookupMap<AccountId, Vec<u64>>,
+  pub next_loan_id: u64,
+  /// Loan principal outstanding across all borrowers.
+  pub total_borrowed: NearToken,
+  pub oracle_config: OracleConfig,
+  /// Oracle asset id -> last fetched USD price.
+  pub prices: LookupMap<String, CachedPrice>,
+  pub staking_config: StakingConfig,
+  pub staking_pools: IterableMap<AccountId, PoolPosition>,
+  /// Withdrawals waiting for liquidity, paid in id order from `withdrawal_queue_head`.
+  pub queued_withdrawals: LookupMap<u64, QueuedWithdrawal>,
+  pub withdrawal_queue_head: u64,
+  pub next_queued_withdrawal_id: u64,
+  pub queued_withdrawals_total: NearToken,
+  pub yield_config: YieldConfig,
+  /// Revenue set aside for savers awaiting the next distribution.
+  pub undistributed_yield: NearToken,
+  /// Cumulative yield per savings share, scaled by `INDEX_SCALE`.
+  pub yield_per_share: u128,
+  pub last_yield_distribution_at: u64,
+  pub fee_schedule: FeeSchedule,
+  /// Fee schedule proposed by the owner and waiting out its timelock.
+  pub pending_fee_schedule: Option<PendingFeeSchedule>,
+  /// Referee -> referrer.
+  pub referrers: LookupMap<AccountId, AccountId>,
+  pub referrer_stats: LookupMap<AccountId, ReferrerStats>,
+  pub referral_config: ReferralConfig,
+  /// Funds set aside from the treasury to pay referral rewards.
+  pub referral_budget: NearToken,
+  pub loyalty_accounts: LookupMap<AccountId, LoyaltyAccount>,
+  pub loyalty_config: LoyaltyConfig,
+  pub kyc_config: KycConfig,
+  /// Tiers last read from the DID registry.
+  pub kyc_cache: LookupMap<AccountId, CachedKycTier>,
+  /// Daily deposit, withdrawal and transfer volume counted against KYC tier limits.
+  pub kyc_usage: LookupMap<AccountId, OutflowWindow>,
+  /// `(merchant_id, reference)` -> credit entry ids of payments received under it.
+  pub payments_by_reference: LookupMap<(AccountId, String), Vec<u64>>,
+  pub inheritance_plans: LookupMap<AccountId, InheritancePlan>,
+  /// Time of each account's last own activity.
+  pub last_activity: LookupMap<AccountId, u64>,
+  pub dormancy_config: DormancyConfig,
+  /// Last non-active status announced by `update_dormancy`.
+  pub dormancy_statuses: LookupMap<AccountId, DormancyStatus>,
+  /// Abandoned balances held in custody for their holders, outside the treasury.
+  pub escheated_balances: LookupMap<AccountId, NearToken>,
+  pub total_escheated: NearToken,
+  /// Open gifts keyed by their claim key.
+  pub gifts: LookupMap<PublicKey, Gift>,
+  pub gifts_by_sender: LookupMap<AccountId, Vec<PublicKey>>,
+  pub merchants: LookupMap<AccountId, Merchant>,
+  /// Customer approvals keyed by `(merchant_id, order_id)`.
+  pub merchant_approvals: LookupMap<(AccountId, String), OrderApproval>,
+  /// Paid orders keyed by `(merchant_id, order_id)`.
+  pub merchant_orders: LookupMap<(AccountId, String), MerchantOrder>,
+  pub merchant_order_ids: LookupMap<AccountId, Vector<String>>,
+  pub merchant_settlements: LookupMap<AccountId, Vector<MerchantSettlement>>,
+  pub payment_intents: LookupMap<u64, PaymentIntent>,
+  pub next_payment_intent_id: u64,
+  pub subscription_plans: LookupMap<u64, SubscriptionPlan>,
+  pub next_subscription_plan_id: u64,
+  pub subscriptions: IterableMap<u64, Subscription>,
+  pub subscriptions_by_account: LookupMap<AccountId, Vec<u64>>,
+  pub next_subscription_id: u64,
+  /// Order ids of charges on hold, per merchant.
+  pub merchant_held_orders: LookupMap<AccountId, Vec<String>>,
+  pub monitoring_rules: MonitoringRules,
+  pub monitoring_activity: LookupMap<AccountId, MonitoringActivity>,
+  /// Expiry (ns) of compliance overrides letting soft-blocked accounts transact.
+  pub monitoring_overrides: LookupMap<AccountId, u64>,
+  /// Account recovery contract holding each user's guardians.
+  pub recovery_contract_id: Option<AccountId>,
+  pub guardian_policies: LookupMap<AccountId, GuardianPolicy>,
+  pub guarded_actions: LookupMap<u64, GuardedAction>,
+  pub guarded_actions_by_account: LookupMap<AccountId, Vec<u64>>,
+  pub next_guarded_action_id: u64,
+  pub address_books: LookupMap<AccountId, AddressBook>,
+  pub trusted_recipients: LookupMap<AccountId, Vec<TrustedRecipient>>,
+  pub withdrawal_timelock_config: WithdrawalTimelockConfig,
+  pub timelocked_withdrawals: IterableMap<u64, TimelockedWithdrawal>,
+  pub timelocked_withdrawals_by_account: LookupMap<AccountId, Vec<u64>>,
+  pub next_timelocked_withdrawal_id: u64,
+  /// Admins who propose and approve withdrawals of contract funds.
+  pub multisig_admins: IterableSet<AccountId>,
+  pub multisig_config: MultisigConfig,
+  pub withdrawal_proposals: IterableMap<u64, WithdrawalProposal>,
+  pub next_withdrawal_proposal_id: u64,
+  /// NEAR owed to users outside the savings pool, whose value is computed from its index.
+  pub total_liabilities: NearToken,
+  /// Wrapped NEAR contract used by `wrap_near` and `unwrap_near`.
+  pub wnear_contract_id: Option<AccountId>,
+  /// Fiat currency code -> oracle asset id quoting its USD value.
+  pub reference_currencies: IterableMap<String, String>,
+  pub dex_config: DexConfig,
+  /// Sorted token pair -> exchange pool routing swaps between them.
+  pub dex_pools: LookupMap<(AccountId, AccountId), u64>,
+  /// Exchange spread collected per output token.
+  pub dex_revenue: LookupMap<AccountId, u128>,
+  pub savings_goals: LookupMap<AccountId, Vec<SavingsGoal>>,
+  pub goal_penalty_bps: u16,
+  pub round_up_settings: LookupMap<AccountId, RoundUpSettings>,
+  pub round_up_stats: LookupMap<AccountId, RoundUpStats>,
+  pub insurance_fund: NearToken,
+  pub insurance_fee_share_bps: u16,
+  pub insurance_claims: IterableMap<u64, InsuranceClaim>,
+  pub open_insurance_claims: LookupMap<AccountId, Vec<u64>>,
+  pub next_insurance_claim_id: u64,
+  pub rate_model: RateModel,
+  pub pending_rate_model: Option<PendingRateModel>,
+  pub credit_records: LookupMap<AccountId, CreditRecord>,
+  pub credit_tiers: Vec<CreditTier>,
+  pub loan_guarantees: LookupMap<u64, Guarantee>,
+  pub guarantees_by_guarantor: LookupMap<AccountId, Vec<u64>>,
+  pub nft_collections: IterableMap<AccountId, u16>,
+  /// Appraisals keyed by `(nft_contract_id, token_id)`, consumed by the next pledge.
+  pub nft_appraisals: LookupMap<(AccountId, String), NftAppraisal>,
+  pub nft_credit_lines: IterableMap<u64, NftCreditLine>,
+  pub nft_credit_lines_by_account: LookupMap<AccountId, Vec<u64>>,
+  pub next_nft_credit_line_id: u64,
+  pub nft_credit_config: NftCreditConfig,
+  /// Accounts whose outgoing funds are frozen by a pending recovery.
+  pub recovery_holds: LookupMap<AccountId, RecoveryHold>,
+  /// Layout version of the stored state; see `migrate`.
+  pub state_version: u16,
+  pub circuit_breaker_config: CircuitBreakerConfig,
+  pub circuit_breaker: CircuitBreakerState,
+  pub claimable_transfers: IterableMap<u64, ClaimableTransfer>,
+  /// Unclaimed transfers by sender and by receiver.
+  pub claimable_transfers_by_account: LookupMap<AccountId, Vec<u64>>,
+  pub next_claimable_transfer_id: u64,
+  pub transfer_delay_rule: TransferDelayRule,
+  pub transfer_delays: LookupMap<AccountId, u64>,
+  pub pending_transfers: IterableMap<u64, PendingTransfer>,
+  pub pending_transfers_by_account: LookupMap<AccountId, Vec<u64>>,
+  pub next_pending_transfer_id: u64,
+  pub ledger_chain_links: LookupMap<u64, ChainLink>,
+  /// Hash of the latest ledger entry, chained over every entry since receipts were added.
+  pub ledger_chain_head: CryptoHash,
+  pub ledger_chain_length: u64,
+  /// `(block_height, first_entry_id)` of every block that booked ledger entries.
+  pub ledger_blocks: Vector<(u64, u64)>,
+  pub profiles: LookupMap<AccountId, AccountProfile>,
+  pub contacts: LookupMap<AccountId, Vec<Contact>>,
+  pub sponsorship_config: SponsorshipConfig,
+  /// NEAR set aside by the owner to pay storage for sponsored registrations.
+  pub sponsorship_pool: NearToken,
+  pub sponsorship_usage: LookupMap<AccountId, SponsorshipUsage>,
+  pub debit_cards: LookupMap<PublicKey, DebitCard>,
+  pub debit_cards_by_account: LookupMap<AccountId, Vec<PublicKey>>,
+  /// Destination and retention buffer of `sweep_fees`.
+  pub fee_sweep_config: FeeSweepConfig,
+  pub prize_savings_config: PrizeSavingsConfig,
+  pub prize_pool: PrizePool,
+  pub prize_savers: IterableMap<AccountId, PrizeSaver>,
+  /// Closed epoch being drawn; entering and leaving the prize pool waits for it.
+  pub pending_prize_draw: Option<PendingPrizeDraw>,
+  pub prize_draws: Vector<PrizeDraw>,
+  /// Ledger pointer and payout of accounts closed with `close_account`.
+  pub closed_accounts: LookupMap<AccountId, ClosedAccount>,
+  /// In-flight asynchronous operation of each account.
+  pub operation_locks: LookupMap<AccountId, OperationLock>,
+  pub next_operation_lock_id: u64,
+  /// Number of ledger entries of each account.
+  pub ledger_lengths: LookupMap<AccountId, u64>,
+  /// Per-account ledger entry ids in chronological order, `LEDGER_SHARD_SIZE` per shard.
+  pub ledger_shards: LookupMap<LedgerShardKey, Vec<u64>>,
+  /// Last withdrawal authorization nonce used by each account. Kept across unregistration
+  /// so signed authorizations cannot be replayed after re-registering.
+  pub authorization_nonces: LookupMap<AccountId, u64>,
+  /// NEAR credited by deposits since counting began.
+  pub total_deposits: NearToken,
+  /// Fees and penalties charged since counting began.
+  pub total_fees_collected: NearToken,
+  /// Sum of all accounts' balances of each NEP-141 token.
+  pub token_liabilities: IterableMap<AccountId, u128>,
+  /// Last read balance of the bank at each token contract.
+  pub token_reserves: LookupMap<AccountId, TokenReserve>,
+  /// Panic on payouts that would break the solvency invariant.
+  pub solvency_checks: bool,
+  /// Recurring jobs dispatched by `run_due_crank_tasks`.
+  pub crank_tasks: IterableMap<u64, CrankTask>,
+  pub next_crank_task_id: u64,
+  /// Taxonomy ledger entries can be filed under.
+  pub spending_categories: Vec<String>,
+  /// Per-account categories of outgoing entries by counterparty.
+  pub category_rules: LookupMap<AccountId, Vec<CategoryRule>>,
+  pub entry_categories: LookupMap<u64, String>,
+  /// Outgoing totals per category keyed by `(account_id, yyyymm)`.
+  pub category_spending: LookupMap<(AccountId, u32), Vec<CategorySpend>>,
+  /// Months with category totals of each account, ascending.
+  pub spending_months: LookupMap<AccountId, Vec<u32>>,
+  /// Verified charities donations can be sent to.
+  pub charities: IterableMap<AccountId, Charity>,
+  /// Bank funds set aside for matching gifts, funded from the treasury.
+  pub matching_pool: NearToken,
+  pub donation_rules: LookupMap<AccountId, DonationRule>,
+  /// Per-charity giving keyed by `(donor_id, year)`.
+  pub donation_records: LookupMap<(AccountId, u32), Vec<CharityDonation>>,
+  /// Years with donation records of each donor, ascending.
+  pub donation_years: LookupMap<AccountId, Vec<u32>>,
+  /// Governance contract allowed to change protocol parameters alongside the owner.
+  pub governance_id: Option<AccountId>,
+  /// Changes to the multisig admins and rules, keyed by id.
+  pub multisig_changes: IterableMap<u64, MultisigChangeProposal>,
+  pub next_multisig_change_id: u64,
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Initializes the contract with an owner.
+  #[init]
+  pub fn new(owner_id: AccountId) -> Self {
+      Self {
+          balances: LookupMap::new(StorageKey::Accounts),
+          legacy_balances: LookupMap::new(StorageKey::Balances),
+          owner_id,
+          token_whitelist: IterableSet::new(StorageKey::TokenWhitelist),
+          ft_balances: LookupMap::new(StorageKey::FtBalances),
+          savings_pool: SavingsPool::default(),
+          savings_accounts: LookupMap::new(StorageKey::SavingsAccounts),
+          term_rates: IterableMap::new(StorageKey::TermRates),
+          early_withdrawal_penalty_bps: 0,
+          term_deposits: LookupMap::new(StorageKey::TermDeposits),
+          term_deposits_by_account: LookupMap::new(StorageKey::TermDepositsByAccount),
+          next_term_deposit_id: 0,
+          treasury_balance: NearToken::from_yoctonear(0),
+          standing_orders: IterableMap::new(StorageKey::StandingOrders),
+          standing_orders_by_account: LookupMap::new(StorageKey::StandingOrdersByAccount),
+          next_standing_order_id: 0,
+          ledger_entries: LookupMap::new(StorageKey::LedgerEntries),
+          next_ledger_entry_id: 0,
+          invoices: LookupMap::new(StorageKey::Invoices),
+          pending_invoices: LookupMap::new(StorageKey::PendingInvoices),
+          next_invoice_id: 0,
+          joint_accounts: LookupMap::new(StorageKey::JointAccounts),
+          joint_accounts_by_owner: LookupMap::new(StorageKey::JointAccountsByOwner),
+          next_joint_account_id: 0,
+          joint_proposals: LookupMap::new(StorageKey::JointProposals),
+          next_joint_proposal_id: 0,
+          roles: LookupMap::new(StorageKey::Roles),
+          spending_limits: LookupMap::new(StorageKey::SpendingLimits),
+          pause_state: PauseState::default(),
+          storage_accounts: LookupMap::new(StorageKey::StorageAccounts),
+          batch_config: BatchConfig::default(),
+          next_batch_id: 0,
+          escrows: LookupMap::new(StorageKey::Escrows),
+          open_escrows_by_account: LookupMap::new(StorageKey::OpenEscrowsByAccount),
+          next_escrow_id: 0,
+          streams: LookupMap::new(StorageKey::Streams),
+          outgoing_streams: LookupMap::new(StorageKey::OutgoingStreams),
+          incoming_streams: LookupMap::new(StorageKey::IncomingStreams),
+          next_stream_id: 0,
+          allowances: LookupMap::new(StorageKey::Allowances),
+          spenders_by_owner: LookupMap::new(StorageKey::SpendersByOwner),
+          mandates: LookupMap::new(StorageKey::Mandates),
+          mandates_by_payer: LookupMap::new(StorageKey::MandatesByPayer),
+          mandates_by_merchant: LookupMap::new(StorageKey::MandatesByMerchant),
+          next_mandate_id: 0,
+          envelopes: LookupMap::new(StorageKey::Envelopes),
+          overdrafts: LookupMap::new(StorageKey::Overdrafts),
+          overdraft_settings: OverdraftSettings::default(),
+          total_overdrawn: NearToken::from_yoctonear(0),
+          kyc_tiers: LookupMap::new(StorageKey::KycTiers),
+          lending_config: LendingConfig::default(),
+          collateral_configs: IterableMap::new(StorageKey::CollateralConfigs),
+          loans: IterableMap::new(StorageKey::Loans),
+          loans_by_account: LookupMap::new(StorageKey::LoansByAccount),
+          next_loan_id: 0,
+          total_borrowed: NearToken::from_yoctonear(0),
+          oracle_config: OracleConfig::default(),
+          prices: LookupMap::new(StorageKey::Prices),
+          staking_config: StakingConfig::default(),
+          staking_pools: IterableMap::new(StorageKey::StakingPools),
+          queued_withdrawals: LookupMap::new(StorageKey::QueuedWithdrawals),
+          withdrawal_queue_head: 0,
+          next_queued_withdrawal_id: 0,
+          queued_withdrawals_total: NearToken::from_yoctonear(0),
+          yield_config: YieldConfig::default(),
+          undistributed_yield: NearToken::from_yoctonear(0),
+          yield_per_share: 0,
+          last_yield_distribution_at: 0,
+          fee_schedule: FeeSchedule::default(),
+          pending_fee_schedule: None,
+          referrers: LookupMap::new(StorageKey::Referrers),
+          referrer_stats: LookupMap::new(StorageKey::ReferrerStats),
+          referral_config: ReferralConfig::default(),
+          referral_budget: NearToken::from_yoctonear(0),
+          loyalty_accounts: LookupMap::new(StorageKey::LoyaltyAccounts),
+          loyalty_config: LoyaltyConfig::default(),
+          kyc_config: KycConfig::default(),
+          kyc_cache: LookupMap::new(StorageKey::KycCache),
+          kyc_usage: LookupMap::new(StorageKey::KycUsage),
+          payments_by_reference: LookupMap::new(StorageKey::PaymentsByReference),
+          inheritance_plans: LookupMap::new(StorageKey::InheritancePlans),
+          last_activity: LookupMap::new(StorageKey::LastActivity),
+          dormancy_config: DormancyConfig::default(),
+          dormancy_statuses: LookupMap::new(StorageKey::DormancyStatuses),
+          escheated_balances: LookupMap::new(StorageKey::EscheatedBalances),
+          total_escheated: NearToken::from_yoctonear(0),
+          gifts: LookupMap::new(StorageKey::Gifts),
+          gifts_by_sender: LookupMap::new(StorageKey::GiftsBySender),
+          merchants: LookupMap::new(StorageKey::Merchants),
+          merchant_approvals: LookupMap::new(StorageKey::MerchantApprovals),
+          merchant_orders: LookupMap::new(StorageKey::MerchantOrders),
+          merchant_order_ids: LookupMap::new(StorageKey::MerchantOrderIdsByMerchant),
+          merchant_settlements: LookupMap::new(StorageKey::MerchantSettlements),
+          payment_intents: LookupMap::new(StorageKey::PaymentIntents),
+          next_payment_intent_id: 0,
+          subscription_plans: LookupMap::new(StorageKey::SubscriptionPlans),
+          next_subscription_plan_id: 0,
+          subscriptions: IterableMap::new(StorageKey::Subscriptions),
+          subscriptions_by_account: LookupMap::new(StorageKey::SubscriptionsByAccount),
+          next_subscription_id: 0,
+          merchant_held_orders: LookupMap::new(StorageKey::MerchantHeldOrders),
+          monitoring_rules: MonitoringRules::default(),
+          monitoring_activity: LookupMap::new(StorageKey::MonitoringActivity),
+          monitoring_overrides: LookupMap::new(StorageKey::MonitoringOverrides),
+          recovery_contract_id: None,
+          guardian_policies: LookupMap::new(StorageKey::GuardianPolicies),
+          guarded_actions: LookupMap::new(StorageKey::GuardedActions),
+          guarded_actions_by_account: LookupMap::new(StorageKey::GuardedActionsByAccount),
+          next_guarded_action_id: 0,
+          address_books: LookupMap::new(StorageKey::AddressBooks),
+          trusted_recipients: LookupMap::new(StorageKey::TrustedRecipients),
+          withdrawal_timelock_config: WithdrawalTimelockConfig::default(),
+          timelocked_withdrawals: IterableMap::new(StorageKey::TimelockedWithdrawals),
+          timelocked_withdrawals_by_account: LookupMap::new(StorageKey::TimelockedWithdrawalsByAccount),
+          next_timelocked_withdrawal_id: 0,
+          multisig_admins: IterableSet::new(StorageKey::MultisigAdmins),
+          multisig_config: MultisigConfig::default(),
+          withdrawal_proposals: IterableMap::new(StorageKey::WithdrawalProposals),
+          next_withdrawal_proposal_id: 0,
+          total_liabilities: NearToken::from_yoctonear(0),
+          wnear_contract_id: None,
+          reference_currencies: IterableMap::new(StorageKey::ReferenceCurrencies),
+          dex_config: DexConfig::default(),
+          dex_pools: LookupMap::new(StorageKey::DexPools),
+          dex_revenue: LookupMap::new(StorageKey::DexRevenue),
+          savings_goals: LookupMap::new(StorageKey::SavingsGoals),
+          goal_penalty_bps: 0,
+          round_up_settings: LookupMap::new(StorageKey::RoundUpSettings),
+          round_up_stats: LookupMap::new(StorageKey::RoundUpStats),
+          insurance_fund: NearToken::from_yoctonear(0),
+          insurance_fee_share_bps: 0,
+          insurance_claims: IterableMap::new(StorageKey::InsuranceClaims),
+          open_insurance_claims: LookupMap::new(StorageKey::OpenInsuranceClaims),
+          next_insurance_claim_id: 0,
+          rate_model: RateModel::default(),
+          pending_rate_model: None,
+          credit_records: LookupMap::new(StorageKey::CreditRecords),
+          credit_tiers: Vec::new(),
+          loan_guarantees: LookupMap::new(StorageKey::LoanGuarantees),
+          guarantees_by_guarantor: LookupMap::new(StorageKey::GuaranteesByGuarantor),
+          nft_collections: IterableMap::new(StorageKey::NftCollections),
+          nft_appraisals: LookupMap::new(StorageKey::NftAppraisals),
+          nft_credit_lines: IterableMap::new(StorageKey::NftCreditLines),
+          nft_credit_lines_by_account: LookupMap::new(StorageKey::NftCreditLinesByAccount),
+          next_nft_credit_line_id: 0,
+          nft_credit_config: NftCreditConfig::default(),
+          recovery_holds: LookupMap::new(StorageKey::RecoveryHolds),
+          state_version: STATE_VERSION,
+          circuit_breaker_config: CircuitBreakerConfig::default(),
+          circuit_breaker: CircuitBreakerState::default(),
+          claimable_transfers: IterableMap::new(StorageKey::ClaimableTransfers),
+          claimable_transfers_by_account: LookupMap::new(StorageKey::ClaimableTransfersByAccount),
+          next_claimable_transfer_id: 0,
+          transfer_delay_rule: TransferDelayRule::default(),
+          transfer_delays: LookupMap::new(StorageKey::TransferDelays),
+          pending_transfers: IterableMap::new(StorageKey::PendingTransfers),
+          pending_transfers_by_account: LookupMap::new(StorageKey::PendingTransfersByAccount),
+          next_pending_transfer_id: 0,
+          ledger_chain_links: LookupMap::new(StorageKey::LedgerChainLinks),
+          ledger_chain_head: CryptoHash::default(),
+          ledger_chain_length: 0,
+          ledger_blocks: Vector::new(StorageKey::LedgerBlocks),
+          profiles: LookupMap::new(StorageKey::Profiles),
+          contacts: LookupMap::new(StorageKey::Contacts),
+          sponsorship_config: SponsorshipConfig::default(),
+          sponsorship_pool: NearToken::from_yoctonear(0),
+          sponsorship_usage: LookupMap::new(StorageKey::SponsorshipUsage),
+          debit_cards: LookupMap::new(StorageKey::DebitCards),
+          debit_cards_by_account: LookupMap::new(StorageKey::DebitCardsByAccount),
+          fee_sweep_config: FeeSweepConfig::default(),
+          prize_savings_config: PrizeSavingsConfig::default(),
+          prize_pool: PrizePool::default(),
+          prize_savers: IterableMap::new(StorageKey::PrizeSavers),
+          pending_prize_draw: None,
+          prize_draws: Vector::new(StorageKey::PrizeDraws),
+          closed_accounts: LookupMap::new(StorageKey::ClosedAccounts),
+          operation_locks: LookupMap::new(StorageKey::OperationLocks),
+          next_operation_lock_id: 0,
+          ledger_lengths: LookupMap::new(StorageKey::LedgerLengths),
+          ledger_shards: LookupMap::new(StorageKey::LedgerShards),
+          authorization_nonces: LookupMap::new(StorageKey::AuthorizationNonces),
+          total_deposits: NearToken::from_yoctonear(0),
+          total_fees_collected: NearToken::from_yoctonear(0),
+          token_liabilities: IterableMap::new(StorageKey::TokenLiabilities),
+          token_reserves: LookupMap::new(StorageKey::TokenReserves),
+          solvency_checks: false,
+          crank_tasks: IterableMap::new(StorageKey::CrankTasks),
+          next_crank_task_id: 0,
+          spending_categories: Vec::new(),
+          category_rules: LookupMap::new(StorageKey::CategoryRules),
+          entry_categories: LookupMap::new(StorageKey::EntryCategories),
+          category_spending: LookupMap::new(StorageKey::CategorySpending),
+          spending_months: LookupMap::new(StorageKey::SpendingMonths),
+          charities: IterableMap::new(StorageKey::Charities),
+          matching_pool: NearToken::from_yoctonear(0),
+          donation_rules: LookupMap::new(StorageKey::DonationRules),
+          donation_records: LookupMap::new(StorageKey::DonationRecords),
+          donation_years: LookupMap::new(StorageKey::DonationYears),
+          governance_id: None,
+          multisig_changes: IterableMap::new(StorageKey::MultisigChanges),
+          next_multisig_change_id: 0,
+      }
+  }
+
+  /// Allows users to deposit NEAR tokens into their account within the contract. A first
+  /// deposit under a DID onboarding policy, or a large deposit by an account whose KYC tier
+  /// is stale, first reads the caller's DID from the registry and completes in the callback;
+  /// it resolves to false if the deposit was refunded instead.
+  /// `memo`, `reference`: Optional note and reconciliation reference kept in the ledger.
+  #[payable]
+  pub fn deposit(&mut self, memo: Option<String>, reference: Option<String>) -> PromiseOrValue<bool> {
+      self.assert_not_paused(Feature::Deposits);
+      let account_id = env::predecessor_account_id();
+      let deposit_amount: NearToken = env::attached_deposit();
+      assert!(deposit_amount.as_yoctonear() > 0, "Attached deposit must be greater than 0.");
+      assert_valid_memo(&memo);
+      assert_valid_reference(&reference);
+      self.assert_registered(&account_id);
+
+      if self.internal_onboarding_check_needed(&account_id) || self.internal_kyc_check_needed(&account_id, deposit_amount) {
+          let action = KycPendingAction::Deposit { amount: deposit_amount, memo, reference, funder_id: None };
+          return self.internal_resolve_kyc(account_id, action).into();
+      }
+      self.internal_deposit(&account_id, None, deposit_amount, memo, reference);
+      PromiseOrValue::Value(true)
+  }
+
+  /// Deposits the attached NEAR into `account_id`'s balance on the caller's behalf, e.g. by
+  /// an employer, parent or on-ramp service. The caller need not be registered; the
+  /// beneficiary must be. Onboarding and KYC checks apply to the beneficiary, and a deposit
+  /// they reject is refunded to the caller. The ledger entry names the caller as counterparty.
+  /// `memo`, `reference`: Optional note and reconciliation reference kept in the ledger.
+  #[payable]
+  pub fn deposit_to(&mut self, account_id: AccountId, memo: Option<String>, reference: Option<String>) -> PromiseOrValue<bool> {
+      self.assert_not_paused(Feature::Deposits);
+      let funder_id = env::predecessor_account_id();
+      let deposit_amount: NearToken = env::attached_deposit();
+      assert!(deposit_amount.as_yoctonear() > 0, "Attached deposit must be greater than 0.");
+      assert!(funder_id != account_id, "Use deposit to fund your own account.");
+      assert_valid_memo(&memo);
+      assert_valid_reference(&reference);
+      self.assert_registered(&account_id);
+
+      if self.internal_onboarding_check_needed(&account_id) || self.internal_kyc_check_needed(&account_id, deposit_amount) {
+          let action = KycPendingAction::Deposit { amount: deposit_amount, memo, reference, funder_id: Some(funder_id) };
+          return self.internal_resolve_kyc(account_id, action).into();
+      }
+      self.internal_deposit(&account_id, Some(&funder_id), deposit_amount, memo, reference);
+      PromiseOrValue::Value(true)
+  }
+
+  /// Allows users to withdraw NEAR tokens from their account in the contract. If liquid
+  /// reserves are short because funds are staked, or others are already waiting, the
+  /// withdrawal is queued and paid by `process_withdrawal_queue` once unstaked funds return.
+  /// The withdrawal fee is debited on top of `amount`. Large withdrawals re-read a stale KYC
+  /// tier from the DID registry first, and withdrawals above the caller's guardian threshold
+  /// are held until a guardian approves them. Withdrawals above the timelock threshold wait
+  /// in a cancellable queue until `execute_timelocked_withdrawal` runs them.
+  /// `envelope`: Optional envelope to spend from; defaults to the unallocated balance.
+  /// `memo`, `reference`: Optional note and reconciliation reference kept in the ledger.
+  /// Resolves to false if the withdrawal was held for a guardian or timelocked, or if the
+  /// NEAR transfer failed and the amount was re-credited.
+  pub fn withdraw(
+      &mut self,
+      amount: NearToken,
+      envelope: Option<String>,
+      memo: Option<String>,
+      reference: Option<String>,
+  ) -> PromiseOrValue<bool> {
+      let account_id = env::predecessor_account_id();
+      self.internal_request_withdrawal(account_id, amount, envelope, memo, reference)
+  }
+
+  /// Callback re-crediting a withdrawal whose NEAR transfer failed and releasing the
+  /// account's lock. The reversal is recorded in the ledger against the original withdrawal
+  /// entry.
+  #[private]
+  pub fn withdraw_callback(
+      &mut self,
+      account_id: AccountId,
+      amount: NearToken,
+      ledger_entry_id: u64,
+      lock_id: Option<u64>,
+      #[callback_result] result: Result<(), PromiseError>,
+  ) -> bool {
+      self.internal_unlock_account(&account_id, lock_id);
+      if result.is_ok() {
+          return true;
+      }
+      self.internal_credit(&account_id, amount);
+      self.internal_add_liabilities(amount);
+      self.internal_record_entry(
+          &account_id, EntryKind::WithdrawalReversal, EntryDirection::Credit, amount,
+          None, Some(format!("ledger:{}", ledger_entry_id)),
+      );
+      BankEvent::WithdrawFailed { account_id, amount, ledger_entry_id }.emit();
+      false
+  }
+
+  /// Moves `amount` from the caller's balance to `receiver_id`'s balance without an
+  /// on-chain NEAR transfer. The receiver must be registered; the sender pays the storage
+  /// for both ledger entries and the transfer fee on top of `amount`. Large transfers re-read
+  /// a stale KYC tier from the DID registry first, and transfers above the caller's guardian
+  /// threshold are held until a guardian approves them. Transfers delayed by the caller's
+  /// `set_transfer_delay` preference or the delay rule are held in a window the caller can
+  /// cancel in, until `execute_pending_transfer` runs them. In address-book mode the receiver
+  /// must be an active trusted recipient. When relayed by a bank relayer, the call counts
+  /// against the sender's sponsorship budget.
+  /// `memo`: Optional free-form note carried in the emitted transfer event.
+  /// `envelope`: Optional envelope to spend from; defaults to the unallocated balance.
+  /// `reference`: Optional reconciliation reference, e.g. an order number. The receiver can
+  /// look the payment up by it with `get_payments_by_reference`.
+  pub fn transfer(
+      &mut self,
+      receiver_id: AccountId,
+      amount: NearToken,
+      memo: Option<String>,
+      envelope: Option<String>,
+      reference: Option<String>,
+  ) -> PromiseOrValue<()> {
+      let sender_id = env::predecessor_account_id();
+      self.internal_meter_sponsorship(&sender_id);
+      self.internal_request_transfer(sender_id, receiver_id, amount, memo, envelope, reference)
+  }
+
+  /// Retrieves the balance of a specific account.
+  pub fn get_balance(&self, account_id: AccountId) -> NearToken {
+      self.internal_get_account(&account_id).map_or(NearToken::from_yoctonear(0), |account| account.balance)
+  }
+
+  /// Retrieves the fees and penalties collected by the bank.
+  pub fn get_treasury_balance(&self) -> NearToken {
+      self.treasury_balance
+  }
+}
+
+impl BioCrypticBankCore {
+  pub(crate) fn assert_owner(&self) {
+      if env::predecessor_account_id() != self.owner_id {
+          ContractError::OwnerOnly.panic();
+      }
+  }
+
+  /// Adds `amount` to the fee treasury.
+  pub(crate) fn internal_collect_fee(&mut self, amount: NearToken) {
+      self.treasury_balance = self.treasury_balance.saturating_add(amount);
+      self.total_fees_collected = self.total_fees_collected.saturating_add(amount);
+  }
+
+  /// Credits a deposit of `amount` to `account_id`, funded by `funder_id` if a third party
+  /// paid it. Storage is charged to the account.
+  pub(crate) fn internal_deposit(
+      &mut self,
+      account_id: &AccountId,
+      funder_id: Option<&AccountId>,
+      amount: NearToken,
+      memo: Option<String>,
+      reference: Option<String>,
+  ) {
+      let initial_storage = env::storage_usage();
+      self.internal_record_kyc_volume(account_id, amount);
+      let balance = self.internal_credit(account_id, amount);
+      self.internal_add_liabilities(amount);
+      self.internal_record_contract_inflow(amount);
+      self.total_deposits = self.total_deposits.saturating_add(amount);
+      self.internal_record_entry_with_counterparty(
+          account_id, funder_id.cloned(), EntryKind::Deposit, EntryDirection::Credit, amount, memo.clone(), reference.clone(),
+      );
+      self.internal_charge_storage(account_id, initial_storage);
+      BankEvent::Deposit { account_id: account_id.clone(), funder_id: funder_id.cloned(), amount, memo, reference }.emit();
+
+      env::log_str(&format!(
+          "Deposited {} yoctoNEAR to {}'s account. New balance: {}",
+          amount.as_yoctonear(), account_id, balance.as_yoctonear()
+      ));
+  }
+
+  /// Runs the checks of `withdraw` for `account_id`, then holds the withdrawal for a
+  /// guardian or the timelock, re-reads a stale KYC tier, or pays it out. An account still
+  /// on the pre-versioning layout is registered first.
+  pub(crate) fn internal_request_withdrawal(
+      &mut self,
+      account_id: AccountId,
+      amount: NearToken,
+      envelope: Option<String>,
+      memo: Option<String>,
+      reference: Option<String>,
+  ) -> PromiseOrValue<bool> {
+      self.assert_not_paused(Feature::Withdrawals);
+      self.internal_register_legacy_account(&account_id);
+      assert!(amount.as_yoctonear() > 0, "Withdrawal amount must be greater than 0.");
+      self.assert_not_dormant(&account_id);
+      self.assert_no_recovery_hold(&account_id);
+      assert_valid_memo(&memo);
+      assert_valid_reference(&reference);
+      if self.internal_guardian_approval_needed(&account_id, amount) {
+          let action = KycPendingAction::Withdraw { amount, envelope, memo, reference };
+          self.internal_hold_for_guardian(account_id, amount, action);
+          return PromiseOrValue::Value(false);
+      }
+      if self.internal_withdrawal_timelock_needed(amount) {
+          self.internal_timelock_withdrawal(account_id, amount, envelope, memo, reference);
+          return PromiseOrValue::Value(false);
+      }
+      if self.internal_kyc_check_needed(&account_id, amount) {
+          let action = KycPendingAction::Withdraw { amount, envelope, memo, reference };
+          return self.internal_resolve_kyc(account_id, action).into();
+      }
+      self.internal_withdraw(&account_id, amount, envelope, memo, reference)
+  }
+
+  /// Runs the checks of `transfer` for `sender_id`, then holds the transfer for a guardian
+  /// or its delay, re-reads a stale KYC tier, or moves the funds. A sender still on the
+  /// pre-versioning layout is registered first.
+  pub(crate) fn internal_request_transfer(
+      &mut self,
+      sender_id: AccountId,
+      receiver_id: AccountId,
+      amount: NearToken,
+      memo: Option<String>,
+      envelope: Option<String>,
+      reference: Option<String>,
+  ) -> PromiseOrValue<()> {
+      self.assert_not_paused(Feature::Transfers);
+      self.internal_register_legacy_account(&sender_id);
+      assert!(amount.as_yoctonear() > 0, "Transfer amount must be greater than 0.");
+      assert!(sender_id != receiver_id, "Cannot transfer to self.");
+      self.assert_not_dormant(&sender_id);
+      self.assert_no_recovery_hold(&sender_id);
+      assert_valid_memo(&memo);
+      assert_valid_reference(&reference);
+      self.assert_registered(&receiver_id);
+      self.assert_trusted_destination(&sender_id, &receiver_id);
+
+      if self.internal_guardian_approval_needed(&sender_id, amount) {
+          let action = KycPendingAction::Transfer { receiver_id, amount, memo, envelope, reference };
+          self.internal_hold_for_guardian(sender_id, amount, action);
+          return PromiseOrValue::Value(());
+      }
+      if let Some(delay_sec) = self.internal_transfer_delay(&sender_id, amount) {
+          self.internal_hold_transfer(sender_id, receiver_id, amount, memo, envelope, reference, delay_sec);
+          return PromiseOrValue::Value(());
+      }
+      if self.internal_kyc_check_needed(&sender_id, amount) {
+          let action = KycPendingAction::Transfer { receiver_id, amount, memo, envelope, reference };
+          return self.internal_resolve_kyc(sender_id, action).into();
+      }
+      self.internal_transfer(&sender_id, &receiver_id, amount, memo, envelope, reference);
+      PromiseOrValue::Value(())
+  }
+
+  /// Debits `amount` plus the withdrawal fee and sends it to `account_id`, or queues it.
+  pub(crate) fn internal_withdraw(
+      &mut self,
+      account_id: &AccountId,
+      amount: NearToken,
+      envelope: Option<String>,
+      memo: Option<String>,
+      reference: Option<String>,
+  ) -> PromiseOrValue<bool> {
+      let initial_storage = env::storage_usage();
+      let lock_id = self.internal_lock_account(account_id, LockedOperation::Withdrawal);
+      self.internal_record_outflow(account_id, amount);
+      self.internal_record_kyc_volume(account_id, amount);
+      let fee = self.internal_discounted_fee(account_id, FeeOperation::Withdrawal, amount);
+      let current_balance_yocto = self.internal_debit_from(account_id, amount.saturating_add(fee), envelope.as_deref()).as_yoctonear();
+      let entry_id = self.internal_record_entry(
+          account_id, EntryKind::Withdrawal, EntryDirection::Debit, amount, memo.clone(), reference.clone(),
+      );
+      self.internal_charge_fee(account_id, fee, Some(format!("ledger:{}", entry_id)));
+      self.internal_record_contract_outflow(amount);
+      BankEvent::Withdraw { account_id: account_id.clone(), amount, memo, reference }.emit();
+      if self.withdrawal_queue_head < self.next_queued_withdrawal_id || self.get_reserves().liquid < amount {
+          self.internal_unlock_account(account_id, Some(lock_id));
+          self.internal_queue_withdrawal(account_id, amount, entry_id);
+          self.internal_charge_storage(account_id, initial_storage);
+          return PromiseOrValue::Value(true);
+      }
+      self.internal_charge_storage(account_id, initial_storage);
+      self.internal_remove_liabilities(amount);
+
+      env::log_str(&format!(
+          "Withdrawing {} yoctoNEAR from {}'s account. New balance: {}",
+          amount.as_yoctonear(), account_id, current_balance_yocto
+      ));
+
+      Promise::new(account_id.clone()).transfer(amount).then(
+          Self::ext(env::current_account_id())
+              .with_static_gas(GAS_FOR_WITHDRAW_CALLBACK)
+              .withdraw_callback(account_id.clone(), amount, entry_id, Some(lock_id))
+      ).into()
+  }
+
+  /// Moves `amount` plus the transfer fee from `sender_id` to `receiver_id`.
+  pub(crate) fn internal_transfer(
+      &mut self,
+      sender_id: &AccountId,
+      receiver_id: &AccountId,
+      amount: NearToken,
+      memo: Option<String>,
+      envelope: Option<String>,
+      reference: Option<String>,
+  ) {
+      let initial_storage = env::storage_usage();
+      self.assert_not_locked(sender_id);
+      self.internal_record_outflow(sender_id, amount);
+      self.internal_record_kyc_volume(sender_id, amount);
+      let fee = self.internal_discounted_fee(sender_id, FeeOperation::Transfer, amount);
+      self.internal_debit_from(sender_id, amount.saturating_add(fee), envelope.as_deref());
+      self.internal_credit(receiver_id, amount);
+      let (_, credit_id) = self.internal_record_movement(
+          sender_id, receiver_id, EntryKind::Transfer, amount, memo.clone(), reference.clone(),
+      );
+      if let Some(reference) = &reference {
+          self.internal_index_payment_reference(receiver_id, reference, credit_id);
+      }
+      self.internal_charge_fee(sender_id, fee, None);
+      self.internal_apply_round_up(sender_id, amount);
+      self.internal_apply_donation_rule(sender_id, amount);
+      self.internal_charge_storage(sender_id, initial_storage);
+
+      BankEvent::Transfer { sender_id: sender_id.clone(), receiver_id: receiver_id.clone(), amount, memo, reference }.emit();
+  }
+
+  /// Adds `amount` to `account_id`'s balance, creating the entry if needed. An open
+  /// overdraft is repaid first, and an account still on the pre-versioning layout is
+  /// registered. Returns the new balance.
+  pub(crate) fn internal_credit(&mut self, account_id: &AccountId, amount: NearToken) -> NearToken {
+      self.internal_register_legacy_account(account_id);
+      self.internal_accrue_holding_points(account_id);
+      let amount = self.internal_repay_overdraft(account_id, amount);
+      let balance = self.get_balance(account_id.clone())
+          .checked_add(amount)
+          .unwrap_or_else(|| env::panic_str("Balance overflow."));
+      self.internal_set_balance(account_id, balance);
+      balance
+  }
+
+  /// Subtracts `amount` from `account_id`'s balance, panicking if funds are insufficient or
+  /// another operation is in flight for the account. Funds reserved in envelopes are not
+  /// available; any shortfall is drawn on the account's overdraft if it has one. An account
+  /// still on the pre-versioning layout is registered first. Returns the new balance.
+  pub(crate) fn internal_debit(&mut self, account_id: &AccountId, amount: NearToken) -> NearToken {
+      self.assert_not_locked(account_id);
+      self.internal_debit_unallocated(account_id, amount)
+  }
+
+  /// `internal_debit` without the operation lock check, for callers holding the lock.
+  pub(crate) fn internal_debit_unallocated(&mut self, account_id: &AccountId, amount: NearToken) -> NearToken {
+      self.internal_register_legacy_account(account_id);
+      self.internal_accrue_holding_points(account_id);
+      let unallocated = self.internal_unallocated_balance(account_id);
+      let shortfall = amount.saturating_sub(unallocated);
+      if !shortfall.is_zero() && !self.internal_draw_overdraft(account_id, shortfall) {
+          assert!(self.get_balance(account_id.clone()) >= amount, "Insufficient balance.");
+          env::panic_str("Insufficient unallocated balance; funds are reserved in envelopes.");
+      }
+      let balance = self.get_balance(account_id.clone()).saturating_sub(amount.min(unallocated));
+      self.internal_set_balance(account_id, balance);
+      balance
+  }
+}
+
+pub(crate) fn assert_valid_memo(memo: &Option<String>) {
+  if let Some(memo) = memo {
+      assert!(memo.len() <= MAX_MEMO_LENGTH, "Memo cannot exceed {} bytes.", MAX_MEMO_LENGTH);
+  }
+}
+
+/// User references may not contain `:`, which is reserved for bank references such as
+/// `invoice:42`, so a payment cannot pass itself off as an invoice settlement.
+pub(crate) fn assert_valid_reference(reference: &Option<String>) {
+  if let Some(reference) = reference {
+      assert!(!reference.is_empty(), "Reference cannot be empty.");
+      assert!(reference.len() <= MAX_REFERENCE_LENGTH, "Reference cannot exceed {} bytes.", MAX_REFERENCE_LENGTH);
+      assert!(!reference.contains(':'), "Reference cannot contain ':'.");
+  }
+}
+
+/// Computes `a * b / c` through a 256-bit intermediate, so only a quotient that does not fit
+/// in `u128` overflows. Rounds down.
+pub(crate) fn mul_div(a: u128, b: u128, c: u128) -> u128 {
+  assert!(c > 0, "Division by zero.");
+  if let Some(product) = a.checked_mul(b) {
+      return product / c;
+  }
+  const LOW_MASK: u128 = u64::MAX as u128;
+  let (a_high, a_low) = (a >> 64, a & LOW_MASK);
+  let (b_high, b_low) = (b >> 64, b & LOW_MASK);
+  let (low_low, high_low, low_high) = (a_low * b_low, a_high * b_low, a_low * b_high);
+  let cross = (low_low >> 64) + (high_low & LOW_MASK) + (low_high & LOW_MASK);
+  let low = (cross << 64) | (low_low & LOW_MASK);
+  let high = a_high * b_high + (high_low >> 64) + (low_high >> 64) + (cross >> 64);
+  assert!(high < c, "Arithmetic overflow.");
+
+  // Long division of (high, low) by `c`; the remainder always stays below `c`.
+  let (mut remainder, mut quotient) = (high, 0u128);
+  for bit in (0..128).rev() {
+      let carry = remainder >> 127;
+      remainder = (remainder << 1) | ((low >> bit) & 1);
+      quotient <<= 1;
+      if carry == 1 || remainder >= c {
+          remainder = remainder.wrapping_sub(c);
+          quotient |= 1;
+      }
+  }
+  quotient
+}
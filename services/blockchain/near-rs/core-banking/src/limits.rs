@@ -0,0 +1,226 @@
+// services/blockchain/near-rs/core-banking/src/limits.rs
+use near_sdk::{near, env, AccountId, NearToken};
+use near_sdk::json_types::U128;
+
+use crate::roles::Role;
+use crate::{BankEvent, BioCrypticBankCore, BioCrypticBankCoreExt};
+
+pub(crate) const DAY_NANOS: u64 = 24 * 60 * 60 * 1_000_000_000;
+const MONTH_NANOS: u64 = 30 * DAY_NANOS;
+/// Delay before a self-service limit increase (or removal) takes effect.
+const LIMIT_INCREASE_DELAY_NANOS: u64 = DAY_NANOS;
+
+/// Outflow accumulated since `started_at`; resets once the window length has passed.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct OutflowWindow {
+  pub started_at: u64,
+  pub spent: NearToken,
+}
+
+impl OutflowWindow {
+  pub(crate) fn spent_at(&self, now: u64, length: u64) -> NearToken {
+      if now < self.started_at + length { self.spent } else { NearToken::from_yoctonear(0) }
+  }
+
+  pub(crate) fn record(&mut self, amount: NearToken, now: u64, length: u64) {
+      if now >= self.started_at + length {
+          self.started_at = now;
+          self.spent = NearToken::from_yoctonear(0);
+      }
+      self.spent = self.spent.saturating_add(amount);
+  }
+}
+
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct PendingLimitChange {
+  pub daily: Option<NearToken>,
+  pub monthly: Option<NearToken>,
+  pub effective_at: u64,
+}
+
+/// Outflow caps for one account. The effective cap is the stricter of the account's own
+/// limit and the limit imposed by a compliance admin.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct SpendingLimits {
+  pub daily: Option<NearToken>,
+  pub monthly: Option<NearToken>,
+  pub compliance_daily: Option<NearToken>,
+  pub compliance_monthly: Option<NearToken>,
+  /// Compliance caps in USD (`USD_DECIMALS` decimals), converted to NEAR at the cached
+  /// price whenever they are evaluated.
+  pub compliance_daily_usd: Option<U128>,
+  pub compliance_monthly_usd: Option<U128>,
+  pub pending_change: Option<PendingLimitChange>,
+  pub day_window: OutflowWindow,
+  pub month_window: OutflowWindow,
+}
+
+#[near(serializers = [json])]
+#[derive(Debug, PartialEq)]
+pub struct SpendingLimitsView {
+  pub limits: SpendingLimits,
+  pub spent_today: NearToken,
+  pub spent_this_month: NearToken,
+  /// `None` when no daily cap applies.
+  pub remaining_daily: Option<NearToken>,
+  pub remaining_monthly: Option<NearToken>,
+}
+
+/// USD compliance caps converted to NEAR: `(daily, monthly)`.
+type UsdCaps = (Option<NearToken>, Option<NearToken>);
+
+impl SpendingLimits {
+  fn effective_daily(&self, usd_caps: UsdCaps) -> Option<NearToken> {
+      stricter(stricter(self.daily, self.compliance_daily), usd_caps.0)
+  }
+
+  fn effective_monthly(&self, usd_caps: UsdCaps) -> Option<NearToken> {
+      stricter(stricter(self.monthly, self.compliance_monthly), usd_caps.1)
+  }
+
+  /// Applies a queued increase once its timelock has elapsed.
+  fn apply_pending(&mut self, now: u64) {
+      if self.pending_change.as_ref().is_some_and(|change| now >= change.effective_at) {
+          let change = self.pending_change.take().unwrap();
+          self.daily = change.daily;
+          self.monthly = change.monthly;
+      }
+  }
+
+  fn remaining(&self, now: u64, usd_caps: UsdCaps) -> (Option<NearToken>, Option<NearToken>) {
+      let daily = self.effective_daily(usd_caps).map(|cap| cap.saturating_sub(self.day_window.spent_at(now, DAY_NANOS)));
+      let monthly = self.effective_monthly(usd_caps).map(|cap| cap.saturating_sub(self.month_window.spent_at(now, MONTH_NANOS)));
+      (daily, monthly)
+  }
+
+  fn allows(&self, amount: NearToken, now: u64, usd_caps: UsdCaps) -> bool {
+      let (daily, monthly) = self.remaining(now, usd_caps);
+      daily.is_none_or(|remaining| amount <= remaining) && monthly.is_none_or(|remaining| amount <= remaining)
+  }
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Sets the caller's own daily and monthly outflow limits (`None` = unlimited). Tightening
+  /// applies immediately; any loosening is queued behind a 24 hour timelock so a compromised
+  /// key cannot lift the caps and drain the account at once.
+  pub fn set_spending_limits(&mut self, daily: Option<NearToken>, monthly: Option<NearToken>) {
+      let initial_storage = env::storage_usage();
+      let account_id = env::predecessor_account_id();
+      let now = env::block_timestamp();
+      let limits = self.spending_limits.entry(account_id.clone()).or_default();
+      limits.apply_pending(now);
+
+      if loosens(limits.daily, daily) || loosens(limits.monthly, monthly) {
+          let effective_at = now + LIMIT_INCREASE_DELAY_NANOS;
+          limits.pending_change = Some(PendingLimitChange { daily, monthly, effective_at });
+          BankEvent::SpendingLimitsIncreaseQueued { account_id: account_id.clone(), daily, monthly, effective_at }.emit();
+      } else {
+          limits.daily = daily;
+          limits.monthly = monthly;
+          limits.pending_change = None;
+          BankEvent::SpendingLimitsUpdated { account_id: account_id.clone(), daily, monthly, set_by: None }.emit();
+      }
+      self.internal_charge_storage(&account_id, initial_storage);
+  }
+
+  /// Cancels the caller's queued limit increase.
+  pub fn cancel_spending_limit_increase(&mut self) {
+      let account_id = env::predecessor_account_id();
+      let limits = self.spending_limits.get_mut(&account_id)
+          .filter(|limits| limits.pending_change.is_some())
+          .unwrap_or_else(|| env::panic_str("No pending limit increase."));
+      limits.pending_change = None;
+  }
+
+  /// Imposes daily and monthly outflow caps on `account_id` that the account cannot raise.
+  /// Takes effect immediately. Compliance admin only.
+  pub fn set_compliance_limits(&mut self, account_id: AccountId, daily: Option<NearToken>, monthly: Option<NearToken>) {
+      self.assert_role(Role::ComplianceAdmin);
+      let limits = self.spending_limits.entry(account_id.clone()).or_default();
+      limits.compliance_daily = daily;
+      limits.compliance_monthly = monthly;
+      BankEvent::SpendingLimitsUpdated { account_id, daily, monthly, set_by: Some(env::predecessor_account_id()) }.emit();
+  }
+
+  /// Imposes daily and monthly outflow caps on `account_id` in USD (`USD_DECIMALS`
+  /// decimals), applied alongside any NEAR caps. Each outflow is checked against the cap
+  /// converted at the cached NEAR price; without a NEAR price the account cannot send.
+  /// Compliance admin only.
+  pub fn set_compliance_limits_usd(&mut self, account_id: AccountId, daily_usd: Option<U128>, monthly_usd: Option<U128>) {
+      self.assert_role(Role::ComplianceAdmin);
+      let limits = self.spending_limits.entry(account_id.clone()).or_default();
+      limits.compliance_daily_usd = daily_usd;
+      limits.compliance_monthly_usd = monthly_usd;
+      BankEvent::ComplianceUsdLimitsUpdated { account_id, daily_usd, monthly_usd, set_by: env::predecessor_account_id() }.emit();
+  }
+
+  /// Retrieves an account's limits, current window usage and remaining allowance.
+  /// View function.
+  pub fn get_spending_limits(&self, account_id: AccountId) -> Option<SpendingLimitsView> {
+      let now = env::block_timestamp();
+      let mut limits = self.spending_limits.get(&account_id)?.clone();
+      limits.apply_pending(now);
+      let (remaining_daily, remaining_monthly) = limits.remaining(now, self.internal_usd_caps(&limits));
+      Some(SpendingLimitsView {
+          spent_today: limits.day_window.spent_at(now, DAY_NANOS),
+          spent_this_month: limits.month_window.spent_at(now, MONTH_NANOS),
+          remaining_daily,
+          remaining_monthly,
+          limits,
+      })
+  }
+}
+
+impl BioCrypticBankCore {
+  /// Checks whether `account_id` may send `amount` right now, under its limits and
+  /// transaction monitoring, without recording anything.
+  pub(crate) fn can_spend(&self, account_id: &AccountId, amount: NearToken) -> bool {
+      let now = env::block_timestamp();
+      !self.internal_monitoring_blocks(account_id, amount) && self.spending_limits.get(account_id).is_none_or(|limits| {
+          let usd_caps = self.internal_usd_caps(limits);
+          let mut limits = limits.clone();
+          limits.apply_pending(now);
+          limits.allows(amount, now, usd_caps)
+      })
+  }
+
+  /// `limits`' USD caps converted to NEAR at the cached price. A cap that cannot be
+  /// converted for lack of a price becomes zero.
+  fn internal_usd_caps(&self, limits: &SpendingLimits) -> UsdCaps {
+      let convert = |usd: U128| self.internal_near_for_usd(usd.0).unwrap_or(NearToken::from_yoctonear(0));
+      (limits.compliance_daily_usd.map(convert), limits.compliance_monthly_usd.map(convert))
+  }
+
+  /// Counts `amount` against `account_id`'s limits and monitoring rules, panicking if it
+  /// would exceed them. Must be called for every outflow leaving the account's control.
+  pub(crate) fn internal_record_outflow(&mut self, account_id: &AccountId, amount: NearToken) {
+      self.internal_monitor_outflow(account_id, amount);
+      let now = env::block_timestamp();
+      let Some(usd_caps) = self.spending_limits.get(account_id).map(|limits| self.internal_usd_caps(limits)) else { return };
+      let limits = self.spending_limits.get_mut(account_id).unwrap();
+      limits.apply_pending(now);
+      assert!(limits.allows(amount, now, usd_caps), "Transaction exceeds spending limits.");
+      limits.day_window.record(amount, now, DAY_NANOS);
+      limits.month_window.record(amount, now, MONTH_NANOS);
+  }
+}
+
+fn stricter(a: Option<NearToken>, b: Option<NearToken>) -> Option<NearToken> {
+  match (a, b) {
+      (Some(a), Some(b)) => Some(a.min(b)),
+      (a, b) => a.or(b),
+  }
+}
+
+/// True if moving from `current` to `proposed` allows more spending.
+fn loosens(current: Option<NearToken>, proposed: Option<NearToken>) -> bool {
+  match (current, proposed) {
+      (Some(current), Some(proposed)) => proposed > current,
+      (Some(_), None) => true,
+      (None, _) => false,
+  }
+}
@@ -0,0 +1,66 @@
+// services/blockchain/near-rs/core-banking/src/liquidation.rs
+use near_sdk::{near, env, NearToken};
+use near_sdk::json_types::U128;
+
+use crate::ledger::{EntryDirection, EntryKind};
+use crate::savings::BPS_DENOMINATOR;
+use crate::{mul_div, BankEvent, BioCrypticBankCore, BioCrypticBankCoreExt};
+
+#[near]
+impl BioCrypticBankCore {
+  /// Repays up to `repay_amount` of an unhealthy loan's debt from the caller's balance in
+  /// exchange for its collateral at the liquidation bonus. Open to anyone but the borrower.
+  /// A single call repays at most the close factor of the debt, and the collateral price
+  /// must be fresh. An active guarantee must be claimed first. Returns the collateral seized.
+  pub fn liquidate(&mut self, loan_id: u64, repay_amount: NearToken) -> U128 {
+      let initial_storage = env::storage_usage();
+      let liquidator_id = env::predecessor_account_id();
+      assert!(repay_amount.as_yoctonear() > 0, "Repay amount must be greater than 0.");
+      let config = self.lending_config.clone();
+      let apr_bps = self.internal_borrow_apr_bps();
+      self.assert_guarantee_claimed(loan_id);
+      let loan = self.internal_get_loan_mut(loan_id);
+      assert!(loan.borrower_id != liquidator_id, "Borrowers cannot liquidate their own loans.");
+      loan.accrue(apr_bps, env::block_timestamp());
+      loan.accrue_late_fees(&config, env::block_timestamp());
+      let loan = loan.clone();
+
+      let collateral_value = self.internal_fresh_collateral_value(&loan.collateral_asset, loan.collateral_amount.0);
+      let health_factor_bps = self.internal_health_factor_at(&loan, collateral_value)
+          .unwrap_or_else(|| env::panic_str("Loan has no debt."));
+      assert!((health_factor_bps as u128) < BPS_DENOMINATOR, "Loan is healthy.");
+
+      let bonus_scale = BPS_DENOMINATOR + config.liquidation_bonus_bps as u128;
+      let max_repay = mul_div(loan.debt().as_yoctonear(), config.close_factor_bps as u128, BPS_DENOMINATOR);
+      let mut repaid = repay_amount.as_yoctonear().min(max_repay);
+      let mut seized = loan.collateral_amount.0;
+      let seize_value = mul_div(repaid, bonus_scale, BPS_DENOMINATOR);
+      if seize_value < collateral_value.as_yoctonear() {
+          seized = mul_div(seize_value, loan.collateral_amount.0, collateral_value.as_yoctonear());
+      } else {
+          // Not enough collateral to cover the bonus: take all of it for proportionally less debt.
+          repaid = mul_div(collateral_value.as_yoctonear(), BPS_DENOMINATOR, bonus_scale);
+      }
+      assert!(repaid > 0 && seized > 0, "Liquidation amount is too small.");
+      let repaid = NearToken::from_yoctonear(repaid);
+
+      let reference = Some(format!("loan:{}", loan_id));
+      self.internal_debit(&liquidator_id, repaid);
+      self.internal_record_entry(&liquidator_id, EntryKind::Loan, EntryDirection::Debit, repaid, None, reference.clone());
+      self.internal_get_loan_mut(loan_id).collateral_amount = U128(loan.collateral_amount.0 - seized);
+      self.internal_asset_credit(&liquidator_id, &loan.collateral_asset, seized, EntryKind::Loan, reference);
+      self.internal_apply_loan_repayment(loan_id, repaid);
+      self.internal_update_credit(&loan.borrower_id, |record| record.liquidations += 1);
+      self.internal_charge_storage(&liquidator_id, initial_storage);
+
+      BankEvent::LoanLiquidated {
+          id: loan_id,
+          borrower_id: loan.borrower_id,
+          liquidator_id,
+          repaid,
+          collateral_seized: U128(seized),
+          health_factor_bps,
+      }.emit();
+      U128(seized)
+  }
+}
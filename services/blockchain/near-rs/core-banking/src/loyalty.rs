@@ -0,0 +1,183 @@
+// services/blockchain/near-rs/core-banking/src/loyalty.rs
+use near_sdk::{near, env, AccountId, NearToken};
+
+use crate::term_deposits::NANOS_PER_DAY;
+use crate::{mul_div, BankEvent, BioCrypticBankCore, BioCrypticBankCoreExt};
+
+const YOCTO_PER_NEAR: u128 = 1_000_000_000_000_000_000_000_000;
+
+/// Earn, redemption and expiry rules for loyalty points. Owner controlled.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct LoyaltyConfig {
+  /// Points per NEAR of balance held for a whole day.
+  pub holding_points_per_near_day: u64,
+  /// Points per NEAR paid to another account by transfer, invoice or direct debit.
+  pub payment_points_per_near: u64,
+  /// Points per NEAR repaid on a loan that was not liquidatable at the time.
+  pub repayment_points_per_near: u64,
+  /// Points redeemed per NEAR of fee discount; zero disables redemption.
+  pub redemption_points_per_near: u64,
+  /// Points expire after this many days without earning any; zero means never.
+  pub expiry_days: u32,
+}
+
+impl Default for LoyaltyConfig {
+  fn default() -> Self {
+      Self {
+          holding_points_per_near_day: 0,
+          payment_points_per_near: 0,
+          repayment_points_per_near: 0,
+          redemption_points_per_near: 0,
+          expiry_days: 365,
+      }
+  }
+}
+
+/// Points are bound to the account: there is no way to transfer them.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct LoyaltyAccount {
+  pub points: u64,
+  /// Unspent fee discount bought with points, consumed by the next fees charged.
+  pub fee_credit: NearToken,
+  pub last_earned_at: u64,
+  /// Time up to which holding points have been accrued.
+  pub holding_accrued_at: u64,
+}
+
+impl LoyaltyAccount {
+  /// Forfeits all points if none were earned within the expiry window.
+  fn expire(&mut self, expiry_days: u32, now: u64) -> u64 {
+      if expiry_days == 0 || now.saturating_sub(self.last_earned_at) <= expiry_days as u64 * NANOS_PER_DAY {
+          return 0;
+      }
+      std::mem::take(&mut self.points)
+  }
+
+  fn earn(&mut self, points: u64, now: u64) {
+      if points > 0 {
+          self.points = self.points.saturating_add(points);
+          self.last_earned_at = now;
+      }
+  }
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Enrolls the caller in the loyalty program. Points are only earned once enrolled.
+  pub fn enroll_loyalty(&mut self) {
+      let initial_storage = env::storage_usage();
+      let account_id = env::predecessor_account_id();
+      self.assert_registered(&account_id);
+      assert!(self.loyalty_accounts.get(&account_id).is_none(), "Already enrolled in loyalty.");
+      let now = env::block_timestamp();
+      self.loyalty_accounts.insert(account_id.clone(), LoyaltyAccount {
+          points: 0,
+          fee_credit: NearToken::from_yoctonear(0),
+          last_earned_at: now,
+          holding_accrued_at: now,
+      });
+      self.internal_charge_storage(&account_id, initial_storage);
+      BankEvent::LoyaltyEnrolled { account_id }.emit();
+  }
+
+  /// Converts `points` into a fee discount applied to the caller's next fees. Returns the
+  /// discount bought.
+  pub fn redeem_points(&mut self, points: u64) -> NearToken {
+      let account_id = env::predecessor_account_id();
+      let rate = self.loyalty_config.redemption_points_per_near;
+      assert!(rate > 0, "Point redemption is disabled.");
+      assert!(points > 0, "Points must be greater than 0.");
+      self.internal_accrue_holding_points(&account_id);
+      let account = self.loyalty_accounts.get_mut(&account_id)
+          .unwrap_or_else(|| env::panic_str("Not enrolled in loyalty."));
+      assert!(account.points >= points, "Insufficient points.");
+      let discount = NearToken::from_yoctonear(mul_div(points as u128, YOCTO_PER_NEAR, rate as u128));
+      account.points -= points;
+      account.fee_credit = account.fee_credit.saturating_add(discount);
+      BankEvent::LoyaltyPointsRedeemed { account_id, points, fee_credit: discount }.emit();
+      discount
+  }
+
+  /// Updates the loyalty rules. Holding points already accrued are kept. Owner only.
+  pub fn set_loyalty_config(&mut self, config: LoyaltyConfig) {
+      self.assert_owner();
+      self.loyalty_config = config;
+  }
+
+  /// `account_id`'s points including holding points accrued up to now, net of expiry.
+  /// View function.
+  pub fn get_loyalty_account(&self, account_id: AccountId) -> Option<LoyaltyAccount> {
+      let mut account = self.loyalty_accounts.get(&account_id)?.clone();
+      let points = self.internal_holding_points(&account_id, &account);
+      account.expire(self.loyalty_config.expiry_days, env::block_timestamp());
+      account.earn(points, env::block_timestamp());
+      Some(account)
+  }
+
+  /// View function.
+  pub fn get_loyalty_config(&self) -> LoyaltyConfig {
+      self.loyalty_config.clone()
+  }
+}
+
+impl BioCrypticBankCore {
+  /// Holding points `account` has earned on its current balance since its last accrual.
+  fn internal_holding_points(&self, account_id: &AccountId, account: &LoyaltyAccount) -> u64 {
+      let days = env::block_timestamp().saturating_sub(account.holding_accrued_at) / NANOS_PER_DAY;
+      let rate = self.loyalty_config.holding_points_per_near_day as u128 * days as u128;
+      mul_div(self.get_balance(account_id.clone()).as_yoctonear(), rate, YOCTO_PER_NEAR).min(u64::MAX as u128) as u64
+  }
+
+  /// Awards the holding points earned on `account_id`'s balance so far. Must run before the
+  /// balance changes.
+  pub(crate) fn internal_accrue_holding_points(&mut self, account_id: &AccountId) {
+      let Some(account) = self.loyalty_accounts.get(account_id) else { return };
+      let now = env::block_timestamp();
+      let days = now.saturating_sub(account.holding_accrued_at) / NANOS_PER_DAY;
+      if days == 0 {
+          return;
+      }
+      let points = self.internal_holding_points(account_id, account);
+      self.internal_award_points(account_id, points);
+      if let Some(account) = self.loyalty_accounts.get_mut(account_id) {
+          account.holding_accrued_at += days * NANOS_PER_DAY;
+      }
+  }
+
+  /// Awards points for `amount` paid to another account.
+  pub(crate) fn internal_award_payment_points(&mut self, account_id: &AccountId, amount: NearToken) {
+      let rate = self.loyalty_config.payment_points_per_near;
+      self.internal_award_points(account_id, points_for(amount, rate));
+  }
+
+  /// Awards points for `amount` repaid on a healthy loan.
+  pub(crate) fn internal_award_repayment_points(&mut self, account_id: &AccountId, amount: NearToken) {
+      let rate = self.loyalty_config.repayment_points_per_near;
+      self.internal_award_points(account_id, points_for(amount, rate));
+  }
+
+  fn internal_award_points(&mut self, account_id: &AccountId, points: u64) {
+      let expiry_days = self.loyalty_config.expiry_days;
+      let Some(account) = self.loyalty_accounts.get_mut(account_id) else { return };
+      let now = env::block_timestamp();
+      let expired = account.expire(expiry_days, now);
+      account.earn(points, now);
+      if expired > 0 {
+          BankEvent::LoyaltyPointsExpired { account_id: account_id.clone(), points: expired }.emit();
+      }
+  }
+
+  /// Applies `account_id`'s fee credit to `fee`. Returns the part still to be paid.
+  pub(crate) fn internal_spend_fee_credit(&mut self, account_id: &AccountId, fee: NearToken) -> NearToken {
+      let Some(account) = self.loyalty_accounts.get_mut(account_id) else { return fee };
+      let discount = fee.min(account.fee_credit);
+      account.fee_credit = account.fee_credit.saturating_sub(discount);
+      fee.saturating_sub(discount)
+  }
+}
+
+fn points_for(amount: NearToken, points_per_near: u64) -> u64 {
+  mul_div(amount.as_yoctonear(), points_per_near as u128, YOCTO_PER_NEAR).min(u64::MAX as u128) as u64
+}
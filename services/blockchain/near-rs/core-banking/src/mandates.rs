@@ -0,0 +1,183 @@
+// services/blockchain/near-rs/core-banking/src/mandates.rs
+use near_sdk::{near, env, AccountId, NearToken};
+
+use crate::ledger::EntryKind;
+use crate::pause::Feature;
+use crate::{assert_valid_memo, BankEvent, BioCrypticBankCore, BioCrypticBankCoreExt};
+
+const NANOS_PER_SECOND: u64 = 1_000_000_000;
+/// Shortest allowed mandate period (1 day).
+const MIN_PERIOD_SECONDS: u64 = 24 * 60 * 60;
+const MAX_MANDATES_PER_ACCOUNT: usize = 50;
+
+#[near(serializers = [borsh, json])]
+#[serde(rename_all = "snake_case")]
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum MandateStatus {
+  Active,
+  Paused,
+  Cancelled,
+}
+
+/// Authorization for `merchant_id` to debit `payer_id` up to `max_per_period` each period.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct Mandate {
+  pub id: u64,
+  pub payer_id: AccountId,
+  pub merchant_id: AccountId,
+  pub max_per_period: NearToken,
+  pub period_seconds: u64,
+  pub status: MandateStatus,
+  /// Start (ns) of the period `collected_in_period` refers to.
+  pub period_start: u64,
+  pub collected_in_period: NearToken,
+  pub total_collected: NearToken,
+  pub created_at: u64,
+}
+
+impl Mandate {
+  /// Rolls the period window forward by whole periods if the current one has ended.
+  fn roll_period(&mut self, now: u64) {
+      let period = self.period_seconds * NANOS_PER_SECOND;
+      if now >= self.period_start + period {
+          self.period_start += (now - self.period_start) / period * period;
+          self.collected_in_period = NearToken::from_yoctonear(0);
+      }
+  }
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Authorizes `merchant_id` to collect up to `max_per_period` from the caller every
+  /// `period_seconds`. Returns the mandate id.
+  pub fn create_mandate(&mut self, merchant_id: AccountId, max_per_period: NearToken, period_seconds: u64) -> u64 {
+      let initial_storage = env::storage_usage();
+      let payer_id = env::predecessor_account_id();
+      assert!(payer_id != merchant_id, "Cannot create a mandate to self.");
+      assert!(max_per_period.as_yoctonear() > 0, "Period cap must be greater than 0.");
+      assert!(period_seconds >= MIN_PERIOD_SECONDS, "Period must be at least {} seconds.", MIN_PERIOD_SECONDS);
+      self.assert_registered(&merchant_id);
+      self.assert_trusted_destination(&payer_id, &merchant_id);
+
+      let id = self.next_mandate_id;
+      self.next_mandate_id += 1;
+      for (index, account_id) in [(&mut self.mandates_by_payer, &payer_id), (&mut self.mandates_by_merchant, &merchant_id)] {
+          let ids = index.entry(account_id.clone()).or_default();
+          assert!(ids.len() < MAX_MANDATES_PER_ACCOUNT, "Too many active mandates.");
+          ids.push(id);
+      }
+      let now = env::block_timestamp();
+      self.mandates.insert(id, Mandate {
+          id,
+          payer_id: payer_id.clone(),
+          merchant_id: merchant_id.clone(),
+          max_per_period,
+          period_seconds,
+          status: MandateStatus::Active,
+          period_start: now,
+          collected_in_period: NearToken::from_yoctonear(0),
+          total_collected: NearToken::from_yoctonear(0),
+          created_at: now,
+      });
+      self.internal_charge_storage(&payer_id, initial_storage);
+      BankEvent::MandateCreated { id, payer_id, merchant_id, max_per_period, period_seconds }.emit();
+      id
+  }
+
+  /// Merchant debits `amount` under an active mandate. `reference`: the merchant's own
+  /// payment reference, stored as the ledger memo.
+  pub fn collect(&mut self, mandate_id: u64, amount: NearToken, reference: String) {
+      self.assert_not_paused(Feature::Transfers);
+      let initial_storage = env::storage_usage();
+      assert!(amount.as_yoctonear() > 0, "Amount must be greater than 0.");
+      let reference = Some(reference);
+      assert_valid_memo(&reference);
+
+      let now = env::block_timestamp();
+      let mandate = self.internal_get_mandate_mut(mandate_id);
+      assert_eq!(mandate.merchant_id, env::predecessor_account_id(), "Only the merchant can collect.");
+      assert_eq!(mandate.status, MandateStatus::Active, "Mandate is not active.");
+      mandate.roll_period(now);
+      let collected = mandate.collected_in_period.saturating_add(amount);
+      assert!(collected <= mandate.max_per_period, "Amount exceeds the mandate's period cap.");
+      mandate.collected_in_period = collected;
+      mandate.total_collected = mandate.total_collected.saturating_add(amount);
+      let (payer_id, merchant_id) = (mandate.payer_id.clone(), mandate.merchant_id.clone());
+
+      self.internal_record_outflow(&payer_id, amount);
+      self.internal_debit(&payer_id, amount);
+      self.internal_credit(&merchant_id, amount);
+      self.internal_record_movement(
+          &payer_id, &merchant_id, EntryKind::DirectDebit, amount, reference.clone(), Some(format!("mandate:{}", mandate_id)),
+      );
+      self.internal_charge_storage(&merchant_id, initial_storage);
+      BankEvent::MandateCollected { id: mandate_id, payer_id, merchant_id, amount, reference }.emit();
+  }
+
+  /// Payer suspends collections under a mandate.
+  pub fn pause_mandate(&mut self, mandate_id: u64) {
+      let mandate = self.internal_get_mandate_mut(mandate_id);
+      assert_eq!(mandate.payer_id, env::predecessor_account_id(), "Only the payer can pause the mandate.");
+      assert_eq!(mandate.status, MandateStatus::Active, "Mandate is not active.");
+      mandate.status = MandateStatus::Paused;
+      BankEvent::MandateStatusChanged { id: mandate_id, status: MandateStatus::Paused }.emit();
+  }
+
+  /// Payer resumes a paused mandate.
+  pub fn resume_mandate(&mut self, mandate_id: u64) {
+      let mandate = self.internal_get_mandate_mut(mandate_id);
+      assert_eq!(mandate.payer_id, env::predecessor_account_id(), "Only the payer can resume the mandate.");
+      assert_eq!(mandate.status, MandateStatus::Paused, "Mandate is not paused.");
+      mandate.status = MandateStatus::Active;
+      BankEvent::MandateStatusChanged { id: mandate_id, status: MandateStatus::Active }.emit();
+  }
+
+  /// Permanently cancels a mandate. Callable by the payer or the merchant.
+  pub fn cancel_mandate(&mut self, mandate_id: u64) {
+      let account_id = env::predecessor_account_id();
+      let mandate = self.internal_get_mandate_mut(mandate_id);
+      assert!(account_id == mandate.payer_id || account_id == mandate.merchant_id, "Only a party to the mandate can cancel it.");
+      assert!(mandate.status != MandateStatus::Cancelled, "Mandate is already cancelled.");
+      mandate.status = MandateStatus::Cancelled;
+      let (payer_id, merchant_id) = (mandate.payer_id.clone(), mandate.merchant_id.clone());
+
+      for (index, account_id) in [(&mut self.mandates_by_payer, &payer_id), (&mut self.mandates_by_merchant, &merchant_id)] {
+          if let Some(ids) = index.get_mut(account_id) {
+              ids.retain(|existing| *existing != mandate_id);
+              if ids.is_empty() {
+                  index.remove(account_id);
+              }
+          }
+      }
+      BankEvent::MandateStatusChanged { id: mandate_id, status: MandateStatus::Cancelled }.emit();
+  }
+
+  /// Retrieves a mandate by id.
+  /// View function.
+  pub fn get_mandate(&self, mandate_id: u64) -> Option<Mandate> {
+      self.mandates.get(&mandate_id).cloned()
+  }
+
+  /// Lists the uncancelled mandates granted by `payer_id`.
+  /// View function.
+  pub fn get_mandates_for_payer(&self, payer_id: AccountId) -> Vec<Mandate> {
+      self.mandates_by_payer.get(&payer_id)
+          .map(|ids| ids.iter().filter_map(|id| self.mandates.get(id).cloned()).collect())
+          .unwrap_or_default()
+  }
+
+  /// Lists the uncancelled mandates `merchant_id` can collect under.
+  /// View function.
+  pub fn get_mandates_for_merchant(&self, merchant_id: AccountId) -> Vec<Mandate> {
+      self.mandates_by_merchant.get(&merchant_id)
+          .map(|ids| ids.iter().filter_map(|id| self.mandates.get(id).cloned()).collect())
+          .unwrap_or_default()
+  }
+}
+
+impl BioCrypticBankCore {
+  fn internal_get_mandate_mut(&mut self, mandate_id: u64) -> &mut Mandate {
+      self.mandates.get_mut(&mandate_id).unwrap_or_else(|| env::panic_str("Mandate not found."))
+  }
+}
@@ -0,0 +1,483 @@
+// services/blockchain/near-rs/core-banking/src/merchants.rs
+use near_sdk::{near, env, AccountId, NearToken};
+use near_sdk::store::Vector;
+
+use crate::fees::FeeOperation;
+use crate::ledger::{EntryDirection, EntryKind};
+use crate::pause::Feature;
+use crate::roles::Role;
+use crate::{assert_valid_memo, assert_valid_reference, BankEvent, BioCrypticBankCore, BioCrypticBankCoreExt, StorageKey, DEFAULT_PAGE_LIMIT};
+
+const MAX_MERCHANT_NAME_LENGTH: usize = 64;
+const NANOS_PER_SECOND: u64 = 1_000_000_000;
+/// Shortest allowed interval between scheduled settlements (1 hour).
+const MIN_SETTLEMENT_INTERVAL_SEC: u64 = 60 * 60;
+/// Longest dispute window a merchant can offer (30 days).
+const MAX_DISPUTE_WINDOW_SEC: u64 = 30 * 24 * 60 * 60;
+/// Charges a merchant can have in hold at once.
+const MAX_HELD_ORDERS_PER_MERCHANT: usize = 200;
+
+/// A registered merchant. Card-style payments, and point-of-sale payments in netting mode,
+/// accumulate in `pending_settlement` and are swept to `settlement_account_id` in batches.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct Merchant {
+  pub name: String,
+  pub settlement_account_id: AccountId,
+  pub settlement_interval_sec: u64,
+  pub pending_settlement: NearToken,
+  /// Orders charged since the last settlement.
+  pub pending_order_count: u64,
+  /// Time customers have to dispute a charge, during which its funds are held. Zero
+  /// settles charges without a hold.
+  pub dispute_window_sec: u64,
+  /// Charges still inside their dispute window or under dispute.
+  pub held_balance: NearToken,
+  /// Charges received, net of chargebacks.
+  pub total_received: NearToken,
+  pub registered_at: u64,
+  pub last_settled_at: u64,
+  /// Number of settlements made, and the index the next one will have.
+  pub settlement_count: u64,
+  /// Whether point-of-sale payments are netted into the pending settlement rather than
+  /// credited one by one.
+  pub pos_netting: bool,
+}
+
+/// A customer's consent to be charged up to `amount` for one order.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct OrderApproval {
+  pub customer_id: AccountId,
+  pub amount: NearToken,
+  pub approved_at: u64,
+}
+
+#[near(serializers = [borsh, json])]
+#[serde(rename_all = "snake_case")]
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum OrderStatus {
+  /// Inside the dispute window; funds are held.
+  Held,
+  /// Disputed by the customer; funds stay held until an arbiter rules.
+  Disputed,
+  /// Added to the merchant's pending settlement.
+  Released,
+  /// Charged back to the customer.
+  Refunded,
+}
+
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct MerchantOrder {
+  pub order_id: String,
+  pub customer_id: AccountId,
+  pub amount: NearToken,
+  pub paid_at: u64,
+  /// Customer-side ledger entry of the payment.
+  pub ledger_entry_id: u64,
+  pub status: OrderStatus,
+  /// End (ns) of the dispute window; equal to `paid_at` when there was none.
+  pub release_at: u64,
+  pub dispute_reason: Option<String>,
+  /// Index of the settlement that includes (or will include) this order, once released.
+  pub settlement_index: Option<u64>,
+}
+
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct MerchantSettlement {
+  pub index: u64,
+  /// Gross amount of the batch, before the merchant fee.
+  pub amount: NearToken,
+  pub fee: NearToken,
+  pub order_count: u64,
+  pub settled_at: u64,
+  /// Ledger entry crediting the settlement account.
+  pub ledger_entry_id: u64,
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Registers the caller as a merchant. Settlements are paid to `settlement_account_id`
+  /// (default: the caller) every `settlement_interval_sec`.
+  pub fn register_merchant(&mut self, name: String, settlement_interval_sec: u64, settlement_account_id: Option<AccountId>) {
+      let initial_storage = env::storage_usage();
+      let merchant_id = env::predecessor_account_id();
+      self.assert_registered(&merchant_id);
+      assert!(self.merchants.get(&merchant_id).is_none(), "Already registered as a merchant.");
+      assert!(!name.is_empty() && name.len() <= MAX_MERCHANT_NAME_LENGTH, "Merchant name must be 1 to {} bytes.", MAX_MERCHANT_NAME_LENGTH);
+      assert!(settlement_interval_sec >= MIN_SETTLEMENT_INTERVAL_SEC, "Settlement interval must be at least {} seconds.", MIN_SETTLEMENT_INTERVAL_SEC);
+      let settlement_account_id = settlement_account_id.unwrap_or_else(|| merchant_id.clone());
+      self.assert_registered(&settlement_account_id);
+
+      let now = env::block_timestamp();
+      self.merchants.insert(merchant_id.clone(), Merchant {
+          name: name.clone(),
+          settlement_account_id,
+          settlement_interval_sec,
+          pending_settlement: NearToken::from_yoctonear(0),
+          pending_order_count: 0,
+          dispute_window_sec: 0,
+          held_balance: NearToken::from_yoctonear(0),
+          total_received: NearToken::from_yoctonear(0),
+          registered_at: now,
+          last_settled_at: now,
+          settlement_count: 0,
+          pos_netting: false,
+      });
+      self.internal_charge_storage(&merchant_id, initial_storage);
+      BankEvent::MerchantRegistered { merchant_id, name }.emit();
+  }
+
+  /// Changes where and how often the caller's settlements are paid. Merchant only.
+  pub fn update_merchant_settlement(&mut self, settlement_interval_sec: u64, settlement_account_id: AccountId) {
+      let merchant_id = env::predecessor_account_id();
+      assert!(settlement_interval_sec >= MIN_SETTLEMENT_INTERVAL_SEC, "Settlement interval must be at least {} seconds.", MIN_SETTLEMENT_INTERVAL_SEC);
+      self.assert_registered(&settlement_account_id);
+      let merchant = self.internal_get_merchant_mut(&merchant_id);
+      merchant.settlement_interval_sec = settlement_interval_sec;
+      merchant.settlement_account_id = settlement_account_id;
+  }
+
+  /// Sets how long customers can dispute the caller's future charges. Merchant only.
+  pub fn set_merchant_dispute_window(&mut self, dispute_window_sec: u64) {
+      assert!(dispute_window_sec <= MAX_DISPUTE_WINDOW_SEC, "Dispute window cannot exceed {} seconds.", MAX_DISPUTE_WINDOW_SEC);
+      let merchant = self.internal_get_merchant_mut(&env::predecessor_account_id());
+      merchant.dispute_window_sec = dispute_window_sec;
+  }
+
+  /// Switches netting of the caller's point-of-sale payments on or off. Payments already in
+  /// the pending settlement stay there. Merchant only.
+  pub fn set_merchant_pos_netting(&mut self, enabled: bool) {
+      let merchant_id = env::predecessor_account_id();
+      self.internal_get_merchant_mut(&merchant_id).pos_netting = enabled;
+      BankEvent::MerchantPosNettingChanged { merchant_id, enabled }.emit();
+  }
+
+  /// Pays `merchant_id` `amount` at the point of sale from the caller's balance. In netting
+  /// mode the payment joins the merchant's pending settlement, which is paid out and charged
+  /// the merchant fee as one batch. Otherwise the settlement account is credited at once,
+  /// less the merchant fee. Point-of-sale payments are not held for disputes.
+  pub fn pay_merchant(&mut self, merchant_id: AccountId, amount: NearToken, memo: Option<String>) {
+      self.assert_not_paused(Feature::Transfers);
+      let initial_storage = env::storage_usage();
+      let customer_id = env::predecessor_account_id();
+      assert!(amount.as_yoctonear() > 0, "Amount must be greater than 0.");
+      assert!(customer_id != merchant_id, "Cannot pay yourself as a merchant.");
+      assert_valid_memo(&memo);
+      self.assert_not_dormant(&customer_id);
+      self.assert_no_recovery_hold(&customer_id);
+      self.assert_trusted_destination(&customer_id, &merchant_id);
+      let merchant = self.merchants.get(&merchant_id).unwrap_or_else(|| env::panic_str("Merchant not found.")).clone();
+
+      self.internal_record_outflow(&customer_id, amount);
+      self.internal_record_kyc_volume(&customer_id, amount);
+      self.internal_debit(&customer_id, amount);
+      self.internal_award_payment_points(&customer_id, amount);
+      if merchant.pos_netting {
+          self.internal_record_entry_with_counterparty(
+              &customer_id, Some(merchant_id.clone()), EntryKind::MerchantPayment, EntryDirection::Debit, amount, memo, None,
+          );
+          let merchant = self.internal_get_merchant_mut(&merchant_id);
+          merchant.pending_settlement = merchant.pending_settlement.saturating_add(amount);
+          merchant.pending_order_count += 1;
+          merchant.total_received = merchant.total_received.saturating_add(amount);
+      } else {
+          let settlement_account_id = merchant.settlement_account_id.clone();
+          self.assert_registered(&settlement_account_id);
+          self.internal_credit(&settlement_account_id, amount);
+          let (debit_id, _) = self.internal_record_movement(
+              &customer_id, &settlement_account_id, EntryKind::MerchantPayment, amount, memo, None,
+          );
+          let fee = self.internal_operation_fee(&merchant_id, FeeOperation::MerchantSettlement, amount);
+          if !fee.is_zero() {
+              self.internal_debit(&settlement_account_id, fee);
+              self.internal_charge_fee(&settlement_account_id, fee, Some(format!("ledger:{}", debit_id)));
+          }
+          let merchant = self.internal_get_merchant_mut(&merchant_id);
+          merchant.total_received = merchant.total_received.saturating_add(amount);
+      }
+      self.internal_charge_storage(&customer_id, initial_storage);
+      BankEvent::MerchantPosPayment { merchant_id, customer_id, amount, netted: merchant.pos_netting }.emit();
+  }
+
+  /// Authorizes `merchant_id` to charge the caller up to `amount` for `order_id`. Replaces
+  /// the caller's previous approval for that order.
+  pub fn approve_merchant_payment(&mut self, merchant_id: AccountId, order_id: String, amount: NearToken) {
+      let initial_storage = env::storage_usage();
+      let customer_id = env::predecessor_account_id();
+      self.assert_registered(&customer_id);
+      assert!(amount.as_yoctonear() > 0, "Amount must be greater than 0.");
+      assert!(customer_id != merchant_id, "Cannot pay yourself as a merchant.");
+      self.assert_trusted_destination(&customer_id, &merchant_id);
+      assert_valid_reference(&Some(order_id.clone()));
+      assert!(self.merchants.get(&merchant_id).is_some(), "Merchant not found.");
+      let key = (merchant_id.clone(), order_id.clone());
+      assert!(self.merchant_orders.get(&key).is_none(), "Order has already been paid.");
+      if let Some(approval) = self.merchant_approvals.get(&key) {
+          assert_eq!(approval.customer_id, customer_id, "Order has been approved by another customer.");
+      }
+      self.merchant_approvals.insert(key, OrderApproval { customer_id: customer_id.clone(), amount, approved_at: env::block_timestamp() });
+      self.internal_charge_storage(&customer_id, initial_storage);
+      BankEvent::MerchantPaymentApproved { merchant_id, customer_id, order_id, amount }.emit();
+  }
+
+  /// Withdraws the caller's unused approval for `order_id`.
+  pub fn revoke_merchant_approval(&mut self, merchant_id: AccountId, order_id: String) {
+      let initial_storage = env::storage_usage();
+      let customer_id = env::predecessor_account_id();
+      let key = (merchant_id, order_id);
+      let approval = self.merchant_approvals.get(&key).unwrap_or_else(|| env::panic_str("Approval not found."));
+      assert_eq!(approval.customer_id, customer_id, "Only the approving customer can revoke.");
+      self.merchant_approvals.remove(&key);
+      self.internal_charge_storage(&customer_id, initial_storage);
+  }
+
+  /// Charges `customer_id` `amount` for `order_id` against the customer's approval. The
+  /// payment joins the caller's pending settlement, after the dispute window if the caller
+  /// has one. Merchant only.
+  pub fn charge(&mut self, customer_id: AccountId, amount: NearToken, order_id: String) {
+      self.assert_not_paused(Feature::Transfers);
+      let initial_storage = env::storage_usage();
+      let merchant_id = env::predecessor_account_id();
+      assert!(amount.as_yoctonear() > 0, "Amount must be greater than 0.");
+      let key = (merchant_id.clone(), order_id.clone());
+      let approval = self.merchant_approvals.remove(&key).unwrap_or_else(|| env::panic_str("Customer has not approved this order."));
+      assert_eq!(approval.customer_id, customer_id, "Order was approved by another customer.");
+      assert!(amount <= approval.amount, "Amount exceeds the approved amount.");
+      self.assert_not_dormant(&customer_id);
+      self.assert_no_recovery_hold(&customer_id);
+
+      self.internal_record_outflow(&customer_id, amount);
+      self.internal_record_kyc_volume(&customer_id, amount);
+      self.internal_debit(&customer_id, amount);
+      let reference = format!("order:{}", order_id);
+      let ledger_entry_id = self.internal_record_entry(
+          &customer_id, EntryKind::MerchantPayment, EntryDirection::Debit, amount, None, Some(reference.clone()),
+      );
+      self.internal_index_payment_reference(&merchant_id, &reference, ledger_entry_id);
+      self.internal_award_payment_points(&customer_id, amount);
+
+      let now = env::block_timestamp();
+      let merchant = self.internal_get_merchant_mut(&merchant_id);
+      merchant.total_received = merchant.total_received.saturating_add(amount);
+      let release_at = now + merchant.dispute_window_sec * NANOS_PER_SECOND;
+      let (status, settlement_index) = if merchant.dispute_window_sec == 0 {
+          merchant.pending_settlement = merchant.pending_settlement.saturating_add(amount);
+          merchant.pending_order_count += 1;
+          (OrderStatus::Released, Some(merchant.settlement_count))
+      } else {
+          merchant.held_balance = merchant.held_balance.saturating_add(amount);
+          let held = self.merchant_held_orders.entry(merchant_id.clone()).or_default();
+          assert!(held.len() < MAX_HELD_ORDERS_PER_MERCHANT, "Too many charges on hold. Release matured holds first.");
+          held.push(order_id.clone());
+          (OrderStatus::Held, None)
+      };
+      self.merchant_orders.insert(key, MerchantOrder {
+          order_id: order_id.clone(),
+          customer_id: customer_id.clone(),
+          amount,
+          paid_at: now,
+          ledger_entry_id,
+          status,
+          release_at,
+          dispute_reason: None,
+          settlement_index,
+      });
+      let order_ids = self.merchant_order_ids.entry(merchant_id.clone())
+          .or_insert_with(|| Vector::new(StorageKey::MerchantOrderIds { merchant_id_hash: env::sha256(merchant_id.as_bytes()) }));
+      order_ids.push(order_id.clone());
+      order_ids.flush();
+      self.internal_charge_storage(&merchant_id, initial_storage);
+      BankEvent::MerchantCharged { merchant_id, customer_id, order_id, amount }.emit();
+  }
+
+  /// Disputes a held charge before its dispute window ends, keeping the funds on hold until
+  /// an arbiter rules. Charged customer only.
+  pub fn dispute_charge(&mut self, merchant_id: AccountId, order_id: String, reason: String) {
+      assert!(!reason.is_empty(), "A dispute needs a reason.");
+      assert_valid_memo(&Some(reason.clone()));
+      let order = self.merchant_orders.get_mut(&(merchant_id.clone(), order_id.clone()))
+          .unwrap_or_else(|| env::panic_str("Order not found."));
+      assert_eq!(order.customer_id, env::predecessor_account_id(), "Only the charged customer can dispute.");
+      assert_eq!(order.status, OrderStatus::Held, "Only held charges can be disputed.");
+      assert!(env::block_timestamp() < order.release_at, "Dispute window has ended.");
+      order.status = OrderStatus::Disputed;
+      order.dispute_reason = Some(reason.clone());
+      BankEvent::ChargeDisputed { merchant_id, order_id, customer_id: order.customer_id.clone(), reason }.emit();
+  }
+
+  /// Rules on a disputed charge: refunds the customer or releases the funds to the
+  /// merchant. Dispute arbiter only.
+  pub fn resolve_charge_dispute(&mut self, merchant_id: AccountId, order_id: String, refund_customer: bool) {
+      self.assert_role(Role::DisputeArbiter);
+      let initial_storage = env::storage_usage();
+      let key = (merchant_id.clone(), order_id.clone());
+      let order = self.merchant_orders.get(&key).unwrap_or_else(|| env::panic_str("Order not found.")).clone();
+      assert_eq!(order.status, OrderStatus::Disputed, "Order is not disputed.");
+      self.internal_remove_held_order(&merchant_id, &order_id);
+      if refund_customer {
+          let merchant = self.internal_get_merchant_mut(&merchant_id);
+          merchant.held_balance = merchant.held_balance.saturating_sub(order.amount);
+          merchant.total_received = merchant.total_received.saturating_sub(order.amount);
+          self.merchant_orders.get_mut(&key).unwrap().status = OrderStatus::Refunded;
+          self.internal_credit(&order.customer_id, order.amount);
+          self.internal_record_entry(
+              &order.customer_id, EntryKind::Chargeback, EntryDirection::Credit, order.amount,
+              None, Some(format!("order:{}", order_id)),
+          );
+      } else {
+          self.internal_release_order(&merchant_id, &order_id);
+      }
+      self.internal_charge_storage(&merchant_id, initial_storage);
+      BankEvent::ChargeDisputeResolved { merchant_id, order_id, refunded: refund_customer }.emit();
+  }
+
+  /// Releases `merchant_id`'s undisputed charges whose dispute window has ended to its
+  /// pending settlement. Callable by anyone. Returns the number of charges released.
+  pub fn release_merchant_holds(&mut self, merchant_id: AccountId) -> u32 {
+      let initial_storage = env::storage_usage();
+      let released = self.internal_release_matured_holds(&merchant_id);
+      self.internal_charge_storage(&merchant_id, initial_storage);
+      released
+  }
+
+  /// Sweeps `merchant_id`'s pending settlement, after releasing matured holds, to its
+  /// settlement account, less the merchant fee on the batch. The merchant may settle at any
+  /// time; anyone else once the settlement interval has elapsed. Returns the gross amount
+  /// settled.
+  pub fn settle_merchant(&mut self, merchant_id: AccountId) -> NearToken {
+      let initial_storage = env::storage_usage();
+      let now = env::block_timestamp();
+      self.internal_release_matured_holds(&merchant_id);
+      let merchant = self.merchants.get(&merchant_id).unwrap_or_else(|| env::panic_str("Merchant not found.")).clone();
+      assert!(
+          env::predecessor_account_id() == merchant_id
+              || now >= merchant.last_settled_at + merchant.settlement_interval_sec * NANOS_PER_SECOND,
+          "Settlement is not due yet."
+      );
+      assert!(!merchant.pending_settlement.is_zero(), "Nothing to settle.");
+      self.assert_registered(&merchant.settlement_account_id);
+
+      let amount = merchant.pending_settlement;
+      let index = merchant.settlement_count;
+      let order_count = merchant.pending_order_count;
+      self.internal_credit(&merchant.settlement_account_id, amount);
+      let reference = format!("settlement:{}", index);
+      let ledger_entry_id = self.internal_record_entry(
+          &merchant.settlement_account_id, EntryKind::MerchantSettlement, EntryDirection::Credit, amount,
+          None, Some(reference.clone()),
+      );
+      let fee = self.internal_operation_fee(&merchant_id, FeeOperation::MerchantSettlement, amount);
+      if !fee.is_zero() {
+          self.internal_debit(&merchant.settlement_account_id, fee);
+          self.internal_charge_fee(&merchant.settlement_account_id, fee, Some(reference));
+      }
+      let settlements = self.merchant_settlements.entry(merchant_id.clone())
+          .or_insert_with(|| Vector::new(StorageKey::MerchantSettlementHistory { merchant_id_hash: env::sha256(merchant_id.as_bytes()) }));
+      settlements.push(MerchantSettlement { index, amount, fee, order_count, settled_at: now, ledger_entry_id });
+      settlements.flush();
+
+      let merchant = self.internal_get_merchant_mut(&merchant_id);
+      merchant.pending_settlement = NearToken::from_yoctonear(0);
+      merchant.pending_order_count = 0;
+      merchant.last_settled_at = now;
+      merchant.settlement_count += 1;
+      self.internal_charge_storage(&merchant_id, initial_storage);
+      BankEvent::MerchantSettled { merchant_id, index, amount, fee, order_count }.emit();
+      amount
+  }
+
+  /// View function.
+  pub fn get_merchant(&self, merchant_id: AccountId) -> Option<Merchant> {
+      self.merchants.get(&merchant_id).cloned()
+  }
+
+  /// Open approval for `order_id` at `merchant_id`, if any.
+  /// View function.
+  pub fn get_merchant_approval(&self, merchant_id: AccountId, order_id: String) -> Option<OrderApproval> {
+      self.merchant_approvals.get(&(merchant_id, order_id)).cloned()
+  }
+
+  /// Payment received by `merchant_id` for `order_id`, if any.
+  /// View function.
+  pub fn get_merchant_order(&self, merchant_id: AccountId, order_id: String) -> Option<MerchantOrder> {
+      self.merchant_orders.get(&(merchant_id, order_id)).cloned()
+  }
+
+  /// Lists the orders paid to `merchant_id` oldest first, paginated.
+  /// View function.
+  pub fn get_merchant_orders(&self, merchant_id: AccountId, from_index: Option<u64>, limit: Option<u64>) -> Vec<MerchantOrder> {
+      let Some(order_ids) = self.merchant_order_ids.get(&merchant_id) else { return Vec::new() };
+      order_ids.iter()
+          .skip(from_index.unwrap_or(0) as usize)
+          .take(limit.unwrap_or(DEFAULT_PAGE_LIMIT) as usize)
+          .filter_map(|order_id| self.merchant_orders.get(&(merchant_id.clone(), order_id.clone())).cloned())
+          .collect()
+  }
+
+  /// Lists `merchant_id`'s charges still on hold or under dispute.
+  /// View function.
+  pub fn get_merchant_held_orders(&self, merchant_id: AccountId) -> Vec<MerchantOrder> {
+      self.merchant_held_orders.get(&merchant_id)
+          .map(|order_ids| order_ids.iter()
+              .filter_map(|order_id| self.merchant_orders.get(&(merchant_id.clone(), order_id.clone())).cloned())
+              .collect())
+          .unwrap_or_default()
+  }
+
+  /// Lists `merchant_id`'s past settlements oldest first, paginated. Orders carry the index
+  /// of the settlement they were paid out in.
+  /// View function.
+  pub fn get_merchant_settlements(&self, merchant_id: AccountId, from_index: Option<u64>, limit: Option<u64>) -> Vec<MerchantSettlement> {
+      let Some(settlements) = self.merchant_settlements.get(&merchant_id) else { return Vec::new() };
+      settlements.iter()
+          .skip(from_index.unwrap_or(0) as usize)
+          .take(limit.unwrap_or(DEFAULT_PAGE_LIMIT) as usize)
+          .cloned()
+          .collect()
+  }
+}
+
+impl BioCrypticBankCore {
+  fn internal_get_merchant_mut(&mut self, merchant_id: &AccountId) -> &mut Merchant {
+      self.merchants.get_mut(merchant_id).unwrap_or_else(|| env::panic_str("Merchant not found."))
+  }
+
+  /// Moves a held order's funds into the merchant's pending settlement.
+  fn internal_release_order(&mut self, merchant_id: &AccountId, order_id: &str) {
+      let order = self.merchant_orders.get_mut(&(merchant_id.clone(), order_id.to_string())).unwrap();
+      let amount = order.amount;
+      let merchant = self.merchants.get_mut(merchant_id).unwrap();
+      order.status = OrderStatus::Released;
+      order.settlement_index = Some(merchant.settlement_count);
+      merchant.held_balance = merchant.held_balance.saturating_sub(amount);
+      merchant.pending_settlement = merchant.pending_settlement.saturating_add(amount);
+      merchant.pending_order_count += 1;
+  }
+
+  fn internal_release_matured_holds(&mut self, merchant_id: &AccountId) -> u32 {
+      let Some(order_ids) = self.merchant_held_orders.get(merchant_id) else { return 0 };
+      let now = env::block_timestamp();
+      let matured: Vec<String> = order_ids.iter()
+          .filter(|order_id| self.merchant_orders.get(&(merchant_id.clone(), (*order_id).clone()))
+              .is_some_and(|order| order.status == OrderStatus::Held && now >= order.release_at))
+          .cloned()
+          .collect();
+      for order_id in &matured {
+          self.internal_remove_held_order(merchant_id, order_id);
+          self.internal_release_order(merchant_id, order_id);
+      }
+      matured.len() as u32
+  }
+
+  fn internal_remove_held_order(&mut self, merchant_id: &AccountId, order_id: &str) {
+      if let Some(order_ids) = self.merchant_held_orders.get_mut(merchant_id) {
+          order_ids.retain(|existing| existing != order_id);
+          if order_ids.is_empty() {
+              self.merchant_held_orders.remove(merchant_id);
+          }
+      }
+  }
+}
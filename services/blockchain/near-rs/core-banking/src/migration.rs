@@ -0,0 +1,225 @@
+// services/blockchain/near-rs/core-banking/src/migration.rs
+use near_sdk::{near, env, AccountId, NearToken};
+use near_sdk::store::LookupMap;
+
+use crate::{BankEvent, BioCrypticBankCore, BioCrypticBankCoreExt};
+
+/// Version of the root state layout written by this code.
+pub const STATE_VERSION: u16 = 1;
+const STATE_KEY: &[u8] = b"STATE";
+
+/// Per-account record as of the current version.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct AccountV1 {
+  pub balance: NearToken,
+}
+
+pub type Account = AccountV1;
+
+/// Per-account record behind a version tag. A new version adds a variant with the extra
+/// fields (e.g. token balances, ledger pointers, limits) and upgrades older entries as they
+/// are read, so no account has to be rewritten during the upgrade itself.
+#[near(serializers = [borsh])]
+#[derive(Debug, PartialEq, Clone)]
+pub enum VersionedAccount {
+  V1(AccountV1),
+}
+
+impl VersionedAccount {
+  pub(crate) fn into_current(self) -> Account {
+      match self {
+          VersionedAccount::V1(account) => account,
+      }
+  }
+}
+
+impl From<Account> for VersionedAccount {
+  fn from(account: Account) -> Self {
+      VersionedAccount::V1(account)
+  }
+}
+
+/// Root state layout before accounts were versioned: balances keyed by account and the owner.
+#[near(serializers = [borsh])]
+pub struct StateV0 {
+  pub balances: LookupMap<AccountId, NearToken>,
+  pub owner_id: AccountId,
+}
+
+impl StateV0 {
+  /// A freshly initialized state whose legacy balances are the stored ones. Both maps use
+  /// the `Balances` prefix, so no entry is rewritten. The balances cannot be enumerated to
+  /// sum them, but the pre-versioning contract held nothing but depositor funds, so
+  /// everything above its storage stake is counted as liabilities.
+  fn into_current(self) -> BioCrypticBankCore {
+      let mut state = BioCrypticBankCore::new(self.owner_id);
+      state.legacy_balances = self.balances;
+      let storage_cost = env::storage_byte_cost().saturating_mul(env::storage_usage().into());
+      state.internal_add_liabilities(env::account_balance().saturating_sub(storage_cost));
+      state
+  }
+}
+
+/// Root state layouts `migrate` can read: the deployed pre-versioning layout and the
+/// current one. Before changing the fields of `BioCrypticBankCore` on a deployed contract,
+/// add the outgoing layout here and bump `STATE_VERSION`.
+pub enum VersionedState {
+  V0(StateV0),
+  Current(Box<BioCrypticBankCore>),
+}
+
+impl VersionedState {
+  /// Decodes the stored root state with the newest layout that fits it.
+  fn read() -> Self {
+      let bytes = env::storage_read(STATE_KEY).unwrap_or_else(|| env::panic_str("Contract is not initialized."));
+      if let Ok(state) = near_sdk::borsh::from_slice::<BioCrypticBankCore>(&bytes) {
+          return VersionedState::Current(Box::new(state));
+      }
+      if let Ok(state) = near_sdk::borsh::from_slice::<StateV0>(&bytes) {
+          return VersionedState::V0(state);
+      }
+      env::panic_str("Stored state does not match any known layout.")
+  }
+
+  /// The version the stored state was written with.
+  fn version(&self) -> u16 {
+      match self {
+          VersionedState::V0(_) => 0,
+          VersionedState::Current(state) => state.state_version,
+      }
+  }
+
+  fn into_current(self) -> BioCrypticBankCore {
+      match self {
+          VersionedState::V0(state) => state.into_current(),
+          VersionedState::Current(state) => *state,
+      }
+  }
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Upgrades the stored state to the layout of the deployed code. Call in the same
+  /// transaction as the code deployment. Balances kept under the pre-versioning layout stay
+  /// readable and move to versioned records as accounts are used.
+  #[private]
+  #[init(ignore_state)]
+  pub fn migrate() -> Self {
+      let stored = VersionedState::read();
+      let from_version = stored.version();
+      let mut state = stored.into_current();
+      assert!(from_version <= STATE_VERSION, "Stored state is newer than this code.");
+      state.state_version = STATE_VERSION;
+      BankEvent::StateMigrated { from_version, to_version: STATE_VERSION }.emit();
+      state
+  }
+
+  /// View function.
+  pub fn get_state_version(&self) -> u16 {
+      self.state_version
+  }
+}
+
+impl BioCrypticBankCore {
+  /// `account_id`'s record, upgraded to the current version. Falls back to the balance
+  /// stored before accounts were versioned.
+  pub(crate) fn internal_get_account(&self, account_id: &AccountId) -> Option<Account> {
+      if let Some(account) = self.balances.get(account_id) {
+          return Some(account.clone().into_current());
+      }
+      self.legacy_balances.get(account_id).map(|balance| Account { balance: *balance })
+  }
+
+  pub(crate) fn internal_has_account(&self, account_id: &AccountId) -> bool {
+      self.balances.contains_key(account_id) || self.legacy_balances.contains_key(account_id)
+  }
+
+  /// Stores `balance` as `account_id`'s balance in a current-version record, retiring any
+  /// pre-versioning entry.
+  pub(crate) fn internal_set_balance(&mut self, account_id: &AccountId, balance: NearToken) {
+      let mut account = self.internal_get_account(account_id).unwrap_or_default();
+      if self.legacy_balances.contains_key(account_id) {
+          self.legacy_balances.remove(account_id);
+      }
+      account.balance = balance;
+      self.balances.insert(account_id.clone(), account.into());
+  }
+
+  pub(crate) fn internal_remove_account(&mut self, account_id: &AccountId) {
+      self.balances.remove(account_id);
+      self.legacy_balances.remove(account_id);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use near_sdk::test_utils::{accounts, VMContextBuilder};
+  use near_sdk::testing_env;
+
+  use super::*;
+
+  #[test]
+  fn pre_versioning_balances_become_legacy_balances() {
+      testing_env!(VMContextBuilder::new().account_balance(NearToken::from_near(3)).storage_usage(0).build());
+      let mut balances = LookupMap::new(crate::StorageKey::Balances);
+      balances.insert(accounts(1), NearToken::from_near(3));
+      balances.flush();
+      let stored = StateV0 { balances, owner_id: accounts(0) };
+      env::storage_write(STATE_KEY, &near_sdk::borsh::to_vec(&stored).unwrap());
+
+      let stored = VersionedState::read();
+      assert_eq!(stored.version(), 0);
+      let state = stored.into_current();
+      assert_eq!(state.owner_id, accounts(0));
+      assert_eq!(state.get_balance(accounts(1)), NearToken::from_near(3));
+      assert!(state.get_total_liabilities() > NearToken::from_near(2));
+      assert!(state.get_withdrawable_surplus().is_zero());
+  }
+
+  #[test]
+  fn legacy_accounts_fund_their_registration_from_their_balance() {
+      testing_env!(VMContextBuilder::new().build());
+      let mut state = BioCrypticBankCore::new(accounts(0));
+      state.legacy_balances.insert(accounts(1), NearToken::from_near(3));
+      state.internal_register_legacy_account(&accounts(1));
+
+      let deposit = state.storage_balance_of(accounts(1)).expect("Account should be registered.").total.0;
+      assert!(deposit > 0);
+      assert_eq!(state.get_balance(accounts(1)).as_yoctonear(), NearToken::from_near(3).as_yoctonear() - deposit);
+      assert!(state.legacy_balances.get(&accounts(1)).is_none());
+  }
+
+  fn migrated_with_legacy_balance() -> BioCrypticBankCore {
+      testing_env!(VMContextBuilder::new().build());
+      let mut state = BioCrypticBankCore::new(accounts(0));
+      state.legacy_balances.insert(accounts(1), NearToken::from_near(3));
+      state
+  }
+
+  #[test]
+  fn legacy_accounts_can_deposit() {
+      let mut state = migrated_with_legacy_balance();
+      testing_env!(VMContextBuilder::new()
+          .predecessor_account_id(accounts(1))
+          .attached_deposit(NearToken::from_near(1))
+          .build());
+      state.deposit(None, None);
+
+      let deposit = state.storage_balance_of(accounts(1)).expect("Account should be registered.").total.0;
+      assert_eq!(state.get_balance(accounts(1)).as_yoctonear(), NearToken::from_near(4).as_yoctonear() - deposit);
+  }
+
+  #[test]
+  fn legacy_accounts_can_send_and_receive_transfers() {
+      let mut state = migrated_with_legacy_balance();
+      state.legacy_balances.insert(accounts(2), NearToken::from_near(3));
+      testing_env!(VMContextBuilder::new().predecessor_account_id(accounts(1)).build());
+      state.transfer(accounts(2), NearToken::from_near(1), None, None, None);
+
+      let sender_deposit = state.storage_balance_of(accounts(1)).expect("Sender should be registered.").total.0;
+      let receiver_deposit = state.storage_balance_of(accounts(2)).expect("Receiver should be registered.").total.0;
+      assert_eq!(state.get_balance(accounts(1)).as_yoctonear(), NearToken::from_near(2).as_yoctonear() - sender_deposit);
+      assert_eq!(state.get_balance(accounts(2)).as_yoctonear(), NearToken::from_near(4).as_yoctonear() - receiver_deposit);
+  }
+}
@@ -0,0 +1,176 @@
+// services/blockchain/near-rs/core-banking/src/monitoring.rs
+use near_sdk::{near, env, AccountId, NearToken};
+
+use crate::limits::{OutflowWindow, DAY_NANOS};
+use crate::roles::Role;
+use crate::savings::BPS_DENOMINATOR;
+use crate::{mul_div, BankEvent, BioCrypticBankCore, BioCrypticBankCoreExt};
+
+const HOUR_NANOS: u64 = 60 * 60 * 1_000_000_000;
+const NANOS_PER_SECOND: u64 = 1_000_000_000;
+/// Longest override a compliance admin can grant in one go (7 days).
+const MAX_OVERRIDE_SECONDS: u64 = 7 * 24 * 60 * 60;
+
+/// Transaction monitoring rules applied to every outflow. `None` disables a rule.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct MonitoringRules {
+  pub max_transactions_per_hour: Option<u32>,
+  pub max_daily_outflow: Option<NearToken>,
+  /// Reporting threshold that structuring tries to stay under.
+  pub structuring_threshold: Option<NearToken>,
+  /// Width of the band just below the threshold, in basis points of it.
+  pub structuring_band_bps: u16,
+  /// Outflows within the band in 24 hours that count as structuring.
+  pub structuring_max_count: u32,
+  /// Block breaching transactions until a compliance admin grants an override, instead of
+  /// only alerting.
+  pub soft_block: bool,
+}
+
+#[near(serializers = [borsh, json])]
+#[serde(rename_all = "snake_case")]
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum MonitoringRule {
+  TransactionVelocity,
+  DailyOutflow,
+  Structuring,
+}
+
+/// Number of events since `started_at`; resets once the window length has passed.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct CountWindow {
+  pub started_at: u64,
+  pub count: u32,
+}
+
+impl CountWindow {
+  fn count_at(&self, now: u64, length: u64) -> u32 {
+      if now < self.started_at + length { self.count } else { 0 }
+  }
+
+  fn record(&mut self, now: u64, length: u64) {
+      if now >= self.started_at + length {
+          self.started_at = now;
+          self.count = 0;
+      }
+      self.count = self.count.saturating_add(1);
+  }
+}
+
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct MonitoringActivity {
+  pub hourly_transactions: CountWindow,
+  pub daily_outflow: OutflowWindow,
+  /// Outflows just below the structuring threshold in the last 24 hours.
+  pub near_threshold: CountWindow,
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Replaces the monitoring rules. Compliance admin only.
+  pub fn set_monitoring_rules(&mut self, rules: MonitoringRules) {
+      self.assert_role(Role::ComplianceAdmin);
+      assert!(rules.structuring_band_bps as u128 <= BPS_DENOMINATOR, "Structuring band cannot exceed 100%.");
+      assert!(
+          rules.structuring_threshold.is_none() || rules.structuring_max_count > 0,
+          "Structuring needs a transaction count."
+      );
+      self.monitoring_rules = rules;
+  }
+
+  /// Lets `account_id`'s transactions through soft blocks for `duration_sec`. Breaches are
+  /// still reported. Compliance admin only.
+  pub fn grant_monitoring_override(&mut self, account_id: AccountId, duration_sec: u64) {
+      self.assert_role(Role::ComplianceAdmin);
+      assert!(duration_sec > 0 && duration_sec <= MAX_OVERRIDE_SECONDS, "Override must last 1 to {} seconds.", MAX_OVERRIDE_SECONDS);
+      let expires_at = env::block_timestamp() + duration_sec * NANOS_PER_SECOND;
+      self.monitoring_overrides.insert(account_id.clone(), expires_at);
+      BankEvent::MonitoringOverrideGranted { account_id, expires_at, granted_by: env::predecessor_account_id() }.emit();
+  }
+
+  /// Ends an override early. Compliance admin only.
+  pub fn revoke_monitoring_override(&mut self, account_id: AccountId) {
+      self.assert_role(Role::ComplianceAdmin);
+      assert!(self.monitoring_overrides.remove(&account_id).is_some(), "No monitoring override.");
+  }
+
+  /// View function.
+  pub fn get_monitoring_rules(&self) -> MonitoringRules {
+      self.monitoring_rules.clone()
+  }
+
+  /// View function.
+  pub fn get_monitoring_activity(&self, account_id: AccountId) -> Option<MonitoringActivity> {
+      self.monitoring_activity.get(&account_id).cloned()
+  }
+
+  /// Expiry (ns) of `account_id`'s override, if one is in force.
+  /// View function.
+  pub fn get_monitoring_override(&self, account_id: AccountId) -> Option<u64> {
+      self.monitoring_overrides.get(&account_id).copied().filter(|expires_at| env::block_timestamp() < *expires_at)
+  }
+}
+
+impl BioCrypticBankCore {
+  /// Rules an outflow of `amount` by `account_id` would breach right now.
+  fn internal_breached_rules(&self, account_id: &AccountId, amount: NearToken) -> Vec<MonitoringRule> {
+      let rules = &self.monitoring_rules;
+      let now = env::block_timestamp();
+      let default = MonitoringActivity::default();
+      let activity = self.monitoring_activity.get(account_id).unwrap_or(&default);
+      let mut breached = Vec::new();
+      if rules.max_transactions_per_hour.is_some_and(|max| activity.hourly_transactions.count_at(now, HOUR_NANOS) >= max) {
+          breached.push(MonitoringRule::TransactionVelocity);
+      }
+      if rules.max_daily_outflow.is_some_and(|max| activity.daily_outflow.spent_at(now, DAY_NANOS).saturating_add(amount) > max) {
+          breached.push(MonitoringRule::DailyOutflow);
+      }
+      if self.internal_is_near_threshold(amount)
+          && activity.near_threshold.count_at(now, DAY_NANOS) + 1 >= rules.structuring_max_count
+      {
+          breached.push(MonitoringRule::Structuring);
+      }
+      breached
+  }
+
+  fn internal_is_near_threshold(&self, amount: NearToken) -> bool {
+      let Some(threshold) = self.monitoring_rules.structuring_threshold else { return false };
+      let band = mul_div(threshold.as_yoctonear(), self.monitoring_rules.structuring_band_bps as u128, BPS_DENOMINATOR);
+      amount < threshold && amount.as_yoctonear() >= threshold.as_yoctonear() - band
+  }
+
+  /// Whether monitoring would block an outflow of `amount` by `account_id`.
+  pub(crate) fn internal_monitoring_blocks(&self, account_id: &AccountId, amount: NearToken) -> bool {
+      self.monitoring_rules.soft_block
+          && self.get_monitoring_override(account_id.clone()).is_none()
+          && !self.internal_breached_rules(account_id, amount).is_empty()
+  }
+
+  /// Evaluates an outflow against the monitoring rules and records it. Each breached rule
+  /// emits a compliance alert; under soft blocking the outflow is then rejected unless the
+  /// account holds an override.
+  pub(crate) fn internal_monitor_outflow(&mut self, account_id: &AccountId, amount: NearToken) {
+      if self.monitoring_rules == MonitoringRules::default() {
+          return;
+      }
+      let breached = self.internal_breached_rules(account_id, amount);
+      let blocked = self.internal_monitoring_blocks(account_id, amount);
+      for rule in &breached {
+          BankEvent::ComplianceAlert { account_id: account_id.clone(), rule: *rule, amount, blocked }.emit();
+      }
+      if blocked {
+          env::panic_str(&format!("Transaction held by compliance monitoring pending review: {:?}.", breached));
+      }
+      let now = env::block_timestamp();
+      let near_threshold = self.internal_is_near_threshold(amount);
+      let activity = self.monitoring_activity.entry(account_id.clone()).or_default();
+      activity.hourly_transactions.record(now, HOUR_NANOS);
+      activity.daily_outflow.record(amount, now, DAY_NANOS);
+      if near_threshold {
+          activity.near_threshold.record(now, DAY_NANOS);
+      }
+  }
+}
@@ -0,0 +1,643 @@
+// services/blockchain/near-rs/core-banking/src/multisig.rs
+use near_sdk::{near, env, AccountId, Gas, NearToken, Promise, PromiseError};
+
+use crate::circuit_breaker::CircuitBreakerConfig;
+use crate::fees::FeeSweepConfig;
+use crate::{assert_valid_memo, BankEvent, BioCrypticBankCore, BioCrypticBankCoreExt, DEFAULT_PAGE_LIMIT};
+
+const NANOS_PER_SECOND: u64 = 1_000_000_000;
+const MIN_TIMELOCK_SEC: u64 = 60 * 60;
+const MAX_TREASURY_ADMINS: u32 = 20;
+const GAS_FOR_PAYOUT_CALLBACK: Gas = Gas::from_tgas(10);
+
+/// Approvals (M of the registered admins) and delay required to move treasury funds or
+/// change the admins and these rules.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct MultisigConfig {
+  pub approvals_required: u32,
+  /// Delay between reaching the approval threshold and being able to execute.
+  pub timelock_sec: u64,
+}
+
+impl Default for MultisigConfig {
+  fn default() -> Self {
+      Self { approvals_required: 2, timelock_sec: 48 * 60 * 60 }
+  }
+}
+
+#[near(serializers = [borsh, json])]
+#[serde(rename_all = "snake_case")]
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ProposalStatus {
+  /// Collecting approvals.
+  Proposed,
+  /// Approved; executable once the timelock ends.
+  Approved,
+  Executed,
+  Cancelled,
+}
+
+/// A proposed payout from the fee treasury.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct WithdrawalProposal {
+  pub id: u64,
+  pub proposer_id: AccountId,
+  pub receiver_id: AccountId,
+  pub amount: NearToken,
+  pub memo: Option<String>,
+  pub approvals: Vec<AccountId>,
+  pub status: ProposalStatus,
+  pub created_at: u64,
+  /// Set once the approval threshold is reached.
+  pub executable_at: Option<u64>,
+}
+
+/// A change to the admins, multisig rules, circuit breaker or fee sweep, or a move of
+/// treasury funds into a bank-run pool.
+#[near(serializers = [borsh, json])]
+#[serde(rename_all = "snake_case")]
+#[derive(Debug, PartialEq, Clone)]
+pub enum MultisigAction {
+  AddAdmin(AccountId),
+  /// Approvals the admin already gave on open proposals no longer count.
+  RemoveAdmin(AccountId),
+  /// Proposals already approved keep their execution time.
+  SetConfig(MultisigConfig),
+  /// When the circuit breaker trips and how many admins must approve a reset.
+  SetCircuitBreakerConfig(CircuitBreakerConfig),
+  /// Where `sweep_fees` sends the treasury and how much it keeps back.
+  SetFeeSweepConfig(FeeSweepConfig),
+  /// Moves fee treasury funds into the donation matching pool.
+  FundMatchingPool(NearToken),
+  /// Moves fee treasury funds into the referral rewards budget.
+  FundReferralBudget(NearToken),
+}
+
+/// A proposed `MultisigAction`, approved and timelocked like a withdrawal.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct MultisigChangeProposal {
+  pub id: u64,
+  pub proposer_id: AccountId,
+  pub action: MultisigAction,
+  pub approvals: Vec<AccountId>,
+  pub status: ProposalStatus,
+  pub created_at: u64,
+  /// Set once the approval threshold is reached.
+  pub executable_at: Option<u64>,
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Registers `account_id` as an admin while there are too few admins to reach the
+  /// approval threshold. Once there are enough, admins are added with a
+  /// `MultisigAction::AddAdmin` proposal. Owner only.
+  pub fn add_multisig_admin(&mut self, account_id: AccountId) {
+      self.assert_owner();
+      assert!(
+          self.multisig_admins.len() < self.multisig_config.approvals_required,
+          "Admins can already reach the approval threshold; propose adding an admin instead."
+      );
+      self.internal_add_multisig_admin(account_id);
+  }
+
+  /// Proposes changing the admins, multisig rules, circuit breaker or fee sweep, or funding a
+  /// pool from the treasury. The proposer's approval is counted. Admins only. Returns the proposal id.
+  pub fn propose_multisig_change(&mut self, action: MultisigAction) -> u64 {
+      let proposer_id = env::predecessor_account_id();
+      self.assert_multisig_admin(&proposer_id);
+      self.assert_valid_multisig_action(&action);
+      let id = self.next_multisig_change_id;
+      self.next_multisig_change_id += 1;
+      self.multisig_changes.insert(id, MultisigChangeProposal {
+          id,
+          proposer_id: proposer_id.clone(),
+          action: action.clone(),
+          approvals: Vec::new(),
+          status: ProposalStatus::Proposed,
+          created_at: env::block_timestamp(),
+          executable_at: None,
+      });
+      BankEvent::MultisigChangeProposed { id, proposer_id: proposer_id.clone(), action }.emit();
+      self.internal_approve_multisig_change(id, proposer_id);
+      id
+  }
+
+  /// Approves change `change_id`. Reaching the threshold starts its timelock. Admins only.
+  pub fn approve_multisig_change(&mut self, change_id: u64) {
+      let admin_id = env::predecessor_account_id();
+      self.assert_multisig_admin(&admin_id);
+      self.internal_approve_multisig_change(change_id, admin_id);
+  }
+
+  /// Cancels a change that has not been executed, vetoing it during its timelock. Admins or
+  /// owner only.
+  pub fn cancel_multisig_change(&mut self, change_id: u64) {
+      let caller_id = env::predecessor_account_id();
+      assert!(
+          caller_id == self.owner_id || self.multisig_admins.contains(&caller_id),
+          "Only an admin or the owner can cancel a proposal."
+      );
+      let change = self.multisig_changes.get_mut(&change_id)
+          .filter(|change| matches!(change.status, ProposalStatus::Proposed | ProposalStatus::Approved))
+          .unwrap_or_else(|| env::panic_str("No open proposal with this id."));
+      change.status = ProposalStatus::Cancelled;
+      BankEvent::MultisigChangeCancelled { id: change_id, cancelled_by: caller_id }.emit();
+  }
+
+  /// Applies approved change `change_id` once its timelock has passed. The approvals are
+  /// re-counted against the current admins and threshold. Admins only.
+  pub fn execute_multisig_change(&mut self, change_id: u64) {
+      self.assert_multisig_admin(&env::predecessor_account_id());
+      let change = self.multisig_changes.get(&change_id)
+          .filter(|change| change.status == ProposalStatus::Approved)
+          .cloned()
+          .unwrap_or_else(|| env::panic_str("Proposal is not approved."));
+      assert!(
+          change.executable_at.is_some_and(|executable_at| env::block_timestamp() >= executable_at),
+          "Proposal is still timelocked."
+      );
+      assert!(
+          self.internal_count_admins(&change.approvals) >= self.multisig_config.approvals_required,
+          "Proposal no longer has enough approvals."
+      );
+      self.assert_valid_multisig_action(&change.action);
+      match change.action.clone() {
+          MultisigAction::AddAdmin(account_id) => self.internal_add_multisig_admin(account_id),
+          MultisigAction::RemoveAdmin(account_id) => {
+              self.multisig_admins.remove(&account_id);
+              BankEvent::MultisigAdminRemoved { account_id }.emit();
+          }
+          MultisigAction::SetConfig(config) => self.multisig_config = config,
+          MultisigAction::SetCircuitBreakerConfig(config) => self.circuit_breaker_config = config,
+          MultisigAction::SetFeeSweepConfig(config) => self.internal_set_fee_sweep_config(config),
+          MultisigAction::FundMatchingPool(amount) => self.internal_fund_matching_pool(amount),
+          MultisigAction::FundReferralBudget(amount) => self.internal_fund_referral_budget(amount),
+      }
+      self.multisig_changes.get_mut(&change_id).unwrap().status = ProposalStatus::Executed;
+      BankEvent::MultisigChangeExecuted { id: change_id, action: change.action }.emit();
+  }
+
+  /// Proposes paying `amount` out of the fee treasury to `receiver_id`. The proposer's
+  /// approval is counted. Admins only. Returns the proposal id.
+  pub fn propose_withdrawal(&mut self, receiver_id: AccountId, amount: NearToken, memo: Option<String>) -> u64 {
+      let proposer_id = env::predecessor_account_id();
+      self.assert_multisig_admin(&proposer_id);
+      assert!(amount.as_yoctonear() > 0, "Withdrawal amount must be greater than 0.");
+      assert_valid_memo(&memo);
+      let id = self.next_withdrawal_proposal_id;
+      self.next_withdrawal_proposal_id += 1;
+      self.withdrawal_proposals.insert(id, WithdrawalProposal {
+          id,
+          proposer_id: proposer_id.clone(),
+          receiver_id: receiver_id.clone(),
+          amount,
+          memo,
+          approvals: Vec::new(),
+          status: ProposalStatus::Proposed,
+          created_at: env::block_timestamp(),
+          executable_at: None,
+      });
+      BankEvent::WithdrawalProposed { id, proposer_id: proposer_id.clone(), receiver_id, amount }.emit();
+      self.internal_approve_proposal(id, proposer_id);
+      id
+  }
+
+  /// Approves proposal `proposal_id`. Reaching the threshold starts its timelock. Admins only.
+  pub fn approve_withdrawal(&mut self, proposal_id: u64) {
+      let admin_id = env::predecessor_account_id();
+      self.assert_multisig_admin(&admin_id);
+      self.internal_approve_proposal(proposal_id, admin_id);
+  }
+
+  /// Cancels a proposal that has not been executed, vetoing it during its timelock. Admins
+  /// or owner only.
+  pub fn cancel_withdrawal_proposal(&mut self, proposal_id: u64) {
+      let caller_id = env::predecessor_account_id();
+      assert!(
+          caller_id == self.owner_id || self.multisig_admins.contains(&caller_id),
+          "Only an admin or the owner can cancel a proposal."
+      );
+      let proposal = self.withdrawal_proposals.get_mut(&proposal_id)
+          .filter(|proposal| matches!(proposal.status, ProposalStatus::Proposed | ProposalStatus::Approved))
+          .unwrap_or_else(|| env::panic_str("No open proposal with this id."));
+      proposal.status = ProposalStatus::Cancelled;
+      BankEvent::WithdrawalProposalCancelled { id: proposal_id, cancelled_by: caller_id }.emit();
+  }
+
+  /// Pays out approved proposal `proposal_id` from the fee treasury once its timelock has
+  /// passed. The approvals are re-counted against the current admins and threshold. Only
+  /// collected fees can leave this way, never depositor funds, storage reserves or the
+  /// insurance fund: the amount must fit in the contract's withdrawable surplus and leave
+  /// the sweep retention buffer in the treasury for operations. The payout counts towards
+  /// the circuit breaker's outflow. Admins only.
+  pub fn execute_withdrawal(&mut self, proposal_id: u64) -> Promise {
+      self.assert_multisig_admin(&env::predecessor_account_id());
+      let approvals = self.internal_valid_approvals(proposal_id);
+      let proposal = self.withdrawal_proposals.get_mut(&proposal_id)
+          .filter(|proposal| proposal.status == ProposalStatus::Approved)
+          .unwrap_or_else(|| env::panic_str("Proposal is not approved."));
+      assert!(
+          proposal.executable_at.is_some_and(|executable_at| env::block_timestamp() >= executable_at),
+          "Proposal is still timelocked."
+      );
+      assert!(approvals >= self.multisig_config.approvals_required, "Proposal no longer has enough approvals.");
+      proposal.status = ProposalStatus::Executed;
+      let (receiver_id, amount) = (proposal.receiver_id.clone(), proposal.amount);
+      self.internal_spend_treasury(amount);
+
+      env::log_str(&format!("Multisig withdrawing {} yoctoNEAR to {}.", amount.as_yoctonear(), receiver_id));
+      BankEvent::WithdrawalExecuted { id: proposal_id, receiver_id: receiver_id.clone(), amount }.emit();
+      BankEvent::TreasuryWithdrawn {
+          receiver_id: receiver_id.clone(),
+          amount,
+          treasury_remaining: self.treasury_balance,
+          retention_buffer: self.fee_sweep_config.retention_buffer,
+          insurance_fund: self.insurance_fund,
+          referral_budget: self.referral_budget,
+          sponsorship_pool: self.sponsorship_pool,
+          user_liabilities: self.get_total_liabilities(),
+      }.emit();
+      Promise::new(receiver_id).transfer(amount).then(
+          Self::ext(env::current_account_id())
+              .with_static_gas(GAS_FOR_PAYOUT_CALLBACK)
+              .on_withdrawal_executed(proposal_id)
+      )
+  }
+
+  /// Callback returning a failed payout to the treasury, taking it back out of the circuit
+  /// breaker's outflow, and re-opening its proposal so it can be executed again.
+  #[private]
+  pub fn on_withdrawal_executed(&mut self, proposal_id: u64, #[callback_result] result: Result<(), PromiseError>) -> bool {
+      if result.is_err() {
+          if let Some(proposal) = self.withdrawal_proposals.get_mut(&proposal_id) {
+              proposal.status = ProposalStatus::Approved;
+              let amount = proposal.amount;
+              self.treasury_balance = self.treasury_balance.saturating_add(amount);
+              self.internal_reverse_contract_outflow(amount);
+          }
+          env::log_str("Multisig withdrawal failed; amount returned to the treasury and proposal re-opened.");
+      }
+      result.is_ok()
+  }
+
+  /// View function.
+  pub fn get_multisig_admins(&self) -> Vec<AccountId> {
+      self.multisig_admins.iter().cloned().collect()
+  }
+
+  /// View function.
+  pub fn get_multisig_config(&self) -> MultisigConfig {
+      self.multisig_config.clone()
+  }
+
+  /// View function.
+  pub fn get_withdrawal_proposal(&self, proposal_id: u64) -> Option<WithdrawalProposal> {
+      self.withdrawal_proposals.get(&proposal_id).cloned()
+  }
+
+  /// View function.
+  pub fn get_multisig_change(&self, change_id: u64) -> Option<MultisigChangeProposal> {
+      self.multisig_changes.get(&change_id).cloned()
+  }
+
+  /// View function.
+  pub fn get_multisig_changes(&self, from_index: Option<u64>, limit: Option<u64>) -> Vec<MultisigChangeProposal> {
+      self.multisig_changes.values()
+          .skip(from_index.unwrap_or(0) as usize)
+          .take(limit.unwrap_or(DEFAULT_PAGE_LIMIT) as usize)
+          .cloned()
+          .collect()
+  }
+
+  /// View function.
+  pub fn get_withdrawal_proposals(&self, from_index: Option<u64>, limit: Option<u64>) -> Vec<WithdrawalProposal> {
+      self.withdrawal_proposals.values()
+          .skip(from_index.unwrap_or(0) as usize)
+          .take(limit.unwrap_or(DEFAULT_PAGE_LIMIT) as usize)
+          .cloned()
+          .collect()
+  }
+}
+
+impl BioCrypticBankCore {
+  pub(crate) fn assert_multisig_admin(&self, account_id: &AccountId) {
+      assert!(self.multisig_admins.contains(account_id), "Only a multisig admin can call this function.");
+  }
+
+  /// Takes `amount` out of the fee treasury for an approved multisig outflow. It must fit in
+  /// the contract's withdrawable surplus and leave the sweep retention buffer behind, and it
+  /// counts towards the circuit breaker's outflow.
+  pub(crate) fn internal_spend_treasury(&mut self, amount: NearToken) {
+      assert!(self.treasury_balance >= amount, "Treasury has insufficient balance.");
+      assert!(
+          self.treasury_balance.saturating_sub(amount) >= self.fee_sweep_config.retention_buffer,
+          "Amount would leave the treasury below its retention buffer."
+      );
+      self.assert_within_surplus(amount);
+      self.treasury_balance = self.treasury_balance.saturating_sub(amount);
+      self.internal_record_contract_outflow(amount);
+  }
+
+  /// Approvals from accounts that are still admins.
+  fn internal_count_admins(&self, approvals: &[AccountId]) -> u32 {
+      approvals.iter().filter(|admin_id| self.multisig_admins.contains(*admin_id)).count() as u32
+  }
+
+  /// Approvals on `proposal_id` from accounts that are still admins.
+  fn internal_valid_approvals(&self, proposal_id: u64) -> u32 {
+      self.withdrawal_proposals.get(&proposal_id).map_or(0, |proposal| self.internal_count_admins(&proposal.approvals))
+  }
+
+  fn internal_add_multisig_admin(&mut self, account_id: AccountId) {
+      assert!(self.multisig_admins.len() < MAX_TREASURY_ADMINS, "Cannot exceed {} admins.", MAX_TREASURY_ADMINS);
+      assert!(self.multisig_admins.insert(account_id.clone()), "Account is already an admin.");
+      BankEvent::MultisigAdminAdded { account_id }.emit();
+  }
+
+  /// Panics unless `action` can be applied to the current admins.
+  fn assert_valid_multisig_action(&self, action: &MultisigAction) {
+      match action {
+          MultisigAction::AddAdmin(account_id) => {
+              assert!(self.multisig_admins.len() < MAX_TREASURY_ADMINS, "Cannot exceed {} admins.", MAX_TREASURY_ADMINS);
+              assert!(!self.multisig_admins.contains(account_id), "Account is already an admin.");
+          }
+          MultisigAction::RemoveAdmin(account_id) => {
+              assert!(self.multisig_admins.contains(account_id), "Account is not an admin.");
+              assert!(
+                  self.multisig_admins.len() > self.multisig_config.approvals_required,
+                  "Removing this admin would leave too few to reach the approval threshold."
+              );
+              assert!(
+                  self.multisig_admins.len() > self.circuit_breaker_config.resets_required,
+                  "Removing this admin would leave too few to reset the circuit breaker."
+              );
+          }
+          MultisigAction::SetConfig(config) => {
+              assert!(config.approvals_required >= 1, "At least one approval is required.");
+              assert!(
+                  config.approvals_required <= self.multisig_admins.len(),
+                  "Approval threshold cannot exceed the number of admins."
+              );
+              assert!(config.timelock_sec >= MIN_TIMELOCK_SEC, "Timelock must be at least {} seconds.", MIN_TIMELOCK_SEC);
+          }
+          MultisigAction::SetCircuitBreakerConfig(config) => self.assert_valid_circuit_breaker_config(config),
+          MultisigAction::SetFeeSweepConfig(_) => {}
+          MultisigAction::FundMatchingPool(amount) | MultisigAction::FundReferralBudget(amount) => {
+              assert!(!amount.is_zero(), "Amount must be greater than 0.");
+          }
+      }
+  }
+
+  fn internal_approve_multisig_change(&mut self, change_id: u64, admin_id: AccountId) {
+      let change = self.multisig_changes.get_mut(&change_id)
+          .filter(|change| matches!(change.status, ProposalStatus::Proposed | ProposalStatus::Approved))
+          .unwrap_or_else(|| env::panic_str("No open proposal with this id."));
+      assert!(!change.approvals.contains(&admin_id), "Already approved.");
+      change.approvals.push(admin_id.clone());
+      BankEvent::MultisigChangeApproved { id: change_id, admin_id }.emit();
+
+      let approvals = self.internal_count_admins(&self.multisig_changes[&change_id].approvals);
+      let required = self.multisig_config.approvals_required;
+      let timelock_sec = self.multisig_config.timelock_sec;
+      let change = self.multisig_changes.get_mut(&change_id).unwrap();
+      if change.status == ProposalStatus::Proposed && approvals >= required {
+          let executable_at = env::block_timestamp() + timelock_sec * NANOS_PER_SECOND;
+          change.status = ProposalStatus::Approved;
+          change.executable_at = Some(executable_at);
+          BankEvent::MultisigChangeProposalApproved { id: change_id, executable_at }.emit();
+      }
+  }
+
+  fn internal_approve_proposal(&mut self, proposal_id: u64, admin_id: AccountId) {
+      let proposal = self.withdrawal_proposals.get_mut(&proposal_id)
+          .filter(|proposal| matches!(proposal.status, ProposalStatus::Proposed | ProposalStatus::Approved))
+          .unwrap_or_else(|| env::panic_str("No open proposal with this id."));
+      assert!(!proposal.approvals.contains(&admin_id), "Already approved.");
+      proposal.approvals.push(admin_id.clone());
+      BankEvent::WithdrawalApproved { id: proposal_id, admin_id }.emit();
+
+      let approvals = self.internal_valid_approvals(proposal_id);
+      let required = self.multisig_config.approvals_required;
+      let timelock_sec = self.multisig_config.timelock_sec;
+      let proposal = self.withdrawal_proposals.get_mut(&proposal_id).unwrap();
+      if proposal.status == ProposalStatus::Proposed && approvals >= required {
+          let executable_at = env::block_timestamp() + timelock_sec * NANOS_PER_SECOND;
+          proposal.status = ProposalStatus::Approved;
+          proposal.executable_at = Some(executable_at);
+          BankEvent::WithdrawalProposalApproved { id: proposal_id, executable_at }.emit();
+      }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use near_sdk::test_utils::{accounts, VMContextBuilder};
+  use near_sdk::testing_env;
+
+  use super::*;
+  use crate::pause::Feature;
+
+  const HOUR: u64 = 60 * 60 * NANOS_PER_SECOND;
+
+  fn call_as(account_id: AccountId, block_timestamp: u64) {
+      testing_env!(VMContextBuilder::new()
+          .predecessor_account_id(account_id)
+          .block_timestamp(block_timestamp)
+          .build());
+  }
+
+  fn bootstrapped() -> BioCrypticBankCore {
+      call_as(accounts(0), 0);
+      let mut contract = BioCrypticBankCore::new(accounts(0));
+      contract.add_multisig_admin(accounts(1));
+      contract.add_multisig_admin(accounts(2));
+      contract
+  }
+
+  #[test]
+  #[should_panic(expected = "propose adding an admin instead")]
+  fn owner_cannot_add_admins_once_they_reach_the_threshold() {
+      let mut contract = bootstrapped();
+      contract.add_multisig_admin(accounts(3));
+  }
+
+  #[test]
+  fn config_changes_need_approvals_and_the_timelock() {
+      let mut contract = bootstrapped();
+      let lowered = MultisigConfig { approvals_required: 1, timelock_sec: MIN_TIMELOCK_SEC };
+      call_as(accounts(1), 0);
+      let id = contract.propose_multisig_change(MultisigAction::SetConfig(lowered.clone()));
+      assert_eq!(contract.get_multisig_change(id).unwrap().status, ProposalStatus::Proposed);
+
+      call_as(accounts(2), 0);
+      contract.approve_multisig_change(id);
+      let executable_at = contract.get_multisig_change(id).unwrap().executable_at.unwrap();
+      assert_eq!(executable_at, 48 * HOUR);
+
+      call_as(accounts(2), executable_at);
+      contract.execute_multisig_change(id);
+      assert_eq!(contract.get_multisig_config(), lowered);
+      assert_eq!(contract.get_multisig_change(id).unwrap().status, ProposalStatus::Executed);
+  }
+
+  #[test]
+  #[should_panic(expected = "Proposal is still timelocked.")]
+  fn config_changes_wait_out_the_timelock() {
+      let mut contract = bootstrapped();
+      call_as(accounts(1), 0);
+      let id = contract.propose_multisig_change(MultisigAction::AddAdmin(accounts(3)));
+      call_as(accounts(2), 0);
+      contract.approve_multisig_change(id);
+      contract.execute_multisig_change(id);
+  }
+
+  #[test]
+  #[should_panic(expected = "Resets required cannot exceed the number of admins.")]
+  fn circuit_breaker_resets_need_enough_admins() {
+      let mut contract = bootstrapped();
+      call_as(accounts(1), 0);
+      contract.propose_multisig_change(MultisigAction::SetCircuitBreakerConfig(CircuitBreakerConfig {
+          resets_required: 3,
+          ..CircuitBreakerConfig::default()
+      }));
+  }
+
+  #[test]
+  fn withdrawals_come_out_of_the_treasury_and_count_as_outflow() {
+      let mut contract = bootstrapped();
+      contract.internal_collect_fee(NearToken::from_near(5));
+      call_as(accounts(1), 0);
+      let id = contract.propose_withdrawal(accounts(3), NearToken::from_near(2), None);
+      call_as(accounts(2), 0);
+      contract.approve_withdrawal(id);
+
+      call_as(accounts(2), 48 * HOUR);
+      contract.execute_withdrawal(id);
+      assert_eq!(contract.get_treasury_balance(), NearToken::from_near(3));
+      assert_eq!(contract.get_circuit_breaker().window_outflow, NearToken::from_near(2));
+  }
+
+  #[test]
+  fn failed_withdrawals_return_to_the_treasury_and_leave_the_outflow() {
+      let mut contract = bootstrapped();
+      contract.internal_collect_fee(NearToken::from_near(5));
+      call_as(accounts(1), 0);
+      let id = contract.propose_withdrawal(accounts(3), NearToken::from_near(2), None);
+      call_as(accounts(2), 0);
+      contract.approve_withdrawal(id);
+
+      call_as(accounts(2), 48 * HOUR);
+      contract.execute_withdrawal(id);
+      assert!(!contract.on_withdrawal_executed(id, Err(PromiseError::Failed)));
+      assert_eq!(contract.get_treasury_balance(), NearToken::from_near(5));
+      assert_eq!(contract.get_circuit_breaker().window_outflow, NearToken::from_near(0));
+      assert_eq!(contract.get_withdrawal_proposal(id).unwrap().status, ProposalStatus::Approved);
+  }
+
+  #[test]
+  fn fee_sweeps_go_to_the_approved_destination_and_count_as_outflow() {
+      let mut contract = bootstrapped();
+      contract.internal_collect_fee(NearToken::from_near(5));
+      let config = FeeSweepConfig { destination: Some(accounts(4)), retention_buffer: NearToken::from_near(1) };
+      call_as(accounts(1), 0);
+      let id = contract.propose_multisig_change(MultisigAction::SetFeeSweepConfig(config.clone()));
+      call_as(accounts(2), 0);
+      contract.approve_multisig_change(id);
+      assert_eq!(contract.get_fee_sweep_config(), FeeSweepConfig::default());
+
+      call_as(accounts(2), 48 * HOUR);
+      contract.execute_multisig_change(id);
+      assert_eq!(contract.get_fee_sweep_config(), config);
+
+      call_as(accounts(5), 48 * HOUR);
+      assert_eq!(contract.sweep_fees(), NearToken::from_near(4));
+      assert_eq!(contract.get_treasury_balance(), NearToken::from_near(1));
+      assert_eq!(contract.get_circuit_breaker().window_outflow, NearToken::from_near(4));
+  }
+
+  #[test]
+  fn matching_pool_funding_is_a_timelocked_treasury_outflow() {
+      let mut contract = bootstrapped();
+      contract.internal_collect_fee(NearToken::from_near(5));
+      call_as(accounts(1), 0);
+      let id = contract.propose_multisig_change(MultisigAction::FundMatchingPool(NearToken::from_near(2)));
+      call_as(accounts(2), 0);
+      contract.approve_multisig_change(id);
+      assert!(contract.get_matching_pool().is_zero());
+
+      call_as(accounts(2), 48 * HOUR);
+      contract.execute_multisig_change(id);
+      assert_eq!(contract.get_matching_pool(), NearToken::from_near(2));
+      assert_eq!(contract.get_treasury_balance(), NearToken::from_near(3));
+      assert_eq!(contract.get_circuit_breaker().window_outflow, NearToken::from_near(2));
+  }
+
+  #[test]
+  fn referral_budget_funding_is_a_timelocked_treasury_outflow() {
+      let mut contract = bootstrapped();
+      contract.internal_collect_fee(NearToken::from_near(5));
+      call_as(accounts(1), 0);
+      let id = contract.propose_multisig_change(MultisigAction::FundReferralBudget(NearToken::from_near(2)));
+      call_as(accounts(2), 0);
+      contract.approve_multisig_change(id);
+
+      call_as(accounts(2), 48 * HOUR);
+      contract.execute_multisig_change(id);
+      assert_eq!(contract.get_referral_budget(), NearToken::from_near(2));
+      assert_eq!(contract.get_treasury_balance(), NearToken::from_near(3));
+      assert_eq!(contract.get_circuit_breaker().window_outflow, NearToken::from_near(2));
+  }
+
+  #[test]
+  #[should_panic(expected = "below its retention buffer")]
+  fn matching_pool_funding_leaves_the_retention_buffer() {
+      let mut contract = bootstrapped();
+      contract.internal_collect_fee(NearToken::from_near(5));
+      contract.internal_set_fee_sweep_config(FeeSweepConfig { destination: None, retention_buffer: NearToken::from_near(4) });
+      call_as(accounts(1), 0);
+      let id = contract.propose_multisig_change(MultisigAction::FundMatchingPool(NearToken::from_near(2)));
+      call_as(accounts(2), 0);
+      contract.approve_multisig_change(id);
+      call_as(accounts(2), 48 * HOUR);
+      contract.execute_multisig_change(id);
+  }
+
+  #[test]
+  fn circuit_breaker_nets_inflows_against_outflows() {
+      let mut contract = bootstrapped();
+      contract.internal_record_contract_inflow(NearToken::from_near(500));
+      contract.internal_record_contract_outflow(NearToken::from_near(500));
+      assert!(contract.get_circuit_breaker().state.tripped_at.is_none());
+
+      contract.internal_record_contract_outflow(NearToken::from_near(101));
+      assert!(contract.get_circuit_breaker().state.tripped_at.is_some());
+      assert!(contract.is_paused(Feature::Withdrawals));
+  }
+
+  #[test]
+  fn circuit_breaker_stays_disarmed_without_enough_admins_to_reset_it() {
+      call_as(accounts(0), 0);
+      let mut contract = BioCrypticBankCore::new(accounts(0));
+      contract.add_multisig_admin(accounts(1));
+      contract.internal_record_contract_outflow(NearToken::from_near(1_000));
+      assert!(contract.get_circuit_breaker().state.tripped_at.is_none());
+      assert!(!contract.is_paused(Feature::Withdrawals));
+  }
+
+  #[test]
+  fn circuit_breaker_reset_keeps_a_manual_withdrawals_pause() {
+      let mut contract = bootstrapped();
+      contract.internal_record_contract_outflow(NearToken::from_near(1_000));
+      contract.pause_state.paused_features.push(Feature::Withdrawals);
+
+      call_as(accounts(1), 0);
+      contract.approve_circuit_breaker_reset();
+      call_as(accounts(2), 0);
+      contract.approve_circuit_breaker_reset();
+      assert!(contract.get_circuit_breaker().state.tripped_at.is_none());
+      assert!(contract.is_paused(Feature::Withdrawals));
+  }
+}
@@ -0,0 +1,491 @@
+// services/blockchain/near-rs/core-banking/src/nft_credit.rs
+use near_sdk::{near, env, ext_contract, AccountId, Gas, NearToken, Promise, PromiseError, PromiseOrValue};
+
+use crate::ledger::{EntryDirection, EntryKind};
+use crate::pause::Feature;
+use crate::roles::Role;
+use crate::savings::{BPS_DENOMINATOR, SECONDS_PER_YEAR};
+use crate::term_deposits::NANOS_PER_DAY;
+use crate::{mul_div, BankEvent, BioCrypticBankCore, BioCrypticBankCoreExt, DEFAULT_PAGE_LIMIT};
+
+const NANOS_PER_SECOND: u64 = 1_000_000_000;
+const MAX_NFT_CREDIT_LINES_PER_ACCOUNT: usize = 10;
+const MAX_TOKEN_ID_LENGTH: usize = 256;
+const GAS_FOR_NFT_TRANSFER: Gas = Gas::from_tgas(20);
+const GAS_FOR_NFT_RELEASE_CALLBACK: Gas = Gas::from_tgas(15);
+
+/// Interface of a NEP-171 non-fungible token contract.
+#[ext_contract(ext_nft)]
+#[allow(dead_code)]
+trait NonFungibleToken {
+  fn nft_transfer(&mut self, receiver_id: AccountId, token_id: String, approval_id: Option<u64>, memo: Option<String>);
+}
+
+/// Terms of NFT-backed credit lines and their default auctions. Owner controlled.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct NftCreditConfig {
+  /// Days from pledging until the line must be repaid.
+  pub term_days: u32,
+  /// Days an appraisal stays valid for pledging.
+  pub appraisal_ttl_days: u32,
+  /// Length of a default auction.
+  pub auction_duration_sec: u64,
+  /// Amount each bid must beat the highest one by.
+  pub min_bid_increment_bps: u16,
+}
+
+impl Default for NftCreditConfig {
+  fn default() -> Self {
+      Self {
+          term_days: 90,
+          appraisal_ttl_days: 7,
+          auction_duration_sec: 24 * 60 * 60,
+          min_bid_increment_bps: 500,
+      }
+  }
+}
+
+/// Value an appraiser assigned to one token.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct NftAppraisal {
+  pub value: NearToken,
+  pub appraiser_id: AccountId,
+  pub appraised_at: u64,
+}
+
+#[near(serializers = [borsh, json])]
+#[serde(rename_all = "snake_case")]
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum NftCreditStatus {
+  /// The NFT is held as collateral and the line can be drawn until it is due.
+  Active,
+  /// Past due with debt outstanding; the NFT is being auctioned.
+  Auction,
+  /// Repaid or auctioned; the NFT is waiting to be sent to `release_to`.
+  Settled,
+  /// The NFT transfer to `release_to` is in flight.
+  Releasing,
+}
+
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct NftAuction {
+  /// Smallest first bid: the debt when the auction started.
+  pub reserve: NearToken,
+  pub ends_at: u64,
+  pub highest_bidder: Option<AccountId>,
+  pub highest_bid: NearToken,
+}
+
+/// Fixed credit line secured by a pledged NFT.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct NftCreditLine {
+  pub id: u64,
+  pub borrower_id: AccountId,
+  pub nft_contract_id: AccountId,
+  pub token_id: String,
+  /// Appraised value times the collection's LTV at pledge time.
+  pub limit: NearToken,
+  pub principal: NearToken,
+  /// Interest accrued and not yet repaid.
+  pub interest: NearToken,
+  pub last_accrued_at: u64,
+  pub opened_at: u64,
+  pub due_at: u64,
+  pub status: NftCreditStatus,
+  pub auction: Option<NftAuction>,
+  /// Account the NFT goes to once the line is settled.
+  pub release_to: Option<AccountId>,
+}
+
+impl NftCreditLine {
+  pub(crate) fn debt(&self) -> NearToken {
+      self.principal.saturating_add(self.interest)
+  }
+
+  fn accrue(&mut self, apr_bps: u16, now: u64) {
+      let seconds = now.saturating_sub(self.last_accrued_at) / NANOS_PER_SECOND;
+      if seconds == 0 {
+          return;
+      }
+      let interest = mul_div(
+          self.principal.as_yoctonear(), apr_bps as u128 * seconds as u128, BPS_DENOMINATOR * SECONDS_PER_YEAR,
+      );
+      self.interest = self.interest.saturating_add(NearToken::from_yoctonear(interest));
+      self.last_accrued_at += seconds * NANOS_PER_SECOND;
+  }
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Accepts NFTs of `nft_contract_id` as collateral, lending up to `ltv_bps` of their
+  /// appraised value, or stops accepting new pledges with `None`. Owner only.
+  pub fn set_nft_collection(&mut self, nft_contract_id: AccountId, ltv_bps: Option<u16>) {
+      self.assert_owner();
+      match ltv_bps {
+          Some(ltv_bps) => {
+              assert!(ltv_bps > 0 && (ltv_bps as u128) < BPS_DENOMINATOR, "LTV must be between 0 and 100%.");
+              self.nft_collections.insert(nft_contract_id.clone(), ltv_bps);
+          }
+          None => {
+              assert!(self.nft_collections.remove(&nft_contract_id).is_some(), "Collection is not accepted.");
+          }
+      }
+      BankEvent::NftCollectionUpdated { nft_contract_id, ltv_bps }.emit();
+  }
+
+  /// Owner only.
+  pub fn set_nft_credit_config(&mut self, config: NftCreditConfig) {
+      self.assert_owner();
+      assert!(config.term_days > 0, "Term must be at least one day.");
+      assert!(config.appraisal_ttl_days > 0, "Appraisals must stay valid for at least one day.");
+      assert!(config.auction_duration_sec > 0, "Auction duration must be greater than 0.");
+      assert!(config.min_bid_increment_bps as u128 <= BPS_DENOMINATOR, "Bid increment cannot exceed 10000 bps.");
+      self.nft_credit_config = config;
+  }
+
+  /// Records the value of token `token_id` of a whitelisted collection, to be used by the
+  /// next pledge of that token. NftAppraiser only.
+  pub fn appraise_nft(&mut self, nft_contract_id: AccountId, token_id: String, value: NearToken) {
+      self.assert_role(Role::NftAppraiser);
+      assert!(self.nft_collections.contains_key(&nft_contract_id), "Collection is not accepted.");
+      assert!(token_id.len() <= MAX_TOKEN_ID_LENGTH, "Token id is too long.");
+      assert!(!value.is_zero(), "Value must be greater than 0.");
+      let appraiser_id = env::predecessor_account_id();
+      self.nft_appraisals.insert((nft_contract_id.clone(), token_id.clone()), NftAppraisal {
+          value,
+          appraiser_id: appraiser_id.clone(),
+          appraised_at: env::block_timestamp(),
+      });
+      BankEvent::NftAppraised { nft_contract_id, token_id, value, appraiser_id }.emit();
+  }
+
+  /// NEP-171 receiver hook. Pledges the NFT as collateral for a new credit line of
+  /// `previous_owner_id`, limited by a fresh appraisal and the collection's LTV. Only
+  /// appraised tokens of whitelisted collections from registered owners are accepted;
+  /// the token is never returned by this call.
+  #[allow(unused_variables)]
+  pub fn nft_on_transfer(
+      &mut self,
+      sender_id: AccountId,
+      previous_owner_id: AccountId,
+      token_id: String,
+      msg: String,
+  ) -> PromiseOrValue<bool> {
+      self.assert_not_paused(Feature::Lending);
+      let initial_storage = env::storage_usage();
+      let nft_contract_id = env::predecessor_account_id();
+      let ltv_bps = *self.nft_collections.get(&nft_contract_id)
+          .unwrap_or_else(|| env::panic_str("Collection is not accepted."));
+      self.assert_registered(&previous_owner_id);
+      let now = env::block_timestamp();
+      let config = self.nft_credit_config.clone();
+      let appraisal = self.nft_appraisals.remove(&(nft_contract_id.clone(), token_id.clone()))
+          .filter(|appraisal| now - appraisal.appraised_at <= config.appraisal_ttl_days as u64 * NANOS_PER_DAY)
+          .unwrap_or_else(|| env::panic_str("Token has no current appraisal."));
+      let limit = NearToken::from_yoctonear(mul_div(appraisal.value.as_yoctonear(), ltv_bps as u128, BPS_DENOMINATOR));
+
+      let id = self.next_nft_credit_line_id;
+      self.next_nft_credit_line_id += 1;
+      let line_ids = self.nft_credit_lines_by_account.entry(previous_owner_id.clone()).or_default();
+      assert!(line_ids.len() < MAX_NFT_CREDIT_LINES_PER_ACCOUNT, "Too many NFT credit lines.");
+      line_ids.push(id);
+      let due_at = now + config.term_days as u64 * NANOS_PER_DAY;
+      self.nft_credit_lines.insert(id, NftCreditLine {
+          id,
+          borrower_id: previous_owner_id.clone(),
+          nft_contract_id: nft_contract_id.clone(),
+          token_id: token_id.clone(),
+          limit,
+          principal: NearToken::from_yoctonear(0),
+          interest: NearToken::from_yoctonear(0),
+          last_accrued_at: now,
+          opened_at: now,
+          due_at,
+          status: NftCreditStatus::Active,
+          auction: None,
+          release_to: None,
+      });
+      self.internal_charge_storage(&previous_owner_id, initial_storage);
+      BankEvent::NftPledged { line_id: id, borrower_id: previous_owner_id, nft_contract_id, token_id, limit, due_at }.emit();
+      PromiseOrValue::Value(false)
+  }
+
+  /// Draws `amount` from credit line `line_id` into the caller's balance, up to its limit
+  /// and before it is due. Borrower only.
+  pub fn draw_nft_credit(&mut self, line_id: u64, amount: NearToken) {
+      self.assert_not_paused(Feature::Lending);
+      assert!(!amount.is_zero(), "Amount must be greater than 0.");
+      let borrower_id = env::predecessor_account_id();
+      let now = env::block_timestamp();
+      let apr_bps = self.internal_borrow_apr_bps();
+      let line = self.internal_get_nft_credit_line_mut(line_id);
+      assert_eq!(line.borrower_id, borrower_id, "Only the borrower can draw the credit line.");
+      assert!(line.status == NftCreditStatus::Active && now < line.due_at, "Credit line is not open for drawing.");
+      assert!(line.principal.saturating_add(amount) <= line.limit, "Amount exceeds the credit line's limit.");
+      line.accrue(apr_bps, now);
+      line.principal = line.principal.saturating_add(amount);
+      let total_borrowed = self.total_borrowed.saturating_add(amount);
+      assert!(total_borrowed <= self.lending_config.borrow_cap, "Borrow cap reached.");
+      self.total_borrowed = total_borrowed;
+
+      self.internal_credit(&borrower_id, amount);
+      self.internal_add_liabilities(amount);
+      self.internal_record_entry(
+          &borrower_id, EntryKind::NftCredit, EntryDirection::Credit, amount, None, Some(format!("nft_line:{}", line_id)),
+      );
+      BankEvent::NftCreditDrawn { line_id, borrower_id, amount }.emit();
+  }
+
+  /// Repays up to `amount` of credit line `line_id` from the caller's balance, interest
+  /// first. Repaying during the default auction stops it once the debt is cleared.
+  /// Borrower only. Returns the amount applied.
+  pub fn repay_nft_credit(&mut self, line_id: u64, amount: NearToken) -> NearToken {
+      let payer_id = env::predecessor_account_id();
+      let apr_bps = self.internal_borrow_apr_bps();
+      let line = self.internal_get_nft_credit_line_mut(line_id);
+      assert_eq!(line.borrower_id, payer_id, "Only the borrower can repay the credit line.");
+      assert!(matches!(line.status, NftCreditStatus::Active | NftCreditStatus::Auction), "Credit line is settled.");
+      line.accrue(apr_bps, env::block_timestamp());
+      let amount = amount.min(line.debt());
+      assert!(!amount.is_zero(), "Amount must be greater than 0.");
+
+      self.internal_debit(&payer_id, amount);
+      self.internal_record_entry(
+          &payer_id, EntryKind::NftCredit, EntryDirection::Debit, amount, None, Some(format!("nft_line:{}", line_id)),
+      );
+      self.internal_apply_nft_credit_repayment(line_id, amount);
+      let line = self.internal_get_nft_credit_line_mut(line_id);
+      if line.status == NftCreditStatus::Auction && line.debt().is_zero() {
+          line.status = NftCreditStatus::Active;
+          let auction = line.auction.take().unwrap();
+          if let Some(bidder_id) = auction.highest_bidder {
+              self.internal_refund_nft_bid(line_id, &bidder_id, auction.highest_bid);
+          }
+      }
+      amount
+  }
+
+  /// Closes fully repaid credit line `line_id` and returns the NFT to the borrower.
+  /// Borrower only.
+  pub fn close_nft_credit_line(&mut self, line_id: u64) -> Promise {
+      let borrower_id = env::predecessor_account_id();
+      let apr_bps = self.internal_borrow_apr_bps();
+      let line = self.internal_get_nft_credit_line_mut(line_id);
+      assert_eq!(line.borrower_id, borrower_id, "Only the borrower can close the credit line.");
+      assert!(line.status == NftCreditStatus::Active, "Credit line is not active.");
+      line.accrue(apr_bps, env::block_timestamp());
+      assert!(line.debt().is_zero(), "Repay the credit line before closing it.");
+      line.status = NftCreditStatus::Settled;
+      line.release_to = Some(borrower_id);
+      self.release_nft_collateral(line_id)
+  }
+
+  /// Puts the NFT of overdue credit line `line_id` up for auction, with the debt as the
+  /// reserve. Callable by anyone.
+  pub fn start_nft_auction(&mut self, line_id: u64) {
+      let now = env::block_timestamp();
+      let apr_bps = self.internal_borrow_apr_bps();
+      let duration = self.nft_credit_config.auction_duration_sec * NANOS_PER_SECOND;
+      let line = self.internal_get_nft_credit_line_mut(line_id);
+      assert!(line.status == NftCreditStatus::Active && now >= line.due_at, "Credit line is not past due.");
+      line.accrue(apr_bps, now);
+      let reserve = line.debt();
+      assert!(!reserve.is_zero(), "Credit line has no debt.");
+      let ends_at = now + duration;
+      line.status = NftCreditStatus::Auction;
+      line.auction = Some(NftAuction { reserve, ends_at, highest_bidder: None, highest_bid: NearToken::from_yoctonear(0) });
+      let borrower_id = line.borrower_id.clone();
+      self.internal_update_credit(&borrower_id, |record| record.late_repayments += 1);
+      BankEvent::NftAuctionStarted { line_id, reserve, ends_at }.emit();
+  }
+
+  /// Bids `amount` from the caller's unallocated balance on the NFT of credit line
+  /// `line_id`. The first bid must meet the reserve and later ones must beat the highest
+  /// by the minimum increment; the outbid bidder is refunded.
+  pub fn bid_on_nft(&mut self, line_id: u64, amount: NearToken) {
+      let bidder_id = env::predecessor_account_id();
+      self.assert_registered(&bidder_id);
+      let increment_bps = self.nft_credit_config.min_bid_increment_bps as u128;
+      let line = self.nft_credit_lines.get(&line_id).unwrap_or_else(|| env::panic_str("Credit line not found."));
+      assert!(line.borrower_id != bidder_id, "Borrowers cannot bid on their own collateral.");
+      let auction = line.auction.clone()
+          .filter(|auction| env::block_timestamp() < auction.ends_at)
+          .unwrap_or_else(|| env::panic_str("No auction is running for this credit line."));
+      let min_bid = if auction.highest_bidder.is_some() {
+          let highest = auction.highest_bid.as_yoctonear();
+          NearToken::from_yoctonear(highest + mul_div(highest, increment_bps, BPS_DENOMINATOR).max(1))
+      } else {
+          auction.reserve
+      };
+      assert!(amount >= min_bid, "Bid must be at least {} yoctoNEAR.", min_bid.as_yoctonear());
+      assert!(self.internal_unallocated_balance(&bidder_id) >= amount, "Insufficient unallocated balance.");
+      self.internal_debit(&bidder_id, amount);
+      self.internal_record_entry(
+          &bidder_id, EntryKind::NftCredit, EntryDirection::Debit, amount, None, Some(format!("nft_line:{}", line_id)),
+      );
+      if let Some(previous_id) = auction.highest_bidder {
+          self.internal_refund_nft_bid(line_id, &previous_id, auction.highest_bid);
+      }
+      let line = self.internal_get_nft_credit_line_mut(line_id);
+      line.auction = Some(NftAuction { highest_bidder: Some(bidder_id.clone()), highest_bid: amount, ..auction });
+      BankEvent::NftAuctionBid { line_id, bidder_id, amount }.emit();
+  }
+
+  /// Ends the auction of credit line `line_id` and sends the NFT to the winner. The winning
+  /// bid repays the debt and any surplus goes to the borrower. Without bids, the NFT goes to
+  /// the owner account and the debt is written off. Callable by anyone after the auction ends.
+  pub fn settle_nft_auction(&mut self, line_id: u64) -> Promise {
+      let now = env::block_timestamp();
+      let apr_bps = self.internal_borrow_apr_bps();
+      let owner_id = self.owner_id.clone();
+      let line = self.internal_get_nft_credit_line_mut(line_id);
+      let auction = line.auction.take()
+          .filter(|auction| line.status == NftCreditStatus::Auction && now >= auction.ends_at)
+          .unwrap_or_else(|| env::panic_str("No finished auction for this credit line."));
+      line.accrue(apr_bps, now);
+      line.status = NftCreditStatus::Settled;
+      line.release_to = Some(auction.highest_bidder.clone().unwrap_or(owner_id));
+      let line = line.clone();
+
+      let proceeds = auction.highest_bid;
+      let repaid = proceeds.min(line.debt());
+      if !repaid.is_zero() {
+          self.internal_apply_nft_credit_repayment(line_id, repaid);
+      }
+      let surplus = proceeds.saturating_sub(repaid);
+      if !surplus.is_zero() {
+          self.internal_credit(&line.borrower_id, surplus);
+          self.internal_record_entry(
+              &line.borrower_id, EntryKind::NftCredit, EntryDirection::Credit, surplus, None, Some(format!("nft_line:{}", line_id)),
+          );
+      }
+      let line = self.internal_get_nft_credit_line_mut(line_id);
+      let written_off = line.principal;
+      line.principal = NearToken::from_yoctonear(0);
+      line.interest = NearToken::from_yoctonear(0);
+      let borrower_id = line.borrower_id.clone();
+      self.total_borrowed = self.total_borrowed.saturating_sub(written_off);
+      self.internal_update_credit(&borrower_id, |record| record.liquidations += 1);
+      BankEvent::NftAuctionSettled { line_id, winner_id: auction.highest_bidder, proceeds, written_off }.emit();
+      self.release_nft_collateral(line_id)
+  }
+
+  /// Sends the NFT of settled credit line `line_id` to its recipient, e.g. to retry a
+  /// transfer that failed. Callable by anyone.
+  pub fn release_nft_collateral(&mut self, line_id: u64) -> Promise {
+      let line = self.internal_get_nft_credit_line_mut(line_id);
+      assert!(line.status == NftCreditStatus::Settled, "Credit line is not settled.");
+      line.status = NftCreditStatus::Releasing;
+      let receiver_id = line.release_to.clone().unwrap();
+      ext_nft::ext(line.nft_contract_id.clone())
+          .with_attached_deposit(NearToken::from_yoctonear(1))
+          .with_static_gas(GAS_FOR_NFT_TRANSFER)
+          .nft_transfer(receiver_id.clone(), line.token_id.clone(), None, Some(format!("nft_line:{}", line_id)))
+          .then(
+              Self::ext(env::current_account_id())
+                  .with_static_gas(GAS_FOR_NFT_RELEASE_CALLBACK)
+                  .on_nft_collateral_released(line_id, receiver_id)
+          )
+  }
+
+  /// Callback removing the credit line once its NFT is delivered, or leaving it settled so
+  /// the release can be retried.
+  #[private]
+  pub fn on_nft_collateral_released(
+      &mut self,
+      line_id: u64,
+      receiver_id: AccountId,
+      #[callback_result] result: Result<(), PromiseError>,
+  ) -> bool {
+      let line = self.internal_get_nft_credit_line_mut(line_id);
+      if result.is_err() {
+          line.status = NftCreditStatus::Settled;
+          env::log_str(&format!("NFT release of credit line {} to {} failed", line_id, receiver_id));
+          return false;
+      }
+      let line = line.clone();
+      self.nft_credit_lines.remove(&line_id);
+      if let Some(ids) = self.nft_credit_lines_by_account.get_mut(&line.borrower_id) {
+          ids.retain(|existing| *existing != line_id);
+          if ids.is_empty() {
+              self.nft_credit_lines_by_account.remove(&line.borrower_id);
+          }
+      }
+      BankEvent::NftCollateralReleased { line_id, nft_contract_id: line.nft_contract_id, token_id: line.token_id, receiver_id }.emit();
+      true
+  }
+
+  /// Credit line `line_id` with interest accrued up to now.
+  /// View function.
+  pub fn get_nft_credit_line(&self, line_id: u64) -> Option<NftCreditLine> {
+      let mut line = self.nft_credit_lines.get(&line_id)?.clone();
+      if matches!(line.status, NftCreditStatus::Active | NftCreditStatus::Auction) {
+          line.accrue(self.internal_borrow_apr_bps(), env::block_timestamp());
+      }
+      Some(line)
+  }
+
+  /// View function.
+  pub fn get_nft_credit_lines_for_account(&self, account_id: AccountId) -> Vec<NftCreditLine> {
+      self.nft_credit_lines_by_account.get(&account_id)
+          .map(|ids| ids.iter().filter_map(|id| self.get_nft_credit_line(*id)).collect())
+          .unwrap_or_default()
+  }
+
+  /// Pages through every credit line, e.g. to find overdue lines to auction.
+  /// View function.
+  pub fn get_nft_credit_lines(&self, from_index: Option<u64>, limit: Option<u64>) -> Vec<NftCreditLine> {
+      self.nft_credit_lines.keys()
+          .skip(from_index.unwrap_or(0) as usize)
+          .take(limit.unwrap_or(DEFAULT_PAGE_LIMIT) as usize)
+          .filter_map(|id| self.get_nft_credit_line(*id))
+          .collect()
+  }
+
+  /// View function.
+  pub fn get_nft_appraisal(&self, nft_contract_id: AccountId, token_id: String) -> Option<NftAppraisal> {
+      self.nft_appraisals.get(&(nft_contract_id, token_id)).cloned()
+  }
+
+  /// View function.
+  pub fn get_nft_collections(&self) -> Vec<(AccountId, u16)> {
+      self.nft_collections.iter().map(|(contract_id, ltv_bps)| (contract_id.clone(), *ltv_bps)).collect()
+  }
+
+  /// View function.
+  pub fn get_nft_credit_config(&self) -> NftCreditConfig {
+      self.nft_credit_config.clone()
+  }
+}
+
+impl BioCrypticBankCore {
+  fn internal_get_nft_credit_line_mut(&mut self, line_id: u64) -> &mut NftCreditLine {
+      self.nft_credit_lines.get_mut(&line_id).unwrap_or_else(|| env::panic_str("Credit line not found."))
+  }
+
+  /// Applies `amount` (already collected) to an accrued credit line, interest first;
+  /// interest is split between savers and the treasury.
+  fn internal_apply_nft_credit_repayment(&mut self, line_id: u64, amount: NearToken) {
+      let line = self.internal_get_nft_credit_line_mut(line_id);
+      let interest = amount.min(line.interest);
+      let principal = amount.saturating_sub(interest).min(line.principal);
+      line.interest = line.interest.saturating_sub(interest);
+      line.principal = line.principal.saturating_sub(principal);
+      let debt_remaining = line.debt();
+      self.total_borrowed = self.total_borrowed.saturating_sub(principal);
+      self.internal_remove_liabilities(principal);
+      self.internal_collect_revenue(interest);
+      BankEvent::NftCreditRepaid { line_id, interest, principal, debt_remaining }.emit();
+  }
+
+  fn internal_refund_nft_bid(&mut self, line_id: u64, bidder_id: &AccountId, amount: NearToken) {
+      self.internal_credit(bidder_id, amount);
+      self.internal_record_entry(
+          bidder_id, EntryKind::NftCredit, EntryDirection::Credit, amount, None, Some(format!("nft_line:{}", line_id)),
+      );
+  }
+}
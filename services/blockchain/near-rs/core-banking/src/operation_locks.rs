@@ -0,0 +1,133 @@
+// services/blockchain/near-rs/core-banking/src/operation_locks.rs
+use near_sdk::{near, env, AccountId};
+
+use crate::{BankEvent, BioCrypticBankCore, BioCrypticBankCoreExt};
+
+const NANOS_PER_SECOND: u64 = 1_000_000_000;
+/// How long an operation may await its callback before its lock can be broken. Callbacks
+/// normally land within a few blocks; a lock this old belongs to a stuck promise.
+pub const OPERATION_LOCK_TTL_SEC: u64 = 15 * 60;
+
+/// Asynchronous operations that hold an account's lock from the initiating call until
+/// their callback.
+#[near(serializers = [borsh, json])]
+#[serde(rename_all = "snake_case")]
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum LockedOperation {
+  Withdrawal,
+  TokenWithdrawal,
+  Swap,
+  WnearConversion,
+  /// A deposit, withdrawal or transfer deferred until the DID registry answers.
+  KycCheck,
+  /// A signed withdrawal authorization awaiting its signing key from the DID registry.
+  AuthorizedWithdrawal,
+}
+
+/// An account's in-flight operation. While it is held, no other locked operation can start
+/// for the account, so two promises never act on the same balance at once.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct OperationLock {
+  pub id: u64,
+  pub operation: LockedOperation,
+  pub locked_at: u64,
+  /// After this time (ns) the lock no longer blocks and can be released by anyone.
+  pub expires_at: u64,
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Releases `account_id`'s lock once it has expired, e.g. after a callback ran out of gas.
+  /// A late callback of the broken lock still settles its funds but releases nothing.
+  /// Callable by anyone.
+  pub fn release_expired_operation_lock(&mut self, account_id: AccountId) {
+      let lock = self.operation_locks.get(&account_id).cloned()
+          .unwrap_or_else(|| env::panic_str("Account has no operation lock."));
+      assert!(env::block_timestamp() > lock.expires_at, "Operation lock has not expired.");
+      self.operation_locks.remove(&account_id);
+      BankEvent::OperationLockExpired { account_id, lock_id: lock.id, operation: lock.operation }.emit();
+  }
+
+  /// `account_id`'s in-flight operation, if any, including an expired one not yet released.
+  /// View function.
+  pub fn get_operation_lock(&self, account_id: AccountId) -> Option<OperationLock> {
+      self.operation_locks.get(&account_id).cloned()
+  }
+}
+
+impl BioCrypticBankCore {
+  /// Locks `account_id` for `operation` until its callback passes the returned id to
+  /// `internal_unlock_account`. Panics while another operation holds an unexpired lock; an
+  /// expired lock is broken.
+  pub(crate) fn internal_lock_account(&mut self, account_id: &AccountId, operation: LockedOperation) -> u64 {
+      let now = env::block_timestamp();
+      if let Some(lock) = self.operation_locks.get(account_id).cloned() {
+          assert!(now > lock.expires_at, "Another operation is in flight for this account. Retry once it settles.");
+          BankEvent::OperationLockExpired { account_id: account_id.clone(), lock_id: lock.id, operation: lock.operation }.emit();
+      }
+      let id = self.next_operation_lock_id;
+      self.next_operation_lock_id += 1;
+      self.operation_locks.insert(account_id.clone(), OperationLock {
+          id,
+          operation,
+          locked_at: now,
+          expires_at: now + OPERATION_LOCK_TTL_SEC * NANOS_PER_SECOND,
+      });
+      id
+  }
+
+  /// Panics while another operation holds an unexpired lock on `account_id`. Every debit
+  /// runs this check, so funds cannot leave an account whose balance a promise is acting on.
+  pub(crate) fn assert_not_locked(&self, account_id: &AccountId) {
+      assert!(
+          self.operation_locks.get(account_id).is_none_or(|lock| env::block_timestamp() > lock.expires_at),
+          "Another operation is in flight for this account. Retry once it settles."
+      );
+  }
+
+  /// Releases `account_id`'s lock if it is still lock `lock_id`. Callbacks of operations
+  /// that took no lock, such as queued withdrawal payouts, pass `None`.
+  pub(crate) fn internal_unlock_account(&mut self, account_id: &AccountId, lock_id: Option<u64>) {
+      if lock_id.is_some() && self.operation_locks.get(account_id).map(|lock| lock.id) == lock_id {
+          self.operation_locks.remove(account_id);
+      }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use near_sdk::test_utils::{accounts, VMContextBuilder};
+  use near_sdk::{testing_env, NearToken};
+
+  use super::*;
+
+  fn funded() -> BioCrypticBankCore {
+      testing_env!(VMContextBuilder::new()
+          .predecessor_account_id(accounts(1))
+          .attached_deposit(NearToken::from_near(1))
+          .build());
+      let mut contract = BioCrypticBankCore::new(accounts(0));
+      contract.storage_deposit(None, None);
+      contract.storage_deposit(Some(accounts(2)), None);
+      contract.deposit(None, None);
+      testing_env!(VMContextBuilder::new().predecessor_account_id(accounts(1)).build());
+      contract
+  }
+
+  #[test]
+  #[should_panic(expected = "Another operation is in flight for this account.")]
+  fn locked_accounts_cannot_transfer() {
+      let mut contract = funded();
+      contract.internal_lock_account(&accounts(1), LockedOperation::Swap);
+      contract.transfer(accounts(2), NearToken::from_millinear(1), None, None, None);
+  }
+
+  #[test]
+  fn withdrawals_hold_the_lock_until_their_callback() {
+      let mut contract = funded();
+      contract.withdraw(NearToken::from_millinear(1), None, None, None);
+      let lock = contract.get_operation_lock(accounts(1)).expect("Withdrawal should hold the lock.");
+      assert_eq!(lock.operation, LockedOperation::Withdrawal);
+  }
+}
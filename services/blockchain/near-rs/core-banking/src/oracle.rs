@@ -0,0 +1,230 @@
+// services/blockchain/near-rs/core-banking/src/oracle.rs
+use near_sdk::{near, env, ext_contract, AccountId, Gas, NearToken, Promise, PromiseError};
+use near_sdk::json_types::{U128, U64};
+use biocrypticbank_common::assets::AssetId;
+
+use crate::{mul_div, BankEvent, BioCrypticBankCore, BioCrypticBankCoreExt};
+
+const NANOS_PER_SECOND: u64 = 1_000_000_000;
+const GAS_FOR_PRICE_DATA: Gas = Gas::from_tgas(10);
+const GAS_FOR_PRICE_DATA_CALLBACK: Gas = Gas::from_tgas(10);
+/// Decimals of the USD amounts returned by this contract.
+pub const USD_DECIMALS: u8 = 6;
+
+/// USD price of one smallest unit of an asset: `multiplier / 10^decimals`.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct Price {
+  pub multiplier: U128,
+  pub decimals: u8,
+}
+
+#[near(serializers = [json])]
+#[derive(Debug, PartialEq)]
+pub struct AssetOptionalPrice {
+  pub asset_id: String,
+  pub price: Option<Price>,
+}
+
+/// Response of the oracle's `get_price_data`.
+#[near(serializers = [json])]
+#[derive(Debug, PartialEq)]
+pub struct PriceData {
+  pub timestamp: U64,
+  pub recency_duration_sec: u32,
+  pub prices: Vec<AssetOptionalPrice>,
+}
+
+/// Interface of a NEAR price oracle contract (`priceoracle.near` style).
+#[ext_contract(ext_price_oracle)]
+#[allow(dead_code)]
+trait PriceOracle {
+  fn get_price_data(&self, asset_ids: Option<Vec<String>>) -> PriceData;
+}
+
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct OracleConfig {
+  pub oracle_id: Option<AccountId>,
+  /// Oracle asset id quoting NEAR itself.
+  pub near_asset_id: String,
+  /// Cached prices older than this are stale and cannot be used to borrow or liquidate.
+  pub max_price_age_sec: u64,
+}
+
+impl Default for OracleConfig {
+  fn default() -> Self {
+      Self { oracle_id: None, near_asset_id: "wrap.near".to_string(), max_price_age_sec: 300 }
+  }
+}
+
+/// Cached oracle price and the oracle timestamp (ns) it was published at.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct CachedPrice {
+  pub price: Price,
+  pub timestamp: u64,
+}
+
+#[near(serializers = [json])]
+#[derive(Debug, PartialEq)]
+pub struct PriceView {
+  pub asset_id: String,
+  pub price: Price,
+  pub timestamp: u64,
+  pub stale: bool,
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Sets the oracle contract, NEAR's asset id on it and the staleness window. Owner only.
+  pub fn set_oracle_config(&mut self, config: OracleConfig) {
+      self.assert_owner();
+      assert!(config.max_price_age_sec > 0, "Maximum price age must be greater than 0.");
+      self.oracle_config = config;
+  }
+
+  /// Fetches current prices for `asset_ids` from the oracle and caches them. Only NEAR and
+  /// whitelisted tokens are cached. Callable by anyone, typically keepers and liquidators.
+  pub fn refresh_prices(&mut self, asset_ids: Vec<String>) -> Promise {
+      let oracle_id = self.oracle_config.oracle_id.clone()
+          .unwrap_or_else(|| env::panic_str("No oracle is configured."));
+      assert!(!asset_ids.is_empty(), "No assets requested.");
+      ext_price_oracle::ext(oracle_id)
+          .with_static_gas(GAS_FOR_PRICE_DATA)
+          .get_price_data(Some(asset_ids))
+          .then(
+              Self::ext(env::current_account_id())
+                  .with_static_gas(GAS_FOR_PRICE_DATA_CALLBACK)
+                  .on_price_data()
+          )
+  }
+
+  /// Callback caching the prices returned by the oracle. Older data never replaces newer.
+  /// Returns the number of prices updated.
+  #[private]
+  pub fn on_price_data(&mut self, #[callback_result] result: Result<PriceData, PromiseError>) -> u32 {
+      let Ok(data) = result else {
+          env::log_str("Oracle price request failed.");
+          return 0;
+      };
+      let timestamp = data.timestamp.0;
+      let mut asset_ids = Vec::new();
+      for AssetOptionalPrice { asset_id, price } in data.prices {
+          let Some(price) = price.filter(|price| price.multiplier.0 > 0) else { continue };
+          if !self.internal_is_priced_asset(&asset_id)
+              || self.prices.get(&asset_id).is_some_and(|cached| cached.timestamp >= timestamp) {
+              continue;
+          }
+          self.prices.insert(asset_id.clone(), CachedPrice { price, timestamp });
+          asset_ids.push(asset_id);
+      }
+      let updated = asset_ids.len() as u32;
+      if updated > 0 {
+          BankEvent::PricesUpdated { asset_ids, timestamp }.emit();
+      }
+      updated
+  }
+
+  /// Cached price of `asset_id` and whether it is too old to act on.
+  /// View function.
+  pub fn get_price(&self, asset_id: String) -> Option<PriceView> {
+      self.prices.get(&asset_id).map(|cached| PriceView {
+          stale: self.internal_is_stale(cached),
+          asset_id,
+          price: cached.price.clone(),
+          timestamp: cached.timestamp,
+      })
+  }
+
+  /// View function.
+  pub fn get_oracle_config(&self) -> OracleConfig {
+      self.oracle_config.clone()
+  }
+
+  /// Value of `account_id`'s NEAR balance in USD (`USD_DECIMALS` decimals) at the cached
+  /// price, or `None` without a NEAR price.
+  /// View function.
+  pub fn get_balance_usd(&self, account_id: AccountId) -> Option<U128> {
+      let balance = self.get_balance(account_id).as_yoctonear();
+      self.internal_usd_value(&self.oracle_config.near_asset_id, balance).map(U128)
+  }
+}
+
+impl BioCrypticBankCore {
+  fn internal_is_priced_asset(&self, asset_id: &str) -> bool {
+      asset_id == self.oracle_config.near_asset_id
+          || asset_id.parse::<AccountId>().is_ok_and(|token_id| self.token_whitelist.contains(&token_id))
+          || self.reference_currencies.values().any(|currency_asset_id| currency_asset_id == asset_id)
+  }
+
+  fn internal_is_stale(&self, cached: &CachedPrice) -> bool {
+      env::block_timestamp().saturating_sub(cached.timestamp) > self.oracle_config.max_price_age_sec * NANOS_PER_SECOND
+  }
+
+  /// Oracle asset id of a collateral asset.
+  pub(crate) fn internal_oracle_asset_id(&self, asset: &AssetId) -> String {
+      match asset {
+          AssetId::Native => self.oracle_config.near_asset_id.clone(),
+          AssetId::Ft(token_id) => token_id.to_string(),
+      }
+  }
+
+  /// Cached price of `asset_id`; with `fresh`, panics if it is missing or stale.
+  pub(crate) fn internal_price(&self, asset_id: &str, fresh: bool) -> Option<&CachedPrice> {
+      let cached = self.prices.get(asset_id);
+      if fresh {
+          let cached = cached.unwrap_or_else(|| env::panic_str(&format!("No price for {}.", asset_id)));
+          assert!(!self.internal_is_stale(cached), "Price of {} is stale.", asset_id);
+      }
+      cached
+  }
+
+  /// USD value (`USD_DECIMALS` decimals) of `amount` smallest units of `asset_id`.
+  pub(crate) fn internal_usd_value(&self, asset_id: &str, amount: u128) -> Option<u128> {
+      let price = &self.prices.get(asset_id)?.price;
+      let value = amount.checked_mul(price.multiplier.0)
+          .unwrap_or_else(|| env::panic_str("Arithmetic overflow."));
+      Some(scale(value, price.decimals, USD_DECIMALS))
+  }
+
+  /// Amount of NEAR worth `usd` (`USD_DECIMALS` decimals) at the cached NEAR price, rounded
+  /// down. `None` without a NEAR price.
+  pub(crate) fn internal_near_for_usd(&self, usd: u128) -> Option<NearToken> {
+      let price = &self.prices.get(&self.oracle_config.near_asset_id)?.price;
+      let scaled = if price.decimals >= USD_DECIMALS {
+          checked_scale_up(usd, price.decimals - USD_DECIMALS)
+      } else {
+          usd / 10u128.pow((USD_DECIMALS - price.decimals) as u32)
+      };
+      Some(NearToken::from_yoctonear(scaled / price.multiplier.0))
+  }
+
+  /// Value in yoctoNEAR of `amount` smallest units of `asset_id`, converted through both USD
+  /// prices. `None` if either price is missing; with `fresh`, panics if either is stale.
+  pub(crate) fn internal_near_value(&self, asset_id: &str, amount: u128, fresh: bool) -> Option<u128> {
+      let asset = &self.internal_price(asset_id, fresh)?.price;
+      let near = &self.internal_price(&self.oracle_config.near_asset_id, fresh)?.price;
+      // Shift by the decimals difference before dividing so no precision is lost.
+      Some(if near.decimals >= asset.decimals {
+          mul_div(amount, checked_scale_up(asset.multiplier.0, near.decimals - asset.decimals), near.multiplier.0)
+      } else {
+          mul_div(amount, asset.multiplier.0, checked_scale_up(near.multiplier.0, asset.decimals - near.decimals))
+      })
+  }
+}
+
+/// Rescales `value` from `from` decimals to `to` decimals, rounding down.
+fn scale(value: u128, from: u8, to: u8) -> u128 {
+  if from >= to {
+      value / 10u128.pow((from - to) as u32)
+  } else {
+      checked_scale_up(value, to - from)
+  }
+}
+
+fn checked_scale_up(value: u128, decimals: u8) -> u128 {
+  10u128.checked_pow(decimals as u32)
+      .and_then(|factor| value.checked_mul(factor))
+      .unwrap_or_else(|| env::panic_str("Arithmetic overflow."))
+}
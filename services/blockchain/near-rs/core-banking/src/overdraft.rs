@@ -0,0 +1,263 @@
+// services/blockchain/near-rs/core-banking/src/overdraft.rs
+use near_sdk::{near, env, AccountId, NearToken};
+
+use crate::roles::Role;
+use crate::savings::BPS_DENOMINATOR;
+use crate::term_deposits::NANOS_PER_DAY;
+use crate::{mul_div, BankEvent, BioCrypticBankCore, BioCrypticBankCoreExt};
+
+const DAYS_PER_YEAR: u128 = 365;
+pub(crate) const MAX_KYC_TIER: u8 = 3;
+const NANOS_PER_SECOND: u64 = 1_000_000_000;
+/// Longest interest-free stretch a facility can be granted (30 days).
+pub(crate) const MAX_OVERDRAFT_GRACE_SEC: u64 = 30 * 24 * 60 * 60;
+
+/// Bank-wide overdraft terms. Owner controlled.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct OverdraftSettings {
+  /// Simple annual interest on the overdrawn principal, accrued once per whole day.
+  pub apr_bps: u16,
+  /// Lowest KYC tier allowed to open a facility.
+  pub min_kyc_tier: u8,
+  /// Minimum age (days) of the account's first ledger entry.
+  pub min_history_days: u32,
+  /// Credit limit granted on opening; zero disables new facilities.
+  pub default_limit: NearToken,
+  /// Interest-free period granted on opening when no credit tiers are set.
+  pub default_grace_period_sec: u64,
+}
+
+impl Default for OverdraftSettings {
+  fn default() -> Self {
+      Self {
+          apr_bps: 0,
+          min_kyc_tier: 1,
+          min_history_days: 30,
+          default_limit: NearToken::from_yoctonear(0),
+          default_grace_period_sec: 48 * 60 * 60,
+      }
+  }
+}
+
+/// An account's overdraft: how far below zero it is and the interest owed on that.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct Overdraft {
+  pub limit: NearToken,
+  pub principal: NearToken,
+  /// Interest accrued and not yet repaid.
+  pub interest: NearToken,
+  pub last_accrued_at: u64,
+  pub opened_at: u64,
+  /// How long each stretch below zero runs interest-free, counted from the draw that took
+  /// the account below zero.
+  pub grace_period_sec: u64,
+  /// When interest starts on the current stretch below zero; `None` while nothing is owed.
+  pub interest_starts_at: Option<u64>,
+}
+
+impl Overdraft {
+  fn owed(&self) -> NearToken {
+      self.principal.saturating_add(self.interest)
+  }
+
+  /// Adds interest for every whole day since the last accrual. Accrual is pushed past the
+  /// grace period when a stretch below zero starts, so no day inside it is charged.
+  fn accrue(&mut self, apr_bps: u16, now: u64) {
+      let days = now.saturating_sub(self.last_accrued_at) / NANOS_PER_DAY;
+      if days == 0 {
+          return;
+      }
+      let interest = mul_div(
+          self.principal.as_yoctonear(), apr_bps as u128 * days as u128, BPS_DENOMINATOR * DAYS_PER_YEAR,
+      );
+      self.interest = self.interest.saturating_add(NearToken::from_yoctonear(interest));
+      self.last_accrued_at += days * NANOS_PER_DAY;
+  }
+}
+
+#[near(serializers = [json])]
+#[derive(Debug, PartialEq)]
+pub struct OverdraftView {
+  /// Facility with interest accrued up to now.
+  pub overdraft: Overdraft,
+  pub owed: NearToken,
+  pub available: NearToken,
+  /// Whether the current stretch below zero is still interest-free.
+  pub in_grace_period: bool,
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Opens an overdraft for the caller at the limit and grace period of its credit tier, or
+  /// the defaults if no tiers are set. Requires the configured KYC tier and account history. Once open, debits beyond the unallocated balance draw on the
+  /// facility and every incoming credit repays it, interest first.
+  pub fn open_overdraft(&mut self) {
+      let initial_storage = env::storage_usage();
+      let account_id = env::predecessor_account_id();
+      assert!(self.overdrafts.get(&account_id).is_none(), "Overdraft is already open.");
+      let (limit, grace_period_sec) = self.internal_overdraft_terms_for(&account_id);
+      assert!(!limit.is_zero(), "No overdraft is available for this account.");
+      assert!(
+          self.get_kyc_tier(account_id.clone()) >= self.overdraft_settings.min_kyc_tier,
+          "KYC tier too low for an overdraft."
+      );
+      let now = env::block_timestamp();
+      let first_activity = self.internal_ledger_entry_at(&account_id, 0)
+          .map(|entry| entry.timestamp)
+          .unwrap_or(now);
+      assert!(
+          now - first_activity >= self.overdraft_settings.min_history_days as u64 * NANOS_PER_DAY,
+          "Account history is too short for an overdraft."
+      );
+
+      self.overdrafts.insert(account_id.clone(), Overdraft {
+          limit,
+          principal: NearToken::from_yoctonear(0),
+          interest: NearToken::from_yoctonear(0),
+          last_accrued_at: now,
+          opened_at: now,
+          grace_period_sec,
+          interest_starts_at: None,
+      });
+      self.internal_charge_storage(&account_id, initial_storage);
+      BankEvent::OverdraftOpened { account_id, limit, grace_period_sec }.emit();
+  }
+
+  /// Closes the caller's overdraft. Everything owed must have been repaid.
+  pub fn close_overdraft(&mut self) {
+      let initial_storage = env::storage_usage();
+      let account_id = env::predecessor_account_id();
+      let overdraft = self.internal_accrued_overdraft(&account_id)
+          .unwrap_or_else(|| env::panic_str("No overdraft is open."));
+      assert!(overdraft.owed().is_zero(), "Repay the overdraft before closing it.");
+      self.overdrafts.remove(&account_id);
+      self.internal_charge_storage(&account_id, initial_storage);
+      BankEvent::OverdraftClosed { account_id }.emit();
+  }
+
+  /// Sets the credit limit of `account_id`'s open overdraft. Lowering it below the amount
+  /// owed only blocks further draws. Compliance admin only.
+  pub fn set_overdraft_limit(&mut self, account_id: AccountId, limit: NearToken) {
+      self.assert_role(Role::ComplianceAdmin);
+      let overdraft = self.overdrafts.get_mut(&account_id)
+          .unwrap_or_else(|| env::panic_str("No overdraft is open."));
+      overdraft.limit = limit;
+      BankEvent::OverdraftLimitUpdated { account_id, limit, set_by: env::predecessor_account_id() }.emit();
+  }
+
+  /// Records the KYC tier (0 to 3) verified for `account_id`. Compliance admin only.
+  pub fn set_kyc_tier(&mut self, account_id: AccountId, tier: u8) {
+      self.assert_role(Role::ComplianceAdmin);
+      assert!(tier <= MAX_KYC_TIER, "KYC tier cannot exceed {}.", MAX_KYC_TIER);
+      if tier == 0 {
+          self.kyc_tiers.remove(&account_id);
+      } else {
+          self.kyc_tiers.insert(account_id.clone(), tier);
+      }
+      BankEvent::KycTierUpdated { account_id, tier }.emit();
+  }
+
+  /// Updates the overdraft terms. A new rate applies from each facility's next accrual.
+  /// Owner only.
+  pub fn set_overdraft_settings(&mut self, settings: OverdraftSettings) {
+      self.assert_owner();
+      assert!(settings.apr_bps as u128 <= BPS_DENOMINATOR, "APR cannot exceed 100%.");
+      assert!(settings.min_kyc_tier <= MAX_KYC_TIER, "KYC tier cannot exceed {}.", MAX_KYC_TIER);
+      assert!(
+          settings.default_grace_period_sec <= MAX_OVERDRAFT_GRACE_SEC,
+          "Grace period cannot exceed {} seconds.", MAX_OVERDRAFT_GRACE_SEC
+      );
+      self.overdraft_settings = settings.clone();
+      BankEvent::OverdraftSettingsUpdated { settings }.emit();
+  }
+
+  /// Retrieves `account_id`'s overdraft with interest accrued up to now. Its
+  /// `interest_starts_at` shows when interest begins on the current stretch below zero.
+  /// View function.
+  pub fn get_overdraft(&self, account_id: AccountId) -> Option<OverdraftView> {
+      let now = env::block_timestamp();
+      self.internal_accrued_overdraft(&account_id).map(|overdraft| OverdraftView {
+          owed: overdraft.owed(),
+          available: overdraft.limit.saturating_sub(overdraft.owed()),
+          in_grace_period: overdraft.interest_starts_at.is_some_and(|starts_at| now < starts_at),
+          overdraft,
+      })
+  }
+
+  /// Total overdraft principal currently lent out by the bank.
+  /// View function.
+  pub fn get_total_overdrawn(&self) -> NearToken {
+      self.total_overdrawn
+  }
+
+  /// View function.
+  pub fn get_overdraft_settings(&self) -> OverdraftSettings {
+      self.overdraft_settings.clone()
+  }
+
+  /// KYC tier of `account_id`: the higher of the tier set by compliance and the tier last
+  /// read from the DID registry; 0 if never verified.
+  /// View function.
+  pub fn get_kyc_tier(&self, account_id: AccountId) -> u8 {
+      let cached = self.kyc_cache.get(&account_id).map_or(0, |cached| cached.tier);
+      self.kyc_tiers.get(&account_id).copied().unwrap_or(0).max(cached)
+  }
+}
+
+impl BioCrypticBankCore {
+  fn internal_accrued_overdraft(&self, account_id: &AccountId) -> Option<Overdraft> {
+      let mut overdraft = self.overdrafts.get(account_id)?.clone();
+      overdraft.accrue(self.overdraft_settings.apr_bps, env::block_timestamp());
+      Some(overdraft)
+  }
+
+  /// Draws `amount` on `account_id`'s overdraft. Returns false, changing nothing, if there is
+  /// no facility or the draw would exceed its limit.
+  pub(crate) fn internal_draw_overdraft(&mut self, account_id: &AccountId, amount: NearToken) -> bool {
+      let apr_bps = self.overdraft_settings.apr_bps;
+      let Some(overdraft) = self.overdrafts.get_mut(account_id) else { return false };
+      let now = env::block_timestamp();
+      overdraft.accrue(apr_bps, now);
+      if overdraft.owed().saturating_add(amount) > overdraft.limit {
+          return false;
+      }
+      if overdraft.owed().is_zero() {
+          let starts_at = now + overdraft.grace_period_sec * NANOS_PER_SECOND;
+          overdraft.interest_starts_at = Some(starts_at);
+          overdraft.last_accrued_at = starts_at;
+      }
+      overdraft.principal = overdraft.principal.saturating_add(amount);
+      let owed = overdraft.owed();
+      let interest_starts_at = overdraft.interest_starts_at;
+      self.total_overdrawn = self.total_overdrawn.saturating_add(amount);
+      self.internal_add_liabilities(amount);
+      BankEvent::OverdraftDrawn { account_id: account_id.clone(), amount, owed, interest_starts_at }.emit();
+      true
+  }
+
+  /// Applies an incoming `amount` to `account_id`'s overdraft, interest first; the interest
+  /// is split between savers and the treasury. Returns the part of `amount` left over for the balance.
+  pub(crate) fn internal_repay_overdraft(&mut self, account_id: &AccountId, amount: NearToken) -> NearToken {
+      let apr_bps = self.overdraft_settings.apr_bps;
+      let Some(overdraft) = self.overdrafts.get_mut(account_id) else { return amount };
+      overdraft.accrue(apr_bps, env::block_timestamp());
+      if overdraft.owed().is_zero() {
+          return amount;
+      }
+      let interest = amount.min(overdraft.interest);
+      let principal = amount.saturating_sub(interest).min(overdraft.principal);
+      overdraft.interest = overdraft.interest.saturating_sub(interest);
+      overdraft.principal = overdraft.principal.saturating_sub(principal);
+      let owed = overdraft.owed();
+      if owed.is_zero() {
+          overdraft.interest_starts_at = None;
+      }
+      self.total_overdrawn = self.total_overdrawn.saturating_sub(principal);
+      self.internal_remove_liabilities(principal);
+      self.internal_collect_revenue(interest);
+      BankEvent::OverdraftRepaid { account_id: account_id.clone(), interest, principal, owed }.emit();
+      amount.saturating_sub(interest).saturating_sub(principal)
+  }
+}
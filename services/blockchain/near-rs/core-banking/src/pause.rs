@@ -0,0 +1,91 @@
+// services/blockchain/near-rs/core-banking/src/pause.rs
+use near_sdk::{near, env};
+
+use crate::roles::Role;
+use crate::{BankEvent, BioCrypticBankCore, BioCrypticBankCoreExt};
+
+/// Functional areas that can be paused independently.
+#[near(serializers = [borsh, json])]
+#[serde(rename_all = "snake_case")]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Feature {
+  Deposits,
+  Withdrawals,
+  Transfers,
+  Lending,
+}
+
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct PauseState {
+  /// Kill switch halting every pausable feature at once.
+  pub global: bool,
+  pub paused_features: Vec<Feature>,
+}
+
+impl PauseState {
+  fn is_paused(&self, feature: Feature) -> bool {
+      self.global || self.paused_features.contains(&feature)
+  }
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Pauses `feature`. Pauser only.
+  pub fn pause(&mut self, feature: Feature) {
+      self.assert_role(Role::Pauser);
+      assert!(!self.pause_state.paused_features.contains(&feature), "Feature is already paused.");
+      self.pause_state.paused_features.push(feature);
+      BankEvent::FeaturePaused { feature: Some(feature), account_id: env::predecessor_account_id() }.emit();
+  }
+
+  /// Resumes `feature`. Has no effect while the global kill switch is engaged, and does not
+  /// release withdrawals held by the circuit breaker, which only admins can reset. Pauser only.
+  pub fn unpause(&mut self, feature: Feature) {
+      self.assert_role(Role::Pauser);
+      assert!(self.pause_state.paused_features.contains(&feature), "Feature is not paused.");
+      self.pause_state.paused_features.retain(|existing| *existing != feature);
+      BankEvent::FeatureUnpaused { feature: Some(feature), account_id: env::predecessor_account_id() }.emit();
+  }
+
+  /// Engages the global kill switch, halting every feature. Pauser only.
+  pub fn pause_all(&mut self) {
+      self.assert_role(Role::Pauser);
+      assert!(!self.pause_state.global, "Contract is already paused.");
+      self.pause_state.global = true;
+      BankEvent::FeaturePaused { feature: None, account_id: env::predecessor_account_id() }.emit();
+  }
+
+  /// Releases the global kill switch. Individually paused features stay paused. Pauser only.
+  pub fn unpause_all(&mut self) {
+      self.assert_role(Role::Pauser);
+      assert!(self.pause_state.global, "Contract is not paused.");
+      self.pause_state.global = false;
+      BankEvent::FeatureUnpaused { feature: None, account_id: env::predecessor_account_id() }.emit();
+  }
+
+  /// Retrieves the kill switch and per-feature pause flags.
+  /// View function.
+  pub fn get_pause_state(&self) -> PauseState {
+      self.pause_state.clone()
+  }
+
+  /// Checks whether `feature` is currently unavailable, whether paused by a pauser or held
+  /// by the circuit breaker.
+  /// View function.
+  pub fn is_paused(&self, feature: Feature) -> bool {
+      self.pause_state.is_paused(feature) || self.is_held_by_circuit_breaker(feature)
+  }
+}
+
+impl BioCrypticBankCore {
+  pub(crate) fn assert_not_paused(&self, feature: Feature) {
+      if self.pause_state.is_paused(feature) {
+          env::panic_str(&format!("Feature {:?} is paused.", feature));
+      }
+      assert!(
+          !self.is_held_by_circuit_breaker(feature),
+          "Withdrawals are paused by the circuit breaker; admins must reset it."
+      );
+  }
+}
@@ -0,0 +1,146 @@
+// services/blockchain/near-rs/core-banking/src/payment_intents.rs
+use near_sdk::{near, env, AccountId, NearToken};
+
+use crate::ledger::EntryKind;
+use crate::pause::Feature;
+use crate::{assert_valid_memo, BankEvent, BioCrypticBankCore, BioCrypticBankCoreExt};
+
+#[near(serializers = [borsh, json])]
+#[serde(rename_all = "snake_case")]
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum IntentStatus {
+  Open,
+  Paid,
+  /// Reported by views once an open intent is past its expiry; never stored.
+  Expired,
+  Cancelled,
+}
+
+/// A one-off payment request any account may settle, e.g. shown as a QR code at a till.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct PaymentIntent {
+  pub id: u64,
+  pub payee_id: AccountId,
+  pub amount: NearToken,
+  pub memo: Option<String>,
+  pub created_at: u64,
+  pub expires_at: u64,
+  pub status: IntentStatus,
+  pub payer_id: Option<AccountId>,
+  pub paid_at: Option<u64>,
+  /// Payer-side ledger entry created when the intent was paid.
+  pub ledger_entry_id: Option<u64>,
+}
+
+impl PaymentIntent {
+  fn current_status(&self, now: u64) -> IntentStatus {
+      if self.status == IntentStatus::Open && now > self.expires_at {
+          IntentStatus::Expired
+      } else {
+          self.status
+      }
+  }
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Requests `amount` from whoever pays the intent before `expires_at` (ns). Returns the
+  /// intent id to encode in a QR code.
+  pub fn create_payment_intent(&mut self, amount: NearToken, memo: Option<String>, expires_at: u64) -> u64 {
+      let initial_storage = env::storage_usage();
+      let payee_id = env::predecessor_account_id();
+      self.assert_registered(&payee_id);
+      assert!(amount.as_yoctonear() > 0, "Amount must be greater than 0.");
+      assert!(expires_at > env::block_timestamp(), "Expiry must be in the future.");
+      assert_valid_memo(&memo);
+
+      let id = self.next_payment_intent_id;
+      self.next_payment_intent_id += 1;
+      self.payment_intents.insert(id, PaymentIntent {
+          id,
+          payee_id: payee_id.clone(),
+          amount,
+          memo,
+          created_at: env::block_timestamp(),
+          expires_at,
+          status: IntentStatus::Open,
+          payer_id: None,
+          paid_at: None,
+          ledger_entry_id: None,
+      });
+      self.internal_charge_storage(&payee_id, initial_storage);
+      BankEvent::PaymentIntentCreated { id, payee_id, amount, expires_at }.emit();
+      id
+  }
+
+  /// Pays intent `intent_id` from the caller's balance. `amount` must match the intent, so a
+  /// payer never pays more than what it was shown. When relayed by a bank relayer, the call
+  /// counts against the payer's sponsorship budget.
+  pub fn pay_intent(&mut self, intent_id: u64, amount: NearToken) {
+      let payer_id = env::predecessor_account_id();
+      self.internal_meter_sponsorship(&payer_id);
+      self.internal_pay_intent(payer_id, intent_id, amount);
+  }
+
+  /// Payee withdraws an open payment intent.
+  pub fn cancel_payment_intent(&mut self, intent_id: u64) {
+      let intent = self.payment_intents.get_mut(&intent_id).unwrap_or_else(|| env::panic_str("Payment intent not found."));
+      assert_eq!(intent.payee_id, env::predecessor_account_id(), "Only the payee can cancel this payment intent.");
+      assert_eq!(intent.status, IntentStatus::Open, "Payment intent is no longer open.");
+      intent.status = IntentStatus::Cancelled;
+      BankEvent::PaymentIntentCancelled { id: intent_id }.emit();
+  }
+
+  /// Retrieves a payment intent, reporting open intents past their expiry as expired.
+  /// View function.
+  pub fn get_payment_intent(&self, intent_id: u64) -> Option<PaymentIntent> {
+      let mut intent = self.payment_intents.get(&intent_id)?.clone();
+      intent.status = intent.current_status(env::block_timestamp());
+      Some(intent)
+  }
+
+  /// Current status of a payment intent, for point-of-sale polling.
+  /// View function.
+  pub fn get_payment_intent_status(&self, intent_id: u64) -> Option<IntentStatus> {
+      self.payment_intents.get(&intent_id).map(|intent| intent.current_status(env::block_timestamp()))
+  }
+}
+
+impl BioCrypticBankCore {
+  /// Pays intent `intent_id` from `payer_id`'s balance.
+  pub(crate) fn internal_pay_intent(&mut self, payer_id: AccountId, intent_id: u64, amount: NearToken) {
+      self.assert_not_paused(Feature::Transfers);
+      let initial_storage = env::storage_usage();
+      let now = env::block_timestamp();
+      let intent = self.payment_intents.get(&intent_id).unwrap_or_else(|| env::panic_str("Payment intent not found.")).clone();
+      match intent.current_status(now) {
+          IntentStatus::Open => {}
+          IntentStatus::Expired => env::panic_str("Payment intent has expired."),
+          _ => env::panic_str("Payment intent is no longer open."),
+      }
+      assert_eq!(amount, intent.amount, "Amount does not match the payment intent.");
+      assert!(payer_id != intent.payee_id, "Cannot pay your own payment intent.");
+      self.assert_trusted_destination(&payer_id, &intent.payee_id);
+      self.assert_not_dormant(&payer_id);
+      self.assert_no_recovery_hold(&payer_id);
+
+      self.internal_record_outflow(&payer_id, amount);
+      self.internal_record_kyc_volume(&payer_id, amount);
+      self.internal_debit(&payer_id, amount);
+      self.internal_credit(&intent.payee_id, amount);
+      let reference = format!("intent:{}", intent_id);
+      let (debit_id, credit_id) = self.internal_record_movement(
+          &payer_id, &intent.payee_id, EntryKind::IntentPayment, amount, intent.memo.clone(), Some(reference.clone()),
+      );
+      self.internal_index_payment_reference(&intent.payee_id, &reference, credit_id);
+      let intent = self.payment_intents.get_mut(&intent_id).unwrap();
+      intent.status = IntentStatus::Paid;
+      intent.payer_id = Some(payer_id.clone());
+      intent.paid_at = Some(now);
+      intent.ledger_entry_id = Some(debit_id);
+      let payee_id = intent.payee_id.clone();
+      self.internal_charge_storage(&payer_id, initial_storage);
+      BankEvent::PaymentIntentPaid { id: intent_id, payee_id, payer_id, amount }.emit();
+  }
+}
@@ -0,0 +1,184 @@
+// services/blockchain/near-rs/core-banking/src/pending_transfers.rs
+use near_sdk::{near, env, AccountId, NearToken, PromiseOrValue};
+
+use crate::kyc::KycPendingAction;
+use crate::pause::Feature;
+use crate::{BankEvent, BioCrypticBankCore, BioCrypticBankCoreExt, DEFAULT_PAGE_LIMIT};
+
+const NANOS_PER_SECOND: u64 = 1_000_000_000;
+/// Longest a transfer can be held for cancellation (24 hours).
+const MAX_TRANSFER_DELAY_SEC: u64 = 24 * 60 * 60;
+const MAX_PENDING_TRANSFERS_PER_ACCOUNT: usize = 20;
+
+/// Risk rule delaying transfers above `threshold` by `delay_sec`. Owner controlled.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct TransferDelayRule {
+  /// `None` disables the rule.
+  pub threshold: Option<NearToken>,
+  pub delay_sec: u64,
+}
+
+/// A transfer held before it runs, during which the sender can cancel it. The funds stay in
+/// the sender's account until it runs.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct PendingTransfer {
+  pub id: u64,
+  pub sender_id: AccountId,
+  pub receiver_id: AccountId,
+  pub amount: NearToken,
+  pub memo: Option<String>,
+  pub envelope: Option<String>,
+  pub reference: Option<String>,
+  pub requested_at: u64,
+  pub executable_at: u64,
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Replaces the risk rule. Transfers already held keep their delay. Owner only.
+  pub fn set_transfer_delay_rule(&mut self, rule: TransferDelayRule) {
+      self.assert_owner();
+      assert!(rule.delay_sec <= MAX_TRANSFER_DELAY_SEC, "Delay cannot exceed {} seconds.", MAX_TRANSFER_DELAY_SEC);
+      self.transfer_delay_rule = rule;
+  }
+
+  /// Holds every transfer the caller sends for `delay_sec` so it can be cancelled, or stops
+  /// delaying them with `None`. Risk rules may still delay large transfers.
+  pub fn set_transfer_delay(&mut self, delay_sec: Option<u64>) {
+      let initial_storage = env::storage_usage();
+      let account_id = env::predecessor_account_id();
+      self.assert_registered(&account_id);
+      match delay_sec {
+          Some(delay_sec) => {
+              assert!(delay_sec > 0 && delay_sec <= MAX_TRANSFER_DELAY_SEC, "Delay must be 1 to {} seconds.", MAX_TRANSFER_DELAY_SEC);
+              self.transfer_delays.insert(account_id.clone(), delay_sec);
+          }
+          None => {
+              self.transfer_delays.remove(&account_id);
+          }
+      }
+      self.internal_charge_storage(&account_id, initial_storage);
+  }
+
+  /// Runs held transfer `transfer_id` once its delay has passed. Anyone can trigger it.
+  /// Large transfers re-read a stale KYC tier from the DID registry first.
+  pub fn execute_pending_transfer(&mut self, transfer_id: u64) -> PromiseOrValue<bool> {
+      self.assert_not_paused(Feature::Transfers);
+      let pending = self.internal_get_pending_transfer(transfer_id);
+      assert!(env::block_timestamp() >= pending.executable_at, "Transfer is still pending.");
+      self.assert_no_recovery_hold(&pending.sender_id);
+      let initial_storage = env::storage_usage();
+      self.internal_remove_pending_transfer(&pending);
+      self.internal_charge_storage(&pending.sender_id, initial_storage);
+      BankEvent::PendingTransferExecuted { id: transfer_id, sender_id: pending.sender_id.clone(), executed_by: env::predecessor_account_id() }.emit();
+      let PendingTransfer { sender_id, receiver_id, amount, memo, envelope, reference, .. } = pending;
+      if self.internal_kyc_check_needed(&sender_id, amount) {
+          let action = KycPendingAction::Transfer { receiver_id, amount, memo, envelope, reference };
+          return self.internal_resolve_kyc(sender_id, action).into();
+      }
+      self.internal_transfer(&sender_id, &receiver_id, amount, memo, envelope, reference);
+      PromiseOrValue::Value(true)
+  }
+
+  /// Cancels held transfer `transfer_id` before it runs. Sender only.
+  pub fn cancel_pending_transfer(&mut self, transfer_id: u64) {
+      let pending = self.internal_get_pending_transfer(transfer_id);
+      assert_eq!(pending.sender_id, env::predecessor_account_id(), "Only the sender can cancel this transfer.");
+      let initial_storage = env::storage_usage();
+      self.internal_remove_pending_transfer(&pending);
+      self.internal_charge_storage(&pending.sender_id, initial_storage);
+      BankEvent::PendingTransferCancelled { id: transfer_id, sender_id: pending.sender_id, receiver_id: pending.receiver_id, amount: pending.amount }.emit();
+  }
+
+  /// Transfers `account_id` has sent that are still held, oldest first.
+  /// View function.
+  pub fn get_pending_outgoing(&self, account_id: AccountId) -> Vec<PendingTransfer> {
+      self.pending_transfers_by_account.get(&account_id)
+          .map(|ids| ids.iter().filter_map(|id| self.pending_transfers.get(id).cloned()).collect())
+          .unwrap_or_default()
+  }
+
+  /// Lists every held transfer, e.g. for a keeper executing due ones.
+  /// View function.
+  pub fn get_pending_transfers(&self, from_index: Option<u64>, limit: Option<u64>) -> Vec<PendingTransfer> {
+      self.pending_transfers.values()
+          .skip(from_index.unwrap_or(0) as usize)
+          .take(limit.unwrap_or(DEFAULT_PAGE_LIMIT) as usize)
+          .cloned()
+          .collect()
+  }
+
+  /// View function.
+  pub fn get_transfer_delay(&self, account_id: AccountId) -> Option<u64> {
+      self.transfer_delays.get(&account_id).copied()
+  }
+
+  /// View function.
+  pub fn get_transfer_delay_rule(&self) -> TransferDelayRule {
+      self.transfer_delay_rule.clone()
+  }
+}
+
+impl BioCrypticBankCore {
+  /// How long a transfer of `amount` by `sender_id` must be held: the longer of the
+  /// sender's own delay and the risk rule's. `None` if it can run immediately.
+  pub(crate) fn internal_transfer_delay(&self, sender_id: &AccountId, amount: NearToken) -> Option<u64> {
+      let rule = &self.transfer_delay_rule;
+      let rule_delay = if rule.threshold.is_some_and(|threshold| amount > threshold) { rule.delay_sec } else { 0 };
+      let delay = self.transfer_delays.get(sender_id).copied().unwrap_or(0).max(rule_delay);
+      (delay > 0).then_some(delay)
+  }
+
+  /// Holds a transfer for `delay_sec`. Returns the held transfer's id.
+  #[allow(clippy::too_many_arguments)]
+  pub(crate) fn internal_hold_transfer(
+      &mut self,
+      sender_id: AccountId,
+      receiver_id: AccountId,
+      amount: NearToken,
+      memo: Option<String>,
+      envelope: Option<String>,
+      reference: Option<String>,
+      delay_sec: u64,
+  ) -> u64 {
+      let initial_storage = env::storage_usage();
+      let ids = self.pending_transfers_by_account.entry(sender_id.clone()).or_default();
+      assert!(ids.len() < MAX_PENDING_TRANSFERS_PER_ACCOUNT, "Cannot exceed {} pending transfers.", MAX_PENDING_TRANSFERS_PER_ACCOUNT);
+      let id = self.next_pending_transfer_id;
+      self.next_pending_transfer_id += 1;
+      ids.push(id);
+      let now = env::block_timestamp();
+      let executable_at = now + delay_sec * NANOS_PER_SECOND;
+      self.pending_transfers.insert(id, PendingTransfer {
+          id,
+          sender_id: sender_id.clone(),
+          receiver_id: receiver_id.clone(),
+          amount,
+          memo,
+          envelope,
+          reference,
+          requested_at: now,
+          executable_at,
+      });
+      self.internal_charge_storage(&sender_id, initial_storage);
+      BankEvent::TransferPending { id, sender_id, receiver_id, amount, executable_at }.emit();
+      id
+  }
+
+  fn internal_get_pending_transfer(&self, transfer_id: u64) -> PendingTransfer {
+      self.pending_transfers.get(&transfer_id).cloned()
+          .unwrap_or_else(|| env::panic_str("Pending transfer not found."))
+  }
+
+  fn internal_remove_pending_transfer(&mut self, pending: &PendingTransfer) {
+      self.pending_transfers.remove(&pending.id);
+      if let Some(ids) = self.pending_transfers_by_account.get_mut(&pending.sender_id) {
+          ids.retain(|existing| *existing != pending.id);
+          if ids.is_empty() {
+              self.pending_transfers_by_account.remove(&pending.sender_id);
+          }
+      }
+  }
+}
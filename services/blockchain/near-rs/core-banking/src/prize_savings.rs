@@ -0,0 +1,402 @@
+// services/blockchain/near-rs/core-banking/src/prize_savings.rs
+use near_sdk::{near, env, AccountId, CryptoHash, NearToken};
+use near_sdk::json_types::{Base58CryptoHash, U128};
+
+use crate::ledger::{EntryDirection, EntryKind};
+use crate::pause::Feature;
+use crate::savings::{BPS_DENOMINATOR, SECONDS_PER_YEAR};
+use crate::{mul_div, BankEvent, BioCrypticBankCore, BioCrypticBankCoreExt, DEFAULT_PAGE_LIMIT};
+
+const MIN_PRIZE_EPOCH_SEC: u64 = 60 * 60;
+const MAX_PRIZE_EPOCH_SEC: u64 = 90 * 24 * 60 * 60;
+const MAX_RANDOMNESS_PROOF_LENGTH: usize = 1024;
+
+/// Epoch length and randomness source of the prize pool. Owner controlled.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct PrizeSavingsConfig {
+  pub epoch_duration_sec: u64,
+  /// VRF oracle supplying each draw's randomness. Draws use the block's random seed while
+  /// unset.
+  pub randomness_oracle: Option<AccountId>,
+}
+
+impl Default for PrizeSavingsConfig {
+  fn default() -> Self {
+      Self { epoch_duration_sec: 7 * 24 * 60 * 60, randomness_oracle: None }
+  }
+}
+
+/// Running totals of the current epoch. A ticket is one yoctoNEAR held for one second, and
+/// the prize is the interest those tickets would have earned at the savings APR.
+#[near(serializers = [borsh])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct PrizePool {
+  pub epoch: u64,
+  pub started_at_sec: u64,
+  pub total_principal: NearToken,
+  pub total_tickets: u128,
+  pub prize: u128,
+  /// Timestamp (seconds) up to which tickets and prize have been accrued.
+  pub last_accrued_sec: u64,
+}
+
+impl Default for PrizePool {
+  fn default() -> Self {
+      Self::new(0, NearToken::from_yoctonear(0), now_sec())
+  }
+}
+
+impl PrizePool {
+  fn new(epoch: u64, total_principal: NearToken, now: u64) -> Self {
+      Self { epoch, started_at_sec: now, total_principal, total_tickets: 0, prize: 0, last_accrued_sec: now }
+  }
+
+  /// Tickets and prize as of `now` at `apr_bps`, without mutating state.
+  fn projected(&self, now: u64, apr_bps: u16) -> (u128, u128) {
+      let tickets = self.total_principal.as_yoctonear()
+          .saturating_mul(now.saturating_sub(self.last_accrued_sec) as u128);
+      let prize = mul_div(tickets, apr_bps as u128, BPS_DENOMINATOR * SECONDS_PER_YEAR);
+      (self.total_tickets.saturating_add(tickets), self.prize.saturating_add(prize))
+  }
+}
+
+/// A participant's principal, which is never at risk, and its tickets in `epoch`.
+#[near(serializers = [borsh])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct PrizeSaver {
+  pub principal: NearToken,
+  pub epoch: u64,
+  pub tickets: u128,
+  pub last_updated_sec: u64,
+}
+
+impl PrizeSaver {
+  /// Tickets held in `epoch` as of `now`. A saver last updated in an earlier epoch held its
+  /// principal for all of `epoch`, which started at `started_at_sec`.
+  fn tickets_in(&self, epoch: u64, started_at_sec: u64, now: u64) -> u128 {
+      let (tickets, since) = if self.epoch == epoch { (self.tickets, self.last_updated_sec) } else { (0, started_at_sec) };
+      tickets.saturating_add(self.principal.as_yoctonear().saturating_mul(now.saturating_sub(since) as u128))
+  }
+}
+
+#[near(serializers = [borsh, json])]
+#[serde(rename_all = "snake_case")]
+#[derive(Debug, PartialEq, Clone)]
+pub enum RandomnessSource {
+  Block { block_height: u64 },
+  /// `proof` is the oracle's VRF proof, kept for verification off-chain.
+  Oracle { oracle_id: AccountId, proof: String },
+}
+
+/// A closed epoch waiting for its randomness, then for `process_prize_draw` to walk the
+/// savers up to the winning ticket. Entering and leaving the pool is frozen meanwhile.
+#[near(serializers = [borsh])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct PendingPrizeDraw {
+  pub epoch: u64,
+  pub started_at_sec: u64,
+  pub ended_at_sec: u64,
+  pub total_tickets: u128,
+  pub prize: NearToken,
+  pub randomness: Option<(CryptoHash, RandomnessSource)>,
+  /// Savers already walked and the tickets they hold.
+  pub cursor: u32,
+  pub tickets_walked: u128,
+}
+
+/// Outcome of an epoch. The winner is the saver holding ticket `winning_ticket`, counting
+/// through savers in `get_prize_savers` order with each one's tickets for the epoch; anyone
+/// can repeat the walk to check it.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct PrizeDraw {
+  pub epoch: u64,
+  pub started_at_sec: u64,
+  pub ended_at_sec: u64,
+  pub total_tickets: U128,
+  pub prize: NearToken,
+  pub randomness: Base58CryptoHash,
+  pub source: RandomnessSource,
+  /// `randomness` read as a little-endian integer from its first 16 bytes, modulo
+  /// `total_tickets`.
+  pub winning_ticket: U128,
+  pub winner_id: AccountId,
+  pub drawn_at: u64,
+}
+
+#[near(serializers = [json])]
+#[derive(Debug, PartialEq)]
+pub struct PrizePoolView {
+  pub epoch: u64,
+  pub started_at_sec: u64,
+  /// Earliest time (seconds) the epoch can be closed.
+  pub ends_at_sec: u64,
+  pub total_principal: NearToken,
+  pub total_tickets: U128,
+  /// Prize accrued so far this epoch.
+  pub prize: NearToken,
+  pub apr_bps: u16,
+  /// Epoch being drawn, if any.
+  pub draw_in_progress: Option<u64>,
+}
+
+#[near(serializers = [json])]
+#[derive(Debug, PartialEq)]
+pub struct PrizeSaverView {
+  pub account_id: AccountId,
+  pub principal: NearToken,
+  /// Tickets held in the current epoch so far.
+  pub tickets: U128,
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Owner only. Takes effect from the next epoch.
+  pub fn set_prize_savings_config(&mut self, config: PrizeSavingsConfig) {
+      self.assert_owner();
+      assert!(
+          (MIN_PRIZE_EPOCH_SEC..=MAX_PRIZE_EPOCH_SEC).contains(&config.epoch_duration_sec),
+          "Epoch duration must be between {} and {} seconds.", MIN_PRIZE_EPOCH_SEC, MAX_PRIZE_EPOCH_SEC
+      );
+      self.prize_savings_config = config.clone();
+      BankEvent::PrizeSavingsConfigUpdated { config }.emit();
+  }
+
+  /// Moves `amount` from the caller's checking balance into the prize pool. The principal
+  /// earns no interest; instead it holds tickets in each epoch's draw of the pool's
+  /// interest, and can be taken back in full at any time outside a draw.
+  pub fn enter_prize_savings(&mut self, amount: NearToken) {
+      self.assert_not_paused(Feature::Deposits);
+      let initial_storage = env::storage_usage();
+      let account_id = env::predecessor_account_id();
+      assert!(amount.as_yoctonear() > 0, "Amount must be greater than 0.");
+      self.assert_registered(&account_id);
+      self.assert_no_recovery_hold(&account_id);
+      let saver = self.internal_update_prize_saver(&account_id);
+      saver.principal = saver.principal.saturating_add(amount);
+      self.prize_pool.total_principal = self.prize_pool.total_principal.saturating_add(amount);
+      self.internal_debit(&account_id, amount);
+      self.internal_record_entry(&account_id, EntryKind::PrizeSavings, EntryDirection::Debit, amount, None, None);
+      self.internal_charge_storage(&account_id, initial_storage);
+      BankEvent::PrizeSavingsEntered { account_id, amount }.emit();
+  }
+
+  /// Moves `amount` of the caller's prize savings principal back to its checking balance.
+  /// Tickets already earned this epoch are kept.
+  pub fn exit_prize_savings(&mut self, amount: NearToken) {
+      let initial_storage = env::storage_usage();
+      let account_id = env::predecessor_account_id();
+      assert!(amount.as_yoctonear() > 0, "Amount must be greater than 0.");
+      assert!(self.prize_savers.contains_key(&account_id), "No prize savings found.");
+      let saver = self.internal_update_prize_saver(&account_id);
+      assert!(saver.principal >= amount, "Insufficient prize savings balance.");
+      saver.principal = saver.principal.saturating_sub(amount);
+      self.prize_pool.total_principal = self.prize_pool.total_principal.saturating_sub(amount);
+      self.internal_credit(&account_id, amount);
+      self.internal_record_entry(&account_id, EntryKind::PrizeSavings, EntryDirection::Credit, amount, None, None);
+      self.internal_charge_storage(&account_id, initial_storage);
+      BankEvent::PrizeSavingsExited { account_id, amount }.emit();
+  }
+
+  /// Permissionless crank closing the current epoch once its duration has passed and
+  /// starting the next. If tickets were held and a prize accrued, a draw is opened, seeded
+  /// from this block unless a randomness oracle is configured. Returns the closed epoch.
+  pub fn close_prize_epoch(&mut self) -> u64 {
+      self.assert_no_prize_draw();
+      let now = now_sec();
+      let pool = self.prize_pool.clone();
+      let duration = self.prize_savings_config.epoch_duration_sec;
+      assert!(now >= pool.started_at_sec + duration, "Prize epoch has not ended.");
+      let (total_tickets, prize) = pool.projected(now, self.savings_pool.apr_bps);
+      let prize = NearToken::from_yoctonear(prize);
+      self.prize_pool = PrizePool::new(pool.epoch + 1, pool.total_principal, now);
+      BankEvent::PrizeEpochClosed { epoch: pool.epoch, total_tickets: U128(total_tickets), prize }.emit();
+      if total_tickets == 0 || prize.is_zero() {
+          return pool.epoch;
+      }
+
+      let randomness = match self.prize_savings_config.randomness_oracle {
+          Some(_) => None,
+          None => {
+              let seed: CryptoHash = env::random_seed_array();
+              Some((seed, RandomnessSource::Block { block_height: env::block_height() }))
+          }
+      };
+      self.pending_prize_draw = Some(PendingPrizeDraw {
+          epoch: pool.epoch,
+          started_at_sec: pool.started_at_sec,
+          ended_at_sec: now,
+          total_tickets,
+          prize,
+          randomness,
+          cursor: 0,
+          tickets_walked: 0,
+      });
+      pool.epoch
+  }
+
+  /// Supplies the randomness of the pending draw. `proof` is the VRF proof of `randomness`,
+  /// recorded with the draw. Randomness oracle only.
+  pub fn submit_prize_randomness(&mut self, epoch: u64, randomness: Base58CryptoHash, proof: String) {
+      let oracle_id = env::predecessor_account_id();
+      assert_eq!(
+          Some(&oracle_id), self.prize_savings_config.randomness_oracle.as_ref(),
+          "Only the randomness oracle can call this function."
+      );
+      assert!(proof.len() <= MAX_RANDOMNESS_PROOF_LENGTH, "Proof cannot exceed {} bytes.", MAX_RANDOMNESS_PROOF_LENGTH);
+      let draw = self.pending_prize_draw.as_mut().unwrap_or_else(|| env::panic_str("No prize draw is pending."));
+      assert_eq!(draw.epoch, epoch, "Randomness is for a different epoch.");
+      assert!(draw.randomness.is_none(), "Randomness has already been supplied.");
+      draw.randomness = Some((randomness.into(), RandomnessSource::Oracle { oracle_id, proof }));
+  }
+
+  /// Permissionless crank walking up to `limit` savers of the pending draw towards the
+  /// winning ticket. Once found, the prize is credited to the winner's checking balance and
+  /// the pool reopens. Returns the winner, if found.
+  pub fn process_prize_draw(&mut self, limit: Option<u32>) -> Option<AccountId> {
+      let mut draw = self.pending_prize_draw.clone().unwrap_or_else(|| env::panic_str("No prize draw is pending."));
+      let (randomness, source) = draw.randomness.clone().unwrap_or_else(|| env::panic_str("Prize draw is waiting for randomness."));
+      let winning_ticket = winning_ticket(&randomness, draw.total_tickets);
+      let mut winner_id = None;
+      for (account_id, saver) in self.prize_savers.iter()
+          .skip(draw.cursor as usize)
+          .take(limit.unwrap_or(DEFAULT_PAGE_LIMIT as u32) as usize)
+      {
+          draw.cursor += 1;
+          draw.tickets_walked = draw.tickets_walked
+              .saturating_add(saver.tickets_in(draw.epoch, draw.started_at_sec, draw.ended_at_sec));
+          if draw.tickets_walked > winning_ticket {
+              winner_id = Some(account_id.clone());
+              break;
+          }
+      }
+      let Some(winner_id) = winner_id else {
+          assert!((draw.cursor as usize) < self.prize_savers.len() as usize, "Prize draw found no winner.");
+          self.pending_prize_draw = Some(draw);
+          return None;
+      };
+
+      self.pending_prize_draw = None;
+      self.internal_credit(&winner_id, draw.prize);
+      self.internal_add_liabilities(draw.prize);
+      self.internal_record_entry(
+          &winner_id, EntryKind::PrizeSavings, EntryDirection::Credit, draw.prize, None, Some(format!("prize:{}", draw.epoch)),
+      );
+      self.prize_draws.push(PrizeDraw {
+          epoch: draw.epoch,
+          started_at_sec: draw.started_at_sec,
+          ended_at_sec: draw.ended_at_sec,
+          total_tickets: U128(draw.total_tickets),
+          prize: draw.prize,
+          randomness: randomness.into(),
+          source,
+          winning_ticket: U128(winning_ticket),
+          winner_id: winner_id.clone(),
+          drawn_at: env::block_timestamp(),
+      });
+      BankEvent::PrizeAwarded { epoch: draw.epoch, winner_id: winner_id.clone(), prize: draw.prize }.emit();
+      Some(winner_id)
+  }
+
+  /// View function.
+  pub fn get_prize_pool(&self) -> PrizePoolView {
+      let (total_tickets, prize) = self.prize_pool.projected(now_sec(), self.savings_pool.apr_bps);
+      PrizePoolView {
+          epoch: self.prize_pool.epoch,
+          started_at_sec: self.prize_pool.started_at_sec,
+          ends_at_sec: self.prize_pool.started_at_sec + self.prize_savings_config.epoch_duration_sec,
+          total_principal: self.prize_pool.total_principal,
+          total_tickets: U128(total_tickets),
+          prize: NearToken::from_yoctonear(prize),
+          apr_bps: self.savings_pool.apr_bps,
+          draw_in_progress: self.pending_prize_draw.as_ref().map(|draw| draw.epoch),
+      }
+  }
+
+  /// View function.
+  pub fn get_prize_savings(&self, account_id: AccountId) -> Option<PrizeSaverView> {
+      let saver = self.prize_savers.get(&account_id)?;
+      let tickets = saver.tickets_in(self.prize_pool.epoch, self.prize_pool.started_at_sec, now_sec());
+      Some(PrizeSaverView { account_id, principal: saver.principal, tickets: U128(tickets) })
+  }
+
+  /// Savers in the order draws walk them, paginated.
+  /// View function.
+  pub fn get_prize_savers(&self, from_index: Option<u64>, limit: Option<u64>) -> Vec<PrizeSaverView> {
+      let now = now_sec();
+      self.prize_savers.iter()
+          .skip(from_index.unwrap_or(0) as usize)
+          .take(limit.unwrap_or(DEFAULT_PAGE_LIMIT) as usize)
+          .map(|(account_id, saver)| PrizeSaverView {
+              account_id: account_id.clone(),
+              principal: saver.principal,
+              tickets: U128(saver.tickets_in(self.prize_pool.epoch, self.prize_pool.started_at_sec, now)),
+          })
+          .collect()
+  }
+
+  /// Past draws, oldest first, paginated.
+  /// View function.
+  pub fn get_prize_draws(&self, from_index: Option<u64>, limit: Option<u64>) -> Vec<PrizeDraw> {
+      self.prize_draws.iter()
+          .skip(from_index.unwrap_or(0) as usize)
+          .take(limit.unwrap_or(DEFAULT_PAGE_LIMIT) as usize)
+          .cloned()
+          .collect()
+  }
+
+  /// View function.
+  pub fn get_prize_savings_config(&self) -> PrizeSavingsConfig {
+      self.prize_savings_config.clone()
+  }
+}
+
+impl BioCrypticBankCore {
+  fn assert_no_prize_draw(&self) {
+      assert!(self.pending_prize_draw.is_none(), "A prize draw is in progress.");
+  }
+
+  /// Accrues the pool and `account_id`'s tickets to now, moving the saver into the current
+  /// epoch, and returns it.
+  fn internal_update_prize_saver(&mut self, account_id: &AccountId) -> &mut PrizeSaver {
+      self.assert_no_prize_draw();
+      let now = now_sec();
+      let (total_tickets, prize) = self.prize_pool.projected(now, self.savings_pool.apr_bps);
+      let pool = &mut self.prize_pool;
+      pool.total_tickets = total_tickets;
+      pool.prize = prize;
+      pool.last_accrued_sec = now;
+      let (epoch, started_at_sec) = (pool.epoch, pool.started_at_sec);
+      let saver = self.prize_savers.entry(account_id.clone()).or_insert(PrizeSaver {
+          principal: NearToken::from_yoctonear(0),
+          epoch,
+          tickets: 0,
+          last_updated_sec: now,
+      });
+      saver.tickets = saver.tickets_in(epoch, started_at_sec, now);
+      saver.epoch = epoch;
+      saver.last_updated_sec = now;
+      saver
+  }
+
+  /// Drops `account_id`'s prize savings record once its principal is gone, with the tickets
+  /// it holds in the current epoch. Called on unregistering.
+  pub(crate) fn internal_remove_empty_prize_saver(&mut self, account_id: &AccountId) {
+      if self.prize_savers.get(account_id).is_some_and(|saver| saver.principal.is_zero()) {
+          let tickets = self.internal_update_prize_saver(account_id).tickets;
+          self.prize_pool.total_tickets = self.prize_pool.total_tickets.saturating_sub(tickets);
+          self.prize_savers.remove(account_id);
+      }
+  }
+}
+
+fn winning_ticket(randomness: &CryptoHash, total_tickets: u128) -> u128 {
+  let mut bytes = [0u8; 16];
+  bytes.copy_from_slice(&randomness[..16]);
+  u128::from_le_bytes(bytes) % total_tickets
+}
+
+fn now_sec() -> u64 {
+  env::block_timestamp() / 1_000_000_000
+}
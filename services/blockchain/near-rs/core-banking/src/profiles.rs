@@ -0,0 +1,129 @@
+// services/blockchain/near-rs/core-banking/src/profiles.rs
+use near_sdk::{near, env, AccountId};
+
+use crate::{BankEvent, BioCrypticBankCore, BioCrypticBankCoreExt, DEFAULT_PAGE_LIMIT};
+
+const MAX_DISPLAY_NAME_LENGTH: usize = 64;
+const MAX_AVATAR_CID_LENGTH: usize = 128;
+const MAX_CONTACT_LABEL_LENGTH: usize = 64;
+const MAX_CONTACTS: usize = 100;
+
+/// Public details the app shows for an account.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct AccountProfile {
+  pub display_name: Option<String>,
+  /// IPFS CID of the avatar image.
+  pub avatar_cid: Option<String>,
+  pub updated_at: u64,
+}
+
+/// The caller's own label for an account it pays. Unlike trusted recipients, contacts do not
+/// restrict where money can go.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct Contact {
+  pub account_id: AccountId,
+  pub label: String,
+  pub added_at: u64,
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Sets the caller's profile, replacing the previous one. Paid from the caller's storage
+  /// deposit.
+  pub fn set_profile(&mut self, display_name: Option<String>, avatar_cid: Option<String>) {
+      let initial_storage = env::storage_usage();
+      let account_id = env::predecessor_account_id();
+      self.assert_registered(&account_id);
+      assert!(
+          display_name.as_ref().is_none_or(|name| !name.is_empty() && name.len() <= MAX_DISPLAY_NAME_LENGTH),
+          "Display name must be 1 to {} bytes.", MAX_DISPLAY_NAME_LENGTH
+      );
+      assert!(
+          avatar_cid.as_ref().is_none_or(|cid| !cid.is_empty() && cid.len() <= MAX_AVATAR_CID_LENGTH),
+          "Avatar CID must be 1 to {} bytes.", MAX_AVATAR_CID_LENGTH
+      );
+      self.profiles.insert(account_id.clone(), AccountProfile { display_name, avatar_cid, updated_at: env::block_timestamp() });
+      self.internal_charge_storage(&account_id, initial_storage);
+      BankEvent::ProfileUpdated { account_id }.emit();
+  }
+
+  /// Deletes the caller's profile, releasing its storage.
+  pub fn delete_profile(&mut self) {
+      let initial_storage = env::storage_usage();
+      let account_id = env::predecessor_account_id();
+      assert!(self.profiles.remove(&account_id).is_some(), "No profile is set.");
+      self.internal_charge_storage(&account_id, initial_storage);
+      BankEvent::ProfileUpdated { account_id }.emit();
+  }
+
+  /// Labels `account_id` in the caller's contacts, adding it or renaming an existing
+  /// contact. No event is emitted, so labels do not reach indexers.
+  pub fn set_contact(&mut self, account_id: AccountId, label: String) {
+      let initial_storage = env::storage_usage();
+      let owner_id = env::predecessor_account_id();
+      assert!(
+          !label.is_empty() && label.len() <= MAX_CONTACT_LABEL_LENGTH,
+          "Label must be 1 to {} bytes.", MAX_CONTACT_LABEL_LENGTH
+      );
+      self.assert_registered(&owner_id);
+      let contacts = self.contacts.entry(owner_id.clone()).or_default();
+      match contacts.iter_mut().find(|contact| contact.account_id == account_id) {
+          Some(contact) => contact.label = label,
+          None => {
+              assert!(contacts.len() < MAX_CONTACTS, "Cannot exceed {} contacts.", MAX_CONTACTS);
+              contacts.push(Contact { account_id, label, added_at: env::block_timestamp() });
+          }
+      }
+      self.internal_charge_storage(&owner_id, initial_storage);
+  }
+
+  /// Removes `account_id` from the caller's contacts.
+  pub fn remove_contact(&mut self, account_id: AccountId) {
+      let initial_storage = env::storage_usage();
+      let owner_id = env::predecessor_account_id();
+      let contacts = self.contacts.get_mut(&owner_id).unwrap_or_else(|| env::panic_str("Contact not found."));
+      let count = contacts.len();
+      contacts.retain(|contact| contact.account_id != account_id);
+      assert!(contacts.len() < count, "Contact not found.");
+      if contacts.is_empty() {
+          self.contacts.remove(&owner_id);
+      }
+      self.internal_charge_storage(&owner_id, initial_storage);
+  }
+
+  /// View function.
+  pub fn get_profile(&self, account_id: AccountId) -> Option<AccountProfile> {
+      self.profiles.get(&account_id).cloned()
+  }
+
+  /// Profiles of `account_ids`, in order, for rendering a list of payees in one call.
+  /// View function.
+  pub fn get_profiles(&self, account_ids: Vec<AccountId>) -> Vec<Option<AccountProfile>> {
+      account_ids.iter().take(DEFAULT_PAGE_LIMIT as usize).map(|account_id| self.profiles.get(account_id).cloned()).collect()
+  }
+
+  /// `owner_id`'s contacts in the order they were added, paginated. Contact state is
+  /// readable on-chain like all contract state; it is kept out of events only.
+  /// View function.
+  pub fn get_contacts(&self, owner_id: AccountId, from_index: Option<u64>, limit: Option<u64>) -> Vec<Contact> {
+      self.contacts.get(&owner_id)
+          .map(|contacts| contacts.iter()
+              .skip(from_index.unwrap_or(0) as usize)
+              .take(limit.unwrap_or(DEFAULT_PAGE_LIMIT) as usize)
+              .cloned()
+              .collect())
+          .unwrap_or_default()
+  }
+
+  /// Name to show `owner_id` for `account_id`: its contact label if it has one, otherwise
+  /// the account's profile display name.
+  /// View function.
+  pub fn get_payee_name(&self, owner_id: AccountId, account_id: AccountId) -> Option<String> {
+      self.contacts.get(&owner_id)
+          .and_then(|contacts| contacts.iter().find(|contact| contact.account_id == account_id))
+          .map(|contact| contact.label.clone())
+          .or_else(|| self.profiles.get(&account_id).and_then(|profile| profile.display_name.clone()))
+  }
+}
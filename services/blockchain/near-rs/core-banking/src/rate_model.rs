@@ -0,0 +1,140 @@
+// services/blockchain/near-rs/core-banking/src/rate_model.rs
+use near_sdk::{near, env, AccountId};
+
+use crate::savings::BPS_DENOMINATOR;
+use crate::term_deposits::TermRate;
+use crate::{mul_div, BankEvent, BioCrypticBankCore, BioCrypticBankCoreExt};
+
+const NANOS_PER_SECOND: u64 = 1_000_000_000;
+/// Delay between proposing a rate model and being able to apply it (48 hours).
+pub const RATE_UPDATE_TIMELOCK_SEC: u64 = 48 * 60 * 60;
+const MAX_TERM_DAYS: u32 = 3650;
+const MAX_TERM_TIERS: usize = 20;
+
+/// Borrow rate as a function of utilization: `base_apr_bps + slope_bps * utilization`.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct LendingRateModel {
+  /// Rate charged at zero utilization.
+  pub base_apr_bps: u16,
+  /// Rate added at full utilization.
+  pub slope_bps: u16,
+}
+
+/// Every interest rate the bank pays or charges. Changed only through
+/// `propose_rate_model` and `apply_rate_model`.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct RateModel {
+  pub lending: LendingRateModel,
+  /// APR of the flexible savings pool.
+  pub savings_apr_bps: u16,
+  /// Fixed APR offered per term deposit length.
+  pub term_tiers: Vec<TermRate>,
+}
+
+/// A rate model waiting out its timelock.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct PendingRateModel {
+  pub model: RateModel,
+  pub proposed_by: AccountId,
+  /// Earliest time (ns) the model can be applied.
+  pub executable_at: u64,
+}
+
+#[near(serializers = [json])]
+#[derive(Debug, PartialEq)]
+pub struct RateModelView {
+  pub model: RateModel,
+  /// Loans outstanding over loans plus liquid reserves.
+  pub utilization_bps: u16,
+  pub borrow_apr_bps: u16,
+  pub pending: Option<PendingRateModel>,
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Proposes a new rate model, replacing any pending proposal. It can be applied once
+  /// `RATE_UPDATE_TIMELOCK_SEC` have passed. Owner or governance only.
+  pub fn propose_rate_model(&mut self, model: RateModel) {
+      self.assert_owner_or_governance();
+      let lending = &model.lending;
+      assert!(
+          lending.base_apr_bps as u128 + lending.slope_bps as u128 <= BPS_DENOMINATOR,
+          "Borrow APR cannot exceed 100% at full utilization."
+      );
+      assert!(model.savings_apr_bps as u128 <= BPS_DENOMINATOR, "Savings APR cannot exceed 10000 bps.");
+      assert!(model.term_tiers.len() <= MAX_TERM_TIERS, "Cannot exceed {} term tiers.", MAX_TERM_TIERS);
+      for (index, tier) in model.term_tiers.iter().enumerate() {
+          assert!(tier.term_days > 0 && tier.term_days <= MAX_TERM_DAYS, "Term must be between 1 and {} days.", MAX_TERM_DAYS);
+          assert!(tier.apr_bps as u128 <= BPS_DENOMINATOR, "APR cannot exceed 10000 bps.");
+          assert!(
+              model.term_tiers[..index].iter().all(|other| other.term_days != tier.term_days),
+              "Duplicate term tier."
+          );
+      }
+      let executable_at = env::block_timestamp() + RATE_UPDATE_TIMELOCK_SEC * NANOS_PER_SECOND;
+      self.pending_rate_model = Some(PendingRateModel {
+          model: model.clone(),
+          proposed_by: env::predecessor_account_id(),
+          executable_at,
+      });
+      BankEvent::RateModelProposed { model, executable_at }.emit();
+  }
+
+  /// Makes the pending rate model current once its timelock has passed. Savings interest up
+  /// to now accrues at the old rate; term deposits keep the rate they were opened at, and
+  /// those whose term is no longer offered stop rolling over. Callable by anyone.
+  pub fn apply_rate_model(&mut self) {
+      let pending = self.pending_rate_model.take()
+          .unwrap_or_else(|| env::panic_str("No rate model is pending."));
+      assert!(env::block_timestamp() >= pending.executable_at, "Rate model timelock has not passed.");
+      let model = pending.model;
+      self.internal_accrue_savings();
+      self.savings_pool.apr_bps = model.savings_apr_bps;
+      self.term_rates.clear();
+      for tier in &model.term_tiers {
+          self.term_rates.insert(tier.term_days, tier.apr_bps);
+      }
+      self.rate_model = model.clone();
+      BankEvent::SavingsAprUpdated { apr_bps: model.savings_apr_bps }.emit();
+      BankEvent::RateModelApplied { model }.emit();
+  }
+
+  /// Discards the pending rate model. Owner or governance only.
+  pub fn cancel_rate_model(&mut self) {
+      self.assert_owner_or_governance();
+      assert!(self.pending_rate_model.take().is_some(), "No rate model is pending.");
+      BankEvent::RateModelCancelled { cancelled_by: env::predecessor_account_id() }.emit();
+  }
+
+  /// The current rate model with the live utilization and borrow rate, and any pending change.
+  /// View function.
+  pub fn get_rate_model(&self) -> RateModelView {
+      RateModelView {
+          model: self.rate_model.clone(),
+          utilization_bps: self.internal_utilization_bps(),
+          borrow_apr_bps: self.internal_borrow_apr_bps(),
+          pending: self.pending_rate_model.clone(),
+      }
+  }
+}
+
+impl BioCrypticBankCore {
+  fn internal_utilization_bps(&self) -> u16 {
+      let borrowed = self.total_borrowed.as_yoctonear();
+      let supply = borrowed.saturating_add(self.get_reserves().liquid.as_yoctonear());
+      if supply == 0 {
+          return 0;
+      }
+      mul_div(borrowed, BPS_DENOMINATOR, supply) as u16
+  }
+
+  /// Current loan APR under the lending rate model.
+  pub(crate) fn internal_borrow_apr_bps(&self) -> u16 {
+      let lending = &self.rate_model.lending;
+      let variable = mul_div(lending.slope_bps as u128, self.internal_utilization_bps() as u128, BPS_DENOMINATOR);
+      lending.base_apr_bps + variable as u16
+  }
+}
@@ -0,0 +1,111 @@
+// services/blockchain/near-rs/core-banking/src/receipts.rs
+use near_sdk::{near, env, AccountId, CryptoHash, NearToken};
+use near_sdk::json_types::Base58CryptoHash;
+
+use crate::ledger::{EntryDirection, EntryKind, LedgerEntry};
+use crate::{BioCrypticBankCore, BioCrypticBankCoreExt};
+
+/// Token every ledger amount is denominated in.
+const NATIVE_TOKEN: &str = "near";
+
+/// Canonical proof that a payment was booked. `entry_hash` is
+/// `sha256(previous_hash ++ borsh(entry))` for the ledger entry behind the receipt, so anyone
+/// holding the entry can recompute it, and walking the chain forward from it reaches
+/// `get_ledger_chain_head`.
+#[near(serializers = [json])]
+#[derive(Debug, PartialEq)]
+pub struct PaymentReceipt {
+  pub entry_id: u64,
+  /// The other side of a movement between two bank accounts.
+  pub linked_entry_id: Option<u64>,
+  /// `None` when the funds came from outside the bank, e.g. a deposit.
+  pub payer_id: Option<AccountId>,
+  /// `None` when the funds left the bank, e.g. a withdrawal.
+  pub payee_id: Option<AccountId>,
+  pub kind: EntryKind,
+  pub amount: NearToken,
+  pub token: String,
+  pub memo: Option<String>,
+  pub reference: Option<String>,
+  pub timestamp: u64,
+  /// The ledger entry the hash covers, as stored.
+  pub entry: LedgerEntry,
+  pub previous_hash: Base58CryptoHash,
+  pub entry_hash: Base58CryptoHash,
+  pub linked_entry_hash: Option<Base58CryptoHash>,
+}
+
+/// Position of one ledger entry in the running hash chain.
+#[near(serializers = [borsh])]
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct ChainLink {
+  pub previous_hash: CryptoHash,
+  pub hash: CryptoHash,
+}
+
+#[near(serializers = [json])]
+#[derive(Debug, PartialEq)]
+pub struct LedgerChainHead {
+  /// Entries hashed into the chain so far.
+  pub length: u64,
+  pub hash: Base58CryptoHash,
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Receipt for ledger entry `tx_id`, e.g. the debit entry of a transfer. `None` if the
+  /// entry does not exist or was removed when its account unregistered.
+  /// View function.
+  pub fn get_receipt(&self, tx_id: u64) -> Option<PaymentReceipt> {
+      let entry = self.ledger_entries.get(&tx_id)?.clone();
+      let link = *self.ledger_chain_links.get(&tx_id)?;
+      let (payer_id, payee_id) = match entry.direction {
+          EntryDirection::Debit => (Some(entry.account_id.clone()), entry.counterparty_id.clone()),
+          EntryDirection::Credit => (entry.counterparty_id.clone(), Some(entry.account_id.clone())),
+      };
+      Some(PaymentReceipt {
+          entry_id: entry.id,
+          linked_entry_id: entry.linked_entry_id,
+          payer_id,
+          payee_id,
+          kind: entry.kind,
+          amount: entry.amount,
+          token: NATIVE_TOKEN.to_string(),
+          memo: entry.memo.clone(),
+          reference: entry.reference.clone(),
+          timestamp: entry.timestamp,
+          previous_hash: link.previous_hash.into(),
+          entry_hash: link.hash.into(),
+          linked_entry_hash: entry.linked_entry_id
+              .and_then(|linked_id| self.ledger_chain_links.get(&linked_id))
+              .map(|linked| linked.hash.into()),
+          entry,
+      })
+  }
+
+  /// Hash of the latest entry in the running ledger hash chain.
+  /// View function.
+  pub fn get_ledger_chain_head(&self) -> LedgerChainHead {
+      LedgerChainHead { length: self.ledger_chain_length, hash: self.ledger_chain_head.into() }
+  }
+
+  /// Chain hash recorded for ledger entry `entry_id`. Hashes are kept after the entry itself
+  /// is removed so the chain stays verifiable.
+  /// View function.
+  pub fn get_ledger_entry_hash(&self, entry_id: u64) -> Option<Base58CryptoHash> {
+      self.ledger_chain_links.get(&entry_id).map(|link| link.hash.into())
+  }
+}
+
+impl BioCrypticBankCore {
+  /// Extends the running hash chain with `entry`.
+  pub(crate) fn internal_chain_entry(&mut self, entry: &LedgerEntry) {
+      let previous_hash = self.ledger_chain_head;
+      let mut preimage = previous_hash.to_vec();
+      preimage.extend(near_sdk::borsh::to_vec(entry).unwrap_or_else(|_| env::panic_str("Cannot serialize ledger entry.")));
+      let hash = env::sha256_array(&preimage);
+      self.ledger_chain_links.insert(entry.id, ChainLink { previous_hash, hash });
+      self.ledger_chain_head = hash;
+      self.ledger_chain_length += 1;
+  }
+}
@@ -0,0 +1,62 @@
+// services/blockchain/near-rs/core-banking/src/recovery_holds.rs
+use near_sdk::{near, env, AccountId};
+
+use crate::{BankEvent, BioCrypticBankCore, BioCrypticBankCoreExt};
+
+const MAX_RECOVERY_ID_LENGTH: usize = 128;
+
+/// Freeze on an account's outgoing funds while a recovery of its keys is pending.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct RecoveryHold {
+  /// Request id on the account recovery contract.
+  pub recovery_id: String,
+  pub placed_at: u64,
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Stops withdrawals, transfers and other payments out of `account_id` until the hold is
+  /// lifted, so funds are safe from the compromised keys while they are being rotated.
+  /// Deposits and incoming payments still arrive. Account recovery contract only.
+  pub fn place_recovery_hold(&mut self, account_id: AccountId, recovery_id: String) {
+      self.assert_recovery_contract();
+      self.assert_registered(&account_id);
+      assert!(!recovery_id.is_empty() && recovery_id.len() <= MAX_RECOVERY_ID_LENGTH, "Recovery id must be 1 to {} bytes.", MAX_RECOVERY_ID_LENGTH);
+      assert!(self.recovery_holds.get(&account_id).is_none(), "Account is already on recovery hold.");
+      self.recovery_holds.insert(account_id.clone(), RecoveryHold { recovery_id: recovery_id.clone(), placed_at: env::block_timestamp() });
+      BankEvent::RecoveryHoldPlaced { account_id, recovery_id }.emit();
+  }
+
+  /// Lifts the hold on `account_id` once its recovery completed or was abandoned.
+  /// Account recovery contract only.
+  pub fn lift_recovery_hold(&mut self, account_id: AccountId) {
+      self.assert_recovery_contract();
+      let hold = self.recovery_holds.remove(&account_id)
+          .unwrap_or_else(|| env::panic_str("Account is not on recovery hold."));
+      BankEvent::RecoveryHoldLifted { account_id, recovery_id: hold.recovery_id }.emit();
+  }
+
+  /// View function.
+  pub fn get_recovery_hold(&self, account_id: AccountId) -> Option<RecoveryHold> {
+      self.recovery_holds.get(&account_id).cloned()
+  }
+}
+
+impl BioCrypticBankCore {
+  fn assert_recovery_contract(&self) {
+      assert!(
+          self.recovery_contract_id.as_ref() == Some(&env::predecessor_account_id()),
+          "Only the account recovery contract can manage recovery holds."
+      );
+  }
+
+  pub(crate) fn is_on_recovery_hold(&self, account_id: &AccountId) -> bool {
+      self.recovery_holds.contains_key(account_id)
+  }
+
+  /// Panics if `account_id`'s outgoing funds are frozen by a pending recovery.
+  pub(crate) fn assert_no_recovery_hold(&self, account_id: &AccountId) {
+      assert!(!self.is_on_recovery_hold(account_id), "Account is on hold while its recovery is pending.");
+  }
+}
@@ -0,0 +1,117 @@
+// services/blockchain/near-rs/core-banking/src/referrals.rs
+use near_sdk::{near, env, AccountId, NearToken};
+
+use crate::ledger::{EntryDirection, EntryKind};
+use crate::savings::BPS_DENOMINATOR;
+use crate::{mul_div, BankEvent, BioCrypticBankCore, BioCrypticBankCoreExt};
+
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct ReferralConfig {
+  /// Share of every fee paid by a referee that accrues to their referrer.
+  pub reward_share_bps: u16,
+}
+
+/// A referrer's running totals.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct ReferrerStats {
+  pub total_referees: u32,
+  /// Rewards accrued to date, claimed or not.
+  pub earned: NearToken,
+  pub claimable: NearToken,
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Records `referrer_id` as the caller's referrer. Only possible during onboarding,
+  /// before the caller has any ledger activity; self-referrals and referral loops are rejected.
+  pub fn register_referral(&mut self, referrer_id: AccountId) {
+      let initial_storage = env::storage_usage();
+      let account_id = env::predecessor_account_id();
+      self.assert_registered(&account_id);
+      self.assert_registered(&referrer_id);
+      assert!(referrer_id != account_id, "Cannot refer yourself.");
+      assert!(self.referrers.get(&account_id).is_none(), "Referrer is already set.");
+      assert!(self.internal_ledger_len(&account_id) == 0, "Referrals can only be registered at onboarding.");
+      assert!(self.referrers.get(&referrer_id) != Some(&account_id), "Cannot refer your own referrer.");
+
+      self.referrers.insert(account_id.clone(), referrer_id.clone());
+      self.referrer_stats.entry(referrer_id.clone()).or_default().total_referees += 1;
+      self.internal_charge_storage(&account_id, initial_storage);
+      BankEvent::ReferralRegistered { account_id, referrer_id }.emit();
+  }
+
+  /// Pays the caller's claimable referral rewards, up to what the rewards budget holds, into
+  /// their balance. Returns the amount paid.
+  pub fn claim_referral_rewards(&mut self) -> NearToken {
+      let initial_storage = env::storage_usage();
+      let account_id = env::predecessor_account_id();
+      let stats = self.referrer_stats.get_mut(&account_id)
+          .unwrap_or_else(|| env::panic_str("No referral rewards."));
+      let amount = stats.claimable.min(self.referral_budget);
+      assert!(!amount.is_zero(), "No referral rewards can be paid right now.");
+      stats.claimable = stats.claimable.saturating_sub(amount);
+      self.referral_budget = self.referral_budget.saturating_sub(amount);
+
+      self.internal_credit(&account_id, amount);
+      self.internal_add_liabilities(amount);
+      self.internal_record_entry(&account_id, EntryKind::Referral, EntryDirection::Credit, amount, None, None);
+      self.internal_charge_storage(&account_id, initial_storage);
+      BankEvent::ReferralRewardsClaimed { account_id, amount }.emit();
+      amount
+  }
+
+  /// Sets the referrer share of referee fees. Applies to fees charged from now on. Owner only.
+  pub fn set_referral_config(&mut self, config: ReferralConfig) {
+      self.assert_owner();
+      assert!(config.reward_share_bps as u128 <= BPS_DENOMINATOR, "Reward share cannot exceed 100%.");
+      self.referral_config = config;
+  }
+
+  /// Referees, earned and claimable rewards of `account_id` as a referrer.
+  /// View function.
+  pub fn get_referral_stats(&self, account_id: AccountId) -> ReferrerStats {
+      self.referrer_stats.get(&account_id).cloned().unwrap_or_default()
+  }
+
+  /// View function.
+  pub fn get_referrer(&self, account_id: AccountId) -> Option<AccountId> {
+      self.referrers.get(&account_id).cloned()
+  }
+
+  /// View function.
+  pub fn get_referral_config(&self) -> ReferralConfig {
+      self.referral_config.clone()
+  }
+
+  /// Funds available to pay referral rewards.
+  /// View function.
+  pub fn get_referral_budget(&self) -> NearToken {
+      self.referral_budget
+  }
+}
+
+impl BioCrypticBankCore {
+  /// Moves `amount` from the fee treasury into the referral rewards budget, as approved by a
+  /// `MultisigAction::FundReferralBudget` proposal.
+  pub(crate) fn internal_fund_referral_budget(&mut self, amount: NearToken) {
+      self.internal_spend_treasury(amount);
+      self.referral_budget = self.referral_budget.saturating_add(amount);
+      BankEvent::ReferralBudgetFunded { amount, budget: self.referral_budget }.emit();
+  }
+
+  /// Accrues the referrer's share of `fee` paid by `account_id`, if it was referred.
+  pub(crate) fn internal_accrue_referral_reward(&mut self, account_id: &AccountId, fee: NearToken) {
+      let Some(referrer_id) = self.referrers.get(account_id) else { return };
+      let reward = NearToken::from_yoctonear(mul_div(
+          fee.as_yoctonear(), self.referral_config.reward_share_bps as u128, BPS_DENOMINATOR,
+      ));
+      if reward.is_zero() {
+          return;
+      }
+      let stats = self.referrer_stats.entry(referrer_id.clone()).or_default();
+      stats.earned = stats.earned.saturating_add(reward);
+      stats.claimable = stats.claimable.saturating_add(reward);
+  }
+}
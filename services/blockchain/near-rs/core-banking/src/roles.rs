@@ -0,0 +1,70 @@
+// services/blockchain/near-rs/core-banking/src/roles.rs
+use near_sdk::{near, env, AccountId};
+
+use crate::{BankEvent, BioCrypticBankCore, BioCrypticBankCoreExt};
+
+/// Operational roles delegated by the owner. The owner implicitly holds every role.
+#[near(serializers = [borsh, json])]
+#[serde(rename_all = "snake_case")]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Role {
+  /// May impose spending limits on any account.
+  ComplianceAdmin,
+  /// May pause and unpause features, including the global kill switch.
+  Pauser,
+  /// May move reserves between liquidity and whitelisted staking pools.
+  TreasuryManager,
+  /// May rule on disputed merchant charges.
+  DisputeArbiter,
+  /// May approve or reject insurance claims.
+  InsuranceAssessor,
+  /// May appraise NFTs pledged as credit line collateral.
+  NftAppraiser,
+  /// May relay NEP-366 meta-transactions the bank pays gas for.
+  Relayer,
+  /// May register and deregister crank tasks.
+  CrankManager,
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Grants `role` to `account_id`. Owner only.
+  pub fn grant_role(&mut self, account_id: AccountId, role: Role) {
+      self.assert_owner();
+      let roles = self.roles.entry(account_id.clone()).or_default();
+      assert!(!roles.contains(&role), "Account already has this role.");
+      roles.push(role);
+      BankEvent::RoleGranted { account_id, role }.emit();
+  }
+
+  /// Revokes `role` from `account_id`. Owner only.
+  pub fn revoke_role(&mut self, account_id: AccountId, role: Role) {
+      self.assert_owner();
+      let roles = self.roles.get_mut(&account_id)
+          .filter(|roles| roles.contains(&role))
+          .unwrap_or_else(|| env::panic_str("Account does not have this role."));
+      roles.retain(|existing| *existing != role);
+      if roles.is_empty() {
+          self.roles.remove(&account_id);
+      }
+      BankEvent::RoleRevoked { account_id, role }.emit();
+  }
+
+  /// Checks whether `account_id` holds `role`, either explicitly or as the owner.
+  /// View function.
+  pub fn has_role(&self, account_id: AccountId, role: Role) -> bool {
+      account_id == self.owner_id || self.roles.get(&account_id).is_some_and(|roles| roles.contains(&role))
+  }
+
+  /// Lists the roles explicitly granted to `account_id`.
+  /// View function.
+  pub fn get_roles(&self, account_id: AccountId) -> Vec<Role> {
+      self.roles.get(&account_id).cloned().unwrap_or_default()
+  }
+}
+
+impl BioCrypticBankCore {
+  pub(crate) fn assert_role(&self, role: Role) {
+      assert!(self.has_role(env::predecessor_account_id(), role), "Caller does not have the required role.");
+  }
+}
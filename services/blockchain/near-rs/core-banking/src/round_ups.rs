@@ -0,0 +1,119 @@
+// services/blockchain/near-rs/core-banking/src/round_ups.rs
+use near_sdk::{near, env, AccountId, NearToken};
+
+use crate::{BankEvent, BioCrypticBankCore, BioCrypticBankCoreExt};
+
+/// Where round-up change is swept.
+#[near(serializers = [borsh, json])]
+#[serde(rename_all = "snake_case")]
+#[derive(Debug, PartialEq, Clone)]
+pub enum RoundUpTarget {
+  /// A savings goal, funded from the checking balance.
+  Goal(String),
+  /// An envelope, reserved within the checking balance.
+  Envelope(String),
+  /// A registered charity, donated to from the checking balance.
+  Charity(AccountId),
+}
+
+/// An account's opt-in round-up rule: every outgoing transfer is rounded up to a multiple of
+/// `unit` and the difference is swept into `target`.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct RoundUpSettings {
+  pub unit: NearToken,
+  pub target: RoundUpTarget,
+}
+
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct RoundUpStats {
+  pub total_swept: NearToken,
+  pub round_ups: u64,
+  pub last_swept_at: Option<u64>,
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Rounds each of the caller's outgoing transfers up to a multiple of `unit` and sweeps the
+  /// difference into `target`, which must exist. Sweeps are skipped when the unallocated
+  /// balance cannot cover them or the target has since been deleted.
+  pub fn set_round_up(&mut self, unit: NearToken, target: RoundUpTarget) {
+      let initial_storage = env::storage_usage();
+      let account_id = env::predecessor_account_id();
+      self.assert_registered(&account_id);
+      assert!(unit.as_yoctonear() > 1, "Round-up unit must be greater than 1 yoctoNEAR.");
+      assert!(self.internal_round_up_target_exists(&account_id, &target), "Round-up target not found.");
+      self.round_up_settings.insert(account_id.clone(), RoundUpSettings { unit, target: target.clone() });
+      self.internal_charge_storage(&account_id, initial_storage);
+      BankEvent::RoundUpEnabled { account_id, unit, target }.emit();
+  }
+
+  /// Stops rounding up the caller's transfers. Accumulated statistics are kept.
+  pub fn disable_round_up(&mut self) {
+      let account_id = env::predecessor_account_id();
+      assert!(self.round_up_settings.remove(&account_id).is_some(), "Round-ups are not enabled.");
+      BankEvent::RoundUpDisabled { account_id }.emit();
+  }
+
+  /// View function.
+  pub fn get_round_up_settings(&self, account_id: AccountId) -> Option<RoundUpSettings> {
+      self.round_up_settings.get(&account_id).cloned()
+  }
+
+  /// Total change swept by round-ups and how many transfers were rounded.
+  /// View function.
+  pub fn get_round_up_stats(&self, account_id: AccountId) -> RoundUpStats {
+      self.round_up_stats.get(&account_id).cloned().unwrap_or_default()
+  }
+}
+
+impl BioCrypticBankCore {
+  fn internal_round_up_target_exists(&self, account_id: &AccountId, target: &RoundUpTarget) -> bool {
+      match target {
+          RoundUpTarget::Goal(name) => self.savings_goals.get(account_id)
+              .is_some_and(|goals| goals.iter().any(|goal| &goal.name == name)),
+          RoundUpTarget::Envelope(name) => self.envelopes.get(account_id)
+              .is_some_and(|envelopes| envelopes.iter().any(|envelope| &envelope.name == name)),
+          RoundUpTarget::Charity(charity_id) => charity_id != account_id && self.internal_charity_exists(charity_id),
+      }
+  }
+
+  /// Sweeps the change rounding `amount`, just sent by `account_id`, up to its round-up
+  /// unit. Best effort: never fails the transfer.
+  pub(crate) fn internal_apply_round_up(&mut self, account_id: &AccountId, amount: NearToken) {
+      let Some(settings) = self.round_up_settings.get(account_id).cloned() else { return };
+      let remainder = amount.as_yoctonear() % settings.unit.as_yoctonear();
+      if remainder == 0 {
+          return;
+      }
+      let change = NearToken::from_yoctonear(settings.unit.as_yoctonear() - remainder);
+      if self.internal_unallocated_balance(account_id) < change
+          || !self.internal_round_up_target_exists(account_id, &settings.target)
+      {
+          return;
+      }
+      match &settings.target {
+          RoundUpTarget::Goal(name) => {
+              self.internal_debit(account_id, change);
+              self.internal_fund_goal(account_id, name, change);
+          }
+          RoundUpTarget::Envelope(name) => {
+              let envelope = self.envelopes.get_mut(account_id)
+                  .and_then(|envelopes| envelopes.iter_mut().find(|envelope| &envelope.name == name))
+                  .unwrap();
+              envelope.balance = envelope.balance.saturating_add(change);
+          }
+          RoundUpTarget::Charity(charity_id) => {
+              self.internal_debit(account_id, change);
+              self.internal_donate(account_id, charity_id, change);
+          }
+      }
+      let mut stats = self.round_up_stats.get(account_id).cloned().unwrap_or_default();
+      stats.total_swept = stats.total_swept.saturating_add(change);
+      stats.round_ups += 1;
+      stats.last_swept_at = Some(env::block_timestamp());
+      self.round_up_stats.insert(account_id.clone(), stats);
+      BankEvent::RoundUpSwept { account_id: account_id.clone(), amount: change, target: settings.target }.emit();
+  }
+}
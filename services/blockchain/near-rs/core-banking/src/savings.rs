@@ -0,0 +1,195 @@
+// services/blockchain/near-rs/core-banking/src/savings.rs
+use near_sdk::{near, env, AccountId, NearToken};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+
+use crate::{mul_div, BankEvent, BioCrypticBankCore, BioCrypticBankCoreExt};
+
+/// Fixed-point scale of the savings index (1.0 == `INDEX_SCALE`).
+pub const INDEX_SCALE: u128 = 1_000_000_000_000_000_000;
+pub const BPS_DENOMINATOR: u128 = 10_000;
+pub const SECONDS_PER_YEAR: u128 = 365 * 24 * 60 * 60;
+
+/// Global accrual state shared by every savings position.
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct SavingsPool {
+  /// Annual percentage rate in basis points.
+  pub apr_bps: u16,
+  /// Cumulative growth index scaled by `INDEX_SCALE`.
+  pub index: u128,
+  /// Timestamp (seconds) up to which `index` has been accrued.
+  pub last_accrued_sec: u64,
+  pub total_shares: u128,
+}
+
+/// A user's stake in the savings pool. The current value is `shares * index / INDEX_SCALE`.
+#[derive(BorshDeserialize, BorshSerialize, Debug, Default)]
+pub struct SavingsAccount {
+  pub shares: u128,
+  pub total_deposited: u128,
+  pub total_withdrawn: u128,
+  /// Global yield accumulator at the last settlement of this account.
+  pub yield_per_share_paid: u128,
+  /// Distributed yield settled but not yet claimed or compounded.
+  pub pending_yield: u128,
+  pub auto_compound: bool,
+}
+
+#[near(serializers = [json])]
+#[derive(Debug, PartialEq)]
+pub struct SavingsAccountView {
+  pub account_id: AccountId,
+  pub balance: NearToken,
+  pub total_deposited: NearToken,
+  pub total_withdrawn: NearToken,
+  /// Interest earned to date: `balance + total_withdrawn - total_deposited`.
+  pub earned_interest: NearToken,
+  /// Distributed yield not yet claimed or compounded.
+  pub pending_yield: NearToken,
+  pub auto_compound: bool,
+}
+
+#[near(serializers = [json])]
+#[derive(Debug, PartialEq)]
+pub struct SavingsPoolView {
+  pub apr_bps: u16,
+  pub index: String,
+  pub last_accrued_sec: u64,
+  pub total_savings: NearToken,
+}
+
+impl Default for SavingsPool {
+  fn default() -> Self {
+      Self { apr_bps: 0, index: INDEX_SCALE, last_accrued_sec: now_sec(), total_shares: 0 }
+  }
+}
+
+impl SavingsPool {
+  /// Index value as of `now`, without mutating state.
+  fn projected_index(&self, now: u64) -> u128 {
+      let elapsed = now.saturating_sub(self.last_accrued_sec) as u128;
+      if elapsed == 0 || self.apr_bps == 0 {
+          return self.index;
+      }
+      let growth = mul_div(self.index, self.apr_bps as u128 * elapsed, BPS_DENOMINATOR * SECONDS_PER_YEAR);
+      self.index.checked_add(growth).unwrap_or_else(|| env::panic_str("Savings index overflow."))
+  }
+
+  fn value_of(&self, shares: u128, index: u128) -> u128 {
+      mul_div(shares, index, INDEX_SCALE)
+  }
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Brings the savings index up to date. Permissionless crank; also run implicitly by
+  /// every savings operation. Returns the new index.
+  pub fn accrue(&mut self) -> String {
+      self.internal_accrue_savings().to_string()
+  }
+
+  /// Moves `amount` from the caller's checking balance into savings.
+  pub fn deposit_to_savings(&mut self, amount: NearToken) {
+      let initial_storage = env::storage_usage();
+      let account_id = env::predecessor_account_id();
+      assert!(amount.as_yoctonear() > 0, "Amount must be greater than 0.");
+      let index = self.internal_accrue_savings();
+      self.internal_debit(&account_id, amount);
+      self.internal_remove_liabilities(amount);
+
+      let shares = mul_div(amount.as_yoctonear(), INDEX_SCALE, index);
+      assert!(shares > 0, "Amount is too small to deposit into savings.");
+      self.internal_settle_yield(&account_id);
+      let yield_per_share = self.yield_per_share;
+      let account = self.savings_accounts.entry(account_id.clone())
+          .or_insert_with(|| SavingsAccount { yield_per_share_paid: yield_per_share, ..Default::default() });
+      account.shares += shares;
+      account.total_deposited += amount.as_yoctonear();
+      self.savings_pool.total_shares += shares;
+      self.internal_charge_storage(&account_id, initial_storage);
+
+      BankEvent::SavingsDeposit { account_id, amount }.emit();
+  }
+
+  /// Moves `amount` from the caller's savings back into their checking balance,
+  /// including any accrued interest.
+  pub fn withdraw_from_savings(&mut self, amount: NearToken) {
+      let account_id = env::predecessor_account_id();
+      assert!(amount.as_yoctonear() > 0, "Amount must be greater than 0.");
+      let index = self.internal_accrue_savings();
+      self.internal_settle_yield(&account_id);
+
+      let account = self.savings_accounts.get_mut(&account_id)
+          .unwrap_or_else(|| env::panic_str("No savings account found."));
+      let value = self.savings_pool.value_of(account.shares, index);
+      assert!(value >= amount.as_yoctonear(), "Insufficient savings balance.");
+
+      // Round the burned shares up so the pool never pays out more than it holds.
+      let shares = if value == amount.as_yoctonear() {
+          account.shares
+      } else {
+          (mul_div(amount.as_yoctonear(), INDEX_SCALE, index) + 1).min(account.shares)
+      };
+      account.shares -= shares;
+      account.total_withdrawn += amount.as_yoctonear();
+      if account.shares == 0 && account.pending_yield == 0 {
+          self.savings_accounts.remove(&account_id);
+      }
+      self.savings_pool.total_shares -= shares;
+      self.internal_credit(&account_id, amount);
+      self.internal_add_liabilities(amount);
+
+      BankEvent::SavingsWithdraw { account_id, amount }.emit();
+  }
+
+  /// Retrieves an account's savings balance and interest earned to date, including
+  /// interest not yet accrued on-chain.
+  /// View function.
+  pub fn get_savings_account(&self, account_id: AccountId) -> Option<SavingsAccountView> {
+      let account = self.savings_accounts.get(&account_id)?;
+      let index = self.savings_pool.projected_index(now_sec());
+      let balance = self.savings_pool.value_of(account.shares, index);
+      let earned = (balance + account.total_withdrawn).saturating_sub(account.total_deposited);
+      Some(SavingsAccountView {
+          balance: NearToken::from_yoctonear(balance),
+          total_deposited: NearToken::from_yoctonear(account.total_deposited),
+          total_withdrawn: NearToken::from_yoctonear(account.total_withdrawn),
+          earned_interest: NearToken::from_yoctonear(earned),
+          pending_yield: self.get_pending_yield(account_id.clone()),
+          auto_compound: account.auto_compound,
+          account_id,
+      })
+  }
+
+  /// Retrieves the savings rate, current index and total savings under management.
+  /// View function.
+  pub fn get_savings_pool(&self) -> SavingsPoolView {
+      let index = self.savings_pool.projected_index(now_sec());
+      SavingsPoolView {
+          apr_bps: self.savings_pool.apr_bps,
+          index: index.to_string(),
+          last_accrued_sec: self.savings_pool.last_accrued_sec,
+          total_savings: NearToken::from_yoctonear(self.savings_pool.value_of(self.savings_pool.total_shares, index)),
+      }
+  }
+}
+
+impl BioCrypticBankCore {
+  /// Advances the savings index to the current block time and returns it.
+  pub(crate) fn internal_accrue_savings(&mut self) -> u128 {
+      let now = now_sec();
+      let index = self.savings_pool.projected_index(now);
+      self.savings_pool.index = index;
+      self.savings_pool.last_accrued_sec = now;
+      index
+  }
+
+  /// Current value of every savings position, including interest not yet accrued on-chain.
+  pub(crate) fn internal_savings_value(&self) -> NearToken {
+      let index = self.savings_pool.projected_index(now_sec());
+      NearToken::from_yoctonear(self.savings_pool.value_of(self.savings_pool.total_shares, index))
+  }
+}
+
+fn now_sec() -> u64 {
+  env::block_timestamp() / 1_000_000_000
+}
@@ -0,0 +1,209 @@
+// services/blockchain/near-rs/core-banking/src/scheduler.rs
+use near_sdk::{near, env, AccountId, NearToken};
+
+use crate::pause::Feature;
+use crate::{assert_valid_memo, BankEvent, BioCrypticBankCore, BioCrypticBankCoreExt};
+
+const NANOS_PER_SECOND: u64 = 1_000_000_000;
+/// Shortest allowed interval between executions (1 hour).
+const MIN_INTERVAL_SECONDS: u64 = 60 * 60;
+/// Delay before a payment that failed for insufficient funds is retried.
+const RETRY_DELAY_SECONDS: u64 = 60 * 60;
+/// Failed attempts after which the current installment is skipped.
+const MAX_RETRIES: u8 = 3;
+const DEFAULT_EXECUTION_LIMIT: u64 = 20;
+const MAX_STANDING_ORDERS_PER_ACCOUNT: usize = 50;
+
+/// A recurring payment from `payer_id` to `receiver_id`.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct StandingOrder {
+  pub id: u64,
+  pub payer_id: AccountId,
+  pub receiver_id: AccountId,
+  pub amount: NearToken,
+  pub interval_seconds: u64,
+  /// Timestamp (ns) at which the current installment was scheduled.
+  pub scheduled_at: u64,
+  /// Timestamp (ns) at which the next attempt is due; later than `scheduled_at` while retrying.
+  pub next_due_at: u64,
+  /// Timestamp (ns) after which no further installments are made.
+  pub end_at: Option<u64>,
+  pub memo: Option<String>,
+  /// Failed attempts for the current installment.
+  pub failed_attempts: u8,
+  pub executions: u64,
+}
+
+#[near(serializers = [json])]
+#[derive(Debug, PartialEq)]
+pub struct ExecutionSummary {
+  pub executed: u32,
+  pub failed: u32,
+  pub completed: u32,
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Creates a standing order paying `amount` to `receiver_id` every `interval_seconds`,
+  /// first at `start_at` (ns, defaults to now) and finishing after `end_at` if given.
+  /// Returns the order id.
+  pub fn create_standing_order(
+      &mut self,
+      receiver_id: AccountId,
+      amount: NearToken,
+      interval_seconds: u64,
+      start_at: Option<u64>,
+      end_at: Option<u64>,
+      memo: Option<String>,
+  ) -> u64 {
+      let initial_storage = env::storage_usage();
+      let payer_id = env::predecessor_account_id();
+      assert!(amount.as_yoctonear() > 0, "Amount must be greater than 0.");
+      assert!(payer_id != receiver_id, "Cannot create a standing order to self.");
+      self.assert_registered(&receiver_id);
+      self.assert_trusted_destination(&payer_id, &receiver_id);
+      assert!(interval_seconds >= MIN_INTERVAL_SECONDS, "Interval must be at least {} seconds.", MIN_INTERVAL_SECONDS);
+      assert_valid_memo(&memo);
+      let now = env::block_timestamp();
+      let next_due_at = start_at.unwrap_or(now).max(now);
+      if let Some(end_at) = end_at {
+          assert!(end_at >= next_due_at, "End date must not be before the first payment.");
+      }
+
+      let ids = self.standing_orders_by_account.entry(payer_id.clone()).or_default();
+      assert!(ids.len() < MAX_STANDING_ORDERS_PER_ACCOUNT, "Cannot exceed {} standing orders.", MAX_STANDING_ORDERS_PER_ACCOUNT);
+      let id = self.next_standing_order_id;
+      self.next_standing_order_id += 1;
+      ids.push(id);
+
+      self.standing_orders.insert(id, StandingOrder {
+          id,
+          payer_id: payer_id.clone(),
+          receiver_id: receiver_id.clone(),
+          amount,
+          interval_seconds,
+          scheduled_at: next_due_at,
+          next_due_at,
+          end_at,
+          memo,
+          failed_attempts: 0,
+          executions: 0,
+      });
+      self.internal_charge_storage(&payer_id, initial_storage);
+      BankEvent::StandingOrderCreated { id, payer_id, receiver_id, amount, interval_seconds, next_due_at }.emit();
+      id
+  }
+
+  /// Cancels one of the caller's standing orders.
+  pub fn cancel_standing_order(&mut self, id: u64) {
+      let order = self.standing_orders.get(&id)
+          .unwrap_or_else(|| env::panic_str("Standing order not found."));
+      assert_eq!(order.payer_id, env::predecessor_account_id(), "Only the payer can cancel this standing order.");
+      self.internal_remove_standing_order(id);
+      BankEvent::StandingOrderCancelled { id }.emit();
+  }
+
+  /// Permissionless crank (suitable for a Croncat task or any keeper) executing up to
+  /// `limit` due standing orders. Installments the payer cannot cover, or that would exceed
+  /// the payer's spending limits, are retried after `RETRY_DELAY_SECONDS` and skipped after
+  /// `MAX_RETRIES` failures. Orders of payers on recovery hold wait until the hold is lifted.
+  pub fn execute_due_payments(&mut self, limit: Option<u64>) -> ExecutionSummary {
+      self.assert_not_paused(Feature::Transfers);
+      let now = env::block_timestamp();
+      let limit = limit.unwrap_or(DEFAULT_EXECUTION_LIMIT) as usize;
+      let due: Vec<u64> = self.standing_orders.iter()
+          .filter(|(_, order)| order.next_due_at <= now && !self.is_on_recovery_hold(&order.payer_id))
+          .map(|(id, _)| *id)
+          .take(limit)
+          .collect();
+
+      let mut summary = ExecutionSummary { executed: 0, failed: 0, completed: 0 };
+      for id in due {
+          let mut order = self.standing_orders.get(&id).unwrap().clone();
+          if self.get_balance(order.payer_id.clone()) >= order.amount && self.can_spend(&order.payer_id, order.amount) {
+              self.internal_record_outflow(&order.payer_id, order.amount);
+              self.internal_debit(&order.payer_id, order.amount);
+              self.internal_credit(&order.receiver_id, order.amount);
+              order.executions += 1;
+              summary.executed += 1;
+              BankEvent::StandingOrderExecuted {
+                  id,
+                  payer_id: order.payer_id.clone(),
+                  receiver_id: order.receiver_id.clone(),
+                  amount: order.amount,
+                  execution: order.executions,
+                  memo: order.memo.clone(),
+              }.emit();
+              order.advance_schedule();
+          } else {
+              order.failed_attempts += 1;
+              summary.failed += 1;
+              let will_retry = order.failed_attempts < MAX_RETRIES;
+              BankEvent::StandingOrderFailed { id, attempt: order.failed_attempts, will_retry }.emit();
+              if will_retry {
+                  order.next_due_at = now + RETRY_DELAY_SECONDS * NANOS_PER_SECOND;
+              } else {
+                  order.advance_schedule();
+              }
+          }
+
+          if order.end_at.is_some_and(|end_at| order.next_due_at > end_at) {
+              self.internal_remove_standing_order(id);
+              summary.completed += 1;
+              BankEvent::StandingOrderCompleted { id, executions: order.executions }.emit();
+          } else {
+              self.standing_orders.insert(id, order);
+          }
+      }
+      summary
+  }
+
+  /// Retrieves a standing order by id.
+  /// View function.
+  pub fn get_standing_order(&self, id: u64) -> Option<StandingOrder> {
+      self.standing_orders.get(&id).cloned()
+  }
+
+  /// Lists the standing orders paid by `account_id`.
+  /// View function.
+  pub fn get_standing_orders(&self, account_id: AccountId) -> Vec<StandingOrder> {
+      self.standing_orders_by_account.get(&account_id)
+          .map(|ids| ids.iter().filter_map(|id| self.standing_orders.get(id).cloned()).collect())
+          .unwrap_or_default()
+  }
+
+  /// Counts the standing orders currently due for execution.
+  /// View function.
+  pub fn get_due_payments_count(&self) -> u64 {
+      let now = env::block_timestamp();
+      self.standing_orders.values().filter(|order| order.next_due_at <= now).count() as u64
+  }
+}
+
+impl StandingOrder {
+  /// Moves to the next installment after an execution or a skipped one. Installments
+  /// missed while the crank was not run are not replayed.
+  fn advance_schedule(&mut self) {
+      let interval = self.interval_seconds * NANOS_PER_SECOND;
+      let now = env::block_timestamp();
+      self.scheduled_at += interval;
+      if self.scheduled_at <= now {
+          self.scheduled_at += (now - self.scheduled_at) / interval * interval + interval;
+      }
+      self.next_due_at = self.scheduled_at;
+      self.failed_attempts = 0;
+  }
+}
+
+impl BioCrypticBankCore {
+  fn internal_remove_standing_order(&mut self, id: u64) {
+      let Some(order) = self.standing_orders.remove(&id) else { return };
+      if let Some(ids) = self.standing_orders_by_account.get_mut(&order.payer_id) {
+          ids.retain(|existing| *existing != id);
+          if ids.is_empty() {
+              self.standing_orders_by_account.remove(&order.payer_id);
+          }
+      }
+  }
+}
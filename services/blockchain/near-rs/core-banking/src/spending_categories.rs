@@ -0,0 +1,212 @@
+// services/blockchain/near-rs/core-banking/src/spending_categories.rs
+use near_sdk::{near, env, AccountId, NearToken};
+
+use crate::ledger::EntryDirection;
+use crate::{BankEvent, BioCrypticBankCore, BioCrypticBankCoreExt};
+
+const NANOS_PER_DAY: u64 = 24 * 60 * 60 * 1_000_000_000;
+const MAX_CATEGORIES: usize = 32;
+const MAX_CATEGORY_LEN: usize = 32;
+const MAX_CATEGORY_RULES: usize = 50;
+/// Most months `get_spending_by_category` covers in one call.
+const MAX_MONTHS_PER_QUERY: usize = 24;
+
+/// Files every outgoing ledger entry to `counterparty_id` under `category`.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct CategoryRule {
+  pub counterparty_id: AccountId,
+  pub category: String,
+}
+
+/// Outgoing entries filed under `category` in one month.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct CategorySpend {
+  pub category: String,
+  pub amount: NearToken,
+  pub count: u64,
+}
+
+#[near(serializers = [json])]
+#[derive(Debug, PartialEq)]
+pub struct MonthlySpending {
+  /// Calendar month (UTC) as `yyyymm`, e.g. `202610`.
+  pub month: u32,
+  pub categories: Vec<CategorySpend>,
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Replaces the taxonomy entries can be filed under. Existing tags and rules keep
+  /// categories that are dropped, but no new ones can use them. Owner only.
+  pub fn set_spending_categories(&mut self, categories: Vec<String>) {
+      self.assert_owner();
+      assert!(categories.len() <= MAX_CATEGORIES, "Cannot exceed {} categories.", MAX_CATEGORIES);
+      for (index, category) in categories.iter().enumerate() {
+          assert!(!category.is_empty() && category.len() <= MAX_CATEGORY_LEN, "Category names must be 1 to {} bytes.", MAX_CATEGORY_LEN);
+          assert!(!categories[..index].contains(category), "Duplicate category: {}.", category);
+      }
+      self.spending_categories = categories.clone();
+      BankEvent::SpendingCategoriesUpdated { categories }.emit();
+  }
+
+  /// Files the caller's future outgoing entries to `counterparty_id` under `category`, or
+  /// removes the rule when `category` is `None`. Rules only apply to new entries.
+  pub fn set_category_rule(&mut self, counterparty_id: AccountId, category: Option<String>) {
+      let initial_storage = env::storage_usage();
+      let account_id = env::predecessor_account_id();
+      self.assert_registered(&account_id);
+      if let Some(category) = &category {
+          self.assert_known_category(category);
+      }
+      let rules = self.category_rules.entry(account_id.clone()).or_default();
+      rules.retain(|rule| rule.counterparty_id != counterparty_id);
+      if let Some(category) = category.clone() {
+          assert!(rules.len() < MAX_CATEGORY_RULES, "Cannot exceed {} category rules.", MAX_CATEGORY_RULES);
+          rules.push(CategoryRule { counterparty_id: counterparty_id.clone(), category });
+      }
+      if rules.is_empty() {
+          self.category_rules.remove(&account_id);
+      }
+      self.internal_charge_storage(&account_id, initial_storage);
+      BankEvent::CategoryRuleUpdated { account_id, counterparty_id, category }.emit();
+  }
+
+  /// Files one of the caller's outgoing ledger entries, e.g. a transfer, under `category`,
+  /// replacing its current category, or clears it when `category` is `None`. The entry's
+  /// month totals move with it.
+  pub fn set_entry_category(&mut self, entry_id: u64, category: Option<String>) {
+      let initial_storage = env::storage_usage();
+      let account_id = env::predecessor_account_id();
+      let entry = self.ledger_entries.get(&entry_id)
+          .filter(|entry| entry.account_id == account_id)
+          .unwrap_or_else(|| env::panic_str("Ledger entry not found."));
+      assert!(entry.direction == EntryDirection::Debit, "Only outgoing entries can be categorized.");
+      let (amount, month) = (entry.amount, month_of(entry.timestamp));
+      if let Some(category) = &category {
+          self.assert_known_category(category);
+      }
+      if let Some(previous) = self.entry_categories.remove(&entry_id) {
+          self.internal_remove_category_spend(&account_id, month, &previous, amount);
+      }
+      if let Some(category) = &category {
+          self.internal_file_entry(&account_id, entry_id, month, category, amount);
+      }
+      self.internal_charge_storage(&account_id, initial_storage);
+      BankEvent::EntryCategorized { account_id, entry_id, category }.emit();
+  }
+
+  /// View function.
+  pub fn get_spending_categories(&self) -> Vec<String> {
+      self.spending_categories.clone()
+  }
+
+  /// View function.
+  pub fn get_category_rules(&self, account_id: AccountId) -> Vec<CategoryRule> {
+      self.category_rules.get(&account_id).cloned().unwrap_or_default()
+  }
+
+  /// View function.
+  pub fn get_entry_category(&self, entry_id: u64) -> Option<String> {
+      self.entry_categories.get(&entry_id).cloned()
+  }
+
+  /// `account_id`'s outgoing entries per category for each month with any in
+  /// `[from_month, to_month]` (`yyyymm`, UTC), oldest first. Entries without a category are
+  /// not counted.
+  /// View function.
+  pub fn get_spending_by_category(&self, account_id: AccountId, from_month: u32, to_month: u32) -> Vec<MonthlySpending> {
+      self.spending_months.get(&account_id).map(|months| {
+          months.iter()
+              .filter(|month| (from_month..=to_month).contains(*month))
+              .take(MAX_MONTHS_PER_QUERY)
+              .map(|month| MonthlySpending {
+                  month: *month,
+                  categories: self.category_spending.get(&(account_id.clone(), *month)).cloned().unwrap_or_default(),
+              })
+              .collect()
+      }).unwrap_or_default()
+  }
+}
+
+impl BioCrypticBankCore {
+  fn assert_known_category(&self, category: &String) {
+      assert!(self.spending_categories.contains(category), "Unknown category: {}.", category);
+  }
+
+  /// Files a new outgoing entry under the category of the account's rule for its
+  /// counterparty, if there is one.
+  pub(crate) fn internal_auto_categorize(&mut self, account_id: &AccountId, counterparty_id: &AccountId, entry_id: u64, amount: NearToken) {
+      let Some(category) = self.category_rules.get(account_id)
+          .and_then(|rules| rules.iter().find(|rule| rule.counterparty_id == *counterparty_id))
+          .map(|rule| rule.category.clone())
+      else {
+          return;
+      };
+      self.internal_file_entry(account_id, entry_id, month_of(env::block_timestamp()), &category, amount);
+  }
+
+  fn internal_file_entry(&mut self, account_id: &AccountId, entry_id: u64, month: u32, category: &str, amount: NearToken) {
+      self.entry_categories.insert(entry_id, category.to_string());
+      let key = (account_id.clone(), month);
+      if !self.category_spending.contains_key(&key) {
+          let months = self.spending_months.entry(account_id.clone()).or_default();
+          let position = months.partition_point(|existing| *existing < month);
+          months.insert(position, month);
+      }
+      let totals = self.category_spending.entry(key).or_default();
+      match totals.iter_mut().find(|total| total.category == category) {
+          Some(total) => {
+              total.amount = total.amount.saturating_add(amount);
+              total.count += 1;
+          }
+          None => totals.push(CategorySpend { category: category.to_string(), amount, count: 1 }),
+      }
+  }
+
+  fn internal_remove_category_spend(&mut self, account_id: &AccountId, month: u32, category: &str, amount: NearToken) {
+      let key = (account_id.clone(), month);
+      let Some(totals) = self.category_spending.get_mut(&key) else { return };
+      if let Some(position) = totals.iter().position(|total| total.category == category) {
+          let total = &mut totals[position];
+          total.amount = total.amount.saturating_sub(amount);
+          total.count = total.count.saturating_sub(1);
+          if total.count == 0 {
+              totals.remove(position);
+          }
+      }
+      if totals.is_empty() {
+          self.category_spending.remove(&key);
+          if let Some(months) = self.spending_months.get_mut(account_id) {
+              months.retain(|existing| *existing != month);
+              if months.is_empty() {
+                  self.spending_months.remove(account_id);
+              }
+          }
+      }
+  }
+
+  /// Deletes `account_id`'s category rules and monthly totals. Entry tags go with the
+  /// ledger entries.
+  pub(crate) fn internal_remove_spending_analytics(&mut self, account_id: &AccountId) {
+      self.category_rules.remove(account_id);
+      for month in self.spending_months.remove(account_id).unwrap_or_default() {
+          self.category_spending.remove(&(account_id.clone(), month));
+      }
+  }
+}
+
+/// Calendar month (UTC) of `timestamp` (ns) as `yyyymm`.
+pub(crate) fn month_of(timestamp: u64) -> u32 {
+  // Civil-from-days conversion on the proleptic Gregorian calendar, with March-based years.
+  let days = (timestamp / NANOS_PER_DAY) as i64 + 719_468;
+  let era = days.div_euclid(146_097);
+  let day_of_era = days.rem_euclid(146_097);
+  let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+  let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+  let shifted_month = (5 * day_of_year + 2) / 153;
+  let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+  let year = year_of_era + era * 400 + i64::from(month <= 2);
+  (year * 100 + month) as u32
+}
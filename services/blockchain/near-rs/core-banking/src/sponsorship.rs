@@ -0,0 +1,193 @@
+// services/blockchain/near-rs/core-banking/src/sponsorship.rs
+use near_sdk::{near, env, AccountId, Gas, NearToken};
+use near_sdk::json_types::U128;
+
+use crate::roles::Role;
+use crate::storage::{StorageAccount, ACCOUNT_STORAGE_BYTES};
+use crate::{BankEvent, BioCrypticBankCore, BioCrypticBankCoreExt};
+
+/// Methods a relayer may sponsor: registering, then paying. Receiving a transfer needs no
+/// call at all once registered.
+const SPONSORED_METHODS: [&str; 3] = ["sponsored_register", "transfer", "pay_intent"];
+
+/// Per-user limits on meta-transactions the bank's relayers pay gas for. Owner controlled.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct SponsorshipConfig {
+  /// Sponsored calls each user gets, registration included. Zero disables sponsorship.
+  pub max_calls_per_user: u32,
+  /// Total gas each user's sponsored calls may attach.
+  pub max_gas_per_user: Gas,
+}
+
+impl Default for SponsorshipConfig {
+  fn default() -> Self {
+      Self { max_calls_per_user: 0, max_gas_per_user: Gas::from_tgas(300) }
+  }
+}
+
+/// Sponsorship a user has consumed.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct SponsorshipUsage {
+  pub calls: u32,
+  pub gas: Gas,
+  /// Storage deposit paid from the sponsorship pool at registration. Returned to the pool,
+  /// not the user, on unregistering.
+  pub storage: NearToken,
+}
+
+/// One function call action of a NEP-366 delegate action, as the relayer received it.
+#[near(serializers = [json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct DelegateFunctionCall {
+  pub method_name: String,
+  pub gas: Gas,
+  pub deposit: U128,
+}
+
+/// The unsigned part of a NEP-366 `SignedDelegateAction`, restricted to function calls.
+/// The protocol checks the signature, nonce and expiry when the relayer submits it; this
+/// contract checks that it is a call the bank will pay for.
+#[near(serializers = [json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct DelegateActionView {
+  pub sender_id: AccountId,
+  pub receiver_id: AccountId,
+  pub actions: Vec<DelegateFunctionCall>,
+  pub max_block_height: u64,
+}
+
+#[near(serializers = [json])]
+#[derive(Debug, PartialEq)]
+pub struct SponsorshipDecision {
+  pub sponsored: bool,
+  /// Why the action would not be sponsored.
+  pub reason: Option<String>,
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Owner only.
+  pub fn set_sponsorship_config(&mut self, config: SponsorshipConfig) {
+      self.assert_owner();
+      self.sponsorship_config = config;
+  }
+
+  /// Adds the attached NEAR to the pool that pays sponsored registrations. Owner only.
+  #[payable]
+  pub fn fund_sponsorship_pool(&mut self) -> NearToken {
+      self.assert_owner();
+      let amount = env::attached_deposit();
+      assert!(!amount.is_zero(), "Attach NEAR to fund the pool.");
+      self.sponsorship_pool = self.sponsorship_pool.saturating_add(amount);
+      BankEvent::SponsorshipPoolFunded { amount, pool: self.sponsorship_pool }.emit();
+      self.sponsorship_pool
+  }
+
+  /// Registers the caller with its storage deposit paid from the sponsorship pool, so a user
+  /// with no NEAR can open an account. Only through a meta-transaction submitted by a bank
+  /// relayer.
+  pub fn sponsored_register(&mut self) {
+      let account_id = env::predecessor_account_id();
+      assert!(self.internal_meter_sponsorship(&account_id), "Registration must be relayed by a bank relayer.");
+      assert!(!self.storage_accounts.contains_key(&account_id), "Account is already registered.");
+      let deposit = env::storage_byte_cost().saturating_mul(ACCOUNT_STORAGE_BYTES.into());
+      assert!(self.sponsorship_pool >= deposit, "Sponsorship pool is empty.");
+      self.sponsorship_pool = self.sponsorship_pool.saturating_sub(deposit);
+      self.sponsorship_usage.get_mut(&account_id).unwrap().storage = deposit;
+      self.storage_accounts.insert(account_id.clone(), StorageAccount::new(deposit));
+      BankEvent::AccountRegistered { account_id: account_id.clone() }.emit();
+      BankEvent::SponsoredRegistration { account_id, storage: deposit }.emit();
+  }
+
+  /// Checks a delegate action a relayer is about to submit: it must target this contract
+  /// before `max_block_height`, only call sponsored methods with no deposit, and fit in the
+  /// sender's remaining budget.
+  /// View function.
+  pub fn check_delegate_action(&self, delegate_action: DelegateActionView) -> SponsorshipDecision {
+      let reason = self.internal_sponsorship_rejection(&delegate_action);
+      SponsorshipDecision { sponsored: reason.is_none(), reason }
+  }
+
+  /// View function.
+  pub fn get_sponsorship_usage(&self, account_id: AccountId) -> SponsorshipUsage {
+      self.sponsorship_usage.get(&account_id).cloned().unwrap_or_default()
+  }
+
+  /// View function.
+  pub fn get_sponsorship_config(&self) -> SponsorshipConfig {
+      self.sponsorship_config.clone()
+  }
+
+  /// View function.
+  pub fn get_sponsorship_pool(&self) -> NearToken {
+      self.sponsorship_pool
+  }
+}
+
+impl BioCrypticBankCore {
+  fn internal_sponsorship_rejection(&self, action: &DelegateActionView) -> Option<String> {
+      if action.receiver_id != env::current_account_id() {
+          return Some("Delegate action must call this contract.".to_string());
+      }
+      if action.max_block_height <= env::block_height() {
+          return Some("Delegate action has expired.".to_string());
+      }
+      if action.actions.is_empty() {
+          return Some("Delegate action has no calls.".to_string());
+      }
+      if let Some(call) = action.actions.iter().find(|call| !SPONSORED_METHODS.contains(&call.method_name.as_str())) {
+          return Some(format!("Method {} is not sponsored.", call.method_name));
+      }
+      if action.actions.iter().any(|call| call.deposit.0 > 0) {
+          return Some("Sponsored calls cannot attach a deposit.".to_string());
+      }
+      let registered = self.storage_accounts.contains_key(&action.sender_id);
+      for call in &action.actions {
+          if call.method_name == "sponsored_register" && registered {
+              return Some("Account is already registered.".to_string());
+          }
+          if call.method_name != "sponsored_register" && !registered {
+              return Some("Account must register before sponsored payments.".to_string());
+          }
+      }
+      let usage = self.get_sponsorship_usage(action.sender_id.clone());
+      let calls = usage.calls.saturating_add(action.actions.len() as u32);
+      let gas = action.actions.iter().fold(usage.gas, |total, call| total.saturating_add(call.gas));
+      if calls > self.sponsorship_config.max_calls_per_user || gas > self.sponsorship_config.max_gas_per_user {
+          return Some("Sponsorship budget exhausted.".to_string());
+      }
+      None
+  }
+
+  /// If this call arrived as a meta-transaction relayed by a bank relayer on behalf of
+  /// `account_id`, charges it to the user's sponsorship budget, panicking once the budget is
+  /// spent. Returns whether the call is sponsored.
+  pub(crate) fn internal_meter_sponsorship(&mut self, account_id: &AccountId) -> bool {
+      let relayer_id = env::signer_account_id();
+      if relayer_id == *account_id || !self.has_role(relayer_id, Role::Relayer) {
+          return false;
+      }
+      let config = self.sponsorship_config.clone();
+      let usage = self.sponsorship_usage.entry(account_id.clone()).or_default();
+      usage.calls += 1;
+      usage.gas = usage.gas.saturating_add(env::prepaid_gas());
+      assert!(
+          usage.calls <= config.max_calls_per_user && usage.gas <= config.max_gas_per_user,
+          "Sponsorship budget exhausted."
+      );
+      true
+  }
+
+  /// Storage deposit the pool paid for `account_id`, handed back to the pool when the
+  /// account unregisters.
+  pub(crate) fn internal_reclaim_sponsored_storage(&mut self, account_id: &AccountId) -> NearToken {
+      let storage = self.sponsorship_usage.get(account_id).map_or(NearToken::from_yoctonear(0), |usage| usage.storage);
+      self.sponsorship_pool = self.sponsorship_pool.saturating_add(storage);
+      if let Some(usage) = self.sponsorship_usage.get_mut(account_id) {
+          usage.storage = NearToken::from_yoctonear(0);
+      }
+      storage
+  }
+}
@@ -0,0 +1,302 @@
+// services/blockchain/near-rs/core-banking/src/staking.rs
+use near_sdk::{near, env, ext_contract, AccountId, Gas, NearToken, Promise, PromiseError};
+use near_sdk::json_types::U128;
+
+use crate::roles::Role;
+use crate::savings::BPS_DENOMINATOR;
+use crate::{mul_div, BankEvent, BioCrypticBankCore, BioCrypticBankCoreExt, DEFAULT_PAGE_LIMIT, GAS_FOR_WITHDRAW_CALLBACK};
+
+const GAS_FOR_POOL_CALL: Gas = Gas::from_tgas(50);
+const GAS_FOR_POOL_CALLBACK: Gas = Gas::from_tgas(10);
+/// Epochs a staking pool keeps unstaked funds locked.
+const UNSTAKE_LOCK_EPOCHS: u64 = 4;
+
+#[ext_contract(ext_staking_pool)]
+#[allow(dead_code)]
+trait StakingPool {
+  fn deposit_and_stake(&mut self);
+  fn unstake(&mut self, amount: U128);
+  fn withdraw_all(&mut self);
+  fn get_account_staked_balance(&self, account_id: AccountId) -> U128;
+}
+
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct StakingConfig {
+  /// Largest share of total reserves (liquid, staked and unstaking) that may be staked.
+  pub max_staked_bps: u16,
+}
+
+/// The bank's position in one whitelisted staking pool. Rewards are added to `staked` when
+/// synced with the pool.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct PoolPosition {
+  pub staked: NearToken,
+  pub unstaking: NearToken,
+  /// Epoch from which `unstaking` can be withdrawn.
+  pub unlock_epoch: u64,
+  /// Set while a call to the pool is in flight; no other call may start until it resolves.
+  pub busy: bool,
+}
+
+/// A user withdrawal waiting for liquidity. The amount was already debited from the account.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct QueuedWithdrawal {
+  pub id: u64,
+  pub account_id: AccountId,
+  pub amount: NearToken,
+  pub ledger_entry_id: u64,
+  pub queued_at: u64,
+}
+
+#[near(serializers = [json])]
+#[derive(Debug, PartialEq)]
+pub struct ReservesView {
+  /// NEAR held by the contract beyond its storage, the withdrawal queue and the fee treasury.
+  pub liquid: NearToken,
+  pub staked: NearToken,
+  pub unstaking: NearToken,
+  pub queued_withdrawals: NearToken,
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Sets how much of the reserves may be staked. Owner only.
+  pub fn set_staking_config(&mut self, config: StakingConfig) {
+      self.assert_owner();
+      assert!(config.max_staked_bps as u128 <= BPS_DENOMINATOR, "Staked share cannot exceed 100%.");
+      self.staking_config = config;
+  }
+
+  /// Whitelists a validator staking pool. Owner only.
+  pub fn add_staking_pool(&mut self, pool_id: AccountId) {
+      self.assert_owner();
+      assert!(self.staking_pools.get(&pool_id).is_none(), "Pool is already whitelisted.");
+      self.staking_pools.insert(pool_id, PoolPosition::default());
+  }
+
+  /// Removes a staking pool that holds none of the bank's funds. Owner only.
+  pub fn remove_staking_pool(&mut self, pool_id: AccountId) {
+      self.assert_owner();
+      let position = self.staking_pools.get(&pool_id).unwrap_or_else(|| env::panic_str("Pool is not whitelisted."));
+      assert!(position.staked.is_zero() && position.unstaking.is_zero(), "Pool still holds funds.");
+      self.staking_pools.remove(&pool_id);
+  }
+
+  /// Delegates `amount` of liquid reserves to `pool_id`, within the configured staked share.
+  /// Treasury manager only.
+  pub fn stake_to_pool(&mut self, pool_id: AccountId, amount: NearToken) -> Promise {
+      self.assert_role(Role::TreasuryManager);
+      assert!(amount.as_yoctonear() > 0, "Amount must be greater than 0.");
+      let reserves = self.get_reserves();
+      assert!(amount <= reserves.liquid, "Insufficient liquid reserves.");
+      let total = reserves.liquid.saturating_add(reserves.staked).saturating_add(reserves.unstaking);
+      let max_staked = mul_div(total.as_yoctonear(), self.staking_config.max_staked_bps as u128, BPS_DENOMINATOR);
+      assert!(
+          reserves.staked.saturating_add(amount).as_yoctonear() <= max_staked,
+          "Amount exceeds the configured staked share."
+      );
+      let position = self.internal_pool_busy_position_mut(&pool_id);
+      position.staked = position.staked.saturating_add(amount);
+
+      ext_staking_pool::ext(pool_id.clone())
+          .with_attached_deposit(amount)
+          .with_static_gas(GAS_FOR_POOL_CALL)
+          .deposit_and_stake()
+          .then(Self::ext(env::current_account_id()).with_static_gas(GAS_FOR_POOL_CALLBACK).on_pool_staked(pool_id, amount))
+  }
+
+  /// Starts unbonding `amount` from `pool_id`; it can be withdrawn after the pool's lock
+  /// period. Treasury manager only.
+  pub fn unstake_from_pool(&mut self, pool_id: AccountId, amount: NearToken) -> Promise {
+      self.assert_role(Role::TreasuryManager);
+      let position = self.internal_pool_busy_position_mut(&pool_id);
+      assert!(amount.as_yoctonear() > 0 && amount <= position.staked, "Amount exceeds the staked balance.");
+      position.staked = position.staked.saturating_sub(amount);
+      position.unstaking = position.unstaking.saturating_add(amount);
+      position.unlock_epoch = env::epoch_height() + UNSTAKE_LOCK_EPOCHS;
+
+      ext_staking_pool::ext(pool_id.clone())
+          .with_static_gas(GAS_FOR_POOL_CALL)
+          .unstake(U128(amount.as_yoctonear()))
+          .then(Self::ext(env::current_account_id()).with_static_gas(GAS_FOR_POOL_CALLBACK).on_pool_unstaked(pool_id, amount))
+  }
+
+  /// Withdraws unbonded funds from `pool_id` back to liquid reserves. Callable by anyone
+  /// once the lock period has passed, so queued user withdrawals never depend on an admin.
+  pub fn withdraw_from_pool(&mut self, pool_id: AccountId) -> Promise {
+      let position = self.internal_pool_busy_position_mut(&pool_id);
+      let amount = position.unstaking;
+      assert!(!amount.is_zero(), "Nothing is unstaking from this pool.");
+      assert!(env::epoch_height() >= position.unlock_epoch, "Unstaked funds are still locked.");
+      position.unstaking = NearToken::from_yoctonear(0);
+
+      ext_staking_pool::ext(pool_id.clone())
+          .with_static_gas(GAS_FOR_POOL_CALL)
+          .withdraw_all()
+          .then(Self::ext(env::current_account_id()).with_static_gas(GAS_FOR_POOL_CALLBACK).on_pool_withdrawn(pool_id, amount))
+  }
+
+  /// Callback reverting the optimistic stake bookkeeping if the deposit failed.
+  #[private]
+  pub fn on_pool_staked(&mut self, pool_id: AccountId, amount: NearToken, #[callback_result] result: Result<(), PromiseError>) -> bool {
+      let position = self.internal_pool_position_mut(&pool_id);
+      position.busy = false;
+      if result.is_err() {
+          position.staked = position.staked.saturating_sub(amount);
+      }
+      BankEvent::PoolStaked { pool_id, amount, success: result.is_ok() }.emit();
+      result.is_ok()
+  }
+
+  /// Callback reverting the optimistic unstake bookkeeping if the pool rejected it.
+  #[private]
+  pub fn on_pool_unstaked(&mut self, pool_id: AccountId, amount: NearToken, #[callback_result] result: Result<(), PromiseError>) -> bool {
+      let position = self.internal_pool_position_mut(&pool_id);
+      position.busy = false;
+      if result.is_err() {
+          position.staked = position.staked.saturating_add(amount);
+          position.unstaking = position.unstaking.saturating_sub(amount);
+      }
+      BankEvent::PoolUnstaked { pool_id, amount, success: result.is_ok() }.emit();
+      result.is_ok()
+  }
+
+  /// Callback restoring the unstaking balance if the pool withdrawal failed.
+  #[private]
+  pub fn on_pool_withdrawn(&mut self, pool_id: AccountId, amount: NearToken, #[callback_result] result: Result<(), PromiseError>) -> bool {
+      let position = self.internal_pool_position_mut(&pool_id);
+      position.busy = false;
+      if result.is_err() {
+          position.unstaking = position.unstaking.saturating_add(amount);
+      }
+      BankEvent::PoolWithdrawn { pool_id, amount, success: result.is_ok() }.emit();
+      result.is_ok()
+  }
+
+  /// Adds the rewards `pool_id` has earned on the bank's stake to the position and splits them
+  /// between savers and the treasury. Callable by anyone.
+  pub fn sync_pool_rewards(&mut self, pool_id: AccountId) -> Promise {
+      self.internal_pool_busy_position_mut(&pool_id);
+      ext_staking_pool::ext(pool_id.clone())
+          .with_static_gas(GAS_FOR_POOL_CALL)
+          .get_account_staked_balance(env::current_account_id())
+          .then(Self::ext(env::current_account_id()).with_static_gas(GAS_FOR_POOL_CALLBACK).on_pool_balance(pool_id))
+  }
+
+  /// Callback recording staking rewards reported by the pool. Returns the rewards found.
+  #[private]
+  pub fn on_pool_balance(&mut self, pool_id: AccountId, #[callback_result] result: Result<U128, PromiseError>) -> NearToken {
+      let position = self.internal_pool_position_mut(&pool_id);
+      position.busy = false;
+      let rewards = result.map_or(NearToken::from_yoctonear(0), |staked| {
+          NearToken::from_yoctonear(staked.0).saturating_sub(position.staked)
+      });
+      if !rewards.is_zero() {
+          position.staked = position.staked.saturating_add(rewards);
+          self.internal_add_liabilities(rewards);
+          self.internal_collect_revenue(rewards);
+          BankEvent::StakingRewardsSynced { pool_id, rewards }.emit();
+      }
+      rewards
+  }
+
+  /// Pays out up to `limit` queued withdrawals in order while liquid reserves cover them.
+  /// Callable by anyone. Returns the number paid.
+  pub fn process_withdrawal_queue(&mut self, limit: Option<u64>) -> u64 {
+      let mut paid = 0;
+      while paid < limit.unwrap_or(DEFAULT_PAGE_LIMIT) {
+          let Some(queued) = self.queued_withdrawals.get(&self.withdrawal_queue_head).cloned() else { break };
+          if self.get_reserves().liquid < queued.amount {
+              break;
+          }
+          let initial_storage = env::storage_usage();
+          self.queued_withdrawals.remove(&queued.id);
+          self.withdrawal_queue_head += 1;
+          self.queued_withdrawals_total = self.queued_withdrawals_total.saturating_sub(queued.amount);
+          self.internal_remove_liabilities(queued.amount);
+          self.internal_charge_storage(&queued.account_id, initial_storage);
+
+          Promise::new(queued.account_id.clone()).transfer(queued.amount).then(
+              Self::ext(env::current_account_id())
+                  .with_static_gas(GAS_FOR_WITHDRAW_CALLBACK)
+                  .withdraw_callback(queued.account_id.clone(), queued.amount, queued.ledger_entry_id, None)
+          );
+          BankEvent::QueuedWithdrawalPaid { id: queued.id, account_id: queued.account_id, amount: queued.amount }.emit();
+          paid += 1;
+      }
+      paid
+  }
+
+  /// Liquid, staked and unbonding reserves, and the NEAR owed to queued withdrawals.
+  /// View function.
+  pub fn get_reserves(&self) -> ReservesView {
+      let (staked, unstaking) = self.staking_pools.values().fold(
+          (NearToken::from_yoctonear(0), NearToken::from_yoctonear(0)),
+          |(staked, unstaking), position| (staked.saturating_add(position.staked), unstaking.saturating_add(position.unstaking)),
+      );
+      let storage_cost = env::storage_byte_cost().saturating_mul(env::storage_usage().into());
+      ReservesView {
+          liquid: env::account_balance()
+              .saturating_sub(storage_cost)
+              .saturating_sub(self.queued_withdrawals_total)
+              .saturating_sub(self.treasury_balance)
+              .saturating_sub(self.insurance_fund)
+              .saturating_sub(self.sponsorship_pool),
+          staked,
+          unstaking,
+          queued_withdrawals: self.queued_withdrawals_total,
+      }
+  }
+
+  /// View function.
+  pub fn get_staking_pools(&self) -> Vec<(AccountId, PoolPosition)> {
+      self.staking_pools.iter().map(|(pool_id, position)| (pool_id.clone(), position.clone())).collect()
+  }
+
+  /// View function.
+  pub fn get_staking_config(&self) -> StakingConfig {
+      self.staking_config.clone()
+  }
+
+  /// Lists queued withdrawals in payout order.
+  /// View function.
+  pub fn get_queued_withdrawals(&self, from_index: Option<u64>, limit: Option<u64>) -> Vec<QueuedWithdrawal> {
+      (self.withdrawal_queue_head + from_index.unwrap_or(0)..self.next_queued_withdrawal_id)
+          .take(limit.unwrap_or(DEFAULT_PAGE_LIMIT) as usize)
+          .filter_map(|id| self.queued_withdrawals.get(&id).cloned())
+          .collect()
+  }
+}
+
+impl BioCrypticBankCore {
+  fn internal_pool_position_mut(&mut self, pool_id: &AccountId) -> &mut PoolPosition {
+      self.staking_pools.get_mut(pool_id).unwrap_or_else(|| env::panic_str("Pool is not whitelisted."))
+  }
+
+  /// Marks `pool_id` busy for an outgoing call, panicking if another call is in flight.
+  fn internal_pool_busy_position_mut(&mut self, pool_id: &AccountId) -> &mut PoolPosition {
+      let position = self.internal_pool_position_mut(pool_id);
+      assert!(!position.busy, "Another call to this pool is in flight.");
+      position.busy = true;
+      position
+  }
+
+  /// Queues an already debited withdrawal until liquid reserves can cover it.
+  pub(crate) fn internal_queue_withdrawal(&mut self, account_id: &AccountId, amount: NearToken, ledger_entry_id: u64) -> u64 {
+      let id = self.next_queued_withdrawal_id;
+      self.next_queued_withdrawal_id += 1;
+      self.queued_withdrawals.insert(id, QueuedWithdrawal {
+          id,
+          account_id: account_id.clone(),
+          amount,
+          ledger_entry_id,
+          queued_at: env::block_timestamp(),
+      });
+      self.queued_withdrawals_total = self.queued_withdrawals_total.saturating_add(amount);
+      BankEvent::WithdrawalQueued { id, account_id: account_id.clone(), amount }.emit();
+      id
+  }
+}
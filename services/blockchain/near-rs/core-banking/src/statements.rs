@@ -0,0 +1,126 @@
+// services/blockchain/near-rs/core-banking/src/statements.rs
+use near_sdk::{near, env, AccountId, NearToken};
+
+use crate::ledger::{EntryDirection, EntryKind, LedgerEntry};
+use crate::{BioCrypticBankCore, BioCrypticBankCoreExt, DEFAULT_PAGE_LIMIT};
+
+/// Credits and debits of one ledger category within a statement period.
+#[near(serializers = [json])]
+#[derive(Debug, PartialEq)]
+pub struct CategoryTotal {
+  pub kind: EntryKind,
+  pub credits: NearToken,
+  pub debits: NearToken,
+  pub count: u64,
+}
+
+#[near(serializers = [json])]
+#[derive(Debug, PartialEq)]
+pub struct Statement {
+  pub account_id: AccountId,
+  pub period_start: u64,
+  pub period_end: u64,
+  /// Balance after the last entry before `period_start`.
+  pub opening_balance: NearToken,
+  /// Balance after the last entry of the period.
+  pub closing_balance: NearToken,
+  pub totals: Vec<CategoryTotal>,
+  /// Number of entries in the period; `transactions` holds one page of them.
+  pub transaction_count: u64,
+  pub transactions: Vec<LedgerEntry>,
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Builds `account_id`'s statement for entries timestamped in `[period_start, period_end)`
+  /// (nanoseconds): opening and closing balances, totals by category over the whole period
+  /// and one page of its entries, oldest first. `from_index` counts from the period's first entry.
+  /// View function.
+  pub fn get_statement(
+      &self,
+      account_id: AccountId,
+      period_start: u64,
+      period_end: u64,
+      from_index: Option<u64>,
+      limit: Option<u64>,
+  ) -> Statement {
+      assert!(period_start < period_end, "Statement period must end after it starts.");
+      let empty = Statement {
+          account_id: account_id.clone(),
+          period_start,
+          period_end,
+          opening_balance: NearToken::from_yoctonear(0),
+          closing_balance: NearToken::from_yoctonear(0),
+          totals: Vec::new(),
+          transaction_count: 0,
+          transactions: Vec::new(),
+      };
+      if self.internal_ledger_len(&account_id) == 0 {
+          return empty;
+      }
+
+      let first = self.internal_first_entry_at(&account_id, period_start);
+      let end = self.internal_first_entry_at(&account_id, period_end);
+      let opening_balance = first.checked_sub(1)
+          .map_or(NearToken::from_yoctonear(0), |index| self.internal_entry_at(&account_id, index).balance_after);
+      let closing_balance = end.checked_sub(1)
+          .map_or(NearToken::from_yoctonear(0), |index| self.internal_entry_at(&account_id, index).balance_after);
+
+      let mut totals: Vec<CategoryTotal> = Vec::new();
+      for index in first..end {
+          let entry = self.internal_entry_at(&account_id, index);
+          let total = match totals.iter_mut().position(|total| total.kind == entry.kind) {
+              Some(position) => &mut totals[position],
+              None => {
+                  totals.push(CategoryTotal {
+                      kind: entry.kind,
+                      credits: NearToken::from_yoctonear(0),
+                      debits: NearToken::from_yoctonear(0),
+                      count: 0,
+                  });
+                  totals.last_mut().unwrap()
+              }
+          };
+          match entry.direction {
+              EntryDirection::Credit => total.credits = total.credits.saturating_add(entry.amount),
+              EntryDirection::Debit => total.debits = total.debits.saturating_add(entry.amount),
+          }
+          total.count += 1;
+      }
+
+      let transactions = (first.saturating_add(from_index.unwrap_or(0))..end)
+          .take(limit.unwrap_or(DEFAULT_PAGE_LIMIT) as usize)
+          .map(|index| self.internal_entry_at(&account_id, index).clone())
+          .collect();
+      Statement {
+          opening_balance,
+          closing_balance,
+          totals,
+          transaction_count: end - first,
+          transactions,
+          ..empty
+      }
+  }
+}
+
+impl BioCrypticBankCore {
+  fn internal_entry_at(&self, account_id: &AccountId, index: u64) -> &LedgerEntry {
+      self.internal_ledger_entry_at(account_id, index)
+          .unwrap_or_else(|| env::panic_str("Ledger index is inconsistent."))
+  }
+
+  /// Index of the first entry timestamped at or after `timestamp`. Entries are appended in
+  /// chronological order, so this is a binary search.
+  fn internal_first_entry_at(&self, account_id: &AccountId, timestamp: u64) -> u64 {
+      let (mut low, mut high) = (0, self.internal_ledger_len(account_id));
+      while low < high {
+          let mid = low + (high - low) / 2;
+          if self.internal_entry_at(account_id, mid).timestamp < timestamp {
+              low = mid + 1;
+          } else {
+              high = mid;
+          }
+      }
+      low
+  }
+}
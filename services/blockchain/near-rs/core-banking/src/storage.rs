@@ -0,0 +1,477 @@
+// services/blockchain/near-rs/core-banking/src/storage.rs
+use near_sdk::{near, env, AccountId, NearToken, Promise};
+use near_sdk::json_types::U128;
+
+use crate::{BankEvent, BioCrypticBankCore, BioCrypticBankCoreExt};
+
+/// Bytes the minimum storage deposit pays for, covering an account's balance entry and the
+/// ledger records of its first deposit and transfer. They stay reserved until the account
+/// unregisters.
+pub(crate) const ACCOUNT_STORAGE_BYTES: u64 = 2_000;
+
+/// Upper bound on the bytes a single deposit writes: a new balance entry and ledger shard,
+/// and a ledger record with the longest account ids, memo and reference.
+pub(crate) const MAX_DEPOSIT_STORAGE_BYTES: u64 = 1_500;
+
+/// NEP-145 storage balance.
+#[near(serializers = [json])]
+#[derive(Debug, PartialEq)]
+pub struct StorageBalance {
+  pub total: U128,
+  pub available: U128,
+}
+
+/// NEP-145 storage balance bounds.
+#[near(serializers = [json])]
+#[derive(Debug, PartialEq)]
+pub struct StorageBalanceBounds {
+  pub min: U128,
+  pub max: Option<U128>,
+}
+
+/// Storage paid for by an account and the bytes its activity has consumed so far.
+#[near(serializers = [borsh])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct StorageAccount {
+  pub deposit: NearToken,
+  pub used_bytes: u64,
+}
+
+impl StorageAccount {
+  /// A fresh registration that has written nothing yet.
+  pub(crate) fn new(deposit: NearToken) -> Self {
+      Self { deposit, used_bytes: 0 }
+  }
+
+  fn used_cost(&self) -> NearToken {
+      env::storage_byte_cost().saturating_mul(self.used_bytes.into())
+  }
+
+  /// Whether the deposit still pays for the bytes used once `extra_bytes` more are written.
+  pub(crate) fn covers(&self, extra_bytes: u64) -> bool {
+      let needed = env::storage_byte_cost().saturating_mul(self.used_bytes.saturating_add(extra_bytes).into());
+      self.deposit >= needed
+  }
+
+  /// Deposit above both the bytes used and the reserve for `ACCOUNT_STORAGE_BYTES`.
+  fn available(&self) -> NearToken {
+      let reserved = env::storage_byte_cost().saturating_mul(self.used_bytes.max(ACCOUNT_STORAGE_BYTES).into());
+      self.deposit.saturating_sub(reserved)
+  }
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// NEP-145: registers `account_id` (default: caller) or tops up its storage deposit.
+  /// With `registration_only`, anything above the minimum is refunded.
+  #[payable]
+  pub fn storage_deposit(&mut self, account_id: Option<AccountId>, registration_only: Option<bool>) -> StorageBalance {
+      let account_id = account_id.unwrap_or_else(env::predecessor_account_id);
+      let attached = env::attached_deposit();
+      let min = self.storage_minimum_balance();
+
+      let refund = match self.storage_accounts.get_mut(&account_id) {
+          Some(_) if registration_only.unwrap_or(false) => attached,
+          Some(storage) => {
+              storage.deposit = storage.deposit.saturating_add(attached);
+              NearToken::from_yoctonear(0)
+          }
+          None => {
+              assert!(attached >= min, "Attached deposit is below the minimum storage balance of {} yoctoNEAR.", min.as_yoctonear());
+              let (deposit, refund) = if registration_only.unwrap_or(false) {
+                  (min, attached.saturating_sub(min))
+              } else {
+                  (attached, NearToken::from_yoctonear(0))
+              };
+              self.storage_accounts.insert(account_id.clone(), StorageAccount::new(deposit));
+              BankEvent::AccountRegistered { account_id: account_id.clone() }.emit();
+              refund
+          }
+      };
+      self.internal_add_liabilities(attached.saturating_sub(refund));
+      self.internal_record_contract_inflow(attached.saturating_sub(refund));
+      if !refund.is_zero() {
+          Promise::new(env::predecessor_account_id()).transfer(refund);
+      }
+      self.storage_balance_of(account_id).unwrap()
+  }
+
+  /// NEP-145: withdraws up to `amount` (default: all) of the caller's unused storage deposit.
+  #[payable]
+  pub fn storage_withdraw(&mut self, amount: Option<U128>) -> StorageBalance {
+      assert_one_yocto();
+      let account_id = env::predecessor_account_id();
+      let storage = self.storage_accounts.get_mut(&account_id)
+          .unwrap_or_else(|| env::panic_str("Account is not registered."));
+      let available = storage.available();
+      let amount = amount.map_or(available, |amount| NearToken::from_yoctonear(amount.0));
+      assert!(amount <= available, "Amount exceeds the available storage balance.");
+      storage.deposit = storage.deposit.saturating_sub(amount);
+      self.internal_remove_liabilities(amount);
+      if !amount.is_zero() {
+          Promise::new(account_id.clone()).transfer(amount);
+      }
+      self.storage_balance_of(account_id).unwrap()
+  }
+
+  /// NEP-145: closes the caller's registration and refunds the full storage deposit. The
+  /// account's NEAR balance must be empty and it must hold no savings, term deposits,
+  /// standing orders, overdraft or loans; its ledger history, loyalty points and inheritance plan are deleted. Token balances are kept and become
+  /// accessible again after re-registering. A deposit paid by the sponsorship pool goes back
+  /// to the pool. `force` does not override these checks, so no
+  /// funds are ever burned. Returns true if the account was unregistered.
+  #[payable]
+  #[allow(unused_variables)]
+  pub fn storage_unregister(&mut self, force: Option<bool>) -> bool {
+      assert_one_yocto();
+      let account_id = env::predecessor_account_id();
+      let Some(storage) = self.storage_accounts.get(&account_id).cloned() else {
+          return false;
+      };
+      assert!(self.get_balance(account_id.clone()).is_zero(), "Withdraw your balance before unregistering.");
+      let refund = self.internal_close_registration(&account_id, storage);
+      if !refund.is_zero() {
+          Promise::new(account_id.clone()).transfer(refund);
+      }
+      BankEvent::AccountUnregistered { account_id }.emit();
+      true
+  }
+
+  /// NEP-145: minimum deposit required to register; there is no maximum.
+  pub fn storage_balance_bounds(&self) -> StorageBalanceBounds {
+      StorageBalanceBounds { min: U128(self.storage_minimum_balance().as_yoctonear()), max: None }
+  }
+
+  /// NEP-145: storage balance of `account_id`, or `None` if not registered.
+  pub fn storage_balance_of(&self, account_id: AccountId) -> Option<StorageBalance> {
+      self.storage_accounts.get(&account_id).map(|storage| StorageBalance {
+          total: U128(storage.deposit.as_yoctonear()),
+          available: U128(storage.available().as_yoctonear()),
+      })
+  }
+}
+
+impl BioCrypticBankCore {
+  fn storage_minimum_balance(&self) -> NearToken {
+      env::storage_byte_cost().saturating_mul(ACCOUNT_STORAGE_BYTES.into())
+  }
+
+  /// Checks `account_id` holds nothing but its empty balance, deletes its per-account state
+  /// and ledger history and ends its registration. Returns the storage deposit owed back,
+  /// which is no longer counted as a liability.
+  pub(crate) fn internal_close_registration(&mut self, account_id: &AccountId, storage: StorageAccount) -> NearToken {
+      assert!(self.savings_accounts.get(account_id).is_none(), "Withdraw your savings before unregistering.");
+      assert!(
+          self.prize_savers.get(account_id).is_none_or(|saver| saver.principal.is_zero()),
+          "Exit prize savings before unregistering."
+      );
+      assert!(self.term_deposits_by_account.get(account_id).is_none(), "Redeem your term deposits before unregistering.");
+      assert!(self.savings_goals.get(account_id).is_none(), "Delete your savings goals before unregistering.");
+      assert!(self.standing_orders_by_account.get(account_id).is_none(), "Cancel your standing orders before unregistering.");
+      assert!(self.subscriptions_by_account.get(account_id).is_none(), "Cancel your subscriptions before unregistering.");
+      assert!(self.guarded_actions_by_account.get(account_id).is_none(), "Cancel your operations awaiting guardian approval before unregistering.");
+      assert!(self.timelocked_withdrawals_by_account.get(account_id).is_none(), "Cancel your timelocked withdrawals before unregistering.");
+      assert!(self.pending_transfers_by_account.get(account_id).is_none(), "Cancel your pending transfers before unregistering.");
+      assert!(self.debit_cards_by_account.get(account_id).is_none(), "Revoke your debit cards before unregistering.");
+      assert!(self.operation_locks.get(account_id).is_none(), "Wait for your in-flight operation to settle before unregistering.");
+      assert!(self.overdrafts.get(account_id).is_none(), "Close your overdraft before unregistering.");
+      assert!(self.loans_by_account.get(account_id).is_none(), "Repay your loans before unregistering.");
+      assert!(self.nft_credit_lines_by_account.get(account_id).is_none(), "Close your NFT credit lines before unregistering.");
+      assert!(self.guarantees_by_guarantor.get(account_id).is_none(), "Wait for the loans you guarantee to be repaid before unregistering.");
+      assert!(self.escheated_balances.get(account_id).is_none(), "Reclaim your escheated funds before unregistering.");
+      assert!(
+          self.get_claimable_transfers_for_account(account_id.clone()).iter().all(|transfer| transfer.sender_id != *account_id),
+          "Wait for your claimable transfers to be claimed or refunded before unregistering."
+      );
+      assert!(self.gifts_by_sender.get(account_id).is_none(), "Wait for your gifts to be claimed or refunded before unregistering.");
+      assert!(self.open_insurance_claims.get(account_id).is_none(), "Wait for your insurance claims to be settled before unregistering.");
+      assert!(
+          self.merchants.get(account_id).is_none_or(|merchant| merchant.pending_settlement.is_zero() && merchant.held_balance.is_zero()),
+          "Settle your merchant balance before unregistering."
+      );
+
+      self.internal_remove_account(account_id);
+      self.spending_limits.remove(account_id);
+      self.envelopes.remove(account_id);
+      self.loyalty_accounts.remove(account_id);
+      self.inheritance_plans.remove(account_id);
+      self.last_activity.remove(account_id);
+      self.dormancy_statuses.remove(account_id);
+      self.monitoring_activity.remove(account_id);
+      self.guardian_policies.remove(account_id);
+      self.address_books.remove(account_id);
+      self.trusted_recipients.remove(account_id);
+      self.round_up_settings.remove(account_id);
+      self.round_up_stats.remove(account_id);
+      self.transfer_delays.remove(account_id);
+      self.profiles.remove(account_id);
+      self.contacts.remove(account_id);
+      self.internal_remove_empty_prize_saver(account_id);
+      self.credit_records.remove(account_id);
+      self.internal_remove_spending_analytics(account_id);
+      self.internal_remove_donations(account_id);
+      self.internal_remove_ledger(account_id);
+      self.storage_accounts.remove(account_id);
+      let refund = storage.deposit.saturating_sub(self.internal_reclaim_sponsored_storage(account_id));
+      self.internal_remove_liabilities(refund);
+      refund
+  }
+
+  /// Registers an account carried over from the pre-versioning layout, which never paid for
+  /// storage, funding the minimum storage deposit from its balance. The amount stays a
+  /// liability, moving from the balance to the storage deposit. A balance below the minimum
+  /// all goes to the deposit, which `storage_unregister` refunds.
+  pub(crate) fn internal_register_legacy_account(&mut self, account_id: &AccountId) {
+      if self.storage_accounts.contains_key(account_id) || !self.legacy_balances.contains_key(account_id) {
+          return;
+      }
+      let balance = self.get_balance(account_id.clone());
+      let deposit = self.storage_minimum_balance().min(balance);
+      self.internal_set_balance(account_id, balance.saturating_sub(deposit));
+      self.storage_accounts.insert(account_id.clone(), StorageAccount::new(deposit));
+      BankEvent::AccountRegistered { account_id: account_id.clone() }.emit();
+  }
+
+  /// Panics unless `account_id` is registered. An account still on the pre-versioning
+  /// layout is registered first.
+  pub(crate) fn assert_registered(&mut self, account_id: &AccountId) {
+      self.internal_register_legacy_account(account_id);
+      assert!(self.storage_accounts.contains_key(account_id), "Account {} is not registered.", account_id);
+  }
+
+  /// Charges the storage growth since `initial_storage` to `account_id`'s storage deposit,
+  /// panicking if the deposit does not cover it. Shrinkage is credited back.
+  pub(crate) fn internal_charge_storage(&mut self, account_id: &AccountId, initial_storage: u64) {
+      self.internal_flush_collections();
+      let current = env::storage_usage();
+      let storage = self.storage_accounts.get_mut(account_id)
+          .unwrap_or_else(|| env::panic_str("Account is not registered."));
+      if current >= initial_storage {
+          storage.used_bytes += current - initial_storage;
+      } else {
+          storage.used_bytes = storage.used_bytes.saturating_sub(initial_storage - current);
+      }
+      assert!(
+          storage.deposit >= storage.used_cost(),
+          "Insufficient storage deposit. Call storage_deposit to add at least {} yoctoNEAR.",
+          storage.used_cost().saturating_sub(storage.deposit).as_yoctonear()
+      );
+  }
+
+  /// Writes every cached collection entry to storage so `env::storage_usage()` is accurate.
+  fn internal_flush_collections(&mut self) {
+      self.balances.flush();
+      self.token_whitelist.flush();
+      self.ft_balances.flush();
+      self.savings_accounts.flush();
+      self.term_rates.flush();
+      self.term_deposits.flush();
+      self.term_deposits_by_account.flush();
+      self.standing_orders.flush();
+      self.standing_orders_by_account.flush();
+      self.ledger_entries.flush();
+      self.ledger_lengths.flush();
+      self.ledger_shards.flush();
+      self.invoices.flush();
+      self.pending_invoices.flush();
+      self.joint_accounts.flush();
+      self.joint_accounts_by_owner.flush();
+      self.joint_proposals.flush();
+      self.roles.flush();
+      self.spending_limits.flush();
+      self.storage_accounts.flush();
+      self.donation_rules.flush();
+      self.donation_records.flush();
+      self.donation_years.flush();
+      self.category_rules.flush();
+      self.entry_categories.flush();
+      self.category_spending.flush();
+      self.spending_months.flush();
+      self.authorization_nonces.flush();
+      self.operation_locks.flush();
+      self.prize_savers.flush();
+      self.debit_cards.flush();
+      self.debit_cards_by_account.flush();
+      self.sponsorship_usage.flush();
+      self.profiles.flush();
+      self.contacts.flush();
+      self.ledger_blocks.flush();
+      self.ledger_chain_links.flush();
+      self.transfer_delays.flush();
+      self.pending_transfers.flush();
+      self.pending_transfers_by_account.flush();
+      self.claimable_transfers.flush();
+      self.claimable_transfers_by_account.flush();
+      self.legacy_balances.flush();
+      self.recovery_holds.flush();
+      self.nft_collections.flush();
+      self.nft_appraisals.flush();
+      self.nft_credit_lines.flush();
+      self.nft_credit_lines_by_account.flush();
+      self.loan_guarantees.flush();
+      self.guarantees_by_guarantor.flush();
+      self.credit_records.flush();
+      self.insurance_claims.flush();
+      self.open_insurance_claims.flush();
+      self.round_up_settings.flush();
+      self.round_up_stats.flush();
+      self.savings_goals.flush();
+      self.dex_pools.flush();
+      self.dex_revenue.flush();
+      self.reference_currencies.flush();
+      self.multisig_admins.flush();
+      self.withdrawal_proposals.flush();
+      self.multisig_changes.flush();
+      self.timelocked_withdrawals.flush();
+      self.timelocked_withdrawals_by_account.flush();
+      self.address_books.flush();
+      self.trusted_recipients.flush();
+      self.guardian_policies.flush();
+      self.guarded_actions.flush();
+      self.guarded_actions_by_account.flush();
+      self.monitoring_activity.flush();
+      self.monitoring_overrides.flush();
+      self.merchant_held_orders.flush();
+      self.subscription_plans.flush();
+      self.subscriptions.flush();
+      self.subscriptions_by_account.flush();
+      self.payment_intents.flush();
+      self.merchants.flush();
+      self.merchant_approvals.flush();
+      self.merchant_orders.flush();
+      self.merchant_order_ids.flush();
+      self.merchant_settlements.flush();
+      self.gifts.flush();
+      self.gifts_by_sender.flush();
+      self.dormancy_statuses.flush();
+      self.escheated_balances.flush();
+      self.inheritance_plans.flush();
+      self.last_activity.flush();
+      self.payments_by_reference.flush();
+      self.kyc_cache.flush();
+      self.kyc_usage.flush();
+      self.loyalty_accounts.flush();
+      self.referrers.flush();
+      self.referrer_stats.flush();
+      self.staking_pools.flush();
+      self.queued_withdrawals.flush();
+      self.prices.flush();
+      self.collateral_configs.flush();
+      self.loans.flush();
+      self.loans_by_account.flush();
+      self.overdrafts.flush();
+      self.kyc_tiers.flush();
+      self.envelopes.flush();
+      self.mandates.flush();
+      self.mandates_by_payer.flush();
+      self.mandates_by_merchant.flush();
+      self.allowances.flush();
+      self.spenders_by_owner.flush();
+      self.streams.flush();
+      self.outgoing_streams.flush();
+      self.incoming_streams.flush();
+      self.escrows.flush();
+      self.open_escrows_by_account.flush();
+      self.closed_accounts.flush();
+      self.token_liabilities.flush();
+      self.token_reserves.flush();
+      self.crank_tasks.flush();
+      self.charities.flush();
+      self.prize_draws.flush();
+  }
+}
+
+fn assert_one_yocto() {
+  assert_eq!(env::attached_deposit(), NearToken::from_yoctonear(1), "Requires attached deposit of exactly 1 yoctoNEAR.");
+}
+
+#[cfg(test)]
+mod tests {
+  use near_sdk::test_utils::{accounts, VMContextBuilder};
+  use near_sdk::testing_env;
+
+  use super::*;
+  use crate::AssetId;
+
+  fn call_with_deposit(attached: NearToken) {
+      testing_env!(VMContextBuilder::new()
+          .predecessor_account_id(accounts(1))
+          .attached_deposit(attached)
+          .build());
+  }
+
+  /// Runs `flow` and checks the bytes charged to `accounts(1)` equal the collection storage
+  /// it wrote. Dropping the contract flushes every collection, listed or not; the root state
+  /// is not charged to accounts, so its change in size is left out.
+  fn assert_charges_storage_growth(contract: BioCrypticBankCore, flow: impl FnOnce(&mut BioCrypticBankCore)) -> BioCrypticBankCore {
+      let initial_root = near_sdk::borsh::to_vec(&contract).unwrap().len() as u64;
+      drop(contract);
+      let initial_usage = env::storage_usage();
+      let mut contract: BioCrypticBankCore = env::state_read().unwrap();
+      let initial_used = contract.storage_accounts.get(&accounts(1)).unwrap().used_bytes;
+      flow(&mut contract);
+      let charged = contract.storage_accounts.get(&accounts(1)).unwrap().used_bytes - initial_used;
+
+      let root = near_sdk::borsh::to_vec(&contract).unwrap().len() as u64;
+      env::state_write(&contract);
+      drop(contract);
+      assert_eq!(charged, env::storage_usage() - initial_usage - (root - initial_root));
+      env::state_read().unwrap()
+  }
+
+  #[test]
+  fn accounts_registered_at_the_minimum_can_deposit_and_transfer() {
+      let min = NearToken::from_yoctonear(BioCrypticBankCore::new(accounts(0)).storage_balance_bounds().min.0);
+      call_with_deposit(min.saturating_add(NearToken::from_near(1)));
+      let mut contract = BioCrypticBankCore::new(accounts(0));
+      let registered = contract.storage_deposit(None, Some(true));
+      assert_eq!(registered.total.0, min.as_yoctonear());
+      assert_eq!(registered.available.0, 0);
+
+      contract.storage_deposit(Some(accounts(2)), Some(true));
+
+      call_with_deposit(NearToken::from_near(1));
+      contract.deposit(None, None);
+      assert_eq!(contract.get_balance(accounts(1)), NearToken::from_near(1));
+      assert_eq!(contract.storage_balance_of(accounts(1)).unwrap().available.0, 0);
+
+      call_with_deposit(NearToken::from_yoctonear(0));
+      contract.transfer(accounts(2), NearToken::from_millinear(1), None, None, None);
+      assert_eq!(contract.get_balance(accounts(2)), NearToken::from_millinear(1));
+  }
+
+  #[test]
+  fn deposits_stay_within_the_deposit_storage_bound() {
+      let account_id: AccountId = "b".repeat(64).parse().unwrap();
+      let funder_id: AccountId = "f".repeat(64).parse().unwrap();
+      testing_env!(VMContextBuilder::new()
+          .predecessor_account_id(account_id.clone())
+          .attached_deposit(NearToken::from_near(1))
+          .build());
+      let mut contract = BioCrypticBankCore::new(accounts(0));
+      contract.storage_deposit(None, None);
+      contract.internal_deposit(
+          &account_id,
+          Some(&funder_id),
+          NearToken::from_near(1),
+          Some("m".repeat(crate::MAX_MEMO_LENGTH)),
+          Some("r".repeat(crate::MAX_REFERENCE_LENGTH)),
+      );
+      assert!(contract.storage_accounts.get(&account_id).unwrap().used_bytes <= MAX_DEPOSIT_STORAGE_BYTES);
+  }
+
+  #[test]
+  fn deposits_are_charged_the_storage_they_write() {
+      call_with_deposit(NearToken::from_near(1));
+      let mut contract = BioCrypticBankCore::new(accounts(0));
+      contract.storage_deposit(None, None);
+      env::state_write(&contract);
+
+      let contract = assert_charges_storage_growth(contract, |contract| {
+          contract.deposit(Some("salary".to_string()), None);
+      });
+      assert_charges_storage_growth(contract, |contract| {
+          contract.internal_deposit_asset(&accounts(1), &AssetId::Ft(accounts(2)), 1_000);
+      });
+  }
+}
@@ -0,0 +1,189 @@
+// services/blockchain/near-rs/core-banking/src/streams.rs
+use near_sdk::{near, env, AccountId, NearToken};
+
+use crate::ledger::{EntryDirection, EntryKind};
+use crate::pause::Feature;
+use crate::{mul_div, BankEvent, BioCrypticBankCore, BioCrypticBankCoreExt};
+
+const NANOS_PER_SECOND: u128 = 1_000_000_000;
+const MAX_STREAMS_PER_ACCOUNT: usize = 50;
+
+/// A continuous payment vesting linearly from `start_at` to `end_at` at `rate_per_second`.
+/// The full `deposit` is locked up front, so vesting is computed in O(1) from the clock.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct Stream {
+  pub id: u64,
+  pub sender_id: AccountId,
+  pub receiver_id: AccountId,
+  /// yoctoNEAR vested per second.
+  pub rate_per_second: NearToken,
+  pub deposit: NearToken,
+  pub start_at: u64,
+  pub end_at: u64,
+  pub withdrawn: NearToken,
+}
+
+impl Stream {
+  fn vested_at(&self, now: u64) -> NearToken {
+      let elapsed = now.clamp(self.start_at, self.end_at) - self.start_at;
+      let vested = mul_div(self.rate_per_second.as_yoctonear(), elapsed as u128, NANOS_PER_SECOND);
+      NearToken::from_yoctonear(vested.min(self.deposit.as_yoctonear()))
+  }
+
+  fn withdrawable_at(&self, now: u64) -> NearToken {
+      self.vested_at(now).saturating_sub(self.withdrawn)
+  }
+}
+
+#[near(serializers = [json])]
+#[derive(Debug, PartialEq)]
+pub struct StreamView {
+  pub stream: Stream,
+  pub vested: NearToken,
+  pub withdrawable: NearToken,
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Streams `rate_per_second` yoctoNEAR to `receiver_id` from `start_at` (ns, default now)
+  /// until `end_at` (ns). The whole amount is debited from the caller up front. Returns the stream id.
+  pub fn create_stream(&mut self, receiver_id: AccountId, rate_per_second: NearToken, start_at: Option<u64>, end_at: u64) -> u64 {
+      self.assert_not_paused(Feature::Transfers);
+      let initial_storage = env::storage_usage();
+      let sender_id = env::predecessor_account_id();
+      assert!(sender_id != receiver_id, "Cannot stream to self.");
+      self.assert_no_recovery_hold(&sender_id);
+      assert!(rate_per_second.as_yoctonear() > 0, "Rate must be greater than 0.");
+      let now = env::block_timestamp();
+      let start_at = start_at.unwrap_or(now).max(now);
+      assert!(end_at > start_at, "Stream must end after it starts.");
+      self.assert_registered(&receiver_id);
+      self.assert_trusted_destination(&sender_id, &receiver_id);
+
+      let deposit = NearToken::from_yoctonear(mul_div(
+          rate_per_second.as_yoctonear(), (end_at - start_at) as u128, NANOS_PER_SECOND,
+      ));
+      assert!(!deposit.is_zero(), "Stream amount must be greater than 0.");
+      let id = self.next_stream_id;
+      self.next_stream_id += 1;
+
+      self.internal_record_outflow(&sender_id, deposit);
+      self.internal_debit(&sender_id, deposit);
+      self.internal_record_entry(&sender_id, EntryKind::Stream, EntryDirection::Debit, deposit, None, Some(format!("stream:{}", id)));
+
+      let outgoing = self.outgoing_streams.entry(sender_id.clone()).or_default();
+      assert!(outgoing.len() < MAX_STREAMS_PER_ACCOUNT, "Too many outgoing streams.");
+      outgoing.push(id);
+      let incoming = self.incoming_streams.entry(receiver_id.clone()).or_default();
+      assert!(incoming.len() < MAX_STREAMS_PER_ACCOUNT, "Receiver has too many incoming streams.");
+      incoming.push(id);
+
+      self.streams.insert(id, Stream {
+          id,
+          sender_id: sender_id.clone(),
+          receiver_id: receiver_id.clone(),
+          rate_per_second,
+          deposit,
+          start_at,
+          end_at,
+          withdrawn: NearToken::from_yoctonear(0),
+      });
+      self.internal_charge_storage(&sender_id, initial_storage);
+
+      BankEvent::StreamCreated { id, sender_id, receiver_id, rate_per_second, deposit, start_at, end_at }.emit();
+      id
+  }
+
+  /// Moves the vested, unwithdrawn part of a stream to the receiver's balance.
+  /// `amount`: Optional cap; defaults to everything withdrawable. Returns the amount moved.
+  pub fn withdraw_from_stream(&mut self, stream_id: u64, amount: Option<NearToken>) -> NearToken {
+      let now = env::block_timestamp();
+      let stream = self.streams.get_mut(&stream_id)
+          .unwrap_or_else(|| env::panic_str("Stream not found."));
+      assert_eq!(stream.receiver_id, env::predecessor_account_id(), "Only the receiver can withdraw from the stream.");
+      let withdrawable = stream.withdrawable_at(now);
+      let amount = amount.unwrap_or(withdrawable);
+      assert!(!amount.is_zero(), "Nothing to withdraw.");
+      assert!(amount <= withdrawable, "Amount exceeds the withdrawable balance.");
+      stream.withdrawn = stream.withdrawn.saturating_add(amount);
+      let stream = stream.clone();
+
+      self.internal_credit(&stream.receiver_id, amount);
+      self.internal_record_entry(
+          &stream.receiver_id, EntryKind::Stream, EntryDirection::Credit, amount, None, Some(format!("stream:{}", stream_id)),
+      );
+      BankEvent::StreamWithdrawn { id: stream_id, receiver_id: stream.receiver_id.clone(), amount }.emit();
+      if stream.withdrawn == stream.deposit {
+          self.internal_remove_stream(&stream);
+          BankEvent::StreamClosed { id: stream_id, receiver_amount: NearToken::from_yoctonear(0), sender_refund: NearToken::from_yoctonear(0) }.emit();
+      }
+      amount
+  }
+
+  /// Cancels a stream. The receiver is paid everything vested so far and the unvested
+  /// remainder returns to the sender. Callable by the sender or the receiver.
+  pub fn cancel_stream(&mut self, stream_id: u64) {
+      let account_id = env::predecessor_account_id();
+      let stream = self.streams.get(&stream_id)
+          .unwrap_or_else(|| env::panic_str("Stream not found."))
+          .clone();
+      assert!(account_id == stream.sender_id || account_id == stream.receiver_id, "Only a party to the stream can cancel it.");
+
+      let now = env::block_timestamp();
+      let receiver_amount = stream.withdrawable_at(now);
+      let sender_refund = stream.deposit.saturating_sub(stream.vested_at(now));
+      let reference = format!("stream:{}", stream_id);
+      if !receiver_amount.is_zero() {
+          self.internal_credit(&stream.receiver_id, receiver_amount);
+          self.internal_record_entry(&stream.receiver_id, EntryKind::Stream, EntryDirection::Credit, receiver_amount, None, Some(reference.clone()));
+      }
+      if !sender_refund.is_zero() {
+          self.internal_credit(&stream.sender_id, sender_refund);
+          self.internal_record_entry(&stream.sender_id, EntryKind::Stream, EntryDirection::Credit, sender_refund, None, Some(reference));
+      }
+      self.internal_remove_stream(&stream);
+      BankEvent::StreamClosed { id: stream_id, receiver_amount, sender_refund }.emit();
+  }
+
+  /// Retrieves a stream with its currently vested and withdrawable amounts.
+  /// View function.
+  pub fn get_stream(&self, stream_id: u64) -> Option<StreamView> {
+      let now = env::block_timestamp();
+      self.streams.get(&stream_id).map(|stream| StreamView {
+          vested: stream.vested_at(now),
+          withdrawable: stream.withdrawable_at(now),
+          stream: stream.clone(),
+      })
+  }
+
+  /// Lists the open streams paying `account_id`.
+  /// View function.
+  pub fn get_incoming_streams(&self, account_id: AccountId) -> Vec<StreamView> {
+      self.incoming_streams.get(&account_id)
+          .map(|ids| ids.iter().filter_map(|id| self.get_stream(*id)).collect())
+          .unwrap_or_default()
+  }
+
+  /// Lists the open streams funded by `account_id`.
+  /// View function.
+  pub fn get_outgoing_streams(&self, account_id: AccountId) -> Vec<StreamView> {
+      self.outgoing_streams.get(&account_id)
+          .map(|ids| ids.iter().filter_map(|id| self.get_stream(*id)).collect())
+          .unwrap_or_default()
+  }
+}
+
+impl BioCrypticBankCore {
+  fn internal_remove_stream(&mut self, stream: &Stream) {
+      self.streams.remove(&stream.id);
+      for (index, account_id) in [(&mut self.outgoing_streams, &stream.sender_id), (&mut self.incoming_streams, &stream.receiver_id)] {
+          if let Some(ids) = index.get_mut(account_id) {
+              ids.retain(|existing| *existing != stream.id);
+              if ids.is_empty() {
+                  index.remove(account_id);
+              }
+          }
+      }
+  }
+}
@@ -0,0 +1,303 @@
+// services/blockchain/near-rs/core-banking/src/subscriptions.rs
+use near_sdk::{near, env, AccountId, NearToken};
+
+use crate::ledger::EntryKind;
+use crate::pause::Feature;
+use crate::{mul_div, BankEvent, BioCrypticBankCore, BioCrypticBankCoreExt};
+
+const NANOS_PER_SECOND: u64 = 1_000_000_000;
+const MAX_PLAN_NAME_LENGTH: usize = 64;
+/// Shortest allowed billing interval (1 day).
+const MIN_INTERVAL_SECONDS: u64 = 24 * 60 * 60;
+/// Delay between billing attempts while a subscription is past due.
+const DUNNING_RETRY_SECONDS: u64 = 24 * 60 * 60;
+const DEFAULT_BILLING_LIMIT: u64 = 20;
+const MAX_SUBSCRIPTIONS_PER_ACCOUNT: usize = 50;
+
+/// A recurring price offered by `merchant_id`.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct SubscriptionPlan {
+  pub id: u64,
+  pub merchant_id: AccountId,
+  pub name: String,
+  pub price: NearToken,
+  pub interval_seconds: u64,
+  /// How long a subscription stays past due, with retries, before it lapses.
+  pub grace_period_seconds: u64,
+  /// Retired plans take no new subscribers; existing subscriptions keep billing.
+  pub active: bool,
+}
+
+#[near(serializers = [borsh, json])]
+#[serde(rename_all = "snake_case")]
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum SubscriptionStatus {
+  Active,
+  /// The last charge failed; retried until the plan's grace period ends.
+  PastDue,
+}
+
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct Subscription {
+  pub id: u64,
+  pub plan_id: u64,
+  pub subscriber_id: AccountId,
+  pub merchant_id: AccountId,
+  /// Price and interval fixed at subscription time.
+  pub price: NearToken,
+  pub interval_seconds: u64,
+  /// Charge against the subscriber's allowance for the merchant instead of its balance alone.
+  pub pay_from_allowance: bool,
+  pub status: SubscriptionStatus,
+  /// Paid period (ns) the subscriber currently has access to.
+  pub period_start: u64,
+  pub period_end: u64,
+  /// Timestamp (ns) of the next billing attempt.
+  pub next_charge_at: u64,
+  pub past_due_since: Option<u64>,
+  pub failed_attempts: u32,
+  pub charges: u64,
+}
+
+#[near(serializers = [json])]
+#[derive(Debug, PartialEq)]
+pub struct BillingSummary {
+  pub charged: u32,
+  pub failed: u32,
+  pub lapsed: u32,
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Publishes a plan billing `price` every `interval_seconds`. Returns the plan id.
+  pub fn create_subscription_plan(&mut self, name: String, price: NearToken, interval_seconds: u64, grace_period_seconds: u64) -> u64 {
+      let initial_storage = env::storage_usage();
+      let merchant_id = env::predecessor_account_id();
+      self.assert_registered(&merchant_id);
+      assert!(!name.is_empty() && name.len() <= MAX_PLAN_NAME_LENGTH, "Plan name must be 1 to {} bytes.", MAX_PLAN_NAME_LENGTH);
+      assert!(price.as_yoctonear() > 0, "Price must be greater than 0.");
+      assert!(interval_seconds >= MIN_INTERVAL_SECONDS, "Interval must be at least {} seconds.", MIN_INTERVAL_SECONDS);
+
+      let id = self.next_subscription_plan_id;
+      self.next_subscription_plan_id += 1;
+      self.subscription_plans.insert(id, SubscriptionPlan {
+          id,
+          merchant_id: merchant_id.clone(),
+          name,
+          price,
+          interval_seconds,
+          grace_period_seconds,
+          active: true,
+      });
+      self.internal_charge_storage(&merchant_id, initial_storage);
+      BankEvent::SubscriptionPlanCreated { id, merchant_id, price, interval_seconds }.emit();
+      id
+  }
+
+  /// Closes a plan to new subscribers. Plan merchant only.
+  pub fn retire_subscription_plan(&mut self, plan_id: u64) {
+      let plan = self.subscription_plans.get_mut(&plan_id).unwrap_or_else(|| env::panic_str("Plan not found."));
+      assert_eq!(plan.merchant_id, env::predecessor_account_id(), "Only the merchant can retire this plan.");
+      plan.active = false;
+  }
+
+  /// Subscribes the caller to `plan_id`, charging the first period now. With
+  /// `pay_from_allowance`, every charge also consumes the allowance the caller granted the
+  /// merchant, capping what the merchant can ever bill. Returns the subscription id.
+  pub fn subscribe(&mut self, plan_id: u64, pay_from_allowance: bool) -> u64 {
+      self.assert_not_paused(Feature::Transfers);
+      let initial_storage = env::storage_usage();
+      let subscriber_id = env::predecessor_account_id();
+      let plan = self.subscription_plans.get(&plan_id).unwrap_or_else(|| env::panic_str("Plan not found.")).clone();
+      assert!(plan.active, "Plan is retired.");
+      assert!(plan.merchant_id != subscriber_id, "Cannot subscribe to your own plan.");
+      self.assert_trusted_destination(&subscriber_id, &plan.merchant_id);
+
+      let ids = self.subscriptions_by_account.entry(subscriber_id.clone()).or_default();
+      assert!(ids.len() < MAX_SUBSCRIPTIONS_PER_ACCOUNT, "Cannot exceed {} subscriptions.", MAX_SUBSCRIPTIONS_PER_ACCOUNT);
+      let id = self.next_subscription_id;
+      self.next_subscription_id += 1;
+      ids.push(id);
+
+      let now = env::block_timestamp();
+      let subscription = Subscription {
+          id,
+          plan_id,
+          subscriber_id: subscriber_id.clone(),
+          merchant_id: plan.merchant_id.clone(),
+          price: plan.price,
+          interval_seconds: plan.interval_seconds,
+          pay_from_allowance,
+          status: SubscriptionStatus::Active,
+          period_start: now,
+          period_end: now + plan.interval_seconds * NANOS_PER_SECOND,
+          next_charge_at: now + plan.interval_seconds * NANOS_PER_SECOND,
+          past_due_since: None,
+          failed_attempts: 0,
+          charges: 1,
+      };
+      assert!(self.internal_can_bill(&subscription), "Insufficient funds or allowance for the first period.");
+      self.internal_bill_subscription(&subscription);
+      self.subscriptions.insert(id, subscription);
+      self.internal_charge_storage(&subscriber_id, initial_storage);
+      BankEvent::Subscribed { id, plan_id, subscriber_id, merchant_id: plan.merchant_id }.emit();
+      id
+  }
+
+  /// Cancels a subscription. The subscriber can cancel its own subscription and the merchant
+  /// any of its subscribers'. The unused part of the current paid period is refunded pro
+  /// rata from the merchant's balance, as far as it covers it. Returns the refund.
+  pub fn cancel_subscription(&mut self, subscription_id: u64) -> NearToken {
+      let subscription = self.subscriptions.get(&subscription_id)
+          .unwrap_or_else(|| env::panic_str("Subscription not found.")).clone();
+      let caller_id = env::predecessor_account_id();
+      assert!(
+          caller_id == subscription.subscriber_id || caller_id == subscription.merchant_id,
+          "Only the subscriber or the merchant can cancel this subscription."
+      );
+      let initial_storage = env::storage_usage();
+      let now = env::block_timestamp();
+      let mut refund = NearToken::from_yoctonear(0);
+      if subscription.status == SubscriptionStatus::Active && now < subscription.period_end {
+          let prorated = mul_div(
+              subscription.price.as_yoctonear(),
+              (subscription.period_end - now) as u128,
+              (subscription.period_end - subscription.period_start) as u128,
+          );
+          refund = NearToken::from_yoctonear(prorated).min(self.internal_unallocated_balance(&subscription.merchant_id));
+      }
+      if !refund.is_zero() {
+          self.internal_debit(&subscription.merchant_id, refund);
+          self.internal_credit(&subscription.subscriber_id, refund);
+          self.internal_record_movement(
+              &subscription.merchant_id, &subscription.subscriber_id, EntryKind::SubscriptionRefund, refund,
+              None, Some(format!("subscription:{}", subscription_id)),
+          );
+      }
+      self.internal_remove_subscription(subscription_id);
+      self.internal_charge_storage(&subscription.subscriber_id, initial_storage);
+      BankEvent::SubscriptionCancelled { id: subscription_id, cancelled_by: caller_id, refund }.emit();
+      refund
+  }
+
+  /// Permissionless crank billing up to `limit` due subscriptions. A failed charge puts the
+  /// subscription past due and emits a dunning event; it is retried daily and lapses once
+  /// the plan's grace period has passed without a successful charge.
+  pub fn bill_due_subscriptions(&mut self, limit: Option<u64>) -> BillingSummary {
+      self.assert_not_paused(Feature::Transfers);
+      let now = env::block_timestamp();
+      let limit = limit.unwrap_or(DEFAULT_BILLING_LIMIT) as usize;
+      let due: Vec<u64> = self.subscriptions.iter()
+          .filter(|(_, subscription)| subscription.next_charge_at <= now)
+          .map(|(id, _)| *id)
+          .take(limit)
+          .collect();
+
+      let mut summary = BillingSummary { charged: 0, failed: 0, lapsed: 0 };
+      for id in due {
+          let mut subscription = self.subscriptions.get(&id).unwrap().clone();
+          if self.internal_can_bill(&subscription) {
+              self.internal_bill_subscription(&subscription);
+              let interval = subscription.interval_seconds * NANOS_PER_SECOND;
+              subscription.period_start = subscription.period_end.max(now);
+              subscription.period_end = subscription.period_start + interval;
+              subscription.next_charge_at = subscription.period_end;
+              subscription.status = SubscriptionStatus::Active;
+              subscription.past_due_since = None;
+              subscription.failed_attempts = 0;
+              subscription.charges += 1;
+              summary.charged += 1;
+              BankEvent::SubscriptionCharged { id, subscriber_id: subscription.subscriber_id.clone(), amount: subscription.price }.emit();
+              self.subscriptions.insert(id, subscription);
+              continue;
+          }
+
+          summary.failed += 1;
+          let grace_period = self.subscription_plans.get(&subscription.plan_id).map_or(0, |plan| plan.grace_period_seconds);
+          let past_due_since = *subscription.past_due_since.get_or_insert(now);
+          let grace_ends_at = past_due_since + grace_period * NANOS_PER_SECOND;
+          subscription.status = SubscriptionStatus::PastDue;
+          subscription.failed_attempts += 1;
+          if now >= grace_ends_at {
+              self.internal_remove_subscription(id);
+              summary.lapsed += 1;
+              BankEvent::SubscriptionLapsed { id, subscriber_id: subscription.subscriber_id }.emit();
+              continue;
+          }
+          subscription.next_charge_at = (now + DUNNING_RETRY_SECONDS * NANOS_PER_SECOND).min(grace_ends_at);
+          BankEvent::SubscriptionPaymentFailed {
+              id,
+              subscriber_id: subscription.subscriber_id.clone(),
+              attempt: subscription.failed_attempts,
+              grace_ends_at,
+          }.emit();
+          self.subscriptions.insert(id, subscription);
+      }
+      summary
+  }
+
+  /// View function.
+  pub fn get_subscription_plan(&self, plan_id: u64) -> Option<SubscriptionPlan> {
+      self.subscription_plans.get(&plan_id).cloned()
+  }
+
+  /// View function.
+  pub fn get_subscription(&self, subscription_id: u64) -> Option<Subscription> {
+      self.subscriptions.get(&subscription_id).cloned()
+  }
+
+  /// Lists the subscriptions `account_id` pays for.
+  /// View function.
+  pub fn get_subscriptions(&self, account_id: AccountId) -> Vec<Subscription> {
+      self.subscriptions_by_account.get(&account_id)
+          .map(|ids| ids.iter().filter_map(|id| self.subscriptions.get(id).cloned()).collect())
+          .unwrap_or_default()
+  }
+}
+
+impl BioCrypticBankCore {
+  /// Whether the next charge of `subscription` would go through right now.
+  fn internal_can_bill(&self, subscription: &Subscription) -> bool {
+      let subscriber_id = &subscription.subscriber_id;
+      let allowance_ok = !subscription.pay_from_allowance || self.allowances
+          .get(&(subscriber_id.clone(), subscription.merchant_id.clone()))
+          .is_some_and(|allowance| {
+              allowance.expires_at.is_none_or(|expires_at| env::block_timestamp() <= expires_at)
+                  && allowance.remaining >= subscription.price
+          });
+      allowance_ok
+          && self.internal_unallocated_balance(subscriber_id) >= subscription.price
+          && self.can_spend(subscriber_id, subscription.price)
+  }
+
+  /// Moves one period's price from the subscriber to the merchant. Callers check
+  /// `internal_can_bill` first.
+  fn internal_bill_subscription(&mut self, subscription: &Subscription) {
+      let (subscriber_id, merchant_id, price) = (&subscription.subscriber_id, &subscription.merchant_id, subscription.price);
+      if subscription.pay_from_allowance {
+          let allowance = self.allowances.get_mut(&(subscriber_id.clone(), merchant_id.clone())).unwrap();
+          allowance.remaining = allowance.remaining.saturating_sub(price);
+          allowance.total_spent = allowance.total_spent.saturating_add(price);
+      }
+      self.internal_record_outflow(subscriber_id, price);
+      self.internal_debit(subscriber_id, price);
+      self.internal_credit(merchant_id, price);
+      let reference = format!("subscription:{}", subscription.id);
+      let (_, credit_id) = self.internal_record_movement(
+          subscriber_id, merchant_id, EntryKind::Subscription, price, None, Some(reference.clone()),
+      );
+      self.internal_index_payment_reference(merchant_id, &reference, credit_id);
+  }
+
+  fn internal_remove_subscription(&mut self, subscription_id: u64) {
+      let Some(subscription) = self.subscriptions.remove(&subscription_id) else { return };
+      if let Some(ids) = self.subscriptions_by_account.get_mut(&subscription.subscriber_id) {
+          ids.retain(|existing| *existing != subscription_id);
+          if ids.is_empty() {
+              self.subscriptions_by_account.remove(&subscription.subscriber_id);
+          }
+      }
+  }
+}
@@ -0,0 +1,195 @@
+// services/blockchain/near-rs/core-banking/src/term_deposits.rs
+use near_sdk::{near, env, AccountId, NearToken};
+
+use crate::savings::{BPS_DENOMINATOR, SECONDS_PER_YEAR};
+use crate::{mul_div, BankEvent, BioCrypticBankCore, BioCrypticBankCoreExt};
+
+pub(crate) const NANOS_PER_DAY: u64 = 24 * 60 * 60 * 1_000_000_000;
+
+/// A fixed-term deposit. Funds are locked until `matures_at`; with `auto_rollover`
+/// principal and interest are re-locked for another term at the then-current rate.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct TermDeposit {
+  pub id: u64,
+  pub account_id: AccountId,
+  pub principal: NearToken,
+  pub term_days: u32,
+  pub apr_bps: u16,
+  pub opened_at: u64,
+  pub matures_at: u64,
+  pub auto_rollover: bool,
+}
+
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct TermRate {
+  pub term_days: u32,
+  pub apr_bps: u16,
+}
+
+impl TermDeposit {
+  /// Simple interest owed on `principal` for one full term.
+  fn interest_for_term(&self) -> u128 {
+      let term_seconds = self.term_days as u128 * 24 * 60 * 60;
+      mul_div(self.principal.as_yoctonear(), self.apr_bps as u128 * term_seconds, BPS_DENOMINATOR * SECONDS_PER_YEAR)
+  }
+
+  fn is_matured(&self, now: u64) -> bool {
+      now >= self.matures_at
+  }
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Sets the penalty, in basis points of principal, charged on early withdrawal. Owner or
+  /// governance only.
+  pub fn set_early_withdrawal_penalty(&mut self, penalty_bps: u16) {
+      self.assert_owner_or_governance();
+      assert!(penalty_bps as u128 <= BPS_DENOMINATOR, "Penalty cannot exceed 10000 bps.");
+      self.early_withdrawal_penalty_bps = penalty_bps;
+  }
+
+  /// Locks `amount` from the caller's checking balance for `term_days` at the offered rate.
+  /// Returns the new deposit id.
+  pub fn open_term_deposit(&mut self, amount: NearToken, term_days: u32, auto_rollover: Option<bool>) -> u64 {
+      let initial_storage = env::storage_usage();
+      let account_id = env::predecessor_account_id();
+      assert!(amount.as_yoctonear() > 0, "Amount must be greater than 0.");
+      let apr_bps = *self.term_rates.get(&term_days)
+          .unwrap_or_else(|| env::panic_str("No term product for this duration."));
+      self.internal_debit(&account_id, amount);
+
+      let id = self.next_term_deposit_id;
+      self.next_term_deposit_id += 1;
+      let now = env::block_timestamp();
+      let deposit = TermDeposit {
+          id,
+          account_id: account_id.clone(),
+          principal: amount,
+          term_days,
+          apr_bps,
+          opened_at: now,
+          matures_at: now + term_days as u64 * NANOS_PER_DAY,
+          auto_rollover: auto_rollover.unwrap_or(false),
+      };
+      BankEvent::TermDepositOpened {
+          id, account_id: account_id.clone(), amount, term_days, apr_bps, matures_at: deposit.matures_at,
+      }.emit();
+      self.term_deposits.insert(id, deposit);
+      self.term_deposits_by_account.entry(account_id.clone()).or_default().push(id);
+      self.internal_charge_storage(&account_id, initial_storage);
+      id
+  }
+
+  /// Turns automatic rollover on or off. Takes effect at the next maturity.
+  pub fn set_term_deposit_rollover(&mut self, id: u64, auto_rollover: bool) {
+      self.internal_roll_forward(id);
+      let deposit = self.internal_get_own_term_deposit_mut(id);
+      deposit.auto_rollover = auto_rollover;
+  }
+
+  /// Closes a term deposit into the caller's checking balance. After maturity the payout is
+  /// principal plus interest; before maturity it is principal minus the early-withdrawal
+  /// penalty, which goes to the fee treasury.
+  pub fn redeem_term_deposit(&mut self, id: u64) -> NearToken {
+      self.internal_roll_forward(id);
+      let deposit = self.internal_get_own_term_deposit_mut(id).clone();
+
+      let now = env::block_timestamp();
+      let (payout, interest, penalty) = if deposit.is_matured(now) {
+          let interest = deposit.interest_for_term();
+          (deposit.principal.as_yoctonear() + interest, interest, 0)
+      } else {
+          let penalty = mul_div(deposit.principal.as_yoctonear(), self.early_withdrawal_penalty_bps as u128, BPS_DENOMINATOR);
+          (deposit.principal.as_yoctonear() - penalty, 0, penalty)
+      };
+
+      self.term_deposits.remove(&id);
+      if let Some(ids) = self.term_deposits_by_account.get_mut(&deposit.account_id) {
+          ids.retain(|existing| *existing != id);
+          if ids.is_empty() {
+              self.term_deposits_by_account.remove(&deposit.account_id);
+          }
+      }
+      let payout = NearToken::from_yoctonear(payout);
+      self.internal_credit(&deposit.account_id, payout);
+      self.internal_add_liabilities(NearToken::from_yoctonear(interest));
+      self.internal_remove_liabilities(NearToken::from_yoctonear(penalty));
+      self.internal_collect_fee(NearToken::from_yoctonear(penalty));
+
+      BankEvent::TermDepositRedeemed {
+          id,
+          account_id: deposit.account_id,
+          payout,
+          interest: NearToken::from_yoctonear(interest),
+          penalty: NearToken::from_yoctonear(penalty),
+      }.emit();
+      payout
+  }
+
+  /// Retrieves a term deposit with any pending rollovers applied.
+  /// View function.
+  pub fn get_term_deposit(&self, id: u64) -> Option<TermDeposit> {
+      let mut deposit = self.term_deposits.get(&id)?.clone();
+      self.apply_rollovers(&mut deposit, env::block_timestamp());
+      Some(deposit)
+  }
+
+  /// Lists an account's term deposits and their maturities, with pending rollovers applied.
+  /// View function.
+  pub fn get_term_deposits(&self, account_id: AccountId) -> Vec<TermDeposit> {
+      self.term_deposits_by_account.get(&account_id)
+          .map(|ids| ids.iter().filter_map(|id| self.get_term_deposit(*id)).collect())
+          .unwrap_or_default()
+  }
+
+  /// Lists the term products currently offered. Set through the rate model.
+  /// View function.
+  pub fn get_term_rates(&self) -> Vec<TermRate> {
+      self.term_rates.iter().map(|(term_days, apr_bps)| TermRate { term_days: *term_days, apr_bps: *apr_bps }).collect()
+  }
+
+  /// Retrieves the early-withdrawal penalty in basis points.
+  /// View function.
+  pub fn get_early_withdrawal_penalty(&self) -> u16 {
+      self.early_withdrawal_penalty_bps
+  }
+}
+
+impl BioCrypticBankCore {
+  fn internal_get_own_term_deposit_mut(&mut self, id: u64) -> &mut TermDeposit {
+      let deposit = self.term_deposits.get_mut(&id)
+          .unwrap_or_else(|| env::panic_str("Term deposit not found."));
+      assert_eq!(deposit.account_id, env::predecessor_account_id(), "Only the depositor can manage this term deposit.");
+      deposit
+  }
+
+  /// Applies every rollover that has come due for `id` to stored state.
+  fn internal_roll_forward(&mut self, id: u64) {
+      let Some(mut deposit) = self.term_deposits.get(&id).cloned() else { return };
+      let previous_maturity = deposit.matures_at;
+      self.apply_rollovers(&mut deposit, env::block_timestamp());
+      if deposit.matures_at != previous_maturity {
+          let previous_principal = self.term_deposits.get(&id).map(|previous| previous.principal).unwrap();
+          self.internal_add_liabilities(deposit.principal.saturating_sub(previous_principal));
+          BankEvent::TermDepositRolledOver {
+              id, principal: deposit.principal, apr_bps: deposit.apr_bps, matures_at: deposit.matures_at,
+          }.emit();
+          self.term_deposits.insert(id, deposit);
+      }
+  }
+
+  /// Compounds interest into principal for each full term elapsed since maturity while
+  /// rollover is enabled and the product is still offered.
+  fn apply_rollovers(&self, deposit: &mut TermDeposit, now: u64) {
+      while deposit.auto_rollover && deposit.is_matured(now) {
+          let Some(apr_bps) = self.term_rates.get(&deposit.term_days) else { break };
+          let interest = deposit.interest_for_term();
+          deposit.principal = deposit.principal.saturating_add(NearToken::from_yoctonear(interest));
+          deposit.apr_bps = *apr_bps;
+          deposit.opened_at = deposit.matures_at;
+          deposit.matures_at += deposit.term_days as u64 * NANOS_PER_DAY;
+      }
+  }
+}
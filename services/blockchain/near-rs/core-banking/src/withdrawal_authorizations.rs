@@ -0,0 +1,235 @@
+// services/blockchain/near-rs/core-banking/src/withdrawal_authorizations.rs
+use std::str::FromStr;
+
+use near_sdk::{near, env, AccountId, CurveType, Gas, NearToken, PromiseError, PromiseOrValue, PublicKey};
+use near_sdk::json_types::Base64VecU8;
+use biocrypticbank_common::did::VerificationMethod;
+use biocrypticbank_common::interfaces::ext_did_registry;
+
+use crate::dormancy::DormancyStatus;
+use crate::operation_locks::LockedOperation;
+use crate::pause::Feature;
+use crate::{BankEvent, BioCrypticBankCore, BioCrypticBankCoreExt};
+
+const GAS_FOR_VERIFICATION_METHOD: Gas = Gas::from_tgas(10);
+const GAS_FOR_AUTHORIZATION_CALLBACK: Gas = Gas::from_tgas(60);
+
+/// A withdrawal the account holder signed off-chain with a key from their DID document.
+/// The signature covers `sha256(borsh((bank_contract_id, authorization)))`, so it is only
+/// valid at this contract and cannot be replayed against another deployment.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct WithdrawalAuthorization {
+  pub account_id: AccountId,
+  pub amount: NearToken,
+  /// Must exceed the account's last used authorization nonce.
+  pub nonce: u64,
+  /// Time (ns) after which the authorization can no longer be executed.
+  pub deadline: u64,
+  /// Id of the ed25519 verification method in the account's DID document that signed it.
+  pub key_id: String,
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Executes a withdrawal to `authorization.account_id` that the account holder signed
+  /// with a key registered in their DID document, so a back-office system can run it
+  /// without holding the holder's NEAR key. The key is read from the DID registry and the
+  /// ed25519 `signature` checked on-chain before the nonce is consumed; from there the
+  /// withdrawal goes through the same guardian, timelock and KYC routing as `withdraw`.
+  /// Resolves to false if the authorization was rejected, or under the same conditions as
+  /// `withdraw`. Callable by anyone.
+  pub fn withdraw_with_authorization(&mut self, authorization: WithdrawalAuthorization, signature: Base64VecU8) -> PromiseOrValue<bool> {
+      self.assert_not_paused(Feature::Withdrawals);
+      let account_id = authorization.account_id.clone();
+      self.assert_registered(&account_id);
+      self.assert_not_dormant(&account_id);
+      self.assert_no_recovery_hold(&account_id);
+      assert!(authorization.amount.as_yoctonear() > 0, "Withdrawal amount must be greater than 0.");
+      assert!(env::block_timestamp() <= authorization.deadline, "Withdrawal authorization has expired.");
+      assert!(authorization.nonce > self.get_authorization_nonce(account_id.clone()), "Withdrawal authorization nonce was already used.");
+      assert!(self.get_balance(account_id.clone()) >= authorization.amount, "Insufficient balance.");
+      assert_eq!(signature.0.len(), 64, "Signature must be 64 bytes.");
+      let registry_id = self.kyc_config.did_registry_id.clone()
+          .unwrap_or_else(|| env::panic_str("No DID registry is configured."));
+
+      let lock_id = self.internal_lock_account(&account_id, LockedOperation::AuthorizedWithdrawal);
+      ext_did_registry::ext(registry_id)
+          .with_static_gas(GAS_FOR_VERIFICATION_METHOD)
+          .get_verification_method(account_id, authorization.key_id.clone())
+          .then(
+              Self::ext(env::current_account_id())
+                  .with_static_gas(GAS_FOR_AUTHORIZATION_CALLBACK)
+                  .on_authorization_key_resolved(authorization, signature, Some(lock_id))
+          )
+          .into()
+  }
+
+  /// Callback verifying the signature against the key read from the DID registry and
+  /// re-checking the withdrawal while the account is still locked, then releasing the lock.
+  /// A missing or non-ed25519 key, a bad signature, an authorization that expired or was
+  /// used while the key was read, or a withdrawal the account can no longer make resolves to
+  /// false; otherwise the nonce is consumed and the withdrawal requested.
+  #[private]
+  pub fn on_authorization_key_resolved(
+      &mut self,
+      authorization: WithdrawalAuthorization,
+      signature: Base64VecU8,
+      lock_id: Option<u64>,
+      #[callback_result] result: Result<Option<VerificationMethod>, PromiseError>,
+  ) -> PromiseOrValue<bool> {
+      let account_id = authorization.account_id.clone();
+      let rejection = match result.ok().flatten() {
+          None => Some("Signing key not found in the DID document"),
+          Some(method) if !self.internal_authorization_signed_by(&authorization, &signature, &method.public_key) => {
+              Some("Invalid authorization signature")
+          }
+          Some(_) if env::block_timestamp() > authorization.deadline
+              || authorization.nonce <= self.get_authorization_nonce(account_id.clone()) => {
+              Some("Authorization expired or its nonce was used")
+          }
+          Some(_) => self.internal_withdrawal_blocker(&account_id, authorization.amount),
+      };
+      self.internal_unlock_account(&account_id, lock_id);
+      if let Some(reason) = rejection {
+          env::log_str(&format!("{}; authorization rejected.", reason));
+          return PromiseOrValue::Value(false);
+      }
+      self.authorization_nonces.insert(account_id.clone(), authorization.nonce);
+      BankEvent::WithdrawalAuthorized {
+          account_id: account_id.clone(),
+          amount: authorization.amount,
+          nonce: authorization.nonce,
+          key_id: authorization.key_id,
+          executor_id: env::signer_account_id(),
+      }.emit();
+      let reference = Some(format!("authorization:{}", authorization.nonce));
+      self.internal_request_withdrawal(account_id, authorization.amount, None, None, reference)
+  }
+
+  /// Last withdrawal authorization nonce used by `account_id`, 0 if none.
+  /// View function.
+  pub fn get_authorization_nonce(&self, account_id: AccountId) -> u64 {
+      self.authorization_nonces.get(&account_id).copied().unwrap_or(0)
+  }
+
+  /// Hash an authorization's signer must sign with ed25519, as base64.
+  /// View function.
+  pub fn get_withdrawal_authorization_hash(&self, authorization: WithdrawalAuthorization) -> Base64VecU8 {
+      Base64VecU8(self.internal_authorization_hash(&authorization).to_vec())
+  }
+}
+
+impl BioCrypticBankCore {
+  /// Why `withdraw_with_authorization`'s checks would now refuse `account_id` withdrawing
+  /// `amount`, if they would.
+  fn internal_withdrawal_blocker(&self, account_id: &AccountId, amount: NearToken) -> Option<&'static str> {
+      if self.is_paused(Feature::Withdrawals) {
+          Some("Withdrawals are paused")
+      } else if !self.storage_accounts.contains_key(account_id) {
+          Some("Account is no longer registered")
+      } else if self.get_dormancy_status(account_id.clone()) == DormancyStatus::Dormant {
+          Some("Account is dormant")
+      } else if self.is_on_recovery_hold(account_id) {
+          Some("Account is on hold while its recovery is pending")
+      } else if self.get_balance(account_id.clone()) < amount {
+          Some("Insufficient balance")
+      } else {
+          None
+      }
+  }
+
+  fn internal_authorization_hash(&self, authorization: &WithdrawalAuthorization) -> [u8; 32] {
+      let message = near_sdk::borsh::to_vec(&(env::current_account_id(), authorization))
+          .unwrap_or_else(|_| env::panic_str("Failed to serialize authorization."));
+      env::sha256_array(&message)
+  }
+
+  /// Whether `signature` is `public_key`'s ed25519 signature of the authorization hash.
+  /// `public_key` is in NEAR's `ed25519:<base58>` form.
+  fn internal_authorization_signed_by(&self, authorization: &WithdrawalAuthorization, signature: &Base64VecU8, public_key: &str) -> bool {
+      let Ok(public_key) = PublicKey::from_str(public_key) else { return false };
+      if public_key.curve_type() != CurveType::ED25519 {
+          return false;
+      }
+      let (Ok(key), Ok(signature)) = (
+          <[u8; 32]>::try_from(&public_key.as_bytes()[1..]),
+          <[u8; 64]>::try_from(signature.0.as_slice()),
+      ) else {
+          return false;
+      };
+      env::ed25519_verify(&signature, &self.internal_authorization_hash(authorization), &key)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use ed25519_dalek::{Signer, SigningKey};
+  use near_sdk::test_utils::{accounts, VMContextBuilder};
+  use near_sdk::testing_env;
+
+  use super::*;
+
+  fn signed(contract: &BioCrypticBankCore, key: &SigningKey, authorization: &WithdrawalAuthorization) -> Base64VecU8 {
+      Base64VecU8(key.sign(&contract.internal_authorization_hash(authorization)).to_bytes().to_vec())
+  }
+
+  fn public_key(key: &SigningKey) -> String {
+      let public_key = PublicKey::from_parts(CurveType::ED25519, key.verifying_key().to_bytes().to_vec()).unwrap();
+      String::from(&public_key)
+  }
+
+  #[test]
+  fn authorization_signature_is_checked_against_did_key() {
+      testing_env!(VMContextBuilder::new().current_account_id(accounts(0)).build());
+      let contract = BioCrypticBankCore::new(accounts(0));
+      let key = SigningKey::from_bytes(&[7u8; 32]);
+      let authorization = WithdrawalAuthorization {
+          account_id: accounts(1),
+          amount: NearToken::from_near(1),
+          nonce: 1,
+          deadline: u64::MAX,
+          key_id: "key-1".to_string(),
+      };
+      let signature = signed(&contract, &key, &authorization);
+
+      assert!(contract.internal_authorization_signed_by(&authorization, &signature, &public_key(&key)));
+
+      let tampered = WithdrawalAuthorization { amount: NearToken::from_near(2), ..authorization.clone() };
+      assert!(!contract.internal_authorization_signed_by(&tampered, &signature, &public_key(&key)));
+
+      let other_key = SigningKey::from_bytes(&[8u8; 32]);
+      assert!(!contract.internal_authorization_signed_by(&authorization, &signature, &public_key(&other_key)));
+  }
+
+  #[test]
+  fn authorizations_the_account_can_no_longer_honour_are_rejected_and_unlocked() {
+      testing_env!(VMContextBuilder::new()
+          .current_account_id(accounts(0))
+          .predecessor_account_id(accounts(1))
+          .attached_deposit(NearToken::from_near(1))
+          .build());
+      let mut contract = BioCrypticBankCore::new(accounts(0));
+      contract.storage_deposit(None, None);
+      let key = SigningKey::from_bytes(&[7u8; 32]);
+      let authorization = WithdrawalAuthorization {
+          account_id: accounts(1),
+          amount: NearToken::from_near(1),
+          nonce: 1,
+          deadline: u64::MAX,
+          key_id: "key-1".to_string(),
+      };
+      let signature = signed(&contract, &key, &authorization);
+      let method = VerificationMethod {
+          id: "key-1".to_string(),
+          method_type: "Ed25519VerificationKey2018".to_string(),
+          public_key: public_key(&key),
+      };
+
+      let lock_id = contract.internal_lock_account(&accounts(1), LockedOperation::AuthorizedWithdrawal);
+      let resolved = contract.on_authorization_key_resolved(authorization, signature, Some(lock_id), Ok(Some(method)));
+      assert!(matches!(resolved, PromiseOrValue::Value(false)));
+      assert!(contract.get_operation_lock(accounts(1)).is_none());
+      assert_eq!(contract.get_authorization_nonce(accounts(1)), 0);
+  }
+}
@@ -0,0 +1,178 @@
+// services/blockchain/near-rs/core-banking/src/withdrawal_timelock.rs
+use near_sdk::{near, env, AccountId, NearToken, PromiseOrValue};
+
+use crate::kyc::KycPendingAction;
+use crate::pause::Feature;
+use crate::roles::Role;
+use crate::{BankEvent, BioCrypticBankCore, BioCrypticBankCoreExt, DEFAULT_PAGE_LIMIT};
+
+const NANOS_PER_HOUR: u64 = 60 * 60 * 1_000_000_000;
+const MAX_DELAY_HOURS: u32 = 7 * 24;
+const MAX_TIMELOCKED_WITHDRAWALS_PER_ACCOUNT: usize = 10;
+
+/// Withdrawals above `threshold` wait `delay_hours` before they can be executed. Owner
+/// controlled.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct WithdrawalTimelockConfig {
+  /// `None` disables the timelock.
+  pub threshold: Option<NearToken>,
+  pub delay_hours: u32,
+}
+
+impl Default for WithdrawalTimelockConfig {
+  fn default() -> Self {
+      Self { threshold: None, delay_hours: 24 }
+  }
+}
+
+/// A large withdrawal waiting out its timelock. The funds stay in the account until it runs.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct TimelockedWithdrawal {
+  pub id: u64,
+  pub account_id: AccountId,
+  pub amount: NearToken,
+  pub envelope: Option<String>,
+  pub memo: Option<String>,
+  pub reference: Option<String>,
+  pub requested_at: u64,
+  pub executable_at: u64,
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Replaces the withdrawal timelock rule. Withdrawals already queued keep their delay.
+  /// Owner only.
+  pub fn set_withdrawal_timelock(&mut self, config: WithdrawalTimelockConfig) {
+      self.assert_owner();
+      assert!(
+          config.delay_hours > 0 && config.delay_hours <= MAX_DELAY_HOURS,
+          "Delay must be 1 to {} hours.", MAX_DELAY_HOURS
+      );
+      self.withdrawal_timelock_config = config;
+  }
+
+  /// Runs timelocked withdrawal `withdrawal_id` once its delay has passed. Anyone can
+  /// trigger it; the NEAR is always sent to the account holder. Large withdrawals re-read a
+  /// stale KYC tier from the DID registry first.
+  pub fn execute_timelocked_withdrawal(&mut self, withdrawal_id: u64) -> PromiseOrValue<bool> {
+      self.assert_not_paused(Feature::Withdrawals);
+      let pending = self.timelocked_withdrawals.get(&withdrawal_id)
+          .unwrap_or_else(|| env::panic_str("Timelocked withdrawal not found."))
+          .clone();
+      assert!(env::block_timestamp() >= pending.executable_at, "Withdrawal is still timelocked.");
+      self.assert_no_recovery_hold(&pending.account_id);
+      let initial_storage = env::storage_usage();
+      self.internal_remove_timelocked_withdrawal(withdrawal_id);
+      self.internal_charge_storage(&pending.account_id, initial_storage);
+      BankEvent::TimelockedWithdrawalExecuted {
+          id: withdrawal_id,
+          account_id: pending.account_id.clone(),
+          amount: pending.amount,
+          executed_by: env::predecessor_account_id(),
+      }.emit();
+      let TimelockedWithdrawal { account_id, amount, envelope, memo, reference, .. } = pending;
+      if self.internal_kyc_check_needed(&account_id, amount) {
+          let action = KycPendingAction::Withdraw { amount, envelope, memo, reference };
+          return self.internal_resolve_kyc(account_id, action).into();
+      }
+      self.internal_withdraw(&account_id, amount, envelope, memo, reference)
+  }
+
+  /// Cancels timelocked withdrawal `withdrawal_id` before it runs. Account holder or
+  /// compliance admin only.
+  pub fn cancel_timelocked_withdrawal(&mut self, withdrawal_id: u64) {
+      let caller_id = env::predecessor_account_id();
+      let pending = self.timelocked_withdrawals.get(&withdrawal_id)
+          .unwrap_or_else(|| env::panic_str("Timelocked withdrawal not found."))
+          .clone();
+      assert!(
+          caller_id == pending.account_id || self.has_role(caller_id.clone(), Role::ComplianceAdmin),
+          "Only the account holder or compliance can cancel this withdrawal."
+      );
+      let initial_storage = env::storage_usage();
+      self.internal_remove_timelocked_withdrawal(withdrawal_id);
+      self.internal_charge_storage(&pending.account_id, initial_storage);
+      BankEvent::TimelockedWithdrawalCancelled { id: withdrawal_id, account_id: pending.account_id, cancelled_by: caller_id }.emit();
+  }
+
+  /// View function.
+  pub fn get_withdrawal_timelock(&self) -> WithdrawalTimelockConfig {
+      self.withdrawal_timelock_config.clone()
+  }
+
+  /// View function.
+  pub fn get_timelocked_withdrawal(&self, withdrawal_id: u64) -> Option<TimelockedWithdrawal> {
+      self.timelocked_withdrawals.get(&withdrawal_id).cloned()
+  }
+
+  /// Lists the whole pending queue, oldest first.
+  /// View function.
+  pub fn get_timelocked_withdrawals(&self, from_index: Option<u64>, limit: Option<u64>) -> Vec<TimelockedWithdrawal> {
+      self.timelocked_withdrawals.values()
+          .skip(from_index.unwrap_or(0) as usize)
+          .take(limit.unwrap_or(DEFAULT_PAGE_LIMIT) as usize)
+          .cloned()
+          .collect()
+  }
+
+  /// View function.
+  pub fn get_account_timelocked_withdrawals(&self, account_id: AccountId) -> Vec<TimelockedWithdrawal> {
+      self.timelocked_withdrawals_by_account.get(&account_id)
+          .map(|ids| ids.iter().filter_map(|id| self.timelocked_withdrawals.get(id).cloned()).collect())
+          .unwrap_or_default()
+  }
+}
+
+impl BioCrypticBankCore {
+  /// True if a withdrawal of `amount` must wait out the timelock.
+  pub(crate) fn internal_withdrawal_timelock_needed(&self, amount: NearToken) -> bool {
+      self.withdrawal_timelock_config.threshold.is_some_and(|threshold| amount > threshold)
+  }
+
+  /// Queues a withdrawal behind the timelock. Returns the queued withdrawal's id.
+  pub(crate) fn internal_timelock_withdrawal(
+      &mut self,
+      account_id: AccountId,
+      amount: NearToken,
+      envelope: Option<String>,
+      memo: Option<String>,
+      reference: Option<String>,
+  ) -> u64 {
+      let initial_storage = env::storage_usage();
+      let ids = self.timelocked_withdrawals_by_account.entry(account_id.clone()).or_default();
+      assert!(
+          ids.len() < MAX_TIMELOCKED_WITHDRAWALS_PER_ACCOUNT,
+          "Cannot exceed {} timelocked withdrawals.", MAX_TIMELOCKED_WITHDRAWALS_PER_ACCOUNT
+      );
+      let id = self.next_timelocked_withdrawal_id;
+      self.next_timelocked_withdrawal_id += 1;
+      ids.push(id);
+      let now = env::block_timestamp();
+      let executable_at = now + self.withdrawal_timelock_config.delay_hours as u64 * NANOS_PER_HOUR;
+      self.timelocked_withdrawals.insert(id, TimelockedWithdrawal {
+          id,
+          account_id: account_id.clone(),
+          amount,
+          envelope,
+          memo,
+          reference,
+          requested_at: now,
+          executable_at,
+      });
+      self.internal_charge_storage(&account_id, initial_storage);
+      BankEvent::WithdrawalTimelocked { id, account_id, amount, executable_at }.emit();
+      id
+  }
+
+  fn internal_remove_timelocked_withdrawal(&mut self, withdrawal_id: u64) {
+      let Some(pending) = self.timelocked_withdrawals.remove(&withdrawal_id) else { return };
+      if let Some(ids) = self.timelocked_withdrawals_by_account.get_mut(&pending.account_id) {
+          ids.retain(|existing| *existing != withdrawal_id);
+          if ids.is_empty() {
+              self.timelocked_withdrawals_by_account.remove(&pending.account_id);
+          }
+      }
+  }
+}
@@ -0,0 +1,157 @@
+// services/blockchain/near-rs/core-banking/src/wnear.rs
+use near_sdk::{near, env, ext_contract, AccountId, Gas, NearToken, Promise, PromiseError};
+use near_sdk::json_types::U128;
+
+use crate::ledger::{EntryDirection, EntryKind};
+use crate::operation_locks::LockedOperation;
+use crate::pause::Feature;
+use crate::{BankEvent, BioCrypticBankCore, BioCrypticBankCoreExt};
+
+const GAS_FOR_WRAP: Gas = Gas::from_tgas(10);
+const GAS_FOR_WRAP_CALLBACK: Gas = Gas::from_tgas(15);
+
+/// Interface of the wrapped NEAR contract (e.g. `wrap.near`).
+#[ext_contract(ext_wnear)]
+#[allow(dead_code)]
+trait WrappedNear {
+  fn near_deposit(&mut self);
+  fn near_withdraw(&mut self, amount: U128);
+}
+
+/// An account's NEAR held natively and as wNEAR.
+#[near(serializers = [json])]
+#[derive(Debug, PartialEq)]
+pub struct NearHoldings {
+  pub near: NearToken,
+  pub wnear: NearToken,
+  pub total: NearToken,
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Sets the wrapped NEAR contract and whitelists it so wNEAR deposits are accepted through
+  /// `ft_on_transfer`. The bank must hold a storage registration on that contract. Owner only.
+  pub fn set_wnear_contract(&mut self, wnear_contract_id: AccountId) {
+      self.assert_owner();
+      self.token_whitelist.insert(wnear_contract_id.clone());
+      self.wnear_contract_id = Some(wnear_contract_id);
+  }
+
+  /// Converts `amount` of the caller's NEAR balance into wNEAR held in its token balance.
+  /// The NEAR is restored if wrapping fails.
+  pub fn wrap_near(&mut self, amount: NearToken) -> Promise {
+      self.assert_not_paused(Feature::Withdrawals);
+      let initial_storage = env::storage_usage();
+      let account_id = env::predecessor_account_id();
+      let wnear_id = self.internal_wnear_contract();
+      assert!(amount.as_yoctonear() > 0, "Amount must be greater than 0.");
+      self.assert_not_dormant(&account_id);
+      self.assert_no_recovery_hold(&account_id);
+      self.internal_debit(&account_id, amount);
+      self.internal_remove_liabilities(amount);
+      self.internal_record_contract_outflow(amount);
+      self.internal_record_entry(&account_id, EntryKind::WnearConversion, EntryDirection::Debit, amount, None, None);
+      self.internal_charge_storage(&account_id, initial_storage);
+      let lock_id = self.internal_lock_account(&account_id, LockedOperation::WnearConversion);
+
+      ext_wnear::ext(wnear_id.clone())
+          .with_attached_deposit(amount)
+          .with_static_gas(GAS_FOR_WRAP)
+          .near_deposit()
+          .then(
+              Self::ext(env::current_account_id())
+                  .with_static_gas(GAS_FOR_WRAP_CALLBACK)
+                  .on_near_wrapped(account_id, wnear_id, amount, Some(lock_id))
+          )
+  }
+
+  /// Callback crediting the minted wNEAR, or restoring the NEAR if `near_deposit` failed.
+  /// Releases the account's lock.
+  #[private]
+  pub fn on_near_wrapped(
+      &mut self,
+      account_id: AccountId,
+      wnear_id: AccountId,
+      amount: NearToken,
+      lock_id: Option<u64>,
+      #[callback_result] result: Result<(), PromiseError>,
+  ) -> bool {
+      self.internal_unlock_account(&account_id, lock_id);
+      if result.is_err() {
+          self.internal_credit(&account_id, amount);
+          self.internal_add_liabilities(amount);
+          self.internal_record_entry(&account_id, EntryKind::WnearConversion, EntryDirection::Credit, amount, None, None);
+          BankEvent::WnearConversionFailed { account_id, amount, wrap: true }.emit();
+          return false;
+      }
+      self.internal_ft_credit(&account_id, &wnear_id, amount.as_yoctonear());
+      BankEvent::NearWrapped { account_id, amount }.emit();
+      true
+  }
+
+  /// Converts `amount` of the caller's wNEAR back into its NEAR balance. The wNEAR is
+  /// restored if unwrapping fails.
+  pub fn unwrap_near(&mut self, amount: NearToken) -> Promise {
+      self.assert_not_paused(Feature::Deposits);
+      let account_id = env::predecessor_account_id();
+      let wnear_id = self.internal_wnear_contract();
+      assert!(amount.as_yoctonear() > 0, "Amount must be greater than 0.");
+      self.assert_registered(&account_id);
+      self.internal_ft_debit(&account_id, &wnear_id, amount.as_yoctonear());
+      let lock_id = self.internal_lock_account(&account_id, LockedOperation::WnearConversion);
+
+      ext_wnear::ext(wnear_id.clone())
+          .with_attached_deposit(NearToken::from_yoctonear(1))
+          .with_static_gas(GAS_FOR_WRAP)
+          .near_withdraw(U128(amount.as_yoctonear()))
+          .then(
+              Self::ext(env::current_account_id())
+                  .with_static_gas(GAS_FOR_WRAP_CALLBACK)
+                  .on_near_unwrapped(account_id, wnear_id, amount, Some(lock_id))
+          )
+  }
+
+  /// Callback crediting the released NEAR, or restoring the wNEAR if `near_withdraw` failed.
+  /// Releases the account's lock.
+  #[private]
+  pub fn on_near_unwrapped(
+      &mut self,
+      account_id: AccountId,
+      wnear_id: AccountId,
+      amount: NearToken,
+      lock_id: Option<u64>,
+      #[callback_result] result: Result<(), PromiseError>,
+  ) -> bool {
+      self.internal_unlock_account(&account_id, lock_id);
+      if result.is_err() {
+          self.internal_ft_credit(&account_id, &wnear_id, amount.as_yoctonear());
+          BankEvent::WnearConversionFailed { account_id, amount, wrap: false }.emit();
+          return false;
+      }
+      self.internal_credit(&account_id, amount);
+      self.internal_add_liabilities(amount);
+      self.internal_record_entry(&account_id, EntryKind::WnearConversion, EntryDirection::Credit, amount, None, None);
+      BankEvent::NearUnwrapped { account_id, amount }.emit();
+      true
+  }
+
+  /// View function.
+  pub fn get_wnear_contract(&self) -> Option<AccountId> {
+      self.wnear_contract_id.clone()
+  }
+
+  /// View function.
+  pub fn get_near_holdings(&self, account_id: AccountId) -> NearHoldings {
+      let near = self.get_balance(account_id.clone());
+      let wnear = self.wnear_contract_id.clone().map_or(NearToken::from_yoctonear(0), |wnear_id| {
+          NearToken::from_yoctonear(self.get_ft_balance(account_id, wnear_id).0)
+      });
+      NearHoldings { near, wnear, total: near.saturating_add(wnear) }
+  }
+}
+
+impl BioCrypticBankCore {
+  fn internal_wnear_contract(&self) -> AccountId {
+      self.wnear_contract_id.clone().unwrap_or_else(|| env::panic_str("No wNEAR contract is configured."))
+  }
+}
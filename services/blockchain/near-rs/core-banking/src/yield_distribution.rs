@@ -0,0 +1,162 @@
+// services/blockchain/near-rs/core-banking/src/yield_distribution.rs
+use near_sdk::{near, env, AccountId, NearToken};
+
+use crate::ledger::{EntryDirection, EntryKind};
+use crate::savings::{SavingsAccount, BPS_DENOMINATOR, INDEX_SCALE};
+use crate::{mul_div, BankEvent, BioCrypticBankCore, BioCrypticBankCoreExt};
+
+const NANOS_PER_SECOND: u64 = 1_000_000_000;
+
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct YieldConfig {
+  /// Share of lending interest and staking rewards set aside for savers; the rest goes to
+  /// the treasury.
+  pub depositor_share_bps: u16,
+  /// Minimum time between two distributions.
+  pub min_distribution_interval_sec: u64,
+}
+
+impl Default for YieldConfig {
+  fn default() -> Self {
+      Self { depositor_share_bps: 0, min_distribution_interval_sec: 24 * 60 * 60 }
+  }
+}
+
+#[near(serializers = [json])]
+#[derive(Debug, PartialEq)]
+pub struct YieldStateView {
+  pub config: YieldConfig,
+  /// Revenue set aside for savers but not yet distributed.
+  pub undistributed: NearToken,
+  /// Yield paid per savings share to date, scaled by `INDEX_SCALE`.
+  pub yield_per_share: String,
+  pub last_distribution_at: u64,
+}
+
+#[near]
+impl BioCrypticBankCore {
+  /// Updates the depositor revenue share and distribution cadence. Owner only.
+  pub fn set_yield_config(&mut self, config: YieldConfig) {
+      self.assert_owner();
+      assert!(config.depositor_share_bps as u128 <= BPS_DENOMINATOR, "Depositor share cannot exceed 100%.");
+      self.yield_config = config;
+  }
+
+  /// Allocates the undistributed yield to every savings share pro rata by advancing the
+  /// global accumulator. Permissionless crank, limited to one run per interval. Returns the
+  /// amount distributed.
+  pub fn distribute_yield(&mut self) -> NearToken {
+      let now = env::block_timestamp();
+      let next_at = self.last_yield_distribution_at + self.yield_config.min_distribution_interval_sec * NANOS_PER_SECOND;
+      assert!(now >= next_at, "Yield was distributed too recently.");
+      let total_shares = self.savings_pool.total_shares;
+      assert!(total_shares > 0, "No savings to distribute to.");
+
+      let increment = mul_div(self.undistributed_yield.as_yoctonear(), INDEX_SCALE, total_shares);
+      // Rounding dust stays undistributed for the next run.
+      let distributed = NearToken::from_yoctonear(mul_div(increment, total_shares, INDEX_SCALE));
+      self.yield_per_share += increment;
+      self.undistributed_yield = self.undistributed_yield.saturating_sub(distributed);
+      self.last_yield_distribution_at = now;
+      BankEvent::YieldDistributed { amount: distributed, yield_per_share: self.yield_per_share.to_string() }.emit();
+      distributed
+  }
+
+  /// Pays the caller's pending yield into their checking balance. Returns the amount paid.
+  pub fn claim_yield(&mut self) -> NearToken {
+      let initial_storage = env::storage_usage();
+      let account_id = env::predecessor_account_id();
+      let amount = self.internal_take_pending_yield(&account_id);
+      self.internal_credit(&account_id, amount);
+      self.internal_record_entry(&account_id, EntryKind::Yield, EntryDirection::Credit, amount, None, None);
+      self.internal_charge_storage(&account_id, initial_storage);
+      BankEvent::YieldClaimed { account_id, amount, compounded: false }.emit();
+      amount
+  }
+
+  /// Reinvests `account_id`'s (default: caller's) pending yield into savings shares. Anyone
+  /// may compound an account that opted into auto-compounding. Returns the amount reinvested.
+  pub fn compound_yield(&mut self, account_id: Option<AccountId>) -> NearToken {
+      let caller = env::predecessor_account_id();
+      let account_id = account_id.unwrap_or_else(|| caller.clone());
+      if account_id != caller {
+          assert!(
+              self.savings_accounts.get(&account_id).is_some_and(|account| account.auto_compound),
+              "Account has not opted into auto-compounding."
+          );
+      }
+      let amount = self.internal_take_pending_yield(&account_id);
+      let index = self.internal_accrue_savings();
+      let shares = mul_div(amount.as_yoctonear(), INDEX_SCALE, index);
+      let yield_per_share = self.yield_per_share;
+      self.savings_accounts.entry(account_id.clone())
+          .or_insert_with(|| SavingsAccount { yield_per_share_paid: yield_per_share, ..Default::default() })
+          .shares += shares;
+      self.savings_pool.total_shares += shares;
+      self.internal_remove_liabilities(amount);
+      BankEvent::YieldClaimed { account_id, amount, compounded: true }.emit();
+      amount
+  }
+
+  /// Lets keepers compound the caller's yield into savings on their behalf.
+  pub fn set_yield_auto_compound(&mut self, enabled: bool) {
+      let account_id = env::predecessor_account_id();
+      self.savings_accounts.get_mut(&account_id)
+          .unwrap_or_else(|| env::panic_str("No savings account found."))
+          .auto_compound = enabled;
+  }
+
+  /// Yield distributed to `account_id` and not yet claimed or compounded.
+  /// View function.
+  pub fn get_pending_yield(&self, account_id: AccountId) -> NearToken {
+      self.savings_accounts.get(&account_id).map_or(NearToken::from_yoctonear(0), |account| {
+          let unsettled = mul_div(account.shares, self.yield_per_share - account.yield_per_share_paid, INDEX_SCALE);
+          NearToken::from_yoctonear(account.pending_yield + unsettled)
+      })
+  }
+
+  /// View function.
+  pub fn get_yield_state(&self) -> YieldStateView {
+      YieldStateView {
+          config: self.yield_config.clone(),
+          undistributed: self.undistributed_yield,
+          yield_per_share: self.yield_per_share.to_string(),
+          last_distribution_at: self.last_yield_distribution_at,
+      }
+  }
+}
+
+impl BioCrypticBankCore {
+  /// Splits interest or rewards earned by the bank between savers and the treasury. `amount`
+  /// must already be counted as owed to users; the treasury share stops being a liability.
+  pub(crate) fn internal_collect_revenue(&mut self, amount: NearToken) {
+      let depositor_share = NearToken::from_yoctonear(mul_div(
+          amount.as_yoctonear(), self.yield_config.depositor_share_bps as u128, BPS_DENOMINATOR,
+      ));
+      self.undistributed_yield = self.undistributed_yield.saturating_add(depositor_share);
+      self.internal_remove_liabilities(amount.saturating_sub(depositor_share));
+      self.internal_collect_fee(amount.saturating_sub(depositor_share));
+  }
+
+  /// Moves yield accrued on `account_id`'s shares since its last settlement into its
+  /// pending yield. Must run before the account's shares change.
+  pub(crate) fn internal_settle_yield(&mut self, account_id: &AccountId) {
+      let yield_per_share = self.yield_per_share;
+      let Some(account) = self.savings_accounts.get_mut(account_id) else { return };
+      account.pending_yield += mul_div(account.shares, yield_per_share - account.yield_per_share_paid, INDEX_SCALE);
+      account.yield_per_share_paid = yield_per_share;
+  }
+
+  fn internal_take_pending_yield(&mut self, account_id: &AccountId) -> NearToken {
+      self.internal_settle_yield(account_id);
+      let account = self.savings_accounts.get_mut(account_id)
+          .unwrap_or_else(|| env::panic_str("No savings account found."));
+      assert!(account.pending_yield > 0, "No pending yield.");
+      let amount = std::mem::take(&mut account.pending_yield);
+      if account.shares == 0 {
+          self.savings_accounts.remove(account_id);
+      }
+      NearToken::from_yoctonear(amount)
+  }
+}
@@ -37,8 +37,7 @@ near deploy "$DID_CONTRACT_ACCOUNT_ID" "$DID_WASM_PATH" \
 echo "DID Management Contract deployed to: $DID_CONTRACT_ACCOUNT_ID"
 
 # 2. Deploy Account Recovery Contract (bcb-rec)
-# This contract's `new` function takes no arguments.
-# NEAR CLI will automatically call 'new' on deploy if no initFunction/initArgs are provided.
+# This contract's `new` function takes the owner account; init.sh initializes it.
 echo "\nDeploying Account Recovery Contract..."
 RECOVERY_CONTRACT_ACCOUNT_ID="bcb-rec.testnet"
 RECOVERY_WASM_PATH="./res/bcb_acc.wasm"
@@ -77,8 +76,35 @@ near call "$CORE_BANKING_CONTRACT_ACCOUNT_ID" new \
 
 echo "Core Banking Contract initialized."
 
+# 4. Deploy Governance Contract (bcb-gov) and hand it the parameter setters of the other three.
+echo "\nDeploying Governance Contract..."
+GOVERNANCE_CONTRACT_ACCOUNT_ID="bcb-gov.testnet"
+GOVERNANCE_WASM_PATH="./res/bcb_gov.wasm"
+if [ ! -f "$GOVERNANCE_WASM_PATH" ]; then
+    echo "Error: WASM file not found for Governance Contract: $GOVERNANCE_WASM_PATH"
+    echo "Please ensure 'build.sh' ran successfully and generated the WASM files."
+    exit 1
+fi
+echo "Deploying $GOVERNANCE_WASM_PATH to $GOVERNANCE_CONTRACT_ACCOUNT_ID..."
+near deploy "$GOVERNANCE_CONTRACT_ACCOUNT_ID" "$GOVERNANCE_WASM_PATH" \
+  --accountId "$DEPLOYER_ACCOUNT_ID"
+
+echo "Initializing Governance Contract..."
+near call "$GOVERNANCE_CONTRACT_ACCOUNT_ID" new \
+  "{\"bank_id\": \"$CORE_BANKING_CONTRACT_ACCOUNT_ID\", \"recovery_id\": \"$RECOVERY_CONTRACT_ACCOUNT_ID\", \"did_id\": \"$DID_CONTRACT_ACCOUNT_ID\", \"config\": {\"proposal_stake\": \"10000000000000000000000000\", \"voting_period_sec\": 259200, \"timelock_sec\": 172800, \"quorum_bps\": 2000}}" \
+  --accountId "$DEPLOYER_ACCOUNT_ID"
+
+for CONTRACT_ACCOUNT_ID in "$CORE_BANKING_CONTRACT_ACCOUNT_ID" "$RECOVERY_CONTRACT_ACCOUNT_ID" "$DID_CONTRACT_ACCOUNT_ID"; do
+    near call "$CONTRACT_ACCOUNT_ID" set_governance \
+      "{\"governance_id\": \"$GOVERNANCE_CONTRACT_ACCOUNT_ID\"}" \
+      --accountId "$DEPLOYER_ACCOUNT_ID"
+done
+
+echo "Governance Contract deployed to: $GOVERNANCE_CONTRACT_ACCOUNT_ID"
+
 echo "\n=== NEAR Rust Deployment Complete ==="
 echo "Please record these deployed contract IDs for your backend and client configurations:"
 echo "DID Management: $DID_CONTRACT_ACCOUNT_ID"
 echo "Account Recovery: $RECOVERY_CONTRACT_ACCOUNT_ID"
 echo "Core Banking: $CORE_BANKING_CONTRACT_ACCOUNT_ID"
+echo "Governance: $GOVERNANCE_CONTRACT_ACCOUNT_ID"
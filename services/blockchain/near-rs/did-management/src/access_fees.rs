@@ -0,0 +1,75 @@
+// services/blockchain/near-rs/did-management/src/access_fees.rs
+use near_sdk::{near, env, AccountId, Gas, NearToken, Promise, PromiseError};
+
+use crate::{DidEvent, DidRegistry, DidRegistryExt};
+
+const GAS_FOR_FEE_WITHDRAW_CALLBACK: Gas = Gas::from_tgas(10);
+
+#[near]
+impl DidRegistry {
+    /// Sets the fee verifiers must attach to `verify_credential` for one of the caller's
+    /// credentials. Pass `None` to make verification free again.
+    pub fn set_credential_access_fee(&mut self, vc_hash: String, fee: Option<NearToken>) {
+        let signer_id = env::predecessor_account_id();
+        let credential = self.internal_get_own_credential_mut(&signer_id, &vc_hash);
+        credential.access_fee = fee.filter(|f| !f.is_zero());
+
+        DidEvent::CredentialAccessFeeSet { account_id: signer_id, vc_hash, fee }.emit();
+    }
+
+    /// Withdraws accrued access fees to the caller. Withdraws the whole balance when
+    /// `amount` is omitted. The balance is restored if the transfer fails.
+    pub fn withdraw_access_fees(&mut self, amount: Option<NearToken>) -> Promise {
+        let signer_id = env::predecessor_account_id();
+        let balance = self.get_access_fee_balance(signer_id.clone());
+        let amount = amount.unwrap_or(balance);
+        assert!(!amount.is_zero(), "Withdrawal amount must be greater than 0.");
+        assert!(balance >= amount, "Insufficient access fee balance.");
+
+        self.set_access_fee_balance(&signer_id, balance.saturating_sub(amount));
+        env::log_str(&format!("Withdrawing {} yoctoNEAR of access fees for {}", amount.as_yoctonear(), signer_id));
+
+        Promise::new(signer_id.clone())
+            .transfer(amount)
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_FEE_WITHDRAW_CALLBACK)
+                    .on_access_fees_withdrawn(signer_id, amount)
+            )
+    }
+
+    /// Callback restoring the access fee balance when a withdrawal transfer fails.
+    #[private]
+    pub fn on_access_fees_withdrawn(
+        &mut self,
+        account_id: AccountId,
+        amount: NearToken,
+        #[callback_result] result: Result<(), PromiseError>,
+    ) {
+        if result.is_err() {
+            self.internal_credit_access_fee(&account_id, amount);
+            env::log_str(&format!("Access fee withdrawal failed for {}. Balance restored.", account_id));
+        }
+    }
+
+    /// Retrieves the withdrawable access fee balance of a given AccountId.
+    /// View function.
+    pub fn get_access_fee_balance(&self, account_id: AccountId) -> NearToken {
+        self.access_fee_balances.get(&account_id).copied().unwrap_or(NearToken::from_yoctonear(0))
+    }
+}
+
+impl DidRegistry {
+    pub(crate) fn internal_credit_access_fee(&mut self, account_id: &AccountId, amount: NearToken) {
+        let balance = self.get_access_fee_balance(account_id.clone());
+        self.set_access_fee_balance(account_id, balance.saturating_add(amount));
+    }
+
+    fn set_access_fee_balance(&mut self, account_id: &AccountId, balance: NearToken) {
+        if balance.is_zero() {
+            self.access_fee_balances.remove(account_id);
+        } else {
+            self.access_fee_balances.insert(account_id.clone(), balance);
+        }
+    }
+}
@@ -0,0 +1,304 @@
+// services/blockchain/near-rs/did-management/src/challenges.rs
+use near_sdk::{near, env, AccountId, NearToken, Promise};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+
+use crate::{CredentialRecord, CredentialRevocation, DidEvent, DidRegistry, DidRegistryExt, RevocationReason};
+
+const MIN_CHALLENGE_BOND: NearToken = NearToken::from_near(1);
+const CHALLENGE_RESPONSE_WINDOW_DAYS: u64 = 7;
+
+#[derive(
+    Debug,
+    PartialEq,
+    near_sdk::serde::Serialize,
+    near_sdk::serde::Deserialize,
+    BorshDeserialize,
+    BorshSerialize,
+    Clone,
+    Copy
+)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(rename_all = "snake_case")]
+pub enum ChallengeStatus {
+    /// Waiting for the issuer to respond.
+    Open,
+    /// The issuer responded; awaiting an arbiter ruling.
+    Contested,
+    /// The issuer missed the response window; the credential is suspended pending arbitration.
+    Suspended,
+    /// An arbiter found the credential fraudulent; it has been revoked.
+    Upheld,
+    /// An arbiter found the challenge unfounded; the bond went to the issuer.
+    Dismissed,
+}
+
+#[derive(
+    Debug,
+    PartialEq,
+    near_sdk::serde::Serialize,
+    near_sdk::serde::Deserialize,
+    BorshDeserialize,
+    BorshSerialize,
+    Clone
+)]
+#[serde(crate = "near_sdk::serde")]
+pub struct CredentialChallenge {
+    pub challenge_id: u64,
+    pub subject_id: AccountId,
+    pub vc_hash: String,
+    pub challenger_id: AccountId,
+    pub bond: NearToken,
+    pub evidence_hash: String,
+    pub response_hash: Option<String>,
+    pub opened_at: u64,
+    pub response_deadline: u64,
+    pub status: ChallengeStatus,
+}
+
+#[near]
+impl DidRegistry {
+    /// Registers an arbiter who rules on contested credential challenges. Owner or
+    /// governance only.
+    pub fn add_arbiter(&mut self, arbiter_id: AccountId) {
+        self.assert_owner_or_governance();
+        assert!(self.arbiters.insert(arbiter_id.clone()), "Account is already an arbiter.");
+        env::log_str(&format!("Arbiter added: {}", arbiter_id));
+    }
+
+    /// Removes an arbiter. Owner or governance only.
+    pub fn remove_arbiter(&mut self, arbiter_id: AccountId) {
+        self.assert_owner_or_governance();
+        assert!(self.arbiters.remove(&arbiter_id), "Account is not an arbiter.");
+        env::log_str(&format!("Arbiter removed: {}", arbiter_id));
+    }
+
+    /// Challenges a credential by posting a bond of at least 1 NEAR and an evidence hash.
+    /// The issuer has 7 days to respond, otherwise the credential is suspended.
+    /// Returns the challenge id.
+    #[payable]
+    pub fn challenge_credential(&mut self, subject_id: AccountId, vc_hash: String, evidence_hash: String) -> u64 {
+        let challenger_id = env::predecessor_account_id();
+        let bond = env::attached_deposit();
+        assert!(bond >= MIN_CHALLENGE_BOND, "Challenge bond must be at least {} yoctoNEAR.", MIN_CHALLENGE_BOND.as_yoctonear());
+        assert!(!evidence_hash.is_empty(), "Evidence hash must not be empty.");
+        assert!(self.is_active(&subject_id), "DID not found or deactivated.");
+
+        let challenge_id = self.next_challenge_id;
+        let credential = self.internal_get_credential_mut(&subject_id, &vc_hash);
+        assert!(credential.revocation.is_none(), "Credential is already revoked.");
+        assert!(credential.challenge_id.is_none(), "Credential already has an unresolved challenge.");
+        assert!(credential.issuer != challenger_id, "Issuers cannot challenge their own credentials.");
+        credential.challenge_id = Some(challenge_id);
+
+        let now = env::block_timestamp();
+        self.challenges.insert(challenge_id, CredentialChallenge {
+            challenge_id,
+            subject_id: subject_id.clone(),
+            vc_hash: vc_hash.clone(),
+            challenger_id: challenger_id.clone(),
+            bond,
+            evidence_hash: evidence_hash.clone(),
+            response_hash: None,
+            opened_at: now,
+            response_deadline: now + CHALLENGE_RESPONSE_WINDOW_DAYS * 24 * 60 * 60 * 1_000_000_000,
+            status: ChallengeStatus::Open,
+        });
+        self.next_challenge_id += 1;
+
+        DidEvent::CredentialChallenged { challenge_id, account_id: subject_id, vc_hash, challenger_id, evidence_hash }.emit();
+        challenge_id
+    }
+
+    /// Lets the credential's issuer contest an open challenge before the deadline.
+    pub fn respond_to_challenge(&mut self, challenge_id: u64, response_hash: String) {
+        let caller_id = env::predecessor_account_id();
+        assert!(!response_hash.is_empty(), "Response hash must not be empty.");
+        let challenge = self.challenges.get(&challenge_id)
+            .unwrap_or_else(|| env::panic_str("Challenge not found."))
+            .clone();
+        assert_eq!(challenge.status, ChallengeStatus::Open, "Challenge is not open.");
+        assert!(env::block_timestamp() <= challenge.response_deadline, "Response window has closed.");
+        let issuer = self.internal_get_credential_mut(&challenge.subject_id, &challenge.vc_hash).issuer.clone();
+        assert_eq!(issuer, caller_id, "Only the issuer can respond to this challenge.");
+
+        let challenge = self.challenges.get_mut(&challenge_id).unwrap();
+        challenge.response_hash = Some(response_hash.clone());
+        challenge.status = ChallengeStatus::Contested;
+
+        DidEvent::ChallengeResponded { challenge_id, issuer, response_hash }.emit();
+    }
+
+    /// Suspends the challenged credential once the response window has passed without
+    /// an issuer response. Callable by anyone.
+    pub fn enforce_challenge_deadline(&mut self, challenge_id: u64) {
+        let challenge = self.challenges.get_mut(&challenge_id)
+            .unwrap_or_else(|| env::panic_str("Challenge not found."));
+        assert_eq!(challenge.status, ChallengeStatus::Open, "Challenge is not open.");
+        assert!(env::block_timestamp() > challenge.response_deadline, "Response window has not closed yet.");
+        challenge.status = ChallengeStatus::Suspended;
+        let (subject_id, vc_hash) = (challenge.subject_id.clone(), challenge.vc_hash.clone());
+
+        if let Some(credential) = self.internal_find_credential_mut(&subject_id, &vc_hash) {
+            credential.suspended = true;
+        }
+        DidEvent::CredentialSuspended { challenge_id, account_id: subject_id, vc_hash }.emit();
+    }
+
+    /// Rules on a contested or suspended challenge. Arbiter only.
+    /// Upholding revokes the credential for fraud and returns the bond to the challenger;
+    /// dismissing reinstates the credential and awards the bond to the issuer. If the
+    /// subject has since removed the credential, the bond is returned to the challenger.
+    pub fn resolve_challenge(&mut self, challenge_id: u64, uphold: bool) {
+        let arbiter_id = env::predecessor_account_id();
+        assert!(self.arbiters.contains(&arbiter_id), "Caller is not an arbiter.");
+        let challenge = self.challenges.get_mut(&challenge_id)
+            .unwrap_or_else(|| env::panic_str("Challenge not found."));
+        let deadline_passed = env::block_timestamp() > challenge.response_deadline;
+        assert!(
+            matches!(challenge.status, ChallengeStatus::Contested | ChallengeStatus::Suspended)
+                || (challenge.status == ChallengeStatus::Open && deadline_passed),
+            "Challenge cannot be resolved yet."
+        );
+        challenge.status = if uphold { ChallengeStatus::Upheld } else { ChallengeStatus::Dismissed };
+        let challenge = challenge.clone();
+
+        let mut bond_recipient = challenge.challenger_id.clone();
+        let mut newly_revoked = false;
+        let now = env::block_timestamp();
+        if let Some(credential) = self.internal_find_credential_mut(&challenge.subject_id, &challenge.vc_hash) {
+            credential.challenge_id = None;
+            credential.suspended = false;
+            if !uphold {
+                bond_recipient = credential.issuer.clone();
+            } else if credential.revocation.is_none() {
+                credential.revocation = Some(CredentialRevocation {
+                    reason: RevocationReason::Fraud,
+                    revoked_by: arbiter_id.clone(),
+                    revoked_at: now,
+                    replacement_vc_hash: None,
+                });
+                newly_revoked = true;
+            }
+        }
+        if newly_revoked {
+            self.record_revocation(RevocationReason::Fraud);
+        }
+        Promise::new(bond_recipient.clone()).transfer(challenge.bond);
+
+        DidEvent::ChallengeResolved {
+            challenge_id,
+            account_id: challenge.subject_id,
+            vc_hash: challenge.vc_hash,
+            arbiter_id,
+            upheld: uphold,
+            bond_recipient,
+        }.emit();
+    }
+
+    /// Retrieves a credential challenge by id.
+    /// View function.
+    pub fn get_challenge(&self, challenge_id: u64) -> Option<CredentialChallenge> {
+        self.challenges.get(&challenge_id).cloned()
+    }
+
+    /// Lists the registered arbiters.
+    /// View function.
+    pub fn get_arbiters(&self) -> Vec<AccountId> {
+        self.arbiters.iter().cloned().collect()
+    }
+}
+
+impl DidRegistry {
+    /// Returns true if the credential is suspended, either explicitly or because its
+    /// challenge response window lapsed without an answer.
+    pub(crate) fn is_credential_suspended(&self, credential: &CredentialRecord) -> bool {
+        credential.suspended
+            || credential.challenge_id
+                .and_then(|id| self.challenges.get(&id))
+                .is_some_and(|c| c.status == ChallengeStatus::Open && env::block_timestamp() > c.response_deadline)
+    }
+
+    /// Returns a credential of any DID for modification, without touching document metadata.
+    fn internal_find_credential_mut(&mut self, account_id: &AccountId, vc_hash: &str) -> Option<&mut CredentialRecord> {
+        self.dids.get_mut(account_id)?
+            .verifiable_credentials
+            .iter_mut()
+            .find(|c| c.vc_hash == vc_hash)
+    }
+
+    fn internal_get_credential_mut(&mut self, account_id: &AccountId, vc_hash: &str) -> &mut CredentialRecord {
+        self.internal_find_credential_mut(account_id, vc_hash)
+            .unwrap_or_else(|| env::panic_str("Verifiable credential not found for this DID."))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use near_sdk::test_utils::{accounts, VMContextBuilder};
+    use near_sdk::testing_env;
+
+    use super::*;
+    use crate::CredentialInput;
+
+    const DAY: u64 = 24 * 60 * 60 * 1_000_000_000;
+
+    fn call_as(account_id: AccountId, attached: NearToken, block_timestamp: u64) {
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(account_id)
+            .attached_deposit(attached)
+            .block_timestamp(block_timestamp)
+            .build());
+    }
+
+    /// Registry owned by `accounts(0)` with `accounts(3)` as arbiter and a DID for
+    /// `accounts(1)` holding the self-issued credential "vc-1".
+    fn registry_with_credential() -> DidRegistry {
+        call_as(accounts(0), NearToken::from_yoctonear(0), 0);
+        let mut contract = DidRegistry::new();
+        contract.add_arbiter(accounts(3));
+        call_as(accounts(1), NearToken::from_near(1), 0);
+        contract.register_did(
+            Some(vec![CredentialInput {
+                vc_hash: "vc-1".to_string(),
+                credential_type: None,
+                tags: None,
+                bound: None,
+                expires_at: None,
+                encrypted_payload: None,
+            }]),
+            None,
+            None,
+        );
+        contract
+    }
+
+    #[test]
+    fn unanswered_challenges_suspend_the_credential_until_upheld() {
+        let mut contract = registry_with_credential();
+        call_as(accounts(2), MIN_CHALLENGE_BOND, 0);
+        let challenge_id = contract.challenge_credential(accounts(1), "vc-1".to_string(), "evidence".to_string());
+
+        call_as(accounts(4), NearToken::from_yoctonear(0), CHALLENGE_RESPONSE_WINDOW_DAYS * DAY + 1);
+        contract.enforce_challenge_deadline(challenge_id);
+        assert_eq!(contract.get_challenge(challenge_id).unwrap().status, ChallengeStatus::Suspended);
+        assert!(contract.verify_credential(accounts(1), "vc-1".to_string()).suspended);
+
+        call_as(accounts(3), NearToken::from_yoctonear(0), CHALLENGE_RESPONSE_WINDOW_DAYS * DAY + 1);
+        contract.resolve_challenge(challenge_id, true);
+        assert_eq!(contract.get_challenge(challenge_id).unwrap().status, ChallengeStatus::Upheld);
+        let verification = contract.verify_credential(accounts(1), "vc-1".to_string());
+        assert!(!verification.valid && !verification.suspended);
+        assert_eq!(verification.revocation.unwrap().reason, RevocationReason::Fraud);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the issuer can respond to this challenge.")]
+    fn only_the_issuer_can_contest_a_challenge() {
+        let mut contract = registry_with_credential();
+        call_as(accounts(2), MIN_CHALLENGE_BOND, 0);
+        let challenge_id = contract.challenge_credential(accounts(1), "vc-1".to_string(), "evidence".to_string());
+
+        call_as(accounts(4), NearToken::from_yoctonear(0), DAY);
+        contract.respond_to_challenge(challenge_id, "response".to_string());
+    }
+}
@@ -0,0 +1,96 @@
+// services/blockchain/near-rs/did-management/src/delegates.rs
+use near_sdk::{near, env, AccountId, NearToken};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+
+use crate::{DidEvent, DidRegistry, DidRegistryExt};
+
+const MAX_APPROVAL_DELEGATES: usize = 10;
+
+/// An account the DID owner authorized to approve banking operations on their behalf,
+/// up to `max_amount` per operation.
+#[derive(
+    Debug,
+    PartialEq,
+    near_sdk::serde::Serialize,
+    near_sdk::serde::Deserialize,
+    BorshDeserialize,
+    BorshSerialize,
+    Clone
+)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ApprovalDelegate {
+    pub delegate_id: AccountId,
+    pub max_amount: NearToken,
+    pub expires_at: Option<u64>,
+    pub added_at: u64,
+}
+
+#[near]
+impl DidRegistry {
+    /// Authorizes `delegate_id` to approve banking operations for the caller up to
+    /// `max_amount` each, optionally until `expires_at`. Re-adding a delegate updates its limit.
+    pub fn set_approval_delegate(&mut self, delegate_id: AccountId, max_amount: NearToken, expires_at: Option<u64>) {
+        let owner_id = env::predecessor_account_id();
+        assert!(owner_id != delegate_id, "Cannot designate self as an approval delegate.");
+        assert!(!max_amount.is_zero(), "Delegate limit must be greater than 0.");
+        let now = env::block_timestamp();
+        if let Some(expires_at) = expires_at {
+            assert!(expires_at > now, "Delegate expiry must be in the future.");
+        }
+        self.internal_update_did(&owner_id);
+
+        let delegates = self.approval_delegates.entry(owner_id.clone()).or_default();
+        delegates.retain(|d| d.delegate_id != delegate_id);
+        assert!(
+            delegates.len() < MAX_APPROVAL_DELEGATES,
+            "Cannot designate more than {} approval delegates.", MAX_APPROVAL_DELEGATES
+        );
+        delegates.push(ApprovalDelegate {
+            delegate_id: delegate_id.clone(),
+            max_amount,
+            expires_at,
+            added_at: now,
+        });
+
+        DidEvent::ApprovalDelegateSet { owner_id, delegate_id, max_amount, expires_at }.emit();
+    }
+
+    /// Removes an approval delegate of the caller.
+    pub fn remove_approval_delegate(&mut self, delegate_id: AccountId) {
+        let owner_id = env::predecessor_account_id();
+        self.internal_update_did(&owner_id);
+        let delegates = self.approval_delegates.get_mut(&owner_id)
+            .unwrap_or_else(|| env::panic_str("No approval delegates set for this account."));
+        let initial_len = delegates.len();
+        delegates.retain(|d| d.delegate_id != delegate_id);
+        assert!(delegates.len() < initial_len, "Account is not an approval delegate.");
+        if delegates.is_empty() {
+            self.approval_delegates.remove(&owner_id);
+        }
+
+        DidEvent::ApprovalDelegateRemoved { owner_id, delegate_id }.emit();
+    }
+
+    /// Returns true if `delegate_id` may approve an operation of `amount` for `owner_id`:
+    /// the owner's DID is active and the delegation exists, is unexpired and covers `amount`.
+    /// View function.
+    pub fn is_authorized_approver(&self, owner_id: AccountId, delegate_id: AccountId, amount: NearToken) -> bool {
+        if !self.is_active(&owner_id) {
+            return false;
+        }
+        let now = env::block_timestamp();
+        self.approval_delegates.get(&owner_id).is_some_and(|delegates| {
+            delegates.iter().any(|d| {
+                d.delegate_id == delegate_id
+                    && amount <= d.max_amount
+                    && d.expires_at.is_none_or(|expires_at| expires_at > now)
+            })
+        })
+    }
+
+    /// Lists the approval delegates of `owner_id`.
+    /// View function.
+    pub fn get_approval_delegates(&self, owner_id: AccountId) -> Vec<ApprovalDelegate> {
+        self.approval_delegates.get(&owner_id).cloned().unwrap_or_default()
+    }
+}
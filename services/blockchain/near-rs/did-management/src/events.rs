@@ -0,0 +1,123 @@
+// services/blockchain/near-rs/did-management/src/events.rs
+use near_sdk::{near, AccountId, NearToken};
+
+use crate::RevocationReason;
+
+/// NEP-297 events emitted by the DID registry.
+#[near(event_json(standard = "bcb-did"))]
+pub enum DidEvent {
+    #[event_version("1.0.0")]
+    CredentialVerifierAdded { account_id: AccountId, vc_hash: String, verifier_id: AccountId },
+
+    #[event_version("1.0.0")]
+    CredentialVerifierRemoved { account_id: AccountId, vc_hash: String, verifier_id: AccountId },
+
+    #[event_version("1.0.0")]
+    CredentialVerifiersSet { account_id: AccountId, vc_hash: String, verifiers: Option<Vec<AccountId>> },
+
+    #[event_version("1.0.0")]
+    CredentialAccessFeeSet { account_id: AccountId, vc_hash: String, fee: Option<NearToken> },
+
+    #[event_version("1.0.0")]
+    CredentialAccessFeePaid { account_id: AccountId, vc_hash: String, verifier_id: AccountId, fee: NearToken },
+
+    #[event_version("1.0.0")]
+    CredentialIssued { account_id: AccountId, vc_hash: String, issuer: AccountId },
+
+    #[event_version("1.0.0")]
+    TrustedIssuerAdded { account_id: AccountId, issuer_id: AccountId },
+
+    #[event_version("1.0.0")]
+    TrustedIssuerRemoved { account_id: AccountId, issuer_id: AccountId },
+
+    #[event_version("1.0.0")]
+    CredentialRevoked {
+        account_id: AccountId,
+        vc_hash: String,
+        issuer: AccountId,
+        reason: RevocationReason,
+        replacement_vc_hash: Option<String>,
+    },
+
+    #[event_version("1.0.0")]
+    CredentialChallenged {
+        challenge_id: u64,
+        account_id: AccountId,
+        vc_hash: String,
+        challenger_id: AccountId,
+        evidence_hash: String,
+    },
+
+    #[event_version("1.0.0")]
+    ChallengeResponded { challenge_id: u64, issuer: AccountId, response_hash: String },
+
+    #[event_version("1.0.0")]
+    CredentialSuspended { challenge_id: u64, account_id: AccountId, vc_hash: String },
+
+    #[event_version("1.0.0")]
+    ChallengeResolved {
+        challenge_id: u64,
+        account_id: AccountId,
+        vc_hash: String,
+        arbiter_id: AccountId,
+        upheld: bool,
+        bond_recipient: AccountId,
+    },
+
+    #[event_version("1.0.0")]
+    VerificationMethodAdded { account_id: AccountId, key_id: String, public_key: String },
+
+    #[event_version("1.0.0")]
+    VerificationMethodRotated { account_id: AccountId, key_id: String, previous_public_key: String, public_key: String },
+
+    #[event_version("1.0.0")]
+    VerificationMethodRemoved { account_id: AccountId, key_id: String },
+
+    #[event_version("1.0.0")]
+    DidControllerTransferProposed { previous_controller_id: AccountId, new_controller_id: AccountId },
+
+    #[event_version("1.0.0")]
+    DidControllerTransferCancelled { previous_controller_id: AccountId, new_controller_id: AccountId },
+
+    #[event_version("1.0.0")]
+    DidControllerTransferred { previous_controller_id: AccountId, new_controller_id: AccountId },
+
+    #[event_version("1.0.0")]
+    BoundCredentialInvalidated { previous_controller_id: AccountId, new_controller_id: AccountId, vc_hash: String, issuer: AccountId },
+
+    #[event_version("1.0.0")]
+    CredentialExpiring { account_id: AccountId, vc_hash: String, issuer: AccountId, expires_at: u64 },
+
+    #[event_version("1.0.0")]
+    PersonhoodStamped { account_id: AccountId, oracle_id: AccountId, expires_at: Option<u64> },
+
+    #[event_version("1.0.0")]
+    PersonhoodRevoked { account_id: AccountId, revoked_by: AccountId },
+
+    #[event_version("1.0.0")]
+    ApprovalDelegateSet { owner_id: AccountId, delegate_id: AccountId, max_amount: NearToken, expires_at: Option<u64> },
+
+    #[event_version("1.0.0")]
+    ApprovalDelegateRemoved { owner_id: AccountId, delegate_id: AccountId },
+
+    #[event_version("1.0.0")]
+    OrgChildAuthorized { parent_id: AccountId, child_id: AccountId, department: Option<String>, authorized_by: AccountId },
+
+    #[event_version("1.0.0")]
+    OrgChildRevoked { parent_id: AccountId, child_id: AccountId, revoked_by: AccountId },
+
+    #[event_version("1.0.0")]
+    OrgChildInvited { parent_id: AccountId, child_id: AccountId, department: Option<String>, invited_by: AccountId },
+
+    #[event_version("1.0.0")]
+    OrgInviteDeclined { parent_id: AccountId, child_id: AccountId },
+
+    #[event_version("1.0.0")]
+    DepartmentDelegateAdded { org_id: AccountId, department: String, delegate_id: AccountId },
+
+    #[event_version("1.0.0")]
+    DepartmentDelegateRemoved { org_id: AccountId, department: String, delegate_id: AccountId },
+
+    #[event_version("1.0.0")]
+    GovernanceUpdated { governance_id: Option<AccountId> },
+}
@@ -0,0 +1,82 @@
+// services/blockchain/near-rs/did-management/src/expiry.rs
+use near_sdk::{near, env};
+
+use crate::{DidEvent, DidRegistry, DidRegistryExt, DEFAULT_PAGE_LIMIT};
+
+const NANOS_PER_DAY: u64 = 24 * 60 * 60 * 1_000_000_000;
+const DEFAULT_EXPIRY_WARNING_DAYS: u64 = 30;
+const MAX_EXPIRY_WARNING_DAYS: u64 = 365;
+
+/// Progress report of one `emit_expiring_credentials` batch.
+#[derive(
+    Debug,
+    PartialEq,
+    near_sdk::serde::Serialize,
+    near_sdk::serde::Deserialize,
+    Clone
+)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ExpiryScanResult {
+    pub scanned: u32,
+    pub warned: u32,
+    /// Index to pass as `from_index` on the next call, or `None` once the registry is exhausted.
+    pub next_index: Option<u64>,
+}
+
+#[near]
+impl DidRegistry {
+    /// Maintenance crank callable by anyone (e.g. a cron service). Scans up to `limit` DIDs
+    /// starting at `from_index` and emits a `credential_expiring` event for every credential
+    /// expiring within `window_days` (default 30). Each credential is warned about only once.
+    pub fn emit_expiring_credentials(
+        &mut self,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+        window_days: Option<u64>,
+    ) -> ExpiryScanResult {
+        let window_days = window_days.unwrap_or(DEFAULT_EXPIRY_WARNING_DAYS);
+        assert!(
+            window_days <= MAX_EXPIRY_WARNING_DAYS,
+            "Expiry warning window cannot exceed {} days.", MAX_EXPIRY_WARNING_DAYS
+        );
+        let now = env::block_timestamp();
+        let horizon = now.saturating_add(window_days * NANOS_PER_DAY);
+
+        let from_index = from_index.unwrap_or(0);
+        let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT);
+        let total = self.dids.len() as u64;
+        let mut scanned: u32 = 0;
+        let mut expiring = Vec::new();
+
+        for (account_id, did_doc) in self.dids.iter().skip(from_index as usize).take(limit as usize) {
+            scanned += 1;
+            if self.metadata.get(account_id).is_none_or(|m| m.deactivated) {
+                continue;
+            }
+            for credential in &did_doc.verifiable_credentials {
+                let Some(expires_at) = credential.expires_at else {
+                    continue;
+                };
+                if credential.revocation.is_none() && !credential.expiry_warned && expires_at >= now && expires_at <= horizon {
+                    expiring.push((account_id.clone(), credential.vc_hash.clone(), credential.issuer.clone(), expires_at));
+                }
+            }
+        }
+
+        let warned = expiring.len() as u32;
+        for (account_id, vc_hash, issuer, expires_at) in expiring {
+            let did_doc = self.dids.get_mut(&account_id).unwrap();
+            if let Some(credential) = did_doc.verifiable_credentials.iter_mut().find(|c| c.vc_hash == vc_hash) {
+                credential.expiry_warned = true;
+            }
+            DidEvent::CredentialExpiring { account_id, vc_hash, issuer, expires_at }.emit();
+        }
+
+        let next_index = from_index + scanned as u64;
+        ExpiryScanResult {
+            scanned,
+            warned,
+            next_index: (next_index < total).then_some(next_index),
+        }
+    }
+}
@@ -0,0 +1,66 @@
+// services/blockchain/near-rs/did-management/src/governance.rs
+use near_sdk::{near, env, AccountId};
+use biocrypticbank_common::ContractError;
+
+use crate::{DidEvent, DidRegistry, DidRegistryExt};
+
+#[near]
+impl DidRegistry {
+    /// Sets the governance contract allowed to manage personhood oracles and arbiters
+    /// alongside the owner, or removes it with `None`. Owner only.
+    pub fn set_governance(&mut self, governance_id: Option<AccountId>) {
+        self.assert_owner();
+        self.governance_id = governance_id.clone();
+        DidEvent::GovernanceUpdated { governance_id }.emit();
+    }
+
+    /// View function.
+    pub fn get_governance(&self) -> Option<AccountId> {
+        self.governance_id.clone()
+    }
+}
+
+impl DidRegistry {
+    pub(crate) fn assert_owner_or_governance(&self) {
+        let caller = env::predecessor_account_id();
+        if caller != self.owner_id && self.governance_id.as_ref() != Some(&caller) {
+            ContractError::OwnerOrGovernanceOnly.panic();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use near_sdk::test_utils::{accounts, VMContextBuilder};
+    use near_sdk::testing_env;
+
+    use super::*;
+
+    fn call_as(account_id: AccountId) {
+        testing_env!(VMContextBuilder::new().predecessor_account_id(account_id).build());
+    }
+
+    #[test]
+    fn governance_manages_oracles_and_arbiters_alongside_the_owner() {
+        call_as(accounts(0));
+        let mut contract = DidRegistry::new();
+        contract.set_governance(Some(accounts(1)));
+
+        call_as(accounts(1));
+        contract.add_arbiter(accounts(2));
+        contract.add_personhood_oracle(accounts(3));
+        assert_eq!(contract.get_arbiters(), vec![accounts(2)]);
+        assert_eq!(contract.get_personhood_oracles(), vec![accounts(3)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the owner or governance can call this function.")]
+    fn other_accounts_cannot_manage_oracles() {
+        call_as(accounts(0));
+        let mut contract = DidRegistry::new();
+        contract.set_governance(Some(accounts(1)));
+
+        call_as(accounts(2));
+        contract.add_personhood_oracle(accounts(3));
+    }
+}
@@ -0,0 +1,171 @@
+// services/blockchain/near-rs/did-management/src/issuance.rs
+use near_sdk::{near, env, AccountId};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+
+use crate::{
+    new_credential_record, public_document, CredentialInput, DidDocument, DidEvent, DidRegistry,
+    DidRegistryExt,
+};
+
+const MAX_TRUSTED_ISSUERS: usize = 20;
+
+/// Machine-readable reason a credential was revoked.
+#[derive(
+    Debug,
+    PartialEq,
+    near_sdk::serde::Serialize,
+    near_sdk::serde::Deserialize,
+    BorshDeserialize,
+    BorshSerialize,
+    Clone,
+    Copy
+)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(rename_all = "kebab-case")]
+pub enum RevocationReason {
+    Compromised,
+    Superseded,
+    Fraud,
+    ExpiredSourceDocument,
+}
+
+#[derive(
+    Debug,
+    PartialEq,
+    near_sdk::serde::Serialize,
+    near_sdk::serde::Deserialize,
+    BorshDeserialize,
+    BorshSerialize,
+    Clone
+)]
+#[serde(crate = "near_sdk::serde")]
+pub struct CredentialRevocation {
+    pub reason: RevocationReason,
+    pub revoked_by: AccountId,
+    pub revoked_at: u64,
+    pub replacement_vc_hash: Option<String>,
+}
+
+#[near]
+impl DidRegistry {
+    /// Issues a credential onto `subject_id`'s DID with the caller recorded as issuer, who
+    /// must be one of the subject's trusted issuers. The issuer pays for the storage; any excess deposit is refunded, and the deposit kept
+    /// counts towards what the subject is refunded on `purge_did`. The subject can remove the
+    /// credential at any time. Returns the subject's document without its gated credentials.
+    #[payable]
+    pub fn issue_credential(&mut self, subject_id: AccountId, credential: CredentialInput) -> DidDocument {
+        let issuer_id = env::predecessor_account_id();
+        assert!(
+            self.is_trusted_issuer(subject_id.clone(), issuer_id.clone()),
+            "Caller is not a trusted issuer for this DID."
+        );
+        let initial_storage = env::storage_usage();
+        let did_doc = self.internal_update_did(&subject_id);
+
+        assert!(!credential.vc_hash.is_empty(), "Verifiable credential must not be empty.");
+        assert!(
+            !did_doc.verifiable_credentials.iter().any(|c| c.vc_hash == credential.vc_hash),
+            "Verifiable credential already exists for this DID."
+        );
+        let record = new_credential_record(credential, &issuer_id, env::block_timestamp(), &did_doc.verification_methods);
+        let vc_hash = record.vc_hash.clone();
+        did_doc.verifiable_credentials.push(record);
+        let did_doc = public_document(did_doc);
+        self.record_credentials_issued(&issuer_id, 1);
+
+        self.internal_charge_document_storage(&subject_id, initial_storage);
+
+        DidEvent::CredentialIssued { account_id: subject_id, vc_hash, issuer: issuer_id }.emit();
+        did_doc
+    }
+
+    /// Allows `issuer_id` to issue credentials onto the caller's DID.
+    pub fn add_trusted_issuer(&mut self, issuer_id: AccountId) {
+        let account_id = env::predecessor_account_id();
+        assert!(account_id != issuer_id, "Add credentials to your own DID directly.");
+        self.internal_update_did(&account_id);
+
+        let issuers = self.trusted_issuers.entry(account_id.clone()).or_default();
+        assert!(!issuers.contains(&issuer_id), "Account is already a trusted issuer.");
+        assert!(
+            issuers.len() < MAX_TRUSTED_ISSUERS,
+            "Cannot trust more than {} issuers.", MAX_TRUSTED_ISSUERS
+        );
+        issuers.push(issuer_id.clone());
+
+        DidEvent::TrustedIssuerAdded { account_id, issuer_id }.emit();
+    }
+
+    /// Stops `issuer_id` from issuing further credentials onto the caller's DID. Credentials
+    /// it already issued are kept.
+    pub fn remove_trusted_issuer(&mut self, issuer_id: AccountId) {
+        let account_id = env::predecessor_account_id();
+        self.internal_update_did(&account_id);
+        let issuers = self.trusted_issuers.get_mut(&account_id)
+            .unwrap_or_else(|| env::panic_str("No trusted issuers set for this account."));
+        let initial_len = issuers.len();
+        issuers.retain(|i| i != &issuer_id);
+        assert!(issuers.len() < initial_len, "Account is not a trusted issuer.");
+        if issuers.is_empty() {
+            self.trusted_issuers.remove(&account_id);
+        }
+
+        DidEvent::TrustedIssuerRemoved { account_id, issuer_id }.emit();
+    }
+
+    /// Returns true if `issuer_id` may issue credentials onto `account_id`'s DID.
+    /// View function.
+    pub fn is_trusted_issuer(&self, account_id: AccountId, issuer_id: AccountId) -> bool {
+        self.trusted_issuers.get(&account_id).is_some_and(|issuers| issuers.contains(&issuer_id))
+    }
+
+    /// Lists the trusted issuers of `account_id`.
+    /// View function.
+    pub fn get_trusted_issuers(&self, account_id: AccountId) -> Vec<AccountId> {
+        self.trusted_issuers.get(&account_id).cloned().unwrap_or_default()
+    }
+
+    /// Revokes a credential with a structured reason. Callable by the credential's issuer.
+    /// The credential stays on the DID so verifiers can see why it no longer verifies.
+    /// `replacement_vc_hash`: Optional credential on the same DID superseding this one.
+    pub fn revoke_credential(
+        &mut self,
+        subject_id: AccountId,
+        vc_hash: String,
+        reason: RevocationReason,
+        replacement_vc_hash: Option<String>,
+    ) {
+        let caller_id = env::predecessor_account_id();
+        let did_doc = self.internal_update_did(&subject_id);
+        if let Some(replacement) = &replacement_vc_hash {
+            assert!(replacement != &vc_hash, "A credential cannot replace itself.");
+            assert!(
+                did_doc.verifiable_credentials.iter().any(|c| &c.vc_hash == replacement),
+                "Replacement credential not found for this DID."
+            );
+        }
+
+        let credential = did_doc.verifiable_credentials
+            .iter_mut()
+            .find(|c| c.vc_hash == vc_hash)
+            .unwrap_or_else(|| env::panic_str("Verifiable credential not found for this DID."));
+        assert_eq!(credential.issuer, caller_id, "Only the issuer can revoke this credential.");
+        assert!(credential.revocation.is_none(), "Credential is already revoked.");
+
+        credential.revocation = Some(CredentialRevocation {
+            reason,
+            revoked_by: caller_id.clone(),
+            revoked_at: env::block_timestamp(),
+            replacement_vc_hash: replacement_vc_hash.clone(),
+        });
+        self.record_revocation(reason);
+
+        DidEvent::CredentialRevoked {
+            account_id: subject_id,
+            vc_hash,
+            issuer: caller_id,
+            reason,
+            replacement_vc_hash,
+        }.emit();
+    }
+}
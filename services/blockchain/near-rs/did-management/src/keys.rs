@@ -0,0 +1,52 @@
+// services/blockchain/near-rs/did-management/src/keys.rs
+use near_sdk::{near, env};
+
+use crate::{assert_valid_verification_methods, DidEvent, DidRegistry, DidRegistryExt, VerificationMethod};
+
+#[near]
+impl DidRegistry {
+    /// Adds a verification method to the caller's DID. Its id must not already be in use.
+    /// The attached deposit must cover the storage it adds; any excess is refunded.
+    #[payable]
+    pub fn add_verification_method(&mut self, method: VerificationMethod) {
+        let signer_id = env::predecessor_account_id();
+        let initial_storage = env::storage_usage();
+        let did_doc = self.internal_update_did(&signer_id);
+        did_doc.verification_methods.push(method.clone());
+        assert_valid_verification_methods(&did_doc.verification_methods);
+        self.internal_charge_document_storage(&signer_id, initial_storage);
+
+        DidEvent::VerificationMethodAdded { account_id: signer_id, key_id: method.id, public_key: method.public_key }.emit();
+    }
+
+    /// Replaces the public key of the caller's verification method `key_id`, keeping its id so
+    /// encrypted payloads addressed to it stay valid. Signatures by the old key stop verifying.
+    /// The attached deposit must cover any storage a longer key adds; any excess is refunded.
+    #[payable]
+    pub fn rotate_verification_method(&mut self, key_id: String, public_key: String) {
+        assert!(!public_key.is_empty(), "Verification method public key must not be empty.");
+        let signer_id = env::predecessor_account_id();
+        let initial_storage = env::storage_usage();
+        let method = self.internal_update_did(&signer_id)
+            .verification_methods
+            .iter_mut()
+            .find(|m| m.id == key_id)
+            .unwrap_or_else(|| env::panic_str("Verification method not found."));
+        assert!(method.public_key != public_key, "Verification method already uses this key.");
+        let previous_public_key = std::mem::replace(&mut method.public_key, public_key.clone());
+        self.internal_charge_document_storage(&signer_id, initial_storage);
+
+        DidEvent::VerificationMethodRotated { account_id: signer_id, key_id, previous_public_key, public_key }.emit();
+    }
+
+    /// Revokes the caller's verification method `key_id`, e.g. after the key was compromised.
+    pub fn remove_verification_method(&mut self, key_id: String) {
+        let signer_id = env::predecessor_account_id();
+        let did_doc = self.internal_update_did(&signer_id);
+        let initial_len = did_doc.verification_methods.len();
+        did_doc.verification_methods.retain(|m| m.id != key_id);
+        assert!(did_doc.verification_methods.len() < initial_len, "Verification method not found.");
+
+        DidEvent::VerificationMethodRemoved { account_id: signer_id, key_id }.emit();
+    }
+}
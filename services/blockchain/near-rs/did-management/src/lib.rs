@@ -1,112 +1,1288 @@
-// services/blockchain/near-rs/did-management/src/lib.rs
-use near_sdk::{near, BorshStorageKey, PanicOnDefault, AccountId, env};
-use near_sdk::store::IterableMap;
-use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
-
-#[derive(
-    Debug,
-    PartialEq,
-    near_sdk::serde::Serialize,
-    near_sdk::serde::Deserialize,
-    BorshDeserialize,
-    BorshSerialize,
-    Clone
-)]
-#[serde(crate = "near_sdk::serde")]
-pub struct DidDocument {
-    pub owner_id: AccountId,
-    pub verifiable_credentials: Vec<String>,
-    pub last_updated: u64,
-}
-
-#[derive(BorshStorageKey, Debug, BorshDeserialize, BorshSerialize)]
-pub enum StorageKey {
-    Dids,
-}
-
-#[near(contract_state)]
-#[derive(PanicOnDefault)]
-pub struct DidRegistry {
-    dids: IterableMap<AccountId, DidDocument>,
-}
-
-#[near]
-impl DidRegistry {
-    /// Initializes the DID registry contract.
-    #[init]
-    pub fn new() -> Self {
-        Self {
-            dids: IterableMap::new(StorageKey::Dids),
-        }
-    }
-
-    /// Registers a DID for the caller.
-    /// A user can only register one DID, linked to their AccountId.
-    pub fn register_did(&mut self) -> DidDocument {
-        let signer_id = env::predecessor_account_id();
-        assert!(!self.dids.contains_key(&signer_id), "DID already registered for this account.");
-
-        let new_did_doc = DidDocument {
-            owner_id: signer_id.clone(),
-            verifiable_credentials: Vec::new(),
-            last_updated: env::block_timestamp(),
-        };
-
-        self.dids.insert(signer_id.clone(), new_did_doc);
-        env::log_str(&format!("DID registered for: {}", signer_id));
-        self.dids.get(&signer_id).unwrap().clone()
-    }
-
-    /// Adds a verifiable credential (VC) hash/URI to an existing DID.
-    /// Only the DID owner can add VCs to their own DID.
-    /// `vc_hash`: A unique identifier or hash of the verifiable credential.
-    pub fn add_verifiable_credential(&mut self, vc_hash: String) -> DidDocument {
-        let signer_id = env::predecessor_account_id();
-        let did_doc = self.dids.get_mut(&signer_id) // FIXED: Removed 'mut'
-            .unwrap_or_else(|| env::panic_str("DID not found for this account."));
-
-        assert!(
-            !did_doc.verifiable_credentials.contains(&vc_hash),
-            "Verifiable credential already exists for this DID."
-        );
-
-        did_doc.verifiable_credentials.push(vc_hash);
-        did_doc.last_updated = env::block_timestamp();
-        env::log_str(&format!("VC added to DID for: {}", signer_id));
-        did_doc.clone()
-    }
-
-    /// Removes a verifiable credential (VC) hash/URI from an existing DID.
-    /// Only the DID owner can remove VCs from their own DID.
-    /// `vc_hash`: The unique identifier or hash of the verifiable credential to remove.
-    pub fn remove_verifiable_credential(&mut self, vc_hash: String) -> DidDocument {
-        let signer_id = env::predecessor_account_id();
-        let did_doc = self.dids.get_mut(&signer_id) // FIXED: Removed 'mut'
-            .unwrap_or_else(|| env::panic_str("DID not found for this account."));
-
-        let initial_len = did_doc.verifiable_credentials.len();
-        did_doc.verifiable_credentials.retain(|h| h != &vc_hash);
-
-        assert!(
-            did_doc.verifiable_credentials.len() < initial_len,
-            "Verifiable credential not found for this DID."
-        );
-
-        did_doc.last_updated = env::block_timestamp();
-        env::log_str(&format!("VC removed from DID for: {}", signer_id));
-        did_doc.clone()
-    }
-
-    /// Retrieves the DidDocument for a given AccountId.
-    /// This is a view function and does not modify the state.
-    /// `account_id`: The NEAR AccountId whose DID is to be retrieved.
-    pub fn get_did_document(&self, account_id: AccountId) -> Option<DidDocument> {
-        self.dids.get(&account_id).cloned()
-    }
-
-    /// Checks if a DID exists for a given AccountId.
-    #[allow(dead_code)]
-    pub fn did_exists(&self, account_id: AccountId) -> bool {
-        self.dids.contains_key(&account_id)
-    }
-}
+// services/blockchain/near-rs/did-management/src/lib.rs
+use near_sdk::{near, BorshStorageKey, PanicOnDefault, AccountId, NearToken, Promise, env};
+use near_sdk::store::{IterableMap, IterableSet, LookupMap, TreeMap};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use biocrypticbank_common::ContractError;
+
+mod access_fees;
+mod challenges;
+mod delegates;
+mod events;
+mod expiry;
+mod governance;
+mod issuance;
+mod keys;
+mod migration;
+mod nft;
+mod organization;
+mod personhood;
+mod stats;
+mod sync;
+mod w3c;
+pub use biocrypticbank_common::VerificationMethod;
+pub use challenges::{ChallengeStatus, CredentialChallenge};
+pub use delegates::ApprovalDelegate;
+pub use events::DidEvent;
+pub use expiry::ExpiryScanResult;
+pub use issuance::{CredentialRevocation, RevocationReason};
+pub use nft::{NFTContractMetadata, Token, TokenMetadata};
+pub use organization::{DepartmentDelegate, OrgMemberView, OrgMembership};
+pub use personhood::PersonhoodAttestation;
+pub use stats::{DailyRegistrations, RegistryStats, RevocationCounts};
+
+const MAX_INITIAL_CREDENTIALS: usize = 20;
+const MAX_VERIFICATION_METHODS: usize = 10;
+const MAX_SERVICES: usize = 10;
+const MAX_CREDENTIAL_TAGS: usize = 10;
+const MAX_ALLOWED_VERIFIERS: usize = 20;
+const SUPPORTED_ENCRYPTION_ALGORITHMS: [&str; 3] = ["ECDH-ES+A256KW", "X25519-XSalsa20-Poly1305", "RSA-OAEP-256"];
+const DEFAULT_CREDENTIAL_TYPE: &str = "generic";
+pub(crate) const DEFAULT_PAGE_LIMIT: u64 = 50;
+pub(crate) const DID_METHOD_PREFIX: &str = "did:near:";
+const RESOLVER_MAX_AGE_SECS: u64 = 300;
+const DEACTIVATED_RESOLVER_MAX_AGE_SECS: u64 = 86_400;
+
+/// A verifiable credential anchored on a DID, e.g. a "kyc" or "proof-of-address" attestation.
+#[derive(
+    Debug,
+    PartialEq,
+    near_sdk::serde::Serialize,
+    near_sdk::serde::Deserialize,
+    BorshDeserialize,
+    BorshSerialize,
+    Clone
+)]
+#[serde(crate = "near_sdk::serde")]
+pub struct CredentialRecord {
+    pub vc_hash: String,
+    pub credential_type: String,
+    pub tags: Vec<String>,
+    pub issuer: AccountId,
+    pub issued_at: u64,
+    /// Block height at which the credential was anchored in the registry.
+    pub anchor_block_height: u64,
+    /// Accounts allowed to verify this credential. `None` means anyone may verify it.
+    pub allowed_verifiers: Option<Vec<AccountId>>,
+    /// Bound credentials are tied to the current controller and are invalidated when
+    /// the DID is transferred to another account.
+    pub bound: bool,
+    pub expires_at: Option<u64>,
+    /// Set once a `credential_expiring` event has been emitted for this credential.
+    pub expiry_warned: bool,
+    /// Fee a verifier must attach to `verify_credential`. Accrues to the subject.
+    pub access_fee: Option<NearToken>,
+    pub revocation: Option<CredentialRevocation>,
+    pub encrypted_payload: Option<EncryptedPayload>,
+    /// Unresolved challenge against this credential, if any.
+    pub challenge_id: Option<u64>,
+    pub suspended: bool,
+}
+
+impl CredentialRecord {
+    /// Gated credentials restrict their verifiers or charge for access, so they are left out
+    /// of the free views and can only be checked through `verify_credential`.
+    pub fn is_gated(&self) -> bool {
+        self.allowed_verifiers.is_some() || self.access_fee.is_some()
+    }
+}
+
+/// Outcome of a `verify_credential` call.
+#[derive(
+    Debug,
+    PartialEq,
+    near_sdk::serde::Serialize,
+    near_sdk::serde::Deserialize,
+    Clone
+)]
+#[serde(crate = "near_sdk::serde")]
+pub struct CredentialVerification {
+    pub account_id: AccountId,
+    pub vc_hash: String,
+    pub valid: bool,
+    pub credential_type: Option<String>,
+    pub issuer: Option<AccountId>,
+    pub issued_at: Option<u64>,
+    pub expires_at: Option<u64>,
+    pub revocation: Option<CredentialRevocation>,
+    pub suspended: bool,
+}
+
+/// Reference to an encrypted credential body stored off-chain. The registry anchors the
+/// ciphertext's integrity and points at the subject's key able to decrypt it.
+#[derive(
+    Debug,
+    PartialEq,
+    near_sdk::serde::Serialize,
+    near_sdk::serde::Deserialize,
+    BorshDeserialize,
+    BorshSerialize,
+    Clone
+)]
+#[serde(crate = "near_sdk::serde")]
+pub struct EncryptedPayload {
+    pub ciphertext_hash: String,
+    /// Id of a verification method in the subject's DID document.
+    pub recipient_key_id: String,
+    pub algorithm: String,
+    pub uri: Option<String>,
+}
+
+/// Caller-supplied description of a credential to anchor.
+#[derive(
+    Debug,
+    PartialEq,
+    near_sdk::serde::Serialize,
+    near_sdk::serde::Deserialize,
+    Clone
+)]
+#[serde(crate = "near_sdk::serde")]
+pub struct CredentialInput {
+    pub vc_hash: String,
+    pub credential_type: Option<String>,
+    pub tags: Option<Vec<String>>,
+    pub bound: Option<bool>,
+    pub expires_at: Option<u64>,
+    pub encrypted_payload: Option<EncryptedPayload>,
+}
+
+/// A service endpoint advertised by the DID subject, e.g. a KYC provider or messaging inbox.
+#[derive(
+    Debug,
+    PartialEq,
+    near_sdk::serde::Serialize,
+    near_sdk::serde::Deserialize,
+    BorshDeserialize,
+    BorshSerialize,
+    Clone
+)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ServiceEndpoint {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub service_type: String,
+    pub endpoint: String,
+}
+
+#[derive(
+    Debug,
+    PartialEq,
+    near_sdk::serde::Serialize,
+    near_sdk::serde::Deserialize,
+    BorshDeserialize,
+    BorshSerialize,
+    Clone
+)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DidDocument {
+    pub owner_id: AccountId,
+    pub verifiable_credentials: Vec<CredentialRecord>,
+    pub verification_methods: Vec<VerificationMethod>,
+    pub services: Vec<ServiceEndpoint>,
+    pub last_updated: u64,
+}
+
+/// DID Core document metadata, tracked separately from the document body and
+/// maintained automatically by every mutation.
+#[derive(
+    Debug,
+    PartialEq,
+    near_sdk::serde::Serialize,
+    near_sdk::serde::Deserialize,
+    BorshDeserialize,
+    BorshSerialize,
+    Clone
+)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DidDocumentMetadata {
+    pub created: u64,
+    pub updated: u64,
+    pub version_id: u64,
+    pub next_update_commitment: Option<String>,
+    pub deactivated: bool,
+}
+
+/// Result of resolving a DID, per the DID Core resolution output.
+#[derive(
+    Debug,
+    PartialEq,
+    near_sdk::serde::Serialize,
+    near_sdk::serde::Deserialize,
+    Clone
+)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DidResolution {
+    pub id: String,
+    pub did_document: DidDocument,
+    pub did_document_metadata: DidDocumentMetadata,
+    pub cache_hints: ResolutionCacheHints,
+}
+
+/// Cache-control style hints letting off-chain resolvers decide whether a cached
+/// resolution is still fresh.
+#[derive(
+    Debug,
+    PartialEq,
+    near_sdk::serde::Serialize,
+    near_sdk::serde::Deserialize,
+    Clone
+)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ResolutionCacheHints {
+    /// Suggested number of seconds a resolver may serve the result without re-checking.
+    pub max_age: u64,
+    /// Hex-encoded sha256 of the Borsh-serialized document body.
+    pub content_hash: String,
+}
+
+/// Minimal audit record left behind after a DID has been purged.
+#[derive(
+    Debug,
+    PartialEq,
+    near_sdk::serde::Serialize,
+    near_sdk::serde::Deserialize,
+    BorshDeserialize,
+    BorshSerialize,
+    Clone
+)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DidTombstone {
+    pub account_id: AccountId,
+    pub deleted_at: u64,
+}
+
+#[derive(BorshStorageKey, Debug, BorshDeserialize, BorshSerialize)]
+pub enum StorageKey {
+    LegacyDids,
+    Tombstones,
+    Metadata,
+    OrgParents,
+    OrgChildren,
+    OrgChildSet { account_id_hash: Vec<u8> },
+    OrgDelegates,
+    NftVisible,
+    AccessFeeBalances,
+    PersonhoodOracles,
+    Personhood,
+    HumanIds,
+    Arbiters,
+    Challenges,
+    RegistrationsByDay,
+    CredentialsByIssuer,
+    ApprovalDelegates,
+    OrgInvites,
+    DidsByUpdate,
+    StorageDeposits,
+    TrustedIssuers,
+    PendingControllers,
+    Dids,
+}
+
+#[near(contract_state)]
+#[derive(PanicOnDefault)]
+pub struct DidRegistry {
+    owner_id: AccountId,
+    dids: IterableMap<AccountId, DidDocument>,
+    tombstones: LookupMap<AccountId, DidTombstone>,
+    metadata: LookupMap<AccountId, DidDocumentMetadata>,
+    org_parents: LookupMap<AccountId, OrgMembership>,
+    org_children: LookupMap<AccountId, IterableSet<AccountId>>,
+    org_delegates: LookupMap<AccountId, Vec<DepartmentDelegate>>,
+    nft_visible: IterableSet<AccountId>,
+    access_fee_balances: LookupMap<AccountId, NearToken>,
+    personhood_oracles: IterableSet<AccountId>,
+    personhood: LookupMap<AccountId, PersonhoodAttestation>,
+    human_ids: LookupMap<String, AccountId>,
+    arbiters: IterableSet<AccountId>,
+    challenges: LookupMap<u64, CredentialChallenge>,
+    next_challenge_id: u64,
+    stats: RegistryStats,
+    registrations_by_day: LookupMap<u64, u64>,
+    credentials_by_issuer: LookupMap<AccountId, u64>,
+    approval_delegates: LookupMap<AccountId, Vec<ApprovalDelegate>>,
+    /// Governance contract allowed to manage oracle and arbiter roles alongside the owner.
+    governance_id: Option<AccountId>,
+    /// Pending organization invites, keyed by (child, parent).
+    org_invites: LookupMap<(AccountId, AccountId), OrgMembership>,
+    /// Every DID keyed by (metadata `updated`, account id), for incremental sync.
+    dids_by_update: TreeMap<(u64, String), ()>,
+    /// Storage deposit paid for each DID, capping what `purge_did` refunds.
+    storage_deposits: LookupMap<AccountId, NearToken>,
+    /// Issuers each DID controller accepts credentials from through `issue_credential`.
+    trusted_issuers: LookupMap<AccountId, Vec<AccountId>>,
+    /// Controller each DID is being transferred to, until that account accepts.
+    pending_controllers: LookupMap<AccountId, AccountId>,
+    /// Documents stored before the registry was versioned, awaiting `migrate_legacy_dids`.
+    legacy_dids: IterableMap<AccountId, migration::DidDocumentV0>,
+}
+
+#[near]
+impl DidRegistry {
+    /// Initializes the DID registry contract.
+    /// The account calling `new` becomes the registry owner, who manages oracle roles and
+    /// can hand them to a governance contract.
+    #[init]
+    pub fn new() -> Self {
+        Self {
+            owner_id: env::predecessor_account_id(),
+            dids: IterableMap::new(StorageKey::Dids),
+            tombstones: LookupMap::new(StorageKey::Tombstones),
+            metadata: LookupMap::new(StorageKey::Metadata),
+            org_parents: LookupMap::new(StorageKey::OrgParents),
+            org_children: LookupMap::new(StorageKey::OrgChildren),
+            org_delegates: LookupMap::new(StorageKey::OrgDelegates),
+            nft_visible: IterableSet::new(StorageKey::NftVisible),
+            access_fee_balances: LookupMap::new(StorageKey::AccessFeeBalances),
+            personhood_oracles: IterableSet::new(StorageKey::PersonhoodOracles),
+            personhood: LookupMap::new(StorageKey::Personhood),
+            human_ids: LookupMap::new(StorageKey::HumanIds),
+            arbiters: IterableSet::new(StorageKey::Arbiters),
+            challenges: LookupMap::new(StorageKey::Challenges),
+            next_challenge_id: 0,
+            stats: RegistryStats::default(),
+            registrations_by_day: LookupMap::new(StorageKey::RegistrationsByDay),
+            credentials_by_issuer: LookupMap::new(StorageKey::CredentialsByIssuer),
+            approval_delegates: LookupMap::new(StorageKey::ApprovalDelegates),
+            governance_id: None,
+            org_invites: LookupMap::new(StorageKey::OrgInvites),
+            dids_by_update: TreeMap::new(StorageKey::DidsByUpdate),
+            storage_deposits: LookupMap::new(StorageKey::StorageDeposits),
+            trusted_issuers: LookupMap::new(StorageKey::TrustedIssuers),
+            pending_controllers: LookupMap::new(StorageKey::PendingControllers),
+            legacy_dids: IterableMap::new(StorageKey::LegacyDids),
+        }
+    }
+
+    /// Registers a DID for the caller.
+    /// A user can only register one DID, linked to their AccountId.
+    /// Initial credentials, verification methods and service endpoints can be supplied so
+    /// onboarding happens in a single transaction. Everything is validated before anything
+    /// is written, and the attached deposit must cover the storage of the whole document.
+    /// Any excess deposit is refunded.
+    #[payable]
+    pub fn register_did(
+        &mut self,
+        verifiable_credentials: Option<Vec<CredentialInput>>,
+        verification_methods: Option<Vec<VerificationMethod>>,
+        services: Option<Vec<ServiceEndpoint>>,
+    ) -> DidDocument {
+        let signer_id = env::predecessor_account_id();
+        assert!(!self.has_did(&signer_id), "DID already registered for this account.");
+
+        let verifiable_credentials = verifiable_credentials.unwrap_or_default();
+        let verification_methods = verification_methods.unwrap_or_default();
+        let services = services.unwrap_or_default();
+
+        assert!(
+            verifiable_credentials.len() <= MAX_INITIAL_CREDENTIALS,
+            "Cannot register more than {} initial credentials.", MAX_INITIAL_CREDENTIALS
+        );
+        assert_unique_non_empty(verifiable_credentials.iter().map(|c| &c.vc_hash), "Verifiable credential");
+        assert_valid_verification_methods(&verification_methods);
+        assert_valid_services(&services);
+
+        let now = env::block_timestamp();
+        let verifiable_credentials: Vec<CredentialRecord> = verifiable_credentials
+            .into_iter()
+            .map(|input| new_credential_record(input, &signer_id, now, &verification_methods))
+            .collect();
+
+        let initial_storage = env::storage_usage();
+        self.record_credentials_issued(&signer_id, verifiable_credentials.len() as u64);
+        let new_did_doc = DidDocument {
+            owner_id: signer_id.clone(),
+            verifiable_credentials,
+            verification_methods,
+            services,
+            last_updated: now,
+        };
+
+        self.dids.insert(signer_id.clone(), new_did_doc);
+        self.metadata.insert(signer_id.clone(), DidDocumentMetadata {
+            created: now,
+            updated: now,
+            version_id: 1,
+            next_update_commitment: None,
+            deactivated: false,
+        });
+        self.internal_index_update(&signer_id, None, now);
+        self.record_registration(now);
+        self.dids.flush();
+        self.metadata.flush();
+        self.dids_by_update.flush();
+        let deposit = settle_storage_deposit(initial_storage);
+        self.internal_add_storage_deposit(&signer_id, deposit);
+
+        env::log_str(&format!("DID registered for: {}", signer_id));
+        self.dids.get(&signer_id).unwrap().clone()
+    }
+
+    /// Adds a verifiable credential (VC) hash/URI to an existing DID.
+    /// Only the DID owner can add VCs to their own DID.
+    /// `vc_hash`: A unique identifier or hash of the verifiable credential.
+    /// `credential_type`: The kind of credential, e.g. "kyc". Defaults to "generic".
+    /// `tags`: Optional free-form labels used for filtering.
+    /// `bound`: Whether the credential is invalidated when the DID changes controller.
+    /// `expires_at`: Optional expiry timestamp in nanoseconds; must be in the future.
+    /// The attached deposit must cover the storage the credential adds; any excess is refunded.
+    #[payable]
+    pub fn add_verifiable_credential(
+        &mut self,
+        vc_hash: String,
+        credential_type: Option<String>,
+        tags: Option<Vec<String>>,
+        bound: Option<bool>,
+        expires_at: Option<u64>,
+    ) -> DidDocument {
+        let signer_id = env::predecessor_account_id();
+        let initial_storage = env::storage_usage();
+        let did_doc = self.internal_update_did(&signer_id);
+
+        assert!(!vc_hash.is_empty(), "Verifiable credential must not be empty.");
+        assert!(
+            !did_doc.verifiable_credentials.iter().any(|c| c.vc_hash == vc_hash),
+            "Verifiable credential already exists for this DID."
+        );
+
+        let input = CredentialInput { vc_hash, credential_type, tags, bound, expires_at, encrypted_payload: None };
+        let record = new_credential_record(input, &signer_id, env::block_timestamp(), &did_doc.verification_methods);
+        did_doc.verifiable_credentials.push(record);
+        let did_doc = did_doc.clone();
+        self.record_credentials_issued(&signer_id, 1);
+        self.internal_charge_document_storage(&signer_id, initial_storage);
+        env::log_str(&format!("VC added to DID for: {}", signer_id));
+        did_doc
+    }
+
+    /// Removes a verifiable credential (VC) hash/URI from an existing DID.
+    /// Only the DID owner can remove VCs from their own DID, and not while the VC has an
+    /// unresolved challenge.
+    /// `vc_hash`: The unique identifier or hash of the verifiable credential to remove.
+    pub fn remove_verifiable_credential(&mut self, vc_hash: String) -> DidDocument {
+        let signer_id = env::predecessor_account_id();
+        let did_doc = self.internal_update_did(&signer_id);
+        assert!(
+            !did_doc.verifiable_credentials.iter().any(|c| c.vc_hash == vc_hash && c.challenge_id.is_some()),
+            "Credential has an unresolved challenge."
+        );
+
+        let initial_len = did_doc.verifiable_credentials.len();
+        did_doc.verifiable_credentials.retain(|c| c.vc_hash != vc_hash);
+
+        assert!(
+            did_doc.verifiable_credentials.len() < initial_len,
+            "Verifiable credential not found for this DID."
+        );
+
+        env::log_str(&format!("VC removed from DID for: {}", signer_id));
+        did_doc.clone()
+    }
+
+    /// Replaces the verifier allowlist of one of the caller's credentials.
+    /// Passing `None` makes the credential publicly verifiable again. The attached deposit
+    /// must cover any storage the allowlist adds; any excess is refunded.
+    #[payable]
+    pub fn set_credential_verifiers(&mut self, vc_hash: String, verifiers: Option<Vec<AccountId>>) {
+        let signer_id = env::predecessor_account_id();
+        let initial_storage = env::storage_usage();
+        if let Some(list) = &verifiers {
+            assert!(
+                list.len() <= MAX_ALLOWED_VERIFIERS,
+                "Cannot allow more than {} verifiers per credential.", MAX_ALLOWED_VERIFIERS
+            );
+            let mut seen: Vec<&AccountId> = Vec::new();
+            for verifier in list {
+                assert!(!seen.contains(&verifier), "Duplicate verifier: {}", verifier);
+                seen.push(verifier);
+            }
+        }
+
+        let credential = self.internal_get_own_credential_mut(&signer_id, &vc_hash);
+        credential.allowed_verifiers = verifiers.clone();
+        self.internal_charge_document_storage(&signer_id, initial_storage);
+
+        DidEvent::CredentialVerifiersSet { account_id: signer_id, vc_hash, verifiers }.emit();
+    }
+
+    /// Allows `verifier_id` to verify one of the caller's credentials.
+    /// Adding a verifier to a public credential restricts it to that verifier. The attached
+    /// deposit must cover the storage it adds; any excess is refunded.
+    #[payable]
+    pub fn add_credential_verifier(&mut self, vc_hash: String, verifier_id: AccountId) {
+        let signer_id = env::predecessor_account_id();
+        let initial_storage = env::storage_usage();
+        let credential = self.internal_get_own_credential_mut(&signer_id, &vc_hash);
+        let verifiers = credential.allowed_verifiers.get_or_insert_with(Vec::new);
+        assert!(!verifiers.contains(&verifier_id), "Verifier is already allowed for this credential.");
+        assert!(
+            verifiers.len() < MAX_ALLOWED_VERIFIERS,
+            "Cannot allow more than {} verifiers per credential.", MAX_ALLOWED_VERIFIERS
+        );
+        verifiers.push(verifier_id.clone());
+        self.internal_charge_document_storage(&signer_id, initial_storage);
+
+        DidEvent::CredentialVerifierAdded { account_id: signer_id, vc_hash, verifier_id }.emit();
+    }
+
+    /// Revokes `verifier_id`'s permission to verify one of the caller's credentials.
+    /// Removing the last verifier leaves the credential verifiable by nobody;
+    /// use `set_credential_verifiers` with `None` to make it public.
+    pub fn remove_credential_verifier(&mut self, vc_hash: String, verifier_id: AccountId) {
+        let signer_id = env::predecessor_account_id();
+        let credential = self.internal_get_own_credential_mut(&signer_id, &vc_hash);
+        let verifiers = credential.allowed_verifiers.as_mut()
+            .unwrap_or_else(|| env::panic_str("Credential has no verifier allowlist."));
+        let initial_len = verifiers.len();
+        verifiers.retain(|v| v != &verifier_id);
+        assert!(verifiers.len() < initial_len, "Verifier is not allowed for this credential.");
+
+        DidEvent::CredentialVerifierRemoved { account_id: signer_id, vc_hash, verifier_id }.emit();
+    }
+
+    /// Attaches (or with `None`, detaches) an encrypted payload envelope to one of the
+    /// caller's credentials. The recipient key must be one of the caller's verification methods.
+    /// The attached deposit must cover any storage the payload adds; any excess is refunded.
+    #[payable]
+    pub fn set_credential_encrypted_payload(&mut self, vc_hash: String, encrypted_payload: Option<EncryptedPayload>) {
+        let signer_id = env::predecessor_account_id();
+        let initial_storage = env::storage_usage();
+        let did_doc = self.internal_update_did(&signer_id);
+        if let Some(payload) = &encrypted_payload {
+            assert_valid_encrypted_payload(payload, &did_doc.verification_methods);
+        }
+        let credential = did_doc.verifiable_credentials
+            .iter_mut()
+            .find(|c| c.vc_hash == vc_hash)
+            .unwrap_or_else(|| env::panic_str("Verifiable credential not found for this DID."));
+        credential.encrypted_payload = encrypted_payload;
+        self.internal_charge_document_storage(&signer_id, initial_storage);
+        env::log_str(&format!("Encrypted payload updated for credential {} of {}", vc_hash, signer_id));
+    }
+
+    /// Verifies that `account_id` holds the credential `vc_hash`.
+    /// The caller must be on the credential's verifier allowlist, if it has one, so this
+    /// must be invoked as a function call (or cross-contract call) rather than an RPC view.
+    /// A caller not on the allowlist gets the same invalid result as for a credential that
+    /// does not exist, so it learns nothing about the credential.
+    /// If the subject set an access fee for the credential it must be attached; it accrues
+    /// to the subject's fee balance and any excess deposit is refunded.
+    #[payable]
+    pub fn verify_credential(&mut self, account_id: AccountId, vc_hash: String) -> CredentialVerification {
+        let attached = env::attached_deposit();
+        let credential = self.dids.get(&account_id)
+            .filter(|_| self.is_active(&account_id))
+            .and_then(|doc| doc.verifiable_credentials.iter().find(|c| c.vc_hash == vc_hash))
+            .cloned();
+
+        let verifier_id = env::predecessor_account_id();
+        let credential = credential.filter(|credential| {
+            credential.allowed_verifiers.as_ref().is_none_or(|verifiers| verifiers.contains(&verifier_id))
+        });
+        let Some(credential) = credential else {
+            refund_to_predecessor(attached);
+            return CredentialVerification {
+                account_id,
+                vc_hash,
+                valid: false,
+                credential_type: None,
+                issuer: None,
+                issued_at: None,
+                expires_at: None,
+                revocation: None,
+                suspended: false,
+            };
+        };
+
+        let fee = credential.access_fee.unwrap_or(NearToken::from_yoctonear(0));
+        assert!(
+            attached >= fee,
+            "Verifying this credential requires an access fee of {} yoctoNEAR.", fee.as_yoctonear()
+        );
+        if !fee.is_zero() {
+            self.internal_credit_access_fee(&account_id, fee);
+            DidEvent::CredentialAccessFeePaid {
+                account_id: account_id.clone(),
+                vc_hash: vc_hash.clone(),
+                verifier_id,
+                fee,
+            }.emit();
+        }
+        refund_to_predecessor(attached.saturating_sub(fee));
+
+        let suspended = self.is_credential_suspended(&credential);
+        CredentialVerification {
+            account_id,
+            vc_hash,
+            valid: credential.revocation.is_none()
+                && !suspended
+                && credential.expires_at.is_none_or(|expires_at| expires_at > env::block_timestamp()),
+            credential_type: Some(credential.credential_type),
+            issuer: Some(credential.issuer),
+            issued_at: Some(credential.issued_at),
+            expires_at: credential.expires_at,
+            revocation: credential.revocation,
+            suspended,
+        }
+    }
+
+    /// Sets the commitment (e.g. a hash of the next update key) that the caller's
+    /// next document update is expected to reveal. Pass `None` to clear it.
+    pub fn set_next_update_commitment(&mut self, commitment: Option<String>) {
+        let signer_id = env::predecessor_account_id();
+        self.internal_update_did(&signer_id);
+        self.metadata.get_mut(&signer_id).unwrap().next_update_commitment = commitment;
+        env::log_str(&format!("Next update commitment set for: {}", signer_id));
+    }
+
+    /// Offers the caller's DID to `new_controller_id`, which must not already have a DID and
+    /// takes it over by calling `accept_did_controller`. Replaces any pending offer.
+    pub fn transfer_did_controller(&mut self, new_controller_id: AccountId) {
+        let signer_id = env::predecessor_account_id();
+        self.assert_can_transfer_did(&signer_id, &new_controller_id);
+        self.internal_update_did(&signer_id);
+        self.pending_controllers.insert(signer_id.clone(), new_controller_id.clone());
+        DidEvent::DidControllerTransferProposed {
+            previous_controller_id: signer_id,
+            new_controller_id,
+        }.emit();
+    }
+
+    /// Withdraws the caller's pending DID transfer.
+    pub fn cancel_did_controller_transfer(&mut self) {
+        let signer_id = env::predecessor_account_id();
+        let new_controller_id = self.pending_controllers.remove(&signer_id)
+            .unwrap_or_else(|| env::panic_str("No DID transfer is pending for this account."));
+        DidEvent::DidControllerTransferCancelled {
+            previous_controller_id: signer_id,
+            new_controller_id,
+        }.emit();
+    }
+
+    /// Accepts the DID `previous_controller_id` offered to the caller, moving its document,
+    /// metadata and credentials over. Bound credentials do not survive the transfer:
+    /// they are removed and an event is emitted for each so their issuers are notified.
+    /// Personhood attestations, approval delegates, trusted issuers and verification methods
+    /// are likewise dropped, as they belong to the previous controller; the new controller
+    /// adds its own keys. The DID must not be part of an organization tree, and none of its
+    /// credentials may have an unresolved challenge.
+    pub fn accept_did_controller(&mut self, previous_controller_id: AccountId) -> DidDocument {
+        let new_controller_id = env::predecessor_account_id();
+        assert!(
+            self.pending_controllers.get(&previous_controller_id) == Some(&new_controller_id),
+            "No DID transfer to this account is pending."
+        );
+        self.pending_controllers.remove(&previous_controller_id);
+        self.assert_can_transfer_did(&previous_controller_id, &new_controller_id);
+        self.internal_update_did(&previous_controller_id);
+
+        let mut did_doc = self.dids.remove(&previous_controller_id).unwrap();
+        let metadata = self.metadata.remove(&previous_controller_id).unwrap();
+        let (invalidated, retained): (Vec<_>, Vec<_>) = did_doc.verifiable_credentials
+            .drain(..)
+            .partition(|c| c.bound);
+        did_doc.verifiable_credentials = retained;
+        did_doc.verification_methods.clear();
+        did_doc.owner_id = new_controller_id.clone();
+
+        self.internal_unindex_update(&previous_controller_id, metadata.updated);
+        self.internal_index_update(&new_controller_id, None, metadata.updated);
+        self.dids.insert(new_controller_id.clone(), did_doc.clone());
+        self.metadata.insert(new_controller_id.clone(), metadata);
+        if let Some(deposit) = self.storage_deposits.remove(&previous_controller_id) {
+            self.storage_deposits.insert(new_controller_id.clone(), deposit);
+        }
+        self.org_delegates.remove(&previous_controller_id);
+        self.nft_visible.remove(&previous_controller_id);
+        self.internal_clear_personhood(&previous_controller_id);
+        self.approval_delegates.remove(&previous_controller_id);
+        self.trusted_issuers.remove(&previous_controller_id);
+
+        for credential in invalidated {
+            DidEvent::BoundCredentialInvalidated {
+                previous_controller_id: previous_controller_id.clone(),
+                new_controller_id: new_controller_id.clone(),
+                vc_hash: credential.vc_hash,
+                issuer: credential.issuer,
+            }.emit();
+        }
+        DidEvent::DidControllerTransferred {
+            previous_controller_id,
+            new_controller_id,
+        }.emit();
+        did_doc
+    }
+
+    /// Deactivates the caller's DID. The document stays resolvable but can no longer be
+    /// modified, and its credentials no longer verify. Unlike `purge_did`, nothing is erased.
+    pub fn deactivate_did(&mut self) {
+        let signer_id = env::predecessor_account_id();
+        self.internal_update_did(&signer_id);
+        self.metadata.get_mut(&signer_id).unwrap().deactivated = true;
+        self.record_deactivation();
+        env::log_str(&format!("DID deactivated for: {}", signer_id));
+    }
+
+    /// Permanently erases the caller's DID document, including every credential hash,
+    /// verification method and service endpoint. Only a tombstone with the account id and
+    /// deletion timestamp is kept for auditability. The storage released is refunded, up to
+    /// the storage deposit paid for the DID.
+    /// The account may register a fresh DID afterwards.
+    pub fn purge_did(&mut self) -> DidTombstone {
+        let signer_id = env::predecessor_account_id();
+        self.internal_upgrade_legacy_did(&signer_id);
+        assert!(self.dids.contains_key(&signer_id), "DID not found for this account.");
+        assert!(
+            !self.org_children.contains_key(&signer_id),
+            "Revoke all child DIDs before purging an organization DID."
+        );
+
+        let initial_storage = env::storage_usage();
+        if let Some(membership) = self.org_parents.remove(&signer_id) {
+            if let Some(siblings) = self.org_children.get_mut(&membership.parent_id) {
+                siblings.remove(&signer_id);
+            }
+        }
+        self.org_delegates.remove(&signer_id);
+        self.nft_visible.remove(&signer_id);
+        self.internal_clear_personhood(&signer_id);
+        self.approval_delegates.remove(&signer_id);
+        self.trusted_issuers.remove(&signer_id);
+        self.pending_controllers.remove(&signer_id);
+        let deposit = self.storage_deposits.remove(&signer_id).unwrap_or(NearToken::from_yoctonear(0));
+        self.dids.remove(&signer_id);
+        if let Some(metadata) = self.metadata.remove(&signer_id) {
+            self.internal_unindex_update(&signer_id, metadata.updated);
+        }
+        self.record_purge();
+        let tombstone = DidTombstone {
+            account_id: signer_id.clone(),
+            deleted_at: env::block_timestamp(),
+        };
+        self.tombstones.insert(signer_id.clone(), tombstone.clone());
+        self.dids.flush();
+        self.metadata.flush();
+        self.org_parents.flush();
+        self.org_children.flush();
+        self.org_delegates.flush();
+        self.nft_visible.flush();
+        self.personhood.flush();
+        self.human_ids.flush();
+        self.approval_delegates.flush();
+        self.trusted_issuers.flush();
+        self.pending_controllers.flush();
+        self.dids_by_update.flush();
+        self.tombstones.flush();
+        self.storage_deposits.flush();
+
+        let bytes_freed = initial_storage.saturating_sub(env::storage_usage());
+        let refund = env::storage_byte_cost().saturating_mul(bytes_freed.into()).min(deposit);
+        if !refund.is_zero() {
+            Promise::new(signer_id.clone()).transfer(refund);
+        }
+
+        env::log_str(&format!("DID purged for: {}", signer_id));
+        tombstone
+    }
+
+    /// Account `account_id`'s DID is being transferred to, if a transfer is pending.
+    /// View function.
+    pub fn get_pending_did_controller(&self, account_id: AccountId) -> Option<AccountId> {
+        self.pending_controllers.get(&account_id).cloned()
+    }
+
+    /// Retrieves the purge tombstone for a given AccountId, if its DID was ever purged.
+    /// View function.
+    pub fn get_did_tombstone(&self, account_id: AccountId) -> Option<DidTombstone> {
+        self.tombstones.get(&account_id).cloned()
+    }
+
+    /// Retrieves the DidDocument for a given AccountId, without its gated credentials.
+    /// This is a view function and does not modify the state.
+    /// `account_id`: The NEAR AccountId whose DID is to be retrieved.
+    pub fn get_did_document(&self, account_id: AccountId) -> Option<DidDocument> {
+        match self.dids.get(&account_id) {
+            Some(did_doc) => Some(public_document(did_doc)),
+            None => self.internal_legacy_did(&account_id).map(|(did_doc, _)| public_document(&did_doc)),
+        }
+    }
+
+    /// Resolves `account_id`'s DID into its document and document metadata. Gated credentials
+    /// are left out of the document.
+    /// View function.
+    pub fn resolve_did(&self, account_id: AccountId) -> Option<DidResolution> {
+        self.internal_resolve(&account_id)
+    }
+
+    /// Enumerates every registered DID in registry order. Purging or transferring a DID moves
+    /// the last DID into its slot, so offsets shift between calls and a page may skip or
+    /// repeat DIDs; indexers should sync with `get_dids_updated_since`, which pages by key.
+    /// View function.
+    pub fn get_all_dids(&self, from_index: Option<u64>, limit: Option<u64>) -> Vec<DidResolution> {
+        self.dids
+            .keys()
+            .skip(from_index.unwrap_or(0) as usize)
+            .take(limit.unwrap_or(DEFAULT_PAGE_LIMIT) as usize)
+            .filter_map(|account_id| self.internal_resolve(account_id))
+            .collect()
+    }
+
+    /// Returns the total number of registered DIDs.
+    /// View function.
+    pub fn get_dids_count(&self) -> u32 {
+        self.dids.len() + self.legacy_dids.len()
+    }
+
+    /// Returns the hex-encoded sha256 of `account_id`'s DID document, so resolvers can
+    /// cheaply check whether a cached copy is stale.
+    /// View function.
+    pub fn get_document_hash(&self, account_id: AccountId) -> Option<String> {
+        self.dids.get(&account_id).map(document_hash)
+    }
+
+    /// Retrieves the document metadata for a given AccountId.
+    /// View function.
+    pub fn get_did_metadata(&self, account_id: AccountId) -> Option<DidDocumentMetadata> {
+        self.metadata.get(&account_id).cloned()
+    }
+
+    /// Looks up a verification method of `account_id` by id, e.g. the recipient key of an
+    /// encrypted credential payload. Returns `None` once the DID is deactivated.
+    /// View function.
+    pub fn get_verification_method(&self, account_id: AccountId, key_id: String) -> Option<VerificationMethod> {
+        if !self.is_active(&account_id) {
+            return None;
+        }
+        self.dids.get(&account_id)?
+            .verification_methods
+            .iter()
+            .find(|m| m.id == key_id)
+            .cloned()
+    }
+
+    /// Returns the ungated credentials of `account_id` whose type matches `credential_type`,
+    /// paginated by `from_index` and `limit` over the matching records.
+    /// View function.
+    pub fn get_credentials_by_type(
+        &self,
+        account_id: AccountId,
+        credential_type: String,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Vec<CredentialRecord> {
+        let credential_type = credential_type.to_lowercase();
+        self.filter_credentials(&account_id, from_index, limit, |c| c.credential_type == credential_type)
+    }
+
+    /// Returns the credentials of `account_id` carrying `tag`, paginated like
+    /// `get_credentials_by_type`.
+    /// View function.
+    pub fn get_credentials_by_tag(
+        &self,
+        account_id: AccountId,
+        tag: String,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Vec<CredentialRecord> {
+        let tag = tag.to_lowercase();
+        self.filter_credentials(&account_id, from_index, limit, |c| c.tags.contains(&tag))
+    }
+
+    /// Returns the registry owner.
+    /// View function.
+    pub fn get_owner_id(&self) -> AccountId {
+        self.owner_id.clone()
+    }
+
+    /// Checks if a DID exists for a given AccountId.
+    #[allow(dead_code)]
+    pub fn did_exists(&self, account_id: AccountId) -> bool {
+        self.has_did(&account_id)
+    }
+}
+
+impl DidRegistry {
+    pub(crate) fn assert_owner(&self) {
+        if env::predecessor_account_id() != self.owner_id {
+            ContractError::OwnerOnly.panic();
+        }
+    }
+
+    /// Returns the caller's active DID document for modification, bumping its
+    /// `updated` timestamp and `version_id`. A document still in the original layout is
+    /// upgraded first.
+    fn internal_update_did(&mut self, account_id: &AccountId) -> &mut DidDocument {
+        self.internal_upgrade_legacy_did(account_id);
+        let now = env::block_timestamp();
+        let metadata = self.metadata.get_mut(account_id)
+            .unwrap_or_else(|| env::panic_str("DID not found for this account."));
+        assert!(!metadata.deactivated, "DID has been deactivated.");
+        let previous = metadata.updated;
+        metadata.updated = now;
+        metadata.version_id += 1;
+        self.internal_index_update(account_id, Some(previous), now);
+
+        let did_doc = self.dids.get_mut(account_id)
+            .unwrap_or_else(|| env::panic_str("DID not found for this account."));
+        did_doc.last_updated = now;
+        did_doc
+    }
+
+    /// Asserts the DID of `controller_id` can move to `new_controller_id`.
+    fn assert_can_transfer_did(&self, controller_id: &AccountId, new_controller_id: &AccountId) {
+        assert!(controller_id != new_controller_id, "DID is already controlled by this account.");
+        assert!(
+            !self.dids.get(controller_id).is_some_and(|doc| doc.verifiable_credentials.iter().any(|c| c.challenge_id.is_some())),
+            "Resolve open credential challenges before transferring this DID."
+        );
+        assert!(!self.has_did(new_controller_id), "New controller already has a DID.");
+        assert!(
+            !self.org_parents.contains_key(controller_id) && !self.org_children.contains_key(controller_id),
+            "Leave or dissolve the organization before transferring this DID."
+        );
+    }
+
+    fn internal_resolve(&self, account_id: &AccountId) -> Option<DidResolution> {
+        let (did_document, did_document_metadata) = match self.dids.get(account_id) {
+            Some(did_doc) => (public_document(did_doc), self.metadata.get(account_id)?.clone()),
+            None => {
+                let (did_doc, metadata) = self.internal_legacy_did(account_id)?;
+                (public_document(&did_doc), metadata)
+            }
+        };
+        let max_age = if did_document_metadata.deactivated {
+            DEACTIVATED_RESOLVER_MAX_AGE_SECS
+        } else {
+            RESOLVER_MAX_AGE_SECS
+        };
+        let cache_hints = ResolutionCacheHints {
+            max_age,
+            content_hash: document_hash(&did_document),
+        };
+        Some(DidResolution {
+            id: format!("{}{}", DID_METHOD_PREFIX, account_id),
+            did_document,
+            did_document_metadata,
+            cache_hints,
+        })
+    }
+
+    /// Charges the storage `account_id`'s DID grew by since `initial_storage` to the attached
+    /// deposit, refunding the rest, and records it as paid towards the DID.
+    pub(crate) fn internal_charge_document_storage(&mut self, account_id: &AccountId, initial_storage: u64) {
+        self.dids.flush();
+        self.metadata.flush();
+        self.dids_by_update.flush();
+        let deposit = settle_storage_deposit(initial_storage);
+        self.internal_add_storage_deposit(account_id, deposit);
+    }
+
+    /// Records `amount` of storage deposit as paid towards `account_id`'s DID.
+    pub(crate) fn internal_add_storage_deposit(&mut self, account_id: &AccountId, amount: NearToken) {
+        if amount.is_zero() {
+            return;
+        }
+        let deposit = self.storage_deposits.get(account_id).copied().unwrap_or(NearToken::from_yoctonear(0));
+        self.storage_deposits.insert(account_id.clone(), deposit.saturating_add(amount));
+    }
+
+    /// Whether `account_id` has a DID, in the current or the original layout.
+    fn has_did(&self, account_id: &AccountId) -> bool {
+        self.dids.contains_key(account_id) || self.legacy_dids.contains_key(account_id)
+    }
+
+    pub(crate) fn is_active(&self, account_id: &AccountId) -> bool {
+        self.metadata.get(account_id).is_some_and(|m| !m.deactivated)
+    }
+
+    pub(crate) fn internal_get_own_credential_mut(&mut self, account_id: &AccountId, vc_hash: &str) -> &mut CredentialRecord {
+        self.internal_update_did(account_id)
+            .verifiable_credentials
+            .iter_mut()
+            .find(|c| c.vc_hash == vc_hash)
+            .unwrap_or_else(|| env::panic_str("Verifiable credential not found for this DID."))
+    }
+
+    fn filter_credentials(
+        &self,
+        account_id: &AccountId,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+        predicate: impl Fn(&CredentialRecord) -> bool,
+    ) -> Vec<CredentialRecord> {
+        let Some(did_doc) = self.dids.get(account_id) else {
+            return Vec::new();
+        };
+        did_doc.verifiable_credentials
+            .iter()
+            .filter(|c| !c.is_gated() && predicate(c))
+            .skip(from_index.unwrap_or(0) as usize)
+            .take(limit.unwrap_or(DEFAULT_PAGE_LIMIT) as usize)
+            .cloned()
+            .collect()
+    }
+}
+
+/// Builds a credential record, normalizing the type and tags to lowercase.
+pub(crate) fn new_credential_record(
+    input: CredentialInput,
+    issuer: &AccountId,
+    issued_at: u64,
+    verification_methods: &[VerificationMethod],
+) -> CredentialRecord {
+    let credential_type = input.credential_type
+        .map(|t| t.to_lowercase())
+        .unwrap_or_else(|| DEFAULT_CREDENTIAL_TYPE.to_string());
+    assert!(!credential_type.is_empty(), "Credential type must not be empty.");
+
+    let tags: Vec<String> = input.tags.unwrap_or_default().into_iter().map(|t| t.to_lowercase()).collect();
+    assert!(
+        tags.len() <= MAX_CREDENTIAL_TAGS,
+        "Cannot attach more than {} tags to a credential.", MAX_CREDENTIAL_TAGS
+    );
+    assert_unique_non_empty(tags.iter(), "Credential tag");
+    if let Some(expires_at) = input.expires_at {
+        assert!(expires_at > issued_at, "Credential expiry must be in the future.");
+    }
+    if let Some(payload) = &input.encrypted_payload {
+        assert_valid_encrypted_payload(payload, verification_methods);
+    }
+
+    CredentialRecord {
+        vc_hash: input.vc_hash,
+        credential_type,
+        tags,
+        issuer: issuer.clone(),
+        issued_at,
+        anchor_block_height: env::block_height(),
+        allowed_verifiers: None,
+        bound: input.bound.unwrap_or(false),
+        expires_at: input.expires_at,
+        expiry_warned: false,
+        access_fee: None,
+        revocation: None,
+        encrypted_payload: input.encrypted_payload,
+        challenge_id: None,
+        suspended: false,
+    }
+}
+
+/// `did_doc` without its gated credentials, as served by the free views.
+pub(crate) fn public_document(did_doc: &DidDocument) -> DidDocument {
+    DidDocument {
+        verifiable_credentials: did_doc.verifiable_credentials.iter().filter(|c| !c.is_gated()).cloned().collect(),
+        ..did_doc.clone()
+    }
+}
+
+fn document_hash(did_doc: &DidDocument) -> String {
+    let bytes = borsh::to_vec(did_doc).unwrap_or_else(|_| env::panic_str("Failed to serialize DID document."));
+    env::sha256_array(&bytes)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<String>()
+}
+
+/// Asserts that the attached deposit covers the storage written since `initial_storage`
+/// and refunds the remainder to the caller. Returns the deposit kept for storage.
+pub(crate) fn settle_storage_deposit(initial_storage: u64) -> NearToken {
+    let bytes_used = env::storage_usage().saturating_sub(initial_storage);
+    let required = env::storage_byte_cost().saturating_mul(bytes_used.into());
+    let attached = env::attached_deposit();
+    assert!(
+        attached >= required,
+        "Insufficient deposit for storage. Required: {} yoctoNEAR.", required.as_yoctonear()
+    );
+
+    refund_to_predecessor(attached.saturating_sub(required));
+    required
+}
+
+fn refund_to_predecessor(amount: NearToken) {
+    if !amount.is_zero() {
+        Promise::new(env::predecessor_account_id()).transfer(amount);
+    }
+}
+
+fn assert_unique_non_empty<'a>(values: impl Iterator<Item = &'a String>, label: &str) {
+    let mut seen: Vec<&String> = Vec::new();
+    for value in values {
+        assert!(!value.is_empty(), "{} must not be empty.", label);
+        assert!(!seen.contains(&value), "Duplicate {}: {}", label.to_lowercase(), value);
+        seen.push(value);
+    }
+}
+
+fn assert_valid_verification_methods(methods: &[VerificationMethod]) {
+    assert!(
+        methods.len() <= MAX_VERIFICATION_METHODS,
+        "Cannot register more than {} verification methods.", MAX_VERIFICATION_METHODS
+    );
+    assert_unique_non_empty(methods.iter().map(|m| &m.id), "Verification method id");
+    for method in methods {
+        assert!(!method.method_type.is_empty(), "Verification method type must not be empty.");
+        assert!(!method.public_key.is_empty(), "Verification method public key must not be empty.");
+    }
+}
+
+fn assert_valid_encrypted_payload(payload: &EncryptedPayload, verification_methods: &[VerificationMethod]) {
+    assert!(!payload.ciphertext_hash.is_empty(), "Ciphertext hash must not be empty.");
+    assert!(
+        SUPPORTED_ENCRYPTION_ALGORITHMS.contains(&payload.algorithm.as_str()),
+        "Unsupported encryption algorithm: {}", payload.algorithm
+    );
+    assert!(
+        verification_methods.iter().any(|m| m.id == payload.recipient_key_id),
+        "Recipient key {} is not a verification method of this DID.", payload.recipient_key_id
+    );
+}
+
+fn assert_valid_services(services: &[ServiceEndpoint]) {
+    assert!(
+        services.len() <= MAX_SERVICES,
+        "Cannot register more than {} services.", MAX_SERVICES
+    );
+    assert_unique_non_empty(services.iter().map(|s| &s.id), "Service id");
+    for service in services {
+        assert!(!service.service_type.is_empty(), "Service type must not be empty.");
+        assert!(!service.endpoint.is_empty(), "Service endpoint must not be empty.");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use near_sdk::mock::MockAction;
+    use near_sdk::test_utils::{accounts, get_created_receipts, VMContextBuilder};
+    use near_sdk::testing_env;
+
+    use super::*;
+
+    fn call_with_deposit(attached: NearToken) {
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(accounts(1))
+            .attached_deposit(attached)
+            .build());
+    }
+
+    fn refunded_to(account_id: &AccountId) -> NearToken {
+        get_created_receipts()
+            .into_iter()
+            .filter(|receipt| &receipt.receiver_id == account_id)
+            .flat_map(|receipt| receipt.actions)
+            .fold(NearToken::from_yoctonear(0), |total, action| match action {
+                MockAction::Transfer { deposit, .. } => total.saturating_add(deposit),
+                _ => total,
+            })
+    }
+
+    #[test]
+    fn growing_a_document_is_charged_to_its_storage_deposit() {
+        call_with_deposit(NearToken::from_near(1));
+        let mut contract = DidRegistry::new();
+        contract.register_did(None, None, None);
+        let registered = contract.storage_deposits.get(&accounts(1)).copied().unwrap();
+
+        call_with_deposit(NearToken::from_near(1));
+        contract.add_verifiable_credential("vc-1".to_string(), None, None, None, None);
+        let charged = contract.storage_deposits.get(&accounts(1)).copied().unwrap();
+        assert!(charged > registered);
+        assert_eq!(refunded_to(&accounts(1)), NearToken::from_near(1).saturating_sub(charged.saturating_sub(registered)));
+    }
+
+    #[test]
+    #[should_panic(expected = "Insufficient deposit for storage.")]
+    fn growing_a_document_needs_a_deposit() {
+        call_with_deposit(NearToken::from_near(1));
+        let mut contract = DidRegistry::new();
+        contract.register_did(None, None, None);
+
+        call_with_deposit(NearToken::from_yoctonear(0));
+        contract.add_verifiable_credential("vc-1".to_string(), None, None, None, None);
+    }
+
+    #[test]
+    fn purging_leaves_a_tombstone_and_refunds_the_storage_deposit() {
+        call_with_deposit(NearToken::from_near(1));
+        let mut contract = DidRegistry::new();
+        contract.register_did(None, None, None);
+        call_with_deposit(NearToken::from_near(1));
+        contract.add_verifiable_credential("vc-1".to_string(), None, None, None, None);
+        let deposit = contract.storage_deposits.get(&accounts(1)).copied().unwrap();
+
+        testing_env!(VMContextBuilder::new().predecessor_account_id(accounts(1)).block_timestamp(42).build());
+        let tombstone = contract.purge_did();
+        assert_eq!(tombstone, DidTombstone { account_id: accounts(1), deleted_at: 42 });
+        assert_eq!(contract.get_did_tombstone(accounts(1)), Some(tombstone));
+        assert!(!contract.did_exists(accounts(1)));
+        assert!(contract.storage_deposits.get(&accounts(1)).is_none());
+
+        let refund = refunded_to(&accounts(1));
+        assert!(!refund.is_zero() && refund <= deposit);
+    }
+
+    #[test]
+    fn unauthorized_verifiers_cannot_tell_a_gated_credential_exists() {
+        call_with_deposit(NearToken::from_near(1));
+        let mut contract = DidRegistry::new();
+        contract.register_did(None, None, None);
+        contract.add_verifiable_credential("vc-1".to_string(), None, None, None, None);
+        contract.set_credential_verifiers("vc-1".to_string(), Some(vec![accounts(2)]));
+
+        testing_env!(VMContextBuilder::new().predecessor_account_id(accounts(3)).build());
+        let gated = contract.verify_credential(accounts(1), "vc-1".to_string());
+        let missing = contract.verify_credential(accounts(1), "vc-2".to_string());
+        assert_eq!(gated, CredentialVerification { vc_hash: "vc-1".to_string(), ..missing });
+
+        testing_env!(VMContextBuilder::new().predecessor_account_id(accounts(2)).build());
+        assert!(contract.verify_credential(accounts(1), "vc-1".to_string()).valid);
+    }
+
+    #[test]
+    fn controller_transfers_move_the_document_once_accepted() {
+        call_with_deposit(NearToken::from_near(1));
+        let mut contract = DidRegistry::new();
+        contract.register_did(
+            Some(vec![
+                CredentialInput {
+                    vc_hash: "vc-kept".to_string(),
+                    credential_type: None,
+                    tags: None,
+                    bound: None,
+                    expires_at: None,
+                    encrypted_payload: None,
+                },
+                CredentialInput {
+                    vc_hash: "vc-bound".to_string(),
+                    credential_type: None,
+                    tags: None,
+                    bound: Some(true),
+                    expires_at: None,
+                    encrypted_payload: None,
+                },
+            ]),
+            None,
+            None,
+        );
+        contract.transfer_did_controller(accounts(2));
+        assert_eq!(contract.get_pending_did_controller(accounts(1)), Some(accounts(2)));
+        assert!(contract.did_exists(accounts(1)));
+
+        testing_env!(VMContextBuilder::new().predecessor_account_id(accounts(2)).build());
+        let did_doc = contract.accept_did_controller(accounts(1));
+        assert_eq!(did_doc.owner_id, accounts(2));
+        let vc_hashes: Vec<&str> = did_doc.verifiable_credentials.iter().map(|c| c.vc_hash.as_str()).collect();
+        assert_eq!(vc_hashes, vec!["vc-kept"]);
+        assert!(!contract.did_exists(accounts(1)));
+        assert!(contract.get_pending_did_controller(accounts(1)).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "No DID transfer to this account is pending.")]
+    fn controller_transfers_can_only_be_accepted_by_their_recipient() {
+        call_with_deposit(NearToken::from_near(1));
+        let mut contract = DidRegistry::new();
+        contract.register_did(None, None, None);
+        contract.transfer_did_controller(accounts(2));
+
+        testing_env!(VMContextBuilder::new().predecessor_account_id(accounts(3)).build());
+        contract.accept_did_controller(accounts(1));
+    }
+}
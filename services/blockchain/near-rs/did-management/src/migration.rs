@@ -0,0 +1,166 @@
+// services/blockchain/near-rs/did-management/src/migration.rs
+use near_sdk::{near, env, AccountId};
+use near_sdk::store::IterableMap;
+
+use crate::{
+    new_credential_record, CredentialInput, DidDocument, DidDocumentMetadata, DidRegistry, DidRegistryExt,
+    DEFAULT_PAGE_LIMIT,
+};
+
+/// DID document as stored before credentials carried records, keys and services.
+#[near(serializers = [borsh])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct DidDocumentV0 {
+    pub owner_id: AccountId,
+    pub verifiable_credentials: Vec<String>,
+    pub last_updated: u64,
+}
+
+impl DidDocumentV0 {
+    /// The document and metadata this DID has in the current layout. Credentials become
+    /// self-issued records of the default type, and the document counts as created at its
+    /// last update.
+    fn into_current(self) -> (DidDocument, DidDocumentMetadata) {
+        let verifiable_credentials = self.verifiable_credentials
+            .into_iter()
+            .map(|vc_hash| new_credential_record(
+                CredentialInput {
+                    vc_hash,
+                    credential_type: None,
+                    tags: None,
+                    bound: None,
+                    expires_at: None,
+                    encrypted_payload: None,
+                },
+                &self.owner_id,
+                self.last_updated,
+                &[],
+            ))
+            .collect();
+        let metadata = DidDocumentMetadata {
+            created: self.last_updated,
+            updated: self.last_updated,
+            version_id: 1,
+            next_update_commitment: None,
+            deactivated: false,
+        };
+        let did_doc = DidDocument {
+            owner_id: self.owner_id,
+            verifiable_credentials,
+            verification_methods: Vec::new(),
+            services: Vec::new(),
+            last_updated: self.last_updated,
+        };
+        (did_doc, metadata)
+    }
+}
+
+/// Root state layout before the registry had an owner: the DID documents only.
+#[near(serializers = [borsh])]
+pub struct DidRegistryV0 {
+    pub dids: IterableMap<AccountId, DidDocumentV0>,
+}
+
+#[near]
+impl DidRegistry {
+    /// Upgrades the stored state to the layout of the deployed code. Call in the same
+    /// transaction as the code deployment. Documents stored under the original layout stay
+    /// where they are and are upgraded by `migrate_legacy_dids`, or when their holder next
+    /// updates them; until then only `get_did_document` and `resolve_did` serve them.
+    /// `owner_id`: The registry owner, who manages oracle roles.
+    #[private]
+    #[init(ignore_state)]
+    pub fn migrate(owner_id: AccountId) -> Self {
+        let bytes = env::storage_read(b"STATE").unwrap_or_else(|| env::panic_str("Contract is not initialized."));
+        if let Ok(state) = near_sdk::borsh::from_slice::<DidRegistry>(&bytes) {
+            return state;
+        }
+        let old: DidRegistryV0 = near_sdk::borsh::from_slice(&bytes)
+            .unwrap_or_else(|_| env::panic_str("Stored state does not match any known layout."));
+        let mut state = Self::new();
+        state.owner_id = owner_id;
+        state.legacy_dids = old.dids;
+        state
+    }
+
+    /// Maintenance crank callable by anyone. Upgrades up to `limit` documents stored under
+    /// the original layout. Returns the number still waiting.
+    pub fn migrate_legacy_dids(&mut self, limit: Option<u64>) -> u32 {
+        let account_ids: Vec<AccountId> = self.legacy_dids
+            .keys()
+            .take(limit.unwrap_or(DEFAULT_PAGE_LIMIT) as usize)
+            .cloned()
+            .collect();
+        for account_id in account_ids {
+            self.internal_upgrade_legacy_did(&account_id);
+        }
+        self.legacy_dids.len()
+    }
+}
+
+impl DidRegistry {
+    /// Moves `account_id`'s document out of the original layout, if it is still there.
+    /// Legacy documents never paid a storage deposit, so the contract carries their storage.
+    pub(crate) fn internal_upgrade_legacy_did(&mut self, account_id: &AccountId) {
+        let Some(legacy) = self.legacy_dids.remove(account_id) else {
+            return;
+        };
+        let (did_doc, metadata) = legacy.into_current();
+        self.record_registration(metadata.created);
+        self.record_credentials_issued(account_id, did_doc.verifiable_credentials.len() as u64);
+        self.internal_index_update(account_id, None, metadata.updated);
+        self.dids.insert(account_id.clone(), did_doc);
+        self.metadata.insert(account_id.clone(), metadata);
+    }
+
+    /// `account_id`'s document and metadata as they will read once upgraded, if it is still
+    /// stored under the original layout.
+    pub(crate) fn internal_legacy_did(&self, account_id: &AccountId) -> Option<(DidDocument, DidDocumentMetadata)> {
+        self.legacy_dids.get(account_id).cloned().map(DidDocumentV0::into_current)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use near_sdk::test_utils::{accounts, VMContextBuilder};
+    use near_sdk::testing_env;
+
+    use super::*;
+    use crate::StorageKey;
+
+    fn stored_v0_registry() {
+        testing_env!(VMContextBuilder::new().block_timestamp(42).build());
+        let mut dids = IterableMap::new(StorageKey::LegacyDids);
+        dids.insert(accounts(1), DidDocumentV0 {
+            owner_id: accounts(1),
+            verifiable_credentials: vec!["vc-1".to_string()],
+            last_updated: 7,
+        });
+        env::state_write(&DidRegistryV0 { dids });
+    }
+
+    #[test]
+    fn legacy_documents_resolve_until_migrated() {
+        stored_v0_registry();
+        let contract = DidRegistry::migrate(accounts(0));
+        assert_eq!(contract.get_owner_id(), accounts(0));
+        assert!(contract.did_exists(accounts(1)));
+
+        let resolution = contract.resolve_did(accounts(1)).expect("Legacy DID should resolve.");
+        assert_eq!(resolution.did_document.verifiable_credentials[0].vc_hash, "vc-1");
+        assert_eq!(resolution.did_document.verifiable_credentials[0].issuer, accounts(1));
+        assert_eq!(resolution.did_document_metadata.created, 7);
+    }
+
+    #[test]
+    fn migrating_legacy_documents_moves_them_to_the_current_layout() {
+        stored_v0_registry();
+        let mut contract = DidRegistry::migrate(accounts(0));
+        assert_eq!(contract.migrate_legacy_dids(None), 0);
+        assert!(contract.legacy_dids.is_empty());
+        assert_eq!(contract.get_dids_count(), 1);
+        assert_eq!(contract.get_did_document(accounts(1)).unwrap().verifiable_credentials.len(), 1);
+        assert_eq!(contract.get_did_metadata(accounts(1)).unwrap().version_id, 1);
+        assert_eq!(contract.get_dids_updated_since(0, None, None).len(), 1);
+    }
+}
@@ -0,0 +1,217 @@
+// services/blockchain/near-rs/did-management/src/nft.rs
+use near_sdk::{near, env, AccountId, PromiseOrValue};
+use near_sdk::json_types::U128;
+use near_sdk::serde_json::json;
+use std::collections::HashMap;
+
+use crate::{CredentialRecord, DidRegistry, DidRegistryExt, DEFAULT_PAGE_LIMIT};
+
+const NFT_METADATA_SPEC: &str = "nft-1.0.0";
+const TOKEN_ID_SEPARATOR: char = ':';
+
+/// NEP-177 contract metadata.
+#[derive(
+    Debug,
+    PartialEq,
+    near_sdk::serde::Serialize,
+    near_sdk::serde::Deserialize,
+    Clone
+)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NFTContractMetadata {
+    pub spec: String,
+    pub name: String,
+    pub symbol: String,
+    pub icon: Option<String>,
+    pub base_uri: Option<String>,
+    pub reference: Option<String>,
+    pub reference_hash: Option<String>,
+}
+
+/// NEP-177 token metadata.
+#[derive(
+    Debug,
+    PartialEq,
+    near_sdk::serde::Serialize,
+    near_sdk::serde::Deserialize,
+    Clone
+)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TokenMetadata {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub media: Option<String>,
+    pub media_hash: Option<String>,
+    pub copies: Option<u64>,
+    pub issued_at: Option<String>,
+    pub expires_at: Option<String>,
+    pub starts_at: Option<String>,
+    pub updated_at: Option<String>,
+    pub extra: Option<String>,
+    pub reference: Option<String>,
+    pub reference_hash: Option<String>,
+}
+
+/// NEP-171 token. Credential tokens never carry approvals.
+#[derive(
+    Debug,
+    PartialEq,
+    near_sdk::serde::Serialize,
+    near_sdk::serde::Deserialize,
+    Clone
+)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Token {
+    pub token_id: String,
+    pub owner_id: AccountId,
+    pub metadata: Option<TokenMetadata>,
+    pub approved_account_ids: Option<HashMap<AccountId, u64>>,
+}
+
+/// Read-only NEP-171/177/181 layer exposing credentials as soulbound tokens.
+/// Token ids have the form `<account_id>:<vc_hash>`. Only ungated credentials of DIDs whose
+/// subject opted in are exposed, and every transfer or approval is rejected.
+#[near]
+impl DidRegistry {
+    /// Shows or hides the caller's credentials in the NFT views.
+    pub fn set_credentials_nft_visible(&mut self, visible: bool) {
+        let signer_id = env::predecessor_account_id();
+        assert!(self.is_active(&signer_id), "DID not found or deactivated.");
+        if visible {
+            self.nft_visible.insert(signer_id.clone());
+        } else {
+            self.nft_visible.remove(&signer_id);
+        }
+        env::log_str(&format!("Credential NFT visibility set to {} for: {}", visible, signer_id));
+    }
+
+    /// NEP-177 contract metadata.
+    /// View function.
+    pub fn nft_metadata(&self) -> NFTContractMetadata {
+        NFTContractMetadata {
+            spec: NFT_METADATA_SPEC.to_string(),
+            name: "BioCrypticBank Credentials".to_string(),
+            symbol: "BCBVC".to_string(),
+            icon: None,
+            base_uri: None,
+            reference: None,
+            reference_hash: None,
+        }
+    }
+
+    /// Returns the credential token with the given id, if visible.
+    /// View function.
+    pub fn nft_token(&self, token_id: String) -> Option<Token> {
+        let (account_id, vc_hash) = token_id.split_once(TOKEN_ID_SEPARATOR)?;
+        let account_id: AccountId = account_id.parse().ok()?;
+        self.visible_credentials(&account_id)
+            .find(|c| c.vc_hash == vc_hash)
+            .map(|c| credential_token(&account_id, c))
+    }
+
+    /// Counts every visible credential token.
+    /// View function.
+    pub fn nft_total_supply(&self) -> U128 {
+        U128(self.nft_visible.iter().map(|a| self.visible_credentials(a).count() as u128).sum())
+    }
+
+    /// Enumerates visible credential tokens across all opted-in DIDs.
+    /// View function.
+    pub fn nft_tokens(&self, from_index: Option<U128>, limit: Option<u64>) -> Vec<Token> {
+        self.nft_visible
+            .iter()
+            .flat_map(|account_id| self.visible_credentials(account_id).map(move |c| credential_token(account_id, c)))
+            .skip(from_index.map_or(0, |i| i.0 as usize))
+            .take(limit.unwrap_or(DEFAULT_PAGE_LIMIT) as usize)
+            .collect()
+    }
+
+    /// Counts the visible credential tokens of `account_id`.
+    /// View function.
+    pub fn nft_supply_for_owner(&self, account_id: AccountId) -> U128 {
+        U128(self.visible_credentials(&account_id).count() as u128)
+    }
+
+    /// Enumerates the visible credential tokens of `account_id`.
+    /// View function.
+    pub fn nft_tokens_for_owner(&self, account_id: AccountId, from_index: Option<U128>, limit: Option<u64>) -> Vec<Token> {
+        self.visible_credentials(&account_id)
+            .skip(from_index.map_or(0, |i| i.0 as usize))
+            .take(limit.unwrap_or(DEFAULT_PAGE_LIMIT) as usize)
+            .map(|c| credential_token(&account_id, c))
+            .collect()
+    }
+
+    /// Always panics: credential tokens are soulbound.
+    #[payable]
+    #[allow(unused_variables)]
+    pub fn nft_transfer(&mut self, receiver_id: AccountId, token_id: String, approval_id: Option<u64>, memo: Option<String>) {
+        env::panic_str("Credential tokens are soulbound and cannot be transferred.");
+    }
+
+    /// Always panics: credential tokens are soulbound.
+    #[payable]
+    #[allow(unused_variables)]
+    pub fn nft_transfer_call(
+        &mut self,
+        receiver_id: AccountId,
+        token_id: String,
+        approval_id: Option<u64>,
+        memo: Option<String>,
+        msg: String,
+    ) -> PromiseOrValue<bool> {
+        env::panic_str("Credential tokens are soulbound and cannot be transferred.");
+    }
+
+    /// Always panics: credential tokens cannot be approved for transfer.
+    #[payable]
+    #[allow(unused_variables)]
+    pub fn nft_approve(&mut self, token_id: String, account_id: AccountId, msg: Option<String>) {
+        env::panic_str("Credential tokens are soulbound and cannot be approved for transfer.");
+    }
+
+    /// Credential tokens never have approvals.
+    /// View function.
+    #[allow(unused_variables)]
+    pub fn nft_is_approved(&self, token_id: String, approved_account_id: AccountId, approval_id: Option<u64>) -> bool {
+        false
+    }
+}
+
+impl DidRegistry {
+    fn visible_credentials<'a>(&'a self, account_id: &AccountId) -> impl Iterator<Item = &'a CredentialRecord> + 'a {
+        let visible = self.nft_visible.contains(account_id) && self.is_active(account_id);
+        self.dids
+            .get(account_id)
+            .filter(|_| visible)
+            .into_iter()
+            .flat_map(|doc| doc.verifiable_credentials.iter())
+            .filter(|c| c.revocation.is_none() && !c.is_gated())
+    }
+}
+
+fn credential_token(account_id: &AccountId, credential: &CredentialRecord) -> Token {
+    Token {
+        token_id: format!("{}{}{}", account_id, TOKEN_ID_SEPARATOR, credential.vc_hash),
+        owner_id: account_id.clone(),
+        metadata: Some(TokenMetadata {
+            title: Some(credential.credential_type.clone()),
+            description: Some(format!("Verifiable credential issued by {}", credential.issuer)),
+            media: None,
+            media_hash: None,
+            copies: Some(1),
+            issued_at: Some((credential.issued_at / 1_000_000).to_string()),
+            expires_at: credential.expires_at.map(|e| (e / 1_000_000).to_string()),
+            starts_at: None,
+            updated_at: None,
+            extra: Some(json!({
+                "vc_hash": credential.vc_hash,
+                "issuer": credential.issuer,
+                "tags": credential.tags,
+            }).to_string()),
+            reference: None,
+            reference_hash: None,
+        }),
+        approved_account_ids: None,
+    }
+}
@@ -0,0 +1,351 @@
+// services/blockchain/near-rs/did-management/src/organization.rs
+use near_sdk::{near, env, AccountId};
+use near_sdk::store::IterableSet;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+
+use crate::{DidEvent, DidRegistry, DidRegistryExt, StorageKey, DEFAULT_PAGE_LIMIT};
+
+const MAX_SUBTREE_REVOCATIONS: usize = 100;
+const MAX_DEPARTMENT_DELEGATES: usize = 20;
+
+/// Link between a child DID and the organization DID that authorized it.
+#[derive(
+    Debug,
+    PartialEq,
+    near_sdk::serde::Serialize,
+    near_sdk::serde::Deserialize,
+    BorshDeserialize,
+    BorshSerialize,
+    Clone
+)]
+#[serde(crate = "near_sdk::serde")]
+pub struct OrgMembership {
+    pub parent_id: AccountId,
+    pub department: Option<String>,
+    pub authorized_by: AccountId,
+    pub authorized_at: u64,
+}
+
+/// An account allowed to manage child DIDs within one department of an organization.
+#[derive(
+    Debug,
+    PartialEq,
+    near_sdk::serde::Serialize,
+    near_sdk::serde::Deserialize,
+    BorshDeserialize,
+    BorshSerialize,
+    Clone
+)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DepartmentDelegate {
+    pub delegate_id: AccountId,
+    pub department: String,
+}
+
+#[derive(
+    Debug,
+    PartialEq,
+    near_sdk::serde::Serialize,
+    near_sdk::serde::Deserialize,
+    Clone
+)]
+#[serde(crate = "near_sdk::serde")]
+pub struct OrgMemberView {
+    pub child_id: AccountId,
+    pub membership: OrgMembership,
+}
+
+#[near]
+impl DidRegistry {
+    /// Invites `child_id`'s DID to join the `parent_id` organization, replacing any earlier
+    /// invite from it. The child becomes a member only once it calls `accept_org_invite`.
+    /// Callable by the organization itself, or by a delegate of the given department.
+    pub fn authorize_child_did(&mut self, parent_id: AccountId, child_id: AccountId, department: Option<String>) {
+        let caller_id = env::predecessor_account_id();
+        let department = department.map(|d| d.to_lowercase());
+        self.assert_org_manager(&caller_id, &parent_id, department.as_deref());
+        self.assert_can_join(&parent_id, &child_id);
+
+        self.org_invites.insert((child_id.clone(), parent_id.clone()), OrgMembership {
+            parent_id: parent_id.clone(),
+            department: department.clone(),
+            authorized_by: caller_id.clone(),
+            authorized_at: env::block_timestamp(),
+        });
+        DidEvent::OrgChildInvited { parent_id, child_id, department, invited_by: caller_id }.emit();
+    }
+
+    /// Accepts the `parent_id` organization's invite, making the caller's DID its child.
+    /// The caller must not already belong to an organization.
+    pub fn accept_org_invite(&mut self, parent_id: AccountId) {
+        let child_id = env::predecessor_account_id();
+        let membership = self.org_invites.remove(&(child_id.clone(), parent_id.clone()))
+            .unwrap_or_else(|| env::panic_str("No invite from this organization."));
+        self.assert_can_join(&parent_id, &child_id);
+        let (department, authorized_by) = (membership.department.clone(), membership.authorized_by.clone());
+
+        self.org_parents.insert(child_id.clone(), membership);
+        self.org_children
+            .entry(parent_id.clone())
+            .or_insert_with(|| IterableSet::new(StorageKey::OrgChildSet { account_id_hash: env::sha256(parent_id.as_bytes()) }))
+            .insert(child_id.clone());
+
+        DidEvent::OrgChildAuthorized { parent_id, child_id, department, authorized_by }.emit();
+    }
+
+    /// Declines the `parent_id` organization's invite to the caller's DID.
+    pub fn decline_org_invite(&mut self, parent_id: AccountId) {
+        let child_id = env::predecessor_account_id();
+        assert!(
+            self.org_invites.remove(&(child_id.clone(), parent_id.clone())).is_some(),
+            "No invite from this organization."
+        );
+        DidEvent::OrgInviteDeclined { parent_id, child_id }.emit();
+    }
+
+    /// Pending invite from `parent_id` to `child_id`, if any.
+    /// View function.
+    pub fn get_org_invite(&self, child_id: AccountId, parent_id: AccountId) -> Option<OrgMembership> {
+        self.org_invites.get(&(child_id, parent_id)).cloned()
+    }
+
+    /// Detaches `child_id` from its organization. Callable by the organization, or by a
+    /// delegate of the child's department. The child's own children stay attached to it.
+    pub fn revoke_child_did(&mut self, child_id: AccountId) {
+        let caller_id = env::predecessor_account_id();
+        let membership = self.org_parents.get(&child_id)
+            .unwrap_or_else(|| env::panic_str("DID does not belong to an organization."))
+            .clone();
+        self.assert_org_manager(&caller_id, &membership.parent_id, membership.department.as_deref());
+
+        self.internal_detach_child(&child_id, &membership.parent_id);
+        DidEvent::OrgChildRevoked { parent_id: membership.parent_id, child_id, revoked_by: caller_id }.emit();
+    }
+
+    /// Revokes `root_id` and every DID beneath it in a single call.
+    /// Callable by any ancestor of `root_id`, or by a delegate of `root_id`'s department
+    /// within its direct parent organization.
+    /// Returns the number of memberships revoked.
+    pub fn revoke_subtree(&mut self, root_id: AccountId) -> u32 {
+        let caller_id = env::predecessor_account_id();
+        let membership = self.org_parents.get(&root_id)
+            .unwrap_or_else(|| env::panic_str("DID does not belong to an organization."))
+            .clone();
+        if !self.is_org_ancestor(&caller_id, &root_id) {
+            self.assert_org_manager(&caller_id, &membership.parent_id, membership.department.as_deref());
+        }
+
+        let mut pending = vec![(root_id, membership.parent_id)];
+        let mut revoked: u32 = 0;
+        while let Some((child_id, parent_id)) = pending.pop() {
+            assert!(
+                (revoked as usize) < MAX_SUBTREE_REVOCATIONS,
+                "Subtree exceeds {} DIDs. Revoke it in smaller branches.", MAX_SUBTREE_REVOCATIONS
+            );
+            if let Some(grandchildren) = self.org_children.get(&child_id) {
+                pending.extend(grandchildren.iter().map(|g| (g.clone(), child_id.clone())));
+            }
+            self.internal_detach_child(&child_id, &parent_id);
+            if let Some(mut grandchildren) = self.org_children.remove(&child_id) {
+                grandchildren.clear();
+            }
+            DidEvent::OrgChildRevoked { parent_id, child_id, revoked_by: caller_id.clone() }.emit();
+            revoked += 1;
+        }
+        revoked
+    }
+
+    /// Lets the caller leave the organization it belongs to.
+    pub fn leave_organization(&mut self) {
+        let signer_id = env::predecessor_account_id();
+        let membership = self.org_parents.get(&signer_id)
+            .unwrap_or_else(|| env::panic_str("DID does not belong to an organization."))
+            .clone();
+        self.internal_detach_child(&signer_id, &membership.parent_id);
+        DidEvent::OrgChildRevoked { parent_id: membership.parent_id, child_id: signer_id.clone(), revoked_by: signer_id }.emit();
+    }
+
+    /// Allows `delegate_id` to authorize and revoke child DIDs in `department` of the caller's organization.
+    pub fn add_department_delegate(&mut self, department: String, delegate_id: AccountId) {
+        let org_id = env::predecessor_account_id();
+        assert!(self.is_active(&org_id), "DID not found or deactivated.");
+        let department = department.to_lowercase();
+        assert!(!department.is_empty(), "Department must not be empty.");
+
+        let delegates = self.org_delegates.entry(org_id.clone()).or_default();
+        assert!(
+            !delegates.iter().any(|d| d.delegate_id == delegate_id && d.department == department),
+            "Delegate already manages this department."
+        );
+        assert!(
+            delegates.len() < MAX_DEPARTMENT_DELEGATES,
+            "Cannot add more than {} department delegates.", MAX_DEPARTMENT_DELEGATES
+        );
+        delegates.push(DepartmentDelegate { delegate_id: delegate_id.clone(), department: department.clone() });
+
+        DidEvent::DepartmentDelegateAdded { org_id, department, delegate_id }.emit();
+    }
+
+    /// Removes `delegate_id`'s authority over `department` of the caller's organization.
+    pub fn remove_department_delegate(&mut self, department: String, delegate_id: AccountId) {
+        let org_id = env::predecessor_account_id();
+        let department = department.to_lowercase();
+        let delegates = self.org_delegates.get_mut(&org_id)
+            .unwrap_or_else(|| env::panic_str("Organization has no department delegates."));
+        let initial_len = delegates.len();
+        delegates.retain(|d| !(d.delegate_id == delegate_id && d.department == department));
+        assert!(delegates.len() < initial_len, "Delegate does not manage this department.");
+
+        DidEvent::DepartmentDelegateRemoved { org_id, department, delegate_id }.emit();
+    }
+
+    /// Returns the organization membership of `account_id`, if any.
+    /// View function.
+    pub fn get_parent_did(&self, account_id: AccountId) -> Option<OrgMembership> {
+        self.org_parents.get(&account_id).cloned()
+    }
+
+    /// Enumerates the direct child DIDs of an organization.
+    /// View function.
+    pub fn get_child_dids(&self, account_id: AccountId, from_index: Option<u64>, limit: Option<u64>) -> Vec<OrgMemberView> {
+        let Some(children) = self.org_children.get(&account_id) else {
+            return Vec::new();
+        };
+        children
+            .iter()
+            .skip(from_index.unwrap_or(0) as usize)
+            .take(limit.unwrap_or(DEFAULT_PAGE_LIMIT) as usize)
+            .filter_map(|child_id| self.org_parents.get(child_id).map(|membership| OrgMemberView {
+                child_id: child_id.clone(),
+                membership: membership.clone(),
+            }))
+            .collect()
+    }
+
+    /// Returns the number of direct child DIDs of an organization.
+    /// View function.
+    pub fn get_child_dids_count(&self, account_id: AccountId) -> u32 {
+        self.org_children.get(&account_id).map(|c| c.len()).unwrap_or(0)
+    }
+
+    /// Lists the department delegates of an organization.
+    /// View function.
+    pub fn get_department_delegates(&self, account_id: AccountId) -> Vec<DepartmentDelegate> {
+        self.org_delegates.get(&account_id).cloned().unwrap_or_default()
+    }
+}
+
+impl DidRegistry {
+    /// Asserts that `child_id`'s DID can become a child of `parent_id`'s: both active, no
+    /// current organization, and no cycle.
+    fn assert_can_join(&self, parent_id: &AccountId, child_id: &AccountId) {
+        assert!(parent_id != child_id, "A DID cannot be its own child.");
+        assert!(self.is_active(parent_id), "Parent DID not found or deactivated.");
+        assert!(self.is_active(child_id), "Child DID not found or deactivated.");
+        assert!(!self.org_parents.contains_key(child_id), "Child DID already belongs to an organization.");
+        assert!(
+            !self.is_org_ancestor(child_id, parent_id),
+            "Child DID is an ancestor of the parent DID."
+        );
+    }
+
+    /// Asserts that `caller_id` may manage children of `org_id` in `department`.
+    fn assert_org_manager(&self, caller_id: &AccountId, org_id: &AccountId, department: Option<&str>) {
+        if caller_id == org_id {
+            return;
+        }
+        let is_delegate = department.is_some_and(|department| {
+            self.org_delegates.get(org_id).is_some_and(|delegates| {
+                delegates.iter().any(|d| &d.delegate_id == caller_id && d.department == department)
+            })
+        });
+        assert!(is_delegate, "Caller is not authorized to manage this organization's DIDs.");
+    }
+
+    /// Returns true if `ancestor_id` appears above `account_id` in the organization tree.
+    pub(crate) fn is_org_ancestor(&self, ancestor_id: &AccountId, account_id: &AccountId) -> bool {
+        let mut current = self.org_parents.get(account_id).map(|m| m.parent_id.clone());
+        while let Some(parent_id) = current {
+            if &parent_id == ancestor_id {
+                return true;
+            }
+            current = self.org_parents.get(&parent_id).map(|m| m.parent_id.clone());
+        }
+        false
+    }
+
+    fn internal_detach_child(&mut self, child_id: &AccountId, parent_id: &AccountId) {
+        self.org_parents.remove(child_id);
+        if let Some(children) = self.org_children.get_mut(parent_id) {
+            children.remove(child_id);
+            if children.is_empty() {
+                self.org_children.remove(parent_id);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use near_sdk::test_utils::{accounts, VMContextBuilder};
+    use near_sdk::{testing_env, NearToken};
+
+    use super::*;
+
+    fn call_as(account_id: AccountId) {
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(account_id)
+            .attached_deposit(NearToken::from_near(1))
+            .build());
+    }
+
+    /// Registry where `accounts(1)` is an organization, `accounts(2)` its child in the
+    /// "finance" department, and `accounts(3)` the child of `accounts(2)`.
+    fn org_tree() -> DidRegistry {
+        call_as(accounts(0));
+        let mut contract = DidRegistry::new();
+        for account_id in [accounts(1), accounts(2), accounts(3)] {
+            call_as(account_id);
+            contract.register_did(None, None, None);
+        }
+        call_as(accounts(1));
+        contract.authorize_child_did(accounts(1), accounts(2), Some("Finance".to_string()));
+        call_as(accounts(2));
+        contract.accept_org_invite(accounts(1));
+        contract.authorize_child_did(accounts(2), accounts(3), None);
+        call_as(accounts(3));
+        contract.accept_org_invite(accounts(2));
+        contract
+    }
+
+    #[test]
+    fn children_join_only_once_they_accept() {
+        let contract = org_tree();
+        let membership = contract.get_parent_did(accounts(2)).unwrap();
+        assert_eq!(membership.parent_id, accounts(1));
+        assert_eq!(membership.department.as_deref(), Some("finance"));
+        assert_eq!(contract.get_child_dids_count(accounts(1)), 1);
+        assert!(contract.get_org_invite(accounts(2), accounts(1)).is_none());
+    }
+
+    #[test]
+    fn revoking_a_subtree_detaches_every_descendant() {
+        let mut contract = org_tree();
+        call_as(accounts(1));
+        assert_eq!(contract.revoke_subtree(accounts(2)), 2);
+        assert!(contract.get_parent_did(accounts(2)).is_none());
+        assert!(contract.get_parent_did(accounts(3)).is_none());
+        assert_eq!(contract.get_child_dids_count(accounts(1)), 0);
+    }
+
+    #[test]
+    fn department_delegates_can_revoke_children_of_their_department() {
+        let mut contract = org_tree();
+        call_as(accounts(1));
+        contract.add_department_delegate("finance".to_string(), accounts(4));
+        call_as(accounts(4));
+        contract.revoke_child_did(accounts(2));
+        assert!(contract.get_parent_did(accounts(2)).is_none());
+        assert_eq!(contract.get_parent_did(accounts(3)).unwrap().parent_id, accounts(2));
+    }
+}
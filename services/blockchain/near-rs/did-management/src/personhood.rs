@@ -0,0 +1,181 @@
+// services/blockchain/near-rs/did-management/src/personhood.rs
+use near_sdk::{near, env, AccountId};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+
+use crate::{DidEvent, DidRegistry, DidRegistryExt};
+
+/// Uniqueness attestation stamped by a personhood oracle after an off-chain liveness check.
+#[derive(
+    Debug,
+    PartialEq,
+    near_sdk::serde::Serialize,
+    near_sdk::serde::Deserialize,
+    BorshDeserialize,
+    BorshSerialize,
+    Clone
+)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PersonhoodAttestation {
+    pub oracle_id: AccountId,
+    /// Oracle-derived identifier of the human (e.g. a hashed biometric template id).
+    /// A human can back at most one account.
+    pub human_id_hash: String,
+    pub attested_at: u64,
+    pub expires_at: Option<u64>,
+}
+
+#[near]
+impl DidRegistry {
+    /// Registers an account allowed to stamp personhood attestations. Owner or governance
+    /// only.
+    pub fn add_personhood_oracle(&mut self, oracle_id: AccountId) {
+        self.assert_owner_or_governance();
+        assert!(self.personhood_oracles.insert(oracle_id.clone()), "Account is already a personhood oracle.");
+        env::log_str(&format!("Personhood oracle added: {}", oracle_id));
+    }
+
+    /// Removes a personhood oracle. Attestations it already stamped stay valid. Owner or
+    /// governance only.
+    pub fn remove_personhood_oracle(&mut self, oracle_id: AccountId) {
+        self.assert_owner_or_governance();
+        assert!(self.personhood_oracles.remove(&oracle_id), "Account is not a personhood oracle.");
+        env::log_str(&format!("Personhood oracle removed: {}", oracle_id));
+    }
+
+    /// Stamps `account_id`'s DID as backed by a unique human. Oracle only.
+    /// Fails if the same human already backs a different account.
+    pub fn stamp_personhood(&mut self, account_id: AccountId, human_id_hash: String, expires_at: Option<u64>) {
+        let oracle_id = env::predecessor_account_id();
+        assert!(self.personhood_oracles.contains(&oracle_id), "Caller is not a personhood oracle.");
+        assert!(self.is_active(&account_id), "DID not found or deactivated.");
+        assert!(!human_id_hash.is_empty(), "Human id hash must not be empty.");
+        let now = env::block_timestamp();
+        if let Some(expires_at) = expires_at {
+            assert!(expires_at > now, "Attestation expiry must be in the future.");
+        }
+        if let Some(existing) = self.human_ids.get(&human_id_hash) {
+            assert_eq!(existing, &account_id, "This human already backs another account.");
+        }
+
+        if let Some(previous) = self.personhood.get(&account_id) {
+            if previous.human_id_hash != human_id_hash {
+                self.human_ids.remove(&previous.human_id_hash);
+            }
+        }
+        self.human_ids.insert(human_id_hash.clone(), account_id.clone());
+        self.personhood.insert(account_id.clone(), PersonhoodAttestation {
+            oracle_id: oracle_id.clone(),
+            human_id_hash,
+            attested_at: now,
+            expires_at,
+        });
+
+        DidEvent::PersonhoodStamped { account_id, oracle_id, expires_at }.emit();
+    }
+
+    /// Removes `account_id`'s personhood attestation. Callable by any oracle or the owner.
+    pub fn revoke_personhood(&mut self, account_id: AccountId) {
+        let caller_id = env::predecessor_account_id();
+        assert!(
+            self.personhood_oracles.contains(&caller_id) || caller_id == self.owner_id,
+            "Caller is not a personhood oracle."
+        );
+        assert!(self.internal_clear_personhood(&account_id), "Account has no personhood attestation.");
+        DidEvent::PersonhoodRevoked { account_id, revoked_by: caller_id }.emit();
+    }
+
+    /// Returns true if `account_id` has an active DID with an unexpired personhood attestation.
+    /// View function.
+    pub fn is_unique_human(&self, account_id: AccountId) -> bool {
+        self.is_active(&account_id)
+            && self.personhood.get(&account_id).is_some_and(|a| {
+                a.expires_at.is_none_or(|expires_at| expires_at > env::block_timestamp())
+            })
+    }
+
+    /// Retrieves the personhood attestation of a given AccountId.
+    /// View function.
+    pub fn get_personhood_attestation(&self, account_id: AccountId) -> Option<PersonhoodAttestation> {
+        self.personhood.get(&account_id).cloned()
+    }
+
+    /// Lists the registered personhood oracles.
+    /// View function.
+    pub fn get_personhood_oracles(&self) -> Vec<AccountId> {
+        self.personhood_oracles.iter().cloned().collect()
+    }
+}
+
+impl DidRegistry {
+    /// Drops `account_id`'s attestation and frees its human id. Returns false if there was none.
+    pub(crate) fn internal_clear_personhood(&mut self, account_id: &AccountId) -> bool {
+        match self.personhood.remove(account_id) {
+            Some(attestation) => {
+                self.human_ids.remove(&attestation.human_id_hash);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use near_sdk::test_utils::{accounts, VMContextBuilder};
+    use near_sdk::{testing_env, NearToken};
+
+    use super::*;
+
+    fn call_as(account_id: AccountId, block_timestamp: u64) {
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(account_id)
+            .attached_deposit(NearToken::from_near(1))
+            .block_timestamp(block_timestamp)
+            .build());
+    }
+
+    /// Registry owned by `accounts(0)` with `accounts(3)` as personhood oracle and DIDs for
+    /// `accounts(1)` and `accounts(2)`.
+    fn registry_with_oracle() -> DidRegistry {
+        call_as(accounts(0), 0);
+        let mut contract = DidRegistry::new();
+        contract.add_personhood_oracle(accounts(3));
+        for account_id in [accounts(1), accounts(2)] {
+            call_as(account_id, 0);
+            contract.register_did(None, None, None);
+        }
+        contract
+    }
+
+    #[test]
+    fn attestations_count_until_they_expire() {
+        let mut contract = registry_with_oracle();
+        call_as(accounts(3), 0);
+        contract.stamp_personhood(accounts(1), "human-1".to_string(), Some(100));
+        assert!(contract.is_unique_human(accounts(1)));
+
+        call_as(accounts(3), 100);
+        assert!(!contract.is_unique_human(accounts(1)));
+    }
+
+    #[test]
+    #[should_panic(expected = "This human already backs another account.")]
+    fn a_human_backs_at_most_one_account() {
+        let mut contract = registry_with_oracle();
+        call_as(accounts(3), 0);
+        contract.stamp_personhood(accounts(1), "human-1".to_string(), None);
+        contract.stamp_personhood(accounts(2), "human-1".to_string(), None);
+    }
+
+    #[test]
+    fn revoking_an_attestation_frees_its_human() {
+        let mut contract = registry_with_oracle();
+        call_as(accounts(3), 0);
+        contract.stamp_personhood(accounts(1), "human-1".to_string(), None);
+        contract.revoke_personhood(accounts(1));
+        assert!(!contract.is_unique_human(accounts(1)));
+
+        contract.stamp_personhood(accounts(2), "human-1".to_string(), None);
+        assert!(contract.is_unique_human(accounts(2)));
+    }
+}
@@ -0,0 +1,127 @@
+// services/blockchain/near-rs/did-management/src/stats.rs
+use near_sdk::{near, AccountId};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+
+use crate::{DidRegistry, DidRegistryExt, RevocationReason};
+
+const NANOS_PER_DAY: u64 = 24 * 60 * 60 * 1_000_000_000;
+const MAX_DAY_BUCKETS: u64 = 366;
+
+#[derive(
+    Debug,
+    Default,
+    PartialEq,
+    near_sdk::serde::Serialize,
+    near_sdk::serde::Deserialize,
+    BorshDeserialize,
+    BorshSerialize,
+    Clone
+)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RevocationCounts {
+    pub compromised: u64,
+    pub superseded: u64,
+    pub fraud: u64,
+    pub expired_source_document: u64,
+}
+
+/// Aggregate registry counters, maintained incrementally by every mutation.
+#[derive(
+    Debug,
+    Default,
+    PartialEq,
+    near_sdk::serde::Serialize,
+    near_sdk::serde::Deserialize,
+    BorshDeserialize,
+    BorshSerialize,
+    Clone
+)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RegistryStats {
+    /// DIDs currently stored (registered minus purged).
+    pub total_dids: u64,
+    pub total_registrations: u64,
+    pub total_deactivations: u64,
+    pub total_purges: u64,
+    pub total_credentials_issued: u64,
+    pub total_revocations: u64,
+    pub revocations_by_reason: RevocationCounts,
+}
+
+#[derive(
+    Debug,
+    PartialEq,
+    near_sdk::serde::Serialize,
+    near_sdk::serde::Deserialize,
+    Clone
+)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DailyRegistrations {
+    /// Days since the Unix epoch.
+    pub day: u64,
+    pub registrations: u64,
+}
+
+#[near]
+impl DidRegistry {
+    /// Returns the aggregate registry counters.
+    /// View function.
+    pub fn get_registry_stats(&self) -> RegistryStats {
+        self.stats.clone()
+    }
+
+    /// Returns the number of DIDs registered on each day in `[from_day, to_day]`, where days
+    /// are counted since the Unix epoch. Days without registrations are omitted.
+    /// View function.
+    pub fn get_registrations_by_day(&self, from_day: u64, to_day: u64) -> Vec<DailyRegistrations> {
+        assert!(from_day <= to_day, "from_day must not be after to_day.");
+        assert!(to_day - from_day < MAX_DAY_BUCKETS, "Cannot query more than {} days at once.", MAX_DAY_BUCKETS);
+        (from_day..=to_day)
+            .filter_map(|day| {
+                self.registrations_by_day.get(&day).map(|&registrations| DailyRegistrations { day, registrations })
+            })
+            .collect()
+    }
+
+    /// Returns the number of credentials ever issued by `issuer_id`.
+    /// View function.
+    pub fn get_credentials_issued_by(&self, issuer_id: AccountId) -> u64 {
+        self.credentials_by_issuer.get(&issuer_id).copied().unwrap_or(0)
+    }
+}
+
+impl DidRegistry {
+    pub(crate) fn record_registration(&mut self, timestamp: u64) {
+        self.stats.total_dids += 1;
+        self.stats.total_registrations += 1;
+        *self.registrations_by_day.entry(timestamp / NANOS_PER_DAY).or_insert(0) += 1;
+    }
+
+    pub(crate) fn record_credentials_issued(&mut self, issuer_id: &AccountId, count: u64) {
+        if count == 0 {
+            return;
+        }
+        self.stats.total_credentials_issued += count;
+        *self.credentials_by_issuer.entry(issuer_id.clone()).or_insert(0) += count;
+    }
+
+    pub(crate) fn record_revocation(&mut self, reason: RevocationReason) {
+        self.stats.total_revocations += 1;
+        let counts = &mut self.stats.revocations_by_reason;
+        match reason {
+            RevocationReason::Compromised => counts.compromised += 1,
+            RevocationReason::Superseded => counts.superseded += 1,
+            RevocationReason::Fraud => counts.fraud += 1,
+            RevocationReason::ExpiredSourceDocument => counts.expired_source_document += 1,
+        }
+    }
+
+    pub(crate) fn record_deactivation(&mut self) {
+        self.stats.total_deactivations += 1;
+    }
+
+    pub(crate) fn record_purge(&mut self) {
+        self.stats.total_dids = self.stats.total_dids.saturating_sub(1);
+        self.stats.total_purges += 1;
+    }
+}
@@ -0,0 +1,46 @@
+// services/blockchain/near-rs/did-management/src/sync.rs
+use std::ops::Bound;
+
+use near_sdk::{near, AccountId};
+
+use crate::{DidRegistry, DidRegistryExt, DidResolution, DEFAULT_PAGE_LIMIT};
+
+#[near]
+impl DidRegistry {
+    /// Returns up to `limit` DIDs ordered by their metadata `updated` timestamp, then account
+    /// id, starting after the DID `after_account_id` updated at `timestamp`, or after every
+    /// DID updated at `timestamp` when `after_account_id` is omitted. Indexers catch up
+    /// incrementally by passing the `updated` timestamp and account id of the last DID they
+    /// received; `timestamp: 0` with no account id pages through the whole registry.
+    /// View function.
+    pub fn get_dids_updated_since(&self, timestamp: u64, after_account_id: Option<AccountId>, limit: Option<u64>) -> Vec<DidResolution> {
+        let start = match after_account_id {
+            Some(account_id) => Bound::Excluded((timestamp, account_id.to_string())),
+            None => match timestamp.checked_add(1) {
+                Some(next) => Bound::Included((next, String::new())),
+                None => return Vec::new(),
+            },
+        };
+        self.dids_by_update
+            .range((start, Bound::Unbounded))
+            .take(limit.unwrap_or(DEFAULT_PAGE_LIMIT) as usize)
+            .filter_map(|((_, account_id), _)| account_id.parse::<AccountId>().ok())
+            .filter_map(|account_id| self.internal_resolve(&account_id))
+            .collect()
+    }
+}
+
+impl DidRegistry {
+    /// Moves `account_id` in the update index from `previous` (if indexed) to `updated`.
+    pub(crate) fn internal_index_update(&mut self, account_id: &AccountId, previous: Option<u64>, updated: u64) {
+        if let Some(previous) = previous {
+            self.dids_by_update.remove(&(previous, account_id.to_string()));
+        }
+        self.dids_by_update.insert((updated, account_id.to_string()), ());
+    }
+
+    /// Drops `account_id`, last updated at `updated`, from the update index.
+    pub(crate) fn internal_unindex_update(&mut self, account_id: &AccountId, updated: u64) {
+        self.dids_by_update.remove(&(updated, account_id.to_string()));
+    }
+}
@@ -0,0 +1,84 @@
+// services/blockchain/near-rs/did-management/src/w3c.rs
+use near_sdk::{near, env, AccountId};
+use near_sdk::serde_json::{json, Map, Value};
+
+use crate::{DidRegistry, DidRegistryExt, DID_METHOD_PREFIX};
+
+const VC_CONTEXT: &str = "https://www.w3.org/2018/credentials/v1";
+const PROOF_TYPE: &str = "NearAnchoredProof";
+
+#[near]
+impl DidRegistry {
+    /// Renders a stored credential as a W3C Verifiable Credential JSON object. The `proof`
+    /// section references the issuer's first verification method and the block at which the
+    /// credential was anchored in this registry. Gated credentials are not rendered.
+    /// View function.
+    pub fn get_credential_as_w3c_vc(&self, account_id: AccountId, vc_hash: String) -> Option<Value> {
+        let credential = self.dids.get(&account_id)?
+            .verifiable_credentials
+            .iter()
+            .find(|c| c.vc_hash == vc_hash && !c.is_gated())?;
+
+        let issuer_did = format!("{}{}", DID_METHOD_PREFIX, credential.issuer);
+        let verification_method = self.dids.get(&credential.issuer)
+            .and_then(|doc| doc.verification_methods.first())
+            .map(|method| format!("{}#{}", issuer_did, method.id))
+            .unwrap_or_else(|| issuer_did.clone());
+
+        let mut vc = Map::new();
+        vc.insert("@context".into(), json!([VC_CONTEXT]));
+        vc.insert("id".into(), json!(format!("urn:bcb:vc:{}", credential.vc_hash)));
+        vc.insert("type".into(), json!(["VerifiableCredential", credential.credential_type]));
+        vc.insert("issuer".into(), json!(issuer_did));
+        vc.insert("issuanceDate".into(), json!(to_iso8601(credential.issued_at)));
+        if let Some(expires_at) = credential.expires_at {
+            vc.insert("expirationDate".into(), json!(to_iso8601(expires_at)));
+        }
+        vc.insert("credentialSubject".into(), json!({
+            "id": format!("{}{}", DID_METHOD_PREFIX, account_id),
+            "vcHash": credential.vc_hash,
+            "tags": credential.tags,
+        }));
+        vc.insert("credentialStatus".into(), json!({
+            "id": format!("near:{}#{}", env::current_account_id(), credential.vc_hash),
+            "type": "NearRegistryStatus",
+            "revoked": credential.revocation.is_some(),
+            "suspended": self.is_credential_suspended(credential),
+        }));
+        vc.insert("proof".into(), json!({
+            "type": PROOF_TYPE,
+            "created": to_iso8601(credential.issued_at),
+            "proofPurpose": "assertionMethod",
+            "verificationMethod": verification_method,
+            "anchor": {
+                "registry": env::current_account_id(),
+                "blockHeight": credential.anchor_block_height,
+                "vcHash": credential.vc_hash,
+            },
+        }));
+        Some(Value::Object(vc))
+    }
+}
+
+/// Formats a nanosecond Unix timestamp as an ISO 8601 UTC date-time.
+fn to_iso8601(timestamp_nanos: u64) -> String {
+    let secs = timestamp_nanos / 1_000_000_000;
+    let days = (secs / 86_400) as i64;
+    let secs_of_day = secs % 86_400;
+
+    // Civil-from-days conversion (proleptic Gregorian calendar).
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year, month, day, secs_of_day / 3_600, (secs_of_day % 3_600) / 60, secs_of_day % 60
+    )
+}
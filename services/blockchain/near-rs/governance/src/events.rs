@@ -0,0 +1,44 @@
+// services/blockchain/near-rs/governance/src/events.rs
+use near_sdk::{near, AccountId, NearToken};
+
+use crate::{GovernanceConfig, ProposalStatus, ProposalTarget, Vote};
+
+/// NEP-297 events emitted by the governance contract.
+#[near(event_json(standard = "bcb-gov"))]
+pub enum GovernanceEvent {
+    #[event_version("1.0.0")]
+    Staked { account_id: AccountId, amount: NearToken, stake: NearToken },
+
+    #[event_version("1.0.0")]
+    Unstaked { account_id: AccountId, amount: NearToken, stake: NearToken },
+
+    #[event_version("1.0.0")]
+    ProposalCreated {
+        proposal_id: u64,
+        proposer_id: AccountId,
+        target: ProposalTarget,
+        method_name: String,
+        voting_ends_at: u64,
+    },
+
+    #[event_version("1.0.0")]
+    VoteCast { proposal_id: u64, voter_id: AccountId, vote: Vote, weight: NearToken },
+
+    #[event_version("1.0.0")]
+    ProposalFinalized {
+        proposal_id: u64,
+        status: ProposalStatus,
+        approve_stake: NearToken,
+        reject_stake: NearToken,
+        executable_at: Option<u64>,
+    },
+
+    #[event_version("1.0.0")]
+    ProposalCancelled { proposal_id: u64 },
+
+    #[event_version("1.0.0")]
+    ProposalExecuted { proposal_id: u64, success: bool },
+
+    #[event_version("1.0.0")]
+    ConfigUpdated { config: GovernanceConfig },
+}
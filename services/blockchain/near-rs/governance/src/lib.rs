@@ -0,0 +1,194 @@
+// services/blockchain/near-rs/governance/src/lib.rs
+//! Stake-weighted governance for the BioCrypticBank protocol. Members stake NEAR, open
+//! proposals that call a parameter setter on the bank (`bcb-core`), account recovery
+//! (`bcb-acc`) or DID registry (`bcb-did`) contract, vote with their stake, and execute
+//! passed proposals once a timelock has passed. Each target contract only accepts these
+//! calls after its owner points `set_governance` at this contract.
+use near_sdk::{near, env, AccountId, BorshStorageKey, Gas, NearToken, PanicOnDefault, Promise, PromiseError};
+use near_sdk::store::{IterableMap, LookupMap};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+
+mod events;
+mod proposals;
+pub use events::GovernanceEvent;
+pub use proposals::{Proposal, ProposalAction, ProposalStatus, ProposalTarget, Vote};
+
+pub(crate) const NANOS_PER_SECOND: u64 = 1_000_000_000;
+pub(crate) const BPS_DENOMINATOR: u128 = 10_000;
+pub(crate) const DEFAULT_PAGE_LIMIT: u64 = 50;
+/// Smallest stake a member can hold; it covers the storage of their record and votes.
+const MIN_STAKE: NearToken = NearToken::from_millinear(100);
+const MIN_VOTING_PERIOD_SEC: u64 = 60 * 60;
+const MAX_VOTING_PERIOD_SEC: u64 = 30 * 24 * 60 * 60;
+const MAX_TIMELOCK_SEC: u64 = 30 * 24 * 60 * 60;
+const GAS_FOR_UNSTAKE_CALLBACK: Gas = Gas::from_tgas(10);
+
+/// Voting rules. Changed only by a passed proposal targeting this contract.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct GovernanceConfig {
+    /// Stake a member needs to open a proposal.
+    pub proposal_stake: NearToken,
+    pub voting_period_sec: u64,
+    /// Delay between the end of voting and a passed proposal becoming executable, giving
+    /// depositors time to react to the change.
+    pub timelock_sec: u64,
+    /// Share of the total stake, in basis points, that must vote for a result to count.
+    pub quorum_bps: u16,
+}
+
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct Member {
+    pub stake: NearToken,
+    /// Time (ns) until which the stake backs votes or proposals and cannot be withdrawn.
+    pub locked_until: u64,
+}
+
+#[derive(BorshStorageKey, Debug, BorshDeserialize, BorshSerialize)]
+pub enum StorageKey {
+    Members,
+    Proposals,
+    Votes,
+}
+
+#[near(contract_state)]
+#[derive(PanicOnDefault)]
+pub struct Governance {
+    bank_id: AccountId,
+    recovery_id: AccountId,
+    did_id: AccountId,
+    config: GovernanceConfig,
+    members: LookupMap<AccountId, Member>,
+    total_stake: NearToken,
+    proposals: IterableMap<u64, Proposal>,
+    votes: LookupMap<(u64, AccountId), Vote>,
+    next_proposal_id: u64,
+}
+
+#[near]
+impl Governance {
+    /// Initializes the governance contract for the given bank, account recovery and DID
+    /// registry contracts.
+    #[init]
+    pub fn new(bank_id: AccountId, recovery_id: AccountId, did_id: AccountId, config: GovernanceConfig) -> Self {
+        assert_valid_config(&config);
+        Self {
+            bank_id,
+            recovery_id,
+            did_id,
+            config,
+            members: LookupMap::new(StorageKey::Members),
+            total_stake: NearToken::from_yoctonear(0),
+            proposals: IterableMap::new(StorageKey::Proposals),
+            votes: LookupMap::new(StorageKey::Votes),
+            next_proposal_id: 0,
+        }
+    }
+
+    /// Adds the attached deposit to the caller's stake, making them a member.
+    #[payable]
+    pub fn stake(&mut self) -> NearToken {
+        let account_id = env::predecessor_account_id();
+        let amount = env::attached_deposit();
+        assert!(!amount.is_zero(), "Attach the NEAR to stake.");
+        let member = self.members.entry(account_id.clone()).or_insert(Member {
+            stake: NearToken::from_yoctonear(0),
+            locked_until: 0,
+        });
+        member.stake = member.stake.saturating_add(amount);
+        assert!(member.stake >= MIN_STAKE, "Stake must be at least {}.", MIN_STAKE.exact_amount_display());
+        let stake = member.stake;
+        self.total_stake = self.total_stake.saturating_add(amount);
+        GovernanceEvent::Staked { account_id, amount, stake }.emit();
+        stake
+    }
+
+    /// Withdraws `amount` of the caller's stake once it no longer backs votes or proposals.
+    /// The remaining stake must be zero or at least the minimum stake.
+    pub fn unstake(&mut self, amount: NearToken) -> Promise {
+        let account_id = env::predecessor_account_id();
+        let member = self.members.get_mut(&account_id)
+            .unwrap_or_else(|| env::panic_str("Caller is not a member."));
+        assert!(!amount.is_zero(), "Amount must be greater than 0.");
+        assert!(member.stake >= amount, "Insufficient stake.");
+        assert!(env::block_timestamp() >= member.locked_until, "Stake is locked by open votes or proposals.");
+        member.stake = member.stake.saturating_sub(amount);
+        assert!(member.stake.is_zero() || member.stake >= MIN_STAKE, "Remaining stake would be below the minimum.");
+        let stake = member.stake;
+        if stake.is_zero() {
+            self.members.remove(&account_id);
+        }
+        self.total_stake = self.total_stake.saturating_sub(amount);
+        GovernanceEvent::Unstaked { account_id: account_id.clone(), amount, stake }.emit();
+        Promise::new(account_id.clone()).transfer(amount).then(
+            Self::ext(env::current_account_id())
+                .with_static_gas(GAS_FOR_UNSTAKE_CALLBACK)
+                .on_unstaked(account_id, amount)
+        )
+    }
+
+    /// Callback restoring stake whose withdrawal transfer failed.
+    #[private]
+    pub fn on_unstaked(&mut self, account_id: AccountId, amount: NearToken, #[callback_result] result: Result<(), PromiseError>) -> bool {
+        if result.is_err() {
+            let member = self.members.entry(account_id).or_insert(Member {
+                stake: NearToken::from_yoctonear(0),
+                locked_until: 0,
+            });
+            member.stake = member.stake.saturating_add(amount);
+            self.total_stake = self.total_stake.saturating_add(amount);
+            env::log_str("Unstake transfer failed; amount returned to the stake.");
+        }
+        result.is_ok()
+    }
+
+    /// Replaces the voting rules. Only callable by this contract, i.e. by executing a passed
+    /// proposal targeting `governance`. Proposals already open keep their voting end.
+    #[private]
+    pub fn update_config(&mut self, config: GovernanceConfig) {
+        assert_valid_config(&config);
+        self.config = config.clone();
+        GovernanceEvent::ConfigUpdated { config }.emit();
+    }
+
+    /// View function.
+    pub fn get_config(&self) -> GovernanceConfig {
+        self.config.clone()
+    }
+
+    /// Bank, account recovery and DID registry contracts proposals can target.
+    /// View function.
+    pub fn get_targets(&self) -> (AccountId, AccountId, AccountId) {
+        (self.bank_id.clone(), self.recovery_id.clone(), self.did_id.clone())
+    }
+
+    /// View function.
+    pub fn get_member(&self, account_id: AccountId) -> Option<Member> {
+        self.members.get(&account_id).cloned()
+    }
+
+    /// View function.
+    pub fn get_total_stake(&self) -> NearToken {
+        self.total_stake
+    }
+}
+
+impl Governance {
+    /// Keeps `account_id`'s stake locked until at least `until` (ns).
+    pub(crate) fn internal_lock_stake(&mut self, account_id: &AccountId, until: u64) {
+        if let Some(member) = self.members.get_mut(account_id) {
+            member.locked_until = member.locked_until.max(until);
+        }
+    }
+}
+
+fn assert_valid_config(config: &GovernanceConfig) {
+    assert!(
+        (MIN_VOTING_PERIOD_SEC..=MAX_VOTING_PERIOD_SEC).contains(&config.voting_period_sec),
+        "Voting period must be between {} and {} seconds.", MIN_VOTING_PERIOD_SEC, MAX_VOTING_PERIOD_SEC
+    );
+    assert!(config.timelock_sec <= MAX_TIMELOCK_SEC, "Timelock cannot exceed {} seconds.", MAX_TIMELOCK_SEC);
+    assert!(config.quorum_bps > 0 && config.quorum_bps as u128 <= BPS_DENOMINATOR, "Quorum must be between 1 and 10000 bps.");
+    assert!(config.proposal_stake >= MIN_STAKE, "Proposal stake cannot be below the minimum stake.");
+}
@@ -0,0 +1,348 @@
+// services/blockchain/near-rs/governance/src/proposals.rs
+use near_sdk::{near, env, AccountId, Gas, NearToken, Promise, PromiseResult};
+use near_sdk::json_types::Base64VecU8;
+use biocrypticbank_common::ContractError;
+
+use crate::{Governance, GovernanceEvent, GovernanceExt, BPS_DENOMINATOR, DEFAULT_PAGE_LIMIT, NANOS_PER_SECOND};
+
+const MAX_DESCRIPTION_LEN: usize = 1_024;
+const MAX_METHOD_NAME_LEN: usize = 64;
+const MAX_ARGS_LEN: usize = 4_096;
+const MIN_ACTION_GAS_TGAS: u64 = 5;
+const MAX_ACTION_GAS_TGAS: u64 = 200;
+const GAS_FOR_EXECUTION_CALLBACK: Gas = Gas::from_tgas(10);
+
+/// Contract a proposal calls into.
+#[near(serializers = [borsh, json])]
+#[serde(rename_all = "snake_case")]
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ProposalTarget {
+    /// The core bank, e.g. `propose_rate_model`, `propose_fee_schedule`,
+    /// `set_early_withdrawal_penalty`.
+    Bank,
+    /// The account recovery contract, e.g. `set_recovery_period`.
+    Recovery,
+    /// The DID registry, e.g. `add_personhood_oracle`, `add_arbiter`.
+    Did,
+    /// This contract, i.e. `update_config`.
+    Governance,
+}
+
+/// The call a passed proposal makes: `method_name` on `target` with JSON `args`.
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct ProposalAction {
+    pub target: ProposalTarget,
+    pub method_name: String,
+    pub args: Base64VecU8,
+    pub gas_tgas: u64,
+}
+
+#[near(serializers = [borsh, json])]
+#[serde(rename_all = "snake_case")]
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ProposalStatus {
+    /// Open for votes until `voting_ends_at`.
+    Voting,
+    /// Passed; executable from `executable_at`. A failed execution returns here.
+    Queued,
+    /// Missed quorum or had no majority.
+    Rejected,
+    /// Withdrawn by its proposer during voting.
+    Cancelled,
+    /// Its call is in flight.
+    Executing,
+    Executed,
+}
+
+#[near(serializers = [borsh, json])]
+#[serde(rename_all = "snake_case")]
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Vote {
+    Approve,
+    Reject,
+}
+
+#[near(serializers = [borsh, json])]
+#[derive(Debug, PartialEq, Clone)]
+pub struct Proposal {
+    pub id: u64,
+    pub proposer_id: AccountId,
+    pub description: String,
+    pub action: ProposalAction,
+    pub status: ProposalStatus,
+    pub approve_stake: NearToken,
+    pub reject_stake: NearToken,
+    /// Total stake when the proposal was opened, which quorum is measured against.
+    pub total_stake: NearToken,
+    pub created_at: u64,
+    pub voting_ends_at: u64,
+    pub executable_at: Option<u64>,
+    pub executed_at: Option<u64>,
+}
+
+#[near]
+impl Governance {
+    /// Opens a proposal to run `action` and returns its id. The caller needs the configured
+    /// proposal stake, which stays locked until voting ends.
+    pub fn propose(&mut self, description: String, action: ProposalAction) -> u64 {
+        let proposer_id = env::predecessor_account_id();
+        let stake = self.members.get(&proposer_id).map(|member| member.stake).unwrap_or_default();
+        assert!(stake >= self.config.proposal_stake, "Insufficient stake to open a proposal.");
+        assert!(!description.is_empty() && description.len() <= MAX_DESCRIPTION_LEN, "Description must be 1 to {} bytes.", MAX_DESCRIPTION_LEN);
+        assert!(
+            !action.method_name.is_empty() && action.method_name.len() <= MAX_METHOD_NAME_LEN,
+            "Method name must be 1 to {} bytes.", MAX_METHOD_NAME_LEN
+        );
+        assert!(action.args.0.len() <= MAX_ARGS_LEN, "Arguments cannot exceed {} bytes.", MAX_ARGS_LEN);
+        assert!(
+            (MIN_ACTION_GAS_TGAS..=MAX_ACTION_GAS_TGAS).contains(&action.gas_tgas),
+            "Gas must be between {} and {} Tgas.", MIN_ACTION_GAS_TGAS, MAX_ACTION_GAS_TGAS
+        );
+
+        let id = self.next_proposal_id;
+        self.next_proposal_id += 1;
+        let now = env::block_timestamp();
+        let voting_ends_at = now + self.config.voting_period_sec * NANOS_PER_SECOND;
+        self.internal_lock_stake(&proposer_id, voting_ends_at);
+        GovernanceEvent::ProposalCreated {
+            proposal_id: id,
+            proposer_id: proposer_id.clone(),
+            target: action.target,
+            method_name: action.method_name.clone(),
+            voting_ends_at,
+        }.emit();
+        self.proposals.insert(id, Proposal {
+            id,
+            proposer_id,
+            description,
+            action,
+            status: ProposalStatus::Voting,
+            approve_stake: NearToken::from_yoctonear(0),
+            reject_stake: NearToken::from_yoctonear(0),
+            total_stake: self.total_stake,
+            created_at: now,
+            voting_ends_at,
+            executable_at: None,
+            executed_at: None,
+        });
+        id
+    }
+
+    /// Casts the caller's whole stake for or against a proposal. The stake stays locked until
+    /// the proposal could execute, so it cannot vote again from another account or leave
+    /// before the change it backed takes effect.
+    pub fn vote(&mut self, proposal_id: u64, vote: Vote) {
+        let voter_id = env::predecessor_account_id();
+        let weight = self.members.get(&voter_id)
+            .map(|member| member.stake)
+            .unwrap_or_else(|| env::panic_str("Caller is not a member."));
+        let key = (proposal_id, voter_id.clone());
+        assert!(!self.votes.contains_key(&key), "Caller has already voted on this proposal.");
+        let proposal = self.proposals.get_mut(&proposal_id)
+            .unwrap_or_else(|| ContractError::NotFound("Proposal").panic());
+        assert!(
+            proposal.status == ProposalStatus::Voting && env::block_timestamp() < proposal.voting_ends_at,
+            "Voting on this proposal is closed."
+        );
+        match vote {
+            Vote::Approve => proposal.approve_stake = proposal.approve_stake.saturating_add(weight),
+            Vote::Reject => proposal.reject_stake = proposal.reject_stake.saturating_add(weight),
+        }
+        let lock_until = proposal.voting_ends_at + self.config.timelock_sec * NANOS_PER_SECOND;
+        self.votes.insert(key, vote);
+        self.internal_lock_stake(&voter_id, lock_until);
+        GovernanceEvent::VoteCast { proposal_id, voter_id, vote, weight }.emit();
+    }
+
+    /// Withdraws a proposal that is still being voted on. Proposer only.
+    pub fn cancel_proposal(&mut self, proposal_id: u64) {
+        let proposal = self.proposals.get_mut(&proposal_id)
+            .unwrap_or_else(|| ContractError::NotFound("Proposal").panic());
+        assert_eq!(proposal.proposer_id, env::predecessor_account_id(), "Only the proposer can cancel a proposal.");
+        assert!(proposal.status == ProposalStatus::Voting, "Only proposals being voted on can be cancelled.");
+        proposal.status = ProposalStatus::Cancelled;
+        GovernanceEvent::ProposalCancelled { proposal_id }.emit();
+    }
+
+    /// Tallies a proposal whose voting has ended. It passes if votes reach the quorum of the
+    /// stake at opening and approvals outweigh rejections; a passed proposal is queued behind
+    /// the timelock. Callable by anyone.
+    pub fn finalize_proposal(&mut self, proposal_id: u64) -> ProposalStatus {
+        let timelock = self.config.timelock_sec * NANOS_PER_SECOND;
+        let quorum_bps = self.config.quorum_bps as u128;
+        let proposal = self.proposals.get_mut(&proposal_id)
+            .unwrap_or_else(|| ContractError::NotFound("Proposal").panic());
+        assert!(proposal.status == ProposalStatus::Voting, "Proposal is not being voted on.");
+        assert!(env::block_timestamp() >= proposal.voting_ends_at, "Voting has not ended.");
+        let turnout = proposal.approve_stake.as_yoctonear() + proposal.reject_stake.as_yoctonear();
+        let quorum_met = turnout * BPS_DENOMINATOR >= proposal.total_stake.as_yoctonear() * quorum_bps;
+        if quorum_met && proposal.approve_stake > proposal.reject_stake {
+            proposal.status = ProposalStatus::Queued;
+            proposal.executable_at = Some(proposal.voting_ends_at + timelock);
+        } else {
+            proposal.status = ProposalStatus::Rejected;
+        }
+        GovernanceEvent::ProposalFinalized {
+            proposal_id,
+            status: proposal.status,
+            approve_stake: proposal.approve_stake,
+            reject_stake: proposal.reject_stake,
+            executable_at: proposal.executable_at,
+        }.emit();
+        proposal.status
+    }
+
+    /// Makes a queued proposal's call once its timelock has passed. If the call fails the
+    /// proposal is queued again and can be retried. Callable by anyone.
+    pub fn execute_proposal(&mut self, proposal_id: u64) -> Promise {
+        let proposal = self.proposals.get_mut(&proposal_id)
+            .unwrap_or_else(|| ContractError::NotFound("Proposal").panic());
+        assert!(proposal.status == ProposalStatus::Queued, "Proposal is not queued for execution.");
+        assert!(
+            proposal.executable_at.is_some_and(|executable_at| env::block_timestamp() >= executable_at),
+            "Proposal timelock has not passed."
+        );
+        proposal.status = ProposalStatus::Executing;
+        let action = proposal.action.clone();
+        Promise::new(self.internal_target_id(action.target))
+            .function_call(action.method_name, action.args.0, NearToken::from_yoctonear(0), Gas::from_tgas(action.gas_tgas))
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_EXECUTION_CALLBACK)
+                    .on_proposal_executed(proposal_id)
+            )
+    }
+
+    /// Callback marking a proposal executed, or queuing it again if its call failed.
+    #[private]
+    pub fn on_proposal_executed(&mut self, proposal_id: u64) -> bool {
+        let success = matches!(env::promise_result(0), PromiseResult::Successful(_));
+        if let Some(proposal) = self.proposals.get_mut(&proposal_id) {
+            if success {
+                proposal.status = ProposalStatus::Executed;
+                proposal.executed_at = Some(env::block_timestamp());
+            } else {
+                proposal.status = ProposalStatus::Queued;
+                env::log_str("Proposal call failed; proposal queued again.");
+            }
+        }
+        GovernanceEvent::ProposalExecuted { proposal_id, success }.emit();
+        success
+    }
+
+    /// View function.
+    pub fn get_proposal(&self, proposal_id: u64) -> Option<Proposal> {
+        self.proposals.get(&proposal_id).cloned()
+    }
+
+    /// View function.
+    pub fn get_proposals(&self, from_index: Option<u64>, limit: Option<u64>) -> Vec<Proposal> {
+        self.proposals.values()
+            .skip(from_index.unwrap_or(0) as usize)
+            .take(limit.unwrap_or(DEFAULT_PAGE_LIMIT) as usize)
+            .cloned()
+            .collect()
+    }
+
+    /// View function.
+    pub fn get_vote(&self, proposal_id: u64, account_id: AccountId) -> Option<Vote> {
+        self.votes.get(&(proposal_id, account_id)).copied()
+    }
+}
+
+impl Governance {
+    fn internal_target_id(&self, target: ProposalTarget) -> AccountId {
+        match target {
+            ProposalTarget::Bank => self.bank_id.clone(),
+            ProposalTarget::Recovery => self.recovery_id.clone(),
+            ProposalTarget::Did => self.did_id.clone(),
+            ProposalTarget::Governance => env::current_account_id(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use near_sdk::test_utils::{accounts, VMContextBuilder};
+    use near_sdk::testing_env;
+
+    use super::*;
+    use crate::GovernanceConfig;
+
+    const HOUR_SEC: u64 = 60 * 60;
+
+    fn call_as(account_id: AccountId, attached: NearToken, block_timestamp: u64) {
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(account_id)
+            .attached_deposit(attached)
+            .block_timestamp(block_timestamp)
+            .build());
+    }
+
+    /// Governance with a one hour vote, a one hour timelock and a 50% quorum, where
+    /// `accounts(1)` and `accounts(2)` stake 10 NEAR each and `accounts(3)` stakes 30 NEAR.
+    fn staked() -> Governance {
+        call_as(accounts(0), NearToken::from_yoctonear(0), 0);
+        let mut contract = Governance::new(accounts(4), accounts(5), accounts(0), GovernanceConfig {
+            proposal_stake: NearToken::from_near(10),
+            voting_period_sec: HOUR_SEC,
+            timelock_sec: HOUR_SEC,
+            quorum_bps: 5_000,
+        });
+        for (account_id, stake) in [(accounts(1), 10), (accounts(2), 10), (accounts(3), 30)] {
+            call_as(account_id, NearToken::from_near(stake), 0);
+            contract.stake();
+        }
+        contract
+    }
+
+    fn proposal(contract: &mut Governance) -> u64 {
+        call_as(accounts(1), NearToken::from_yoctonear(0), 0);
+        contract.propose("Lengthen the recovery period".to_string(), ProposalAction {
+            target: ProposalTarget::Recovery,
+            method_name: "set_recovery_period".to_string(),
+            args: Base64VecU8(b"{\"days\":14}".to_vec()),
+            gas_tgas: 20,
+        })
+    }
+
+    #[test]
+    fn passed_proposals_execute_after_the_timelock() {
+        let mut contract = staked();
+        let id = proposal(&mut contract);
+        call_as(accounts(3), NearToken::from_yoctonear(0), 0);
+        contract.vote(id, Vote::Approve);
+
+        call_as(accounts(2), NearToken::from_yoctonear(0), HOUR_SEC * NANOS_PER_SECOND);
+        assert_eq!(contract.finalize_proposal(id), ProposalStatus::Queued);
+        assert_eq!(contract.get_proposal(id).unwrap().executable_at, Some(2 * HOUR_SEC * NANOS_PER_SECOND));
+
+        call_as(accounts(2), NearToken::from_yoctonear(0), 2 * HOUR_SEC * NANOS_PER_SECOND);
+        contract.execute_proposal(id);
+        assert_eq!(contract.get_proposal(id).unwrap().status, ProposalStatus::Executing);
+    }
+
+    #[test]
+    fn proposals_below_quorum_are_rejected() {
+        let mut contract = staked();
+        let id = proposal(&mut contract);
+        call_as(accounts(2), NearToken::from_yoctonear(0), 0);
+        contract.vote(id, Vote::Approve);
+
+        call_as(accounts(2), NearToken::from_yoctonear(0), HOUR_SEC * NANOS_PER_SECOND);
+        assert_eq!(contract.finalize_proposal(id), ProposalStatus::Rejected);
+    }
+
+    #[test]
+    #[should_panic(expected = "Stake is locked by open votes or proposals.")]
+    fn voters_cannot_unstake_before_the_change_takes_effect() {
+        let mut contract = staked();
+        let id = proposal(&mut contract);
+        call_as(accounts(3), NearToken::from_yoctonear(0), 0);
+        contract.vote(id, Vote::Approve);
+
+        call_as(accounts(3), NearToken::from_yoctonear(0), HOUR_SEC * NANOS_PER_SECOND);
+        contract.unstake(NearToken::from_near(30));
+    }
+}
@@ -11,7 +11,7 @@ DEPLOYER_ACCOUNT_ID="mcfreack.testnet"
 echo "Using deployer account ID: $DEPLOYER_ACCOUNT_ID"
 
 # 1. Initialize DID Management Contract (bcb-did)
-# The `new` function of bcb-did.testnet takes no arguments.
+# The `new` function of bcb-did.testnet takes no arguments; the calling account becomes the registry owner.
 echo "\nInitializing DID Management Contract (bcb-did.testnet)..."
 DID_CONTRACT_ACCOUNT_ID="bcb-did.testnet"
 near call "$DID_CONTRACT_ACCOUNT_ID" new --accountId "$DEPLOYER_ACCOUNT_ID"
@@ -19,10 +19,12 @@ near call "$DID_CONTRACT_ACCOUNT_ID" new --accountId "$DEPLOYER_ACCOUNT_ID"
 echo "DID Management Contract initialized."
 
 # 2. Initialize Account Recovery Contract (bcb-acc)
-# The `new` function of bcb-acc.testnet takes no arguments.
+# The `new` function of bcb-acc.testnet takes the owner account, who configures governance.
 echo "\nInitializing Account Recovery Contract (bcb-acc.testnet)..."
 RECOVERY_CONTRACT_ACCOUNT_ID="bcb-rec.testnet"
-near call "$RECOVERY_CONTRACT_ACCOUNT_ID" new --accountId "$DEPLOYER_ACCOUNT_ID"
+near call "$RECOVERY_CONTRACT_ACCOUNT_ID" new \
+  "{\"owner_id\": \"$DEPLOYER_ACCOUNT_ID\"}" \
+  --accountId "$DEPLOYER_ACCOUNT_ID"
 
 echo "Account Recovery Contract initialized."
 